@@ -5,4 +5,18 @@ pub mod xpubpos;
 mod transactions_merge;
 pub mod balance;
 pub mod cache;
-pub mod allowance;
\ No newline at end of file
+pub mod allowance;
+pub mod validation;
+pub mod sync_state;
+pub mod calendar;
+pub mod permissions;
+pub mod quarantine;
+pub mod asset;
+pub mod address_registry;
+pub mod address_format;
+pub mod nonces;
+pub mod tasks;
+pub mod flags;
+pub mod tokens;
+pub mod query;
+pub mod integrity;
@@ -0,0 +1,173 @@
+//!
+//! Formats of the plain addresses by the blockchain, used to validate the addresses and to bring them to a common form.
+//!
+//! All known blockchains have a format by default, where Bitcoin and its testnet use the Bitcoin format and all others
+//! are EVM based, so they use the Ethereum format. The app can add a new blockchain, or replace the format of an
+//! existing one, with `register`. The registry is global, i.e. it's shared by all storages opened by the app.
+//!
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use emerald_vault::util::keccak256;
+use lazy_static::lazy_static;
+use protobuf::ProtobufEnum;
+use crate::errors::InvalidValueError;
+use crate::proto::transactions::BlockchainId;
+
+lazy_static! {
+    static ref FORMATS: RwLock<HashMap<u32, Arc<dyn AddressFormat>>> = RwLock::new(default_formats());
+}
+
+///
+/// Format of the addresses on a blockchain
+pub trait AddressFormat: Send + Sync {
+
+    ///
+    /// Check that the address is valid for the blockchain
+    fn validate(&self, address: &str) -> Result<(), InvalidValueError>;
+
+    ///
+    /// Form of a valid address to compare it with other addresses. Default is the address as is.
+    fn normalize(&self, address: &str) -> String {
+        address.to_string()
+    }
+
+    ///
+    /// Form of a valid address to show it to the user. Default is the address as is.
+    fn display(&self, address: &str) -> String {
+        address.to_string()
+    }
+}
+
+///
+/// Bitcoin addresses, where the legacy ones are case-sensitive and bech32 are always in a single case
+pub struct BitcoinFormat;
+
+///
+/// Ethereum (and other EVM) addresses, compared in lowercase and shown with the EIP-55 checksum
+pub struct EthereumFormat;
+
+impl AddressFormat for BitcoinFormat {
+    fn validate(&self, address: &str) -> Result<(), InvalidValueError> {
+        bitcoin::util::address::Address::from_str(address)
+            .map(|_| ())
+            .map_err(|_| InvalidValueError::Other("Invalid address".to_string()))
+    }
+}
+
+impl EthereumFormat {
+
+    ///
+    /// EIP-55 form of an address, i.e. where a letter is uppercase if the matching nibble of the Keccak-256 hash of the
+    /// lowercase address is 8 or more. It expects a valid `0x` prefixed hex address.
+    pub fn to_checksum(address: &str) -> String {
+        let lowercase = address.trim_start_matches("0x").to_lowercase();
+        let hash = keccak256(lowercase.as_bytes());
+        let hex: String = lowercase.chars().enumerate()
+            .map(|(i, c)| {
+                let nibble = if i % 2 == 0 { hash[i / 2] >> 4 } else { hash[i / 2] & 0x0f };
+                if nibble >= 8 { c.to_ascii_uppercase() } else { c }
+            })
+            .collect();
+        format!("0x{}", hex)
+    }
+}
+
+impl AddressFormat for EthereumFormat {
+    fn validate(&self, address: &str) -> Result<(), InvalidValueError> {
+        let good_size = address.len() == 42;
+        let good_prefix = address.starts_with("0x");
+        if !good_size || !good_prefix {
+            return Err(InvalidValueError::Other("Invalid address".to_string()))
+        }
+        let hex = &address[2..];
+        let is_hex = hex.chars().all(|c| c.is_ascii_hexdigit());
+        if !is_hex {
+            return Err(InvalidValueError::Other("Invalid address".to_string()))
+        }
+        // an address in a single case has no checksum, otherwise it's expected to be EIP-55
+        let is_mixed_case = hex.chars().any(|c| c.is_ascii_lowercase()) && hex.chars().any(|c| c.is_ascii_uppercase());
+        if is_mixed_case && EthereumFormat::to_checksum(address) != address {
+            return Err(InvalidValueError::Other("Invalid address checksum".to_string()))
+        }
+        Ok(())
+    }
+
+    fn normalize(&self, address: &str) -> String {
+        address.to_lowercase()
+    }
+
+    fn display(&self, address: &str) -> String {
+        EthereumFormat::to_checksum(address)
+    }
+}
+
+fn default_formats() -> HashMap<u32, Arc<dyn AddressFormat>> {
+    let mut formats: HashMap<u32, Arc<dyn AddressFormat>> = HashMap::new();
+    for blockchain in BlockchainId::values() {
+        let format: Arc<dyn AddressFormat> = match blockchain {
+            BlockchainId::CHAIN_UNSPECIFIED => continue,
+            BlockchainId::CHAIN_BITCOIN | BlockchainId::CHAIN_TESTNET_BITCOIN => Arc::new(BitcoinFormat),
+            _ => Arc::new(EthereumFormat),
+        };
+        formats.insert(blockchain.value() as u32, format);
+    }
+    formats
+}
+
+///
+/// Set the format of the addresses on the blockchain, replacing the current one if it's already known
+pub fn register(blockchain: u32, format: Arc<dyn AddressFormat>) {
+    FORMATS.write().unwrap().insert(blockchain, format);
+}
+
+///
+/// Format of the addresses on the blockchain, or `None` if the blockchain is not supported
+pub fn get(blockchain: u32) -> Option<Arc<dyn AddressFormat>> {
+    FORMATS.read().unwrap().get(&blockchain).cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use crate::access::address_format::{self, AddressFormat, BitcoinFormat, EthereumFormat};
+    use crate::errors::InvalidValueError;
+
+    struct TestFormat;
+
+    impl AddressFormat for TestFormat {
+        fn validate(&self, address: &str) -> Result<(), InvalidValueError> {
+            if address.starts_with("test:") {
+                Ok(())
+            } else {
+                Err(InvalidValueError::Other("Invalid address".to_string()))
+            }
+        }
+    }
+
+    #[test]
+    fn known_blockchains_by_default() {
+        assert!(address_format::get(1).unwrap().validate("bc1q2dz68vuh65h4tmp7kla5lrq907kqx0fwfccwqd").is_ok());
+        assert!(address_format::get(100).unwrap().validate("0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb").is_ok());
+        assert!(address_format::get(10009).unwrap().validate("0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb").is_ok());
+        assert!(address_format::get(10009).unwrap().validate("bc1q2dz68vuh65h4tmp7kla5lrq907kqx0fwfccwqd").is_err());
+        assert!(address_format::get(0).is_none());
+        assert!(address_format::get(20001).is_none());
+    }
+
+    #[test]
+    fn normalize_and_display() {
+        let address = "0xedd91797204d3537fbabde0e0e42aae99975f2bb";
+        assert_eq!(EthereumFormat.display(address), "0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb");
+        assert_eq!(EthereumFormat.normalize("0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb"), address);
+        assert_eq!(BitcoinFormat.normalize("3JudqvZAr6X2z1BxhnPxajZNdwC9vfP8wb"), "3JudqvZAr6X2z1BxhnPxajZNdwC9vfP8wb");
+    }
+
+    #[test]
+    fn register_new_blockchain() {
+        address_format::register(20002, Arc::new(TestFormat));
+        let format = address_format::get(20002).unwrap();
+        assert!(format.validate("test:alice").is_ok());
+        assert!(format.validate("0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb").is_err());
+    }
+}
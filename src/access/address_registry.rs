@@ -0,0 +1,34 @@
+use uuid::Uuid;
+use crate::errors::StateError;
+
+///
+/// Reference to an entry of a wallet
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EntryRef {
+    pub wallet_id: Uuid,
+    pub entry_id: u32,
+}
+
+///
+/// Known receive addresses of the wallet entries, as they are provided by the app from the Vault. When a transaction
+/// is submitted with a change on a known address but without a wallet, the change is attributed to the entry
+/// of the address.
+pub trait AddressRegistry {
+
+    ///
+    /// Replace the known addresses of the entry with the `addresses` on the `blockchain`. An address that was known for
+    /// another entry on the same blockchain is moved to this one.
+    fn set_addresses(&self, entry: EntryRef, blockchain: u32, addresses: Vec<String>) -> Result<(), StateError>;
+
+    ///
+    /// Known addresses of the entry, as pairs of `(blockchain, address)` ordered by the blockchain and address
+    fn get_addresses(&self, entry: EntryRef) -> Result<Vec<(u32, String)>, StateError>;
+
+    ///
+    /// Find the entry the address on the blockchain belongs to. `Ok(None)` if the address is not known.
+    fn find_entry(&self, blockchain: u32, address: String) -> Result<Option<EntryRef>, StateError>;
+
+    ///
+    /// Forget all addresses of all entries of the wallet. Returns the number of removed addresses.
+    fn remove_wallet(&self, wallet_id: Uuid) -> Result<usize, StateError>;
+}
@@ -1,18 +1,127 @@
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
 use std::str::FromStr;
-use chrono::Utc;
+use chrono::{DateTime, Duration, TimeZone, Utc};
 use emerald_vault::blockchain::bitcoin::{AddressType, XPub};
+use std::sync::Arc;
 use protobuf::ProtobufEnum;
 use uuid::Uuid;
+use num_bigint::BigUint;
+use crate::access::address_format::{self, AddressFormat, BitcoinFormat, EthereumFormat};
+use crate::access::asset::AssetId;
 use crate::access::pagination::{PageQuery, PageResult};
+use crate::access::validation::{CheckedWrite, ValidationMode};
 use crate::errors::{InvalidValueError, StateError};
-use crate::proto::addressbook::{Address, Address_AddressType, BookItem};
+use crate::proto::addressbook::{Address, Address_AddressType, AssetVolume as proto_AssetVolume, Avatar, BookItem, BookItemUsage as proto_BookItemUsage, NetworkKind};
 use crate::proto::transactions::BlockchainId;
 
+/// Minimal score of an item found by `AddressBook::search_fuzzy`
+pub const FUZZY_MIN_SCORE: f32 = 0.4;
+
+/// Maximum size of an avatar image, in bytes
+pub const MAX_AVATAR_SIZE: usize = 64 * 1024;
+
+/// Maximum number of the unused addresses of an xpub, after the last used one, a wallet checks for the payments (BIP-44)
+pub const XPUB_GAP_LIMIT: u32 = 20;
+
+/// Maximum length of a description, in chars. It's indexed for the text search, so a long text makes the index large.
+pub const MAX_DESCRIPTION_LENGTH: usize = 1000;
+
 pub struct Filter {
     /// Filter by blockchain id
     pub blockchain: Option<u32>,
-    /// Filter by text containing in the label, description or address itself
+    /// Filter by text containing in the label, description, domain name or address itself
     pub text: Option<String>,
+    /// Preferred locale of the labels in the results, ex. `pt-BR`
+    pub locale: Option<String>,
+    /// Filter by the kind of the network, ex. to never show testnet items in a mainnet address picker
+    pub network: Option<NetworkKind>,
+    /// Include the items archived by the user, which are skipped by default
+    pub include_archived: bool,
+    /// Filter by the group of the item, case insensitive
+    pub group: Option<String>,
+    /// Order of the results. For a sort other than by the creation time the query goes through all items in that
+    /// order and checks the other criteria, so it may be slower for a narrow filter.
+    pub sort: BookItemSort,
+    /// Number of the next addresses of an xpub item to return in `BookItemEnriched::next_addresses`, up to
+    /// `XPUB_GAP_LIMIT`. Default is 1, i.e. only the current address.
+    pub xpub_lookahead: usize,
+    /// Require an item created at or after the specified moment
+    pub created_after: Option<DateTime<Utc>>,
+    /// Require an item created at or before the specified moment
+    pub created_before: Option<DateTime<Utc>>,
+    /// Require an item updated at or after the specified moment, ex. to fetch the items changed since the last export.
+    /// It's most efficient with `BookItemSort::Updated`, otherwise each item is checked.
+    pub updated_after: Option<DateTime<Utc>>,
+}
+
+///
+/// Order of the Address Book query results. The `PageOrder` of the query is applied to it, where the default
+/// `NewestFirst` is the direct order (ex. A to Z for the label) and `OldestFirst` is the reversed one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BookItemSort {
+    /// By the creation time, most recent first
+    Created,
+    /// By the default label alphabetically, case insensitive. Items without a label are at the end.
+    Label,
+    /// By the update time, most recent first
+    Updated,
+}
+
+///
+/// A field of an Address Book Item, used to specify which fields should be loaded by a query
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BookItemField {
+    Id,
+    Address,
+    Label,
+    Blockchain,
+    CreateTimestamp,
+    UpdateTimestamp,
+    LocalizedLabels,
+    Network,
+    Archived,
+    Group,
+    DomainName,
+    DomainResolution,
+    Avatar,
+    Description,
+}
+
+impl BookItemField {
+    /// Field number in the protobuf definition
+    pub(crate) fn get_number(&self) -> u32 {
+        match self {
+            BookItemField::Id => 1,
+            BookItemField::Address => 2,
+            BookItemField::Label => 3,
+            BookItemField::Blockchain => 5,
+            BookItemField::CreateTimestamp => 6,
+            BookItemField::UpdateTimestamp => 7,
+            BookItemField::LocalizedLabels => 8,
+            BookItemField::Network => 9,
+            BookItemField::Archived => 10,
+            BookItemField::Group => 11,
+            BookItemField::DomainName => 12,
+            BookItemField::DomainResolution => 13,
+            BookItemField::Avatar => 14,
+            BookItemField::Description => 15,
+        }
+    }
+}
+
+///
+/// What to do when an added item has the same address on the same blockchain as an existing one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicatePolicy {
+    /// Add it as another item
+    Allow,
+    /// Fail with an error, and don't add any of the items
+    Reject,
+    /// Update the existing item with the non-empty fields of the added one
+    Merge,
+    /// Keep the existing item as is, and return its id
+    ReturnExisting,
 }
 
 ///
@@ -23,6 +132,107 @@ pub struct BookItemEnriched {
     /// Current address. For a plain address it's the same as stored, but for Xpub it tried to find actual position,
     /// or just put a first address in the xpub
     pub current_address: String,
+    /// Label in the locale requested by the query. It's the default label if the item has no variant for that locale.
+    pub label: String,
+    /// Current address in the form to compare it with other addresses, i.e. lowercase for Ethereum
+    pub normalized_address: String,
+    /// Current address in the form to show it to the user, i.e. with the EIP-55 checksum for Ethereum
+    pub display_address: String,
+    /// For an xpub, the unused addresses starting with the current one, as many as requested by
+    /// `Filter::xpub_lookahead`. For a plain address it's just the address itself.
+    pub next_addresses: Vec<String>,
+    /// How the user sent to the contact, or `None` if never
+    pub usage: Option<ContactUsage>,
+}
+
+///
+/// Sends to a contact, as recorded by `AddressBook::record_send`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContactUsage {
+    /// Number of the sends
+    pub sends: u32,
+    /// Total sent amount per asset
+    pub volume: BTreeMap<AssetId, BigUint>,
+    pub last_tx_id: String,
+    pub last_sent: DateTime<Utc>,
+}
+
+impl BookItemEnriched {
+
+    pub(crate) fn new(data: BookItem, current_address: String, label: String, next_addresses: Vec<String>) -> BookItemEnriched {
+        // an xpub always gives bitcoin addresses, whatever the blockchain is
+        let format = if data.get_address().get_field_type() == Address_AddressType::XPUB {
+            Some(Arc::new(BitcoinFormat) as Arc<dyn AddressFormat>)
+        } else {
+            address_format::get(data.blockchain)
+        };
+        let (normalized_address, display_address) = match format {
+            Some(format) => (format.normalize(current_address.as_str()), format.display(current_address.as_str())),
+            None => (current_address.clone(), current_address.clone()),
+        };
+        let next_addresses = if next_addresses.is_empty() { vec![current_address.clone()] } else { next_addresses };
+        BookItemEnriched {
+            data,
+            current_address,
+            label,
+            normalized_address,
+            display_address,
+            next_addresses,
+            usage: None,
+        }
+    }
+}
+
+impl TryFrom<&proto_BookItemUsage> for ContactUsage {
+    type Error = StateError;
+
+    fn try_from(value: &proto_BookItemUsage) -> Result<Self, Self::Error> {
+        let mut volume = BTreeMap::new();
+        for v in value.volumes.iter() {
+            let asset = AssetId::from_str(v.asset.as_str()).map_err(|_| StateError::CorruptedValue)?;
+            let amount = BigUint::from_str(v.amount.as_str()).map_err(|_| StateError::CorruptedValue)?;
+            volume.insert(asset, amount);
+        }
+        Ok(ContactUsage {
+            sends: value.sends,
+            volume,
+            last_tx_id: value.last_tx_id.clone(),
+            last_sent: Utc.timestamp_millis_opt(value.last_timestamp as i64).unwrap(),
+        })
+    }
+}
+
+impl From<&ContactUsage> for proto_BookItemUsage {
+    fn from(value: &ContactUsage) -> Self {
+        let mut proto = proto_BookItemUsage::new();
+        proto.sends = value.sends;
+        for (asset, amount) in value.volume.iter() {
+            let mut v = proto_AssetVolume::new();
+            v.asset = asset.to_string();
+            v.amount = amount.to_string();
+            proto.volumes.push(v);
+        }
+        proto.last_tx_id = value.last_tx_id.clone();
+        proto.last_timestamp = value.last_sent.timestamp_millis() as u64;
+        proto
+    }
+}
+
+///
+/// Address Book Item found by a fuzzy or ranked search
+pub struct ScoredBookItem {
+    pub item: BookItemEnriched,
+    /// How close the item is to the searched text, from 0.0 to 1.0 where higher is better
+    pub score: f32,
+}
+
+///
+/// Address Book record which exists in the storage but cannot be read or used, as reported by `AddressBook::verify_all`
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorruptedBookItem {
+    pub id: Uuid,
+    /// Usually a `StateError::CorruptedValue`
+    pub error: StateError,
 }
 
 pub trait AddressBook {
@@ -34,22 +244,116 @@ pub trait AddressBook {
     /// Returns list of IDs of created/updated records.
     fn add(&self, items: Vec<BookItem>) -> Result<Vec<Uuid>, StateError>;
 
+    ///
+    /// Same as `add`, but checks if there is already an item for the same address on the same blockchain, including
+    /// the items added before in the same call, and handles it according to the `policy`. An item with the same id is
+    /// an update and not a duplicate.
+    /// Returns list of IDs of created/updated records, or of the existing records for the duplicates.
+    fn add_with_policy(&self, items: Vec<BookItem>, policy: DuplicatePolicy) -> Result<Vec<Uuid>, StateError>;
+
+    ///
+    /// Same as `add`, but validates the items according to the `mode`. In a non-strict mode an invalid item is skipped
+    /// with a warning instead of rejecting all of them.
+    fn add_checked(&self, items: Vec<BookItem>, mode: ValidationMode) -> Result<CheckedWrite<Uuid>, StateError>;
+
     ///
     /// Get an item if it exists.
-    /// Returns `Ok(Some)` when it exists, or `Ok(None)` if not. Or `Err(StateError)` if cannot read
+    /// Returns `Ok(Some)` when it exists, or `Ok(None)` if not. Or `Err(StateError)` if cannot read, which is `CorruptedValue`
+    /// if the stored record is not usable (see `verify_all`)
     fn get(&self, id: Uuid) -> Result<Option<BookItemEnriched>, StateError>;
 
+    ///
+    /// Get all items for exactly that address on the blockchain, most recent first, ex. to show the name of a
+    /// counterparty of a transaction. The address is compared case-insensitively, and the archived items are included.
+    /// Note that an xpub item is found only by the xpub itself, and not by the addresses derived from it.
+    fn get_by_address(&self, blockchain: u32, address: String) -> Result<Vec<BookItemEnriched>, StateError>;
+
     ///
     /// Remove a record with the specified id, if it does exit. Otherwise does nothing, returns ok in both cases.
     fn remove(&self, id: Uuid) -> Result<(), StateError>;
 
+    ///
+    /// Archive the record, so it's kept but skipped by the queries unless they include the archived records.
+    /// Returns `false` if there is no such record.
+    fn archive(&self, id: Uuid) -> Result<bool, StateError>;
+
+    ///
+    /// Restore an archived record. Returns `false` if there is no such record.
+    fn unarchive(&self, id: Uuid) -> Result<bool, StateError>;
+
+    ///
+    /// Groups used by the items, with the number of items in each, ordered by the name. Archived items are not counted.
+    /// The name is spelled as in the oldest item of the group, as the groups are compared case-insensitively.
+    fn get_groups(&self) -> Result<Vec<(String, usize)>, StateError>;
+
+    ///
+    /// Remember that the domain name of the record was resolved to the `address` at the `timestamp`. It doesn't change
+    /// the record itself, i.e. its address or update time.
+    /// Returns `false` if there is no such record.
+    fn set_domain_resolution(&self, id: Uuid, address: String, timestamp: DateTime<Utc>) -> Result<bool, StateError>;
+
+    ///
+    /// Set the avatar of the record, which is an encoded image (ex. PNG) of the `content_type` up to `MAX_AVATAR_SIZE`
+    /// bytes. It replaces the current avatar, if any.
+    /// Returns `false` if there is no such record.
+    fn set_avatar(&self, id: Uuid, image: Vec<u8>, content_type: String) -> Result<bool, StateError>;
+
+    ///
+    /// Remove the avatar of the record, if it has one.
+    /// Returns `false` if there is no such record.
+    fn clear_avatar(&self, id: Uuid) -> Result<bool, StateError>;
+
+    ///
+    /// Count a send of the `amount` of the `asset` to the contact in the transaction `tx_id`, to show the usage in
+    /// `BookItemEnriched::usage`. It's supposed to be called when the transaction is submitted. A call with the same
+    /// `tx_id` as the last recorded send is ignored, so a retry doesn't count it twice.
+    /// Returns `false` if there is no such record.
+    fn record_send(&self, id: Uuid, tx_id: String, asset: AssetId, amount: BigUint) -> Result<bool, StateError>;
+
     ///
     /// Query for records in storage using specified filter and page
     fn query(&self, filter: Filter, page: PageQuery) -> Result<PageResult<BookItemEnriched>, StateError>;
 
+    ///
+    /// Search for the items similar to the `filter.text`, which tolerates small typos in it (ex. `Jhon` finds `John`).
+    /// The text is compared with the labels and the domain name by the share of their common n-grams, and the items
+    /// with a score below `FUZZY_MIN_SCORE` are skipped. The other criteria of the filter are applied as usual.
+    /// Returns up to `limit` items, best match first.
+    fn search_fuzzy(&self, filter: Filter, limit: usize) -> Result<Vec<ScoredBookItem>, StateError>;
+
+    ///
+    /// Search for the items containing the `filter.text`, same as `query`, but ordered by the relevance instead of
+    /// the time, ex. for an autocomplete that shows only a few best matches. An item with a label starting with the text
+    /// is the most relevant, then an item with the text in the label or the domain name, and then in the address. Within
+    /// the same kind of match a shorter label, i.e. a larger part of it matched, goes first.
+    /// Returns up to `limit` items, best match first.
+    fn search_ranked(&self, filter: Filter, limit: usize) -> Result<Vec<ScoredBookItem>, StateError>;
+
+    ///
+    /// Query for records, but load only the specified `fields` of each record. All other fields have default values.
+    /// Note that the id, address and the fields required to check the filter are always loaded.
+    fn query_projected(&self, filter: Filter, page: PageQuery, fields: Vec<BookItemField>) -> Result<PageResult<BookItemEnriched>, StateError>;
+
     ///
     /// Update the store Address Book item with new values
     fn update(&self, id: Uuid, update: BookItem) -> Result<(), StateError>;
+
+    ///
+    /// Export the Address Book as a compact binary bundle which is not larger than `max_bytes`, ex. to transfer it
+    /// to a companion device through a QR code or a file. If the whole book doesn't fit into the budget it takes
+    /// the most recently updated items. The avatars are not exported, as a single image would take most of the budget.
+    fn export_bundle(&self, max_bytes: usize) -> Result<Vec<u8>, StateError>;
+
+    ///
+    /// Import a bundle produced by `export_bundle`. An item is merged with an existing record with the same id, or with
+    /// the same address on the same blockchain, and replaces it only if it has a later `update_timestamp`.
+    /// Returns list of IDs of created/updated records.
+    fn import_bundle(&self, bundle: &[u8]) -> Result<Vec<Uuid>, StateError>;
+
+    ///
+    /// Check all stored records and find those which cannot be read or used, ex. a legacy or a hand-edited record without
+    /// an address, or with an invalid xpub. Such records are skipped by the queries, so it's a way to find and fix them.
+    fn verify_all(&self) -> Result<Vec<CorruptedBookItem>, StateError>;
 }
 
 impl BookItem {
@@ -74,6 +378,8 @@ impl BookItem {
             copy.set_update_timestamp(now);
         }
 
+        copy.domain_name = copy.domain_name.trim().to_lowercase();
+
         if let Some(mut address) = copy.address.clone().into_option() {
             if XPub::from_str(address.address.as_str()).is_ok() {
                 address.set_field_type(Address_AddressType::XPUB);
@@ -84,6 +390,88 @@ impl BookItem {
         Ok(copy)
     }
 
+    ///
+    /// Best variant of the label for the `locale`. It prefers the exact locale (ex. `pt-BR`), then the same language
+    /// (ex. `pt` or `pt-PT`), and falls back to the default label.
+    pub fn get_label_for(&self, locale: &str) -> &str {
+        let normalize = |l: &str| l.trim().replace('_', "-").to_lowercase();
+        let language = |l: &str| normalize(l).split('-').next().unwrap_or("").to_string();
+        let locale = normalize(locale);
+        let variants = || self.localized_labels.iter().filter(|v| !v.text.is_empty());
+        variants().find(|v| normalize(&v.locale) == locale)
+            .or_else(|| variants().find(|v| language(&v.locale) == language(&locale)))
+            .map(|v| v.text.as_str())
+            .unwrap_or(self.label.as_str())
+    }
+
+    ///
+    /// Copy of the item updated with the non-empty fields of the `update`. It keeps the id and the creation time of
+    /// the item, and it's not archived anymore as the user added it again.
+    pub(crate) fn merge(&self, update: &BookItem) -> BookItem {
+        let mut merged = self.clone();
+        if !update.label.trim().is_empty() {
+            merged.label = update.label.clone();
+        }
+        for variant in update.localized_labels.iter() {
+            merged.localized_labels.retain(|v| v.locale != variant.locale);
+            merged.localized_labels.push(variant.clone());
+        }
+        if !update.group.trim().is_empty() {
+            merged.group = update.group.clone();
+        }
+        if !update.domain_name.is_empty() && update.domain_name != self.domain_name {
+            // the previous resolution is for another name
+            merged.domain_name = update.domain_name.clone();
+            merged.clear_domain_resolution();
+        }
+        if update.has_domain_resolution() && update.get_domain_resolution().timestamp > merged.get_domain_resolution().timestamp {
+            merged.set_domain_resolution(update.get_domain_resolution().clone());
+        }
+        if update.has_avatar() {
+            merged.set_avatar(update.get_avatar().clone());
+        }
+        if !update.description.trim().is_empty() {
+            merged.description = update.description.clone();
+        }
+        merged.archived = false;
+        merged.update_timestamp = update.update_timestamp.max(self.update_timestamp);
+        merged
+    }
+
+    ///
+    /// Form of the group name used to compare and index it
+    pub(crate) fn normalize_group(group: &str) -> String {
+        group.trim().to_lowercase()
+    }
+
+    fn label_contains(&self, q: &str) -> bool {
+        self.label.to_lowercase().contains(q)
+            || self.localized_labels.iter().any(|v| v.text.to_lowercase().contains(q))
+    }
+
+    ///
+    /// Address the domain name was resolved to, if it was resolved within `max_age` from now. `None` if the item has no
+    /// domain name, or it was never resolved, or the resolution is too old to trust it.
+    pub fn get_resolved_address(&self, max_age: Duration) -> Option<&str> {
+        if self.domain_name.is_empty() || !self.has_domain_resolution() {
+            return None
+        }
+        let resolution = self.get_domain_resolution();
+        let age = Utc::now().timestamp_millis() - resolution.timestamp as i64;
+        if age > max_age.num_milliseconds() {
+            return None
+        }
+        Some(resolution.address.as_str())
+    }
+
+    fn domain_contains(&self, q: &str) -> bool {
+        self.domain_name.contains(q)
+    }
+
+    fn description_contains(&self, q: &str) -> bool {
+        self.description.to_lowercase().contains(q)
+    }
+
     fn address_contains(&self, q: String) -> bool {
         if !self.has_address() {
             return false
@@ -100,39 +488,63 @@ impl BookItem {
            return Err(InvalidValueError::Name("id".to_string()));
         }
 
-        let blockchain = BlockchainId::from_i32(self.blockchain as i32)
+        let format = address_format::get(self.blockchain)
             .ok_or(InvalidValueError::Name("blockchain".to_string()))?;
         match self.address.clone().into_option() {
-            Some(address) => address.validate(blockchain),
+            Some(address) => address.validate(format.as_ref()),
             None => Err(InvalidValueError::NameMessage("address".to_string(), "Address is empty".to_string()))
+        }?;
+
+        let is_valid_locale = |l: &str| !l.is_empty() && l.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if self.localized_labels.iter().any(|v| !is_valid_locale(v.locale.as_str())) {
+            return Err(InvalidValueError::Name("locale".to_string()));
+        }
+
+        // ex. `alice.eth`, where each part is not empty
+        let is_valid_domain = |d: &str| d.contains('.') && d.split('.').all(|part| !part.is_empty() && !part.chars().any(|c| c.is_whitespace() || c == '/'));
+        if !self.domain_name.is_empty() && !is_valid_domain(self.domain_name.as_str()) {
+            return Err(InvalidValueError::NameMessage("domain_name".to_string(), format!("Invalid domain name: {}", self.domain_name)));
+        }
+
+        if let Some(avatar) = self.avatar.as_ref() {
+            avatar.validate()?;
         }
+
+        if self.description.chars().count() > MAX_DESCRIPTION_LENGTH {
+            return Err(InvalidValueError::NameMessage("description".to_string(), format!("Description is longer than {} chars", MAX_DESCRIPTION_LENGTH)));
+        }
+        Ok(())
+    }
+}
+
+impl Avatar {
+
+    pub(crate) fn validate(&self) -> Result<(), InvalidValueError> {
+        if self.image.is_empty() {
+            return Err(InvalidValueError::NameMessage("avatar".to_string(), "Image is empty".to_string()))
+        }
+        if self.image.len() > MAX_AVATAR_SIZE {
+            return Err(InvalidValueError::NameMessage("avatar".to_string(), format!("Image is larger than {} bytes", MAX_AVATAR_SIZE)))
+        }
+        if !self.content_type.starts_with("image/") {
+            return Err(InvalidValueError::NameMessage("avatar".to_string(), format!("Not an image: {}", self.content_type)))
+        }
+        Ok(())
     }
 }
 
 impl Address {
 
-    fn validate(&self, blockchain: BlockchainId) -> Result<(), InvalidValueError> {
+    ///
+    /// EIP-55 form of an Ethereum address, i.e. where a letter is uppercase if the matching nibble of the Keccak-256 hash
+    /// of the lowercase address is 8 or more. It expects a valid `0x` prefixed hex address.
+    pub fn to_checksum(address: &str) -> String {
+        EthereumFormat::to_checksum(address)
+    }
+
+    fn validate(&self, format: &dyn AddressFormat) -> Result<(), InvalidValueError> {
         match self.get_field_type() {
-            Address_AddressType::PLAIN => {
-                match blockchain {
-                    BlockchainId::CHAIN_BITCOIN | BlockchainId::CHAIN_TESTNET_BITCOIN => {
-                        let _ = bitcoin::util::address::Address::from_str(self.address.as_str())
-                            .map_err(|_| InvalidValueError::Other("Invalid address".to_string()))?;
-                    },
-                    // those are all ethereum blockchains
-                    _ => {
-                        let good_size = self.address.len() == 42;
-                        let good_prefix = self.address.starts_with("0x");
-                        if !good_size || !good_prefix {
-                            return Err(InvalidValueError::Other("Invalid address".to_string()))
-                        }
-                        let is_hex = self.address[2..].chars().all(|c| c.is_ascii_hexdigit());
-                        if !is_hex {
-                            return Err(InvalidValueError::Other("Invalid address".to_string()))
-                        }
-                    }
-                }
-            }
+            Address_AddressType::PLAIN => format.validate(self.address.as_str())?,
             Address_AddressType::XPUB => {
                 let xpub = XPub::from_str(self.address.as_str())
                     .map_err(|_| InvalidValueError::Other("Not an XPub address".to_string()))?;
@@ -150,6 +562,40 @@ impl Address {
 }
 
 impl Filter {
+
+    ///
+    /// Fields of an item required to check the filter and enrich the result
+    pub(crate) fn get_required_fields(&self) -> Vec<BookItemField> {
+        let mut fields = vec![BookItemField::Id, BookItemField::Address];
+        if self.blockchain.is_some() {
+            fields.push(BookItemField::Blockchain);
+        }
+        if self.text.is_some() || self.locale.is_some() {
+            fields.push(BookItemField::Label);
+            fields.push(BookItemField::LocalizedLabels);
+        }
+        if self.text.is_some() {
+            fields.push(BookItemField::DomainName);
+            fields.push(BookItemField::Description);
+        }
+        if self.network.is_some() {
+            fields.push(BookItemField::Network);
+        }
+        if !self.include_archived {
+            fields.push(BookItemField::Archived);
+        }
+        if self.group.is_some() {
+            fields.push(BookItemField::Group);
+        }
+        if self.created_after.is_some() || self.created_before.is_some() {
+            fields.push(BookItemField::CreateTimestamp);
+        }
+        if self.updated_after.is_some() {
+            fields.push(BookItemField::UpdateTimestamp);
+        }
+        fields
+    }
+
     pub fn check_filter(&self, t: &BookItem) -> bool {
         let by_blockchain = if let Some(b) = &self.blockchain {
             t.blockchain == *b
@@ -159,12 +605,44 @@ impl Filter {
 
         let by_text = if let Some(q) = &self.text {
             let q = q.to_lowercase().trim().to_string();
-            t.label.to_lowercase().contains(&q) || t.address_contains(q)
+            t.label_contains(&q) || t.domain_contains(&q) || t.description_contains(&q) || t.address_contains(q)
+        } else {
+            true
+        };
+
+        let by_network = if let Some(network) = &self.network {
+            t.network == *network
         } else {
             true
         };
 
-        by_blockchain && by_text
+        let by_archived = self.include_archived || !t.archived;
+
+        let by_group = if let Some(group) = &self.group {
+            BookItem::normalize_group(group) == BookItem::normalize_group(&t.group)
+        } else {
+            true
+        };
+
+        let created = t.create_timestamp as i64;
+        let by_created = self.created_after.is_none_or(|ts| created >= ts.timestamp_millis())
+            && self.created_before.is_none_or(|ts| created <= ts.timestamp_millis());
+        let by_updated = self.updated_after.is_none_or(|ts| t.update_timestamp as i64 >= ts.timestamp_millis());
+
+        by_blockchain && by_text && by_network && by_archived && by_group && by_created && by_updated
+    }
+}
+
+impl NetworkKind {
+
+    ///
+    /// Kind of the network of the blockchain, or `NETWORK_UNSPECIFIED` if the blockchain is not known
+    pub fn of_blockchain(blockchain: u32) -> NetworkKind {
+        match BlockchainId::from_i32(blockchain as i32) {
+            Some(BlockchainId::CHAIN_BITCOIN) | Some(BlockchainId::CHAIN_ETHEREUM) | Some(BlockchainId::CHAIN_ETHEREUM_CLASSIC) => NetworkKind::NETWORK_MAINNET,
+            Some(BlockchainId::CHAIN_UNSPECIFIED) | None => NetworkKind::NETWORK_UNSPECIFIED,
+            Some(_) => NetworkKind::NETWORK_TESTNET,
+        }
     }
 }
 
@@ -173,6 +651,15 @@ impl Default for Filter {
         Filter {
             blockchain: None,
             text: None,
+            locale: None,
+            network: None,
+            include_archived: false,
+            group: None,
+            sort: BookItemSort::Created,
+            xpub_lookahead: 1,
+            created_after: None,
+            created_before: None,
+            updated_after: None,
         }
     }
 }
@@ -182,8 +669,11 @@ mod tests {
     use std::str::FromStr;
     use uuid::Uuid;
     use crate::errors::InvalidValueError;
-    use super::{Filter};
-    use crate::proto::addressbook::{BookItem as proto_BookItem, Address as proto_Address, Address_AddressType};
+    use chrono::{Duration, Utc};
+    use std::sync::Arc;
+    use super::{BookItemEnriched, Filter, MAX_DESCRIPTION_LENGTH};
+    use crate::access::address_format::{self, AddressFormat};
+    use crate::proto::addressbook::{BookItem as proto_BookItem, Address as proto_Address, Address_AddressType, DomainResolution, LocalizedLabel, NetworkKind};
 
     #[test]
     fn default_filter_accept_any() {
@@ -221,6 +711,25 @@ mod tests {
         assert!(!filter.check_filter(&item));
     }
 
+    #[test]
+    fn filter_by_network() {
+        let filter = Filter {
+            network: Some(NetworkKind::NETWORK_MAINNET),
+            ..Filter::default()
+        };
+
+        let mut item = proto_BookItem::new();
+        item.id = "989d7648-13e3-4cb9-acfb-85464f063b34".to_string();
+        item.blockchain = 101;
+        item.network = NetworkKind::of_blockchain(item.blockchain);
+        assert!(filter.check_filter(&item));
+
+        item.blockchain = 10009;
+        item.network = NetworkKind::of_blockchain(item.blockchain);
+        assert_eq!(item.network, NetworkKind::NETWORK_TESTNET);
+        assert!(!filter.check_filter(&item));
+    }
+
     #[test]
     fn filter_by_label() {
         let filter = Filter {
@@ -243,6 +752,30 @@ mod tests {
         assert!(!filter.check_filter(&item));
     }
 
+    #[test]
+    fn filter_by_description() {
+        let filter = Filter {
+            text: Some("Conference".to_string()),
+            ..Filter::default()
+        };
+
+        let mut item = proto_BookItem::new();
+        item.id = "989d7648-13e3-4cb9-acfb-85464f063b34".to_string();
+        item.blockchain = 101;
+        item.label = "Alice".to_string();
+        item.description = "Met at conference, pays invoices monthly".to_string();
+        let mut address = proto_Address::new();
+        address.address = "0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb".to_string();
+        item.set_address(address);
+
+        assert!(filter.check_filter(&item));
+        assert!(item.validate().is_ok());
+
+        item.description = "x".repeat(MAX_DESCRIPTION_LENGTH + 1);
+        assert!(!filter.check_filter(&item));
+        assert!(item.validate().is_err());
+    }
+
     #[test]
     fn filter_by_address() {
         let filter = Filter {
@@ -265,6 +798,24 @@ mod tests {
         assert!(!filter.check_filter(&item));
     }
 
+    #[test]
+    fn choose_label_for_locale() {
+        let mut item = proto_BookItem::new();
+        item.label = "Contact".to_string();
+        for (locale, text) in [("pt-PT", "Contacto"), ("pt-BR", "Contato"), ("ru", "Контакт")] {
+            let mut variant = LocalizedLabel::new();
+            variant.locale = locale.to_string();
+            variant.text = text.to_string();
+            item.localized_labels.push(variant);
+        }
+
+        assert_eq!(item.get_label_for("pt-BR"), "Contato");
+        assert_eq!(item.get_label_for("pt_br"), "Contato");
+        assert_eq!(item.get_label_for("pt"), "Contacto");
+        assert_eq!(item.get_label_for("ru-RU"), "Контакт");
+        assert_eq!(item.get_label_for("en"), "Contact");
+    }
+
     #[test]
     fn accept_valid_ethereum_address() {
         let addresses = vec![
@@ -284,6 +835,39 @@ mod tests {
         }
     }
 
+    #[test]
+    fn checksum_ethereum_address() {
+        let addresses = vec![
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+            "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+            "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+            "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+        ];
+        for value in addresses {
+            assert_eq!(proto_Address::to_checksum(value.to_lowercase().as_str()), value);
+            assert_eq!(proto_Address::to_checksum(value.to_uppercase().replace("0X", "0x").as_str()), value);
+        }
+    }
+
+    #[test]
+    fn verify_ethereum_address_checksum() {
+        let mut item = proto_BookItem::new();
+        item.id = "989d7648-13e3-4cb9-acfb-85464f063b34".to_string();
+        item.blockchain = 101;
+        let mut address = proto_Address::new();
+
+        // no checksum
+        for value in ["0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed", "0x5AAEB6053F3E94C9B9A09F33669435E7EF1BEAED"] {
+            address.set_address(value.to_string());
+            item.set_address(address.clone());
+            assert!(item.validate().is_ok(), "{}", value);
+        }
+
+        address.set_address("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD".to_string());
+        item.set_address(address.clone());
+        assert_eq!(item.validate(), Err(InvalidValueError::Other("Invalid address checksum".to_string())));
+    }
+
     #[test]
     fn deny_invalid_ethereum_address() {
         let addresses = vec![
@@ -417,6 +1001,42 @@ mod tests {
         }
     }
 
+    #[test]
+    fn validate_with_registered_format() {
+        struct AccountFormat;
+        impl AddressFormat for AccountFormat {
+            fn validate(&self, address: &str) -> Result<(), InvalidValueError> {
+                if address.starts_with("acc_") {
+                    Ok(())
+                } else {
+                    Err(InvalidValueError::Other("Invalid address".to_string()))
+                }
+            }
+            fn normalize(&self, address: &str) -> String {
+                address.to_lowercase()
+            }
+        }
+
+        let mut item = proto_BookItem::new();
+        item.id = "989d7648-13e3-4cb9-acfb-85464f063b34".to_string();
+        item.blockchain = 20001;
+        let mut address = proto_Address::new();
+        address.set_address("acc_Alice".to_string());
+        item.set_address(address.clone());
+        assert_eq!(item.validate(), Err(InvalidValueError::Name("blockchain".to_string())));
+
+        address_format::register(20001, Arc::new(AccountFormat));
+        assert!(item.validate().is_ok());
+
+        let enriched = BookItemEnriched::new(item.clone(), "acc_Alice".to_string(), "".to_string(), vec![]);
+        assert_eq!(enriched.normalized_address, "acc_alice");
+        assert_eq!(enriched.display_address, "acc_Alice");
+
+        address.set_address("0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb".to_string());
+        item.set_address(address);
+        assert!(item.validate().is_err());
+    }
+
     #[test]
     fn preprocess_changes_type_to_xpub() {
         let mut item = proto_BookItem::new();
@@ -488,4 +1108,60 @@ mod tests {
         assert_eq!(err, InvalidValueError::Name("id".to_string()))
     }
 
+    #[test]
+    fn validate_domain_name() {
+        let mut item = proto_BookItem::new();
+        item.id = "989d7648-13e3-4cb9-acfb-85464f063b34".to_string();
+        item.blockchain = 101;
+        let mut address = proto_Address::new();
+        address.set_address("0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb".to_string());
+        item.set_address(address);
+
+        for value in ["alice.eth", "pay.alice.crypto"] {
+            item.domain_name = value.to_string();
+            assert!(item.validate().is_ok(), "{}", value);
+        }
+        for value in ["alice", "alice..eth", "alice eth.eth", ".eth"] {
+            item.domain_name = value.to_string();
+            assert!(item.validate().is_err(), "{}", value);
+        }
+    }
+
+    #[test]
+    fn filter_by_domain_name() {
+        let filter = Filter {
+            text: Some("Alice.ETH".to_string()),
+            ..Filter::default()
+        };
+
+        let mut item = proto_BookItem::new();
+        item.id = "989d7648-13e3-4cb9-acfb-85464f063b34".to_string();
+        item.label = "Contact".to_string();
+        item.domain_name = " Alice.eth".to_string();
+        let item = item.preprocess().unwrap();
+        assert_eq!(item.domain_name, "alice.eth");
+        assert!(filter.check_filter(&item));
+    }
+
+    #[test]
+    fn use_recent_domain_resolution() {
+        let mut item = proto_BookItem::new();
+        item.domain_name = "alice.eth".to_string();
+        assert_eq!(item.get_resolved_address(Duration::hours(1)), None);
+
+        let mut resolution = DomainResolution::new();
+        resolution.address = "0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb".to_string();
+        resolution.timestamp = (Utc::now() - Duration::minutes(10)).timestamp_millis() as u64;
+        item.set_domain_resolution(resolution);
+        assert_eq!(item.get_resolved_address(Duration::hours(1)), Some("0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb"));
+        assert_eq!(item.get_resolved_address(Duration::minutes(5)), None);
+
+        // a new name makes the resolution obsolete
+        let mut update = proto_BookItem::new();
+        update.domain_name = "bob.eth".to_string();
+        let merged = item.merge(&update);
+        assert_eq!(merged.domain_name, "bob.eth");
+        assert_eq!(merged.get_resolved_address(Duration::hours(1)), None);
+        assert_eq!(item.merge(&proto_BookItem::new()).get_resolved_address(Duration::hours(1)), Some("0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb"));
+    }
 }
\ No newline at end of file
@@ -1,8 +1,46 @@
+use std::str::FromStr;
 use uuid::Uuid;
+use crate::access::asset::AssetId;
 use crate::access::pagination::PageResult;
-use crate::errors::StateError;
+use crate::errors::{InvalidValueError, StateError};
 use crate::proto::balance::Allowance;
 
+///
+/// Identifies a single allowance in the cache
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AllowanceKey {
+    pub wallet_id: Uuid,
+    pub blockchain: u32,
+    /// Address of the ERC20 token
+    pub token: String,
+    pub owner: String,
+    pub spender: String,
+}
+
+impl AllowanceKey {
+
+    ///
+    /// Id of the token the allowance is for
+    pub fn get_asset_id(&self) -> AssetId {
+        AssetId::Erc20(self.blockchain, self.token.to_lowercase())
+    }
+}
+
+impl TryFrom<&Allowance> for AllowanceKey {
+    type Error = StateError;
+
+    fn try_from(value: &Allowance) -> Result<Self, Self::Error> {
+        Ok(AllowanceKey {
+            wallet_id: Uuid::from_str(&value.wallet_id)
+                .map_err(|_| InvalidValueError::Name("wallet_id".to_string()))?,
+            blockchain: value.blockchain,
+            token: value.token.clone(),
+            owner: value.owner.clone(),
+            spender: value.spender.clone(),
+        })
+    }
+}
+
 ///
 /// Cache for ERC-20 allowance data
 pub trait Allowances {
@@ -11,7 +49,7 @@ pub trait Allowances {
     /// Add an allowance to the cache
     ///
     /// - `allowance` - Allowance to add
-    /// - `ttl` - Time to live in milliseconds (default 24 hours)
+    /// - `ttl` - Time to live in milliseconds (default is set by the `TtlPolicy`, 24 hours unless configured)
     fn add(&self, allowance: Allowance, ttl: Option<u64>) -> Result<(), StateError>;
 
     ///
@@ -25,4 +63,11 @@ pub trait Allowances {
     /// - `blockchain` - Blockchain ID, if set only allowances for that blockchain are removed, otherwise any blockchain is removed
     /// - `min_ts` - Minimum timestamp (ms), if set only allowances with a timestamp lesser than this value are removed, otherwise any timestamp is removed
     fn remove(&self, wallet_id: Uuid, blockchain: Option<u32>, min_ts: Option<u64>) -> Result<usize, StateError>;
+
+    ///
+    /// Mark a cached allowance as revoked by the specified transaction, so it can be shown as revoked until the cache
+    /// is updated with the actual data. The TTL is kept as is.
+    /// Returns the updated allowance, or `None` if it's not in the cache. An unreadable allowance is kept as is and
+    /// reported with `StateError::CorruptedValue`.
+    fn mark_revoked(&self, key: AllowanceKey, tx_id: String) -> Result<Option<Allowance>, StateError>;
 }
\ No newline at end of file
@@ -0,0 +1,143 @@
+use std::fmt;
+use std::str::FromStr;
+use crate::errors::{InvalidValueError, StateError};
+use crate::proto::transactions::Change;
+use crate::validate;
+
+/// Codes used for the native coins before `AssetId`, which may still be found in the stored data
+const LEGACY_NATIVE: [&str; 5] = ["ETHER", "ETH", "ETC", "BTC", "TESTBTC"];
+/// Prefix of an ERC-20 token in the legacy format, i.e. `ERC20:<ADDRESS>`
+const LEGACY_ERC20: &str = "ERC20:";
+
+///
+/// Identifier of an asset. The canonical form is:
+///
+/// - `native:<BLOCKCHAIN>` for the native coin of the blockchain, ex. `native:100` for Ether
+/// - `erc20:<BLOCKCHAIN>:<ADDRESS>` for an ERC-20 token, with the address in lowercase
+/// - `slip44:<COIN_TYPE>` for a coin identified by its SLIP-44 type
+///
+/// The data stored before it was introduced uses free-form codes (ex. `ETHER` or `ERC20:0xdAC1...`), which can be read
+/// with `AssetId::from_legacy`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum AssetId {
+    /// Native coin of the blockchain
+    Native(u32),
+    /// ERC-20 token on the blockchain, by the token contract address
+    Erc20(u32, String),
+    /// Coin by its SLIP-44 coin type
+    Slip44(u32),
+}
+
+impl AssetId {
+
+    ///
+    /// Create an ERC-20 token id, checking the address and bringing it to the canonical lowercase form
+    pub fn erc20<S: AsRef<str>>(blockchain: u32, address: S) -> Result<AssetId, StateError> {
+        let address = address.as_ref();
+        validate::check_ethereum_address(address)
+            .map_err(|_| InvalidValueError::Name("asset".to_string()))?;
+        Ok(AssetId::Erc20(blockchain, address.to_lowercase()))
+    }
+
+    ///
+    /// Parse an asset that may be stored in the legacy format, where the blockchain is not a part of the code and
+    /// must be provided separately. An id in the canonical form is accepted as well.
+    pub fn from_legacy(blockchain: u32, value: &str) -> Result<AssetId, StateError> {
+        if let Ok(id) = AssetId::from_str(value) {
+            return Ok(id)
+        }
+        let value = value.trim();
+        if LEGACY_NATIVE.contains(&value.to_uppercase().as_str()) {
+            return Ok(AssetId::Native(blockchain))
+        }
+        if value.len() > LEGACY_ERC20.len() && value[..LEGACY_ERC20.len()].eq_ignore_ascii_case(LEGACY_ERC20) {
+            return AssetId::erc20(blockchain, &value[LEGACY_ERC20.len()..])
+        }
+        Err(StateError::InvalidValue(InvalidValueError::Name("asset".to_string())))
+    }
+
+    ///
+    /// Blockchain of the asset, if it's bound to one
+    pub fn get_blockchain(&self) -> Option<u32> {
+        match self {
+            AssetId::Native(blockchain) => Some(*blockchain),
+            AssetId::Erc20(blockchain, _) => Some(*blockchain),
+            AssetId::Slip44(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for AssetId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AssetId::Native(blockchain) => write!(f, "native:{}", blockchain),
+            AssetId::Erc20(blockchain, address) => write!(f, "erc20:{}:{}", blockchain, address),
+            AssetId::Slip44(coin) => write!(f, "slip44:{}", coin),
+        }
+    }
+}
+
+impl FromStr for AssetId {
+    type Err = StateError;
+
+    ///
+    /// Parse an id in the canonical form
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || StateError::InvalidValue(InvalidValueError::Name("asset".to_string()));
+        let parts: Vec<&str> = s.trim().split(':').collect();
+        let number = |v: &str| u32::from_str(v).map_err(|_| invalid());
+        match parts.as_slice() {
+            ["native", blockchain] => Ok(AssetId::Native(number(blockchain)?)),
+            ["erc20", blockchain, address] => AssetId::erc20(number(blockchain)?, address),
+            ["slip44", coin] => Ok(AssetId::Slip44(number(coin)?)),
+            _ => Err(invalid())
+        }
+    }
+}
+
+impl Change {
+
+    ///
+    /// Typed id of the changed asset, which is stored as a string that may be in the legacy format
+    pub fn get_asset_id(&self, blockchain: u32) -> Result<AssetId, StateError> {
+        AssetId::from_legacy(blockchain, self.asset.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use crate::access::asset::AssetId;
+
+    #[test]
+    fn parse_canonical() {
+        assert_eq!(AssetId::from_str("native:100").unwrap(), AssetId::Native(100));
+        assert_eq!(
+            AssetId::from_str("erc20:100:0xdAC17F958D2ee523a2206206994597C13D831ec7").unwrap(),
+            AssetId::Erc20(100, "0xdac17f958d2ee523a2206206994597c13d831ec7".to_string())
+        );
+        assert_eq!(AssetId::from_str("slip44:60").unwrap(), AssetId::Slip44(60));
+        assert!(AssetId::from_str("erc20:100:0xdAC17F").is_err());
+        assert!(AssetId::from_str("native").is_err());
+        assert!(AssetId::from_str("ETHER").is_err());
+    }
+
+    #[test]
+    fn write_canonical() {
+        for value in ["native:1", "erc20:100:0xdac17f958d2ee523a2206206994597c13d831ec7", "slip44:0"] {
+            assert_eq!(AssetId::from_str(value).unwrap().to_string(), value);
+        }
+    }
+
+    #[test]
+    fn parse_legacy() {
+        assert_eq!(AssetId::from_legacy(100, "ETHER").unwrap(), AssetId::Native(100));
+        assert_eq!(AssetId::from_legacy(1, "BTC").unwrap(), AssetId::Native(1));
+        assert_eq!(
+            AssetId::from_legacy(100, "ERC20:0xdAC17F958D2ee523a2206206994597C13D831ec7").unwrap(),
+            AssetId::Erc20(100, "0xdac17f958d2ee523a2206206994597c13d831ec7".to_string())
+        );
+        assert_eq!(AssetId::from_legacy(1, "native:1").unwrap(), AssetId::Native(1));
+        assert!(AssetId::from_legacy(100, "USDT").is_err());
+    }
+}
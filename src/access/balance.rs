@@ -1,7 +1,11 @@
 use std::str::FromStr;
-use chrono::{DateTime, TimeZone, Utc};
+use std::sync::mpsc::Receiver;
+use chrono::{DateTime, Duration, TimeZone, Utc};
 use num_bigint::BigUint;
 use num_traits::identities::Zero;
+use crate::access::asset::AssetId;
+use crate::access::pagination::{PageQuery, PageResult};
+use crate::access::transactions::Staleness;
 use crate::errors::{StateError};
 use crate::proto::balance::{Balance as proto_Balance, BalanceBundle as proto_BalanceBundle, Utxo as proto_Utxo};
 
@@ -11,7 +15,7 @@ pub struct Balance {
     pub ts: DateTime<Utc>,
     pub address: String,
     pub blockchain: u32,
-    pub asset: String,
+    pub asset: AssetId,
     pub utxo: Vec<Utxo>,
 }
 
@@ -20,6 +24,107 @@ pub struct Utxo {
     pub txid: String,
     pub vout: u32,
     pub amount: u64,
+    /// Id of the pending transaction the output is reserved for, see `Balances::reserve_utxo`. It's only provided by
+    /// the store, and ignored when the balance is set.
+    pub reserved_by: Option<String>,
+}
+
+///
+/// Age of an unspent output, by the time of the transaction that created it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum UtxoAge {
+    /// Less than a day
+    Day,
+    /// Less than a week
+    Week,
+    /// Less than 30 days
+    Month,
+    /// Less than 365 days
+    Year,
+    /// A year or more
+    Older,
+    /// The transaction is not known locally
+    Unknown,
+}
+
+impl UtxoAge {
+    fn of(ts: Option<DateTime<Utc>>, now: DateTime<Utc>) -> UtxoAge {
+        let age = match ts {
+            Some(ts) => now - ts,
+            None => return UtxoAge::Unknown
+        };
+        if age < Duration::days(1) {
+            UtxoAge::Day
+        } else if age < Duration::weeks(1) {
+            UtxoAge::Week
+        } else if age < Duration::days(30) {
+            UtxoAge::Month
+        } else if age < Duration::days(365) {
+            UtxoAge::Year
+        } else {
+            UtxoAge::Older
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UtxoAgeBucket {
+    pub age: UtxoAge,
+    /// Number of outputs of that age
+    pub count: usize,
+    /// Total amount of the outputs of that age
+    pub amount: u64,
+}
+
+///
+/// Summary of the unspent outputs of an address, which helps to decide if they should be consolidated
+#[derive(Debug, Clone, PartialEq)]
+pub struct UtxoReport {
+    /// Outputs grouped by age, from the most recent. Has only the non-empty groups.
+    pub ages: Vec<UtxoAgeBucket>,
+    /// Outputs with an amount below the dust threshold, which are likely to cost more in fees than they hold
+    pub dust: Vec<Utxo>,
+    /// Outputs suggested to be consolidated into one, the smallest first. Empty if there are too few of them.
+    pub consolidate: Vec<Utxo>,
+}
+
+/// Min number of the non-dust outputs to suggest a consolidation
+const CONSOLIDATE_MIN_COUNT: usize = 5;
+
+impl UtxoReport {
+
+    ///
+    /// Build a report from the outputs and time of their transactions, if known. It suggests to consolidate all
+    /// the non-dust outputs except the largest one, when there are enough of them.
+    pub(crate) fn build(utxo: Vec<(Utxo, Option<DateTime<Utc>>)>, dust_threshold: u64, now: DateTime<Utc>) -> UtxoReport {
+        let mut ages: Vec<UtxoAgeBucket> = Vec::new();
+        let mut dust = Vec::new();
+        let mut consolidate = Vec::new();
+        for (u, ts) in utxo {
+            let age = UtxoAge::of(ts, now);
+            match ages.iter_mut().find(|b| b.age == age) {
+                Some(bucket) => {
+                    bucket.count += 1;
+                    bucket.amount += u.amount;
+                }
+                None => ages.push(UtxoAgeBucket { age, count: 1, amount: u.amount })
+            }
+            if u.amount < dust_threshold {
+                dust.push(u);
+            } else {
+                consolidate.push(u);
+            }
+        }
+        ages.sort_by_key(|b| b.age);
+        dust.sort_by_key(|u| u.amount);
+        consolidate.sort_by_key(|u| u.amount);
+        if consolidate.len() < CONSOLIDATE_MIN_COUNT {
+            consolidate.clear();
+        } else {
+            consolidate.pop();
+        }
+        UtxoReport { ages, dust, consolidate }
+    }
 }
 
 impl Default for Balance {
@@ -29,7 +134,7 @@ impl Default for Balance {
             ts: Utc::now(),
             address: "NONE".to_string(),
             blockchain: 0,
-            asset: "NONE".to_string(),
+            asset: AssetId::Native(0),
             utxo: vec![]
         }
     }
@@ -37,6 +142,12 @@ impl Default for Balance {
 
 impl Balance {
 
+    ///
+    /// Time since the balance was fetched, or zero if it's in the future
+    pub fn age(&self, now: DateTime<Utc>) -> Duration {
+        (now - self.ts).max(Duration::zero())
+    }
+
     ///
     /// Make sure that the balance object is consistent.
     /// If it contains Utxo the sum of Utxo must equal the total amount.
@@ -58,6 +169,98 @@ impl Balance {
     }
 }
 
+///
+/// Balances of the addresses derived from an xpub, see `Balances::list_xpub`
+#[derive(Debug, Clone, PartialEq)]
+pub struct XPubBalance {
+    /// Known balances of the derived addresses, in the order of the derivation. The addresses without a known balance
+    /// are not included.
+    pub addresses: Vec<Balance>,
+    /// Sum of the balances per blockchain and asset, where the address is the xpub itself, the timestamp is the oldest
+    /// of the summed balances, and the utxo are all utxo of them.
+    pub total: Vec<Balance>,
+    /// Number of the derived addresses that were checked, i.e. from index 0 up to the known position plus the gap
+    pub scanned: u32,
+}
+
+impl XPubBalance {
+    pub(crate) fn new(xpub: String, addresses: Vec<Balance>, scanned: u32) -> XPubBalance {
+        let mut total: Vec<Balance> = Vec::new();
+        for b in addresses.iter() {
+            match total.iter_mut().find(|t| t.blockchain == b.blockchain && t.asset == b.asset) {
+                Some(t) => {
+                    t.amount += &b.amount;
+                    t.ts = t.ts.min(b.ts);
+                    t.utxo.extend(b.utxo.iter().cloned());
+                }
+                None => total.push(Balance {
+                    address: xpub.clone(),
+                    ..b.clone()
+                })
+            }
+        }
+        // an address with inconsistent utxo is stored without them, and then the total cannot have them either
+        let total = total.into_iter().map(|t| t.validated()).collect();
+        XPubBalance { addresses, total, scanned }
+    }
+}
+
+///
+/// Amount of a balance changed by `Balances::set` or `Balances::set_many`
+#[derive(Debug, Clone, PartialEq)]
+pub struct BalanceChange {
+    pub address: String,
+    pub blockchain: u32,
+    pub asset: AssetId,
+    /// Amount before the change, or `None` if the balance wasn't known
+    pub old_amount: Option<BigUint>,
+    pub new_amount: BigUint,
+}
+
+impl BalanceChange {
+
+    ///
+    /// Change made by setting the `value` over the `base` balances of the same address. Returns `None` if the amount
+    /// is the same, or if a previously unknown balance is zero.
+    pub(crate) fn of(base: &[Balance], value: &Balance) -> Option<BalanceChange> {
+        let old_amount = base.iter()
+            .find(|b| b.blockchain == value.blockchain && b.asset == value.asset)
+            .map(|b| b.amount.clone());
+        let changed = match &old_amount {
+            Some(old) => *old != value.amount,
+            None => !value.amount.is_zero(),
+        };
+        if !changed {
+            return None
+        }
+        Some(BalanceChange {
+            address: value.address.clone(),
+            blockchain: value.blockchain,
+            asset: value.asset.clone(),
+            old_amount,
+            new_amount: value.amount.clone(),
+        })
+    }
+}
+
+///
+/// Balances changed by a write to the store, as it's sent to the subscribers. It's sent also when the storage is in
+/// the degraded mode and the write was only queued in memory, as the new amounts are already returned by the reads.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BalancesChange {
+    pub changes: Vec<BalanceChange>,
+    /// Number of the write in the global sequence of the storage, same as `SledStorage::get_sequence` right after it,
+    /// or `None` if the write was queued in memory
+    pub seq: Option<u64>,
+}
+
+impl BalancesChange {
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
 pub(crate) fn concat(base: Vec<Balance>, extra: Balance) -> Vec<Balance> {
     let mut result = Vec::new();
     for b in base {
@@ -78,13 +281,70 @@ pub trait Balances {
     /// Set current value. It merges multiple balances per address in one list, so all of them fetched in bulk later
     fn set(&self, value: Balance) -> Result<(), StateError>;
 
+    ///
+    /// Set multiple values at once, ex. after refreshing all addresses of a wallet. All of them are validated first,
+    /// so nothing is stored if any of them is invalid, and then it's written as a single batch.
+    fn set_many(&self, values: Vec<Balance>) -> Result<(), StateError>;
+
     ///
     /// List all known balances per address. The address is supposed to be a single address, not a XPub
     fn list(&self, address: String) -> Result<Vec<Balance>, StateError>;
 
+    ///
+    /// Same as `list`, but only the balances on the `blockchain` and of the `asset`, if they are specified
+    fn list_filtered(&self, address: String, blockchain: Option<u32>, asset: Option<AssetId>) -> Result<Vec<Balance>, StateError>;
+
+    ///
+    /// Get the balance of the `asset` on the `blockchain` for the address, if it's known
+    fn get(&self, address: String, blockchain: u32, asset: AssetId) -> Result<Option<Balance>, StateError>;
+
+    ///
+    /// Same as `list`, but with the age of each balance and whether it's stale, i.e. older than `balance_stale_after`
+    /// of the `TtlPolicy`. It's never `Staleness::Missing`, as there is no entry for a missing balance.
+    fn list_with_age(&self, address: String) -> Result<Vec<(Balance, Duration, Staleness)>, StateError>;
+
+    ///
+    /// Find the addresses with a balance older than `max_age`, the most outdated first, so a refresher knows what
+    /// to fetch first. An address is included if any of its balances is that old.
+    fn list_stale(&self, max_age: Duration) -> Result<Vec<String>, StateError>;
+
+    ///
+    /// List all stored balances, ordered by the address (`PageOrder::NewestFirst` is A to Z). All balances of an address
+    /// are always on the same page, so a page may have a few more balances than the `limit`.
+    fn list_all(&self, page: PageQuery) -> Result<PageResult<Balance>, StateError>;
+
+    ///
+    /// Reserve the unspent outputs, as `(txid, vout)`, for the pending transaction `tx_id`, so another transaction
+    /// doesn't spend them too. Fails if any of them is already reserved for another transaction, and then nothing is
+    /// reserved. The reserved outputs are marked with `Utxo::reserved_by` in the listed balances.
+    /// A reservation is released automatically when a transaction spending the output is submitted or set as confirmed,
+    /// or when the transaction `tx_id` is submitted or set as dropped or replaced.
+    fn reserve_utxo(&self, tx_id: String, outputs: Vec<(String, u32)>) -> Result<(), StateError>;
+
+    ///
+    /// Release all outputs reserved for the transaction, ex. when a draft is discarded before it's sent.
+    /// Returns the number of released outputs.
+    fn release_utxo(&self, tx_id: String) -> Result<usize, StateError>;
+
     /// Clear all known balances per address
     fn clear(&self, address: String) -> Result<(), StateError>;
 
+    ///
+    /// Summarize the known unspent outputs of the address: their age (by the stored transactions), the outputs with an
+    /// amount below `dust_threshold`, and which of them are worth to consolidate.
+    fn utxo_report(&self, address: String, dust_threshold: u64) -> Result<UtxoReport, StateError>;
+
+    ///
+    /// List the known balances of the addresses derived from the `xpub`, and their total. It checks the addresses from
+    /// index 0 up to the position known by `XPubPosition` plus the `gap` (usually `XPUB_GAP_LIMIT`), so a payment to
+    /// an address the wallet gave out but hasn't seen used yet is included too.
+    fn list_xpub(&self, xpub: String, gap: u32) -> Result<XPubBalance, StateError>;
+
+    ///
+    /// Subscribe to the changes of the amounts made by `set` and `set_many` through any handle of the same storage,
+    /// ex. to notify the user about incoming funds. The subscription ends when the receiver is dropped.
+    fn subscribe(&self) -> Result<Receiver<BalancesChange>, StateError>;
+
 }
 
 impl TryFrom<&proto_Balance> for Balance {
@@ -97,7 +357,8 @@ impl TryFrom<&proto_Balance> for Balance {
             ts: Utc.timestamp_millis(value.ts as i64),
             address: value.address.clone(),
             blockchain: value.blockchain,
-            asset: value.asset.clone(),
+            asset: AssetId::from_legacy(value.blockchain, value.asset.as_str())
+                .map_err(|_| StateError::CorruptedValue)?,
             utxo: value.utxo.to_vec().iter().map(|p| p.into()).collect()
         }.validated())
     }
@@ -111,7 +372,7 @@ impl Into<proto_Balance> for Balance {
         proto.set_ts(self.ts.timestamp_millis() as u64);
         proto.set_address(self.address);
         proto.set_blockchain(self.blockchain);
-        proto.set_asset(self.asset);
+        proto.set_asset(self.asset.to_string());
         proto.set_utxo(self.utxo.iter().map(|u| u.clone().into()).collect());
 
         proto
@@ -147,7 +408,8 @@ impl From<&proto_Utxo> for Utxo {
         Utxo {
             amount: value.get_amount(),
             txid: value.get_txid().to_string(),
-            vout: value.get_vout()
+            vout: value.get_vout(),
+            reserved_by: None,
         }
     }
 }
@@ -0,0 +1,138 @@
+use chrono::{Datelike, DateTime, Duration, NaiveDate, TimeZone, Utc};
+
+///
+/// A calendar range relative to the current moment, as the user sees it in their timezone
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalendarRange {
+    Today,
+    Yesterday,
+    /// Current week, starting on Monday
+    ThisWeek,
+    LastWeek,
+    ThisMonth,
+    LastMonth,
+    ThisYear,
+    LastYear,
+    /// Specific month, as year and month number (1-12)
+    Month(i32, u32),
+    /// Specific year
+    Year(i32),
+}
+
+impl CalendarRange {
+
+    ///
+    /// First and last day of the range (both inclusive), given the current date in the user's timezone
+    fn get_days(&self, today: NaiveDate) -> Option<(NaiveDate, NaiveDate)> {
+        let month_start = |year: i32, month: u32| NaiveDate::from_ymd_opt(year, month, 1);
+        let next_month_start = |year: i32, month: u32| if month == 12 {
+            NaiveDate::from_ymd_opt(year + 1, 1, 1)
+        } else {
+            NaiveDate::from_ymd_opt(year, month + 1, 1)
+        };
+        let month = |year: i32, month: u32| -> Option<(NaiveDate, NaiveDate)> {
+            Some((month_start(year, month)?, next_month_start(year, month)?.pred_opt()?))
+        };
+        let year = |year: i32| -> Option<(NaiveDate, NaiveDate)> {
+            Some((NaiveDate::from_ymd_opt(year, 1, 1)?, NaiveDate::from_ymd_opt(year, 12, 31)?))
+        };
+        let week_start = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+
+        match self {
+            CalendarRange::Today => Some((today, today)),
+            CalendarRange::Yesterday => today.pred_opt().map(|d| (d, d)),
+            CalendarRange::ThisWeek => Some((week_start, week_start + Duration::days(6))),
+            CalendarRange::LastWeek => Some((week_start - Duration::days(7), week_start - Duration::days(1))),
+            CalendarRange::ThisMonth => month(today.year(), today.month()),
+            CalendarRange::LastMonth => if today.month() == 1 {
+                month(today.year() - 1, 12)
+            } else {
+                month(today.year(), today.month() - 1)
+            },
+            CalendarRange::ThisYear => year(today.year()),
+            CalendarRange::LastYear => year(today.year() - 1),
+            CalendarRange::Month(y, m) => month(*y, *m),
+            CalendarRange::Year(y) => year(*y),
+        }
+    }
+
+    ///
+    /// Get UTC bounds of the range, as `(after, before)` where both are inclusive, which is the format used by the
+    /// `after`/`before` of a transactions filter. The calendar days are taken in the timezone of `now`.
+    /// Returns `None` if the range is invalid (ex. month 13).
+    pub fn get_bounds<Tz: TimeZone>(&self, now: DateTime<Tz>) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        let tz = now.timezone();
+        let (first, last) = self.get_days(now.date_naive())?;
+        let start = start_of_day(&tz, first)?;
+        let end = start_of_day(&tz, last.succ_opt()?)? - Duration::milliseconds(1);
+        Some((start, end))
+    }
+}
+
+///
+/// Beginning of the day in the specified timezone, as UTC.
+/// Midnight may not exist or be ambiguous on a DST switch, in that case it takes the earliest existing moment of the day.
+pub fn start_of_day<Tz: TimeZone>(tz: &Tz, date: NaiveDate) -> Option<DateTime<Utc>> {
+    let midnight = date.and_hms_opt(0, 0, 0)?;
+    // a DST gap is never longer than a couple of hours, so the day always starts before 3am
+    (0..=3)
+        .filter_map(|h| tz.from_local_datetime(&(midnight + Duration::hours(h))).earliest())
+        .next()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{FixedOffset, TimeZone, Utc};
+    use crate::access::calendar::CalendarRange;
+
+    #[test]
+    fn today_in_utc() {
+        let now = Utc.with_ymd_and_hms(2022, 3, 16, 13, 45, 10).unwrap();
+        let (after, before) = CalendarRange::Today.get_bounds(now).unwrap();
+        assert_eq!(after, Utc.with_ymd_and_hms(2022, 3, 16, 0, 0, 0).unwrap());
+        assert_eq!(before.timestamp_millis(), Utc.with_ymd_and_hms(2022, 3, 17, 0, 0, 0).unwrap().timestamp_millis() - 1);
+    }
+
+    #[test]
+    fn today_in_timezone() {
+        // it's still 15th in UTC, but already 16th in UTC+10
+        let tz = FixedOffset::east_opt(10 * 3600).unwrap();
+        let now = Utc.with_ymd_and_hms(2022, 3, 15, 20, 0, 0).unwrap().with_timezone(&tz);
+        let (after, _) = CalendarRange::Today.get_bounds(now).unwrap();
+        assert_eq!(after, Utc.with_ymd_and_hms(2022, 3, 15, 14, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn last_month_in_january() {
+        let tz = FixedOffset::west_opt(5 * 3600).unwrap();
+        let now = tz.with_ymd_and_hms(2022, 1, 10, 12, 0, 0).unwrap();
+        let (after, before) = CalendarRange::LastMonth.get_bounds(now).unwrap();
+        assert_eq!(after, Utc.with_ymd_and_hms(2021, 12, 1, 5, 0, 0).unwrap());
+        assert_eq!(before.timestamp_millis(), Utc.with_ymd_and_hms(2022, 1, 1, 5, 0, 0).unwrap().timestamp_millis() - 1);
+    }
+
+    #[test]
+    fn last_week() {
+        // Wednesday
+        let now = Utc.with_ymd_and_hms(2022, 3, 16, 13, 45, 10).unwrap();
+        let (after, before) = CalendarRange::LastWeek.get_bounds(now).unwrap();
+        assert_eq!(after, Utc.with_ymd_and_hms(2022, 3, 7, 0, 0, 0).unwrap());
+        assert_eq!(before.timestamp_millis(), Utc.with_ymd_and_hms(2022, 3, 14, 0, 0, 0).unwrap().timestamp_millis() - 1);
+    }
+
+    #[test]
+    fn whole_year() {
+        let now = Utc.with_ymd_and_hms(2022, 3, 16, 13, 45, 10).unwrap();
+        let (after, before) = CalendarRange::LastYear.get_bounds(now).unwrap();
+        assert_eq!(after, Utc.with_ymd_and_hms(2021, 1, 1, 0, 0, 0).unwrap());
+        assert_eq!(before.timestamp_millis(), Utc.with_ymd_and_hms(2022, 1, 1, 0, 0, 0).unwrap().timestamp_millis() - 1);
+    }
+
+    #[test]
+    fn invalid_month() {
+        let now = Utc.with_ymd_and_hms(2022, 3, 16, 13, 45, 10).unwrap();
+        assert!(CalendarRange::Month(2022, 13).get_bounds(now).is_none());
+        assert!(CalendarRange::Month(2022, 12).get_bounds(now).is_some());
+    }
+}
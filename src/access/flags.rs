@@ -0,0 +1,88 @@
+use std::collections::BTreeSet;
+use std::sync::mpsc::Receiver;
+use crate::errors::StateError;
+use crate::proto::flags::{FeatureFlag, FeatureFlag_oneof_value};
+
+///
+/// Value of a feature flag
+#[derive(Debug, Clone, PartialEq)]
+pub enum FlagValue {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Text(String),
+}
+
+///
+/// Flags modified by a write to the store, as it's sent to the subscribers
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FlagsChange {
+    /// Keys of the set or removed flags
+    pub keys: BTreeSet<String>,
+    /// Number of the write in the global sequence of the storage, same as `SledStorage::get_sequence` right after it
+    pub seq: u64,
+}
+
+impl FlagsChange {
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+}
+
+///
+/// Persistent feature flags, ex. experimental toggles or a snapshot of a remote config. Each flag keeps a typed value
+/// and who set it and when.
+pub trait Flags {
+
+    ///
+    /// Set the value of the flag, `set_by` is a free form name of the source, ex. `user` or `remote-config`.
+    /// Returns the stored flag.
+    fn set(&self, key: String, value: FlagValue, set_by: String) -> Result<FeatureFlag, StateError>;
+
+    ///
+    /// Replace all the flags set by `set_by` with the `values`, i.e. set the values and remove the flags previously set
+    /// by the same source but missing in the snapshot. It's applied at once, so the readers never see a partial snapshot.
+    /// Returns the number of changed flags.
+    fn set_snapshot(&self, values: Vec<(String, FlagValue)>, set_by: String) -> Result<usize, StateError>;
+
+    ///
+    /// Get the flag, or `None` if it's not set
+    fn get(&self, key: String) -> Result<Option<FeatureFlag>, StateError>;
+
+    ///
+    /// All set flags, ordered by the key
+    fn list(&self) -> Result<Vec<FeatureFlag>, StateError>;
+
+    ///
+    /// Remove the flag. Returns `false` if it wasn't set.
+    fn remove(&self, key: String) -> Result<bool, StateError>;
+
+    ///
+    /// Subscribe to the changes of the flags made through any handle of the same storage. The subscription ends when
+    /// the receiver is dropped.
+    fn subscribe(&self) -> Result<Receiver<FlagsChange>, StateError>;
+}
+
+impl FeatureFlag {
+
+    ///
+    /// Typed value of the flag, or `None` if it's not set
+    pub fn get_flag_value(&self) -> Option<FlagValue> {
+        self.value.as_ref().map(|v| match v {
+            FeatureFlag_oneof_value::bool_value(v) => FlagValue::Bool(*v),
+            FeatureFlag_oneof_value::int_value(v) => FlagValue::Int(*v),
+            FeatureFlag_oneof_value::float_value(v) => FlagValue::Float(*v),
+            FeatureFlag_oneof_value::text_value(v) => FlagValue::Text(v.clone()),
+        })
+    }
+
+    pub(crate) fn set_flag_value(&mut self, value: FlagValue) {
+        match value {
+            FlagValue::Bool(v) => self.set_bool_value(v),
+            FlagValue::Int(v) => self.set_int_value(v),
+            FlagValue::Float(v) => self.set_float_value(v),
+            FlagValue::Text(v) => self.set_text_value(v),
+        }
+    }
+}
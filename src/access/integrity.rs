@@ -0,0 +1,58 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+///
+/// A kind of record referencing a transaction from outside of the transaction itself
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TxReference {
+    /// Meta of the transaction (label, tags, flags) with its history
+    Meta,
+    /// An allowance revoked by the transaction, see `Allowance.revoked_by_tx`
+    AllowanceRevoke,
+}
+
+///
+/// What to do with a referencing record when the transaction is forgotten
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CascadePolicy {
+    /// Delete the referencing record
+    Delete,
+    /// Keep the record, but remove the reference from it. The meta has no reference field, it's bound to the tx id, so
+    /// it's just kept and applies again if the same transaction is submitted later.
+    Detach,
+}
+
+///
+/// A reference to a transaction which doesn't exist in the storage
+#[derive(Debug, Clone, PartialEq)]
+pub struct DanglingReference {
+    pub kind: TxReference,
+    /// Storage key of the referencing record
+    pub key: String,
+    pub blockchain: u32,
+    pub tx_id: String,
+}
+
+///
+/// Registry of the cascade policies applied to the records referencing a transaction when it's forgotten. It's shared
+/// by all stores opened from the same storage.
+#[derive(Default)]
+pub struct References {
+    policies: RwLock<HashMap<TxReference, CascadePolicy>>,
+}
+
+impl References {
+
+    ///
+    /// Set the policy for the kind of the references
+    pub fn set_policy(&self, kind: TxReference, policy: CascadePolicy) {
+        self.policies.write().unwrap().insert(kind, policy);
+    }
+
+    ///
+    /// Policy for the kind of the references. By default all of them are detached, so nothing made by the user is
+    /// deleted implicitly.
+    pub fn get_policy(&self, kind: TxReference) -> CascadePolicy {
+        self.policies.read().unwrap().get(&kind).copied().unwrap_or(CascadePolicy::Detach)
+    }
+}
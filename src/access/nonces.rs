@@ -0,0 +1,20 @@
+use crate::errors::StateError;
+
+///
+/// Keep the highest used nonce per address, so a new transaction can get the next nonce even when the node doesn't
+/// know yet about the transactions just sent. It's updated from the submitted transactions with a `nonce_ref`.
+pub trait Nonces {
+
+    ///
+    /// Remember that the `nonce` is used by the `address` on the `blockchain`. If the currently stored nonce has a
+    /// larger value it stays as is.
+    fn set_at_least(&self, blockchain: u32, address: String, nonce: u64) -> Result<(), StateError>;
+
+    ///
+    /// Get the highest known used nonce of the `address`
+    fn get(&self, blockchain: u32, address: String) -> Result<Option<u64>, StateError>;
+
+    ///
+    /// Get the nonce to use for a new transaction from the `address`. Returns zero if no nonce is known.
+    fn get_next_nonce(&self, blockchain: u32, address: String) -> Result<u64, StateError>;
+}
@@ -1,9 +1,50 @@
+use std::ops::{Bound, RangeBounds};
+use crate::errors::{InvalidValueError, StateError};
+
 #[derive(Debug, Clone)]
 pub struct Cursor {
-    // a db key to start querying from
+    // an opaque value produced by the store, which references the position to continue querying from
     pub offset: String,
 }
 
+impl Cursor {
+
+    ///
+    /// Create a cursor for the db key of the store index
+    pub(crate) fn encode(store: &str, order: PageOrder, key: String) -> Cursor {
+        Cursor {
+            offset: hex::encode(format!("{}|{}|{}", store, order.get_code(), key)),
+        }
+    }
+
+    ///
+    /// Get the db key referenced by the cursor, checking that it was produced by the same store with the same order,
+    /// and that the key is within any of the `ranges` of the current query
+    pub(crate) fn decode(&self, store: &str, order: PageOrder, ranges: &[(Bound<String>, Bound<String>)]) -> Result<String, StateError> {
+        let invalid = || StateError::InvalidValue(InvalidValueError::Name("cursor".to_string()));
+        let value = hex::decode(&self.offset).map_err(|_| invalid())?;
+        let value = String::from_utf8(value).map_err(|_| invalid())?;
+        let mut parts = value.splitn(3, '|');
+        let (cursor_store, cursor_order, key) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(s), Some(o), Some(k)) => (s, o, k.to_string()),
+            _ => return Err(invalid())
+        };
+        if cursor_store != store || cursor_order != order.get_code() || !ranges.iter().any(|r| r.contains(&key)) {
+            return Err(invalid())
+        }
+        Ok(key)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Order of the items by their time
+pub enum PageOrder {
+    /// Most recent first, which is the default
+    NewestFirst,
+    /// Oldest first, ex. to load the history from the beginning
+    OldestFirst,
+}
+
 #[derive(Debug, Clone)]
 /// Pagination options
 pub struct PageQuery {
@@ -11,6 +52,17 @@ pub struct PageQuery {
     pub limit: usize,
     /// Cursor value to start from
     pub cursor: Option<Cursor>,
+    /// Order of the items. A cursor must be used with the same order as the page it's received from
+    pub order: PageOrder,
+}
+
+impl PageOrder {
+    fn get_code(&self) -> &'static str {
+        match self {
+            PageOrder::NewestFirst => "N",
+            PageOrder::OldestFirst => "O",
+        }
+    }
 }
 
 impl Default for PageQuery {
@@ -18,6 +70,7 @@ impl Default for PageQuery {
         PageQuery {
             limit: 100,
             cursor: None,
+            order: PageOrder::NewestFirst,
         }
     }
 }
@@ -29,5 +82,7 @@ pub struct PageResult<T> {
     pub values: Vec<T>,
     /// Cursor to start next page, or None if finished
     pub cursor: Option<Cursor>,
+    /// Number of records matched by the query but skipped because they cannot be read (ex. a corrupted data)
+    pub skipped: usize,
 }
 
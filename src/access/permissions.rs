@@ -0,0 +1,165 @@
+use crate::errors::StateError;
+
+///
+/// A store of the `SledStorage`, as a subject of the permissions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Store {
+    Transactions,
+    AddressBook,
+    XPubPosition,
+    Balances,
+    Cache,
+    Allowances,
+    SyncState,
+    Quarantine,
+    AddressRegistry,
+    Nonces,
+    Tasks,
+    Flags,
+    Tokens,
+}
+
+impl Store {
+    fn get_flag(&self) -> u32 {
+        match self {
+            Store::Transactions => 1,
+            Store::AddressBook => 1 << 1,
+            Store::XPubPosition => 1 << 2,
+            Store::Balances => 1 << 3,
+            Store::Cache => 1 << 4,
+            Store::Allowances => 1 << 5,
+            Store::SyncState => 1 << 6,
+            Store::Quarantine => 1 << 7,
+            Store::AddressRegistry => 1 << 8,
+            Store::Nonces => 1 << 9,
+            Store::Tasks => 1 << 10,
+            Store::Flags => 1 << 11,
+            Store::Tokens => 1 << 12,
+        }
+    }
+}
+
+const ALL_STORES: u32 = (1 << 13) - 1;
+
+///
+/// Read and write permissions for each of the stores. A `SledStorage` restricted with the permissions checks them on
+/// every call, and a call not permitted fails with `StateError::NotPermitted`. Ex., a storage that can only read
+/// transactions but can also update the cache:
+///
+/// ```
+/// use emerald_wallet_state::access::permissions::{Permissions, Store};
+/// let permissions = Permissions::none()
+///     .with_read(Store::Transactions)
+///     .with_write(Store::Cache);
+/// assert!(permissions.can_read(Store::Transactions));
+/// assert!(!permissions.can_write(Store::Transactions));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Permissions {
+    read: u32,
+    write: u32,
+}
+
+impl Default for Permissions {
+    fn default() -> Self {
+        Permissions::full()
+    }
+}
+
+impl Permissions {
+
+    ///
+    /// Everything is allowed
+    pub fn full() -> Permissions {
+        Permissions { read: ALL_STORES, write: ALL_STORES }
+    }
+
+    ///
+    /// All the stores can be read, but nothing can be changed
+    pub fn read_only() -> Permissions {
+        Permissions { read: ALL_STORES, write: 0 }
+    }
+
+    ///
+    /// Nothing is allowed
+    pub fn none() -> Permissions {
+        Permissions { read: 0, write: 0 }
+    }
+
+    ///
+    /// Allow reading the store
+    pub fn with_read(self, store: Store) -> Permissions {
+        Permissions { read: self.read | store.get_flag(), write: self.write }
+    }
+
+    ///
+    /// Allow reading and changing the store
+    pub fn with_write(self, store: Store) -> Permissions {
+        Permissions { read: self.read | store.get_flag(), write: self.write | store.get_flag() }
+    }
+
+    ///
+    /// Deny any access to the store
+    pub fn without(self, store: Store) -> Permissions {
+        Permissions { read: self.read & !store.get_flag(), write: self.write & !store.get_flag() }
+    }
+
+    ///
+    /// Permissions allowed by both, i.e. the current permissions limited by the `other`
+    pub fn intersect(&self, other: &Permissions) -> Permissions {
+        Permissions { read: self.read & other.read, write: self.write & other.write }
+    }
+
+    pub fn can_read(&self, store: Store) -> bool {
+        self.read & store.get_flag() != 0
+    }
+
+    pub fn can_write(&self, store: Store) -> bool {
+        self.write & store.get_flag() != 0
+    }
+
+    ///
+    /// True if all the stores can be read
+    pub fn can_read_all(&self) -> bool {
+        self.read == ALL_STORES
+    }
+
+    pub(crate) fn check_read(&self, store: Store) -> Result<(), StateError> {
+        if !self.can_read(store) {
+            return Err(StateError::NotPermitted)
+        }
+        Ok(())
+    }
+
+    pub(crate) fn check_write(&self, store: Store) -> Result<(), StateError> {
+        if !self.can_write(store) {
+            return Err(StateError::NotPermitted)
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::access::permissions::{Permissions, Store};
+
+    #[test]
+    fn write_includes_read() {
+        let permissions = Permissions::none().with_write(Store::Cache);
+        assert!(permissions.can_read(Store::Cache));
+        assert!(permissions.can_write(Store::Cache));
+        assert!(!permissions.can_read(Store::Transactions));
+    }
+
+    #[test]
+    fn intersect_doesnt_extend() {
+        let current = Permissions::read_only().with_write(Store::Cache);
+        let requested = Permissions::full().without(Store::Balances);
+        let act = current.intersect(&requested);
+        assert!(act.can_write(Store::Cache));
+        assert!(!act.can_write(Store::Transactions));
+        assert!(act.can_read(Store::Transactions));
+        assert!(!act.can_read(Store::Balances));
+        assert!(!act.can_read_all());
+    }
+}
@@ -0,0 +1,27 @@
+use crate::errors::StateError;
+use crate::proto::quarantine::QuarantinedRecord;
+
+///
+/// Records which cannot be decoded, ex. written by a newer version or damaged on disk. Instead of being silently
+/// skipped on each read, such a record is moved out of its store into the quarantine, together with the details of
+/// the failure, so it can be inspected, restored after an upgrade, or dropped.
+pub trait Quarantine {
+
+    ///
+    /// List all quarantined records, ordered by the original key
+    fn list(&self) -> Result<Vec<QuarantinedRecord>, StateError>;
+
+    ///
+    /// Try to decode the quarantined records again, and put back to their stores those which can be decoded now.
+    /// If a store already has a new value under the same key, the quarantined record is dropped as outdated.
+    /// Returns the number of restored records.
+    fn retry(&self) -> Result<usize, StateError>;
+
+    ///
+    /// Export all quarantined records as a `QuarantineBundle`, ex. to attach to a bug report
+    fn export(&self) -> Result<Vec<u8>, StateError>;
+
+    ///
+    /// Delete all quarantined records. Returns the number of deleted records.
+    fn purge(&self) -> Result<usize, StateError>;
+}
@@ -0,0 +1,312 @@
+//!
+//! A small query language to build the filters from a text, ex. for power users or a debug console:
+//!
+//! ```text
+//! wallet:72279ede-44c4-4951-925b-f51a7b9e929a asset:USDC after:2024-01-01 direction:send text:"rent"
+//! ```
+//!
+//! A query is a list of `field:value` terms separated by spaces, and all of them are required. A value with spaces must
+//! be in double quotes. A term without a field is a text search, i.e. `rent` is the same as `text:rent`.
+//!
+//! Fields of a transactions query:
+//!
+//! - `wallet:<UUID>` or `wallet:<UUID>/<ENTRY>`
+//! - `address:<ADDRESS>`, which may be repeated to accept any of them
+//! - `blockchain:<ID>`, which may be repeated to accept any of them
+//! - `after:<DATE>` and `before:<DATE>`, as `2024-01-01` (midnight UTC), RFC 3339 or a timestamp in milliseconds
+//! - `state:<prepared|submitted|replaced|confirmed|dropped>`
+//! - `status:<unknown|ok|failed>`
+//! - `direction:<send|receive>`
+//! - `asset:<CODE>`
+//! - `tag:<TAG>`
+//! - `confirmations:<N>`
+//! - `is:<starred|review|hidden|unattributed|final>`, where `hidden` includes the hidden transactions and `final`
+//!   skips the replaced ones
+//! - `text:<TEXT>`
+//!
+//! Fields of an address book query: `blockchain:<ID>`, `network:<mainnet|testnet>`, `group:<GROUP>`, `locale:<LOCALE>`,
+//! `is:archived` to include the archived items, `sort:<created|label|updated>`, `created-after:<DATE>`,
+//! `created-before:<DATE>`, `updated-after:<DATE>`, and `text:<TEXT>`.
+//!
+use std::str::FromStr;
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use uuid::Uuid;
+use crate::access::addressbook::{BookItemSort, Filter as BookFilter};
+use crate::access::transactions::{AddressRef, Filter as TxFilter, WalletRef};
+use crate::errors::{InvalidValueError, StateError};
+use crate::proto::addressbook::NetworkKind;
+use crate::proto::transactions::{Direction, State, Status};
+
+///
+/// A single `field:value` term of a query, where the field is `None` for a plain text
+#[derive(Debug, Clone, PartialEq)]
+struct Term {
+    field: Option<String>,
+    value: String,
+}
+
+fn invalid(field: &str, message: &str) -> StateError {
+    StateError::InvalidValue(InvalidValueError::NameMessage(field.to_string(), message.to_string()))
+}
+
+///
+/// Split the query into the terms, keeping the quoted values together
+fn tokenize(query: &str) -> Result<Vec<Term>, StateError> {
+    let mut terms = Vec::new();
+    let mut chars = query.chars().peekable();
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        if chars.peek().is_none() {
+            break
+        }
+        let mut field: Option<String> = None;
+        let mut value = String::new();
+        let mut quoted = false;
+        while let Some(c) = chars.next() {
+            match c {
+                '"' if value.is_empty() && !quoted => quoted = true,
+                '"' if quoted => {
+                    quoted = false;
+                    if chars.peek().is_some_and(|c| !c.is_whitespace()) {
+                        return Err(invalid("query", "Unexpected text after a quoted value"))
+                    }
+                    break
+                }
+                ':' if field.is_none() && !quoted && !value.is_empty() => field = Some(std::mem::take(&mut value).to_lowercase()),
+                c if c.is_whitespace() && !quoted => break,
+                c => value.push(c),
+            }
+        }
+        if quoted {
+            return Err(invalid("query", "Quote is not closed"))
+        }
+        if let Some(field) = &field {
+            if value.is_empty() {
+                return Err(invalid(field, "Empty value"))
+            }
+        }
+        terms.push(Term { field, value });
+    }
+    Ok(terms)
+}
+
+fn parse_date(field: &str, value: &str) -> Result<DateTime<Utc>, StateError> {
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return date.and_hms_opt(0, 0, 0)
+            .map(|dt| Utc.from_utc_datetime(&dt))
+            .ok_or_else(|| invalid(field, "Invalid date"))
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Ok(dt.with_timezone(&Utc))
+    }
+    value.parse::<i64>().ok()
+        .and_then(|ts| Utc.timestamp_millis_opt(ts).single())
+        .ok_or_else(|| invalid(field, "Invalid date"))
+}
+
+fn parse_number<T: FromStr>(field: &str, value: &str) -> Result<T, StateError> {
+    T::from_str(value).map_err(|_| invalid(field, "Not a number"))
+}
+
+fn parse_wallet(value: &str) -> Result<WalletRef, StateError> {
+    match value.split_once('/') {
+        Some((wallet_id, entry_id)) => Ok(WalletRef::SelectedEntry(Uuid::from_str(wallet_id)?, parse_number("wallet", entry_id)?)),
+        None => Ok(WalletRef::WholeWallet(Uuid::from_str(value)?)),
+    }
+}
+
+fn append_text(current: &mut Option<String>, value: String) {
+    *current = Some(match current.take() {
+        Some(text) => format!("{} {}", text, value),
+        None => value
+    });
+}
+
+impl FromStr for TxFilter {
+    type Err = StateError;
+
+    ///
+    /// Parse a transactions query, see the module docs for the syntax
+    fn from_str(query: &str) -> Result<Self, Self::Err> {
+        let mut filter = TxFilter::default();
+        for term in tokenize(query)? {
+            let value = term.value;
+            let field = match term.field {
+                Some(field) => field,
+                None => {
+                    append_text(&mut filter.text, value);
+                    continue
+                }
+            };
+            match field.as_str() {
+                "wallet" => filter.wallet = Some(parse_wallet(value.as_str())?),
+                "address" => filter.addresses.get_or_insert_with(Vec::new).push(AddressRef::SingleAddress(value)),
+                "blockchain" => filter.blockchains.get_or_insert_with(Vec::new).push(parse_number(&field, value.as_str())?),
+                "after" => filter.after = Some(parse_date(&field, value.as_str())?),
+                "before" => filter.before = Some(parse_date(&field, value.as_str())?),
+                "state" => filter.state = Some(match value.to_lowercase().as_str() {
+                    "prepared" => State::PREPARED,
+                    "submitted" => State::SUBMITTED,
+                    "replaced" => State::REPLACED,
+                    "confirmed" => State::CONFIRMED,
+                    "dropped" => State::DROPPED,
+                    _ => return Err(invalid(&field, "Unknown state"))
+                }),
+                "status" => filter.status = Some(match value.to_lowercase().as_str() {
+                    "unknown" => Status::UNKNOWN,
+                    "ok" => Status::OK,
+                    "failed" => Status::FAILED,
+                    _ => return Err(invalid(&field, "Unknown status"))
+                }),
+                "direction" => filter.direction = Some(match value.to_lowercase().as_str() {
+                    "send" => Direction::SEND,
+                    "receive" => Direction::RECEIVE,
+                    _ => return Err(invalid(&field, "Unknown direction"))
+                }),
+                "asset" => filter.asset = Some(value),
+                "tag" => filter.tag = Some(value),
+                "confirmations" => filter.min_confirmations = Some(parse_number(&field, value.as_str())?),
+                "is" => match value.to_lowercase().as_str() {
+                    "starred" => filter.starred_only = true,
+                    "review" => filter.needs_review_only = true,
+                    "hidden" => filter.include_hidden = true,
+                    "unattributed" => filter.unattributed_only = true,
+                    "final" => filter.collapse_replaced = true,
+                    _ => return Err(invalid(&field, "Unknown flag"))
+                },
+                "text" => append_text(&mut filter.text, value),
+                _ => return Err(invalid(&field, "Unknown field"))
+            }
+        }
+        Ok(filter)
+    }
+}
+
+impl FromStr for BookFilter {
+    type Err = StateError;
+
+    ///
+    /// Parse an address book query, see the module docs for the syntax
+    fn from_str(query: &str) -> Result<Self, Self::Err> {
+        let mut filter = BookFilter::default();
+        for term in tokenize(query)? {
+            let value = term.value;
+            let field = match term.field {
+                Some(field) => field,
+                None => {
+                    append_text(&mut filter.text, value);
+                    continue
+                }
+            };
+            match field.as_str() {
+                "blockchain" => filter.blockchain = Some(parse_number(&field, value.as_str())?),
+                "network" => filter.network = Some(match value.to_lowercase().as_str() {
+                    "mainnet" => NetworkKind::NETWORK_MAINNET,
+                    "testnet" => NetworkKind::NETWORK_TESTNET,
+                    _ => return Err(invalid(&field, "Unknown network"))
+                }),
+                "group" => filter.group = Some(value),
+                "locale" => filter.locale = Some(value),
+                "is" => match value.to_lowercase().as_str() {
+                    "archived" => filter.include_archived = true,
+                    _ => return Err(invalid(&field, "Unknown flag"))
+                },
+                "sort" => filter.sort = match value.to_lowercase().as_str() {
+                    "created" => BookItemSort::Created,
+                    "label" => BookItemSort::Label,
+                    "updated" => BookItemSort::Updated,
+                    _ => return Err(invalid(&field, "Unknown sort"))
+                },
+                "created-after" => filter.created_after = Some(parse_date(&field, value.as_str())?),
+                "created-before" => filter.created_before = Some(parse_date(&field, value.as_str())?),
+                "updated-after" => filter.updated_after = Some(parse_date(&field, value.as_str())?),
+                "text" => append_text(&mut filter.text, value),
+                _ => return Err(invalid(&field, "Unknown field"))
+            }
+        }
+        Ok(filter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use uuid::Uuid;
+    use crate::access::addressbook::{BookItemSort, Filter as BookFilter};
+    use crate::proto::addressbook::NetworkKind;
+    use crate::access::query::{tokenize, Term};
+    use crate::access::transactions::{AddressRef, Filter as TxFilter, WalletRef};
+    use crate::errors::{InvalidValueError, StateError};
+    use crate::proto::transactions::{Direction, State};
+
+    #[test]
+    fn tokenize_quoted() {
+        let act = tokenize(r#"  asset:USDC text:"rent for march" salary "#).unwrap();
+        assert_eq!(act, vec![
+            Term { field: Some("asset".to_string()), value: "USDC".to_string() },
+            Term { field: Some("text".to_string()), value: "rent for march".to_string() },
+            Term { field: None, value: "salary".to_string() },
+        ]);
+
+        assert!(tokenize(r#"text:"rent"#).is_err());
+        assert!(tokenize("asset:").is_err());
+        // only the first colon separates the field
+        let act = tokenize("asset:erc20:100:0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").unwrap();
+        assert_eq!(act[0].value, "erc20:100:0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48");
+    }
+
+    #[test]
+    fn parse_tx_query() {
+        let filter: TxFilter = r#"wallet:72279ede-44c4-4951-925b-f51a7b9e929a asset:USDC after:2024-01-01 direction:send text:"rent""#
+            .parse().unwrap();
+        match filter.wallet {
+            Some(WalletRef::WholeWallet(id)) => assert_eq!(id, Uuid::parse_str("72279ede-44c4-4951-925b-f51a7b9e929a").unwrap()),
+            _ => panic!("no wallet")
+        }
+        assert_eq!(filter.asset, Some("USDC".to_string()));
+        assert_eq!(filter.after, Some(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()));
+        assert_eq!(filter.direction, Some(Direction::SEND));
+        assert_eq!(filter.text, Some("rent".to_string()));
+
+        let filter: TxFilter = "wallet:72279ede-44c4-4951-925b-f51a7b9e929a/2 blockchain:100 blockchain:101 state:confirmed is:starred is:hidden address:0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48"
+            .parse().unwrap();
+        assert!(matches!(filter.wallet, Some(WalletRef::SelectedEntry(_, 2))));
+        assert_eq!(filter.blockchains, Some(vec![100, 101]));
+        assert_eq!(filter.state, Some(State::CONFIRMED));
+        assert!(filter.starred_only);
+        assert!(filter.include_hidden);
+        assert!(matches!(filter.addresses.as_deref(), Some([AddressRef::SingleAddress(_)])));
+    }
+
+    #[test]
+    fn reject_invalid_tx_query() {
+        let act = "amount:100".parse::<TxFilter>();
+        assert_eq!(act.err(), Some(StateError::InvalidValue(InvalidValueError::NameMessage("amount".to_string(), "Unknown field".to_string()))));
+        assert!("direction:sideways".parse::<TxFilter>().is_err());
+        assert!("after:yesterday".parse::<TxFilter>().is_err());
+        assert_eq!("wallet:abc".parse::<TxFilter>().err(), Some(StateError::InvalidId));
+    }
+
+    #[test]
+    fn parse_book_query() {
+        let filter: BookFilter = r#"network:testnet blockchain:10009 alice is:archived group:"Old Friends""#.parse().unwrap();
+        assert_eq!(filter.group, Some("Old Friends".to_string()));
+        assert_eq!(filter.network, Some(NetworkKind::NETWORK_TESTNET));
+        assert!(filter.include_archived);
+        assert_eq!(filter.blockchain, Some(10009));
+        assert_eq!(filter.text, Some("alice".to_string()));
+        assert_eq!(filter.sort, BookItemSort::Created);
+
+        let filter: BookFilter = "sort:label".parse().unwrap();
+        assert_eq!(filter.sort, BookItemSort::Label);
+
+        let filter: BookFilter = "updated-after:2024-03-01 created-before:1709251200000".parse().unwrap();
+        assert_eq!(filter.updated_after, Some(Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap()));
+        assert_eq!(filter.created_before, Some(Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap()));
+        assert_eq!(filter.created_after, None);
+        assert!("created-after:yesterday".parse::<BookFilter>().is_err());
+
+        assert!("direction:send".parse::<BookFilter>().is_err());
+        assert!("sort:size".parse::<BookFilter>().is_err());
+    }
+}
@@ -0,0 +1,35 @@
+use chrono::{DateTime, Utc};
+use crate::errors::StateError;
+use crate::proto::sync::SyncState;
+
+///
+/// Bookkeeping of the remote sync for addresses and xpubs. It remembers when each of them was synced and the recent
+/// failures, so the sync engine can decide what to fetch next and back off from the failing ones.
+pub trait SyncStates {
+
+    ///
+    /// Get current sync state of the `target` (an address or xpub). `Ok(None)` if it was never synced
+    fn get(&self, target: String) -> Result<Option<SyncState>, StateError>;
+
+    ///
+    /// Remember a successful sync of the `target` at the moment `ts`. Resets the failures counter.
+    fn on_success(&self, target: String, ts: DateTime<Utc>) -> Result<SyncState, StateError>;
+
+    ///
+    /// Remember a failed sync of the `target` at the moment `ts`. The next attempt is allowed only after a delay, which
+    /// grows exponentially with the number of consecutive failures.
+    fn on_failure(&self, target: String, error: String, ts: DateTime<Utc>) -> Result<SyncState, StateError>;
+
+    ///
+    /// Check if the `target` can be synced at the moment `now`, i.e. it's not backed off after a failure. A target which
+    /// was never synced is always allowed.
+    fn is_allowed(&self, target: String, now: DateTime<Utc>) -> Result<bool, StateError>;
+
+    ///
+    /// List the known targets allowed to be synced at the moment `now`, the least recently synced first.
+    fn list_allowed(&self, now: DateTime<Utc>, limit: usize) -> Result<Vec<SyncState>, StateError>;
+
+    ///
+    /// Forget the sync state of the `target`. Does nothing if it's not known.
+    fn remove(&self, target: String) -> Result<(), StateError>;
+}
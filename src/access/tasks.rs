@@ -0,0 +1,43 @@
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+use crate::errors::StateError;
+use crate::proto::tasks::Task;
+
+///
+/// Durable queue of the background tasks (maintenance, broadcast, sync, etc.), so the work survives a restart of the
+/// application. A task is leased by a worker for a limited time, and if the worker neither completes nor fails it
+/// within that time the task becomes available to another worker.
+pub trait Tasks {
+
+    ///
+    /// Add a new task of the `kind` to the queue. It's attempted at most `max_attempts` times (at least once).
+    fn enqueue(&self, kind: String, payload: Vec<u8>, max_attempts: u32) -> Result<Task, StateError>;
+
+    ///
+    /// Lease up to `limit` pending tasks of the `kind` available at the moment `now`, in the order they were enqueued. The leased
+    /// tasks are hidden from other workers for the `timeout`. A task whose last attempt has expired without being completed
+    /// or failed is marked as failed instead.
+    fn lease(&self, kind: String, now: DateTime<Utc>, timeout: Duration, limit: usize) -> Result<Vec<Task>, StateError>;
+
+    ///
+    /// Remove the `task` leased before as successfully processed. Returns `false` if the lease has expired and the
+    /// task was leased again or removed since then.
+    fn complete(&self, task: &Task) -> Result<bool, StateError>;
+
+    ///
+    /// Record a failed attempt of the leased `task`. It's retried after a delay, growing with the number of attempts,
+    /// or marked as failed if no attempts left. Returns `false` if the lease has expired.
+    fn fail(&self, task: &Task, error: String, now: DateTime<Utc>) -> Result<bool, StateError>;
+
+    ///
+    /// Get the task by its id
+    fn get(&self, kind: String, id: Uuid) -> Result<Option<Task>, StateError>;
+
+    ///
+    /// List tasks of the `kind` which are marked as failed after all the attempts
+    fn list_failed(&self, kind: String) -> Result<Vec<Task>, StateError>;
+
+    ///
+    /// Remove the task regardless of its status. Does nothing if it's not known.
+    fn remove(&self, kind: String, id: Uuid) -> Result<(), StateError>;
+}
@@ -0,0 +1,27 @@
+use crate::errors::StateError;
+use crate::proto::tokens::TokenMetadata;
+
+///
+/// Cached details of the ERC20 tokens, per blockchain and contract address. Any place that shows an amount of a token
+/// needs at least its decimals, so the metadata is kept in a dedicated store instead of the generic cache. Each entry
+/// has a TTL, and an expired entry is treated as missing.
+pub trait Tokens {
+
+    ///
+    /// Set the metadata of the token, replacing the current one for the same blockchain and contract.
+    /// `ttl` is in milliseconds, and if it's not specified the default from the `TtlPolicy` is used.
+    /// Returns the stored metadata.
+    fn set(&self, meta: TokenMetadata, ttl: Option<u64>) -> Result<TokenMetadata, StateError>;
+
+    ///
+    /// Get the metadata of the token, or `None` if it's unknown or expired
+    fn get(&self, blockchain: u32, contract: String) -> Result<Option<TokenMetadata>, StateError>;
+
+    ///
+    /// All known tokens, optionally only those on the specified blockchain. Ordered by the blockchain and contract.
+    fn list(&self, blockchain: Option<u32>) -> Result<Vec<TokenMetadata>, StateError>;
+
+    ///
+    /// Remove the metadata of the token. Returns `false` if it wasn't known.
+    fn remove(&self, blockchain: u32, contract: String) -> Result<bool, StateError>;
+}
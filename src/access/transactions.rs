@@ -1,9 +1,16 @@
-use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::io::BufRead;
+use std::sync::mpsc::Receiver;
+use chrono::{Datelike, DateTime, Duration, TimeZone, Utc};
+use num_bigint::BigUint;
 use protobuf::ProtobufEnum;
 use uuid::Uuid;
+use crate::access::calendar::CalendarRange;
+use crate::access::integrity::DanglingReference;
 use crate::access::pagination::{PageQuery, PageResult};
+use crate::access::validation::{CheckedWrite, ValidationMode};
 use crate::errors::StateError;
-use crate::proto::transactions::{Transaction, TransactionMeta, State, Status};
+use crate::proto::transactions::{Change, Change_ChangeType, Direction, FirstSeen, MetaRevision, Transaction, TransactionMeta, State, Status};
 
 #[derive(Debug, Clone)]
 /// Reference to a wallet or its part
@@ -33,6 +40,18 @@ pub enum AddressRef {
     Xpub(String, u32, u32),
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// Time of a transaction checked against the `after` and `before` of a `Filter`
+pub enum TimeMatch {
+    /// Either the time the transaction is known or the time it's confirmed, which is the default. The history is
+    /// ordered by neither of them, so the whole history is scanned.
+    #[default]
+    KnownOrConfirmed,
+    /// The time the transaction is confirmed, or the time it's known if it's not confirmed. The history is ordered by
+    /// that time, so only the part of the history within the window is scanned.
+    Ordered,
+}
+
 #[derive(Debug, Clone)]
 /// Transactions Query Filter to select which transactions are accepted.
 /// It's _AND_ type of filter between groups, i.e. all of the non-empty criteria are required, but
@@ -49,10 +68,33 @@ pub struct Filter {
     pub after: Option<DateTime<Utc>>,
     /// require a transaction known or confirmed before the specified moment
     pub before: Option<DateTime<Utc>>,
+    /// which time of a transaction is checked against `after` and `before`
+    pub time_match: TimeMatch,
     /// requre the following state (PREPARED/SUBMITTED/etc, see protobuf definition)
     pub state: Option<State>,
     /// requre the following satus (UNKNOWN/OK/FAILED, see protobuf definition)
     pub status: Option<Status>,
+    /// skip transactions replaced by another transaction (i.e., with `replaced_by`), so only the final one is returned
+    pub collapse_replaced: bool,
+    /// require the tag assigned by the user with the transaction meta. Tags are case insensitive.
+    pub tag: Option<String>,
+    /// require a change without a wallet, i.e. not attributed to any of the wallets
+    pub unattributed_only: bool,
+    /// require a transaction starred by the user
+    pub starred_only: bool,
+    /// require a transaction marked by the user for a review
+    pub needs_review_only: bool,
+    /// include the transactions hidden by the user, which are skipped by default
+    pub include_hidden: bool,
+    /// require a confirmed transaction with at least the specified number of confirmations, counted from the head height
+    /// set for its blockchain. A transaction on a blockchain without a known head height is not accepted.
+    pub min_confirmations: Option<u64>,
+    /// require a change of the specified asset, ex. `ETHER`. It's case insensitive.
+    pub asset: Option<String>,
+    /// require a change in the specified direction. If the `asset` is set too, both are required for the same change.
+    pub direction: Option<Direction>,
+    /// require the text in the label or the tags assigned by the user with the transaction meta. It's case insensitive.
+    pub text: Option<String>,
 }
 
 impl Default for Filter {
@@ -63,12 +105,306 @@ impl Default for Filter {
             blockchains: None,
             after: None,
             before: None,
+            time_match: TimeMatch::default(),
             state: None,
             status: None,
+            collapse_replaced: false,
+            tag: None,
+            unattributed_only: false,
+            starred_only: false,
+            needs_review_only: false,
+            include_hidden: false,
+            min_confirmations: None,
+            asset: None,
+            direction: None,
+            text: None,
+        }
+    }
+}
+
+///
+/// A field of a Transaction, used to specify which fields should be loaded by a query
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TransactionField {
+    Blockchain,
+    TxId,
+    SinceTimestamp,
+    SyncTimestamp,
+    ConfirmTimestamp,
+    State,
+    Block,
+    BlockPos,
+    Status,
+    Changes,
+    Version,
+    NonceRef,
+    ReplacedBy,
+    InternalTransfer,
+    Inputs,
+    Outputs,
+    VSize,
+    TokenTransfers,
+}
+
+impl TransactionField {
+    /// Field number in the protobuf definition
+    pub(crate) fn get_number(&self) -> u32 {
+        match self {
+            TransactionField::Blockchain => 1,
+            TransactionField::TxId => 2,
+            TransactionField::SinceTimestamp => 3,
+            TransactionField::SyncTimestamp => 4,
+            TransactionField::ConfirmTimestamp => 5,
+            TransactionField::State => 6,
+            TransactionField::Block => 7,
+            TransactionField::Status => 8,
+            TransactionField::Changes => 9,
+            TransactionField::Version => 10,
+            TransactionField::BlockPos => 11,
+            TransactionField::NonceRef => 12,
+            TransactionField::ReplacedBy => 13,
+            TransactionField::InternalTransfer => 14,
+            TransactionField::Inputs => 15,
+            TransactionField::Outputs => 16,
+            TransactionField::VSize => 17,
+            TransactionField::TokenTransfers => 18,
+        }
+    }
+
+    /// Field by its number in the protobuf definition
+    pub(crate) fn from_number(number: u32) -> Option<TransactionField> {
+        match number {
+            1 => Some(TransactionField::Blockchain),
+            2 => Some(TransactionField::TxId),
+            3 => Some(TransactionField::SinceTimestamp),
+            4 => Some(TransactionField::SyncTimestamp),
+            5 => Some(TransactionField::ConfirmTimestamp),
+            6 => Some(TransactionField::State),
+            7 => Some(TransactionField::Block),
+            8 => Some(TransactionField::Status),
+            9 => Some(TransactionField::Changes),
+            10 => Some(TransactionField::Version),
+            11 => Some(TransactionField::BlockPos),
+            12 => Some(TransactionField::NonceRef),
+            13 => Some(TransactionField::ReplacedBy),
+            14 => Some(TransactionField::InternalTransfer),
+            15 => Some(TransactionField::Inputs),
+            16 => Some(TransactionField::Outputs),
+            17 => Some(TransactionField::VSize),
+            18 => Some(TransactionField::TokenTransfers),
+            _ => None
+        }
+    }
+}
+
+///
+/// A flag set by the user on a transaction, stored with the transaction meta
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TxFlag {
+    Starred,
+    /// Hidden from the history, but still stored
+    Hidden,
+    NeedsReview,
+}
+
+impl TxFlag {
+    /// Code of the flag used in the index
+    pub(crate) fn get_code(&self) -> &'static str {
+        match self {
+            TxFlag::Starred => "starred",
+            TxFlag::Hidden => "hidden",
+            TxFlag::NeedsReview => "review",
+        }
+    }
+
+    pub fn is_set(&self, meta: &TransactionMeta) -> bool {
+        match self {
+            TxFlag::Starred => meta.starred,
+            TxFlag::Hidden => meta.hidden,
+            TxFlag::NeedsReview => meta.needs_review,
+        }
+    }
+
+    pub(crate) fn set(&self, meta: &mut TransactionMeta, value: bool) {
+        match self {
+            TxFlag::Starred => meta.starred = value,
+            TxFlag::Hidden => meta.hidden = value,
+            TxFlag::NeedsReview => meta.needs_review = value,
+        }
+    }
+}
+
+///
+/// Result of submitting a transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SubmitOutcome {
+    /// The transaction wasn't known before
+    Created,
+    /// The transaction was merged with the stored one, which changed the listed fields
+    Merged { changed_fields: Vec<TransactionField> },
+    /// The stored transaction already has the same data, so it's not updated
+    Unchanged,
+}
+
+///
+/// How a submitted transaction is merged with the stored one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergePolicy {
+    /// Merge by the usual rules, which keep the known details the update may not have, such as fees and wallets
+    Default,
+    /// Keep the stored values, and take from the update only what is not known yet
+    PreferLocal,
+    /// Overwrite the stored transaction with the update, ex. for a full re-sync from a trusted indexer
+    PreferRemote,
+}
+
+///
+/// Which of the old transactions are removed by `prune`. Only the transactions in a final state, i.e. confirmed, replaced
+/// or dropped, are removed, and a transaction is removed if it's out of any of the limits.
+#[derive(Debug, Clone)]
+pub struct RetentionPolicy {
+    /// Keep only the specified number of the latest transactions per wallet. A transaction of multiple wallets is kept
+    /// if any of them keeps it, and a transaction without a wallet is not limited by it.
+    pub max_per_wallet: Option<usize>,
+    /// Keep only the transactions confirmed (or, if not confirmed, known) within the specified period
+    pub max_age: Option<Duration>,
+    /// Keep the transactions curated by the user, i.e. with a label, tags, or a star or review flag in the meta
+    pub keep_labeled: bool,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        RetentionPolicy {
+            max_per_wallet: None,
+            max_age: None,
+            keep_labeled: true,
+        }
+    }
+}
+
+impl TransactionMeta {
+
+    ///
+    /// Checks if the user assigned anything that makes the transaction worth keeping, which is the label, tags, or
+    /// the star and review flags
+    pub fn is_curated(&self) -> bool {
+        !self.label.is_empty() || !self.tags.is_empty() || self.starred || self.needs_review
+    }
+}
+
+///
+/// Size of a period used to aggregate transaction statistics. Periods are aligned in UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Granularity {
+    Day,
+    /// A week starting on Monday
+    Week,
+    Month,
+}
+
+impl Granularity {
+    ///
+    /// Beginning of the period which includes the specified moment
+    pub fn get_period_start(&self, ts: DateTime<Utc>) -> DateTime<Utc> {
+        let date = ts.date_naive();
+        let start = match self {
+            Granularity::Day => date,
+            Granularity::Week => date - Duration::days(date.weekday().num_days_from_monday() as i64),
+            Granularity::Month => date.with_day(1).unwrap(),
+        };
+        Utc.from_utc_datetime(&start.and_hms_opt(0, 0, 0).unwrap())
+    }
+}
+
+///
+/// Total amounts transferred in a single asset
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssetTotal {
+    pub asset: String,
+    /// Total sent, including fees
+    pub sent: BigUint,
+    pub received: BigUint,
+}
+
+///
+/// Aggregated statistics of transactions over a period
+#[derive(Debug, Clone, PartialEq)]
+pub struct PeriodStats {
+    /// Beginning of the period
+    pub start: DateTime<Utc>,
+    /// Number of transactions in the period
+    pub count: usize,
+    /// Totals per asset, ordered by the asset name
+    pub assets: Vec<AssetTotal>,
+}
+
+///
+/// Number of transactions matching a filter, grouped by a few properties. Each group ignores the filter's own
+/// criterion for that property, i.e. `by_blockchain` counts all blockchains even if the filter selects one of them, so
+/// it can be used to show a badge for each of them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Facets {
+    /// Count per blockchain id
+    pub by_blockchain: BTreeMap<u32, usize>,
+    /// Count per state
+    pub by_state: HashMap<State, usize>,
+}
+
+///
+/// A page of transactions together with the facet counts for the whole filter
+#[derive(Debug, Clone)]
+pub struct FacetedResult {
+    pub page: PageResult<Transaction>,
+    pub facets: Facets,
+}
+
+///
+/// Transactions modified by a write to the store, as it's sent to the subscribers
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TransactionsChange {
+    /// Wallets of the modified transactions
+    pub wallets: BTreeSet<Uuid>,
+    /// Modified transactions, as pairs of `(blockchain, tx_id)`
+    pub transactions: BTreeSet<(u32, String)>,
+    /// Number of the write in the global sequence of the storage, same as `SledStorage::get_sequence` right after it.
+    /// If the change was written in parts, ex. by a large `prune`, it's the number of the last part.
+    pub seq: u64,
+}
+
+impl TransactionsChange {
+
+    pub fn is_empty(&self) -> bool {
+        self.transactions.is_empty()
+    }
+
+    pub(crate) fn add(&mut self, tx: &Transaction) {
+        self.transactions.insert((tx.blockchain.value() as u32, tx.tx_id.clone()));
+        for change in tx.changes.iter() {
+            if let Ok(wallet_id) = Uuid::parse_str(change.wallet_id.as_str()) {
+                self.wallets.insert(wallet_id);
+            }
         }
     }
 }
 
+///
+/// A transaction together with its meta, if the meta is set
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransactionWithMeta {
+    pub tx: Transaction,
+    pub meta: Option<TransactionMeta>,
+}
+
+///
+/// Result of an import of transactions
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportReport {
+    /// Number of stored transactions
+    pub imported: usize,
+    /// Lines which were not imported, as pairs of the line number (starting from 1) and the error
+    pub errors: Vec<(usize, String)>,
+}
+
 ///
 /// A reference to an external _cursor_ used to fetch updates for an address
 #[derive(Debug, Clone)]
@@ -79,9 +415,78 @@ pub struct RemoteCursor {
     pub since: DateTime<Utc>,
 }
 
+///
+/// How up to date is the remote cursor of an address
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Staleness {
+    /// The cursor was updated recently
+    Fresh,
+    /// The cursor is older than the threshold, so the address should be fetched again
+    Stale,
+    /// There is no cursor for the address
+    Missing,
+}
+
+impl Staleness {
+
+    ///
+    /// Classify the cursor, where `stale_after` is the maximum age of a fresh cursor
+    pub fn of(cursor: &Option<RemoteCursor>, now: DateTime<Utc>, stale_after: Duration) -> Staleness {
+        match cursor {
+            Some(cursor) if now - cursor.since > stale_after => Staleness::Stale,
+            Some(_) => Staleness::Fresh,
+            None => Staleness::Missing,
+        }
+    }
+}
+
 impl Filter {
+
+    ///
+    /// Limit the filter to transactions known or confirmed within the calendar range, where the days are taken in the
+    /// timezone of `now`. Returns `false` and keeps the filter as is if the range is invalid.
+    pub fn set_range<Tz: TimeZone>(&mut self, range: CalendarRange, now: DateTime<Tz>) -> bool {
+        match range.get_bounds(now) {
+            Some((after, before)) => {
+                self.after = Some(after);
+                self.before = Some(before);
+                true
+            }
+            None => false
+        }
+    }
+
+    ///
+    /// Fields of a transaction required to check the filter
+    pub(crate) fn get_required_fields(&self) -> Vec<TransactionField> {
+        let mut fields = vec![TransactionField::Blockchain, TransactionField::TxId];
+        if self.state.is_some() {
+            fields.push(TransactionField::State);
+        }
+        if self.status.is_some() {
+            fields.push(TransactionField::Status);
+        }
+        if self.after.is_some() || self.before.is_some() {
+            fields.push(TransactionField::SinceTimestamp);
+            fields.push(TransactionField::ConfirmTimestamp);
+        }
+        if self.wallet.is_some() || self.addresses.is_some() || self.unattributed_only || self.asset.is_some() || self.direction.is_some() {
+            fields.push(TransactionField::Changes);
+        }
+        if self.collapse_replaced {
+            fields.push(TransactionField::ReplacedBy);
+        }
+        if self.min_confirmations.is_some() {
+            fields.push(TransactionField::State);
+            fields.push(TransactionField::Block);
+        }
+        fields
+    }
+
     /// Checks the filter against the transaction.
-    /// Returns `true` if the transaction fits the criteria
+    /// Returns `true` if the transaction fits the criteria.
+    /// Note that the `tag`, the `text` and the flags are not checked here, because they are part of the transaction meta
+    /// and matched by the storage. The `min_confirmations` depends on the head height, so it's checked with `check_confirmations`.
     pub fn check_filter(&self, t: &Transaction) -> bool {
         let tbid: u32 = t.blockchain.value() as u32;
         let blockchains_ok = if let Some(blockchains) = &self.blockchains {
@@ -106,12 +511,38 @@ impl Filter {
             return false
         }
 
+        if self.collapse_replaced && !t.replaced_by.is_empty() {
+            return false
+        }
+
+        if self.unattributed_only && !t.get_changes().iter().any(|c| c.wallet_id.is_empty()) {
+            return false
+        }
+
+        if self.asset.is_some() || self.direction.is_some() {
+            let change_ok = t.get_changes().iter().any(|c|
+                self.asset.as_ref().is_none_or(|asset| c.asset.eq_ignore_ascii_case(asset))
+                    && self.direction.is_none_or(|direction| c.direction == direction)
+            );
+            if !change_ok {
+                return false
+            }
+        }
+
+        // same timestamp as used to order the history
+        let ordered_ts = if t.confirm_timestamp > 0 { t.confirm_timestamp } else { t.since_timestamp };
         let after_ok = match &self.after.map(|ts| ts.timestamp_millis() as u64) {
-            Some(ts) => (t.since_timestamp != 0 && t.since_timestamp >= *ts) || (t.confirm_timestamp != 0 && t.confirm_timestamp >= *ts),
+            Some(ts) => match self.time_match {
+                TimeMatch::KnownOrConfirmed => (t.since_timestamp != 0 && t.since_timestamp >= *ts) || (t.confirm_timestamp != 0 && t.confirm_timestamp >= *ts),
+                TimeMatch::Ordered => ordered_ts != 0 && ordered_ts >= *ts,
+            },
             None => true
         };
         let before_ok = match &self.before.map(|ts| ts.timestamp_millis() as u64) {
-            Some(ts) => (t.since_timestamp != 0 && t.since_timestamp <= *ts) || (t.confirm_timestamp != 0 && t.confirm_timestamp <= *ts),
+            Some(ts) => match self.time_match {
+                TimeMatch::KnownOrConfirmed => (t.since_timestamp != 0 && t.since_timestamp <= *ts) || (t.confirm_timestamp != 0 && t.confirm_timestamp <= *ts),
+                TimeMatch::Ordered => ordered_ts != 0 && ordered_ts <= *ts,
+            },
             None => true
         };
         let time_ok = after_ok && before_ok;
@@ -141,6 +572,102 @@ impl Filter {
 
         wallet_ok && address_ok
     }
+
+    ///
+    /// Checks the number of confirmations of the transaction, with `heads` as the current head height per blockchain
+    pub(crate) fn check_confirmations(&self, t: &Transaction, heads: &HashMap<u32, u64>) -> bool {
+        let min = match self.min_confirmations {
+            Some(min) => min,
+            None => return true
+        };
+        match heads.get(&(t.blockchain.value() as u32)) {
+            Some(head) => t.get_confirmations(*head) >= min,
+            None => false
+        }
+    }
+
+    ///
+    /// Checks if the change of an accepted transaction belongs to the wallet or addresses required by the filter
+    pub(crate) fn check_change(&self, c: &Change) -> bool {
+        let wallet_ok = match &self.wallet {
+            Some(WalletRef::WholeWallet(uuid)) => c.wallet_id == uuid.to_string(),
+            Some(WalletRef::SelectedEntry(uuid, index)) => c.wallet_id == uuid.to_string() && c.entry_id == *index,
+            None => true
+        };
+        let address_ok = match &self.addresses {
+            Some(addresses) => addresses.iter().any(|a|
+                match a {
+                    AddressRef::SingleAddress(addr) => c.address.eq(addr),
+                    AddressRef::Xpub(_, _, _) => false
+                }
+            ),
+            None => true
+        };
+        let unattributed_ok = !self.unattributed_only || c.wallet_id.is_empty();
+        wallet_ok && address_ok && unattributed_ok
+    }
+}
+
+impl Transaction {
+
+    ///
+    /// Check if the transaction is a transfer between own wallets, i.e. it has a sending change of one wallet and
+    /// a receiving change of another
+    pub fn detect_internal_transfer(&self) -> bool {
+        let wallets = |direction: Direction| -> HashSet<&str> {
+            self.changes.iter()
+                .filter(|c| c.change_type == Change_ChangeType::TRANSFER && c.direction == direction && !c.wallet_id.is_empty())
+                .map(|c| c.wallet_id.as_str())
+                .collect()
+        };
+        let senders = wallets(Direction::SEND);
+        wallets(Direction::RECEIVE).iter().any(|w| senders.iter().any(|s| s != w))
+    }
+
+    ///
+    /// Number of confirmations of the transaction when the blockchain is at `head_height`, i.e. 1 for a transaction in
+    /// the head block. Zero if it's not confirmed. If the head height is behind the block of the transaction, which means
+    /// it's not updated yet, the transaction is considered as having 1 confirmation.
+    pub fn get_confirmations(&self, head_height: u64) -> u64 {
+        let height = self.get_block().height;
+        if self.state != State::CONFIRMED || height == 0 {
+            return 0
+        }
+        if head_height < height {
+            return 1
+        }
+        head_height - height + 1
+    }
+
+    ///
+    /// Fee of a Bitcoin transaction in satoshi, computed from the stored inputs and outputs. `None` if they are not
+    /// stored, or an amount of any of the inputs is unknown.
+    pub fn get_bitcoin_fee(&self) -> Option<u64> {
+        if self.inputs.is_empty() || self.outputs.is_empty() || self.inputs.iter().any(|i| i.amount == 0) {
+            return None
+        }
+        let inputs: u64 = self.inputs.iter().map(|i| i.amount).sum();
+        let outputs: u64 = self.outputs.iter().map(|o| o.amount).sum();
+        inputs.checked_sub(outputs)
+    }
+
+    ///
+    /// Outputs spent by a Bitcoin transaction, as pairs of the lowercase txid and the vout. Empty if the inputs are not
+    /// stored.
+    pub fn get_spent_outpoints(&self) -> HashSet<(String, u32)> {
+        self.inputs.iter()
+            .map(|i| (i.txid.to_lowercase(), i.vout))
+            .collect()
+    }
+
+    ///
+    /// Effective fee rate of a Bitcoin transaction in satoshi per vbyte, if the fee and the size are known
+    pub fn get_fee_rate(&self) -> Option<f64> {
+        if self.vsize == 0 {
+            return None
+        }
+        self.get_bitcoin_fee().map(|fee| fee as f64 / self.vsize as f64)
+    }
 }
 
 pub trait Transactions {
@@ -149,48 +676,299 @@ pub trait Transactions {
     fn query(&self, filter: Filter, page: PageQuery) -> Result<PageResult<Transaction>, StateError>;
 
     ///
-    /// Get Tx, if exist
+    /// Find transactions given filter, but load only the specified `fields` of each transaction, which is much cheaper
+    /// when the full details are not needed (ex. only `TxId` and `State`). All other fields have default values.
+    /// Note that the fields required to check the filter are loaded too.
+    fn query_projected(&self, filter: Filter, page: PageQuery, fields: Vec<TransactionField>) -> Result<PageResult<Transaction>, StateError>;
+
+    ///
+    /// Find transactions given filter, and also count all transactions matching the filter per blockchain and per state.
+    /// It's much cheaper than making a `get_count` for each of the blockchains or states, as it scans the data only once.
+    fn query_faceted(&self, filter: Filter, page: PageQuery) -> Result<FacetedResult, StateError>;
+
+    ///
+    /// Find transactions given filter, each together with its meta (i.e., label, tags, etc.), so there is no need to
+    /// read the meta of each transaction on the page with a separate call.
+    fn query_enriched(&self, filter: Filter, page: PageQuery) -> Result<PageResult<TransactionWithMeta>, StateError>;
+
+    ///
+    /// Latest transactions of each wallet, up to `limit` per wallet, ex. for an activity feed over all the wallets.
+    /// The transactions are in the same order as for a query by the wallet, and the hidden ones are excluded.
+    /// It reads only the first entries of the index of each wallet, so it's much cheaper than a query per wallet.
+    fn get_recent_activity(&self, limit: usize) -> Result<BTreeMap<Uuid, Vec<Transaction>>, StateError>;
+
+    ///
+    /// Subscribe to the changes made by `submit`, `forget`, `set_state`, `set_tx_meta` and other calls modifying the
+    /// transactions, through any handle of the same storage. The subscription ends when the receiver is dropped.
+    fn subscribe(&self) -> Result<Receiver<TransactionsChange>, StateError>;
+
+    ///
+    /// Get Tx, if exist. The tx id is case-insensitive, and the `0x` prefix is optional.
+    /// Returns `None` when the storage is not permitted to read transactions.
     fn get_tx(&self, blockchain: u32, txid: &str) -> Option<Transaction>;
 
+    ///
+    /// Get multiple Tx by their blockchain and tx id. The result is in the same order as the provided `refs`, with `None`
+    /// for each tx that doesn't exist
+    fn get_txs(&self, refs: Vec<(u32, String)>) -> Vec<Option<Transaction>>;
+
     ///
     /// Get Transaction user assigned info, such as label. `Ok(None)` if user has no assigned meta for that tx
     fn get_tx_meta(&self, blockchain: u32, txid: &str) -> Result<Option<TransactionMeta>, StateError>;
 
+    ///
+    /// Get Tx together with its meta in a single call, ex. for a screen with the details of the transaction.
+    /// The raw payload may be large, so it's kept in the meta only if `include_raw` is set. `Ok(None)` if the tx doesn't exist.
+    fn get_tx_with_meta(&self, blockchain: u32, txid: &str, include_raw: bool) -> Result<Option<TransactionWithMeta>, StateError>;
+
+    ///
+    /// History of the changes of the transaction meta, starting from the first one. Each revision is linked to the
+    /// previous one by its hash, see `storage::meta_proof`. The meta of a revision is encoded, as it's hashed.
+    fn get_meta_history(&self, blockchain: u32, txid: &str) -> Result<Vec<MetaRevision>, StateError>;
+
+    ///
+    /// Export the history of the transaction meta as a JSON document, which can be checked by `meta_proof::verify`
+    fn export_meta_proof(&self, blockchain: u32, txid: &str) -> Result<String, StateError>;
+
+    ///
+    /// Get the first known activity of the address, i.e. the earliest of the submitted transactions with a transfer on
+    /// it. `Ok(None)` if the wallet has never interacted with the address, ex. to warn the user about sending to a new
+    /// address. If that transaction is removed with `forget` the record is removed as well, because the previous activity
+    /// is not tracked.
+    fn get_first_seen(&self, address: String) -> Result<Option<FirstSeen>, StateError>;
+
     ///
     /// Set user assigned meta to the transaction. If a value is already exists it gets updated with the new values
     /// only if the new meta is fresh (i.e. a later timestamp)
     fn set_tx_meta(&self, value: TransactionMeta) -> Result<TransactionMeta, StateError>;
 
+    ///
+    /// Set multiple user assigned metas at once, ex. when importing labels from another device. All values are written
+    /// in a single batch and each of them follows the same rule as `set_tx_meta`, i.e. replaces the existing value only if
+    /// it has a later timestamp. Returns the actual stored values in the same order as provided.
+    fn set_tx_meta_bulk(&self, values: Vec<TransactionMeta>) -> Result<Vec<TransactionMeta>, StateError>;
+
+    ///
+    /// Labels previously assigned to transactions with the specified counterparty address, with the number of
+    /// transactions for each label, starting from the most used. Ethereum addresses are compared case-insensitively.
+    fn suggest_labels(&self, address: String) -> Result<Vec<(String, usize)>, StateError>;
+
+    ///
+    /// Add a tag to the user assigned meta of the transaction, creating the meta if it doesn't exist yet.
+    /// The tag is stored in lowercase, and must not be empty or contain `/`. Returns the updated meta.
+    fn add_tag(&self, blockchain: u32, tx_id: String, tag: String) -> Result<TransactionMeta, StateError>;
+
+    ///
+    /// Remove a tag from the user assigned meta of the transaction. Returns the updated meta, or `None` if there is no meta.
+    fn remove_tag(&self, blockchain: u32, tx_id: String, tag: String) -> Result<Option<TransactionMeta>, StateError>;
+
+    ///
+    /// Set or clear a flag in the user assigned meta of the transaction, creating the meta if it doesn't exist yet.
+    /// Returns the updated meta.
+    fn set_flag(&self, blockchain: u32, tx_id: String, flag: TxFlag, value: bool) -> Result<TransactionMeta, StateError>;
+
+    ///
+    /// Remove the raw payload from the meta of transactions confirmed more than `max_age` ago, keeping only its hash
+    /// in `raw_hash`, so it can be verified if the payload is fetched again. Returns the number of compacted metas.
+    fn compact_raw(&self, max_age: std::time::Duration) -> Result<usize, StateError>;
+
+    ///
+    /// Put back the raw payload removed by `compact_raw`, ex. when it was refetched from the blockchain. The payload
+    /// must match the kept hash. Returns the updated meta, or `None` if there is no meta.
+    fn restore_raw(&self, blockchain: u32, tx_id: String, raw: Vec<u8>) -> Result<Option<TransactionMeta>, StateError>;
+
     ///
     /// Update a new transactions. Update may be a new transactions or a new state to an existing
-    /// Ex. initially a tx added with basic details only, just for future reference, and then updated when it changed
-    fn submit(&self, transactions: Vec<Transaction>) -> Result<(), StateError>;
+    /// Ex. initially a tx added with basic details only, just for future reference, and then updated when it changed.
+    /// For Bitcoin, a pending transaction spending any of the same inputs but with a different id (i.e., a malleated or
+    /// rebroadcasted copy, or a replacement by fee) is marked as `REPLACED` by the submitted one. It needs the `inputs`
+    /// of both transactions.
+    /// The tx id is stored lowercase, with `0x` prefix for Ethereum and without it for Bitcoin.
+    /// Returns the outcome for each of the submitted transactions, in the same order.
+    fn submit(&self, transactions: Vec<Transaction>) -> Result<Vec<SubmitOutcome>, StateError>;
 
     ///
-    /// Remove transaction from index
+    /// Same as `submit`, but merges with the stored transactions using the specified policy
+    fn submit_with(&self, transactions: Vec<Transaction>, policy: MergePolicy) -> Result<Vec<SubmitOutcome>, StateError>;
+
+    ///
+    /// Same as `submit`, but validates the transactions according to the `mode`. In a non-strict mode an invalid
+    /// transaction is skipped with a warning instead of rejecting all of them, ex. when restoring an old backup.
+    fn submit_checked(&self, transactions: Vec<Transaction>, mode: ValidationMode) -> Result<CheckedWrite<SubmitOutcome>, StateError>;
+
+    ///
+    /// Update only the lifecycle state of an existing transaction (ex. SUBMITTED -> DROPPED), without submitting the full
+    /// details. The `timestamp` is the moment when the state was observed, and it's stored as the tx `sync_timestamp`; if the
+    /// stored transaction was synced later than that, the update is ignored.
+    /// Returns the actual stored transaction, or `Ok(None)` if there is no such transaction.
+    fn set_state(&self, blockchain: u32, tx_id: String, state: State, timestamp: u64) -> Result<Option<Transaction>, StateError>;
+
+    ///
+    /// Get all known transactions with the same sender and nonce as the specified transaction (see `nonce_ref`), i.e.
+    /// the transactions replacing each other with a speed up or cancel. Includes the specified transaction itself, which is
+    /// the only one returned if it has no `nonce_ref`. Empty if the transaction is unknown.
+    fn get_replacements(&self, blockchain: u32, tx_id: String) -> Result<Vec<Transaction>, StateError>;
+
+    ///
+    /// Mark the specified transaction as the winner among the transactions with the same sender and nonce. All other
+    /// transactions get the `REPLACED` state and `replaced_by` pointing to the winner. If the winner was previously
+    /// replaced itself, its state is restored to `CONFIRMED` if it has a block, or to `SUBMITTED` otherwise.
+    /// Returns ids of the replaced transactions.
+    fn set_replaced(&self, blockchain: u32, winner_tx_id: String) -> Result<Vec<String>, StateError>;
+
+    ///
+    /// Remove transaction from index. The records referencing the transaction are deleted or detached according to
+    /// the policies set in `SledStorage::references`.
     fn forget(&self, blockchain: u32, tx_id: String) -> Result<(), StateError>;
 
+    ///
+    /// Find the records referencing transactions which don't exist in the storage. Note that a meta may be set before
+    /// the transaction is submitted, so it's not necessarily an error.
+    fn find_dangling(&self) -> Result<Vec<DanglingReference>, StateError>;
+
+    ///
+    /// Import transactions from JSON Lines, i.e. one transaction per line in the Protobuf JSON format, as exported from
+    /// another machine or from an explorer. Valid lines are submitted in batches, so an invalid line doesn't stop the
+    /// import and is reported with its error instead. A line with an unknown field is invalid too, as the field would
+    /// be lost otherwise. The whole import counts as a single write for the rate limit, so it's either throttled before
+    /// anything is imported or not at all. Fails only if the data cannot be read or stored.
+    fn import_jsonl<R: BufRead>(&self, reader: R) -> Result<ImportReport, StateError>;
+
     ///
     /// Get total count of transactions by given filter
     fn get_count(&self, filter: Filter) -> Result<usize, StateError>;
 
+    ///
+    /// Aggregate transactions accepted by the filter into statistics per period of the specified size, ex. to draw an
+    /// activity chart. If the filter specifies a wallet or addresses only the changes related to them are counted in the totals.
+    /// Returns only the periods that have transactions, ordered from the earliest.
+    fn stats(&self, filter: Filter, granularity: Granularity) -> Result<Vec<PeriodStats>, StateError>;
+
     ///
     /// Get current `cursor` for an `address`.
     fn get_cursor<S: AsRef<str>>(&self, address: S) -> Result<Option<RemoteCursor>, StateError>;
 
     ///
-    /// Update `cursor` value for an `address`
+    /// Get current cursors for multiple addresses at once, as `(address, cursor, staleness)` in the same order as the
+    /// addresses. A cursor is stale when it's older than `cursor_stale_after` of the `TtlPolicy`.
+    fn get_cursors<S: AsRef<str>>(&self, addresses: Vec<S>) -> Result<Vec<(String, Option<RemoteCursor>, Staleness)>, StateError>;
+
+    ///
+    /// Update `cursor` value for an `address`. Doesn't write anything if the value is the same as already stored
     fn set_cursor<S: AsRef<str> + ToString>(&self, address: S, cursor: S) -> Result<(), StateError>;
+
+    ///
+    /// Update `cursor` values for multiple addresses at once, as pairs of `(address, cursor)`.
+    /// Same as `set_cursor` it keeps the stored value (and its timestamp) as is if it's not changed.
+    fn set_cursors<S: AsRef<str> + ToString>(&self, cursors: Vec<(S, S)>) -> Result<(), StateError>;
+
+    ///
+    /// Set the current height of the blockchain, which is used to calculate the confirmations of the stored transactions.
+    /// It may go down as well, ex. after a reorg.
+    fn set_head_height(&self, blockchain: u32, height: u64) -> Result<(), StateError>;
+
+    ///
+    /// Get the current height of the blockchain, if it was set
+    fn get_head_height(&self, blockchain: u32) -> Result<Option<u64>, StateError>;
+
+    ///
+    /// List all stored cursors as pairs of `(address, cursor)`, ordered by address. Useful to resume fetching the
+    /// updates for each of the tracked addresses after a restart.
+    fn list_cursors(&self) -> Result<Vec<(String, RemoteCursor)>, StateError>;
+
+    ///
+    /// Remove the stored cursors for the addresses, ex. when a wallet must be fully re-synced. Returns the number of
+    /// actually removed cursors.
+    fn clear_cursors<S: AsRef<str>>(&self, addresses: Vec<S>) -> Result<usize, StateError>;
+
+    ///
+    /// Move the confirmed transactions out of the recent section of the wallet history when they get older than the
+    /// `tx_recent_window` of the `TtlPolicy`. It's a maintenance task supposed to be called periodically.
+    /// Returns the number of moved transactions.
+    fn refresh_recent(&self) -> Result<usize, StateError>;
+
+    ///
+    /// Remove the old transactions by the retention policy, together with their meta and indexes, to keep the size of a
+    /// long-lived storage under control. Other records referencing a removed transaction are handled by the cascade
+    /// policies, same as with `forget`. It's a maintenance task supposed to be called periodically.
+    /// Returns the number of removed transactions.
+    fn prune(&self, policy: RetentionPolicy) -> Result<usize, StateError>;
+
+    ///
+    /// Drop all the indexes of the transactions and build them again from the stored transactions. It's a recovery
+    /// tool for the indexes that got out of sync with the data, ex. after a crash in the middle of a write.
+    /// Returns the number of indexed transactions.
+    fn rebuild_indexes(&self) -> Result<usize, StateError>;
+
+    ///
+    /// Attribute the stored transactions on the `blockchain` to the wallet entry, i.e. set the wallet for each change on
+    /// any of the `addresses` that doesn't have a wallet yet, and update the indexes. It's supposed to repair the history
+    /// when the address registry learns new addresses of the entry, and the addresses are matched the same way as by the
+    /// registry. Returns the number of updated transactions.
+    fn reattribute(&self, wallet_id: Uuid, entry_id: u32, blockchain: u32, addresses: Vec<String>) -> Result<usize, StateError>;
 }
 
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
     use std::str::FromStr;
     use protobuf::ProtobufEnum;
     use uuid::Uuid;
-    use crate::access::transactions::{AddressRef, Filter, WalletRef};
-    use crate::proto::transactions::{BlockchainId, Transaction as proto_Transaction, Change as proto_Change, State, Status};
+    use chrono::{Duration, FixedOffset, TimeZone, Utc};
+    use crate::access::calendar::CalendarRange;
+    use crate::access::transactions::{AddressRef, Filter, Granularity, RemoteCursor, Staleness, TimeMatch, WalletRef};
+    use crate::proto::transactions::{BitcoinInput, BitcoinOutput, BlockRef, BlockchainId, Transaction as proto_Transaction, Change as proto_Change, Change_ChangeType, Direction, State, Status};
+
+    #[test]
+    fn period_start_by_granularity() {
+        // Wednesday
+        let ts = Utc.with_ymd_and_hms(2022, 3, 16, 13, 45, 10).unwrap();
+        assert_eq!(Granularity::Day.get_period_start(ts), Utc.with_ymd_and_hms(2022, 3, 16, 0, 0, 0).unwrap());
+        assert_eq!(Granularity::Week.get_period_start(ts), Utc.with_ymd_and_hms(2022, 3, 14, 0, 0, 0).unwrap());
+        assert_eq!(Granularity::Month.get_period_start(ts), Utc.with_ymd_and_hms(2022, 3, 1, 0, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn filter_by_calendar_range() {
+        let mut tx = proto_Transaction::new();
+        tx.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        // 2022-03-15 03:10 UTC
+        tx.since_timestamp = 1_647_313_850_992;
+
+        let mut filter = Filter::default();
+        let now = Utc.with_ymd_and_hms(2022, 3, 15, 12, 0, 0).unwrap();
+        assert!(filter.set_range(CalendarRange::Today, now));
+        assert!(filter.check_filter(&tx));
+
+        // it's still 14th in UTC-5
+        let tz = FixedOffset::west_opt(5 * 3600).unwrap();
+        assert!(filter.set_range(CalendarRange::Today, now.with_timezone(&tz)));
+        assert!(!filter.check_filter(&tx));
+    }
+
+    #[test]
+    fn filter_by_confirm_time() {
+        let mut tx = proto_Transaction::new();
+        tx.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        tx.since_timestamp = 1_647_313_850_992;
+        tx.confirm_timestamp = 1_647_313_870_992;
+
+        // known before, but confirmed after
+        let moment = Utc.timestamp_millis_opt(1_647_313_860_000).unwrap();
+        let filter = Filter { before: Some(moment), ..Filter::default() };
+        assert!(filter.check_filter(&tx));
+        let filter = Filter { after: Some(moment), ..Filter::default() };
+        assert!(filter.check_filter(&tx));
+
+        // in the history it's after, same as it's in the index
+        let filter = Filter { before: Some(moment), time_match: TimeMatch::Ordered, ..Filter::default() };
+        assert!(!filter.check_filter(&tx));
+        let filter = Filter { after: Some(moment), time_match: TimeMatch::Ordered, ..Filter::default() };
+        assert!(filter.check_filter(&tx));
+    }
 
     #[test]
     fn empty_filter_accept_any() {
@@ -436,4 +1214,97 @@ mod tests {
         let ok = filter.check_filter(&tx);
         assert!(!ok)
     }
+
+    #[test]
+    fn detect_internal_transfer() {
+        let mut tx = proto_Transaction::new();
+        tx.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx.since_timestamp = 1_647_313_850_992;
+        let mut change1 = proto_Change::new();
+        change1.wallet_id = "72279ede-44c4-4951-925b-f51a7b9e929a".to_string();
+        change1.direction = Direction::SEND;
+        change1.change_type = Change_ChangeType::TRANSFER;
+        tx.changes.push(change1);
+        let mut change2 = proto_Change::new();
+        change2.direction = Direction::RECEIVE;
+        change2.change_type = Change_ChangeType::TRANSFER;
+        tx.changes.push(change2);
+
+        // the receiver is not own wallet
+        assert!(!tx.detect_internal_transfer());
+
+        tx.changes[1].wallet_id = "72279ede-44c4-4951-925b-f51a7b9e929a".to_string();
+        // same wallet, ex. a change address
+        assert!(!tx.detect_internal_transfer());
+
+        tx.changes[1].wallet_id = "11c7ec4d-2b5f-4f8a-9e6a-0d3a8b1c3e6f".to_string();
+        assert!(tx.detect_internal_transfer());
+    }
+
+    #[test]
+    fn fee_rate_from_inputs_and_outputs() {
+        let mut tx = proto_Transaction::new();
+        tx.blockchain = BlockchainId::CHAIN_BITCOIN;
+        tx.since_timestamp = 1_647_313_850_992;
+        let mut input = BitcoinInput::new();
+        input.txid = "01ff3e2b6d2f1e52aa548e79b8f43d0091e9541bc4f70cda4e6549aaf836268b".to_string();
+        input.vout = 1;
+        input.own = true;
+        tx.inputs.push(input);
+        let mut output = BitcoinOutput::new();
+        output.amount = 80_000;
+        tx.outputs.push(output.clone());
+        output.vout = 1;
+        output.amount = 17_180;
+        output.own = true;
+        tx.outputs.push(output);
+        tx.vsize = 141;
+
+        // the input amount is unknown
+        assert_eq!(tx.get_bitcoin_fee(), None);
+        assert_eq!(tx.get_fee_rate(), None);
+
+        tx.inputs[0].amount = 100_000;
+        assert_eq!(tx.get_bitcoin_fee(), Some(2_820));
+        assert_eq!(tx.get_fee_rate(), Some(20.0));
+
+        tx.vsize = 0;
+        assert_eq!(tx.get_fee_rate(), None);
+    }
+
+    #[test]
+    fn check_confirmations() {
+        let mut tx = proto_Transaction::new();
+        tx.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx.state = State::SUBMITTED;
+        let filter = Filter { min_confirmations: Some(12), ..Filter::default() };
+        let heads = HashMap::from([(100u32, 15_000_011u64)]);
+
+        assert_eq!(tx.get_confirmations(15_000_011), 0);
+        assert!(!filter.check_confirmations(&tx, &heads));
+
+        tx.state = State::CONFIRMED;
+        let mut block = BlockRef::new();
+        block.height = 15_000_000;
+        tx.set_block(block);
+        assert_eq!(tx.get_confirmations(15_000_000), 1);
+        assert_eq!(tx.get_confirmations(14_999_990), 1);
+        assert_eq!(tx.get_confirmations(15_000_011), 12);
+        assert!(filter.check_confirmations(&tx, &heads));
+        assert!(!Filter { min_confirmations: Some(13), ..Filter::default() }.check_confirmations(&tx, &heads));
+        // unknown head
+        assert!(!filter.check_confirmations(&tx, &HashMap::new()));
+        assert!(Filter::default().check_confirmations(&tx, &HashMap::new()));
+    }
+
+    #[test]
+    fn cursor_staleness() {
+        let now = Utc.with_ymd_and_hms(2022, 3, 16, 13, 45, 10).unwrap();
+        let stale_after = Duration::minutes(10);
+        let cursor = |since| Some(RemoteCursor { value: "cursor".to_string(), since });
+        assert_eq!(Staleness::of(&None, now, stale_after), Staleness::Missing);
+        assert_eq!(Staleness::of(&cursor(now - Duration::minutes(5)), now, stale_after), Staleness::Fresh);
+        assert_eq!(Staleness::of(&cursor(now - Duration::minutes(10)), now, stale_after), Staleness::Fresh);
+        assert_eq!(Staleness::of(&cursor(now - Duration::minutes(11)), now, stale_after), Staleness::Stale);
+    }
 }
\ No newline at end of file
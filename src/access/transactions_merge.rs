@@ -4,6 +4,8 @@
 //! For a transaction we just get most fields from the newly proposed transaction, with exceptions for:
 //! - get latest of `confirm_timestamp`
 //! - keep `since_timestamp` if already set
+//! - keep `nonce_ref` and `replaced_by` if not provided with update
+//! - merge `token_transfers` by the log index, with the proposed transfer replacing the known one
 //!
 //! For _changes_ the process is a bit more complex. We distinguish two types of a change: transfer and fee.
 //!
@@ -15,17 +17,39 @@
 //! - get fields from the proposed change
 //! - but ensure that `wallet_id` and `entry_id` are not erased
 //!
+//! Before merging, the identical changes (i.e., with the same address, asset, amount, direction and type) on each side are
+//! collapsed into one, because some data sources report the same transfer twice (ex. from logs and from a trace).
+//!
 //! _Fees_ are replaced only if provided with update. I.e., if we have a fee already in the db we just
 //! keep it as is. That's the case of bitcoin multi-user transaction, because we know our part of the fees
 //! when we created the tx, and the following updates may not know our share.
 //!
+//! # Policies
+//!
+//! The rules above are the `MergePolicy::Default`. With `PreferLocal` the same rules are applied with the sides
+//! swapped, i.e. the stored values win and the update only fills what is missing. With `PreferRemote` the update
+//! replaces the stored transaction as is, which is supposed for a full re-sync from a trusted source.
 //!
 use std::cmp;
+use std::collections::BTreeMap;
 use protobuf::RepeatedField;
-use crate::proto::transactions::{Change, Change_ChangeType, Transaction};
+use crate::access::transactions::MergePolicy;
+use crate::proto::transactions::{Change, Change_ChangeType, TokenTransfer, Transaction};
 
 impl Transaction {
 
+    pub(crate) fn merge_with(self, update: Transaction, policy: MergePolicy) -> Transaction {
+        match policy {
+            MergePolicy::Default => self.merge(update),
+            MergePolicy::PreferLocal => update.merge(self),
+            MergePolicy::PreferRemote => {
+                let mut merged = update;
+                merged.version = cmp::max(self.version, merged.version) + 1;
+                merged
+            }
+        }
+    }
+
     pub(crate) fn merge(self, update: Transaction) -> Transaction {
         let mut merged = update.clone();
         merged.version = cmp::max(self.version, update.version) + 1;
@@ -35,10 +59,50 @@ impl Transaction {
         if merged.since_timestamp == 0 {
             merged.set_since_timestamp(self.since_timestamp);
         }
+        if !merged.has_nonce_ref() && self.has_nonce_ref() {
+            merged.set_nonce_ref(self.get_nonce_ref().clone());
+        }
+        if merged.replaced_by.is_empty() {
+            merged.set_replaced_by(self.replaced_by.clone());
+        }
+        // the inputs and outputs are optional details, which an update may not have
+        if merged.inputs.is_empty() {
+            merged.set_inputs(self.inputs.clone());
+        }
+        if merged.outputs.is_empty() {
+            merged.set_outputs(self.outputs.clone());
+        }
+        if merged.vsize == 0 {
+            merged.set_vsize(self.vsize);
+        }
+        let token_transfers = merge_token_transfers(self.get_token_transfers(), update.get_token_transfers());
+        merged.set_token_transfers(RepeatedField::from_vec(token_transfers));
         let changes = merge_changes(self.get_changes(), update.get_changes());
         merged.set_changes(RepeatedField::from_vec(changes));
         merged
     }
+
+    ///
+    /// Collapse the identical changes into one
+    pub(crate) fn dedup_changes(&mut self) {
+        let changes = dedup_changes(self.get_changes());
+        if changes.len() != self.changes.len() {
+            self.set_changes(RepeatedField::from_vec(changes));
+        }
+    }
+}
+
+///
+/// Merge the decoded token transfers. An update may have only some of them decoded, so the known transfers are kept
+/// unless the update has a transfer with the same log index. The result is ordered by the log index.
+fn merge_token_transfers(current: &[TokenTransfer], update: &[TokenTransfer]) -> Vec<TokenTransfer> {
+    let mut result: BTreeMap<u32, TokenTransfer> = current.iter()
+        .map(|t| (t.log_index, t.clone()))
+        .collect();
+    for t in update {
+        result.insert(t.log_index, t.clone());
+    }
+    result.into_values().collect()
 }
 
 impl Change {
@@ -46,6 +110,12 @@ impl Change {
         self.amount == another.amount && self.direction == another.direction && self.asset == another.asset && self.address == another.address
     }
 
+    fn is_duplicate_of(&self, another: &Change) -> bool {
+        // the same address may be in different wallets, and each of them needs its own change
+        let same_wallet = self.wallet_id.is_empty() || another.wallet_id.is_empty() || self.wallet_id == another.wallet_id;
+        self.is_similar_to(another) && self.change_type == another.change_type && same_wallet
+    }
+
     pub(crate) fn merge(self, update: Change) -> Change {
         let mut merged = update.clone();
         if update.wallet_id.is_empty() {
@@ -72,17 +142,37 @@ impl ChangeMerge {
     }
 }
 
+///
+/// Keep only the first of the identical changes, but fill its wallet from a duplicate if it doesn't have one
+fn dedup_changes(changes: &[Change]) -> Vec<Change> {
+    let mut result: Vec<Change> = Vec::with_capacity(changes.len());
+    for change in changes {
+        match result.iter_mut().find(|c| c.is_duplicate_of(change)) {
+            Some(known) => {
+                if known.wallet_id.is_empty() && !change.wallet_id.is_empty() {
+                    known.wallet_id = change.wallet_id.clone();
+                    known.entry_id = change.entry_id;
+                }
+            }
+            None => result.push(change.clone()),
+        }
+    }
+    result
+}
+
 fn merge_changes(existing: &[Change], proposed: &[Change]) -> Vec<Change> {
+    let existing = dedup_changes(existing);
+    let proposed = dedup_changes(proposed);
     // get all transfers, including old, etc
     let transfers = merge_changes_transfer(
-        only_change_type(existing, Change_ChangeType::TRANSFER),
-        only_change_type(proposed, Change_ChangeType::TRANSFER)
+        only_change_type(&existing, Change_ChangeType::TRANSFER),
+        only_change_type(&proposed, Change_ChangeType::TRANSFER)
     );
 
     // check if we have a proposed fees, otherwise just use the previous change for fee if it exist
-    let proposed_fees = only_change_type(proposed, Change_ChangeType::FEE);
+    let proposed_fees = only_change_type(&proposed, Change_ChangeType::FEE);
     let fees = if proposed_fees.is_empty() {
-        only_change_type(existing, Change_ChangeType::FEE)
+        only_change_type(&existing, Change_ChangeType::FEE)
     } else {
         proposed_fees
     };
@@ -136,7 +226,8 @@ fn merge_changes_transfer(left: Vec<Change>, right: Vec<Change>) -> Vec<ChangeMe
 
 #[cfg(test)]
 mod tests {
-    use crate::proto::transactions::{BlockchainId, Change, Change_ChangeType, Direction, Transaction};
+    use crate::access::transactions::MergePolicy;
+    use crate::proto::transactions::{BitcoinInput, BitcoinOutput, BlockchainId, Change, Change_ChangeType, Direction, TokenStandard, TokenTransfer, Transaction};
 
     #[test]
     fn merge_same_transaction() {
@@ -354,6 +445,156 @@ mod tests {
         assert_eq!(merged.changes.get(2).unwrap(), &change_fee1);
     }
 
+    #[test]
+    fn keeps_inputs_and_outputs() {
+        let mut tx = Transaction::new();
+        tx.blockchain = BlockchainId::CHAIN_BITCOIN;
+        tx.since_timestamp = 1_647_313_850_992;
+        let mut input = BitcoinInput::new();
+        input.txid = "01ff3e2b6d2f1e52aa548e79b8f43d0091e9541bc4f70cda4e6549aaf836268b".to_string();
+        input.amount = 100_000;
+        tx.inputs.push(input);
+        let mut output = BitcoinOutput::new();
+        output.amount = 97_180;
+        tx.outputs.push(output);
+        tx.vsize = 141;
+
+        // an update without the details, ex. just a new state
+        let mut update = Transaction::new();
+        update.blockchain = BlockchainId::CHAIN_BITCOIN;
+        update.since_timestamp = 1_647_313_850_992;
+        update.confirm_timestamp = 1_647_313_950_992;
+
+        let merged = tx.clone().merge(update);
+        assert_eq!(merged.inputs, tx.inputs);
+        assert_eq!(merged.outputs, tx.outputs);
+        assert_eq!(merged.vsize, 141);
+        assert_eq!(merged.confirm_timestamp, 1_647_313_950_992);
+    }
+
+    #[test]
+    fn merge_by_policy() {
+        let mut change_fee = Change::new();
+        change_fee.change_type = Change_ChangeType::FEE;
+        change_fee.wallet_id = "72279ede-44c4-4951-925b-f51a7b9e929a".to_string();
+        change_fee.amount = "100".to_string();
+
+        let mut tx = Transaction::new();
+        tx.blockchain = BlockchainId::CHAIN_BITCOIN;
+        tx.since_timestamp = 1_647_313_850_992;
+        tx.replaced_by = "01ff3e2b6d2f1e52aa548e79b8f43d0091e9541bc4f70cda4e6549aaf836268b".to_string();
+        tx.changes.push(change_fee.clone());
+        tx.version = 2;
+
+        let mut update = Transaction::new();
+        update.blockchain = BlockchainId::CHAIN_BITCOIN;
+        update.since_timestamp = 1_647_313_860_992;
+        update.confirm_timestamp = 1_647_313_950_992;
+
+        let merged = tx.clone().merge_with(update.clone(), MergePolicy::Default);
+        assert_eq!(merged, tx.clone().merge(update.clone()));
+
+        // the stored values win, but the missing are taken from the update
+        let merged = tx.clone().merge_with(update.clone(), MergePolicy::PreferLocal);
+        assert_eq!(merged.since_timestamp, 1_647_313_850_992);
+        assert_eq!(merged.confirm_timestamp, 1_647_313_950_992);
+        assert_eq!(merged.replaced_by, tx.replaced_by);
+        assert_eq!(merged.changes.to_vec(), vec![change_fee]);
+        assert_eq!(merged.version, 3);
+
+        // the stored values are overwritten, including the fee and the replacement
+        let merged = tx.clone().merge_with(update.clone(), MergePolicy::PreferRemote);
+        assert_eq!(merged.since_timestamp, 1_647_313_860_992);
+        assert!(merged.replaced_by.is_empty());
+        assert!(merged.changes.is_empty());
+        assert_eq!(merged.version, 3);
+    }
+
+    #[test]
+    fn merge_token_transfers() {
+        let mut tx = Transaction::new();
+        tx.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx.since_timestamp = 1_647_313_850_992;
+        let mut transfer1 = TokenTransfer::new();
+        transfer1.log_index = 12;
+        transfer1.contract = "0xdac17f958d2ee523a2206206994597c13d831ec7".to_string();
+        transfer1.standard = TokenStandard::TOKEN_ERC20;
+        transfer1.from = "0x6218b36c1d19d4a2e9eb0ce3606eb48a0b86991c".to_string();
+        transfer1.to = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
+        transfer1.amount = "1000000".to_string();
+        tx.token_transfers.push(transfer1.clone());
+
+        // without the transfers
+        let mut update = Transaction::new();
+        update.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        update.since_timestamp = 1_647_313_850_992;
+        let merged = tx.clone().merge(update.clone());
+        assert_eq!(merged.token_transfers.to_vec(), vec![transfer1.clone()]);
+
+        // with a new one and an updated one
+        let mut transfer2 = TokenTransfer::new();
+        transfer2.log_index = 5;
+        transfer2.contract = "0xbc4ca0eda7647a8ab7c2061c2e118a18a936f13d".to_string();
+        transfer2.standard = TokenStandard::TOKEN_ERC721;
+        transfer2.from = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
+        transfer2.to = "0x6218b36c1d19d4a2e9eb0ce3606eb48a0b86991c".to_string();
+        transfer2.token_id = "4711".to_string();
+        let mut transfer1_upd = transfer1.clone();
+        transfer1_upd.amount = "2000000".to_string();
+        update.token_transfers.push(transfer1_upd.clone());
+        update.token_transfers.push(transfer2.clone());
+        let merged = tx.merge(update);
+        assert_eq!(merged.token_transfers.to_vec(), vec![transfer2, transfer1_upd]);
+    }
+
+    #[test]
+    fn dedup_identical_changes() {
+        let mut change1 = Change::new();
+        change1.wallet_id = "72279ede-44c4-4951-925b-f51a7b9e929a".to_string();
+        change1.entry_id = 1;
+        change1.address = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
+        change1.amount = "100000000".to_string();
+        change1.direction = Direction::SEND;
+        change1.change_type = Change_ChangeType::TRANSFER;
+
+        let mut change1_fee = change1.clone();
+        change1_fee.change_type = Change_ChangeType::FEE;
+
+        let mut change1_copy = change1.clone();
+        change1_copy.clear_wallet_id();
+        change1_copy.clear_entry_id();
+
+        let mut change1_other_wallet = change1.clone();
+        change1_other_wallet.wallet_id = "b76ebc4f-0da4-4fe2-8ed9-d8b0ec2c5da3".to_string();
+
+        let mut tx = Transaction::new();
+        tx.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx.since_timestamp = 1_647_313_850_992;
+        tx.changes.push(change1_copy.clone());
+        tx.changes.push(change1.clone());
+        tx.changes.push(change1_fee.clone());
+
+        // the first one is kept, but gets the wallet from the duplicate
+        let mut deduped = tx.clone();
+        deduped.dedup_changes();
+        assert_eq!(deduped.changes.to_vec(), vec![change1.clone(), change1_fee.clone()]);
+
+        let mut update = tx.clone();
+        update.changes.clear();
+        update.changes.push(change1_copy.clone());
+        update.changes.push(change1_copy.clone());
+        let merged = deduped.merge(update);
+        assert_eq!(merged.changes.to_vec(), vec![change1.clone(), change1_fee]);
+
+        // the same change in another wallet is not a duplicate
+        let mut shared = tx.clone();
+        shared.changes.clear();
+        shared.changes.push(change1.clone());
+        shared.changes.push(change1_other_wallet.clone());
+        shared.dedup_changes();
+        assert_eq!(shared.changes.to_vec(), vec![change1, change1_other_wallet]);
+    }
+
     #[test]
     fn updates_fee_if_new_come() {
         let mut change1 = Change::new();
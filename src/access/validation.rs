@@ -0,0 +1,177 @@
+use std::sync::RwLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+use uuid::Uuid;
+use crate::access::address_format;
+use crate::errors::{InvalidValueError, StateError};
+use crate::proto::addressbook::BookItem;
+use crate::proto::transactions::{BlockchainId, Transaction, TransactionMeta};
+
+/// Validation plugin for a transaction, called before it's stored
+pub type TransactionValidator = Box<dyn Fn(&Transaction) -> Result<(), InvalidValueError> + Send + Sync>;
+/// Validation plugin for a transaction meta, called before it's stored
+pub type TransactionMetaValidator = Box<dyn Fn(&TransactionMeta) -> Result<(), InvalidValueError> + Send + Sync>;
+/// Validation plugin for an Address Book item, called before it's stored
+pub type BookItemValidator = Box<dyn Fn(&BookItem) -> Result<(), InvalidValueError> + Send + Sync>;
+
+///
+/// How strictly the records are validated before they are stored
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ValidationMode {
+    /// Any invalid record rejects the whole write
+    #[default]
+    Strict,
+    /// Recoverable issues (ex. a missing timestamp or an unknown blockchain) are fixed or accepted with a warning,
+    /// and an invalid record is skipped with a warning instead of rejecting the whole write
+    Lenient,
+    /// No validation, for imports of already trusted data. Only a record that cannot be stored at all is skipped
+    Off,
+}
+
+///
+/// An issue found with a record written in a non-strict mode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationWarning {
+    /// Position of the record in the submitted list
+    pub index: usize,
+    /// `true` if the record wasn't stored because of the issue
+    pub skipped: bool,
+    /// Description of the issue
+    pub message: String,
+}
+
+///
+/// Result of a write with a `ValidationMode`
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckedWrite<T> {
+    /// Result for each of the submitted records, in the same order, or `None` if the record was skipped
+    pub results: Vec<Option<T>>,
+    /// Issues found with the submitted records
+    pub warnings: Vec<ValidationWarning>,
+}
+
+///
+/// Additional validations provided by the embedding app, ex. to deny blacklisted addresses.
+/// They are applied on top of the standard validation, and if any of them fails the whole write is rejected.
+#[derive(Default)]
+pub struct Validators {
+    transactions: RwLock<Vec<TransactionValidator>>,
+    transaction_meta: RwLock<Vec<TransactionMetaValidator>>,
+    addressbook: RwLock<Vec<BookItemValidator>>,
+    unique_book_labels: AtomicBool,
+}
+
+impl Validators {
+
+    ///
+    /// Register a validator applied to each submitted transaction
+    pub fn add_transaction_validator(&self, validator: TransactionValidator) {
+        self.transactions.write().unwrap().push(validator)
+    }
+
+    ///
+    /// Register a validator applied to each transaction meta update
+    pub fn add_transaction_meta_validator(&self, validator: TransactionMetaValidator) {
+        self.transaction_meta.write().unwrap().push(validator)
+    }
+
+    ///
+    /// Register a validator applied to each added or updated Address Book item
+    pub fn add_addressbook_validator(&self, validator: BookItemValidator) {
+        self.addressbook.write().unwrap().push(validator)
+    }
+
+    ///
+    /// Require a unique label for each Address Book item, compared case-insensitively, so an item with the same label
+    /// as another one is rejected. Items without a label are not checked. It's off by default.
+    pub fn set_unique_book_labels(&self, enabled: bool) {
+        self.unique_book_labels.store(enabled, Ordering::Relaxed)
+    }
+
+    pub(crate) fn is_unique_book_labels(&self) -> bool {
+        self.unique_book_labels.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn check_transaction(&self, value: &Transaction) -> Result<(), StateError> {
+        for validator in self.transactions.read().unwrap().iter() {
+            validator(value)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn check_transaction_meta(&self, value: &TransactionMeta) -> Result<(), StateError> {
+        for validator in self.transaction_meta.read().unwrap().iter() {
+            validator(value)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn check_book_item(&self, value: &BookItem) -> Result<(), StateError> {
+        for validator in self.addressbook.read().unwrap().iter() {
+            validator(value)?;
+        }
+        Ok(())
+    }
+
+    ///
+    /// Validate the transaction according to the `mode`, fixing the recoverable issues in the lenient mode.
+    /// Returns the description of each fixed issue, or an error if the transaction must not be stored.
+    pub(crate) fn check_transaction_with(&self, value: &mut Transaction, mode: ValidationMode, now: u64) -> Result<Vec<String>, StateError> {
+        if mode == ValidationMode::Strict {
+            self.check_transaction(value)?;
+            return Ok(vec![])
+        }
+        // it's a part of the key, so cannot be stored without it
+        if value.tx_id.is_empty() {
+            return Err(StateError::InvalidValue(InvalidValueError::Name("tx_id".to_string())))
+        }
+        if mode == ValidationMode::Off {
+            return Ok(vec![])
+        }
+        let mut warnings = Vec::new();
+        if value.blockchain == BlockchainId::CHAIN_UNSPECIFIED {
+            warnings.push("Unknown blockchain".to_string());
+        }
+        if value.since_timestamp == 0 {
+            value.since_timestamp = if value.confirm_timestamp > 0 { value.confirm_timestamp } else { now };
+            warnings.push("Missing timestamp".to_string());
+        }
+        self.check_transaction(value)?;
+        Ok(warnings)
+    }
+
+    ///
+    /// Validate the Address Book item according to the `mode`.
+    /// Returns the description of each accepted issue, or an error if the item must not be stored.
+    pub(crate) fn check_book_item_with(&self, value: &BookItem, mode: ValidationMode) -> Result<Vec<String>, StateError> {
+        match mode {
+            ValidationMode::Strict => {
+                value.validate()?;
+                self.check_book_item(value)?;
+                Ok(vec![])
+            }
+            ValidationMode::Lenient => {
+                let mut warnings = Vec::new();
+                if address_format::get(value.blockchain).is_some() {
+                    value.validate()?;
+                } else {
+                    // the address format is unknown, so it checks only that it's there
+                    if Uuid::parse_str(value.get_id()).is_err() {
+                        return Err(StateError::InvalidValue(InvalidValueError::Name("id".to_string())))
+                    }
+                    if value.get_address().get_address().is_empty() {
+                        return Err(StateError::InvalidValue(InvalidValueError::NameMessage("address".to_string(), "Address is empty".to_string())))
+                    }
+                    warnings.push(format!("Unknown blockchain {}", value.blockchain));
+                }
+                self.check_book_item(value)?;
+                Ok(warnings)
+            }
+            ValidationMode::Off => {
+                if Uuid::parse_str(value.get_id()).is_err() {
+                    return Err(StateError::InvalidValue(InvalidValueError::Name("id".to_string())))
+                }
+                Ok(vec![])
+            }
+        }
+    }
+}
@@ -0,0 +1,257 @@
+//!
+//! Utilities to measure performance of the storage, enabled with the `bench` feature.
+//!
+//! It generates a reproducible dataset (the same parameters always produce the same data) in a temporary store, and
+//! times the typical operations on it. Ex.:
+//!
+//! ```ignore
+//! let store = BenchStore::generate(Dataset { wallets: 10, transactions: 10_000, contacts: 100 })?;
+//! let timing = store.time_query(Filter::default(), PageQuery::default(), 100)?;
+//! println!("{}", timing);
+//! ```
+//!
+use std::fmt;
+use std::time::{Duration, Instant};
+use protobuf::ProtobufEnum;
+use tempdir::TempDir;
+use uuid::Uuid;
+use crate::access::addressbook::AddressBook;
+use crate::access::pagination::PageQuery;
+use crate::access::transactions::{Filter, Transactions};
+use crate::errors::StateError;
+use crate::proto::addressbook::{Address, BookItem};
+use crate::proto::transactions::{BlockchainId, Change, Change_ChangeType, Direction, State, Status, Transaction};
+use crate::storage::sled_access::SledStorage;
+
+/// Timestamp of the first generated transaction
+const START_TIMESTAMP: u64 = 1_647_313_850_000;
+/// Number of transactions submitted at once when generating the dataset
+const SUBMIT_CHUNK: usize = 1_000;
+
+///
+/// Size of a generated dataset
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dataset {
+    pub wallets: usize,
+    /// Total number of transactions, spread evenly between the wallets
+    pub transactions: usize,
+    /// Number of Address Book items
+    pub contacts: usize,
+}
+
+///
+/// Result of timing an operation over multiple runs
+#[derive(Debug, Clone)]
+pub struct Timing {
+    pub name: String,
+    pub runs: usize,
+    pub total: Duration,
+    pub min: Duration,
+    pub max: Duration,
+}
+
+impl Timing {
+    pub fn mean(&self) -> Duration {
+        if self.runs == 0 {
+            return Duration::ZERO
+        }
+        self.total / self.runs as u32
+    }
+}
+
+impl fmt::Display for Timing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} runs, mean {:?}, min {:?}, max {:?}", self.name, self.runs, self.mean(), self.min, self.max)
+    }
+}
+
+///
+/// Run the operation `runs` times and measure the time of each run. Stops on the first error.
+pub fn measure<F>(name: &str, runs: usize, mut f: F) -> Result<Timing, StateError>
+    where F: FnMut(usize) -> Result<(), StateError> {
+    let mut timing = Timing {
+        name: name.to_string(),
+        runs,
+        total: Duration::ZERO,
+        min: Duration::MAX,
+        max: Duration::ZERO,
+    };
+    for i in 0..runs {
+        let start = Instant::now();
+        f(i)?;
+        let elapsed = start.elapsed();
+        timing.total += elapsed;
+        timing.min = timing.min.min(elapsed);
+        timing.max = timing.max.max(elapsed);
+    }
+    if runs == 0 {
+        timing.min = Duration::ZERO;
+    }
+    Ok(timing)
+}
+
+///
+/// A store in a temporary directory filled with generated data. The directory is deleted when the store is dropped.
+pub struct BenchStore {
+    pub storage: SledStorage,
+    /// Ids of the generated wallets
+    pub wallets: Vec<Uuid>,
+    /// Number of already generated transactions, used to produce new unique transactions
+    transactions: usize,
+    _dir: TempDir,
+}
+
+impl BenchStore {
+
+    ///
+    /// Create a new store with the generated dataset
+    pub fn generate(dataset: Dataset) -> Result<BenchStore, StateError> {
+        let dir = TempDir::new("emerald-state-bench")?;
+        let storage = SledStorage::open(dir.path().to_path_buf())?;
+        let wallets = (0..dataset.wallets)
+            .map(|i| Uuid::from_u128(0x72279ede_44c4_4951_925b_000000000000 + i as u128))
+            .collect();
+        let mut store = BenchStore { storage, wallets, transactions: 0, _dir: dir };
+
+        let mut remaining = dataset.transactions;
+        while remaining > 0 {
+            let size = remaining.min(SUBMIT_CHUNK);
+            store.submit_new(size)?;
+            remaining -= size;
+        }
+
+        let contacts: Vec<BookItem> = (0..dataset.contacts).map(create_contact).collect();
+        if !contacts.is_empty() {
+            store.storage.get_addressbook().add(contacts)?;
+        }
+        Ok(store)
+    }
+
+    ///
+    /// Create `count` new transactions, following the already generated ones. Wallets are used in a round-robin order,
+    /// so a store without wallets gets transactions without changes.
+    pub fn create_transactions(&mut self, count: usize) -> Vec<Transaction> {
+        let result = (self.transactions..self.transactions + count)
+            .map(|i| {
+                let wallet = if self.wallets.is_empty() {
+                    None
+                } else {
+                    Some(self.wallets[i % self.wallets.len()])
+                };
+                create_transaction(i, wallet)
+            })
+            .collect();
+        self.transactions += count;
+        result
+    }
+
+    fn submit_new(&mut self, count: usize) -> Result<(), StateError> {
+        let transactions = self.create_transactions(count);
+        self.storage.get_transactions().submit(transactions).map(|_| ())
+    }
+
+    ///
+    /// Time a query of the transactions
+    pub fn time_query(&self, filter: Filter, page: PageQuery, runs: usize) -> Result<Timing, StateError> {
+        let transactions = self.storage.get_transactions();
+        measure("query", runs, |_| {
+            transactions.query(filter.clone(), page.clone()).map(|_| ())
+        })
+    }
+
+    ///
+    /// Time a submit of `batch_size` new transactions
+    pub fn time_submit(&mut self, batch_size: usize, runs: usize) -> Result<Timing, StateError> {
+        let batches: Vec<Vec<Transaction>> = (0..runs).map(|_| self.create_transactions(batch_size)).collect();
+        let transactions = self.storage.get_transactions();
+        measure("submit", runs, |i| {
+            transactions.submit(batches[i].clone()).map(|_| ())
+        })
+    }
+}
+
+///
+/// Generate a deterministic pseudo-random value for the position, so the data looks random but is reproducible
+fn get_noise(i: usize) -> u64 {
+    // splitmix64
+    let mut z = (i as u64).wrapping_add(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+fn create_transaction(i: usize, wallet: Option<Uuid>) -> Transaction {
+    let noise = get_noise(i);
+    let mut tx = Transaction::new();
+    tx.blockchain = BlockchainId::CHAIN_ETHEREUM;
+    tx.tx_id = format!("0x{:016x}{:048x}", noise, i);
+    tx.since_timestamp = START_TIMESTAMP + i as u64 * 60_000;
+    // keep a few last of every hundred as pending
+    if i % 100 < 95 {
+        tx.state = State::CONFIRMED;
+        tx.status = Status::OK;
+        tx.confirm_timestamp = tx.since_timestamp + 15_000;
+    } else {
+        tx.state = State::SUBMITTED;
+    }
+    if let Some(wallet) = wallet {
+        let mut change = Change::new();
+        change.wallet_id = wallet.to_string();
+        change.address = format!("0x{:040x}", noise);
+        change.asset = "ETH".to_string();
+        change.amount = (noise % 1_000_000_000_000_000_000).to_string();
+        change.direction = if noise % 3 == 1 { Direction::SEND } else { Direction::RECEIVE };
+        change.change_type = Change_ChangeType::TRANSFER;
+        tx.changes.push(change);
+    }
+    tx
+}
+
+fn create_contact(i: usize) -> BookItem {
+    let mut item = BookItem::new();
+    item.id = Uuid::from_u128(0xf0ad8a03_9d57_4d9c_8c29_000000000000 + i as u128).to_string();
+    item.blockchain = BlockchainId::CHAIN_ETHEREUM.value() as u32;
+    item.label = format!("Contact {}", i);
+    item.create_timestamp = START_TIMESTAMP + i as u64;
+    item.update_timestamp = START_TIMESTAMP + i as u64;
+    let mut address = Address::new();
+    address.address = format!("0x{:040x}", get_noise(i));
+    item.set_address(address);
+    item
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::access::addressbook::{AddressBook, Filter as BookFilter};
+    use crate::access::pagination::PageQuery;
+    use crate::access::transactions::{Filter, Transactions};
+    use crate::bench::{BenchStore, Dataset};
+
+    #[test]
+    fn generate_dataset() {
+        let mut store = BenchStore::generate(Dataset { wallets: 3, transactions: 1_500, contacts: 10 }).unwrap();
+        assert_eq!(store.wallets.len(), 3);
+
+        let count = store.storage.get_transactions().get_count(Filter::default()).unwrap();
+        assert_eq!(count, 1_500);
+        let contacts = store.storage.get_addressbook()
+            .query(BookFilter::default(), PageQuery::default()).unwrap();
+        assert_eq!(contacts.values.len(), 10);
+
+        let timing = store.time_query(Filter::default(), PageQuery::default(), 5).unwrap();
+        assert_eq!(timing.runs, 5);
+        assert!(timing.min <= timing.max);
+
+        let timing = store.time_submit(10, 2).unwrap();
+        assert_eq!(timing.runs, 2);
+        let count = store.storage.get_transactions().get_count(Filter::default()).unwrap();
+        assert_eq!(count, 1_520);
+    }
+
+    #[test]
+    fn reproducible() {
+        let mut a = BenchStore::generate(Dataset { wallets: 2, transactions: 0, contacts: 0 }).unwrap();
+        let mut b = BenchStore::generate(Dataset { wallets: 2, transactions: 0, contacts: 0 }).unwrap();
+        assert_eq!(a.create_transactions(10), b.create_transactions(10));
+    }
+}
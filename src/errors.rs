@@ -1,3 +1,4 @@
+use std::io::ErrorKind;
 use protobuf::ProtobufError;
 
 #[derive(Clone, Debug, PartialEq)]
@@ -6,6 +7,12 @@ pub enum StateError {
     InvalidId,
     InvalidValue(InvalidValueError),
     CorruptedValue,
+    /// The storage handle doesn't have the permission for the operation, see `Permissions`
+    NotPermitted,
+    /// The write is rejected by the rate limit of the store, see `SledStorage::set_rate_limit`
+    Throttled,
+    /// There is no space left on the disk, and the storage is in the degraded mode, see `SledStorage::health`
+    DiskFull,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -16,8 +23,20 @@ pub enum InvalidValueError {
 }
 
 impl From<sled::Error> for StateError {
-    fn from(_: sled::Error) -> Self {
-        StateError::IOError
+    fn from(e: sled::Error) -> Self {
+        match e {
+            sled::Error::Io(e) => StateError::from(e),
+            _ => StateError::IOError
+        }
+    }
+}
+
+impl From<std::io::Error> for StateError {
+    fn from(e: std::io::Error) -> Self {
+        match e.kind() {
+            ErrorKind::StorageFull => StateError::DiskFull,
+            _ => StateError::IOError
+        }
     }
 }
 
@@ -0,0 +1,390 @@
+//!
+//! Export of the transaction history to formats supported by accounting tools.
+//!
+//! The transactions are converted to a list of _entries_, where each entry is a total change of a single asset made
+//! by a transaction, from the point of view of the wallet or addresses specified by the filter. I.e., a transaction that
+//! sends ether and pays a fee produces two entries: a negative transfer and a negative fee.
+//! Replaced or dropped transactions are skipped, and for a failed transaction only the fee is exported.
+//!
+//! The amounts are written as decimals scaled by the units of the asset, which must be provided with the options.
+//!
+use std::collections::HashMap;
+use std::str::FromStr;
+use chrono::{DateTime, TimeZone, Utc};
+use num_bigint::{BigInt, BigUint};
+use num_traits::Zero;
+use crate::access::transactions::Filter;
+use crate::errors::{InvalidValueError, StateError};
+use crate::proto::tokens::TokenMetadata;
+use crate::proto::transactions::{Change_ChangeType, Direction, State, Status, Transaction};
+
+pub mod ofx;
+pub mod qif;
+
+///
+/// Options shared by the export writers
+#[derive(Debug, Clone, Default)]
+pub struct ExportOptions {
+    /// Units of the assets by the asset code. All exported assets must be known, and the export fails otherwise.
+    pub assets: HashMap<String, AssetUnits>,
+    /// If set, a human-readable amount (ex. `1,234.5 USDC`) is included in the memo
+    pub number_format: Option<NumberFormat>,
+}
+
+///
+/// Units of an asset used to format its amounts
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssetUnits {
+    /// Number of decimals in the integer amount, ex. 18 for Ether or 6 for USDC
+    pub decimals: u32,
+    /// Symbol to display after the amount
+    pub symbol: String,
+}
+
+impl From<&TokenMetadata> for AssetUnits {
+    fn from(value: &TokenMetadata) -> Self {
+        AssetUnits {
+            decimals: value.decimals,
+            symbol: value.symbol.clone(),
+        }
+    }
+}
+
+impl AssetUnits {
+
+    ///
+    /// Split the integer amount into the whole and fractional parts, with trailing zeroes removed from the fraction
+    fn split(&self, amount: &BigInt) -> (String, String) {
+        let decimals = self.decimals as usize;
+        let digits = amount.magnitude().to_string();
+        let digits = format!("{:0>width$}", digits, width = decimals + 1);
+        let (whole, fraction) = digits.split_at(digits.len() - decimals);
+        (whole.to_string(), fraction.trim_end_matches('0').to_string())
+    }
+
+    ///
+    /// Plain decimal amount, as used by the export formats, ex. `-1.2345`
+    pub fn to_decimal(&self, amount: &BigInt) -> String {
+        let (whole, fraction) = self.split(amount);
+        let mut result = String::new();
+        if amount < &BigInt::zero() {
+            result.push('-');
+        }
+        result.push_str(whole.as_str());
+        if !fraction.is_empty() {
+            result.push('.');
+            result.push_str(fraction.as_str());
+        }
+        result
+    }
+}
+
+///
+/// Locale-specific format of human-readable amounts
+#[derive(Debug, Clone)]
+pub struct NumberFormat {
+    pub decimal_separator: char,
+    /// Separator between groups of thousands, if any
+    pub group_separator: Option<char>,
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        NumberFormat {
+            decimal_separator: '.',
+            group_separator: Some(','),
+        }
+    }
+}
+
+impl NumberFormat {
+    ///
+    /// Format the integer amount of the asset with the specified units
+    pub fn format(&self, units: &AssetUnits, amount: &BigInt) -> String {
+        let (whole, fraction) = units.split(amount);
+
+        let mut result = String::new();
+        if amount < &BigInt::zero() {
+            result.push('-');
+        }
+        for (i, c) in whole.chars().enumerate() {
+            if i > 0 && (whole.len() - i) % 3 == 0 {
+                if let Some(sep) = self.group_separator {
+                    result.push(sep);
+                }
+            }
+            result.push(c);
+        }
+        if !fraction.is_empty() {
+            result.push(self.decimal_separator);
+            result.push_str(fraction.as_str());
+        }
+        result.push(' ');
+        result.push_str(units.symbol.as_str());
+        result
+    }
+}
+
+impl ExportOptions {
+
+    ///
+    /// Units of the asset of the entry, or an error if they are not provided
+    pub(crate) fn get_units(&self, asset: &str) -> Result<&AssetUnits, StateError> {
+        self.assets.get(asset)
+            .ok_or_else(|| StateError::InvalidValue(InvalidValueError::NameMessage("asset".to_string(), format!("unknown units of {}", asset))))
+    }
+
+    ///
+    /// Decimal amount of the entry
+    pub(crate) fn get_amount(&self, entry: &ExportEntry) -> Result<String, StateError> {
+        Ok(self.get_units(entry.asset.as_str())?.to_decimal(&entry.amount))
+    }
+
+    ///
+    /// Human-readable amount of the entry, if enabled by the options
+    pub(crate) fn format_amount(&self, entry: &ExportEntry) -> Result<Option<String>, StateError> {
+        match &self.number_format {
+            Some(f) => Ok(Some(f.format(self.get_units(entry.asset.as_str())?, &entry.amount))),
+            None => Ok(None)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ExportEntry {
+    pub tx_id: String,
+    pub timestamp: DateTime<Utc>,
+    pub asset: String,
+    /// Positive for received, negative for sent
+    pub amount: BigInt,
+    pub is_fee: bool,
+}
+
+impl ExportEntry {
+    ///
+    /// Unique id of the entry, based on the tx id
+    pub fn get_id(&self) -> String {
+        if self.is_fee {
+            format!("{}/fee/{}", self.tx_id, self.asset)
+        } else {
+            format!("{}/{}", self.tx_id, self.asset)
+        }
+    }
+
+    ///
+    /// Short human-readable description of the entry
+    pub fn get_title(&self) -> &'static str {
+        if self.is_fee {
+            "Fee"
+        } else if self.amount < BigInt::zero() {
+            "Sent"
+        } else {
+            "Received"
+        }
+    }
+}
+
+///
+/// Convert transactions to the entries for export, ordered by time starting from the earliest
+pub(crate) fn to_entries(transactions: &[Transaction], filter: &Filter) -> Vec<ExportEntry> {
+    let mut result = Vec::new();
+    for tx in transactions {
+        if tx.state == State::REPLACED || tx.state == State::DROPPED {
+            continue
+        }
+        let ts = if tx.confirm_timestamp > 0 { tx.confirm_timestamp } else { tx.since_timestamp };
+        let timestamp = Utc.timestamp_millis_opt(ts as i64).unwrap();
+        // asset -> (transfers, fees), in order of appearance
+        let mut totals: Vec<(String, BigInt, BigInt)> = Vec::new();
+        for change in tx.changes.iter().filter(|c| filter.check_change(c)) {
+            let amount = match BigUint::from_str(change.amount.as_str()) {
+                Ok(amount) => BigInt::from(amount),
+                Err(_) => continue
+            };
+            let amount = match change.direction {
+                Direction::SEND => -amount,
+                Direction::RECEIVE => amount,
+            };
+            let pos = match totals.iter().position(|t| t.0 == change.asset) {
+                Some(pos) => pos,
+                None => {
+                    totals.push((change.asset.clone(), BigInt::zero(), BigInt::zero()));
+                    totals.len() - 1
+                }
+            };
+            if change.change_type == Change_ChangeType::FEE {
+                totals[pos].2 += amount;
+            } else {
+                totals[pos].1 += amount;
+            }
+        }
+        for (asset, transfers, fees) in totals {
+            if !transfers.is_zero() && tx.status != Status::FAILED {
+                result.push(ExportEntry { tx_id: tx.tx_id.clone(), timestamp, asset: asset.clone(), amount: transfers, is_fee: false });
+            }
+            if !fees.is_zero() {
+                result.push(ExportEntry { tx_id: tx.tx_id.clone(), timestamp, asset, amount: fees, is_fee: true });
+            }
+        }
+    }
+    result.sort_by_key(|e| e.timestamp);
+    result
+}
+
+///
+/// List of assets used by the entries, in order of appearance
+pub(crate) fn get_assets(entries: &[ExportEntry]) -> Vec<String> {
+    let mut assets: Vec<String> = Vec::new();
+    for e in entries {
+        if !assets.contains(&e.asset) {
+            assets.push(e.asset.clone());
+        }
+    }
+    assets
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::collections::HashMap;
+    use std::str::FromStr;
+    use num_bigint::BigInt;
+    use uuid::Uuid;
+    use crate::access::transactions::{Filter, WalletRef};
+    use crate::export::{to_entries, AssetUnits, ExportOptions, NumberFormat};
+    use crate::proto::transactions::{BlockchainId, Change as proto_Change, Change_ChangeType, Direction, State, Status, Transaction as proto_Transaction};
+
+    pub(crate) const WALLET_ID: &str = "72279ede-44c4-4951-925b-f51a7b9e929a";
+
+    pub(crate) fn create_change(wallet_id: &str, asset: &str, amount: &str, direction: Direction, change_type: Change_ChangeType) -> proto_Change {
+        let mut change = proto_Change::new();
+        change.wallet_id = wallet_id.to_string();
+        change.asset = asset.to_string();
+        change.amount = amount.to_string();
+        change.direction = direction;
+        change.change_type = change_type;
+        change
+    }
+
+    ///
+    /// A sent transaction with fee, and a received transaction, for the WALLET_ID
+    pub(crate) fn create_transactions() -> Vec<proto_Transaction> {
+        let mut tx1 = proto_Transaction::new();
+        tx1.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx1.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        tx1.since_timestamp = 1_647_313_850_992;
+        tx1.state = State::CONFIRMED;
+        tx1.changes.push(create_change(WALLET_ID, "ETH", "1000", Direction::SEND, Change_ChangeType::TRANSFER));
+        tx1.changes.push(create_change(WALLET_ID, "ETH", "21", Direction::SEND, Change_ChangeType::FEE));
+        tx1.changes.push(create_change("f0ad8a03-9d57-4d9c-8c29-8b5a5ea4a4a5", "ETH", "1000", Direction::RECEIVE, Change_ChangeType::TRANSFER));
+
+        let mut tx2 = proto_Transaction::new();
+        tx2.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx2.tx_id = "0x5f88e4e1ab5d1a3ccb1e0ddfb6f9b5cc0ad43eb7ae1ef1b38ba6b6fcd4a6fb4c".to_string();
+        tx2.since_timestamp = 1_647_400_000_000;
+        tx2.state = State::CONFIRMED;
+        tx2.changes.push(create_change(WALLET_ID, "USDT", "500", Direction::RECEIVE, Change_ChangeType::TRANSFER));
+
+        vec![tx2, tx1]
+    }
+
+    pub(crate) fn wallet_filter() -> Filter {
+        Filter {
+            wallet: Some(WalletRef::WholeWallet(Uuid::from_str(WALLET_ID).unwrap())),
+            ..Filter::default()
+        }
+    }
+
+    fn eth() -> AssetUnits {
+        AssetUnits { decimals: 18, symbol: "ETH".to_string() }
+    }
+
+    fn usdt() -> AssetUnits {
+        AssetUnits { decimals: 6, symbol: "USDT".to_string() }
+    }
+
+    pub(crate) fn export_options() -> ExportOptions {
+        let mut assets = HashMap::new();
+        assets.insert("ETH".to_string(), eth());
+        assets.insert("USDT".to_string(), usdt());
+        ExportOptions {
+            assets,
+            number_format: None,
+        }
+    }
+
+    #[test]
+    fn decimal_amounts() {
+        assert_eq!(usdt().to_decimal(&BigInt::from(1_234_500)), "1.2345");
+        assert_eq!(usdt().to_decimal(&BigInt::from(-1_234_500)), "-1.2345");
+        assert_eq!(usdt().to_decimal(&BigInt::from(1_234_567_000_000i64)), "1234567");
+        assert_eq!(usdt().to_decimal(&BigInt::from(0)), "0");
+        assert_eq!(eth().to_decimal(&BigInt::from_str("1000000000000000000").unwrap()), "1");
+        assert_eq!(eth().to_decimal(&BigInt::from(-21)), "-0.000000000000000021");
+    }
+
+    #[test]
+    fn format_amounts() {
+        let format = NumberFormat::default();
+        assert_eq!(format.format(&usdt(), &BigInt::from(1_234_500)), "1.2345 USDT");
+        assert_eq!(format.format(&usdt(), &BigInt::from(-1_234_500)), "-1.2345 USDT");
+        assert_eq!(format.format(&usdt(), &BigInt::from(1_234_567_000_000i64)), "1,234,567 USDT");
+        assert_eq!(format.format(&usdt(), &BigInt::from(500)), "0.0005 USDT");
+        assert_eq!(format.format(&usdt(), &BigInt::from(0)), "0 USDT");
+        assert_eq!(format.format(&eth(), &BigInt::from_str("21000000000000").unwrap()), "0.000021 ETH");
+    }
+
+    #[test]
+    fn format_amounts_with_locale() {
+        let format = NumberFormat {
+            decimal_separator: ',',
+            group_separator: Some(' '),
+        };
+        assert_eq!(format.format(&usdt(), &BigInt::from(1_234_567_890_000i64)), "1 234 567,89 USDT");
+        let format = NumberFormat {
+            group_separator: None,
+            ..NumberFormat::default()
+        };
+        assert_eq!(format.format(&usdt(), &BigInt::from(1_234_567_890_000i64)), "1234567.89 USDT");
+    }
+
+    #[test]
+    fn require_units_of_asset() {
+        let mut options = export_options();
+        options.assets.remove("USDT");
+        let entries = to_entries(&create_transactions(), &wallet_filter());
+        assert!(options.get_amount(&entries[0]).is_ok());
+        assert!(options.get_amount(&entries[2]).is_err());
+    }
+
+    #[test]
+    fn convert_to_entries() {
+        let entries = to_entries(&create_transactions(), &wallet_filter());
+        assert_eq!(entries.len(), 3);
+
+        assert_eq!(entries[0].asset, "ETH");
+        assert_eq!(entries[0].amount, BigInt::from(-1000));
+        assert!(!entries[0].is_fee);
+        assert_eq!(entries[1].asset, "ETH");
+        assert_eq!(entries[1].amount, BigInt::from(-21));
+        assert!(entries[1].is_fee);
+        assert_eq!(entries[2].asset, "USDT");
+        assert_eq!(entries[2].amount, BigInt::from(500));
+    }
+
+    #[test]
+    fn only_fee_for_failed() {
+        let mut transactions = create_transactions();
+        transactions[1].status = Status::FAILED;
+        let entries = to_entries(&transactions[1..], &wallet_filter());
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0].is_fee);
+    }
+
+    #[test]
+    fn skip_replaced() {
+        let mut transactions = create_transactions();
+        transactions[1].state = State::REPLACED;
+        let entries = to_entries(&transactions[1..], &wallet_filter());
+        assert!(entries.is_empty());
+    }
+}
@@ -0,0 +1,173 @@
+//!
+//! OFX 2.2 (XML) export. Each asset is exported as a separate bank statement. The account id is limited to 22 chars,
+//! which is not enough for a token address, so it's a short hash of the asset code, and the code itself is in the memo.
+//!
+use std::io::Write;
+use bitcoin::hashes::{sha256, Hash};
+use chrono::{DateTime, Utc};
+use num_bigint::BigInt;
+use num_traits::Zero;
+use crate::access::transactions::Filter;
+use crate::errors::StateError;
+use crate::export::{get_assets, to_entries, ExportEntry, ExportOptions};
+use crate::proto::transactions::Transaction;
+
+fn format_date(ts: &DateTime<Utc>) -> String {
+    ts.format("%Y%m%d%H%M%S%.3f[0:GMT]").to_string()
+}
+
+/// Escape text for an XML element
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+///
+/// Stable account id of the asset, as the first 8 bytes of its hash in hex
+fn get_account_id(asset: &str) -> String {
+    let hash = sha256::Hash::hash(asset.as_bytes());
+    hex::encode(&hash.into_inner()[0..8])
+}
+
+fn write_status<W: Write>(out: &mut W) -> Result<(), StateError> {
+    writeln!(out, "<STATUS><CODE>0</CODE><SEVERITY>INFO</SEVERITY></STATUS>")?;
+    Ok(())
+}
+
+fn write_statement<W: Write>(out: &mut W, asset: &str, entries: &[&ExportEntry], now: &DateTime<Utc>, options: &ExportOptions) -> Result<(), StateError> {
+    writeln!(out, "<STMTTRNRS>")?;
+    writeln!(out, "<TRNUID>0</TRNUID>")?;
+    write_status(out)?;
+    writeln!(out, "<STMTRS>")?;
+    // the currency must be an ISO-4217 code, and `XXX` is the code for _no currency_
+    writeln!(out, "<CURDEF>XXX</CURDEF>")?;
+    writeln!(out, "<BANKACCTFROM><BANKID>EMERALD</BANKID><ACCTID>{}</ACCTID><ACCTTYPE>CHECKING</ACCTTYPE></BANKACCTFROM>", get_account_id(asset))?;
+    writeln!(out, "<BANKTRANLIST>")?;
+    let start = entries.first().map(|e| e.timestamp).unwrap_or(*now);
+    let end = entries.last().map(|e| e.timestamp).unwrap_or(*now);
+    writeln!(out, "<DTSTART>{}</DTSTART>", format_date(&start))?;
+    writeln!(out, "<DTEND>{}</DTEND>", format_date(&end))?;
+    let mut total = BigInt::zero();
+    for e in entries {
+        let trn_type = if e.is_fee {
+            "FEE"
+        } else if e.amount < BigInt::zero() {
+            "DEBIT"
+        } else {
+            "CREDIT"
+        };
+        writeln!(out, "<STMTTRN>")?;
+        writeln!(out, "<TRNTYPE>{}</TRNTYPE>", trn_type)?;
+        writeln!(out, "<DTPOSTED>{}</DTPOSTED>", format_date(&e.timestamp))?;
+        writeln!(out, "<TRNAMT>{}</TRNAMT>", options.get_amount(e)?)?;
+        writeln!(out, "<FITID>{}</FITID>", escape(&e.get_id()))?;
+        writeln!(out, "<NAME>{}</NAME>", escape(e.get_title()))?;
+        match options.format_amount(e)? {
+            Some(amount) => writeln!(out, "<MEMO>{} {} {}</MEMO>", escape(&amount), escape(asset), escape(&e.tx_id))?,
+            None => writeln!(out, "<MEMO>{} {}</MEMO>", escape(asset), escape(&e.tx_id))?,
+        }
+        writeln!(out, "</STMTTRN>")?;
+        total += &e.amount;
+    }
+    writeln!(out, "</BANKTRANLIST>")?;
+    // we don't know the actual balance, so it's a sum of the exported transactions
+    writeln!(out, "<LEDGERBAL><BALAMT>{}</BALAMT><DTASOF>{}</DTASOF></LEDGERBAL>", options.get_units(asset)?.to_decimal(&total), format_date(&end))?;
+    writeln!(out, "</STMTRS>")?;
+    writeln!(out, "</STMTTRNRS>")?;
+    Ok(())
+}
+
+///
+/// Write the transactions as an OFX document. Only changes accepted by `filter.check_change` are included, i.e. if the
+/// filter specifies a wallet then it's the history of that wallet.
+/// The amounts are decimals scaled by the units from the `options`, and if the `options` enable formatting then
+/// a human-readable amount is added to the memo.
+pub fn write_ofx<W: Write>(out: &mut W, transactions: &[Transaction], filter: &Filter, options: &ExportOptions) -> Result<(), StateError> {
+    let entries = to_entries(transactions, filter);
+    let now = Utc::now();
+
+    writeln!(out, "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>")?;
+    writeln!(out, "<?OFX OFXHEADER=\"200\" VERSION=\"220\" SECURITY=\"NONE\" OLDFILEUID=\"NONE\" NEWFILEUID=\"NONE\"?>")?;
+    writeln!(out, "<OFX>")?;
+    writeln!(out, "<SIGNONMSGSRSV1>")?;
+    writeln!(out, "<SONRS>")?;
+    write_status(out)?;
+    writeln!(out, "<DTSERVER>{}</DTSERVER>", format_date(&now))?;
+    writeln!(out, "<LANGUAGE>ENG</LANGUAGE>")?;
+    writeln!(out, "</SONRS>")?;
+    writeln!(out, "</SIGNONMSGSRSV1>")?;
+    writeln!(out, "<BANKMSGSRSV1>")?;
+    for asset in get_assets(&entries) {
+        let asset_entries: Vec<&ExportEntry> = entries.iter().filter(|e| e.asset == asset).collect();
+        write_statement(out, &asset, &asset_entries, &now, options)?;
+    }
+    writeln!(out, "</BANKMSGSRSV1>")?;
+    writeln!(out, "</OFX>")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::export::{AssetUnits, NumberFormat};
+    use crate::export::ofx::{get_account_id, write_ofx};
+    use crate::export::tests::{create_change, create_transactions, export_options, wallet_filter, WALLET_ID};
+    use crate::proto::transactions::{Change_ChangeType, Direction};
+
+    #[test]
+    fn write_statements_per_asset() {
+        let mut out = Vec::new();
+        write_ofx(&mut out, &create_transactions(), &wallet_filter(), &export_options()).unwrap();
+        let act = String::from_utf8(out).unwrap();
+
+        assert!(act.starts_with("<?xml"));
+        assert_eq!(act.matches("<STMTRS>").count(), 2);
+        assert_eq!(act.matches("<STMTTRN>").count(), 3);
+        assert!(act.contains(format!("<ACCTID>{}</ACCTID>", get_account_id("ETH")).as_str()));
+        assert!(act.contains(format!("<ACCTID>{}</ACCTID>", get_account_id("USDT")).as_str()));
+        assert!(act.contains("<TRNTYPE>DEBIT</TRNTYPE>\n<DTPOSTED>20220315031050.992[0:GMT]</DTPOSTED>\n<TRNAMT>-0.000000000000001</TRNAMT>"));
+        assert!(act.contains("<TRNTYPE>FEE</TRNTYPE>\n<DTPOSTED>20220315031050.992[0:GMT]</DTPOSTED>\n<TRNAMT>-0.000000000000000021</TRNAMT>"));
+        assert!(act.contains("<TRNTYPE>CREDIT</TRNTYPE>"));
+        assert!(act.contains("<FITID>0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b/fee/ETH</FITID>"));
+        assert!(act.contains("<MEMO>ETH 0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b</MEMO>"));
+        assert!(act.contains("<BALAMT>-0.000000000000001021</BALAMT>"));
+        assert!(act.trim_end().ends_with("</OFX>"));
+    }
+
+    #[test]
+    fn write_formatted_amounts() {
+        let mut out = Vec::new();
+        let mut options = export_options();
+        options.number_format = Some(NumberFormat::default());
+        write_ofx(&mut out, &create_transactions(), &wallet_filter(), &options).unwrap();
+        let act = String::from_utf8(out).unwrap();
+
+        assert!(act.contains("<TRNAMT>0.0005</TRNAMT>"));
+        assert!(act.contains("<MEMO>0.0005 USDT USDT 0x5f88e4e1ab5d1a3ccb1e0ddfb6f9b5cc0ad43eb7ae1ef1b38ba6b6fcd4a6fb4c</MEMO>"));
+    }
+
+    #[test]
+    fn short_account_id_for_token() {
+        let token = "erc20:100:0xdac17f958d2ee523a2206206994597c13d831ec7";
+        let mut transactions = create_transactions();
+        transactions[0].changes.push(create_change(WALLET_ID, token, "2500000", Direction::RECEIVE, Change_ChangeType::TRANSFER));
+        let mut options = export_options();
+        options.assets.insert(token.to_string(), AssetUnits { decimals: 6, symbol: "USDT".to_string() });
+
+        let mut out = Vec::new();
+        write_ofx(&mut out, &transactions, &wallet_filter(), &options).unwrap();
+        let act = String::from_utf8(out).unwrap();
+
+        assert_eq!(get_account_id(token).len(), 16);
+        assert_eq!(get_account_id(token), get_account_id(token));
+        assert!(act.contains(format!("<ACCTID>{}</ACCTID>", get_account_id(token)).as_str()));
+        assert!(act.contains("<TRNAMT>2.5</TRNAMT>"));
+        assert!(act.contains(format!("<MEMO>{} 0x5f88e4e1ab5d1a3ccb1e0ddfb6f9b5cc0ad43eb7ae1ef1b38ba6b6fcd4a6fb4c</MEMO>", token).as_str()));
+    }
+
+    #[test]
+    fn fail_for_unknown_units() {
+        let mut options = export_options();
+        options.assets.remove("USDT");
+        let mut out = Vec::new();
+        assert!(write_ofx(&mut out, &create_transactions(), &wallet_filter(), &options).is_err());
+    }
+}
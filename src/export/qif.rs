@@ -0,0 +1,94 @@
+//!
+//! QIF export. Each asset is exported as a separate bank account named by the asset code, and fees are put into
+//! the `Fees` category.
+//!
+use std::io::Write;
+use crate::access::transactions::Filter;
+use crate::errors::StateError;
+use crate::export::{get_assets, to_entries, ExportOptions};
+use crate::proto::transactions::Transaction;
+
+const FEE_CATEGORY: &str = "Fees";
+
+///
+/// Write the transactions as a QIF document. Only changes accepted by `filter.check_change` are included, i.e. if the
+/// filter specifies a wallet then it's the history of that wallet.
+/// The amounts are decimals scaled by the units from the `options`, and if the `options` enable formatting then
+/// a human-readable amount is added to the memo.
+pub fn write_qif<W: Write>(out: &mut W, transactions: &[Transaction], filter: &Filter, options: &ExportOptions) -> Result<(), StateError> {
+    let entries = to_entries(transactions, filter);
+    for asset in get_assets(&entries) {
+        writeln!(out, "!Account")?;
+        writeln!(out, "N{}", asset)?;
+        writeln!(out, "TBank")?;
+        writeln!(out, "^")?;
+        writeln!(out, "!Type:Bank")?;
+        for e in entries.iter().filter(|e| e.asset == asset) {
+            writeln!(out, "D{}", e.timestamp.format("%m/%d/%Y"))?;
+            writeln!(out, "T{}", options.get_amount(e)?)?;
+            writeln!(out, "P{}", e.get_title())?;
+            match options.format_amount(e)? {
+                Some(amount) => writeln!(out, "M{} {}", amount, e.tx_id)?,
+                None => writeln!(out, "M{}", e.tx_id)?,
+            }
+            if e.is_fee {
+                writeln!(out, "L{}", FEE_CATEGORY)?;
+            }
+            writeln!(out, "^")?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::export::NumberFormat;
+    use crate::export::qif::write_qif;
+    use crate::export::tests::{create_transactions, export_options, wallet_filter};
+
+    #[test]
+    fn write_accounts_per_asset() {
+        let mut out = Vec::new();
+        write_qif(&mut out, &create_transactions(), &wallet_filter(), &export_options()).unwrap();
+        let act = String::from_utf8(out).unwrap();
+
+        let exp = "!Account\n\
+            NETH\n\
+            TBank\n\
+            ^\n\
+            !Type:Bank\n\
+            D03/15/2022\n\
+            T-0.000000000000001\n\
+            PSent\n\
+            M0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b\n\
+            ^\n\
+            D03/15/2022\n\
+            T-0.000000000000000021\n\
+            PFee\n\
+            M0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b\n\
+            LFees\n\
+            ^\n\
+            !Account\n\
+            NUSDT\n\
+            TBank\n\
+            ^\n\
+            !Type:Bank\n\
+            D03/16/2022\n\
+            T0.0005\n\
+            PReceived\n\
+            M0x5f88e4e1ab5d1a3ccb1e0ddfb6f9b5cc0ad43eb7ae1ef1b38ba6b6fcd4a6fb4c\n\
+            ^\n";
+        assert_eq!(act, exp);
+    }
+
+    #[test]
+    fn write_formatted_amounts() {
+        let mut out = Vec::new();
+        let mut options = export_options();
+        options.number_format = Some(NumberFormat::default());
+        write_qif(&mut out, &create_transactions(), &wallet_filter(), &options).unwrap();
+        let act = String::from_utf8(out).unwrap();
+
+        assert!(act.contains("T-0.000000000000000021\nPFee\nM-0.000000000000000021 ETH 0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b\n"));
+    }
+}
@@ -0,0 +1,314 @@
+//!
+//! Deterministic test data, enabled with the `fixtures` feature.
+//!
+//! The data is generated from a seed, and the same seed always produces the same data. It lets the integration tests
+//! and the benchmarks of the dependent projects share a realistic dataset without copying the builders. Ex.:
+//!
+//! ```ignore
+//! let mut fixtures = Fixtures::new(42);
+//! let wallets = fixtures.fill(&storage, FixtureSize { wallets: 2, transactions: 100, contacts: 10, balances: 5 })?;
+//! ```
+//!
+use std::str::FromStr;
+use bitcoin::Network;
+use bitcoin::secp256k1::Secp256k1;
+use bitcoin::util::bip32::{ChildNumber, ExtendedPrivKey, ExtendedPubKey};
+use chrono::{TimeZone, Utc};
+use num_bigint::BigUint;
+use protobuf::ProtobufEnum;
+use uuid::Uuid;
+use crate::access::addressbook::AddressBook;
+use crate::access::asset::AssetId;
+use crate::access::balance::{Balance, Balances, Utxo};
+use crate::access::transactions::Transactions;
+use crate::errors::StateError;
+use crate::proto::addressbook::{Address, Address_AddressType, BookItem};
+use crate::proto::transactions::{BlockRef, BlockchainId, Change, Change_ChangeType, Direction, State, Status, Transaction};
+use crate::storage::sled_access::SledStorage;
+
+/// Timestamp of the first generated transaction
+const START_TIMESTAMP: u64 = 1_647_313_850_000;
+/// Height of the block of the first generated transaction
+const START_HEIGHT: u64 = 14_380_000;
+
+///
+/// Size of the data generated by `Fixtures::fill`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixtureSize {
+    pub wallets: usize,
+    /// Number of distinct transactions, spread between the wallets
+    pub transactions: usize,
+    /// Number of Address Book items
+    pub contacts: usize,
+    /// Number of addresses with balances
+    pub balances: usize,
+}
+
+///
+/// Generated transactions, where the `updates` are supposed to be submitted after the `transactions`
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransactionHistory {
+    pub transactions: Vec<Transaction>,
+    /// Later versions of some of the `transactions`, which are merged with them when submitted
+    pub updates: Vec<Transaction>,
+}
+
+///
+/// Generator of deterministic data. Each call continues the sequence, so the result depends on the seed and on the
+/// order of the calls.
+pub struct Fixtures {
+    state: u64,
+    /// Number of already generated transactions, to keep their ids and timestamps unique
+    transactions: usize,
+}
+
+impl Fixtures {
+
+    pub fn new(seed: u64) -> Fixtures {
+        Fixtures { state: seed, transactions: 0 }
+    }
+
+    ///
+    /// Next pseudo-random value (splitmix64)
+    fn next(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    fn next_uuid(&mut self) -> Uuid {
+        let value = ((self.next() as u128) << 64) | self.next() as u128;
+        // make it look like a random (v4) uuid
+        Uuid::from_u128((value & !(0xf000 << 64) & !(0xc000 << 48)) | (0x4000 << 64) | (0x8000 << 48))
+    }
+
+    fn next_ethereum_address(&mut self) -> String {
+        format!("0x{:016x}{:016x}{:08x}", self.next(), self.next(), self.next() as u32)
+    }
+
+    fn next_xpub(&mut self) -> ExtendedPubKey {
+        let secp = Secp256k1::new();
+        let seed: Vec<u8> = (0..4).flat_map(|_| self.next().to_be_bytes()).collect();
+        let master = ExtendedPrivKey::new_master(Network::Bitcoin, seed.as_slice())
+            .expect("valid seed");
+        let path = [
+            ChildNumber::from_hardened_idx(44).unwrap(),
+            ChildNumber::from_hardened_idx(0).unwrap(),
+            ChildNumber::from_hardened_idx(0).unwrap(),
+        ];
+        let account = master.derive_priv(&secp, &path).expect("valid path");
+        ExtendedPubKey::from_priv(&secp, &account)
+    }
+
+    ///
+    /// Generate ids of the wallets
+    pub fn wallets(&mut self, count: usize) -> Vec<Uuid> {
+        (0..count).map(|_| self.next_uuid()).collect()
+    }
+
+    ///
+    /// Generate `count` Ethereum transactions of the wallets. About a third of them is pending, and has an update as
+    /// confirmed, where the update doesn't know the wallet as if it came from a remote indexer. Without wallets the
+    /// transactions have no changes.
+    pub fn transactions(&mut self, wallets: &[Uuid], count: usize) -> TransactionHistory {
+        let mut result = Vec::with_capacity(count);
+        let mut updates = Vec::new();
+        for _ in 0..count {
+            let i = self.transactions;
+            self.transactions += 1;
+            let noise = self.next();
+            let mut tx = Transaction::new();
+            tx.blockchain = BlockchainId::CHAIN_ETHEREUM;
+            tx.tx_id = format!("0x{:016x}{:016x}{:032x}", noise, self.next(), i);
+            tx.since_timestamp = START_TIMESTAMP + i as u64 * 60_000 + noise % 60_000;
+            if !wallets.is_empty() {
+                let mut change = Change::new();
+                change.wallet_id = wallets[(noise % wallets.len() as u64) as usize].to_string();
+                change.address = self.next_ethereum_address();
+                change.asset = AssetId::Native(BlockchainId::CHAIN_ETHEREUM.value() as u32).to_string();
+                change.amount = (self.next() % 1_000_000_000_000_000_000).to_string();
+                change.direction = if noise % 3 == 1 { Direction::SEND } else { Direction::RECEIVE };
+                change.change_type = Change_ChangeType::TRANSFER;
+                tx.changes.push(change);
+                if tx.changes[0].direction == Direction::SEND {
+                    let mut fee = Change::new();
+                    fee.wallet_id = tx.changes[0].wallet_id.clone();
+                    fee.address = tx.changes[0].address.clone();
+                    fee.asset = tx.changes[0].asset.clone();
+                    fee.amount = (21_000 * (self.next() % 100 + 1) * 1_000_000_000).to_string();
+                    fee.direction = Direction::SEND;
+                    fee.change_type = Change_ChangeType::FEE;
+                    tx.changes.push(fee);
+                }
+            }
+            let mut confirmed = tx.clone();
+            confirmed.state = State::CONFIRMED;
+            confirmed.status = if noise % 50 == 7 { Status::FAILED } else { Status::OK };
+            confirmed.confirm_timestamp = tx.since_timestamp + 15_000 + noise % 30_000;
+            let mut block = BlockRef::new();
+            block.height = START_HEIGHT + i as u64 * 5;
+            block.block_id = format!("0x{:016x}{:048x}", self.next(), block.height);
+            block.timestamp = confirmed.confirm_timestamp;
+            confirmed.set_block(block);
+            confirmed.block_pos = (noise % 200) as u32;
+            if noise.is_multiple_of(3) {
+                tx.state = State::SUBMITTED;
+                result.push(tx);
+                for change in confirmed.changes.iter_mut() {
+                    if change.change_type == Change_ChangeType::TRANSFER {
+                        change.wallet_id = String::new();
+                    }
+                }
+                updates.push(confirmed);
+            } else {
+                result.push(confirmed);
+            }
+        }
+        TransactionHistory { transactions: result, updates }
+    }
+
+    ///
+    /// Generate Address Book items. Every fourth of them is a Bitcoin xpub, others are Ethereum addresses.
+    pub fn contacts(&mut self, count: usize) -> Vec<BookItem> {
+        (0..count).map(|i| {
+            let mut item = BookItem::new();
+            item.id = self.next_uuid().to_string();
+            item.create_timestamp = START_TIMESTAMP + (self.next() % 86_400_000);
+            item.update_timestamp = item.create_timestamp;
+            let mut address = Address::new();
+            if i % 4 == 3 {
+                item.blockchain = BlockchainId::CHAIN_BITCOIN.value() as u32;
+                item.label = format!("Bitcoin Contact {}", i);
+                address.set_field_type(Address_AddressType::XPUB);
+                address.address = self.next_xpub().to_string();
+            } else {
+                item.blockchain = BlockchainId::CHAIN_ETHEREUM.value() as u32;
+                item.label = format!("Contact {}", i);
+                address.address = self.next_ethereum_address();
+            }
+            item.set_address(address);
+            item
+        }).collect()
+    }
+
+    ///
+    /// Generate balances. Every other is a Bitcoin balance with the unspent outputs, which sum up to the amount.
+    pub fn balances(&mut self, count: usize) -> Vec<Balance> {
+        (0..count).map(|i| {
+            let ts = Utc.timestamp_millis_opt((START_TIMESTAMP + self.next() % 86_400_000) as i64).unwrap();
+            if i % 2 == 1 {
+                let xpub = self.next_xpub()
+                    .ckd_pub(&Secp256k1::new(), ChildNumber::from_normal_idx(0).unwrap())
+                    .expect("valid child");
+                let address = bitcoin::Address::p2wpkh(&bitcoin::PublicKey::new(xpub.public_key), Network::Bitcoin)
+                    .expect("compressed key");
+                let utxo: Vec<Utxo> = (0..(self.next() % 5 + 1)).map(|vout| Utxo {
+                    txid: format!("{:016x}{:048x}", self.next(), i),
+                    vout: vout as u32,
+                    amount: self.next() % 10_000_000 + 546,
+                    reserved_by: None,
+                }).collect();
+                Balance {
+                    amount: BigUint::from(utxo.iter().map(|u| u.amount).sum::<u64>()),
+                    ts,
+                    address: address.to_string(),
+                    blockchain: BlockchainId::CHAIN_BITCOIN.value() as u32,
+                    asset: AssetId::Native(BlockchainId::CHAIN_BITCOIN.value() as u32),
+                    utxo,
+                }
+            } else {
+                Balance {
+                    amount: BigUint::from_str(&format!("{}{:09}", self.next() % 100, self.next() % 1_000_000_000)).unwrap(),
+                    ts,
+                    address: self.next_ethereum_address(),
+                    blockchain: BlockchainId::CHAIN_ETHEREUM.value() as u32,
+                    asset: AssetId::Native(BlockchainId::CHAIN_ETHEREUM.value() as u32),
+                    utxo: vec![],
+                }
+            }
+        }).collect()
+    }
+
+    ///
+    /// Generate the data and put it into the storage. Returns the ids of the generated wallets.
+    pub fn fill(&mut self, storage: &SledStorage, size: FixtureSize) -> Result<Vec<Uuid>, StateError> {
+        let wallets = self.wallets(size.wallets);
+        let history = self.transactions(&wallets, size.transactions);
+        // the updates must be submitted separately to be merged with the already stored transactions
+        for transactions in [history.transactions, history.updates] {
+            if !transactions.is_empty() {
+                storage.get_transactions().submit(transactions)?;
+            }
+        }
+        let contacts = self.contacts(size.contacts);
+        if !contacts.is_empty() {
+            storage.get_addressbook().add(contacts)?;
+        }
+        let balances = storage.get_balance();
+        for balance in self.balances(size.balances) {
+            balances.set(balance)?;
+        }
+        Ok(wallets)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempdir::TempDir;
+    use crate::access::addressbook::{AddressBook, Filter as BookFilter};
+    use crate::access::balance::Balances;
+    use crate::access::pagination::PageQuery;
+    use crate::access::transactions::{Filter, Transactions};
+    use crate::fixtures::{Fixtures, FixtureSize};
+    use crate::proto::transactions::State;
+    use crate::storage::sled_access::SledStorage;
+
+    #[test]
+    fn reproducible() {
+        let mut a = Fixtures::new(42);
+        let mut b = Fixtures::new(42);
+        let wallets = a.wallets(2);
+        assert_eq!(wallets, b.wallets(2));
+        let history = a.transactions(&wallets, 20);
+        assert_eq!(history.transactions.len(), 20);
+        assert!(!history.updates.is_empty());
+        assert_eq!(history, b.transactions(&wallets, 20));
+        assert_eq!(a.contacts(8), b.contacts(8));
+        assert_eq!(a.balances(4), b.balances(4));
+
+        let mut c = Fixtures::new(43);
+        assert_ne!(c.wallets(2), wallets);
+    }
+
+    #[test]
+    fn fill_storage() {
+        let tmp_dir = TempDir::new("fixtures").unwrap();
+        let storage = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let size = FixtureSize { wallets: 3, transactions: 60, contacts: 8, balances: 4 };
+        let wallets = Fixtures::new(42).fill(&storage, size).unwrap();
+        assert_eq!(wallets.len(), 3);
+
+        let transactions = storage.get_transactions();
+        assert_eq!(transactions.get_count(Filter::default()).unwrap(), 60);
+        // the pending ones got confirmed by the updates, and kept the wallet
+        let results = transactions.query(Filter::default(), PageQuery { limit: 100, ..PageQuery::default() }).unwrap();
+        assert!(results.values.iter().all(|tx| tx.state == State::CONFIRMED));
+        assert!(results.values.iter().all(|tx| tx.changes.iter().all(|c| !c.wallet_id.is_empty())));
+
+        let contacts = storage.get_addressbook()
+            .query(BookFilter::default(), PageQuery::default()).unwrap();
+        assert_eq!(contacts.values.len(), 8);
+
+        // the same sequence of calls as in `fill`
+        let mut replica = Fixtures::new(42);
+        let wallets = replica.wallets(3);
+        replica.transactions(&wallets, 60);
+        replica.contacts(8);
+        let balances = replica.balances(4);
+        let stored = storage.get_balance().list(balances[1].address.clone()).unwrap();
+        assert_eq!(stored, vec![balances[1].clone()]);
+        assert!(!stored[0].utxo.is_empty());
+    }
+}
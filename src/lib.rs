@@ -9,4 +9,9 @@ pub mod errors;
 pub mod proto;
 pub mod access;
 pub mod storage;
+pub mod export;
+#[cfg(feature = "bench")]
+pub mod bench;
+#[cfg(feature = "fixtures")]
+pub mod fixtures;
 pub(crate) mod validate;
\ No newline at end of file
@@ -2,4 +2,10 @@ pub mod transactions;
 pub mod addressbook;
 pub(crate) mod internal;
 pub mod balance;
-pub(crate) mod cache;
\ No newline at end of file
+pub(crate) mod cache;
+pub mod sync;
+pub mod backup;
+pub mod quarantine;
+pub mod tasks;
+pub mod flags;
+pub mod tokens;
\ No newline at end of file
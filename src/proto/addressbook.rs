@@ -1,4 +1,4 @@
-// This file is generated by rust-protobuf 2.25.2. Do not edit
+// This file is generated by rust-protobuf 2.28.0. Do not edit
 // @generated
 
 // https://github.com/rust-lang/rust-clippy/issues/702
@@ -21,7 +21,7 @@
 
 /// Generated files are compatible only with the same version
 /// of protobuf runtime.
-// const _PROTOBUF_VERSION_CHECK: () = ::protobuf::VERSION_2_25_2;
+// const _PROTOBUF_VERSION_CHECK: () = ::protobuf::VERSION_2_28_0;
 
 #[derive(PartialEq,Clone,Default)]
 pub struct BookItem {
@@ -32,6 +32,14 @@ pub struct BookItem {
     pub blockchain: u32,
     pub create_timestamp: u64,
     pub update_timestamp: u64,
+    pub localized_labels: ::protobuf::RepeatedField<LocalizedLabel>,
+    pub network: NetworkKind,
+    pub archived: bool,
+    pub group: ::std::string::String,
+    pub domain_name: ::std::string::String,
+    pub domain_resolution: ::protobuf::SingularPtrField<DomainResolution>,
+    pub avatar: ::protobuf::SingularPtrField<Avatar>,
+    pub description: ::std::string::String,
     // special fields
     pub unknown_fields: ::protobuf::UnknownFields,
     pub cached_size: ::protobuf::CachedSize,
@@ -177,6 +185,205 @@ impl BookItem {
     pub fn set_update_timestamp(&mut self, v: u64) {
         self.update_timestamp = v;
     }
+
+    // repeated .emerald.state.LocalizedLabel localized_labels = 8;
+
+
+    pub fn get_localized_labels(&self) -> &[LocalizedLabel] {
+        &self.localized_labels
+    }
+    pub fn clear_localized_labels(&mut self) {
+        self.localized_labels.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_localized_labels(&mut self, v: ::protobuf::RepeatedField<LocalizedLabel>) {
+        self.localized_labels = v;
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_localized_labels(&mut self) -> &mut ::protobuf::RepeatedField<LocalizedLabel> {
+        &mut self.localized_labels
+    }
+
+    // Take field
+    pub fn take_localized_labels(&mut self) -> ::protobuf::RepeatedField<LocalizedLabel> {
+        ::std::mem::replace(&mut self.localized_labels, ::protobuf::RepeatedField::new())
+    }
+
+    // .emerald.state.NetworkKind network = 9;
+
+
+    pub fn get_network(&self) -> NetworkKind {
+        self.network
+    }
+    pub fn clear_network(&mut self) {
+        self.network = NetworkKind::NETWORK_UNSPECIFIED;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_network(&mut self, v: NetworkKind) {
+        self.network = v;
+    }
+
+    // bool archived = 10;
+
+
+    pub fn get_archived(&self) -> bool {
+        self.archived
+    }
+    pub fn clear_archived(&mut self) {
+        self.archived = false;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_archived(&mut self, v: bool) {
+        self.archived = v;
+    }
+
+    // string group = 11;
+
+
+    pub fn get_group(&self) -> &str {
+        &self.group
+    }
+    pub fn clear_group(&mut self) {
+        self.group.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_group(&mut self, v: ::std::string::String) {
+        self.group = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_group(&mut self) -> &mut ::std::string::String {
+        &mut self.group
+    }
+
+    // Take field
+    pub fn take_group(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.group, ::std::string::String::new())
+    }
+
+    // string domain_name = 12;
+
+
+    pub fn get_domain_name(&self) -> &str {
+        &self.domain_name
+    }
+    pub fn clear_domain_name(&mut self) {
+        self.domain_name.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_domain_name(&mut self, v: ::std::string::String) {
+        self.domain_name = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_domain_name(&mut self) -> &mut ::std::string::String {
+        &mut self.domain_name
+    }
+
+    // Take field
+    pub fn take_domain_name(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.domain_name, ::std::string::String::new())
+    }
+
+    // .emerald.state.DomainResolution domain_resolution = 13;
+
+
+    pub fn get_domain_resolution(&self) -> &DomainResolution {
+        self.domain_resolution.as_ref().unwrap_or_else(|| <DomainResolution as ::protobuf::Message>::default_instance())
+    }
+    pub fn clear_domain_resolution(&mut self) {
+        self.domain_resolution.clear();
+    }
+
+    pub fn has_domain_resolution(&self) -> bool {
+        self.domain_resolution.is_some()
+    }
+
+    // Param is passed by value, moved
+    pub fn set_domain_resolution(&mut self, v: DomainResolution) {
+        self.domain_resolution = ::protobuf::SingularPtrField::some(v);
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_domain_resolution(&mut self) -> &mut DomainResolution {
+        if self.domain_resolution.is_none() {
+            self.domain_resolution.set_default();
+        }
+        self.domain_resolution.as_mut().unwrap()
+    }
+
+    // Take field
+    pub fn take_domain_resolution(&mut self) -> DomainResolution {
+        self.domain_resolution.take().unwrap_or_else(|| DomainResolution::new())
+    }
+
+    // .emerald.state.Avatar avatar = 14;
+
+
+    pub fn get_avatar(&self) -> &Avatar {
+        self.avatar.as_ref().unwrap_or_else(|| <Avatar as ::protobuf::Message>::default_instance())
+    }
+    pub fn clear_avatar(&mut self) {
+        self.avatar.clear();
+    }
+
+    pub fn has_avatar(&self) -> bool {
+        self.avatar.is_some()
+    }
+
+    // Param is passed by value, moved
+    pub fn set_avatar(&mut self, v: Avatar) {
+        self.avatar = ::protobuf::SingularPtrField::some(v);
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_avatar(&mut self) -> &mut Avatar {
+        if self.avatar.is_none() {
+            self.avatar.set_default();
+        }
+        self.avatar.as_mut().unwrap()
+    }
+
+    // Take field
+    pub fn take_avatar(&mut self) -> Avatar {
+        self.avatar.take().unwrap_or_else(|| Avatar::new())
+    }
+
+    // string description = 15;
+
+
+    pub fn get_description(&self) -> &str {
+        &self.description
+    }
+    pub fn clear_description(&mut self) {
+        self.description.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_description(&mut self, v: ::std::string::String) {
+        self.description = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_description(&mut self) -> &mut ::std::string::String {
+        &mut self.description
+    }
+
+    // Take field
+    pub fn take_description(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.description, ::std::string::String::new())
+    }
 }
 
 impl ::protobuf::Message for BookItem {
@@ -186,6 +393,21 @@ impl ::protobuf::Message for BookItem {
                 return false;
             }
         };
+        for v in &self.localized_labels {
+            if !v.is_initialized() {
+                return false;
+            }
+        };
+        for v in &self.domain_resolution {
+            if !v.is_initialized() {
+                return false;
+            }
+        };
+        for v in &self.avatar {
+            if !v.is_initialized() {
+                return false;
+            }
+        };
         true
     }
 
@@ -223,6 +445,34 @@ impl ::protobuf::Message for BookItem {
                     let tmp = is.read_uint64()?;
                     self.update_timestamp = tmp;
                 },
+                8 => {
+                    ::protobuf::rt::read_repeated_message_into(wire_type, is, &mut self.localized_labels)?;
+                },
+                9 => {
+                    ::protobuf::rt::read_proto3_enum_with_unknown_fields_into(wire_type, is, &mut self.network, 9, &mut self.unknown_fields)?
+                },
+                10 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_bool()?;
+                    self.archived = tmp;
+                },
+                11 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.group)?;
+                },
+                12 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.domain_name)?;
+                },
+                13 => {
+                    ::protobuf::rt::read_singular_message_into(wire_type, is, &mut self.domain_resolution)?;
+                },
+                14 => {
+                    ::protobuf::rt::read_singular_message_into(wire_type, is, &mut self.avatar)?;
+                },
+                15 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.description)?;
+                },
                 _ => {
                     ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
                 },
@@ -254,6 +504,33 @@ impl ::protobuf::Message for BookItem {
         if self.update_timestamp != 0 {
             my_size += ::protobuf::rt::value_size(7, self.update_timestamp, ::protobuf::wire_format::WireTypeVarint);
         }
+        for value in &self.localized_labels {
+            let len = value.compute_size();
+            my_size += 1 + ::protobuf::rt::compute_raw_varint32_size(len) + len;
+        };
+        if self.network != NetworkKind::NETWORK_UNSPECIFIED {
+            my_size += ::protobuf::rt::enum_size(9, self.network);
+        }
+        if self.archived != false {
+            my_size += 2;
+        }
+        if !self.group.is_empty() {
+            my_size += ::protobuf::rt::string_size(11, &self.group);
+        }
+        if !self.domain_name.is_empty() {
+            my_size += ::protobuf::rt::string_size(12, &self.domain_name);
+        }
+        if let Some(ref v) = self.domain_resolution.as_ref() {
+            let len = v.compute_size();
+            my_size += 1 + ::protobuf::rt::compute_raw_varint32_size(len) + len;
+        }
+        if let Some(ref v) = self.avatar.as_ref() {
+            let len = v.compute_size();
+            my_size += 1 + ::protobuf::rt::compute_raw_varint32_size(len) + len;
+        }
+        if !self.description.is_empty() {
+            my_size += ::protobuf::rt::string_size(15, &self.description);
+        }
         my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
         self.cached_size.set(my_size);
         my_size
@@ -280,6 +557,36 @@ impl ::protobuf::Message for BookItem {
         if self.update_timestamp != 0 {
             os.write_uint64(7, self.update_timestamp)?;
         }
+        for v in &self.localized_labels {
+            os.write_tag(8, ::protobuf::wire_format::WireTypeLengthDelimited)?;
+            os.write_raw_varint32(v.get_cached_size())?;
+            v.write_to_with_cached_sizes(os)?;
+        };
+        if self.network != NetworkKind::NETWORK_UNSPECIFIED {
+            os.write_enum(9, ::protobuf::ProtobufEnum::value(&self.network))?;
+        }
+        if self.archived != false {
+            os.write_bool(10, self.archived)?;
+        }
+        if !self.group.is_empty() {
+            os.write_string(11, &self.group)?;
+        }
+        if !self.domain_name.is_empty() {
+            os.write_string(12, &self.domain_name)?;
+        }
+        if let Some(ref v) = self.domain_resolution.as_ref() {
+            os.write_tag(13, ::protobuf::wire_format::WireTypeLengthDelimited)?;
+            os.write_raw_varint32(v.get_cached_size())?;
+            v.write_to_with_cached_sizes(os)?;
+        }
+        if let Some(ref v) = self.avatar.as_ref() {
+            os.write_tag(14, ::protobuf::wire_format::WireTypeLengthDelimited)?;
+            os.write_raw_varint32(v.get_cached_size())?;
+            v.write_to_with_cached_sizes(os)?;
+        }
+        if !self.description.is_empty() {
+            os.write_string(15, &self.description)?;
+        }
         os.write_unknown_fields(self.get_unknown_fields())?;
         ::std::result::Result::Ok(())
     }
@@ -348,6 +655,46 @@ impl ::protobuf::Message for BookItem {
                 |m: &BookItem| { &m.update_timestamp },
                 |m: &mut BookItem| { &mut m.update_timestamp },
             ));
+            fields.push(::protobuf::reflect::accessor::make_repeated_field_accessor::<_, ::protobuf::types::ProtobufTypeMessage<LocalizedLabel>>(
+                "localized_labels",
+                |m: &BookItem| { &m.localized_labels },
+                |m: &mut BookItem| { &mut m.localized_labels },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeEnum<NetworkKind>>(
+                "network",
+                |m: &BookItem| { &m.network },
+                |m: &mut BookItem| { &mut m.network },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBool>(
+                "archived",
+                |m: &BookItem| { &m.archived },
+                |m: &mut BookItem| { &mut m.archived },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "group",
+                |m: &BookItem| { &m.group },
+                |m: &mut BookItem| { &mut m.group },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "domain_name",
+                |m: &BookItem| { &m.domain_name },
+                |m: &mut BookItem| { &mut m.domain_name },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_singular_ptr_field_accessor::<_, ::protobuf::types::ProtobufTypeMessage<DomainResolution>>(
+                "domain_resolution",
+                |m: &BookItem| { &m.domain_resolution },
+                |m: &mut BookItem| { &mut m.domain_resolution },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_singular_ptr_field_accessor::<_, ::protobuf::types::ProtobufTypeMessage<Avatar>>(
+                "avatar",
+                |m: &BookItem| { &m.avatar },
+                |m: &mut BookItem| { &mut m.avatar },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "description",
+                |m: &BookItem| { &m.description },
+                |m: &mut BookItem| { &mut m.description },
+            ));
             ::protobuf::reflect::MessageDescriptor::new_pb_name::<BookItem>(
                 "BookItem",
                 fields,
@@ -370,6 +717,14 @@ impl ::protobuf::Clear for BookItem {
         self.blockchain = 0;
         self.create_timestamp = 0;
         self.update_timestamp = 0;
+        self.localized_labels.clear();
+        self.network = NetworkKind::NETWORK_UNSPECIFIED;
+        self.archived = false;
+        self.group.clear();
+        self.domain_name.clear();
+        self.domain_resolution.clear();
+        self.avatar.clear();
+        self.description.clear();
         self.unknown_fields.clear();
     }
 }
@@ -387,69 +742,80 @@ impl ::protobuf::reflect::ProtobufValue for BookItem {
 }
 
 #[derive(PartialEq,Clone,Default)]
-pub struct Address {
+pub struct Avatar {
     // message fields
-    pub field_type: Address_AddressType,
-    pub address: ::std::string::String,
+    pub image: ::std::vec::Vec<u8>,
+    pub content_type: ::std::string::String,
     // special fields
     pub unknown_fields: ::protobuf::UnknownFields,
     pub cached_size: ::protobuf::CachedSize,
 }
 
-impl<'a> ::std::default::Default for &'a Address {
-    fn default() -> &'a Address {
-        <Address as ::protobuf::Message>::default_instance()
+impl<'a> ::std::default::Default for &'a Avatar {
+    fn default() -> &'a Avatar {
+        <Avatar as ::protobuf::Message>::default_instance()
     }
 }
 
-impl Address {
-    pub fn new() -> Address {
+impl Avatar {
+    pub fn new() -> Avatar {
         ::std::default::Default::default()
     }
 
-    // .emerald.state.Address.AddressType type = 1;
+    // bytes image = 1;
 
 
-    pub fn get_field_type(&self) -> Address_AddressType {
-        self.field_type
+    pub fn get_image(&self) -> &[u8] {
+        &self.image
     }
-    pub fn clear_field_type(&mut self) {
-        self.field_type = Address_AddressType::PLAIN;
+    pub fn clear_image(&mut self) {
+        self.image.clear();
     }
 
     // Param is passed by value, moved
-    pub fn set_field_type(&mut self, v: Address_AddressType) {
-        self.field_type = v;
+    pub fn set_image(&mut self, v: ::std::vec::Vec<u8>) {
+        self.image = v;
     }
 
-    // string address = 2;
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_image(&mut self) -> &mut ::std::vec::Vec<u8> {
+        &mut self.image
+    }
+
+    // Take field
+    pub fn take_image(&mut self) -> ::std::vec::Vec<u8> {
+        ::std::mem::replace(&mut self.image, ::std::vec::Vec::new())
+    }
 
+    // string content_type = 2;
 
-    pub fn get_address(&self) -> &str {
-        &self.address
+
+    pub fn get_content_type(&self) -> &str {
+        &self.content_type
     }
-    pub fn clear_address(&mut self) {
-        self.address.clear();
+    pub fn clear_content_type(&mut self) {
+        self.content_type.clear();
     }
 
     // Param is passed by value, moved
-    pub fn set_address(&mut self, v: ::std::string::String) {
-        self.address = v;
+    pub fn set_content_type(&mut self, v: ::std::string::String) {
+        self.content_type = v;
     }
 
     // Mutable pointer to the field.
     // If field is not initialized, it is initialized with default value first.
-    pub fn mut_address(&mut self) -> &mut ::std::string::String {
-        &mut self.address
+    pub fn mut_content_type(&mut self) -> &mut ::std::string::String {
+        &mut self.content_type
     }
 
     // Take field
-    pub fn take_address(&mut self) -> ::std::string::String {
-        ::std::mem::replace(&mut self.address, ::std::string::String::new())
+    pub fn take_content_type(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.content_type, ::std::string::String::new())
     }
 }
 
-impl ::protobuf::Message for Address {
+impl ::protobuf::Message for Avatar {
     fn is_initialized(&self) -> bool {
         true
     }
@@ -459,10 +825,10 @@ impl ::protobuf::Message for Address {
             let (field_number, wire_type) = is.read_tag_unpack()?;
             match field_number {
                 1 => {
-                    ::protobuf::rt::read_proto3_enum_with_unknown_fields_into(wire_type, is, &mut self.field_type, 1, &mut self.unknown_fields)?
+                    ::protobuf::rt::read_singular_proto3_bytes_into(wire_type, is, &mut self.image)?;
                 },
                 2 => {
-                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.address)?;
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.content_type)?;
                 },
                 _ => {
                     ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
@@ -476,11 +842,11 @@ impl ::protobuf::Message for Address {
     #[allow(unused_variables)]
     fn compute_size(&self) -> u32 {
         let mut my_size = 0;
-        if self.field_type != Address_AddressType::PLAIN {
-            my_size += ::protobuf::rt::enum_size(1, self.field_type);
+        if !self.image.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(1, &self.image);
         }
-        if !self.address.is_empty() {
-            my_size += ::protobuf::rt::string_size(2, &self.address);
+        if !self.content_type.is_empty() {
+            my_size += ::protobuf::rt::string_size(2, &self.content_type);
         }
         my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
         self.cached_size.set(my_size);
@@ -488,11 +854,11 @@ impl ::protobuf::Message for Address {
     }
 
     fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
-        if self.field_type != Address_AddressType::PLAIN {
-            os.write_enum(1, ::protobuf::ProtobufEnum::value(&self.field_type))?;
+        if !self.image.is_empty() {
+            os.write_bytes(1, &self.image)?;
         }
-        if !self.address.is_empty() {
-            os.write_string(2, &self.address)?;
+        if !self.content_type.is_empty() {
+            os.write_string(2, &self.content_type)?;
         }
         os.write_unknown_fields(self.get_unknown_fields())?;
         ::std::result::Result::Ok(())
@@ -524,81 +890,1364 @@ impl ::protobuf::Message for Address {
         Self::descriptor_static()
     }
 
-    fn new() -> Address {
-        Address::new()
+    fn new() -> Avatar {
+        Avatar::new()
     }
 
     fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
         static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
         descriptor.get(|| {
             let mut fields = ::std::vec::Vec::new();
-            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeEnum<Address_AddressType>>(
-                "type",
-                |m: &Address| { &m.field_type },
-                |m: &mut Address| { &mut m.field_type },
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
+                "image",
+                |m: &Avatar| { &m.image },
+                |m: &mut Avatar| { &mut m.image },
             ));
             fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
-                "address",
-                |m: &Address| { &m.address },
-                |m: &mut Address| { &mut m.address },
+                "content_type",
+                |m: &Avatar| { &m.content_type },
+                |m: &mut Avatar| { &mut m.content_type },
             ));
-            ::protobuf::reflect::MessageDescriptor::new_pb_name::<Address>(
-                "Address",
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<Avatar>(
+                "Avatar",
                 fields,
                 file_descriptor_proto()
             )
         })
     }
 
-    fn default_instance() -> &'static Address {
-        static instance: ::protobuf::rt::LazyV2<Address> = ::protobuf::rt::LazyV2::INIT;
-        instance.get(Address::new)
+    fn default_instance() -> &'static Avatar {
+        static instance: ::protobuf::rt::LazyV2<Avatar> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(Avatar::new)
     }
 }
 
-impl ::protobuf::Clear for Address {
+impl ::protobuf::Clear for Avatar {
     fn clear(&mut self) {
-        self.field_type = Address_AddressType::PLAIN;
-        self.address.clear();
+        self.image.clear();
+        self.content_type.clear();
         self.unknown_fields.clear();
     }
 }
 
-impl ::std::fmt::Debug for Address {
+impl ::std::fmt::Debug for Avatar {
     fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
         ::protobuf::text_format::fmt(self, f)
     }
 }
 
-impl ::protobuf::reflect::ProtobufValue for Address {
+impl ::protobuf::reflect::ProtobufValue for Avatar {
     fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
         ::protobuf::reflect::ReflectValueRef::Message(self)
     }
 }
 
-#[derive(Clone,PartialEq,Eq,Debug,Hash)]
-pub enum Address_AddressType {
-    PLAIN = 0,
-    XPUB = 1,
+#[derive(PartialEq,Clone,Default)]
+pub struct DomainResolution {
+    // message fields
+    pub address: ::std::string::String,
+    pub timestamp: u64,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
 }
 
-impl ::protobuf::ProtobufEnum for Address_AddressType {
-    fn value(&self) -> i32 {
-        *self as i32
+impl<'a> ::std::default::Default for &'a DomainResolution {
+    fn default() -> &'a DomainResolution {
+        <DomainResolution as ::protobuf::Message>::default_instance()
     }
+}
 
-    fn from_i32(value: i32) -> ::std::option::Option<Address_AddressType> {
-        match value {
-            0 => ::std::option::Option::Some(Address_AddressType::PLAIN),
-            1 => ::std::option::Option::Some(Address_AddressType::XPUB),
-            _ => ::std::option::Option::None
-        }
+impl DomainResolution {
+    pub fn new() -> DomainResolution {
+        ::std::default::Default::default()
+    }
+
+    // string address = 1;
+
+
+    pub fn get_address(&self) -> &str {
+        &self.address
+    }
+    pub fn clear_address(&mut self) {
+        self.address.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_address(&mut self, v: ::std::string::String) {
+        self.address = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_address(&mut self) -> &mut ::std::string::String {
+        &mut self.address
+    }
+
+    // Take field
+    pub fn take_address(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.address, ::std::string::String::new())
+    }
+
+    // uint64 timestamp = 2;
+
+
+    pub fn get_timestamp(&self) -> u64 {
+        self.timestamp
+    }
+    pub fn clear_timestamp(&mut self) {
+        self.timestamp = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_timestamp(&mut self, v: u64) {
+        self.timestamp = v;
+    }
+}
+
+impl ::protobuf::Message for DomainResolution {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.address)?;
+                },
+                2 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.timestamp = tmp;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.address.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.address);
+        }
+        if self.timestamp != 0 {
+            my_size += ::protobuf::rt::value_size(2, self.timestamp, ::protobuf::wire_format::WireTypeVarint);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.address.is_empty() {
+            os.write_string(1, &self.address)?;
+        }
+        if self.timestamp != 0 {
+            os.write_uint64(2, self.timestamp)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> DomainResolution {
+        DomainResolution::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "address",
+                |m: &DomainResolution| { &m.address },
+                |m: &mut DomainResolution| { &mut m.address },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                "timestamp",
+                |m: &DomainResolution| { &m.timestamp },
+                |m: &mut DomainResolution| { &mut m.timestamp },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<DomainResolution>(
+                "DomainResolution",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static DomainResolution {
+        static instance: ::protobuf::rt::LazyV2<DomainResolution> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(DomainResolution::new)
+    }
+}
+
+impl ::protobuf::Clear for DomainResolution {
+    fn clear(&mut self) {
+        self.address.clear();
+        self.timestamp = 0;
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for DomainResolution {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for DomainResolution {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct LocalizedLabel {
+    // message fields
+    pub locale: ::std::string::String,
+    pub text: ::std::string::String,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a LocalizedLabel {
+    fn default() -> &'a LocalizedLabel {
+        <LocalizedLabel as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl LocalizedLabel {
+    pub fn new() -> LocalizedLabel {
+        ::std::default::Default::default()
+    }
+
+    // string locale = 1;
+
+
+    pub fn get_locale(&self) -> &str {
+        &self.locale
+    }
+    pub fn clear_locale(&mut self) {
+        self.locale.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_locale(&mut self, v: ::std::string::String) {
+        self.locale = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_locale(&mut self) -> &mut ::std::string::String {
+        &mut self.locale
+    }
+
+    // Take field
+    pub fn take_locale(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.locale, ::std::string::String::new())
+    }
+
+    // string text = 2;
+
+
+    pub fn get_text(&self) -> &str {
+        &self.text
+    }
+    pub fn clear_text(&mut self) {
+        self.text.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_text(&mut self, v: ::std::string::String) {
+        self.text = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_text(&mut self) -> &mut ::std::string::String {
+        &mut self.text
+    }
+
+    // Take field
+    pub fn take_text(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.text, ::std::string::String::new())
+    }
+}
+
+impl ::protobuf::Message for LocalizedLabel {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.locale)?;
+                },
+                2 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.text)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.locale.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.locale);
+        }
+        if !self.text.is_empty() {
+            my_size += ::protobuf::rt::string_size(2, &self.text);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.locale.is_empty() {
+            os.write_string(1, &self.locale)?;
+        }
+        if !self.text.is_empty() {
+            os.write_string(2, &self.text)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> LocalizedLabel {
+        LocalizedLabel::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "locale",
+                |m: &LocalizedLabel| { &m.locale },
+                |m: &mut LocalizedLabel| { &mut m.locale },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "text",
+                |m: &LocalizedLabel| { &m.text },
+                |m: &mut LocalizedLabel| { &mut m.text },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<LocalizedLabel>(
+                "LocalizedLabel",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static LocalizedLabel {
+        static instance: ::protobuf::rt::LazyV2<LocalizedLabel> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(LocalizedLabel::new)
+    }
+}
+
+impl ::protobuf::Clear for LocalizedLabel {
+    fn clear(&mut self) {
+        self.locale.clear();
+        self.text.clear();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for LocalizedLabel {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for LocalizedLabel {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct Address {
+    // message fields
+    pub field_type: Address_AddressType,
+    pub address: ::std::string::String,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a Address {
+    fn default() -> &'a Address {
+        <Address as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl Address {
+    pub fn new() -> Address {
+        ::std::default::Default::default()
+    }
+
+    // .emerald.state.Address.AddressType type = 1;
+
+
+    pub fn get_field_type(&self) -> Address_AddressType {
+        self.field_type
+    }
+    pub fn clear_field_type(&mut self) {
+        self.field_type = Address_AddressType::PLAIN;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_field_type(&mut self, v: Address_AddressType) {
+        self.field_type = v;
+    }
+
+    // string address = 2;
+
+
+    pub fn get_address(&self) -> &str {
+        &self.address
+    }
+    pub fn clear_address(&mut self) {
+        self.address.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_address(&mut self, v: ::std::string::String) {
+        self.address = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_address(&mut self) -> &mut ::std::string::String {
+        &mut self.address
+    }
+
+    // Take field
+    pub fn take_address(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.address, ::std::string::String::new())
+    }
+}
+
+impl ::protobuf::Message for Address {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_proto3_enum_with_unknown_fields_into(wire_type, is, &mut self.field_type, 1, &mut self.unknown_fields)?
+                },
+                2 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.address)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if self.field_type != Address_AddressType::PLAIN {
+            my_size += ::protobuf::rt::enum_size(1, self.field_type);
+        }
+        if !self.address.is_empty() {
+            my_size += ::protobuf::rt::string_size(2, &self.address);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if self.field_type != Address_AddressType::PLAIN {
+            os.write_enum(1, ::protobuf::ProtobufEnum::value(&self.field_type))?;
+        }
+        if !self.address.is_empty() {
+            os.write_string(2, &self.address)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> Address {
+        Address::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeEnum<Address_AddressType>>(
+                "type",
+                |m: &Address| { &m.field_type },
+                |m: &mut Address| { &mut m.field_type },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "address",
+                |m: &Address| { &m.address },
+                |m: &mut Address| { &mut m.address },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<Address>(
+                "Address",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static Address {
+        static instance: ::protobuf::rt::LazyV2<Address> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(Address::new)
+    }
+}
+
+impl ::protobuf::Clear for Address {
+    fn clear(&mut self) {
+        self.field_type = Address_AddressType::PLAIN;
+        self.address.clear();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for Address {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for Address {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(Clone,PartialEq,Eq,Debug,Hash)]
+pub enum Address_AddressType {
+    PLAIN = 0,
+    XPUB = 1,
+}
+
+impl ::protobuf::ProtobufEnum for Address_AddressType {
+    fn value(&self) -> i32 {
+        *self as i32
+    }
+
+    fn from_i32(value: i32) -> ::std::option::Option<Address_AddressType> {
+        match value {
+            0 => ::std::option::Option::Some(Address_AddressType::PLAIN),
+            1 => ::std::option::Option::Some(Address_AddressType::XPUB),
+            _ => ::std::option::Option::None
+        }
+    }
+
+    fn values() -> &'static [Self] {
+        static values: &'static [Address_AddressType] = &[
+            Address_AddressType::PLAIN,
+            Address_AddressType::XPUB,
+        ];
+        values
+    }
+
+    fn enum_descriptor_static() -> &'static ::protobuf::reflect::EnumDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::EnumDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            ::protobuf::reflect::EnumDescriptor::new_pb_name::<Address_AddressType>("Address.AddressType", file_descriptor_proto())
+        })
+    }
+}
+
+impl ::std::marker::Copy for Address_AddressType {
+}
+
+impl ::std::default::Default for Address_AddressType {
+    fn default() -> Self {
+        Address_AddressType::PLAIN
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for Address_AddressType {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Enum(::protobuf::ProtobufEnum::descriptor(self))
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct BookItemUsage {
+    // message fields
+    pub sends: u32,
+    pub volumes: ::protobuf::RepeatedField<AssetVolume>,
+    pub last_tx_id: ::std::string::String,
+    pub last_timestamp: u64,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a BookItemUsage {
+    fn default() -> &'a BookItemUsage {
+        <BookItemUsage as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl BookItemUsage {
+    pub fn new() -> BookItemUsage {
+        ::std::default::Default::default()
+    }
+
+    // uint32 sends = 1;
+
+
+    pub fn get_sends(&self) -> u32 {
+        self.sends
+    }
+    pub fn clear_sends(&mut self) {
+        self.sends = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_sends(&mut self, v: u32) {
+        self.sends = v;
+    }
+
+    // repeated .emerald.state.AssetVolume volumes = 2;
+
+
+    pub fn get_volumes(&self) -> &[AssetVolume] {
+        &self.volumes
+    }
+    pub fn clear_volumes(&mut self) {
+        self.volumes.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_volumes(&mut self, v: ::protobuf::RepeatedField<AssetVolume>) {
+        self.volumes = v;
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_volumes(&mut self) -> &mut ::protobuf::RepeatedField<AssetVolume> {
+        &mut self.volumes
+    }
+
+    // Take field
+    pub fn take_volumes(&mut self) -> ::protobuf::RepeatedField<AssetVolume> {
+        ::std::mem::replace(&mut self.volumes, ::protobuf::RepeatedField::new())
+    }
+
+    // string last_tx_id = 3;
+
+
+    pub fn get_last_tx_id(&self) -> &str {
+        &self.last_tx_id
+    }
+    pub fn clear_last_tx_id(&mut self) {
+        self.last_tx_id.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_last_tx_id(&mut self, v: ::std::string::String) {
+        self.last_tx_id = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_last_tx_id(&mut self) -> &mut ::std::string::String {
+        &mut self.last_tx_id
+    }
+
+    // Take field
+    pub fn take_last_tx_id(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.last_tx_id, ::std::string::String::new())
+    }
+
+    // uint64 last_timestamp = 4;
+
+
+    pub fn get_last_timestamp(&self) -> u64 {
+        self.last_timestamp
+    }
+    pub fn clear_last_timestamp(&mut self) {
+        self.last_timestamp = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_last_timestamp(&mut self, v: u64) {
+        self.last_timestamp = v;
+    }
+}
+
+impl ::protobuf::Message for BookItemUsage {
+    fn is_initialized(&self) -> bool {
+        for v in &self.volumes {
+            if !v.is_initialized() {
+                return false;
+            }
+        };
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint32()?;
+                    self.sends = tmp;
+                },
+                2 => {
+                    ::protobuf::rt::read_repeated_message_into(wire_type, is, &mut self.volumes)?;
+                },
+                3 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.last_tx_id)?;
+                },
+                4 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.last_timestamp = tmp;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if self.sends != 0 {
+            my_size += ::protobuf::rt::value_size(1, self.sends, ::protobuf::wire_format::WireTypeVarint);
+        }
+        for value in &self.volumes {
+            let len = value.compute_size();
+            my_size += 1 + ::protobuf::rt::compute_raw_varint32_size(len) + len;
+        };
+        if !self.last_tx_id.is_empty() {
+            my_size += ::protobuf::rt::string_size(3, &self.last_tx_id);
+        }
+        if self.last_timestamp != 0 {
+            my_size += ::protobuf::rt::value_size(4, self.last_timestamp, ::protobuf::wire_format::WireTypeVarint);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if self.sends != 0 {
+            os.write_uint32(1, self.sends)?;
+        }
+        for v in &self.volumes {
+            os.write_tag(2, ::protobuf::wire_format::WireTypeLengthDelimited)?;
+            os.write_raw_varint32(v.get_cached_size())?;
+            v.write_to_with_cached_sizes(os)?;
+        };
+        if !self.last_tx_id.is_empty() {
+            os.write_string(3, &self.last_tx_id)?;
+        }
+        if self.last_timestamp != 0 {
+            os.write_uint64(4, self.last_timestamp)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> BookItemUsage {
+        BookItemUsage::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint32>(
+                "sends",
+                |m: &BookItemUsage| { &m.sends },
+                |m: &mut BookItemUsage| { &mut m.sends },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_repeated_field_accessor::<_, ::protobuf::types::ProtobufTypeMessage<AssetVolume>>(
+                "volumes",
+                |m: &BookItemUsage| { &m.volumes },
+                |m: &mut BookItemUsage| { &mut m.volumes },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "last_tx_id",
+                |m: &BookItemUsage| { &m.last_tx_id },
+                |m: &mut BookItemUsage| { &mut m.last_tx_id },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                "last_timestamp",
+                |m: &BookItemUsage| { &m.last_timestamp },
+                |m: &mut BookItemUsage| { &mut m.last_timestamp },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<BookItemUsage>(
+                "BookItemUsage",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static BookItemUsage {
+        static instance: ::protobuf::rt::LazyV2<BookItemUsage> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(BookItemUsage::new)
+    }
+}
+
+impl ::protobuf::Clear for BookItemUsage {
+    fn clear(&mut self) {
+        self.sends = 0;
+        self.volumes.clear();
+        self.last_tx_id.clear();
+        self.last_timestamp = 0;
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for BookItemUsage {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for BookItemUsage {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct AssetVolume {
+    // message fields
+    pub asset: ::std::string::String,
+    pub amount: ::std::string::String,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a AssetVolume {
+    fn default() -> &'a AssetVolume {
+        <AssetVolume as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl AssetVolume {
+    pub fn new() -> AssetVolume {
+        ::std::default::Default::default()
+    }
+
+    // string asset = 1;
+
+
+    pub fn get_asset(&self) -> &str {
+        &self.asset
+    }
+    pub fn clear_asset(&mut self) {
+        self.asset.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_asset(&mut self, v: ::std::string::String) {
+        self.asset = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_asset(&mut self) -> &mut ::std::string::String {
+        &mut self.asset
+    }
+
+    // Take field
+    pub fn take_asset(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.asset, ::std::string::String::new())
+    }
+
+    // string amount = 2;
+
+
+    pub fn get_amount(&self) -> &str {
+        &self.amount
+    }
+    pub fn clear_amount(&mut self) {
+        self.amount.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_amount(&mut self, v: ::std::string::String) {
+        self.amount = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_amount(&mut self) -> &mut ::std::string::String {
+        &mut self.amount
+    }
+
+    // Take field
+    pub fn take_amount(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.amount, ::std::string::String::new())
+    }
+}
+
+impl ::protobuf::Message for AssetVolume {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.asset)?;
+                },
+                2 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.amount)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.asset.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.asset);
+        }
+        if !self.amount.is_empty() {
+            my_size += ::protobuf::rt::string_size(2, &self.amount);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.asset.is_empty() {
+            os.write_string(1, &self.asset)?;
+        }
+        if !self.amount.is_empty() {
+            os.write_string(2, &self.amount)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> AssetVolume {
+        AssetVolume::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "asset",
+                |m: &AssetVolume| { &m.asset },
+                |m: &mut AssetVolume| { &mut m.asset },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "amount",
+                |m: &AssetVolume| { &m.amount },
+                |m: &mut AssetVolume| { &mut m.amount },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<AssetVolume>(
+                "AssetVolume",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static AssetVolume {
+        static instance: ::protobuf::rt::LazyV2<AssetVolume> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(AssetVolume::new)
+    }
+}
+
+impl ::protobuf::Clear for AssetVolume {
+    fn clear(&mut self) {
+        self.asset.clear();
+        self.amount.clear();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for AssetVolume {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for AssetVolume {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct BookItemBundle {
+    // message fields
+    pub items: ::protobuf::RepeatedField<BookItem>,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a BookItemBundle {
+    fn default() -> &'a BookItemBundle {
+        <BookItemBundle as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl BookItemBundle {
+    pub fn new() -> BookItemBundle {
+        ::std::default::Default::default()
+    }
+
+    // repeated .emerald.state.BookItem items = 1;
+
+
+    pub fn get_items(&self) -> &[BookItem] {
+        &self.items
+    }
+    pub fn clear_items(&mut self) {
+        self.items.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_items(&mut self, v: ::protobuf::RepeatedField<BookItem>) {
+        self.items = v;
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_items(&mut self) -> &mut ::protobuf::RepeatedField<BookItem> {
+        &mut self.items
+    }
+
+    // Take field
+    pub fn take_items(&mut self) -> ::protobuf::RepeatedField<BookItem> {
+        ::std::mem::replace(&mut self.items, ::protobuf::RepeatedField::new())
+    }
+}
+
+impl ::protobuf::Message for BookItemBundle {
+    fn is_initialized(&self) -> bool {
+        for v in &self.items {
+            if !v.is_initialized() {
+                return false;
+            }
+        };
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_repeated_message_into(wire_type, is, &mut self.items)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        for value in &self.items {
+            let len = value.compute_size();
+            my_size += 1 + ::protobuf::rt::compute_raw_varint32_size(len) + len;
+        };
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        for v in &self.items {
+            os.write_tag(1, ::protobuf::wire_format::WireTypeLengthDelimited)?;
+            os.write_raw_varint32(v.get_cached_size())?;
+            v.write_to_with_cached_sizes(os)?;
+        };
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> BookItemBundle {
+        BookItemBundle::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_repeated_field_accessor::<_, ::protobuf::types::ProtobufTypeMessage<BookItem>>(
+                "items",
+                |m: &BookItemBundle| { &m.items },
+                |m: &mut BookItemBundle| { &mut m.items },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<BookItemBundle>(
+                "BookItemBundle",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static BookItemBundle {
+        static instance: ::protobuf::rt::LazyV2<BookItemBundle> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(BookItemBundle::new)
+    }
+}
+
+impl ::protobuf::Clear for BookItemBundle {
+    fn clear(&mut self) {
+        self.items.clear();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for BookItemBundle {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for BookItemBundle {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(Clone,PartialEq,Eq,Debug,Hash)]
+pub enum NetworkKind {
+    NETWORK_UNSPECIFIED = 0,
+    NETWORK_MAINNET = 1,
+    NETWORK_TESTNET = 2,
+}
+
+impl ::protobuf::ProtobufEnum for NetworkKind {
+    fn value(&self) -> i32 {
+        *self as i32
+    }
+
+    fn from_i32(value: i32) -> ::std::option::Option<NetworkKind> {
+        match value {
+            0 => ::std::option::Option::Some(NetworkKind::NETWORK_UNSPECIFIED),
+            1 => ::std::option::Option::Some(NetworkKind::NETWORK_MAINNET),
+            2 => ::std::option::Option::Some(NetworkKind::NETWORK_TESTNET),
+            _ => ::std::option::Option::None
+        }
     }
 
     fn values() -> &'static [Self] {
-        static values: &'static [Address_AddressType] = &[
-            Address_AddressType::PLAIN,
-            Address_AddressType::XPUB,
+        static values: &'static [NetworkKind] = &[
+            NetworkKind::NETWORK_UNSPECIFIED,
+            NetworkKind::NETWORK_MAINNET,
+            NetworkKind::NETWORK_TESTNET,
         ];
         values
     }
@@ -606,68 +2255,61 @@ impl ::protobuf::ProtobufEnum for Address_AddressType {
     fn enum_descriptor_static() -> &'static ::protobuf::reflect::EnumDescriptor {
         static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::EnumDescriptor> = ::protobuf::rt::LazyV2::INIT;
         descriptor.get(|| {
-            ::protobuf::reflect::EnumDescriptor::new_pb_name::<Address_AddressType>("Address.AddressType", file_descriptor_proto())
+            ::protobuf::reflect::EnumDescriptor::new_pb_name::<NetworkKind>("NetworkKind", file_descriptor_proto())
         })
     }
 }
 
-impl ::std::marker::Copy for Address_AddressType {
+impl ::std::marker::Copy for NetworkKind {
 }
 
-impl ::std::default::Default for Address_AddressType {
+impl ::std::default::Default for NetworkKind {
     fn default() -> Self {
-        Address_AddressType::PLAIN
+        NetworkKind::NETWORK_UNSPECIFIED
     }
 }
 
-impl ::protobuf::reflect::ProtobufValue for Address_AddressType {
+impl ::protobuf::reflect::ProtobufValue for NetworkKind {
     fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
         ::protobuf::reflect::ReflectValueRef::Enum(::protobuf::ProtobufEnum::descriptor(self))
     }
 }
 
 static file_descriptor_proto_data: &'static [u8] = b"\
-    \n\x11addressbook.proto\x12\remerald.state\"\xd8\x01\n\x08BookItem\x12\
-    \x0e\n\x02id\x18\x01\x20\x01(\tR\x02id\x120\n\x07address\x18\x02\x20\x01\
-    (\x0b2\x16.emerald.state.AddressR\x07address\x12\x14\n\x05label\x18\x03\
-    \x20\x01(\tR\x05label\x12\x1e\n\nblockchain\x18\x05\x20\x01(\rR\nblockch\
-    ain\x12)\n\x10create_timestamp\x18\x06\x20\x01(\x04R\x0fcreateTimestamp\
-    \x12)\n\x10update_timestamp\x18\x07\x20\x01(\x04R\x0fupdateTimestamp\"\
-    \x7f\n\x07Address\x126\n\x04type\x18\x01\x20\x01(\x0e2\".emerald.state.A\
-    ddress.AddressTypeR\x04type\x12\x18\n\x07address\x18\x02\x20\x01(\tR\x07\
-    address\"\"\n\x0bAddressType\x12\t\n\x05PLAIN\x10\0\x12\x08\n\x04XPUB\
-    \x10\x01J\xfe\x04\n\x06\x12\x04\0\0\x14\x01\n\x08\n\x01\x0c\x12\x03\0\0\
-    \x12\n\x08\n\x01\x02\x12\x03\x01\0\x16\n\n\n\x02\x04\0\x12\x04\x03\0\n\
-    \x01\n\n\n\x03\x04\0\x01\x12\x03\x03\x08\x10\n\x0b\n\x04\x04\0\x02\0\x12\
-    \x03\x04\x02\x10\n\x0c\n\x05\x04\0\x02\0\x05\x12\x03\x04\x02\x08\n\x0c\n\
-    \x05\x04\0\x02\0\x01\x12\x03\x04\t\x0b\n\x0c\n\x05\x04\0\x02\0\x03\x12\
-    \x03\x04\x0e\x0f\n\x0b\n\x04\x04\0\x02\x01\x12\x03\x05\x02\x16\n\x0c\n\
-    \x05\x04\0\x02\x01\x06\x12\x03\x05\x02\t\n\x0c\n\x05\x04\0\x02\x01\x01\
-    \x12\x03\x05\n\x11\n\x0c\n\x05\x04\0\x02\x01\x03\x12\x03\x05\x14\x15\n\
-    \x0b\n\x04\x04\0\x02\x02\x12\x03\x06\x02\x13\n\x0c\n\x05\x04\0\x02\x02\
-    \x05\x12\x03\x06\x02\x08\n\x0c\n\x05\x04\0\x02\x02\x01\x12\x03\x06\t\x0e\
-    \n\x0c\n\x05\x04\0\x02\x02\x03\x12\x03\x06\x11\x12\n\x0b\n\x04\x04\0\x02\
-    \x03\x12\x03\x07\x02\x18\n\x0c\n\x05\x04\0\x02\x03\x05\x12\x03\x07\x02\
-    \x08\n\x0c\n\x05\x04\0\x02\x03\x01\x12\x03\x07\t\x13\n\x0c\n\x05\x04\0\
-    \x02\x03\x03\x12\x03\x07\x16\x17\n\x0b\n\x04\x04\0\x02\x04\x12\x03\x08\
-    \x02\x1e\n\x0c\n\x05\x04\0\x02\x04\x05\x12\x03\x08\x02\x08\n\x0c\n\x05\
-    \x04\0\x02\x04\x01\x12\x03\x08\t\x19\n\x0c\n\x05\x04\0\x02\x04\x03\x12\
-    \x03\x08\x1c\x1d\n\x0b\n\x04\x04\0\x02\x05\x12\x03\t\x02\x1e\n\x0c\n\x05\
-    \x04\0\x02\x05\x05\x12\x03\t\x02\x08\n\x0c\n\x05\x04\0\x02\x05\x01\x12\
-    \x03\t\t\x19\n\x0c\n\x05\x04\0\x02\x05\x03\x12\x03\t\x1c\x1d\n\n\n\x02\
-    \x04\x01\x12\x04\x0c\0\x14\x01\n\n\n\x03\x04\x01\x01\x12\x03\x0c\x08\x0f\
-    \n\x0b\n\x04\x04\x01\x02\0\x12\x03\r\x02\x17\n\x0c\n\x05\x04\x01\x02\0\
-    \x06\x12\x03\r\x02\r\n\x0c\n\x05\x04\x01\x02\0\x01\x12\x03\r\x0e\x12\n\
-    \x0c\n\x05\x04\x01\x02\0\x03\x12\x03\r\x15\x16\n\x0b\n\x04\x04\x01\x02\
-    \x01\x12\x03\x0e\x02\x15\n\x0c\n\x05\x04\x01\x02\x01\x05\x12\x03\x0e\x02\
-    \x08\n\x0c\n\x05\x04\x01\x02\x01\x01\x12\x03\x0e\t\x10\n\x0c\n\x05\x04\
-    \x01\x02\x01\x03\x12\x03\x0e\x13\x14\n\x0c\n\x04\x04\x01\x04\0\x12\x04\
-    \x10\x02\x13\x03\n\x0c\n\x05\x04\x01\x04\0\x01\x12\x03\x10\x07\x12\n\r\n\
-    \x06\x04\x01\x04\0\x02\0\x12\x03\x11\x04\x0e\n\x0e\n\x07\x04\x01\x04\0\
-    \x02\0\x01\x12\x03\x11\x04\t\n\x0e\n\x07\x04\x01\x04\0\x02\0\x02\x12\x03\
-    \x11\x0c\r\n\r\n\x06\x04\x01\x04\0\x02\x01\x12\x03\x12\x04\r\n\x0e\n\x07\
-    \x04\x01\x04\0\x02\x01\x01\x12\x03\x12\x04\x08\n\x0e\n\x07\x04\x01\x04\0\
-    \x02\x01\x02\x12\x03\x12\x0b\x0cb\x06proto3\
+    \n\x11addressbook.proto\x12\remerald.state\"\xe8\x04\n\x08BookItem\x12\
+    \x10\n\x02id\x18\x01\x20\x01(\tR\x02idB\0\x122\n\x07address\x18\x02\x20\
+    \x01(\x0b2\x16.emerald.state.AddressR\x07addressB\0\x12\x16\n\x05label\
+    \x18\x03\x20\x01(\tR\x05labelB\0\x12\x20\n\nblockchain\x18\x05\x20\x01(\
+    \rR\nblockchainB\0\x12+\n\x10create_timestamp\x18\x06\x20\x01(\x04R\x0fc\
+    reateTimestampB\0\x12+\n\x10update_timestamp\x18\x07\x20\x01(\x04R\x0fup\
+    dateTimestampB\0\x12J\n\x10localized_labels\x18\x08\x20\x03(\x0b2\x1d.em\
+    erald.state.LocalizedLabelR\x0flocalizedLabelsB\0\x126\n\x07network\x18\
+    \t\x20\x01(\x0e2\x1a.emerald.state.NetworkKindR\x07networkB\0\x12\x1c\n\
+    \x08archived\x18\n\x20\x01(\x08R\x08archivedB\0\x12\x16\n\x05group\x18\
+    \x0b\x20\x01(\tR\x05groupB\0\x12!\n\x0bdomain_name\x18\x0c\x20\x01(\tR\n\
+    domainNameB\0\x12N\n\x11domain_resolution\x18\r\x20\x01(\x0b2\x1f.emeral\
+    d.state.DomainResolutionR\x10domainResolutionB\0\x12/\n\x06avatar\x18\
+    \x0e\x20\x01(\x0b2\x15.emerald.state.AvatarR\x06avatarB\0\x12\"\n\x0bdes\
+    cription\x18\x0f\x20\x01(\tR\x0bdescriptionB\0:\0\"G\n\x06Avatar\x12\x16\
+    \n\x05image\x18\x01\x20\x01(\x0cR\x05imageB\0\x12#\n\x0ccontent_type\x18\
+    \x02\x20\x01(\tR\x0bcontentTypeB\0:\0\"P\n\x10DomainResolution\x12\x1a\n\
+    \x07address\x18\x01\x20\x01(\tR\x07addressB\0\x12\x1e\n\ttimestamp\x18\
+    \x02\x20\x01(\x04R\ttimestampB\0:\0\"B\n\x0eLocalizedLabel\x12\x18\n\x06\
+    locale\x18\x01\x20\x01(\tR\x06localeB\0\x12\x14\n\x04text\x18\x02\x20\
+    \x01(\tR\x04textB\0:\0\"\x87\x01\n\x07Address\x128\n\x04type\x18\x01\x20\
+    \x01(\x0e2\".emerald.state.Address.AddressTypeR\x04typeB\0\x12\x1a\n\x07\
+    address\x18\x02\x20\x01(\tR\x07addressB\0\"$\n\x0bAddressType\x12\t\n\
+    \x05PLAIN\x10\0\x12\x08\n\x04XPUB\x10\x01\x1a\0:\0\"\xaa\x01\n\rBookItem\
+    Usage\x12\x16\n\x05sends\x18\x01\x20\x01(\rR\x05sendsB\0\x126\n\x07volum\
+    es\x18\x02\x20\x03(\x0b2\x1a.emerald.state.AssetVolumeR\x07volumesB\0\
+    \x12\x1e\n\nlast_tx_id\x18\x03\x20\x01(\tR\x08lastTxIdB\0\x12'\n\x0elast\
+    _timestamp\x18\x04\x20\x01(\x04R\rlastTimestampB\0:\0\"A\n\x0bAssetVolum\
+    e\x12\x16\n\x05asset\x18\x01\x20\x01(\tR\x05assetB\0\x12\x18\n\x06amount\
+    \x18\x02\x20\x01(\tR\x06amountB\0:\0\"C\n\x0eBookItemBundle\x12/\n\x05it\
+    ems\x18\x01\x20\x03(\x0b2\x17.emerald.state.BookItemR\x05itemsB\0:\0*R\n\
+    \x0bNetworkKind\x12\x17\n\x13NETWORK_UNSPECIFIED\x10\0\x12\x13\n\x0fNETW\
+    ORK_MAINNET\x10\x01\x12\x13\n\x0fNETWORK_TESTNET\x10\x02\x1a\0B\0b\x06pr\
+    oto3\
 ";
 
 static file_descriptor_proto_lazy: ::protobuf::rt::LazyV2<::protobuf::descriptor::FileDescriptorProto> = ::protobuf::rt::LazyV2::INIT;
@@ -1,4 +1,4 @@
-// This file is generated by rust-protobuf 2.25.2. Do not edit
+// This file is generated by rust-protobuf 2.28.0. Do not edit
 // @generated
 
 // https://github.com/rust-lang/rust-clippy/issues/702
@@ -21,7 +21,7 @@
 
 /// Generated files are compatible only with the same version
 /// of protobuf runtime.
-// const _PROTOBUF_VERSION_CHECK: () = ::protobuf::VERSION_2_25_2;
+// const _PROTOBUF_VERSION_CHECK: () = ::protobuf::VERSION_2_28_0;
 
 #[derive(PartialEq,Clone,Default)]
 pub struct Balance {
@@ -791,6 +791,10 @@ pub struct Allowance {
     pub owner: ::std::string::String,
     pub spender: ::std::string::String,
     pub amount: ::std::string::String,
+    pub revoked_by_tx: ::std::string::String,
+    pub last_checked_block: u64,
+    pub token_symbol: ::std::string::String,
+    pub token_decimals: u32,
     // special fields
     pub unknown_fields: ::protobuf::UnknownFields,
     pub cached_size: ::protobuf::CachedSize,
@@ -981,6 +985,88 @@ impl Allowance {
     pub fn take_amount(&mut self) -> ::std::string::String {
         ::std::mem::replace(&mut self.amount, ::std::string::String::new())
     }
+
+    // string revoked_by_tx = 9;
+
+
+    pub fn get_revoked_by_tx(&self) -> &str {
+        &self.revoked_by_tx
+    }
+    pub fn clear_revoked_by_tx(&mut self) {
+        self.revoked_by_tx.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_revoked_by_tx(&mut self, v: ::std::string::String) {
+        self.revoked_by_tx = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_revoked_by_tx(&mut self) -> &mut ::std::string::String {
+        &mut self.revoked_by_tx
+    }
+
+    // Take field
+    pub fn take_revoked_by_tx(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.revoked_by_tx, ::std::string::String::new())
+    }
+
+    // uint64 last_checked_block = 10;
+
+
+    pub fn get_last_checked_block(&self) -> u64 {
+        self.last_checked_block
+    }
+    pub fn clear_last_checked_block(&mut self) {
+        self.last_checked_block = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_last_checked_block(&mut self, v: u64) {
+        self.last_checked_block = v;
+    }
+
+    // string token_symbol = 11;
+
+
+    pub fn get_token_symbol(&self) -> &str {
+        &self.token_symbol
+    }
+    pub fn clear_token_symbol(&mut self) {
+        self.token_symbol.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_token_symbol(&mut self, v: ::std::string::String) {
+        self.token_symbol = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_token_symbol(&mut self) -> &mut ::std::string::String {
+        &mut self.token_symbol
+    }
+
+    // Take field
+    pub fn take_token_symbol(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.token_symbol, ::std::string::String::new())
+    }
+
+    // uint32 token_decimals = 12;
+
+
+    pub fn get_token_decimals(&self) -> u32 {
+        self.token_decimals
+    }
+    pub fn clear_token_decimals(&mut self) {
+        self.token_decimals = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_token_decimals(&mut self, v: u32) {
+        self.token_decimals = v;
+    }
 }
 
 impl ::protobuf::Message for Allowance {
@@ -1028,6 +1114,26 @@ impl ::protobuf::Message for Allowance {
                 8 => {
                     ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.amount)?;
                 },
+                9 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.revoked_by_tx)?;
+                },
+                10 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.last_checked_block = tmp;
+                },
+                11 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.token_symbol)?;
+                },
+                12 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint32()?;
+                    self.token_decimals = tmp;
+                },
                 _ => {
                     ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
                 },
@@ -1064,6 +1170,18 @@ impl ::protobuf::Message for Allowance {
         if !self.amount.is_empty() {
             my_size += ::protobuf::rt::string_size(8, &self.amount);
         }
+        if !self.revoked_by_tx.is_empty() {
+            my_size += ::protobuf::rt::string_size(9, &self.revoked_by_tx);
+        }
+        if self.last_checked_block != 0 {
+            my_size += ::protobuf::rt::value_size(10, self.last_checked_block, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if !self.token_symbol.is_empty() {
+            my_size += ::protobuf::rt::string_size(11, &self.token_symbol);
+        }
+        if self.token_decimals != 0 {
+            my_size += ::protobuf::rt::value_size(12, self.token_decimals, ::protobuf::wire_format::WireTypeVarint);
+        }
         my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
         self.cached_size.set(my_size);
         my_size
@@ -1094,6 +1212,18 @@ impl ::protobuf::Message for Allowance {
         if !self.amount.is_empty() {
             os.write_string(8, &self.amount)?;
         }
+        if !self.revoked_by_tx.is_empty() {
+            os.write_string(9, &self.revoked_by_tx)?;
+        }
+        if self.last_checked_block != 0 {
+            os.write_uint64(10, self.last_checked_block)?;
+        }
+        if !self.token_symbol.is_empty() {
+            os.write_string(11, &self.token_symbol)?;
+        }
+        if self.token_decimals != 0 {
+            os.write_uint32(12, self.token_decimals)?;
+        }
         os.write_unknown_fields(self.get_unknown_fields())?;
         ::std::result::Result::Ok(())
     }
@@ -1172,6 +1302,26 @@ impl ::protobuf::Message for Allowance {
                 |m: &Allowance| { &m.amount },
                 |m: &mut Allowance| { &mut m.amount },
             ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "revoked_by_tx",
+                |m: &Allowance| { &m.revoked_by_tx },
+                |m: &mut Allowance| { &mut m.revoked_by_tx },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                "last_checked_block",
+                |m: &Allowance| { &m.last_checked_block },
+                |m: &mut Allowance| { &mut m.last_checked_block },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "token_symbol",
+                |m: &Allowance| { &m.token_symbol },
+                |m: &mut Allowance| { &mut m.token_symbol },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint32>(
+                "token_decimals",
+                |m: &Allowance| { &m.token_decimals },
+                |m: &mut Allowance| { &mut m.token_decimals },
+            ));
             ::protobuf::reflect::MessageDescriptor::new_pb_name::<Allowance>(
                 "Allowance",
                 fields,
@@ -1196,6 +1346,10 @@ impl ::protobuf::Clear for Allowance {
         self.owner.clear();
         self.spender.clear();
         self.amount.clear();
+        self.revoked_by_tx.clear();
+        self.last_checked_block = 0;
+        self.token_symbol.clear();
+        self.token_decimals = 0;
         self.unknown_fields.clear();
     }
 }
@@ -1213,83 +1367,26 @@ impl ::protobuf::reflect::ProtobufValue for Allowance {
 }
 
 static file_descriptor_proto_data: &'static [u8] = b"\
-    \n\rbalance.proto\x12\remerald.state\"\xaa\x01\n\x07Balance\x12\x18\n\
-    \x07address\x18\x01\x20\x01(\tR\x07address\x12\x0e\n\x02ts\x18\x02\x20\
-    \x01(\x04R\x02ts\x12\x1e\n\nblockchain\x18\x03\x20\x01(\rR\nblockchain\
-    \x12\x14\n\x05asset\x18\x04\x20\x01(\tR\x05asset\x12\x16\n\x06amount\x18\
-    \x05\x20\x01(\tR\x06amount\x12'\n\x04utxo\x18\x06\x20\x03(\x0b2\x13.emer\
-    ald.state.UtxoR\x04utxo\"C\n\rBalanceBundle\x122\n\x08balances\x18\x01\
-    \x20\x03(\x0b2\x16.emerald.state.BalanceR\x08balances\"F\n\x04Utxo\x12\
-    \x12\n\x04txid\x18\x01\x20\x01(\tR\x04txid\x12\x12\n\x04vout\x18\x02\x20\
-    \x01(\rR\x04vout\x12\x16\n\x06amount\x18\x03\x20\x01(\x04R\x06amount\"\
-    \xc8\x01\n\tAllowance\x12\x0e\n\x02ts\x18\x01\x20\x01(\x04R\x02ts\x12\
-    \x10\n\x03ttl\x18\x02\x20\x01(\x04R\x03ttl\x12\x1b\n\twallet_id\x18\x03\
-    \x20\x01(\tR\x08walletId\x12\x1e\n\nblockchain\x18\x04\x20\x01(\rR\nbloc\
-    kchain\x12\x14\n\x05token\x18\x05\x20\x01(\tR\x05token\x12\x14\n\x05owne\
-    r\x18\x06\x20\x01(\tR\x05owner\x12\x18\n\x07spender\x18\x07\x20\x01(\tR\
-    \x07spender\x12\x16\n\x06amount\x18\x08\x20\x01(\tR\x06amountJ\xbe\n\n\
-    \x06\x12\x04\0\0%\x01\n\x08\n\x01\x0c\x12\x03\0\0\x12\n\x08\n\x01\x02\
-    \x12\x03\x01\0\x16\n\n\n\x02\x04\0\x12\x04\x03\0\x0b\x01\n\n\n\x03\x04\0\
-    \x01\x12\x03\x03\x08\x0f\n\x0b\n\x04\x04\0\x02\0\x12\x03\x04\x02\x15\n\
-    \x0c\n\x05\x04\0\x02\0\x05\x12\x03\x04\x02\x08\n\x0c\n\x05\x04\0\x02\0\
-    \x01\x12\x03\x04\t\x10\n\x0c\n\x05\x04\0\x02\0\x03\x12\x03\x04\x13\x14\n\
-    \x0b\n\x04\x04\0\x02\x01\x12\x03\x05\x02\x10\n\x0c\n\x05\x04\0\x02\x01\
-    \x05\x12\x03\x05\x02\x08\n\x0c\n\x05\x04\0\x02\x01\x01\x12\x03\x05\t\x0b\
-    \n\x0c\n\x05\x04\0\x02\x01\x03\x12\x03\x05\x0e\x0f\n\x0b\n\x04\x04\0\x02\
-    \x02\x12\x03\x06\x02\x18\n\x0c\n\x05\x04\0\x02\x02\x05\x12\x03\x06\x02\
-    \x08\n\x0c\n\x05\x04\0\x02\x02\x01\x12\x03\x06\t\x13\n\x0c\n\x05\x04\0\
-    \x02\x02\x03\x12\x03\x06\x16\x17\n\x0b\n\x04\x04\0\x02\x03\x12\x03\x07\
-    \x02\x13\n\x0c\n\x05\x04\0\x02\x03\x05\x12\x03\x07\x02\x08\n\x0c\n\x05\
-    \x04\0\x02\x03\x01\x12\x03\x07\t\x0e\n\x0c\n\x05\x04\0\x02\x03\x03\x12\
-    \x03\x07\x11\x12\n\x0b\n\x04\x04\0\x02\x04\x12\x03\x08\x02\x14\n\x0c\n\
-    \x05\x04\0\x02\x04\x05\x12\x03\x08\x02\x08\n\x0c\n\x05\x04\0\x02\x04\x01\
-    \x12\x03\x08\t\x0f\n\x0c\n\x05\x04\0\x02\x04\x03\x12\x03\x08\x12\x13\n$\
-    \n\x04\x04\0\x02\x05\x12\x03\n\x02\x19\x1a\x17\x20optional\x20utxo\x20de\
-    tails\n\n\x0c\n\x05\x04\0\x02\x05\x04\x12\x03\n\x02\n\n\x0c\n\x05\x04\0\
-    \x02\x05\x06\x12\x03\n\x0b\x0f\n\x0c\n\x05\x04\0\x02\x05\x01\x12\x03\n\
-    \x10\x14\n\x0c\n\x05\x04\0\x02\x05\x03\x12\x03\n\x17\x18\n\n\n\x02\x04\
-    \x01\x12\x04\r\0\x0f\x01\n\n\n\x03\x04\x01\x01\x12\x03\r\x08\x15\n\x0b\n\
-    \x04\x04\x01\x02\0\x12\x03\x0e\x02\x20\n\x0c\n\x05\x04\x01\x02\0\x04\x12\
-    \x03\x0e\x02\n\n\x0c\n\x05\x04\x01\x02\0\x06\x12\x03\x0e\x0b\x12\n\x0c\n\
-    \x05\x04\x01\x02\0\x01\x12\x03\x0e\x13\x1b\n\x0c\n\x05\x04\x01\x02\0\x03\
-    \x12\x03\x0e\x1e\x1f\n\n\n\x02\x04\x02\x12\x04\x11\0\x16\x01\n\n\n\x03\
-    \x04\x02\x01\x12\x03\x11\x08\x0c\n\x0b\n\x04\x04\x02\x02\0\x12\x03\x12\
-    \x02\x12\n\x0c\n\x05\x04\x02\x02\0\x05\x12\x03\x12\x02\x08\n\x0c\n\x05\
-    \x04\x02\x02\0\x01\x12\x03\x12\t\r\n\x0c\n\x05\x04\x02\x02\0\x03\x12\x03\
-    \x12\x10\x11\n\x0b\n\x04\x04\x02\x02\x01\x12\x03\x13\x02\x12\n\x0c\n\x05\
-    \x04\x02\x02\x01\x05\x12\x03\x13\x02\x08\n\x0c\n\x05\x04\x02\x02\x01\x01\
-    \x12\x03\x13\t\r\n\x0c\n\x05\x04\x02\x02\x01\x03\x12\x03\x13\x10\x11\nE\
-    \n\x04\x04\x02\x02\x02\x12\x03\x15\x02\x14\x1a8\x20we\x20use\x20it\x20on\
-    ly\x20for\x20bitcoin,\x20so\x2064\x20bit\x20number\x20is\x20enough\n\n\
-    \x0c\n\x05\x04\x02\x02\x02\x05\x12\x03\x15\x02\x08\n\x0c\n\x05\x04\x02\
-    \x02\x02\x01\x12\x03\x15\t\x0f\n\x0c\n\x05\x04\x02\x02\x02\x03\x12\x03\
-    \x15\x12\x13\n\n\n\x02\x04\x03\x12\x04\x18\0%\x01\n\n\n\x03\x04\x03\x01\
-    \x12\x03\x18\x08\x11\n\x0b\n\x04\x04\x03\x02\0\x12\x03\x19\x02\x10\n\x0c\
-    \n\x05\x04\x03\x02\0\x05\x12\x03\x19\x02\x08\n\x0c\n\x05\x04\x03\x02\0\
-    \x01\x12\x03\x19\t\x0b\n\x0c\n\x05\x04\x03\x02\0\x03\x12\x03\x19\x0e\x0f\
-    \n\x0b\n\x04\x04\x03\x02\x01\x12\x03\x1a\x02\x11\n\x0c\n\x05\x04\x03\x02\
-    \x01\x05\x12\x03\x1a\x02\x08\n\x0c\n\x05\x04\x03\x02\x01\x01\x12\x03\x1a\
-    \t\x0c\n\x0c\n\x05\x04\x03\x02\x01\x03\x12\x03\x1a\x0f\x10\n\x0b\n\x04\
-    \x04\x03\x02\x02\x12\x03\x1b\x02\x17\n\x0c\n\x05\x04\x03\x02\x02\x05\x12\
-    \x03\x1b\x02\x08\n\x0c\n\x05\x04\x03\x02\x02\x01\x12\x03\x1b\t\x12\n\x0c\
-    \n\x05\x04\x03\x02\x02\x03\x12\x03\x1b\x15\x16\n\x0b\n\x04\x04\x03\x02\
-    \x03\x12\x03\x1c\x02\x18\n\x0c\n\x05\x04\x03\x02\x03\x05\x12\x03\x1c\x02\
-    \x08\n\x0c\n\x05\x04\x03\x02\x03\x01\x12\x03\x1c\t\x13\n\x0c\n\x05\x04\
-    \x03\x02\x03\x03\x12\x03\x1c\x16\x17\n)\n\x04\x04\x03\x02\x04\x12\x03\
-    \x1e\x02\x13\x1a\x1c\x20address\x20of\x20the\x20ERC20\x20token\n\n\x0c\n\
-    \x05\x04\x03\x02\x04\x05\x12\x03\x1e\x02\x08\n\x0c\n\x05\x04\x03\x02\x04\
-    \x01\x12\x03\x1e\t\x0e\n\x0c\n\x05\x04\x03\x02\x04\x03\x12\x03\x1e\x11\
-    \x12\n%\n\x04\x04\x03\x02\x05\x12\x03\x20\x02\x13\x1a\x18\x20who\x20owns\
-    \x20the\x20allowance\n\n\x0c\n\x05\x04\x03\x02\x05\x05\x12\x03\x20\x02\
-    \x08\n\x0c\n\x05\x04\x03\x02\x05\x01\x12\x03\x20\t\x0e\n\x0c\n\x05\x04\
-    \x03\x02\x05\x03\x12\x03\x20\x11\x12\n*\n\x04\x04\x03\x02\x06\x12\x03\"\
-    \x02\x15\x1a\x1d\x20who\x20can\x20spend\x20the\x20allowance\n\n\x0c\n\
-    \x05\x04\x03\x02\x06\x05\x12\x03\"\x02\x08\n\x0c\n\x05\x04\x03\x02\x06\
-    \x01\x12\x03\"\t\x10\n\x0c\n\x05\x04\x03\x02\x06\x03\x12\x03\"\x13\x14\n\
-    )\n\x04\x04\x03\x02\x07\x12\x03$\x02\x14\x1a\x1c\x20amount\x20encoded\
-    \x20as\x20a\x20string\n\n\x0c\n\x05\x04\x03\x02\x07\x05\x12\x03$\x02\x08\
-    \n\x0c\n\x05\x04\x03\x02\x07\x01\x12\x03$\t\x0f\n\x0c\n\x05\x04\x03\x02\
-    \x07\x03\x12\x03$\x12\x13b\x06proto3\
+    \n\rbalance.proto\x12\remerald.state\"\xb8\x01\n\x07Balance\x12\x1a\n\
+    \x07address\x18\x01\x20\x01(\tR\x07addressB\0\x12\x10\n\x02ts\x18\x02\
+    \x20\x01(\x04R\x02tsB\0\x12\x20\n\nblockchain\x18\x03\x20\x01(\rR\nblock\
+    chainB\0\x12\x16\n\x05asset\x18\x04\x20\x01(\tR\x05assetB\0\x12\x18\n\
+    \x06amount\x18\x05\x20\x01(\tR\x06amountB\0\x12)\n\x04utxo\x18\x06\x20\
+    \x03(\x0b2\x13.emerald.state.UtxoR\x04utxoB\0:\0\"G\n\rBalanceBundle\x12\
+    4\n\x08balances\x18\x01\x20\x03(\x0b2\x16.emerald.state.BalanceR\x08bala\
+    ncesB\0:\0\"N\n\x04Utxo\x12\x14\n\x04txid\x18\x01\x20\x01(\tR\x04txidB\0\
+    \x12\x14\n\x04vout\x18\x02\x20\x01(\rR\x04voutB\0\x12\x18\n\x06amount\
+    \x18\x03\x20\x01(\x04R\x06amountB\0:\0\"\xfe\x02\n\tAllowance\x12\x10\n\
+    \x02ts\x18\x01\x20\x01(\x04R\x02tsB\0\x12\x12\n\x03ttl\x18\x02\x20\x01(\
+    \x04R\x03ttlB\0\x12\x1d\n\twallet_id\x18\x03\x20\x01(\tR\x08walletIdB\0\
+    \x12\x20\n\nblockchain\x18\x04\x20\x01(\rR\nblockchainB\0\x12\x16\n\x05t\
+    oken\x18\x05\x20\x01(\tR\x05tokenB\0\x12\x16\n\x05owner\x18\x06\x20\x01(\
+    \tR\x05ownerB\0\x12\x1a\n\x07spender\x18\x07\x20\x01(\tR\x07spenderB\0\
+    \x12\x18\n\x06amount\x18\x08\x20\x01(\tR\x06amountB\0\x12$\n\rrevoked_by\
+    _tx\x18\t\x20\x01(\tR\x0brevokedByTxB\0\x12.\n\x12last_checked_block\x18\
+    \n\x20\x01(\x04R\x10lastCheckedBlockB\0\x12#\n\x0ctoken_symbol\x18\x0b\
+    \x20\x01(\tR\x0btokenSymbolB\0\x12'\n\x0etoken_decimals\x18\x0c\x20\x01(\
+    \rR\rtokenDecimalsB\0:\0B\0b\x06proto3\
 ";
 
 static file_descriptor_proto_lazy: ::protobuf::rt::LazyV2<::protobuf::descriptor::FileDescriptorProto> = ::protobuf::rt::LazyV2::INIT;
@@ -0,0 +1,496 @@
+// This file is generated by rust-protobuf 2.28.0. Do not edit
+// @generated
+
+// https://github.com/rust-lang/rust-clippy/issues/702
+#![allow(unknown_lints)]
+#![allow(clippy::all)]
+
+#![allow(unused_attributes)]
+#![cfg_attr(rustfmt, rustfmt::skip)]
+
+#![allow(box_pointers)]
+#![allow(dead_code)]
+#![allow(missing_docs)]
+#![allow(non_camel_case_types)]
+#![allow(non_snake_case)]
+#![allow(non_upper_case_globals)]
+#![allow(trivial_casts)]
+#![allow(unused_imports)]
+#![allow(unused_results)]
+//! Generated file from `flags.proto`
+
+/// Generated files are compatible only with the same version
+/// of protobuf runtime.
+// const _PROTOBUF_VERSION_CHECK: () = ::protobuf::VERSION_2_28_0;
+
+#[derive(PartialEq,Clone,Default)]
+pub struct FeatureFlag {
+    // message fields
+    pub key: ::std::string::String,
+    pub set_by: ::std::string::String,
+    pub updated_at: u64,
+    // message oneof groups
+    pub value: ::std::option::Option<FeatureFlag_oneof_value>,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a FeatureFlag {
+    fn default() -> &'a FeatureFlag {
+        <FeatureFlag as ::protobuf::Message>::default_instance()
+    }
+}
+
+#[derive(Clone,PartialEq,Debug)]
+pub enum FeatureFlag_oneof_value {
+    bool_value(bool),
+    int_value(i64),
+    float_value(f64),
+    text_value(::std::string::String),
+}
+
+impl FeatureFlag {
+    pub fn new() -> FeatureFlag {
+        ::std::default::Default::default()
+    }
+
+    // string key = 1;
+
+
+    pub fn get_key(&self) -> &str {
+        &self.key
+    }
+    pub fn clear_key(&mut self) {
+        self.key.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_key(&mut self, v: ::std::string::String) {
+        self.key = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_key(&mut self) -> &mut ::std::string::String {
+        &mut self.key
+    }
+
+    // Take field
+    pub fn take_key(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.key, ::std::string::String::new())
+    }
+
+    // bool bool_value = 2;
+
+
+    pub fn get_bool_value(&self) -> bool {
+        match self.value {
+            ::std::option::Option::Some(FeatureFlag_oneof_value::bool_value(v)) => v,
+            _ => false,
+        }
+    }
+    pub fn clear_bool_value(&mut self) {
+        self.value = ::std::option::Option::None;
+    }
+
+    pub fn has_bool_value(&self) -> bool {
+        match self.value {
+            ::std::option::Option::Some(FeatureFlag_oneof_value::bool_value(..)) => true,
+            _ => false,
+        }
+    }
+
+    // Param is passed by value, moved
+    pub fn set_bool_value(&mut self, v: bool) {
+        self.value = ::std::option::Option::Some(FeatureFlag_oneof_value::bool_value(v))
+    }
+
+    // int64 int_value = 3;
+
+
+    pub fn get_int_value(&self) -> i64 {
+        match self.value {
+            ::std::option::Option::Some(FeatureFlag_oneof_value::int_value(v)) => v,
+            _ => 0,
+        }
+    }
+    pub fn clear_int_value(&mut self) {
+        self.value = ::std::option::Option::None;
+    }
+
+    pub fn has_int_value(&self) -> bool {
+        match self.value {
+            ::std::option::Option::Some(FeatureFlag_oneof_value::int_value(..)) => true,
+            _ => false,
+        }
+    }
+
+    // Param is passed by value, moved
+    pub fn set_int_value(&mut self, v: i64) {
+        self.value = ::std::option::Option::Some(FeatureFlag_oneof_value::int_value(v))
+    }
+
+    // double float_value = 4;
+
+
+    pub fn get_float_value(&self) -> f64 {
+        match self.value {
+            ::std::option::Option::Some(FeatureFlag_oneof_value::float_value(v)) => v,
+            _ => 0.,
+        }
+    }
+    pub fn clear_float_value(&mut self) {
+        self.value = ::std::option::Option::None;
+    }
+
+    pub fn has_float_value(&self) -> bool {
+        match self.value {
+            ::std::option::Option::Some(FeatureFlag_oneof_value::float_value(..)) => true,
+            _ => false,
+        }
+    }
+
+    // Param is passed by value, moved
+    pub fn set_float_value(&mut self, v: f64) {
+        self.value = ::std::option::Option::Some(FeatureFlag_oneof_value::float_value(v))
+    }
+
+    // string text_value = 5;
+
+
+    pub fn get_text_value(&self) -> &str {
+        match self.value {
+            ::std::option::Option::Some(FeatureFlag_oneof_value::text_value(ref v)) => v,
+            _ => "",
+        }
+    }
+    pub fn clear_text_value(&mut self) {
+        self.value = ::std::option::Option::None;
+    }
+
+    pub fn has_text_value(&self) -> bool {
+        match self.value {
+            ::std::option::Option::Some(FeatureFlag_oneof_value::text_value(..)) => true,
+            _ => false,
+        }
+    }
+
+    // Param is passed by value, moved
+    pub fn set_text_value(&mut self, v: ::std::string::String) {
+        self.value = ::std::option::Option::Some(FeatureFlag_oneof_value::text_value(v))
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_text_value(&mut self) -> &mut ::std::string::String {
+        if let ::std::option::Option::Some(FeatureFlag_oneof_value::text_value(_)) = self.value {
+        } else {
+            self.value = ::std::option::Option::Some(FeatureFlag_oneof_value::text_value(::std::string::String::new()));
+        }
+        match self.value {
+            ::std::option::Option::Some(FeatureFlag_oneof_value::text_value(ref mut v)) => v,
+            _ => panic!(),
+        }
+    }
+
+    // Take field
+    pub fn take_text_value(&mut self) -> ::std::string::String {
+        if self.has_text_value() {
+            match self.value.take() {
+                ::std::option::Option::Some(FeatureFlag_oneof_value::text_value(v)) => v,
+                _ => panic!(),
+            }
+        } else {
+            ::std::string::String::new()
+        }
+    }
+
+    // string set_by = 6;
+
+
+    pub fn get_set_by(&self) -> &str {
+        &self.set_by
+    }
+    pub fn clear_set_by(&mut self) {
+        self.set_by.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_set_by(&mut self, v: ::std::string::String) {
+        self.set_by = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_set_by(&mut self) -> &mut ::std::string::String {
+        &mut self.set_by
+    }
+
+    // Take field
+    pub fn take_set_by(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.set_by, ::std::string::String::new())
+    }
+
+    // uint64 updated_at = 7;
+
+
+    pub fn get_updated_at(&self) -> u64 {
+        self.updated_at
+    }
+    pub fn clear_updated_at(&mut self) {
+        self.updated_at = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_updated_at(&mut self, v: u64) {
+        self.updated_at = v;
+    }
+}
+
+impl ::protobuf::Message for FeatureFlag {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.key)?;
+                },
+                2 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    self.value = ::std::option::Option::Some(FeatureFlag_oneof_value::bool_value(is.read_bool()?));
+                },
+                3 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    self.value = ::std::option::Option::Some(FeatureFlag_oneof_value::int_value(is.read_int64()?));
+                },
+                4 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeFixed64 {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    self.value = ::std::option::Option::Some(FeatureFlag_oneof_value::float_value(is.read_double()?));
+                },
+                5 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeLengthDelimited {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    self.value = ::std::option::Option::Some(FeatureFlag_oneof_value::text_value(is.read_string()?));
+                },
+                6 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.set_by)?;
+                },
+                7 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.updated_at = tmp;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.key.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.key);
+        }
+        if !self.set_by.is_empty() {
+            my_size += ::protobuf::rt::string_size(6, &self.set_by);
+        }
+        if self.updated_at != 0 {
+            my_size += ::protobuf::rt::value_size(7, self.updated_at, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if let ::std::option::Option::Some(ref v) = self.value {
+            match v {
+                &FeatureFlag_oneof_value::bool_value(v) => {
+                    my_size += 2;
+                },
+                &FeatureFlag_oneof_value::int_value(v) => {
+                    my_size += ::protobuf::rt::value_size(3, v, ::protobuf::wire_format::WireTypeVarint);
+                },
+                &FeatureFlag_oneof_value::float_value(v) => {
+                    my_size += 9;
+                },
+                &FeatureFlag_oneof_value::text_value(ref v) => {
+                    my_size += ::protobuf::rt::string_size(5, &v);
+                },
+            };
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.key.is_empty() {
+            os.write_string(1, &self.key)?;
+        }
+        if !self.set_by.is_empty() {
+            os.write_string(6, &self.set_by)?;
+        }
+        if self.updated_at != 0 {
+            os.write_uint64(7, self.updated_at)?;
+        }
+        if let ::std::option::Option::Some(ref v) = self.value {
+            match v {
+                &FeatureFlag_oneof_value::bool_value(v) => {
+                    os.write_bool(2, v)?;
+                },
+                &FeatureFlag_oneof_value::int_value(v) => {
+                    os.write_int64(3, v)?;
+                },
+                &FeatureFlag_oneof_value::float_value(v) => {
+                    os.write_double(4, v)?;
+                },
+                &FeatureFlag_oneof_value::text_value(ref v) => {
+                    os.write_string(5, v)?;
+                },
+            };
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> FeatureFlag {
+        FeatureFlag::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "key",
+                |m: &FeatureFlag| { &m.key },
+                |m: &mut FeatureFlag| { &mut m.key },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_singular_bool_accessor::<_>(
+                "bool_value",
+                FeatureFlag::has_bool_value,
+                FeatureFlag::get_bool_value,
+            ));
+            fields.push(::protobuf::reflect::accessor::make_singular_i64_accessor::<_>(
+                "int_value",
+                FeatureFlag::has_int_value,
+                FeatureFlag::get_int_value,
+            ));
+            fields.push(::protobuf::reflect::accessor::make_singular_f64_accessor::<_>(
+                "float_value",
+                FeatureFlag::has_float_value,
+                FeatureFlag::get_float_value,
+            ));
+            fields.push(::protobuf::reflect::accessor::make_singular_string_accessor::<_>(
+                "text_value",
+                FeatureFlag::has_text_value,
+                FeatureFlag::get_text_value,
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "set_by",
+                |m: &FeatureFlag| { &m.set_by },
+                |m: &mut FeatureFlag| { &mut m.set_by },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                "updated_at",
+                |m: &FeatureFlag| { &m.updated_at },
+                |m: &mut FeatureFlag| { &mut m.updated_at },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<FeatureFlag>(
+                "FeatureFlag",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static FeatureFlag {
+        static instance: ::protobuf::rt::LazyV2<FeatureFlag> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(FeatureFlag::new)
+    }
+}
+
+impl ::protobuf::Clear for FeatureFlag {
+    fn clear(&mut self) {
+        self.key.clear();
+        self.value = ::std::option::Option::None;
+        self.value = ::std::option::Option::None;
+        self.value = ::std::option::Option::None;
+        self.value = ::std::option::Option::None;
+        self.set_by.clear();
+        self.updated_at = 0;
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for FeatureFlag {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for FeatureFlag {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+static file_descriptor_proto_data: &'static [u8] = b"\
+    \n\x0bflags.proto\x12\remerald.state\"\xf2\x01\n\x0bFeatureFlag\x12\x12\
+    \n\x03key\x18\x01\x20\x01(\tR\x03keyB\0\x12!\n\nbool_value\x18\x02\x20\
+    \x01(\x08H\0R\tboolValueB\0\x12\x1f\n\tint_value\x18\x03\x20\x01(\x03H\0\
+    R\x08intValueB\0\x12#\n\x0bfloat_value\x18\x04\x20\x01(\x01H\0R\nfloatVa\
+    lueB\0\x12!\n\ntext_value\x18\x05\x20\x01(\tH\0R\ttextValueB\0\x12\x17\n\
+    \x06set_by\x18\x06\x20\x01(\tR\x05setByB\0\x12\x1f\n\nupdated_at\x18\x07\
+    \x20\x01(\x04R\tupdatedAtB\0B\x07\n\x05value:\0B\0b\x06proto3\
+";
+
+static file_descriptor_proto_lazy: ::protobuf::rt::LazyV2<::protobuf::descriptor::FileDescriptorProto> = ::protobuf::rt::LazyV2::INIT;
+
+fn parse_descriptor_proto() -> ::protobuf::descriptor::FileDescriptorProto {
+    ::protobuf::Message::parse_from_bytes(file_descriptor_proto_data).unwrap()
+}
+
+pub fn file_descriptor_proto() -> &'static ::protobuf::descriptor::FileDescriptorProto {
+    file_descriptor_proto_lazy.get(|| {
+        parse_descriptor_proto()
+    })
+}
@@ -181,15 +181,168 @@ impl ::protobuf::reflect::ProtobufValue for Indexes {
     }
 }
 
+#[derive(PartialEq,Clone,Default)]
+pub struct ChangedKeys {
+    // message fields
+    pub keys: ::protobuf::RepeatedField<::std::vec::Vec<u8>>,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a ChangedKeys {
+    fn default() -> &'a ChangedKeys {
+        <ChangedKeys as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl ChangedKeys {
+    pub fn new() -> ChangedKeys {
+        ::std::default::Default::default()
+    }
+
+    // repeated bytes keys = 1;
+
+
+    pub fn get_keys(&self) -> &[::std::vec::Vec<u8>] {
+        &self.keys
+    }
+    pub fn clear_keys(&mut self) {
+        self.keys.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_keys(&mut self, v: ::protobuf::RepeatedField<::std::vec::Vec<u8>>) {
+        self.keys = v;
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_keys(&mut self) -> &mut ::protobuf::RepeatedField<::std::vec::Vec<u8>> {
+        &mut self.keys
+    }
+
+    // Take field
+    pub fn take_keys(&mut self) -> ::protobuf::RepeatedField<::std::vec::Vec<u8>> {
+        ::std::mem::replace(&mut self.keys, ::protobuf::RepeatedField::new())
+    }
+}
+
+impl ::protobuf::Message for ChangedKeys {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_repeated_bytes_into(wire_type, is, &mut self.keys)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        for value in &self.keys {
+            my_size += ::protobuf::rt::bytes_size(1, &value);
+        };
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        for v in &self.keys {
+            os.write_bytes(1, &v)?;
+        };
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> ChangedKeys {
+        ChangedKeys::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_repeated_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
+                "keys",
+                |m: &ChangedKeys| { &m.keys },
+                |m: &mut ChangedKeys| { &mut m.keys },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<ChangedKeys>(
+                "ChangedKeys",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static ChangedKeys {
+        static instance: ::protobuf::rt::LazyV2<ChangedKeys> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(ChangedKeys::new)
+    }
+}
+
+impl ::protobuf::Clear for ChangedKeys {
+    fn clear(&mut self) {
+        self.keys.clear();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for ChangedKeys {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for ChangedKeys {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
 static file_descriptor_proto_data: &'static [u8] = b"\
-    \n\x0einternal.proto\x12\remerald.state\"\x1d\n\x07Indexes\x12\x12\n\x04\
-    keys\x18\x01\x20\x03(\tR\x04keysJy\n\x06\x12\x04\0\0\x05\x01\n\x08\n\x01\
-    \x0c\x12\x03\0\0\x12\n\x08\n\x01\x02\x12\x03\x01\0\x16\n\n\n\x02\x04\0\
-    \x12\x04\x03\0\x05\x01\n\n\n\x03\x04\0\x01\x12\x03\x03\x08\x0f\n\x0b\n\
-    \x04\x04\0\x02\0\x12\x03\x04\x02\x1b\n\x0c\n\x05\x04\0\x02\0\x04\x12\x03\
-    \x04\x02\n\n\x0c\n\x05\x04\0\x02\0\x05\x12\x03\x04\x0b\x11\n\x0c\n\x05\
-    \x04\0\x02\0\x01\x12\x03\x04\x12\x16\n\x0c\n\x05\x04\0\x02\0\x03\x12\x03\
-    \x04\x19\x1ab\x06proto3\
+    \n\x0einternal.proto\x12\remerald.state\"!\n\x07Indexes\x12\x14\n\x04key\
+    s\x18\x01\x20\x03(\tR\x04keysB\0:\0\"%\n\x0bChangedKeys\x12\x14\n\x04key\
+    s\x18\x01\x20\x03(\x0cR\x04keysB\0:\0B\0b\x06proto3\
 ";
 
 static file_descriptor_proto_lazy: ::protobuf::rt::LazyV2<::protobuf::descriptor::FileDescriptorProto> = ::protobuf::rt::LazyV2::INIT;
@@ -0,0 +1,576 @@
+// This file is generated by rust-protobuf 2.28.0. Do not edit
+// @generated
+
+// https://github.com/rust-lang/rust-clippy/issues/702
+#![allow(unknown_lints)]
+#![allow(clippy::all)]
+
+#![allow(unused_attributes)]
+#![cfg_attr(rustfmt, rustfmt::skip)]
+
+#![allow(box_pointers)]
+#![allow(dead_code)]
+#![allow(missing_docs)]
+#![allow(non_camel_case_types)]
+#![allow(non_snake_case)]
+#![allow(non_upper_case_globals)]
+#![allow(trivial_casts)]
+#![allow(unused_imports)]
+#![allow(unused_results)]
+//! Generated file from `quarantine.proto`
+
+/// Generated files are compatible only with the same version
+/// of protobuf runtime.
+// const _PROTOBUF_VERSION_CHECK: () = ::protobuf::VERSION_2_28_0;
+
+#[derive(PartialEq,Clone,Default)]
+pub struct QuarantinedRecord {
+    // message fields
+    pub store: StoreType,
+    pub key: ::std::vec::Vec<u8>,
+    pub value: ::std::vec::Vec<u8>,
+    pub error: ::std::string::String,
+    pub timestamp: u64,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a QuarantinedRecord {
+    fn default() -> &'a QuarantinedRecord {
+        <QuarantinedRecord as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl QuarantinedRecord {
+    pub fn new() -> QuarantinedRecord {
+        ::std::default::Default::default()
+    }
+
+    // .emerald.state.StoreType store = 1;
+
+
+    pub fn get_store(&self) -> StoreType {
+        self.store
+    }
+    pub fn clear_store(&mut self) {
+        self.store = StoreType::STORE_UNSPECIFIED;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_store(&mut self, v: StoreType) {
+        self.store = v;
+    }
+
+    // bytes key = 2;
+
+
+    pub fn get_key(&self) -> &[u8] {
+        &self.key
+    }
+    pub fn clear_key(&mut self) {
+        self.key.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_key(&mut self, v: ::std::vec::Vec<u8>) {
+        self.key = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_key(&mut self) -> &mut ::std::vec::Vec<u8> {
+        &mut self.key
+    }
+
+    // Take field
+    pub fn take_key(&mut self) -> ::std::vec::Vec<u8> {
+        ::std::mem::replace(&mut self.key, ::std::vec::Vec::new())
+    }
+
+    // bytes value = 3;
+
+
+    pub fn get_value(&self) -> &[u8] {
+        &self.value
+    }
+    pub fn clear_value(&mut self) {
+        self.value.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_value(&mut self, v: ::std::vec::Vec<u8>) {
+        self.value = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_value(&mut self) -> &mut ::std::vec::Vec<u8> {
+        &mut self.value
+    }
+
+    // Take field
+    pub fn take_value(&mut self) -> ::std::vec::Vec<u8> {
+        ::std::mem::replace(&mut self.value, ::std::vec::Vec::new())
+    }
+
+    // string error = 4;
+
+
+    pub fn get_error(&self) -> &str {
+        &self.error
+    }
+    pub fn clear_error(&mut self) {
+        self.error.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_error(&mut self, v: ::std::string::String) {
+        self.error = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_error(&mut self) -> &mut ::std::string::String {
+        &mut self.error
+    }
+
+    // Take field
+    pub fn take_error(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.error, ::std::string::String::new())
+    }
+
+    // uint64 timestamp = 5;
+
+
+    pub fn get_timestamp(&self) -> u64 {
+        self.timestamp
+    }
+    pub fn clear_timestamp(&mut self) {
+        self.timestamp = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_timestamp(&mut self, v: u64) {
+        self.timestamp = v;
+    }
+}
+
+impl ::protobuf::Message for QuarantinedRecord {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_proto3_enum_with_unknown_fields_into(wire_type, is, &mut self.store, 1, &mut self.unknown_fields)?
+                },
+                2 => {
+                    ::protobuf::rt::read_singular_proto3_bytes_into(wire_type, is, &mut self.key)?;
+                },
+                3 => {
+                    ::protobuf::rt::read_singular_proto3_bytes_into(wire_type, is, &mut self.value)?;
+                },
+                4 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.error)?;
+                },
+                5 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.timestamp = tmp;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if self.store != StoreType::STORE_UNSPECIFIED {
+            my_size += ::protobuf::rt::enum_size(1, self.store);
+        }
+        if !self.key.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(2, &self.key);
+        }
+        if !self.value.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(3, &self.value);
+        }
+        if !self.error.is_empty() {
+            my_size += ::protobuf::rt::string_size(4, &self.error);
+        }
+        if self.timestamp != 0 {
+            my_size += ::protobuf::rt::value_size(5, self.timestamp, ::protobuf::wire_format::WireTypeVarint);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if self.store != StoreType::STORE_UNSPECIFIED {
+            os.write_enum(1, ::protobuf::ProtobufEnum::value(&self.store))?;
+        }
+        if !self.key.is_empty() {
+            os.write_bytes(2, &self.key)?;
+        }
+        if !self.value.is_empty() {
+            os.write_bytes(3, &self.value)?;
+        }
+        if !self.error.is_empty() {
+            os.write_string(4, &self.error)?;
+        }
+        if self.timestamp != 0 {
+            os.write_uint64(5, self.timestamp)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> QuarantinedRecord {
+        QuarantinedRecord::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeEnum<StoreType>>(
+                "store",
+                |m: &QuarantinedRecord| { &m.store },
+                |m: &mut QuarantinedRecord| { &mut m.store },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
+                "key",
+                |m: &QuarantinedRecord| { &m.key },
+                |m: &mut QuarantinedRecord| { &mut m.key },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
+                "value",
+                |m: &QuarantinedRecord| { &m.value },
+                |m: &mut QuarantinedRecord| { &mut m.value },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "error",
+                |m: &QuarantinedRecord| { &m.error },
+                |m: &mut QuarantinedRecord| { &mut m.error },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                "timestamp",
+                |m: &QuarantinedRecord| { &m.timestamp },
+                |m: &mut QuarantinedRecord| { &mut m.timestamp },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<QuarantinedRecord>(
+                "QuarantinedRecord",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static QuarantinedRecord {
+        static instance: ::protobuf::rt::LazyV2<QuarantinedRecord> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(QuarantinedRecord::new)
+    }
+}
+
+impl ::protobuf::Clear for QuarantinedRecord {
+    fn clear(&mut self) {
+        self.store = StoreType::STORE_UNSPECIFIED;
+        self.key.clear();
+        self.value.clear();
+        self.error.clear();
+        self.timestamp = 0;
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for QuarantinedRecord {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for QuarantinedRecord {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct QuarantineBundle {
+    // message fields
+    pub records: ::protobuf::RepeatedField<QuarantinedRecord>,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a QuarantineBundle {
+    fn default() -> &'a QuarantineBundle {
+        <QuarantineBundle as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl QuarantineBundle {
+    pub fn new() -> QuarantineBundle {
+        ::std::default::Default::default()
+    }
+
+    // repeated .emerald.state.QuarantinedRecord records = 1;
+
+
+    pub fn get_records(&self) -> &[QuarantinedRecord] {
+        &self.records
+    }
+    pub fn clear_records(&mut self) {
+        self.records.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_records(&mut self, v: ::protobuf::RepeatedField<QuarantinedRecord>) {
+        self.records = v;
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_records(&mut self) -> &mut ::protobuf::RepeatedField<QuarantinedRecord> {
+        &mut self.records
+    }
+
+    // Take field
+    pub fn take_records(&mut self) -> ::protobuf::RepeatedField<QuarantinedRecord> {
+        ::std::mem::replace(&mut self.records, ::protobuf::RepeatedField::new())
+    }
+}
+
+impl ::protobuf::Message for QuarantineBundle {
+    fn is_initialized(&self) -> bool {
+        for v in &self.records {
+            if !v.is_initialized() {
+                return false;
+            }
+        };
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_repeated_message_into(wire_type, is, &mut self.records)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        for value in &self.records {
+            let len = value.compute_size();
+            my_size += 1 + ::protobuf::rt::compute_raw_varint32_size(len) + len;
+        };
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        for v in &self.records {
+            os.write_tag(1, ::protobuf::wire_format::WireTypeLengthDelimited)?;
+            os.write_raw_varint32(v.get_cached_size())?;
+            v.write_to_with_cached_sizes(os)?;
+        };
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> QuarantineBundle {
+        QuarantineBundle::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_repeated_field_accessor::<_, ::protobuf::types::ProtobufTypeMessage<QuarantinedRecord>>(
+                "records",
+                |m: &QuarantineBundle| { &m.records },
+                |m: &mut QuarantineBundle| { &mut m.records },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<QuarantineBundle>(
+                "QuarantineBundle",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static QuarantineBundle {
+        static instance: ::protobuf::rt::LazyV2<QuarantineBundle> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(QuarantineBundle::new)
+    }
+}
+
+impl ::protobuf::Clear for QuarantineBundle {
+    fn clear(&mut self) {
+        self.records.clear();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for QuarantineBundle {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for QuarantineBundle {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(Clone,PartialEq,Eq,Debug,Hash)]
+pub enum StoreType {
+    STORE_UNSPECIFIED = 0,
+    STORE_TRANSACTIONS = 1,
+    STORE_ADDRESS_BOOK = 2,
+}
+
+impl ::protobuf::ProtobufEnum for StoreType {
+    fn value(&self) -> i32 {
+        *self as i32
+    }
+
+    fn from_i32(value: i32) -> ::std::option::Option<StoreType> {
+        match value {
+            0 => ::std::option::Option::Some(StoreType::STORE_UNSPECIFIED),
+            1 => ::std::option::Option::Some(StoreType::STORE_TRANSACTIONS),
+            2 => ::std::option::Option::Some(StoreType::STORE_ADDRESS_BOOK),
+            _ => ::std::option::Option::None
+        }
+    }
+
+    fn values() -> &'static [Self] {
+        static values: &'static [StoreType] = &[
+            StoreType::STORE_UNSPECIFIED,
+            StoreType::STORE_TRANSACTIONS,
+            StoreType::STORE_ADDRESS_BOOK,
+        ];
+        values
+    }
+
+    fn enum_descriptor_static() -> &'static ::protobuf::reflect::EnumDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::EnumDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            ::protobuf::reflect::EnumDescriptor::new_pb_name::<StoreType>("StoreType", file_descriptor_proto())
+        })
+    }
+}
+
+impl ::std::marker::Copy for StoreType {
+}
+
+impl ::std::default::Default for StoreType {
+    fn default() -> Self {
+        StoreType::STORE_UNSPECIFIED
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for StoreType {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Enum(::protobuf::ProtobufEnum::descriptor(self))
+    }
+}
+
+static file_descriptor_proto_data: &'static [u8] = b"\
+    \n\x10quarantine.proto\x12\remerald.state\"\xab\x01\n\x11QuarantinedReco\
+    rd\x120\n\x05store\x18\x01\x20\x01(\x0e2\x18.emerald.state.StoreTypeR\
+    \x05storeB\0\x12\x12\n\x03key\x18\x02\x20\x01(\x0cR\x03keyB\0\x12\x16\n\
+    \x05value\x18\x03\x20\x01(\x0cR\x05valueB\0\x12\x16\n\x05error\x18\x04\
+    \x20\x01(\tR\x05errorB\0\x12\x1e\n\ttimestamp\x18\x05\x20\x01(\x04R\ttim\
+    estampB\0:\0\"R\n\x10QuarantineBundle\x12<\n\x07records\x18\x01\x20\x03(\
+    \x0b2\x20.emerald.state.QuarantinedRecordR\x07recordsB\0:\0*T\n\tStoreTy\
+    pe\x12\x15\n\x11STORE_UNSPECIFIED\x10\0\x12\x16\n\x12STORE_TRANSACTIONS\
+    \x10\x01\x12\x16\n\x12STORE_ADDRESS_BOOK\x10\x02\x1a\0B\0b\x06proto3\
+";
+
+static file_descriptor_proto_lazy: ::protobuf::rt::LazyV2<::protobuf::descriptor::FileDescriptorProto> = ::protobuf::rt::LazyV2::INIT;
+
+fn parse_descriptor_proto() -> ::protobuf::descriptor::FileDescriptorProto {
+    ::protobuf::Message::parse_from_bytes(file_descriptor_proto_data).unwrap()
+}
+
+pub fn file_descriptor_proto() -> &'static ::protobuf::descriptor::FileDescriptorProto {
+    file_descriptor_proto_lazy.get(|| {
+        parse_descriptor_proto()
+    })
+}
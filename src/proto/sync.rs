@@ -0,0 +1,386 @@
+// This file is generated by rust-protobuf 2.28.0. Do not edit
+// @generated
+
+// https://github.com/rust-lang/rust-clippy/issues/702
+#![allow(unknown_lints)]
+#![allow(clippy::all)]
+
+#![allow(unused_attributes)]
+#![cfg_attr(rustfmt, rustfmt::skip)]
+
+#![allow(box_pointers)]
+#![allow(dead_code)]
+#![allow(missing_docs)]
+#![allow(non_camel_case_types)]
+#![allow(non_snake_case)]
+#![allow(non_upper_case_globals)]
+#![allow(trivial_casts)]
+#![allow(unused_imports)]
+#![allow(unused_results)]
+//! Generated file from `sync.proto`
+
+/// Generated files are compatible only with the same version
+/// of protobuf runtime.
+// const _PROTOBUF_VERSION_CHECK: () = ::protobuf::VERSION_2_28_0;
+
+#[derive(PartialEq,Clone,Default)]
+pub struct SyncState {
+    // message fields
+    pub target: ::std::string::String,
+    pub last_attempt: u64,
+    pub last_success: u64,
+    pub failures: u32,
+    pub next_attempt: u64,
+    pub last_error: ::std::string::String,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a SyncState {
+    fn default() -> &'a SyncState {
+        <SyncState as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl SyncState {
+    pub fn new() -> SyncState {
+        ::std::default::Default::default()
+    }
+
+    // string target = 1;
+
+
+    pub fn get_target(&self) -> &str {
+        &self.target
+    }
+    pub fn clear_target(&mut self) {
+        self.target.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_target(&mut self, v: ::std::string::String) {
+        self.target = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_target(&mut self) -> &mut ::std::string::String {
+        &mut self.target
+    }
+
+    // Take field
+    pub fn take_target(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.target, ::std::string::String::new())
+    }
+
+    // uint64 last_attempt = 2;
+
+
+    pub fn get_last_attempt(&self) -> u64 {
+        self.last_attempt
+    }
+    pub fn clear_last_attempt(&mut self) {
+        self.last_attempt = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_last_attempt(&mut self, v: u64) {
+        self.last_attempt = v;
+    }
+
+    // uint64 last_success = 3;
+
+
+    pub fn get_last_success(&self) -> u64 {
+        self.last_success
+    }
+    pub fn clear_last_success(&mut self) {
+        self.last_success = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_last_success(&mut self, v: u64) {
+        self.last_success = v;
+    }
+
+    // uint32 failures = 4;
+
+
+    pub fn get_failures(&self) -> u32 {
+        self.failures
+    }
+    pub fn clear_failures(&mut self) {
+        self.failures = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_failures(&mut self, v: u32) {
+        self.failures = v;
+    }
+
+    // uint64 next_attempt = 5;
+
+
+    pub fn get_next_attempt(&self) -> u64 {
+        self.next_attempt
+    }
+    pub fn clear_next_attempt(&mut self) {
+        self.next_attempt = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_next_attempt(&mut self, v: u64) {
+        self.next_attempt = v;
+    }
+
+    // string last_error = 6;
+
+
+    pub fn get_last_error(&self) -> &str {
+        &self.last_error
+    }
+    pub fn clear_last_error(&mut self) {
+        self.last_error.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_last_error(&mut self, v: ::std::string::String) {
+        self.last_error = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_last_error(&mut self) -> &mut ::std::string::String {
+        &mut self.last_error
+    }
+
+    // Take field
+    pub fn take_last_error(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.last_error, ::std::string::String::new())
+    }
+}
+
+impl ::protobuf::Message for SyncState {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.target)?;
+                },
+                2 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.last_attempt = tmp;
+                },
+                3 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.last_success = tmp;
+                },
+                4 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint32()?;
+                    self.failures = tmp;
+                },
+                5 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.next_attempt = tmp;
+                },
+                6 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.last_error)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.target.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.target);
+        }
+        if self.last_attempt != 0 {
+            my_size += ::protobuf::rt::value_size(2, self.last_attempt, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.last_success != 0 {
+            my_size += ::protobuf::rt::value_size(3, self.last_success, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.failures != 0 {
+            my_size += ::protobuf::rt::value_size(4, self.failures, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.next_attempt != 0 {
+            my_size += ::protobuf::rt::value_size(5, self.next_attempt, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if !self.last_error.is_empty() {
+            my_size += ::protobuf::rt::string_size(6, &self.last_error);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.target.is_empty() {
+            os.write_string(1, &self.target)?;
+        }
+        if self.last_attempt != 0 {
+            os.write_uint64(2, self.last_attempt)?;
+        }
+        if self.last_success != 0 {
+            os.write_uint64(3, self.last_success)?;
+        }
+        if self.failures != 0 {
+            os.write_uint32(4, self.failures)?;
+        }
+        if self.next_attempt != 0 {
+            os.write_uint64(5, self.next_attempt)?;
+        }
+        if !self.last_error.is_empty() {
+            os.write_string(6, &self.last_error)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> SyncState {
+        SyncState::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "target",
+                |m: &SyncState| { &m.target },
+                |m: &mut SyncState| { &mut m.target },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                "last_attempt",
+                |m: &SyncState| { &m.last_attempt },
+                |m: &mut SyncState| { &mut m.last_attempt },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                "last_success",
+                |m: &SyncState| { &m.last_success },
+                |m: &mut SyncState| { &mut m.last_success },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint32>(
+                "failures",
+                |m: &SyncState| { &m.failures },
+                |m: &mut SyncState| { &mut m.failures },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                "next_attempt",
+                |m: &SyncState| { &m.next_attempt },
+                |m: &mut SyncState| { &mut m.next_attempt },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "last_error",
+                |m: &SyncState| { &m.last_error },
+                |m: &mut SyncState| { &mut m.last_error },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<SyncState>(
+                "SyncState",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static SyncState {
+        static instance: ::protobuf::rt::LazyV2<SyncState> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(SyncState::new)
+    }
+}
+
+impl ::protobuf::Clear for SyncState {
+    fn clear(&mut self) {
+        self.target.clear();
+        self.last_attempt = 0;
+        self.last_success = 0;
+        self.failures = 0;
+        self.next_attempt = 0;
+        self.last_error.clear();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for SyncState {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for SyncState {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+static file_descriptor_proto_data: &'static [u8] = b"\
+    \n\nsync.proto\x12\remerald.state\"\xd5\x01\n\tSyncState\x12\x18\n\x06ta\
+    rget\x18\x01\x20\x01(\tR\x06targetB\0\x12#\n\x0clast_attempt\x18\x02\x20\
+    \x01(\x04R\x0blastAttemptB\0\x12#\n\x0clast_success\x18\x03\x20\x01(\x04\
+    R\x0blastSuccessB\0\x12\x1c\n\x08failures\x18\x04\x20\x01(\rR\x08failure\
+    sB\0\x12#\n\x0cnext_attempt\x18\x05\x20\x01(\x04R\x0bnextAttemptB\0\x12\
+    \x1f\n\nlast_error\x18\x06\x20\x01(\tR\tlastErrorB\0:\0B\0b\x06proto3\
+";
+
+static file_descriptor_proto_lazy: ::protobuf::rt::LazyV2<::protobuf::descriptor::FileDescriptorProto> = ::protobuf::rt::LazyV2::INIT;
+
+fn parse_descriptor_proto() -> ::protobuf::descriptor::FileDescriptorProto {
+    ::protobuf::Message::parse_from_bytes(file_descriptor_proto_data).unwrap()
+}
+
+pub fn file_descriptor_proto() -> &'static ::protobuf::descriptor::FileDescriptorProto {
+    file_descriptor_proto_lazy.get(|| {
+        parse_descriptor_proto()
+    })
+}
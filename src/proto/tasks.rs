@@ -0,0 +1,591 @@
+// This file is generated by rust-protobuf 2.28.0. Do not edit
+// @generated
+
+// https://github.com/rust-lang/rust-clippy/issues/702
+#![allow(unknown_lints)]
+#![allow(clippy::all)]
+
+#![allow(unused_attributes)]
+#![cfg_attr(rustfmt, rustfmt::skip)]
+
+#![allow(box_pointers)]
+#![allow(dead_code)]
+#![allow(missing_docs)]
+#![allow(non_camel_case_types)]
+#![allow(non_snake_case)]
+#![allow(non_upper_case_globals)]
+#![allow(trivial_casts)]
+#![allow(unused_imports)]
+#![allow(unused_results)]
+//! Generated file from `tasks.proto`
+
+/// Generated files are compatible only with the same version
+/// of protobuf runtime.
+// const _PROTOBUF_VERSION_CHECK: () = ::protobuf::VERSION_2_28_0;
+
+#[derive(PartialEq,Clone,Default)]
+pub struct Task {
+    // message fields
+    pub id: ::std::string::String,
+    pub kind: ::std::string::String,
+    pub payload: ::std::vec::Vec<u8>,
+    pub created_at: u64,
+    pub attempts: u32,
+    pub max_attempts: u32,
+    pub visible_at: u64,
+    pub last_error: ::std::string::String,
+    pub status: TaskStatus,
+    pub position: u64,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a Task {
+    fn default() -> &'a Task {
+        <Task as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl Task {
+    pub fn new() -> Task {
+        ::std::default::Default::default()
+    }
+
+    // string id = 1;
+
+
+    pub fn get_id(&self) -> &str {
+        &self.id
+    }
+    pub fn clear_id(&mut self) {
+        self.id.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_id(&mut self, v: ::std::string::String) {
+        self.id = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_id(&mut self) -> &mut ::std::string::String {
+        &mut self.id
+    }
+
+    // Take field
+    pub fn take_id(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.id, ::std::string::String::new())
+    }
+
+    // string kind = 2;
+
+
+    pub fn get_kind(&self) -> &str {
+        &self.kind
+    }
+    pub fn clear_kind(&mut self) {
+        self.kind.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_kind(&mut self, v: ::std::string::String) {
+        self.kind = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_kind(&mut self) -> &mut ::std::string::String {
+        &mut self.kind
+    }
+
+    // Take field
+    pub fn take_kind(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.kind, ::std::string::String::new())
+    }
+
+    // bytes payload = 3;
+
+
+    pub fn get_payload(&self) -> &[u8] {
+        &self.payload
+    }
+    pub fn clear_payload(&mut self) {
+        self.payload.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_payload(&mut self, v: ::std::vec::Vec<u8>) {
+        self.payload = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_payload(&mut self) -> &mut ::std::vec::Vec<u8> {
+        &mut self.payload
+    }
+
+    // Take field
+    pub fn take_payload(&mut self) -> ::std::vec::Vec<u8> {
+        ::std::mem::replace(&mut self.payload, ::std::vec::Vec::new())
+    }
+
+    // uint64 created_at = 4;
+
+
+    pub fn get_created_at(&self) -> u64 {
+        self.created_at
+    }
+    pub fn clear_created_at(&mut self) {
+        self.created_at = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_created_at(&mut self, v: u64) {
+        self.created_at = v;
+    }
+
+    // uint32 attempts = 5;
+
+
+    pub fn get_attempts(&self) -> u32 {
+        self.attempts
+    }
+    pub fn clear_attempts(&mut self) {
+        self.attempts = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_attempts(&mut self, v: u32) {
+        self.attempts = v;
+    }
+
+    // uint32 max_attempts = 6;
+
+
+    pub fn get_max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+    pub fn clear_max_attempts(&mut self) {
+        self.max_attempts = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_max_attempts(&mut self, v: u32) {
+        self.max_attempts = v;
+    }
+
+    // uint64 visible_at = 7;
+
+
+    pub fn get_visible_at(&self) -> u64 {
+        self.visible_at
+    }
+    pub fn clear_visible_at(&mut self) {
+        self.visible_at = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_visible_at(&mut self, v: u64) {
+        self.visible_at = v;
+    }
+
+    // string last_error = 8;
+
+
+    pub fn get_last_error(&self) -> &str {
+        &self.last_error
+    }
+    pub fn clear_last_error(&mut self) {
+        self.last_error.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_last_error(&mut self, v: ::std::string::String) {
+        self.last_error = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_last_error(&mut self) -> &mut ::std::string::String {
+        &mut self.last_error
+    }
+
+    // Take field
+    pub fn take_last_error(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.last_error, ::std::string::String::new())
+    }
+
+    // .emerald.state.TaskStatus status = 9;
+
+
+    pub fn get_status(&self) -> TaskStatus {
+        self.status
+    }
+    pub fn clear_status(&mut self) {
+        self.status = TaskStatus::TASK_PENDING;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_status(&mut self, v: TaskStatus) {
+        self.status = v;
+    }
+
+    // uint64 position = 10;
+
+
+    pub fn get_position(&self) -> u64 {
+        self.position
+    }
+    pub fn clear_position(&mut self) {
+        self.position = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_position(&mut self, v: u64) {
+        self.position = v;
+    }
+}
+
+impl ::protobuf::Message for Task {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.id)?;
+                },
+                2 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.kind)?;
+                },
+                3 => {
+                    ::protobuf::rt::read_singular_proto3_bytes_into(wire_type, is, &mut self.payload)?;
+                },
+                4 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.created_at = tmp;
+                },
+                5 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint32()?;
+                    self.attempts = tmp;
+                },
+                6 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint32()?;
+                    self.max_attempts = tmp;
+                },
+                7 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.visible_at = tmp;
+                },
+                8 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.last_error)?;
+                },
+                9 => {
+                    ::protobuf::rt::read_proto3_enum_with_unknown_fields_into(wire_type, is, &mut self.status, 9, &mut self.unknown_fields)?
+                },
+                10 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.position = tmp;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.id.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.id);
+        }
+        if !self.kind.is_empty() {
+            my_size += ::protobuf::rt::string_size(2, &self.kind);
+        }
+        if !self.payload.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(3, &self.payload);
+        }
+        if self.created_at != 0 {
+            my_size += ::protobuf::rt::value_size(4, self.created_at, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.attempts != 0 {
+            my_size += ::protobuf::rt::value_size(5, self.attempts, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.max_attempts != 0 {
+            my_size += ::protobuf::rt::value_size(6, self.max_attempts, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.visible_at != 0 {
+            my_size += ::protobuf::rt::value_size(7, self.visible_at, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if !self.last_error.is_empty() {
+            my_size += ::protobuf::rt::string_size(8, &self.last_error);
+        }
+        if self.status != TaskStatus::TASK_PENDING {
+            my_size += ::protobuf::rt::enum_size(9, self.status);
+        }
+        if self.position != 0 {
+            my_size += ::protobuf::rt::value_size(10, self.position, ::protobuf::wire_format::WireTypeVarint);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.id.is_empty() {
+            os.write_string(1, &self.id)?;
+        }
+        if !self.kind.is_empty() {
+            os.write_string(2, &self.kind)?;
+        }
+        if !self.payload.is_empty() {
+            os.write_bytes(3, &self.payload)?;
+        }
+        if self.created_at != 0 {
+            os.write_uint64(4, self.created_at)?;
+        }
+        if self.attempts != 0 {
+            os.write_uint32(5, self.attempts)?;
+        }
+        if self.max_attempts != 0 {
+            os.write_uint32(6, self.max_attempts)?;
+        }
+        if self.visible_at != 0 {
+            os.write_uint64(7, self.visible_at)?;
+        }
+        if !self.last_error.is_empty() {
+            os.write_string(8, &self.last_error)?;
+        }
+        if self.status != TaskStatus::TASK_PENDING {
+            os.write_enum(9, ::protobuf::ProtobufEnum::value(&self.status))?;
+        }
+        if self.position != 0 {
+            os.write_uint64(10, self.position)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> Task {
+        Task::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "id",
+                |m: &Task| { &m.id },
+                |m: &mut Task| { &mut m.id },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "kind",
+                |m: &Task| { &m.kind },
+                |m: &mut Task| { &mut m.kind },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
+                "payload",
+                |m: &Task| { &m.payload },
+                |m: &mut Task| { &mut m.payload },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                "created_at",
+                |m: &Task| { &m.created_at },
+                |m: &mut Task| { &mut m.created_at },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint32>(
+                "attempts",
+                |m: &Task| { &m.attempts },
+                |m: &mut Task| { &mut m.attempts },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint32>(
+                "max_attempts",
+                |m: &Task| { &m.max_attempts },
+                |m: &mut Task| { &mut m.max_attempts },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                "visible_at",
+                |m: &Task| { &m.visible_at },
+                |m: &mut Task| { &mut m.visible_at },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "last_error",
+                |m: &Task| { &m.last_error },
+                |m: &mut Task| { &mut m.last_error },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeEnum<TaskStatus>>(
+                "status",
+                |m: &Task| { &m.status },
+                |m: &mut Task| { &mut m.status },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                "position",
+                |m: &Task| { &m.position },
+                |m: &mut Task| { &mut m.position },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<Task>(
+                "Task",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static Task {
+        static instance: ::protobuf::rt::LazyV2<Task> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(Task::new)
+    }
+}
+
+impl ::protobuf::Clear for Task {
+    fn clear(&mut self) {
+        self.id.clear();
+        self.kind.clear();
+        self.payload.clear();
+        self.created_at = 0;
+        self.attempts = 0;
+        self.max_attempts = 0;
+        self.visible_at = 0;
+        self.last_error.clear();
+        self.status = TaskStatus::TASK_PENDING;
+        self.position = 0;
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for Task {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for Task {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(Clone,PartialEq,Eq,Debug,Hash)]
+pub enum TaskStatus {
+    TASK_PENDING = 0,
+    TASK_FAILED = 1,
+}
+
+impl ::protobuf::ProtobufEnum for TaskStatus {
+    fn value(&self) -> i32 {
+        *self as i32
+    }
+
+    fn from_i32(value: i32) -> ::std::option::Option<TaskStatus> {
+        match value {
+            0 => ::std::option::Option::Some(TaskStatus::TASK_PENDING),
+            1 => ::std::option::Option::Some(TaskStatus::TASK_FAILED),
+            _ => ::std::option::Option::None
+        }
+    }
+
+    fn values() -> &'static [Self] {
+        static values: &'static [TaskStatus] = &[
+            TaskStatus::TASK_PENDING,
+            TaskStatus::TASK_FAILED,
+        ];
+        values
+    }
+
+    fn enum_descriptor_static() -> &'static ::protobuf::reflect::EnumDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::EnumDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            ::protobuf::reflect::EnumDescriptor::new_pb_name::<TaskStatus>("TaskStatus", file_descriptor_proto())
+        })
+    }
+}
+
+impl ::std::marker::Copy for TaskStatus {
+}
+
+impl ::std::default::Default for TaskStatus {
+    fn default() -> Self {
+        TaskStatus::TASK_PENDING
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for TaskStatus {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Enum(::protobuf::ProtobufEnum::descriptor(self))
+    }
+}
+
+static file_descriptor_proto_data: &'static [u8] = b"\
+    \n\x0btasks.proto\x12\remerald.state\"\xc5\x02\n\x04Task\x12\x10\n\x02id\
+    \x18\x01\x20\x01(\tR\x02idB\0\x12\x14\n\x04kind\x18\x02\x20\x01(\tR\x04k\
+    indB\0\x12\x1a\n\x07payload\x18\x03\x20\x01(\x0cR\x07payloadB\0\x12\x1f\
+    \n\ncreated_at\x18\x04\x20\x01(\x04R\tcreatedAtB\0\x12\x1c\n\x08attempts\
+    \x18\x05\x20\x01(\rR\x08attemptsB\0\x12#\n\x0cmax_attempts\x18\x06\x20\
+    \x01(\rR\x0bmaxAttemptsB\0\x12\x1f\n\nvisible_at\x18\x07\x20\x01(\x04R\t\
+    visibleAtB\0\x12\x1f\n\nlast_error\x18\x08\x20\x01(\tR\tlastErrorB\0\x12\
+    3\n\x06status\x18\t\x20\x01(\x0e2\x19.emerald.state.TaskStatusR\x06statu\
+    sB\0\x12\x1c\n\x08position\x18\n\x20\x01(\x04R\x08positionB\0:\0*1\n\nTa\
+    skStatus\x12\x10\n\x0cTASK_PENDING\x10\0\x12\x0f\n\x0bTASK_FAILED\x10\
+    \x01\x1a\0B\0b\x06proto3\
+";
+
+static file_descriptor_proto_lazy: ::protobuf::rt::LazyV2<::protobuf::descriptor::FileDescriptorProto> = ::protobuf::rt::LazyV2::INIT;
+
+fn parse_descriptor_proto() -> ::protobuf::descriptor::FileDescriptorProto {
+    ::protobuf::Message::parse_from_bytes(file_descriptor_proto_data).unwrap()
+}
+
+pub fn file_descriptor_proto() -> &'static ::protobuf::descriptor::FileDescriptorProto {
+    file_descriptor_proto_lazy.get(|| {
+        parse_descriptor_proto()
+    })
+}
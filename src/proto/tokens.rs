@@ -0,0 +1,507 @@
+// This file is generated by rust-protobuf 2.28.0. Do not edit
+// @generated
+
+// https://github.com/rust-lang/rust-clippy/issues/702
+#![allow(unknown_lints)]
+#![allow(clippy::all)]
+
+#![allow(unused_attributes)]
+#![cfg_attr(rustfmt, rustfmt::skip)]
+
+#![allow(box_pointers)]
+#![allow(dead_code)]
+#![allow(missing_docs)]
+#![allow(non_camel_case_types)]
+#![allow(non_snake_case)]
+#![allow(non_upper_case_globals)]
+#![allow(trivial_casts)]
+#![allow(unused_imports)]
+#![allow(unused_results)]
+//! Generated file from `tokens.proto`
+
+/// Generated files are compatible only with the same version
+/// of protobuf runtime.
+// const _PROTOBUF_VERSION_CHECK: () = ::protobuf::VERSION_2_28_0;
+
+#[derive(PartialEq,Clone,Default)]
+pub struct TokenMetadata {
+    // message fields
+    pub blockchain: u32,
+    pub contract: ::std::string::String,
+    pub symbol: ::std::string::String,
+    pub decimals: u32,
+    pub name: ::std::string::String,
+    pub icon_url: ::std::string::String,
+    pub verified: bool,
+    pub ts: u64,
+    pub ttl: u64,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a TokenMetadata {
+    fn default() -> &'a TokenMetadata {
+        <TokenMetadata as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl TokenMetadata {
+    pub fn new() -> TokenMetadata {
+        ::std::default::Default::default()
+    }
+
+    // uint32 blockchain = 1;
+
+
+    pub fn get_blockchain(&self) -> u32 {
+        self.blockchain
+    }
+    pub fn clear_blockchain(&mut self) {
+        self.blockchain = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_blockchain(&mut self, v: u32) {
+        self.blockchain = v;
+    }
+
+    // string contract = 2;
+
+
+    pub fn get_contract(&self) -> &str {
+        &self.contract
+    }
+    pub fn clear_contract(&mut self) {
+        self.contract.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_contract(&mut self, v: ::std::string::String) {
+        self.contract = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_contract(&mut self) -> &mut ::std::string::String {
+        &mut self.contract
+    }
+
+    // Take field
+    pub fn take_contract(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.contract, ::std::string::String::new())
+    }
+
+    // string symbol = 3;
+
+
+    pub fn get_symbol(&self) -> &str {
+        &self.symbol
+    }
+    pub fn clear_symbol(&mut self) {
+        self.symbol.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_symbol(&mut self, v: ::std::string::String) {
+        self.symbol = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_symbol(&mut self) -> &mut ::std::string::String {
+        &mut self.symbol
+    }
+
+    // Take field
+    pub fn take_symbol(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.symbol, ::std::string::String::new())
+    }
+
+    // uint32 decimals = 4;
+
+
+    pub fn get_decimals(&self) -> u32 {
+        self.decimals
+    }
+    pub fn clear_decimals(&mut self) {
+        self.decimals = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_decimals(&mut self, v: u32) {
+        self.decimals = v;
+    }
+
+    // string name = 5;
+
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+    pub fn clear_name(&mut self) {
+        self.name.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_name(&mut self, v: ::std::string::String) {
+        self.name = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_name(&mut self) -> &mut ::std::string::String {
+        &mut self.name
+    }
+
+    // Take field
+    pub fn take_name(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.name, ::std::string::String::new())
+    }
+
+    // string icon_url = 6;
+
+
+    pub fn get_icon_url(&self) -> &str {
+        &self.icon_url
+    }
+    pub fn clear_icon_url(&mut self) {
+        self.icon_url.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_icon_url(&mut self, v: ::std::string::String) {
+        self.icon_url = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_icon_url(&mut self) -> &mut ::std::string::String {
+        &mut self.icon_url
+    }
+
+    // Take field
+    pub fn take_icon_url(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.icon_url, ::std::string::String::new())
+    }
+
+    // bool verified = 7;
+
+
+    pub fn get_verified(&self) -> bool {
+        self.verified
+    }
+    pub fn clear_verified(&mut self) {
+        self.verified = false;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_verified(&mut self, v: bool) {
+        self.verified = v;
+    }
+
+    // uint64 ts = 8;
+
+
+    pub fn get_ts(&self) -> u64 {
+        self.ts
+    }
+    pub fn clear_ts(&mut self) {
+        self.ts = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_ts(&mut self, v: u64) {
+        self.ts = v;
+    }
+
+    // uint64 ttl = 9;
+
+
+    pub fn get_ttl(&self) -> u64 {
+        self.ttl
+    }
+    pub fn clear_ttl(&mut self) {
+        self.ttl = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_ttl(&mut self, v: u64) {
+        self.ttl = v;
+    }
+}
+
+impl ::protobuf::Message for TokenMetadata {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint32()?;
+                    self.blockchain = tmp;
+                },
+                2 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.contract)?;
+                },
+                3 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.symbol)?;
+                },
+                4 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint32()?;
+                    self.decimals = tmp;
+                },
+                5 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.name)?;
+                },
+                6 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.icon_url)?;
+                },
+                7 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_bool()?;
+                    self.verified = tmp;
+                },
+                8 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.ts = tmp;
+                },
+                9 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.ttl = tmp;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if self.blockchain != 0 {
+            my_size += ::protobuf::rt::value_size(1, self.blockchain, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if !self.contract.is_empty() {
+            my_size += ::protobuf::rt::string_size(2, &self.contract);
+        }
+        if !self.symbol.is_empty() {
+            my_size += ::protobuf::rt::string_size(3, &self.symbol);
+        }
+        if self.decimals != 0 {
+            my_size += ::protobuf::rt::value_size(4, self.decimals, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if !self.name.is_empty() {
+            my_size += ::protobuf::rt::string_size(5, &self.name);
+        }
+        if !self.icon_url.is_empty() {
+            my_size += ::protobuf::rt::string_size(6, &self.icon_url);
+        }
+        if self.verified != false {
+            my_size += 2;
+        }
+        if self.ts != 0 {
+            my_size += ::protobuf::rt::value_size(8, self.ts, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.ttl != 0 {
+            my_size += ::protobuf::rt::value_size(9, self.ttl, ::protobuf::wire_format::WireTypeVarint);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if self.blockchain != 0 {
+            os.write_uint32(1, self.blockchain)?;
+        }
+        if !self.contract.is_empty() {
+            os.write_string(2, &self.contract)?;
+        }
+        if !self.symbol.is_empty() {
+            os.write_string(3, &self.symbol)?;
+        }
+        if self.decimals != 0 {
+            os.write_uint32(4, self.decimals)?;
+        }
+        if !self.name.is_empty() {
+            os.write_string(5, &self.name)?;
+        }
+        if !self.icon_url.is_empty() {
+            os.write_string(6, &self.icon_url)?;
+        }
+        if self.verified != false {
+            os.write_bool(7, self.verified)?;
+        }
+        if self.ts != 0 {
+            os.write_uint64(8, self.ts)?;
+        }
+        if self.ttl != 0 {
+            os.write_uint64(9, self.ttl)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> TokenMetadata {
+        TokenMetadata::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint32>(
+                "blockchain",
+                |m: &TokenMetadata| { &m.blockchain },
+                |m: &mut TokenMetadata| { &mut m.blockchain },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "contract",
+                |m: &TokenMetadata| { &m.contract },
+                |m: &mut TokenMetadata| { &mut m.contract },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "symbol",
+                |m: &TokenMetadata| { &m.symbol },
+                |m: &mut TokenMetadata| { &mut m.symbol },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint32>(
+                "decimals",
+                |m: &TokenMetadata| { &m.decimals },
+                |m: &mut TokenMetadata| { &mut m.decimals },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "name",
+                |m: &TokenMetadata| { &m.name },
+                |m: &mut TokenMetadata| { &mut m.name },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "icon_url",
+                |m: &TokenMetadata| { &m.icon_url },
+                |m: &mut TokenMetadata| { &mut m.icon_url },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBool>(
+                "verified",
+                |m: &TokenMetadata| { &m.verified },
+                |m: &mut TokenMetadata| { &mut m.verified },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                "ts",
+                |m: &TokenMetadata| { &m.ts },
+                |m: &mut TokenMetadata| { &mut m.ts },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                "ttl",
+                |m: &TokenMetadata| { &m.ttl },
+                |m: &mut TokenMetadata| { &mut m.ttl },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<TokenMetadata>(
+                "TokenMetadata",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static TokenMetadata {
+        static instance: ::protobuf::rt::LazyV2<TokenMetadata> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(TokenMetadata::new)
+    }
+}
+
+impl ::protobuf::Clear for TokenMetadata {
+    fn clear(&mut self) {
+        self.blockchain = 0;
+        self.contract.clear();
+        self.symbol.clear();
+        self.decimals = 0;
+        self.name.clear();
+        self.icon_url.clear();
+        self.verified = false;
+        self.ts = 0;
+        self.ttl = 0;
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for TokenMetadata {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for TokenMetadata {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+static file_descriptor_proto_data: &'static [u8] = b"\
+    \n\x0ctokens.proto\x12\remerald.state\"\x80\x02\n\rTokenMetadata\x12\x20\
+    \n\nblockchain\x18\x01\x20\x01(\rR\nblockchainB\0\x12\x1c\n\x08contract\
+    \x18\x02\x20\x01(\tR\x08contractB\0\x12\x18\n\x06symbol\x18\x03\x20\x01(\
+    \tR\x06symbolB\0\x12\x1c\n\x08decimals\x18\x04\x20\x01(\rR\x08decimalsB\
+    \0\x12\x14\n\x04name\x18\x05\x20\x01(\tR\x04nameB\0\x12\x1b\n\x08icon_ur\
+    l\x18\x06\x20\x01(\tR\x07iconUrlB\0\x12\x1c\n\x08verified\x18\x07\x20\
+    \x01(\x08R\x08verifiedB\0\x12\x10\n\x02ts\x18\x08\x20\x01(\x04R\x02tsB\0\
+    \x12\x12\n\x03ttl\x18\t\x20\x01(\x04R\x03ttlB\0:\0B\0b\x06proto3\
+";
+
+static file_descriptor_proto_lazy: ::protobuf::rt::LazyV2<::protobuf::descriptor::FileDescriptorProto> = ::protobuf::rt::LazyV2::INIT;
+
+fn parse_descriptor_proto() -> ::protobuf::descriptor::FileDescriptorProto {
+    ::protobuf::Message::parse_from_bytes(file_descriptor_proto_data).unwrap()
+}
+
+pub fn file_descriptor_proto() -> &'static ::protobuf::descriptor::FileDescriptorProto {
+    file_descriptor_proto_lazy.get(|| {
+        parse_descriptor_proto()
+    })
+}
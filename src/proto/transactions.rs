@@ -1,4 +1,4 @@
-// This file is generated by rust-protobuf 2.25.2. Do not edit
+// This file is generated by rust-protobuf 2.28.0. Do not edit
 // @generated
 
 // https://github.com/rust-lang/rust-clippy/issues/702
@@ -21,7 +21,7 @@
 
 /// Generated files are compatible only with the same version
 /// of protobuf runtime.
-// const _PROTOBUF_VERSION_CHECK: () = ::protobuf::VERSION_2_25_2;
+// const _PROTOBUF_VERSION_CHECK: () = ::protobuf::VERSION_2_28_0;
 
 #[derive(PartialEq,Clone,Default)]
 pub struct Transaction {
@@ -37,6 +37,13 @@ pub struct Transaction {
     pub status: Status,
     pub changes: ::protobuf::RepeatedField<Change>,
     pub version: u64,
+    pub nonce_ref: ::protobuf::SingularPtrField<NonceRef>,
+    pub replaced_by: ::std::string::String,
+    pub internal_transfer: bool,
+    pub inputs: ::protobuf::RepeatedField<BitcoinInput>,
+    pub outputs: ::protobuf::RepeatedField<BitcoinOutput>,
+    pub vsize: u32,
+    pub token_transfers: ::protobuf::RepeatedField<TokenTransfer>,
     // special fields
     pub unknown_fields: ::protobuf::UnknownFields,
     pub cached_size: ::protobuf::CachedSize,
@@ -256,6 +263,170 @@ impl Transaction {
     pub fn set_version(&mut self, v: u64) {
         self.version = v;
     }
+
+    // .emerald.state.NonceRef nonce_ref = 12;
+
+
+    pub fn get_nonce_ref(&self) -> &NonceRef {
+        self.nonce_ref.as_ref().unwrap_or_else(|| <NonceRef as ::protobuf::Message>::default_instance())
+    }
+    pub fn clear_nonce_ref(&mut self) {
+        self.nonce_ref.clear();
+    }
+
+    pub fn has_nonce_ref(&self) -> bool {
+        self.nonce_ref.is_some()
+    }
+
+    // Param is passed by value, moved
+    pub fn set_nonce_ref(&mut self, v: NonceRef) {
+        self.nonce_ref = ::protobuf::SingularPtrField::some(v);
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_nonce_ref(&mut self) -> &mut NonceRef {
+        if self.nonce_ref.is_none() {
+            self.nonce_ref.set_default();
+        }
+        self.nonce_ref.as_mut().unwrap()
+    }
+
+    // Take field
+    pub fn take_nonce_ref(&mut self) -> NonceRef {
+        self.nonce_ref.take().unwrap_or_else(|| NonceRef::new())
+    }
+
+    // string replaced_by = 13;
+
+
+    pub fn get_replaced_by(&self) -> &str {
+        &self.replaced_by
+    }
+    pub fn clear_replaced_by(&mut self) {
+        self.replaced_by.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_replaced_by(&mut self, v: ::std::string::String) {
+        self.replaced_by = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_replaced_by(&mut self) -> &mut ::std::string::String {
+        &mut self.replaced_by
+    }
+
+    // Take field
+    pub fn take_replaced_by(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.replaced_by, ::std::string::String::new())
+    }
+
+    // bool internal_transfer = 14;
+
+
+    pub fn get_internal_transfer(&self) -> bool {
+        self.internal_transfer
+    }
+    pub fn clear_internal_transfer(&mut self) {
+        self.internal_transfer = false;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_internal_transfer(&mut self, v: bool) {
+        self.internal_transfer = v;
+    }
+
+    // repeated .emerald.state.BitcoinInput inputs = 15;
+
+
+    pub fn get_inputs(&self) -> &[BitcoinInput] {
+        &self.inputs
+    }
+    pub fn clear_inputs(&mut self) {
+        self.inputs.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_inputs(&mut self, v: ::protobuf::RepeatedField<BitcoinInput>) {
+        self.inputs = v;
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_inputs(&mut self) -> &mut ::protobuf::RepeatedField<BitcoinInput> {
+        &mut self.inputs
+    }
+
+    // Take field
+    pub fn take_inputs(&mut self) -> ::protobuf::RepeatedField<BitcoinInput> {
+        ::std::mem::replace(&mut self.inputs, ::protobuf::RepeatedField::new())
+    }
+
+    // repeated .emerald.state.BitcoinOutput outputs = 16;
+
+
+    pub fn get_outputs(&self) -> &[BitcoinOutput] {
+        &self.outputs
+    }
+    pub fn clear_outputs(&mut self) {
+        self.outputs.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_outputs(&mut self, v: ::protobuf::RepeatedField<BitcoinOutput>) {
+        self.outputs = v;
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_outputs(&mut self) -> &mut ::protobuf::RepeatedField<BitcoinOutput> {
+        &mut self.outputs
+    }
+
+    // Take field
+    pub fn take_outputs(&mut self) -> ::protobuf::RepeatedField<BitcoinOutput> {
+        ::std::mem::replace(&mut self.outputs, ::protobuf::RepeatedField::new())
+    }
+
+    // uint32 vsize = 17;
+
+
+    pub fn get_vsize(&self) -> u32 {
+        self.vsize
+    }
+    pub fn clear_vsize(&mut self) {
+        self.vsize = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_vsize(&mut self, v: u32) {
+        self.vsize = v;
+    }
+
+    // repeated .emerald.state.TokenTransfer token_transfers = 18;
+
+
+    pub fn get_token_transfers(&self) -> &[TokenTransfer] {
+        &self.token_transfers
+    }
+    pub fn clear_token_transfers(&mut self) {
+        self.token_transfers.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_token_transfers(&mut self, v: ::protobuf::RepeatedField<TokenTransfer>) {
+        self.token_transfers = v;
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_token_transfers(&mut self) -> &mut ::protobuf::RepeatedField<TokenTransfer> {
+        &mut self.token_transfers
+    }
+
+    // Take field
+    pub fn take_token_transfers(&mut self) -> ::protobuf::RepeatedField<TokenTransfer> {
+        ::std::mem::replace(&mut self.token_transfers, ::protobuf::RepeatedField::new())
+    }
 }
 
 impl ::protobuf::Message for Transaction {
@@ -270,6 +441,26 @@ impl ::protobuf::Message for Transaction {
                 return false;
             }
         };
+        for v in &self.nonce_ref {
+            if !v.is_initialized() {
+                return false;
+            }
+        };
+        for v in &self.inputs {
+            if !v.is_initialized() {
+                return false;
+            }
+        };
+        for v in &self.outputs {
+            if !v.is_initialized() {
+                return false;
+            }
+        };
+        for v in &self.token_transfers {
+            if !v.is_initialized() {
+                return false;
+            }
+        };
         true
     }
 
@@ -330,6 +521,35 @@ impl ::protobuf::Message for Transaction {
                     let tmp = is.read_uint64()?;
                     self.version = tmp;
                 },
+                12 => {
+                    ::protobuf::rt::read_singular_message_into(wire_type, is, &mut self.nonce_ref)?;
+                },
+                13 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.replaced_by)?;
+                },
+                14 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_bool()?;
+                    self.internal_transfer = tmp;
+                },
+                15 => {
+                    ::protobuf::rt::read_repeated_message_into(wire_type, is, &mut self.inputs)?;
+                },
+                16 => {
+                    ::protobuf::rt::read_repeated_message_into(wire_type, is, &mut self.outputs)?;
+                },
+                17 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint32()?;
+                    self.vsize = tmp;
+                },
+                18 => {
+                    ::protobuf::rt::read_repeated_message_into(wire_type, is, &mut self.token_transfers)?;
+                },
                 _ => {
                     ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
                 },
@@ -377,6 +597,31 @@ impl ::protobuf::Message for Transaction {
         if self.version != 0 {
             my_size += ::protobuf::rt::value_size(10, self.version, ::protobuf::wire_format::WireTypeVarint);
         }
+        if let Some(ref v) = self.nonce_ref.as_ref() {
+            let len = v.compute_size();
+            my_size += 1 + ::protobuf::rt::compute_raw_varint32_size(len) + len;
+        }
+        if !self.replaced_by.is_empty() {
+            my_size += ::protobuf::rt::string_size(13, &self.replaced_by);
+        }
+        if self.internal_transfer != false {
+            my_size += 2;
+        }
+        for value in &self.inputs {
+            let len = value.compute_size();
+            my_size += 1 + ::protobuf::rt::compute_raw_varint32_size(len) + len;
+        };
+        for value in &self.outputs {
+            let len = value.compute_size();
+            my_size += 2 + ::protobuf::rt::compute_raw_varint32_size(len) + len;
+        };
+        if self.vsize != 0 {
+            my_size += ::protobuf::rt::value_size(17, self.vsize, ::protobuf::wire_format::WireTypeVarint);
+        }
+        for value in &self.token_transfers {
+            let len = value.compute_size();
+            my_size += 2 + ::protobuf::rt::compute_raw_varint32_size(len) + len;
+        };
         my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
         self.cached_size.set(my_size);
         my_size
@@ -420,6 +665,35 @@ impl ::protobuf::Message for Transaction {
         if self.version != 0 {
             os.write_uint64(10, self.version)?;
         }
+        if let Some(ref v) = self.nonce_ref.as_ref() {
+            os.write_tag(12, ::protobuf::wire_format::WireTypeLengthDelimited)?;
+            os.write_raw_varint32(v.get_cached_size())?;
+            v.write_to_with_cached_sizes(os)?;
+        }
+        if !self.replaced_by.is_empty() {
+            os.write_string(13, &self.replaced_by)?;
+        }
+        if self.internal_transfer != false {
+            os.write_bool(14, self.internal_transfer)?;
+        }
+        for v in &self.inputs {
+            os.write_tag(15, ::protobuf::wire_format::WireTypeLengthDelimited)?;
+            os.write_raw_varint32(v.get_cached_size())?;
+            v.write_to_with_cached_sizes(os)?;
+        };
+        for v in &self.outputs {
+            os.write_tag(16, ::protobuf::wire_format::WireTypeLengthDelimited)?;
+            os.write_raw_varint32(v.get_cached_size())?;
+            v.write_to_with_cached_sizes(os)?;
+        };
+        if self.vsize != 0 {
+            os.write_uint32(17, self.vsize)?;
+        }
+        for v in &self.token_transfers {
+            os.write_tag(18, ::protobuf::wire_format::WireTypeLengthDelimited)?;
+            os.write_raw_varint32(v.get_cached_size())?;
+            v.write_to_with_cached_sizes(os)?;
+        };
         os.write_unknown_fields(self.get_unknown_fields())?;
         ::std::result::Result::Ok(())
     }
@@ -513,6 +787,41 @@ impl ::protobuf::Message for Transaction {
                 |m: &Transaction| { &m.version },
                 |m: &mut Transaction| { &mut m.version },
             ));
+            fields.push(::protobuf::reflect::accessor::make_singular_ptr_field_accessor::<_, ::protobuf::types::ProtobufTypeMessage<NonceRef>>(
+                "nonce_ref",
+                |m: &Transaction| { &m.nonce_ref },
+                |m: &mut Transaction| { &mut m.nonce_ref },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "replaced_by",
+                |m: &Transaction| { &m.replaced_by },
+                |m: &mut Transaction| { &mut m.replaced_by },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBool>(
+                "internal_transfer",
+                |m: &Transaction| { &m.internal_transfer },
+                |m: &mut Transaction| { &mut m.internal_transfer },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_repeated_field_accessor::<_, ::protobuf::types::ProtobufTypeMessage<BitcoinInput>>(
+                "inputs",
+                |m: &Transaction| { &m.inputs },
+                |m: &mut Transaction| { &mut m.inputs },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_repeated_field_accessor::<_, ::protobuf::types::ProtobufTypeMessage<BitcoinOutput>>(
+                "outputs",
+                |m: &Transaction| { &m.outputs },
+                |m: &mut Transaction| { &mut m.outputs },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint32>(
+                "vsize",
+                |m: &Transaction| { &m.vsize },
+                |m: &mut Transaction| { &mut m.vsize },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_repeated_field_accessor::<_, ::protobuf::types::ProtobufTypeMessage<TokenTransfer>>(
+                "token_transfers",
+                |m: &Transaction| { &m.token_transfers },
+                |m: &mut Transaction| { &mut m.token_transfers },
+            ));
             ::protobuf::reflect::MessageDescriptor::new_pb_name::<Transaction>(
                 "Transaction",
                 fields,
@@ -540,6 +849,13 @@ impl ::protobuf::Clear for Transaction {
         self.status = Status::UNKNOWN;
         self.changes.clear();
         self.version = 0;
+        self.nonce_ref.clear();
+        self.replaced_by.clear();
+        self.internal_transfer = false;
+        self.inputs.clear();
+        self.outputs.clear();
+        self.vsize = 0;
+        self.token_transfers.clear();
         self.unknown_fields.clear();
     }
 }
@@ -557,165 +873,225 @@ impl ::protobuf::reflect::ProtobufValue for Transaction {
 }
 
 #[derive(PartialEq,Clone,Default)]
-pub struct TransactionMeta {
+pub struct TokenTransfer {
     // message fields
-    pub timestamp: u64,
-    pub blockchain: BlockchainId,
-    pub tx_id: ::std::string::String,
-    pub label: ::std::string::String,
-    pub raw: ::std::vec::Vec<u8>,
+    pub log_index: u32,
+    pub contract: ::std::string::String,
+    pub standard: TokenStandard,
+    pub from: ::std::string::String,
+    pub to: ::std::string::String,
+    pub amount: ::std::string::String,
+    pub token_id: ::std::string::String,
     // special fields
     pub unknown_fields: ::protobuf::UnknownFields,
     pub cached_size: ::protobuf::CachedSize,
 }
 
-impl<'a> ::std::default::Default for &'a TransactionMeta {
-    fn default() -> &'a TransactionMeta {
-        <TransactionMeta as ::protobuf::Message>::default_instance()
+impl<'a> ::std::default::Default for &'a TokenTransfer {
+    fn default() -> &'a TokenTransfer {
+        <TokenTransfer as ::protobuf::Message>::default_instance()
     }
 }
 
-impl TransactionMeta {
-    pub fn new() -> TransactionMeta {
+impl TokenTransfer {
+    pub fn new() -> TokenTransfer {
         ::std::default::Default::default()
     }
 
-    // uint64 timestamp = 1;
+    // uint32 log_index = 1;
 
 
-    pub fn get_timestamp(&self) -> u64 {
-        self.timestamp
+    pub fn get_log_index(&self) -> u32 {
+        self.log_index
     }
-    pub fn clear_timestamp(&mut self) {
-        self.timestamp = 0;
+    pub fn clear_log_index(&mut self) {
+        self.log_index = 0;
     }
 
     // Param is passed by value, moved
-    pub fn set_timestamp(&mut self, v: u64) {
-        self.timestamp = v;
+    pub fn set_log_index(&mut self, v: u32) {
+        self.log_index = v;
     }
 
-    // .emerald.state.BlockchainId blockchain = 2;
+    // string contract = 2;
 
 
-    pub fn get_blockchain(&self) -> BlockchainId {
-        self.blockchain
+    pub fn get_contract(&self) -> &str {
+        &self.contract
     }
-    pub fn clear_blockchain(&mut self) {
-        self.blockchain = BlockchainId::CHAIN_UNSPECIFIED;
+    pub fn clear_contract(&mut self) {
+        self.contract.clear();
     }
 
     // Param is passed by value, moved
-    pub fn set_blockchain(&mut self, v: BlockchainId) {
-        self.blockchain = v;
+    pub fn set_contract(&mut self, v: ::std::string::String) {
+        self.contract = v;
     }
 
-    // string tx_id = 3;
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_contract(&mut self) -> &mut ::std::string::String {
+        &mut self.contract
+    }
 
+    // Take field
+    pub fn take_contract(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.contract, ::std::string::String::new())
+    }
 
-    pub fn get_tx_id(&self) -> &str {
-        &self.tx_id
+    // .emerald.state.TokenStandard standard = 3;
+
+
+    pub fn get_standard(&self) -> TokenStandard {
+        self.standard
     }
-    pub fn clear_tx_id(&mut self) {
-        self.tx_id.clear();
+    pub fn clear_standard(&mut self) {
+        self.standard = TokenStandard::TOKEN_UNKNOWN;
     }
 
     // Param is passed by value, moved
-    pub fn set_tx_id(&mut self, v: ::std::string::String) {
-        self.tx_id = v;
+    pub fn set_standard(&mut self, v: TokenStandard) {
+        self.standard = v;
+    }
+
+    // string from = 4;
+
+
+    pub fn get_from(&self) -> &str {
+        &self.from
+    }
+    pub fn clear_from(&mut self) {
+        self.from.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_from(&mut self, v: ::std::string::String) {
+        self.from = v;
     }
 
     // Mutable pointer to the field.
     // If field is not initialized, it is initialized with default value first.
-    pub fn mut_tx_id(&mut self) -> &mut ::std::string::String {
-        &mut self.tx_id
+    pub fn mut_from(&mut self) -> &mut ::std::string::String {
+        &mut self.from
     }
 
     // Take field
-    pub fn take_tx_id(&mut self) -> ::std::string::String {
-        ::std::mem::replace(&mut self.tx_id, ::std::string::String::new())
+    pub fn take_from(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.from, ::std::string::String::new())
     }
 
-    // string label = 4;
+    // string to = 5;
 
 
-    pub fn get_label(&self) -> &str {
-        &self.label
+    pub fn get_to(&self) -> &str {
+        &self.to
     }
-    pub fn clear_label(&mut self) {
-        self.label.clear();
+    pub fn clear_to(&mut self) {
+        self.to.clear();
     }
 
     // Param is passed by value, moved
-    pub fn set_label(&mut self, v: ::std::string::String) {
-        self.label = v;
+    pub fn set_to(&mut self, v: ::std::string::String) {
+        self.to = v;
     }
 
     // Mutable pointer to the field.
     // If field is not initialized, it is initialized with default value first.
-    pub fn mut_label(&mut self) -> &mut ::std::string::String {
-        &mut self.label
+    pub fn mut_to(&mut self) -> &mut ::std::string::String {
+        &mut self.to
     }
 
     // Take field
-    pub fn take_label(&mut self) -> ::std::string::String {
-        ::std::mem::replace(&mut self.label, ::std::string::String::new())
+    pub fn take_to(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.to, ::std::string::String::new())
     }
 
-    // bytes raw = 5;
+    // string amount = 6;
 
 
-    pub fn get_raw(&self) -> &[u8] {
-        &self.raw
+    pub fn get_amount(&self) -> &str {
+        &self.amount
     }
-    pub fn clear_raw(&mut self) {
-        self.raw.clear();
+    pub fn clear_amount(&mut self) {
+        self.amount.clear();
     }
 
     // Param is passed by value, moved
-    pub fn set_raw(&mut self, v: ::std::vec::Vec<u8>) {
-        self.raw = v;
+    pub fn set_amount(&mut self, v: ::std::string::String) {
+        self.amount = v;
     }
 
     // Mutable pointer to the field.
     // If field is not initialized, it is initialized with default value first.
-    pub fn mut_raw(&mut self) -> &mut ::std::vec::Vec<u8> {
-        &mut self.raw
+    pub fn mut_amount(&mut self) -> &mut ::std::string::String {
+        &mut self.amount
     }
 
     // Take field
-    pub fn take_raw(&mut self) -> ::std::vec::Vec<u8> {
-        ::std::mem::replace(&mut self.raw, ::std::vec::Vec::new())
+    pub fn take_amount(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.amount, ::std::string::String::new())
     }
-}
 
-impl ::protobuf::Message for TransactionMeta {
-    fn is_initialized(&self) -> bool {
-        true
+    // string token_id = 7;
+
+
+    pub fn get_token_id(&self) -> &str {
+        &self.token_id
+    }
+    pub fn clear_token_id(&mut self) {
+        self.token_id.clear();
     }
 
-    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
-        while !is.eof()? {
-            let (field_number, wire_type) = is.read_tag_unpack()?;
-            match field_number {
-                1 => {
-                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+    // Param is passed by value, moved
+    pub fn set_token_id(&mut self, v: ::std::string::String) {
+        self.token_id = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_token_id(&mut self) -> &mut ::std::string::String {
+        &mut self.token_id
+    }
+
+    // Take field
+    pub fn take_token_id(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.token_id, ::std::string::String::new())
+    }
+}
+
+impl ::protobuf::Message for TokenTransfer {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
                         return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
                     }
-                    let tmp = is.read_uint64()?;
-                    self.timestamp = tmp;
+                    let tmp = is.read_uint32()?;
+                    self.log_index = tmp;
                 },
                 2 => {
-                    ::protobuf::rt::read_proto3_enum_with_unknown_fields_into(wire_type, is, &mut self.blockchain, 2, &mut self.unknown_fields)?
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.contract)?;
                 },
                 3 => {
-                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.tx_id)?;
+                    ::protobuf::rt::read_proto3_enum_with_unknown_fields_into(wire_type, is, &mut self.standard, 3, &mut self.unknown_fields)?
                 },
                 4 => {
-                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.label)?;
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.from)?;
                 },
                 5 => {
-                    ::protobuf::rt::read_singular_proto3_bytes_into(wire_type, is, &mut self.raw)?;
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.to)?;
+                },
+                6 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.amount)?;
+                },
+                7 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.token_id)?;
                 },
                 _ => {
                     ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
@@ -729,20 +1105,26 @@ impl ::protobuf::Message for TransactionMeta {
     #[allow(unused_variables)]
     fn compute_size(&self) -> u32 {
         let mut my_size = 0;
-        if self.timestamp != 0 {
-            my_size += ::protobuf::rt::value_size(1, self.timestamp, ::protobuf::wire_format::WireTypeVarint);
+        if self.log_index != 0 {
+            my_size += ::protobuf::rt::value_size(1, self.log_index, ::protobuf::wire_format::WireTypeVarint);
         }
-        if self.blockchain != BlockchainId::CHAIN_UNSPECIFIED {
-            my_size += ::protobuf::rt::enum_size(2, self.blockchain);
+        if !self.contract.is_empty() {
+            my_size += ::protobuf::rt::string_size(2, &self.contract);
         }
-        if !self.tx_id.is_empty() {
-            my_size += ::protobuf::rt::string_size(3, &self.tx_id);
+        if self.standard != TokenStandard::TOKEN_UNKNOWN {
+            my_size += ::protobuf::rt::enum_size(3, self.standard);
         }
-        if !self.label.is_empty() {
-            my_size += ::protobuf::rt::string_size(4, &self.label);
+        if !self.from.is_empty() {
+            my_size += ::protobuf::rt::string_size(4, &self.from);
         }
-        if !self.raw.is_empty() {
-            my_size += ::protobuf::rt::bytes_size(5, &self.raw);
+        if !self.to.is_empty() {
+            my_size += ::protobuf::rt::string_size(5, &self.to);
+        }
+        if !self.amount.is_empty() {
+            my_size += ::protobuf::rt::string_size(6, &self.amount);
+        }
+        if !self.token_id.is_empty() {
+            my_size += ::protobuf::rt::string_size(7, &self.token_id);
         }
         my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
         self.cached_size.set(my_size);
@@ -750,20 +1132,26 @@ impl ::protobuf::Message for TransactionMeta {
     }
 
     fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
-        if self.timestamp != 0 {
-            os.write_uint64(1, self.timestamp)?;
+        if self.log_index != 0 {
+            os.write_uint32(1, self.log_index)?;
         }
-        if self.blockchain != BlockchainId::CHAIN_UNSPECIFIED {
-            os.write_enum(2, ::protobuf::ProtobufEnum::value(&self.blockchain))?;
+        if !self.contract.is_empty() {
+            os.write_string(2, &self.contract)?;
         }
-        if !self.tx_id.is_empty() {
-            os.write_string(3, &self.tx_id)?;
+        if self.standard != TokenStandard::TOKEN_UNKNOWN {
+            os.write_enum(3, ::protobuf::ProtobufEnum::value(&self.standard))?;
         }
-        if !self.label.is_empty() {
-            os.write_string(4, &self.label)?;
+        if !self.from.is_empty() {
+            os.write_string(4, &self.from)?;
         }
-        if !self.raw.is_empty() {
-            os.write_bytes(5, &self.raw)?;
+        if !self.to.is_empty() {
+            os.write_string(5, &self.to)?;
+        }
+        if !self.amount.is_empty() {
+            os.write_string(6, &self.amount)?;
+        }
+        if !self.token_id.is_empty() {
+            os.write_string(7, &self.token_id)?;
         }
         os.write_unknown_fields(self.get_unknown_fields())?;
         ::std::result::Result::Ok(())
@@ -795,156 +1183,227 @@ impl ::protobuf::Message for TransactionMeta {
         Self::descriptor_static()
     }
 
-    fn new() -> TransactionMeta {
-        TransactionMeta::new()
+    fn new() -> TokenTransfer {
+        TokenTransfer::new()
     }
 
     fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
         static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
         descriptor.get(|| {
             let mut fields = ::std::vec::Vec::new();
-            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
-                "timestamp",
-                |m: &TransactionMeta| { &m.timestamp },
-                |m: &mut TransactionMeta| { &mut m.timestamp },
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint32>(
+                "log_index",
+                |m: &TokenTransfer| { &m.log_index },
+                |m: &mut TokenTransfer| { &mut m.log_index },
             ));
-            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeEnum<BlockchainId>>(
-                "blockchain",
-                |m: &TransactionMeta| { &m.blockchain },
-                |m: &mut TransactionMeta| { &mut m.blockchain },
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "contract",
+                |m: &TokenTransfer| { &m.contract },
+                |m: &mut TokenTransfer| { &mut m.contract },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeEnum<TokenStandard>>(
+                "standard",
+                |m: &TokenTransfer| { &m.standard },
+                |m: &mut TokenTransfer| { &mut m.standard },
             ));
             fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
-                "tx_id",
-                |m: &TransactionMeta| { &m.tx_id },
-                |m: &mut TransactionMeta| { &mut m.tx_id },
+                "from",
+                |m: &TokenTransfer| { &m.from },
+                |m: &mut TokenTransfer| { &mut m.from },
             ));
             fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
-                "label",
-                |m: &TransactionMeta| { &m.label },
-                |m: &mut TransactionMeta| { &mut m.label },
+                "to",
+                |m: &TokenTransfer| { &m.to },
+                |m: &mut TokenTransfer| { &mut m.to },
             ));
-            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
-                "raw",
-                |m: &TransactionMeta| { &m.raw },
-                |m: &mut TransactionMeta| { &mut m.raw },
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "amount",
+                |m: &TokenTransfer| { &m.amount },
+                |m: &mut TokenTransfer| { &mut m.amount },
             ));
-            ::protobuf::reflect::MessageDescriptor::new_pb_name::<TransactionMeta>(
-                "TransactionMeta",
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "token_id",
+                |m: &TokenTransfer| { &m.token_id },
+                |m: &mut TokenTransfer| { &mut m.token_id },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<TokenTransfer>(
+                "TokenTransfer",
                 fields,
                 file_descriptor_proto()
             )
         })
     }
 
-    fn default_instance() -> &'static TransactionMeta {
-        static instance: ::protobuf::rt::LazyV2<TransactionMeta> = ::protobuf::rt::LazyV2::INIT;
-        instance.get(TransactionMeta::new)
+    fn default_instance() -> &'static TokenTransfer {
+        static instance: ::protobuf::rt::LazyV2<TokenTransfer> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(TokenTransfer::new)
     }
 }
 
-impl ::protobuf::Clear for TransactionMeta {
+impl ::protobuf::Clear for TokenTransfer {
     fn clear(&mut self) {
-        self.timestamp = 0;
-        self.blockchain = BlockchainId::CHAIN_UNSPECIFIED;
-        self.tx_id.clear();
-        self.label.clear();
-        self.raw.clear();
+        self.log_index = 0;
+        self.contract.clear();
+        self.standard = TokenStandard::TOKEN_UNKNOWN;
+        self.from.clear();
+        self.to.clear();
+        self.amount.clear();
+        self.token_id.clear();
         self.unknown_fields.clear();
     }
 }
 
-impl ::std::fmt::Debug for TransactionMeta {
+impl ::std::fmt::Debug for TokenTransfer {
     fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
         ::protobuf::text_format::fmt(self, f)
     }
 }
 
-impl ::protobuf::reflect::ProtobufValue for TransactionMeta {
+impl ::protobuf::reflect::ProtobufValue for TokenTransfer {
     fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
         ::protobuf::reflect::ReflectValueRef::Message(self)
     }
 }
 
 #[derive(PartialEq,Clone,Default)]
-pub struct BlockRef {
+pub struct BitcoinInput {
     // message fields
-    pub height: u64,
-    pub block_id: ::std::string::String,
-    pub timestamp: u64,
+    pub txid: ::std::string::String,
+    pub vout: u32,
+    pub amount: u64,
+    pub script_type: ScriptType,
+    pub address: ::std::string::String,
+    pub own: bool,
     // special fields
     pub unknown_fields: ::protobuf::UnknownFields,
     pub cached_size: ::protobuf::CachedSize,
 }
 
-impl<'a> ::std::default::Default for &'a BlockRef {
-    fn default() -> &'a BlockRef {
-        <BlockRef as ::protobuf::Message>::default_instance()
+impl<'a> ::std::default::Default for &'a BitcoinInput {
+    fn default() -> &'a BitcoinInput {
+        <BitcoinInput as ::protobuf::Message>::default_instance()
     }
 }
 
-impl BlockRef {
-    pub fn new() -> BlockRef {
+impl BitcoinInput {
+    pub fn new() -> BitcoinInput {
         ::std::default::Default::default()
     }
 
-    // uint64 height = 1;
+    // string txid = 1;
 
 
-    pub fn get_height(&self) -> u64 {
-        self.height
+    pub fn get_txid(&self) -> &str {
+        &self.txid
     }
-    pub fn clear_height(&mut self) {
-        self.height = 0;
+    pub fn clear_txid(&mut self) {
+        self.txid.clear();
     }
 
     // Param is passed by value, moved
-    pub fn set_height(&mut self, v: u64) {
-        self.height = v;
+    pub fn set_txid(&mut self, v: ::std::string::String) {
+        self.txid = v;
     }
 
-    // string block_id = 2;
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_txid(&mut self) -> &mut ::std::string::String {
+        &mut self.txid
+    }
+
+    // Take field
+    pub fn take_txid(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.txid, ::std::string::String::new())
+    }
 
+    // uint32 vout = 2;
 
-    pub fn get_block_id(&self) -> &str {
-        &self.block_id
+
+    pub fn get_vout(&self) -> u32 {
+        self.vout
     }
-    pub fn clear_block_id(&mut self) {
-        self.block_id.clear();
+    pub fn clear_vout(&mut self) {
+        self.vout = 0;
     }
 
     // Param is passed by value, moved
-    pub fn set_block_id(&mut self, v: ::std::string::String) {
-        self.block_id = v;
+    pub fn set_vout(&mut self, v: u32) {
+        self.vout = v;
+    }
+
+    // uint64 amount = 3;
+
+
+    pub fn get_amount(&self) -> u64 {
+        self.amount
+    }
+    pub fn clear_amount(&mut self) {
+        self.amount = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_amount(&mut self, v: u64) {
+        self.amount = v;
+    }
+
+    // .emerald.state.ScriptType script_type = 4;
+
+
+    pub fn get_script_type(&self) -> ScriptType {
+        self.script_type
+    }
+    pub fn clear_script_type(&mut self) {
+        self.script_type = ScriptType::SCRIPT_UNKNOWN;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_script_type(&mut self, v: ScriptType) {
+        self.script_type = v;
+    }
+
+    // string address = 5;
+
+
+    pub fn get_address(&self) -> &str {
+        &self.address
+    }
+    pub fn clear_address(&mut self) {
+        self.address.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_address(&mut self, v: ::std::string::String) {
+        self.address = v;
     }
 
     // Mutable pointer to the field.
     // If field is not initialized, it is initialized with default value first.
-    pub fn mut_block_id(&mut self) -> &mut ::std::string::String {
-        &mut self.block_id
+    pub fn mut_address(&mut self) -> &mut ::std::string::String {
+        &mut self.address
     }
 
     // Take field
-    pub fn take_block_id(&mut self) -> ::std::string::String {
-        ::std::mem::replace(&mut self.block_id, ::std::string::String::new())
+    pub fn take_address(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.address, ::std::string::String::new())
     }
 
-    // uint64 timestamp = 3;
+    // bool own = 6;
 
 
-    pub fn get_timestamp(&self) -> u64 {
-        self.timestamp
+    pub fn get_own(&self) -> bool {
+        self.own
     }
-    pub fn clear_timestamp(&mut self) {
-        self.timestamp = 0;
+    pub fn clear_own(&mut self) {
+        self.own = false;
     }
 
     // Param is passed by value, moved
-    pub fn set_timestamp(&mut self, v: u64) {
-        self.timestamp = v;
+    pub fn set_own(&mut self, v: bool) {
+        self.own = v;
     }
 }
 
-impl ::protobuf::Message for BlockRef {
+impl ::protobuf::Message for BitcoinInput {
     fn is_initialized(&self) -> bool {
         true
     }
@@ -954,21 +1413,34 @@ impl ::protobuf::Message for BlockRef {
             let (field_number, wire_type) = is.read_tag_unpack()?;
             match field_number {
                 1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.txid)?;
+                },
+                2 => {
                     if wire_type != ::protobuf::wire_format::WireTypeVarint {
                         return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
                     }
-                    let tmp = is.read_uint64()?;
-                    self.height = tmp;
-                },
-                2 => {
-                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.block_id)?;
+                    let tmp = is.read_uint32()?;
+                    self.vout = tmp;
                 },
                 3 => {
                     if wire_type != ::protobuf::wire_format::WireTypeVarint {
                         return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
                     }
                     let tmp = is.read_uint64()?;
-                    self.timestamp = tmp;
+                    self.amount = tmp;
+                },
+                4 => {
+                    ::protobuf::rt::read_proto3_enum_with_unknown_fields_into(wire_type, is, &mut self.script_type, 4, &mut self.unknown_fields)?
+                },
+                5 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.address)?;
+                },
+                6 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_bool()?;
+                    self.own = tmp;
                 },
                 _ => {
                     ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
@@ -982,14 +1454,23 @@ impl ::protobuf::Message for BlockRef {
     #[allow(unused_variables)]
     fn compute_size(&self) -> u32 {
         let mut my_size = 0;
-        if self.height != 0 {
-            my_size += ::protobuf::rt::value_size(1, self.height, ::protobuf::wire_format::WireTypeVarint);
+        if !self.txid.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.txid);
         }
-        if !self.block_id.is_empty() {
-            my_size += ::protobuf::rt::string_size(2, &self.block_id);
+        if self.vout != 0 {
+            my_size += ::protobuf::rt::value_size(2, self.vout, ::protobuf::wire_format::WireTypeVarint);
         }
-        if self.timestamp != 0 {
-            my_size += ::protobuf::rt::value_size(3, self.timestamp, ::protobuf::wire_format::WireTypeVarint);
+        if self.amount != 0 {
+            my_size += ::protobuf::rt::value_size(3, self.amount, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.script_type != ScriptType::SCRIPT_UNKNOWN {
+            my_size += ::protobuf::rt::enum_size(4, self.script_type);
+        }
+        if !self.address.is_empty() {
+            my_size += ::protobuf::rt::string_size(5, &self.address);
+        }
+        if self.own != false {
+            my_size += 2;
         }
         my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
         self.cached_size.set(my_size);
@@ -997,14 +1478,23 @@ impl ::protobuf::Message for BlockRef {
     }
 
     fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
-        if self.height != 0 {
-            os.write_uint64(1, self.height)?;
+        if !self.txid.is_empty() {
+            os.write_string(1, &self.txid)?;
         }
-        if !self.block_id.is_empty() {
-            os.write_string(2, &self.block_id)?;
+        if self.vout != 0 {
+            os.write_uint32(2, self.vout)?;
         }
-        if self.timestamp != 0 {
-            os.write_uint64(3, self.timestamp)?;
+        if self.amount != 0 {
+            os.write_uint64(3, self.amount)?;
+        }
+        if self.script_type != ScriptType::SCRIPT_UNKNOWN {
+            os.write_enum(4, ::protobuf::ProtobufEnum::value(&self.script_type))?;
+        }
+        if !self.address.is_empty() {
+            os.write_string(5, &self.address)?;
+        }
+        if self.own != false {
+            os.write_bool(6, self.own)?;
         }
         os.write_unknown_fields(self.get_unknown_fields())?;
         ::std::result::Result::Ok(())
@@ -1036,72 +1526,1653 @@ impl ::protobuf::Message for BlockRef {
         Self::descriptor_static()
     }
 
-    fn new() -> BlockRef {
-        BlockRef::new()
+    fn new() -> BitcoinInput {
+        BitcoinInput::new()
     }
 
     fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
         static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
         descriptor.get(|| {
             let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "txid",
+                |m: &BitcoinInput| { &m.txid },
+                |m: &mut BitcoinInput| { &mut m.txid },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint32>(
+                "vout",
+                |m: &BitcoinInput| { &m.vout },
+                |m: &mut BitcoinInput| { &mut m.vout },
+            ));
             fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
-                "height",
-                |m: &BlockRef| { &m.height },
-                |m: &mut BlockRef| { &mut m.height },
+                "amount",
+                |m: &BitcoinInput| { &m.amount },
+                |m: &mut BitcoinInput| { &mut m.amount },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeEnum<ScriptType>>(
+                "script_type",
+                |m: &BitcoinInput| { &m.script_type },
+                |m: &mut BitcoinInput| { &mut m.script_type },
             ));
             fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
-                "block_id",
-                |m: &BlockRef| { &m.block_id },
-                |m: &mut BlockRef| { &mut m.block_id },
+                "address",
+                |m: &BitcoinInput| { &m.address },
+                |m: &mut BitcoinInput| { &mut m.address },
             ));
-            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
-                "timestamp",
-                |m: &BlockRef| { &m.timestamp },
-                |m: &mut BlockRef| { &mut m.timestamp },
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBool>(
+                "own",
+                |m: &BitcoinInput| { &m.own },
+                |m: &mut BitcoinInput| { &mut m.own },
             ));
-            ::protobuf::reflect::MessageDescriptor::new_pb_name::<BlockRef>(
-                "BlockRef",
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<BitcoinInput>(
+                "BitcoinInput",
                 fields,
                 file_descriptor_proto()
             )
         })
     }
 
-    fn default_instance() -> &'static BlockRef {
-        static instance: ::protobuf::rt::LazyV2<BlockRef> = ::protobuf::rt::LazyV2::INIT;
-        instance.get(BlockRef::new)
+    fn default_instance() -> &'static BitcoinInput {
+        static instance: ::protobuf::rt::LazyV2<BitcoinInput> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(BitcoinInput::new)
     }
 }
 
-impl ::protobuf::Clear for BlockRef {
+impl ::protobuf::Clear for BitcoinInput {
     fn clear(&mut self) {
-        self.height = 0;
-        self.block_id.clear();
-        self.timestamp = 0;
+        self.txid.clear();
+        self.vout = 0;
+        self.amount = 0;
+        self.script_type = ScriptType::SCRIPT_UNKNOWN;
+        self.address.clear();
+        self.own = false;
         self.unknown_fields.clear();
     }
 }
 
-impl ::std::fmt::Debug for BlockRef {
+impl ::std::fmt::Debug for BitcoinInput {
     fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
         ::protobuf::text_format::fmt(self, f)
     }
 }
 
-impl ::protobuf::reflect::ProtobufValue for BlockRef {
+impl ::protobuf::reflect::ProtobufValue for BitcoinInput {
     fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
         ::protobuf::reflect::ReflectValueRef::Message(self)
     }
 }
 
 #[derive(PartialEq,Clone,Default)]
-pub struct Change {
+pub struct BitcoinOutput {
     // message fields
-    pub wallet_id: ::std::string::String,
-    pub entry_id: u32,
+    pub vout: u32,
+    pub amount: u64,
+    pub script_type: ScriptType,
     pub address: ::std::string::String,
-    pub hd_path: ::std::string::String,
-    pub asset: ::std::string::String,
+    pub own: bool,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a BitcoinOutput {
+    fn default() -> &'a BitcoinOutput {
+        <BitcoinOutput as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl BitcoinOutput {
+    pub fn new() -> BitcoinOutput {
+        ::std::default::Default::default()
+    }
+
+    // uint32 vout = 1;
+
+
+    pub fn get_vout(&self) -> u32 {
+        self.vout
+    }
+    pub fn clear_vout(&mut self) {
+        self.vout = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_vout(&mut self, v: u32) {
+        self.vout = v;
+    }
+
+    // uint64 amount = 2;
+
+
+    pub fn get_amount(&self) -> u64 {
+        self.amount
+    }
+    pub fn clear_amount(&mut self) {
+        self.amount = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_amount(&mut self, v: u64) {
+        self.amount = v;
+    }
+
+    // .emerald.state.ScriptType script_type = 3;
+
+
+    pub fn get_script_type(&self) -> ScriptType {
+        self.script_type
+    }
+    pub fn clear_script_type(&mut self) {
+        self.script_type = ScriptType::SCRIPT_UNKNOWN;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_script_type(&mut self, v: ScriptType) {
+        self.script_type = v;
+    }
+
+    // string address = 4;
+
+
+    pub fn get_address(&self) -> &str {
+        &self.address
+    }
+    pub fn clear_address(&mut self) {
+        self.address.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_address(&mut self, v: ::std::string::String) {
+        self.address = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_address(&mut self) -> &mut ::std::string::String {
+        &mut self.address
+    }
+
+    // Take field
+    pub fn take_address(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.address, ::std::string::String::new())
+    }
+
+    // bool own = 5;
+
+
+    pub fn get_own(&self) -> bool {
+        self.own
+    }
+    pub fn clear_own(&mut self) {
+        self.own = false;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_own(&mut self, v: bool) {
+        self.own = v;
+    }
+}
+
+impl ::protobuf::Message for BitcoinOutput {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint32()?;
+                    self.vout = tmp;
+                },
+                2 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.amount = tmp;
+                },
+                3 => {
+                    ::protobuf::rt::read_proto3_enum_with_unknown_fields_into(wire_type, is, &mut self.script_type, 3, &mut self.unknown_fields)?
+                },
+                4 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.address)?;
+                },
+                5 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_bool()?;
+                    self.own = tmp;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if self.vout != 0 {
+            my_size += ::protobuf::rt::value_size(1, self.vout, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.amount != 0 {
+            my_size += ::protobuf::rt::value_size(2, self.amount, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.script_type != ScriptType::SCRIPT_UNKNOWN {
+            my_size += ::protobuf::rt::enum_size(3, self.script_type);
+        }
+        if !self.address.is_empty() {
+            my_size += ::protobuf::rt::string_size(4, &self.address);
+        }
+        if self.own != false {
+            my_size += 2;
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if self.vout != 0 {
+            os.write_uint32(1, self.vout)?;
+        }
+        if self.amount != 0 {
+            os.write_uint64(2, self.amount)?;
+        }
+        if self.script_type != ScriptType::SCRIPT_UNKNOWN {
+            os.write_enum(3, ::protobuf::ProtobufEnum::value(&self.script_type))?;
+        }
+        if !self.address.is_empty() {
+            os.write_string(4, &self.address)?;
+        }
+        if self.own != false {
+            os.write_bool(5, self.own)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> BitcoinOutput {
+        BitcoinOutput::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint32>(
+                "vout",
+                |m: &BitcoinOutput| { &m.vout },
+                |m: &mut BitcoinOutput| { &mut m.vout },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                "amount",
+                |m: &BitcoinOutput| { &m.amount },
+                |m: &mut BitcoinOutput| { &mut m.amount },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeEnum<ScriptType>>(
+                "script_type",
+                |m: &BitcoinOutput| { &m.script_type },
+                |m: &mut BitcoinOutput| { &mut m.script_type },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "address",
+                |m: &BitcoinOutput| { &m.address },
+                |m: &mut BitcoinOutput| { &mut m.address },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBool>(
+                "own",
+                |m: &BitcoinOutput| { &m.own },
+                |m: &mut BitcoinOutput| { &mut m.own },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<BitcoinOutput>(
+                "BitcoinOutput",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static BitcoinOutput {
+        static instance: ::protobuf::rt::LazyV2<BitcoinOutput> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(BitcoinOutput::new)
+    }
+}
+
+impl ::protobuf::Clear for BitcoinOutput {
+    fn clear(&mut self) {
+        self.vout = 0;
+        self.amount = 0;
+        self.script_type = ScriptType::SCRIPT_UNKNOWN;
+        self.address.clear();
+        self.own = false;
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for BitcoinOutput {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for BitcoinOutput {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct NonceRef {
+    // message fields
+    pub from: ::std::string::String,
+    pub nonce: u64,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a NonceRef {
+    fn default() -> &'a NonceRef {
+        <NonceRef as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl NonceRef {
+    pub fn new() -> NonceRef {
+        ::std::default::Default::default()
+    }
+
+    // string from = 1;
+
+
+    pub fn get_from(&self) -> &str {
+        &self.from
+    }
+    pub fn clear_from(&mut self) {
+        self.from.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_from(&mut self, v: ::std::string::String) {
+        self.from = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_from(&mut self) -> &mut ::std::string::String {
+        &mut self.from
+    }
+
+    // Take field
+    pub fn take_from(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.from, ::std::string::String::new())
+    }
+
+    // uint64 nonce = 2;
+
+
+    pub fn get_nonce(&self) -> u64 {
+        self.nonce
+    }
+    pub fn clear_nonce(&mut self) {
+        self.nonce = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_nonce(&mut self, v: u64) {
+        self.nonce = v;
+    }
+}
+
+impl ::protobuf::Message for NonceRef {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.from)?;
+                },
+                2 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.nonce = tmp;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.from.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.from);
+        }
+        if self.nonce != 0 {
+            my_size += ::protobuf::rt::value_size(2, self.nonce, ::protobuf::wire_format::WireTypeVarint);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.from.is_empty() {
+            os.write_string(1, &self.from)?;
+        }
+        if self.nonce != 0 {
+            os.write_uint64(2, self.nonce)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> NonceRef {
+        NonceRef::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "from",
+                |m: &NonceRef| { &m.from },
+                |m: &mut NonceRef| { &mut m.from },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                "nonce",
+                |m: &NonceRef| { &m.nonce },
+                |m: &mut NonceRef| { &mut m.nonce },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<NonceRef>(
+                "NonceRef",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static NonceRef {
+        static instance: ::protobuf::rt::LazyV2<NonceRef> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(NonceRef::new)
+    }
+}
+
+impl ::protobuf::Clear for NonceRef {
+    fn clear(&mut self) {
+        self.from.clear();
+        self.nonce = 0;
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for NonceRef {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for NonceRef {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct TransactionMeta {
+    // message fields
+    pub timestamp: u64,
+    pub blockchain: BlockchainId,
+    pub tx_id: ::std::string::String,
+    pub label: ::std::string::String,
+    pub raw: ::std::vec::Vec<u8>,
+    pub tags: ::protobuf::RepeatedField<::std::string::String>,
+    pub raw_hash: ::std::vec::Vec<u8>,
+    pub starred: bool,
+    pub hidden: bool,
+    pub needs_review: bool,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a TransactionMeta {
+    fn default() -> &'a TransactionMeta {
+        <TransactionMeta as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl TransactionMeta {
+    pub fn new() -> TransactionMeta {
+        ::std::default::Default::default()
+    }
+
+    // uint64 timestamp = 1;
+
+
+    pub fn get_timestamp(&self) -> u64 {
+        self.timestamp
+    }
+    pub fn clear_timestamp(&mut self) {
+        self.timestamp = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_timestamp(&mut self, v: u64) {
+        self.timestamp = v;
+    }
+
+    // .emerald.state.BlockchainId blockchain = 2;
+
+
+    pub fn get_blockchain(&self) -> BlockchainId {
+        self.blockchain
+    }
+    pub fn clear_blockchain(&mut self) {
+        self.blockchain = BlockchainId::CHAIN_UNSPECIFIED;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_blockchain(&mut self, v: BlockchainId) {
+        self.blockchain = v;
+    }
+
+    // string tx_id = 3;
+
+
+    pub fn get_tx_id(&self) -> &str {
+        &self.tx_id
+    }
+    pub fn clear_tx_id(&mut self) {
+        self.tx_id.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_tx_id(&mut self, v: ::std::string::String) {
+        self.tx_id = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_tx_id(&mut self) -> &mut ::std::string::String {
+        &mut self.tx_id
+    }
+
+    // Take field
+    pub fn take_tx_id(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.tx_id, ::std::string::String::new())
+    }
+
+    // string label = 4;
+
+
+    pub fn get_label(&self) -> &str {
+        &self.label
+    }
+    pub fn clear_label(&mut self) {
+        self.label.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_label(&mut self, v: ::std::string::String) {
+        self.label = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_label(&mut self) -> &mut ::std::string::String {
+        &mut self.label
+    }
+
+    // Take field
+    pub fn take_label(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.label, ::std::string::String::new())
+    }
+
+    // bytes raw = 5;
+
+
+    pub fn get_raw(&self) -> &[u8] {
+        &self.raw
+    }
+    pub fn clear_raw(&mut self) {
+        self.raw.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_raw(&mut self, v: ::std::vec::Vec<u8>) {
+        self.raw = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_raw(&mut self) -> &mut ::std::vec::Vec<u8> {
+        &mut self.raw
+    }
+
+    // Take field
+    pub fn take_raw(&mut self) -> ::std::vec::Vec<u8> {
+        ::std::mem::replace(&mut self.raw, ::std::vec::Vec::new())
+    }
+
+    // repeated string tags = 6;
+
+
+    pub fn get_tags(&self) -> &[::std::string::String] {
+        &self.tags
+    }
+    pub fn clear_tags(&mut self) {
+        self.tags.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_tags(&mut self, v: ::protobuf::RepeatedField<::std::string::String>) {
+        self.tags = v;
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_tags(&mut self) -> &mut ::protobuf::RepeatedField<::std::string::String> {
+        &mut self.tags
+    }
+
+    // Take field
+    pub fn take_tags(&mut self) -> ::protobuf::RepeatedField<::std::string::String> {
+        ::std::mem::replace(&mut self.tags, ::protobuf::RepeatedField::new())
+    }
+
+    // bytes raw_hash = 7;
+
+
+    pub fn get_raw_hash(&self) -> &[u8] {
+        &self.raw_hash
+    }
+    pub fn clear_raw_hash(&mut self) {
+        self.raw_hash.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_raw_hash(&mut self, v: ::std::vec::Vec<u8>) {
+        self.raw_hash = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_raw_hash(&mut self) -> &mut ::std::vec::Vec<u8> {
+        &mut self.raw_hash
+    }
+
+    // Take field
+    pub fn take_raw_hash(&mut self) -> ::std::vec::Vec<u8> {
+        ::std::mem::replace(&mut self.raw_hash, ::std::vec::Vec::new())
+    }
+
+    // bool starred = 8;
+
+
+    pub fn get_starred(&self) -> bool {
+        self.starred
+    }
+    pub fn clear_starred(&mut self) {
+        self.starred = false;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_starred(&mut self, v: bool) {
+        self.starred = v;
+    }
+
+    // bool hidden = 9;
+
+
+    pub fn get_hidden(&self) -> bool {
+        self.hidden
+    }
+    pub fn clear_hidden(&mut self) {
+        self.hidden = false;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_hidden(&mut self, v: bool) {
+        self.hidden = v;
+    }
+
+    // bool needs_review = 10;
+
+
+    pub fn get_needs_review(&self) -> bool {
+        self.needs_review
+    }
+    pub fn clear_needs_review(&mut self) {
+        self.needs_review = false;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_needs_review(&mut self, v: bool) {
+        self.needs_review = v;
+    }
+}
+
+impl ::protobuf::Message for TransactionMeta {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.timestamp = tmp;
+                },
+                2 => {
+                    ::protobuf::rt::read_proto3_enum_with_unknown_fields_into(wire_type, is, &mut self.blockchain, 2, &mut self.unknown_fields)?
+                },
+                3 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.tx_id)?;
+                },
+                4 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.label)?;
+                },
+                5 => {
+                    ::protobuf::rt::read_singular_proto3_bytes_into(wire_type, is, &mut self.raw)?;
+                },
+                6 => {
+                    ::protobuf::rt::read_repeated_string_into(wire_type, is, &mut self.tags)?;
+                },
+                7 => {
+                    ::protobuf::rt::read_singular_proto3_bytes_into(wire_type, is, &mut self.raw_hash)?;
+                },
+                8 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_bool()?;
+                    self.starred = tmp;
+                },
+                9 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_bool()?;
+                    self.hidden = tmp;
+                },
+                10 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_bool()?;
+                    self.needs_review = tmp;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if self.timestamp != 0 {
+            my_size += ::protobuf::rt::value_size(1, self.timestamp, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.blockchain != BlockchainId::CHAIN_UNSPECIFIED {
+            my_size += ::protobuf::rt::enum_size(2, self.blockchain);
+        }
+        if !self.tx_id.is_empty() {
+            my_size += ::protobuf::rt::string_size(3, &self.tx_id);
+        }
+        if !self.label.is_empty() {
+            my_size += ::protobuf::rt::string_size(4, &self.label);
+        }
+        if !self.raw.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(5, &self.raw);
+        }
+        for value in &self.tags {
+            my_size += ::protobuf::rt::string_size(6, &value);
+        };
+        if !self.raw_hash.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(7, &self.raw_hash);
+        }
+        if self.starred != false {
+            my_size += 2;
+        }
+        if self.hidden != false {
+            my_size += 2;
+        }
+        if self.needs_review != false {
+            my_size += 2;
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if self.timestamp != 0 {
+            os.write_uint64(1, self.timestamp)?;
+        }
+        if self.blockchain != BlockchainId::CHAIN_UNSPECIFIED {
+            os.write_enum(2, ::protobuf::ProtobufEnum::value(&self.blockchain))?;
+        }
+        if !self.tx_id.is_empty() {
+            os.write_string(3, &self.tx_id)?;
+        }
+        if !self.label.is_empty() {
+            os.write_string(4, &self.label)?;
+        }
+        if !self.raw.is_empty() {
+            os.write_bytes(5, &self.raw)?;
+        }
+        for v in &self.tags {
+            os.write_string(6, &v)?;
+        };
+        if !self.raw_hash.is_empty() {
+            os.write_bytes(7, &self.raw_hash)?;
+        }
+        if self.starred != false {
+            os.write_bool(8, self.starred)?;
+        }
+        if self.hidden != false {
+            os.write_bool(9, self.hidden)?;
+        }
+        if self.needs_review != false {
+            os.write_bool(10, self.needs_review)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> TransactionMeta {
+        TransactionMeta::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                "timestamp",
+                |m: &TransactionMeta| { &m.timestamp },
+                |m: &mut TransactionMeta| { &mut m.timestamp },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeEnum<BlockchainId>>(
+                "blockchain",
+                |m: &TransactionMeta| { &m.blockchain },
+                |m: &mut TransactionMeta| { &mut m.blockchain },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "tx_id",
+                |m: &TransactionMeta| { &m.tx_id },
+                |m: &mut TransactionMeta| { &mut m.tx_id },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "label",
+                |m: &TransactionMeta| { &m.label },
+                |m: &mut TransactionMeta| { &mut m.label },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
+                "raw",
+                |m: &TransactionMeta| { &m.raw },
+                |m: &mut TransactionMeta| { &mut m.raw },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_repeated_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "tags",
+                |m: &TransactionMeta| { &m.tags },
+                |m: &mut TransactionMeta| { &mut m.tags },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
+                "raw_hash",
+                |m: &TransactionMeta| { &m.raw_hash },
+                |m: &mut TransactionMeta| { &mut m.raw_hash },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBool>(
+                "starred",
+                |m: &TransactionMeta| { &m.starred },
+                |m: &mut TransactionMeta| { &mut m.starred },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBool>(
+                "hidden",
+                |m: &TransactionMeta| { &m.hidden },
+                |m: &mut TransactionMeta| { &mut m.hidden },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBool>(
+                "needs_review",
+                |m: &TransactionMeta| { &m.needs_review },
+                |m: &mut TransactionMeta| { &mut m.needs_review },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<TransactionMeta>(
+                "TransactionMeta",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static TransactionMeta {
+        static instance: ::protobuf::rt::LazyV2<TransactionMeta> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(TransactionMeta::new)
+    }
+}
+
+impl ::protobuf::Clear for TransactionMeta {
+    fn clear(&mut self) {
+        self.timestamp = 0;
+        self.blockchain = BlockchainId::CHAIN_UNSPECIFIED;
+        self.tx_id.clear();
+        self.label.clear();
+        self.raw.clear();
+        self.tags.clear();
+        self.raw_hash.clear();
+        self.starred = false;
+        self.hidden = false;
+        self.needs_review = false;
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for TransactionMeta {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for TransactionMeta {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct MetaRevision {
+    // message fields
+    pub seq: u64,
+    pub recorded_at: u64,
+    pub meta: ::std::vec::Vec<u8>,
+    pub prev_hash: ::std::vec::Vec<u8>,
+    pub hash: ::std::vec::Vec<u8>,
+    pub write_seq: u64,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a MetaRevision {
+    fn default() -> &'a MetaRevision {
+        <MetaRevision as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl MetaRevision {
+    pub fn new() -> MetaRevision {
+        ::std::default::Default::default()
+    }
+
+    // uint64 seq = 1;
+
+
+    pub fn get_seq(&self) -> u64 {
+        self.seq
+    }
+    pub fn clear_seq(&mut self) {
+        self.seq = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_seq(&mut self, v: u64) {
+        self.seq = v;
+    }
+
+    // uint64 recorded_at = 2;
+
+
+    pub fn get_recorded_at(&self) -> u64 {
+        self.recorded_at
+    }
+    pub fn clear_recorded_at(&mut self) {
+        self.recorded_at = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_recorded_at(&mut self, v: u64) {
+        self.recorded_at = v;
+    }
+
+    // bytes meta = 3;
+
+
+    pub fn get_meta(&self) -> &[u8] {
+        &self.meta
+    }
+    pub fn clear_meta(&mut self) {
+        self.meta.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_meta(&mut self, v: ::std::vec::Vec<u8>) {
+        self.meta = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_meta(&mut self) -> &mut ::std::vec::Vec<u8> {
+        &mut self.meta
+    }
+
+    // Take field
+    pub fn take_meta(&mut self) -> ::std::vec::Vec<u8> {
+        ::std::mem::replace(&mut self.meta, ::std::vec::Vec::new())
+    }
+
+    // bytes prev_hash = 4;
+
+
+    pub fn get_prev_hash(&self) -> &[u8] {
+        &self.prev_hash
+    }
+    pub fn clear_prev_hash(&mut self) {
+        self.prev_hash.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_prev_hash(&mut self, v: ::std::vec::Vec<u8>) {
+        self.prev_hash = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_prev_hash(&mut self) -> &mut ::std::vec::Vec<u8> {
+        &mut self.prev_hash
+    }
+
+    // Take field
+    pub fn take_prev_hash(&mut self) -> ::std::vec::Vec<u8> {
+        ::std::mem::replace(&mut self.prev_hash, ::std::vec::Vec::new())
+    }
+
+    // bytes hash = 5;
+
+
+    pub fn get_hash(&self) -> &[u8] {
+        &self.hash
+    }
+    pub fn clear_hash(&mut self) {
+        self.hash.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_hash(&mut self, v: ::std::vec::Vec<u8>) {
+        self.hash = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_hash(&mut self) -> &mut ::std::vec::Vec<u8> {
+        &mut self.hash
+    }
+
+    // Take field
+    pub fn take_hash(&mut self) -> ::std::vec::Vec<u8> {
+        ::std::mem::replace(&mut self.hash, ::std::vec::Vec::new())
+    }
+
+    // uint64 write_seq = 6;
+
+
+    pub fn get_write_seq(&self) -> u64 {
+        self.write_seq
+    }
+    pub fn clear_write_seq(&mut self) {
+        self.write_seq = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_write_seq(&mut self, v: u64) {
+        self.write_seq = v;
+    }
+}
+
+impl ::protobuf::Message for MetaRevision {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.seq = tmp;
+                },
+                2 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.recorded_at = tmp;
+                },
+                3 => {
+                    ::protobuf::rt::read_singular_proto3_bytes_into(wire_type, is, &mut self.meta)?;
+                },
+                4 => {
+                    ::protobuf::rt::read_singular_proto3_bytes_into(wire_type, is, &mut self.prev_hash)?;
+                },
+                5 => {
+                    ::protobuf::rt::read_singular_proto3_bytes_into(wire_type, is, &mut self.hash)?;
+                },
+                6 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.write_seq = tmp;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if self.seq != 0 {
+            my_size += ::protobuf::rt::value_size(1, self.seq, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.recorded_at != 0 {
+            my_size += ::protobuf::rt::value_size(2, self.recorded_at, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if !self.meta.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(3, &self.meta);
+        }
+        if !self.prev_hash.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(4, &self.prev_hash);
+        }
+        if !self.hash.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(5, &self.hash);
+        }
+        if self.write_seq != 0 {
+            my_size += ::protobuf::rt::value_size(6, self.write_seq, ::protobuf::wire_format::WireTypeVarint);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if self.seq != 0 {
+            os.write_uint64(1, self.seq)?;
+        }
+        if self.recorded_at != 0 {
+            os.write_uint64(2, self.recorded_at)?;
+        }
+        if !self.meta.is_empty() {
+            os.write_bytes(3, &self.meta)?;
+        }
+        if !self.prev_hash.is_empty() {
+            os.write_bytes(4, &self.prev_hash)?;
+        }
+        if !self.hash.is_empty() {
+            os.write_bytes(5, &self.hash)?;
+        }
+        if self.write_seq != 0 {
+            os.write_uint64(6, self.write_seq)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> MetaRevision {
+        MetaRevision::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                "seq",
+                |m: &MetaRevision| { &m.seq },
+                |m: &mut MetaRevision| { &mut m.seq },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                "recorded_at",
+                |m: &MetaRevision| { &m.recorded_at },
+                |m: &mut MetaRevision| { &mut m.recorded_at },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
+                "meta",
+                |m: &MetaRevision| { &m.meta },
+                |m: &mut MetaRevision| { &mut m.meta },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
+                "prev_hash",
+                |m: &MetaRevision| { &m.prev_hash },
+                |m: &mut MetaRevision| { &mut m.prev_hash },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
+                "hash",
+                |m: &MetaRevision| { &m.hash },
+                |m: &mut MetaRevision| { &mut m.hash },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                "write_seq",
+                |m: &MetaRevision| { &m.write_seq },
+                |m: &mut MetaRevision| { &mut m.write_seq },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<MetaRevision>(
+                "MetaRevision",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static MetaRevision {
+        static instance: ::protobuf::rt::LazyV2<MetaRevision> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(MetaRevision::new)
+    }
+}
+
+impl ::protobuf::Clear for MetaRevision {
+    fn clear(&mut self) {
+        self.seq = 0;
+        self.recorded_at = 0;
+        self.meta.clear();
+        self.prev_hash.clear();
+        self.hash.clear();
+        self.write_seq = 0;
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for MetaRevision {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for MetaRevision {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct BlockRef {
+    // message fields
+    pub height: u64,
+    pub block_id: ::std::string::String,
+    pub timestamp: u64,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a BlockRef {
+    fn default() -> &'a BlockRef {
+        <BlockRef as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl BlockRef {
+    pub fn new() -> BlockRef {
+        ::std::default::Default::default()
+    }
+
+    // uint64 height = 1;
+
+
+    pub fn get_height(&self) -> u64 {
+        self.height
+    }
+    pub fn clear_height(&mut self) {
+        self.height = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_height(&mut self, v: u64) {
+        self.height = v;
+    }
+
+    // string block_id = 2;
+
+
+    pub fn get_block_id(&self) -> &str {
+        &self.block_id
+    }
+    pub fn clear_block_id(&mut self) {
+        self.block_id.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_block_id(&mut self, v: ::std::string::String) {
+        self.block_id = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_block_id(&mut self) -> &mut ::std::string::String {
+        &mut self.block_id
+    }
+
+    // Take field
+    pub fn take_block_id(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.block_id, ::std::string::String::new())
+    }
+
+    // uint64 timestamp = 3;
+
+
+    pub fn get_timestamp(&self) -> u64 {
+        self.timestamp
+    }
+    pub fn clear_timestamp(&mut self) {
+        self.timestamp = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_timestamp(&mut self, v: u64) {
+        self.timestamp = v;
+    }
+}
+
+impl ::protobuf::Message for BlockRef {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.height = tmp;
+                },
+                2 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.block_id)?;
+                },
+                3 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.timestamp = tmp;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if self.height != 0 {
+            my_size += ::protobuf::rt::value_size(1, self.height, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if !self.block_id.is_empty() {
+            my_size += ::protobuf::rt::string_size(2, &self.block_id);
+        }
+        if self.timestamp != 0 {
+            my_size += ::protobuf::rt::value_size(3, self.timestamp, ::protobuf::wire_format::WireTypeVarint);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if self.height != 0 {
+            os.write_uint64(1, self.height)?;
+        }
+        if !self.block_id.is_empty() {
+            os.write_string(2, &self.block_id)?;
+        }
+        if self.timestamp != 0 {
+            os.write_uint64(3, self.timestamp)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> BlockRef {
+        BlockRef::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                "height",
+                |m: &BlockRef| { &m.height },
+                |m: &mut BlockRef| { &mut m.height },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "block_id",
+                |m: &BlockRef| { &m.block_id },
+                |m: &mut BlockRef| { &mut m.block_id },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                "timestamp",
+                |m: &BlockRef| { &m.timestamp },
+                |m: &mut BlockRef| { &mut m.timestamp },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<BlockRef>(
+                "BlockRef",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static BlockRef {
+        static instance: ::protobuf::rt::LazyV2<BlockRef> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(BlockRef::new)
+    }
+}
+
+impl ::protobuf::Clear for BlockRef {
+    fn clear(&mut self) {
+        self.height = 0;
+        self.block_id.clear();
+        self.timestamp = 0;
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for BlockRef {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for BlockRef {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct Change {
+    // message fields
+    pub wallet_id: ::std::string::String,
+    pub entry_id: u32,
+    pub address: ::std::string::String,
+    pub hd_path: ::std::string::String,
+    pub asset: ::std::string::String,
     pub amount: ::std::string::String,
     pub change_type: Change_ChangeType,
     pub direction: Direction,
@@ -1548,48 +3619,286 @@ impl ::protobuf::ProtobufEnum for Change_ChangeType {
         values
     }
 
-    fn enum_descriptor_static() -> &'static ::protobuf::reflect::EnumDescriptor {
-        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::EnumDescriptor> = ::protobuf::rt::LazyV2::INIT;
-        descriptor.get(|| {
-            ::protobuf::reflect::EnumDescriptor::new_pb_name::<Change_ChangeType>("Change.ChangeType", file_descriptor_proto())
-        })
+    fn enum_descriptor_static() -> &'static ::protobuf::reflect::EnumDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::EnumDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            ::protobuf::reflect::EnumDescriptor::new_pb_name::<Change_ChangeType>("Change.ChangeType", file_descriptor_proto())
+        })
+    }
+}
+
+impl ::std::marker::Copy for Change_ChangeType {
+}
+
+impl ::std::default::Default for Change_ChangeType {
+    fn default() -> Self {
+        Change_ChangeType::UNSPECIFIED
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for Change_ChangeType {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Enum(::protobuf::ProtobufEnum::descriptor(self))
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct Cursor {
+    // message fields
+    pub address: ::std::string::String,
+    pub value: ::std::string::String,
+    pub ts: u64,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a Cursor {
+    fn default() -> &'a Cursor {
+        <Cursor as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl Cursor {
+    pub fn new() -> Cursor {
+        ::std::default::Default::default()
+    }
+
+    // string address = 1;
+
+
+    pub fn get_address(&self) -> &str {
+        &self.address
+    }
+    pub fn clear_address(&mut self) {
+        self.address.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_address(&mut self, v: ::std::string::String) {
+        self.address = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_address(&mut self) -> &mut ::std::string::String {
+        &mut self.address
+    }
+
+    // Take field
+    pub fn take_address(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.address, ::std::string::String::new())
+    }
+
+    // string value = 2;
+
+
+    pub fn get_value(&self) -> &str {
+        &self.value
+    }
+    pub fn clear_value(&mut self) {
+        self.value.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_value(&mut self, v: ::std::string::String) {
+        self.value = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_value(&mut self) -> &mut ::std::string::String {
+        &mut self.value
+    }
+
+    // Take field
+    pub fn take_value(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.value, ::std::string::String::new())
+    }
+
+    // uint64 ts = 3;
+
+
+    pub fn get_ts(&self) -> u64 {
+        self.ts
+    }
+    pub fn clear_ts(&mut self) {
+        self.ts = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_ts(&mut self, v: u64) {
+        self.ts = v;
+    }
+}
+
+impl ::protobuf::Message for Cursor {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.address)?;
+                },
+                2 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.value)?;
+                },
+                3 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.ts = tmp;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.address.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.address);
+        }
+        if !self.value.is_empty() {
+            my_size += ::protobuf::rt::string_size(2, &self.value);
+        }
+        if self.ts != 0 {
+            my_size += ::protobuf::rt::value_size(3, self.ts, ::protobuf::wire_format::WireTypeVarint);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.address.is_empty() {
+            os.write_string(1, &self.address)?;
+        }
+        if !self.value.is_empty() {
+            os.write_string(2, &self.value)?;
+        }
+        if self.ts != 0 {
+            os.write_uint64(3, self.ts)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> Cursor {
+        Cursor::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "address",
+                |m: &Cursor| { &m.address },
+                |m: &mut Cursor| { &mut m.address },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "value",
+                |m: &Cursor| { &m.value },
+                |m: &mut Cursor| { &mut m.value },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                "ts",
+                |m: &Cursor| { &m.ts },
+                |m: &mut Cursor| { &mut m.ts },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<Cursor>(
+                "Cursor",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static Cursor {
+        static instance: ::protobuf::rt::LazyV2<Cursor> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(Cursor::new)
     }
 }
 
-impl ::std::marker::Copy for Change_ChangeType {
+impl ::protobuf::Clear for Cursor {
+    fn clear(&mut self) {
+        self.address.clear();
+        self.value.clear();
+        self.ts = 0;
+        self.unknown_fields.clear();
+    }
 }
 
-impl ::std::default::Default for Change_ChangeType {
-    fn default() -> Self {
-        Change_ChangeType::UNSPECIFIED
+impl ::std::fmt::Debug for Cursor {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
     }
 }
 
-impl ::protobuf::reflect::ProtobufValue for Change_ChangeType {
+impl ::protobuf::reflect::ProtobufValue for Cursor {
     fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
-        ::protobuf::reflect::ReflectValueRef::Enum(::protobuf::ProtobufEnum::descriptor(self))
+        ::protobuf::reflect::ReflectValueRef::Message(self)
     }
 }
 
 #[derive(PartialEq,Clone,Default)]
-pub struct Cursor {
+pub struct FirstSeen {
     // message fields
     pub address: ::std::string::String,
-    pub value: ::std::string::String,
-    pub ts: u64,
+    pub blockchain: BlockchainId,
+    pub timestamp: u64,
+    pub tx_id: ::std::string::String,
+    pub direction: Direction,
     // special fields
     pub unknown_fields: ::protobuf::UnknownFields,
     pub cached_size: ::protobuf::CachedSize,
 }
 
-impl<'a> ::std::default::Default for &'a Cursor {
-    fn default() -> &'a Cursor {
-        <Cursor as ::protobuf::Message>::default_instance()
+impl<'a> ::std::default::Default for &'a FirstSeen {
+    fn default() -> &'a FirstSeen {
+        <FirstSeen as ::protobuf::Message>::default_instance()
     }
 }
 
-impl Cursor {
-    pub fn new() -> Cursor {
+impl FirstSeen {
+    pub fn new() -> FirstSeen {
         ::std::default::Default::default()
     }
 
@@ -1619,49 +3928,79 @@ impl Cursor {
         ::std::mem::replace(&mut self.address, ::std::string::String::new())
     }
 
-    // string value = 2;
+    // .emerald.state.BlockchainId blockchain = 2;
 
 
-    pub fn get_value(&self) -> &str {
-        &self.value
+    pub fn get_blockchain(&self) -> BlockchainId {
+        self.blockchain
     }
-    pub fn clear_value(&mut self) {
-        self.value.clear();
+    pub fn clear_blockchain(&mut self) {
+        self.blockchain = BlockchainId::CHAIN_UNSPECIFIED;
     }
 
     // Param is passed by value, moved
-    pub fn set_value(&mut self, v: ::std::string::String) {
-        self.value = v;
+    pub fn set_blockchain(&mut self, v: BlockchainId) {
+        self.blockchain = v;
+    }
+
+    // uint64 timestamp = 3;
+
+
+    pub fn get_timestamp(&self) -> u64 {
+        self.timestamp
+    }
+    pub fn clear_timestamp(&mut self) {
+        self.timestamp = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_timestamp(&mut self, v: u64) {
+        self.timestamp = v;
+    }
+
+    // string tx_id = 4;
+
+
+    pub fn get_tx_id(&self) -> &str {
+        &self.tx_id
+    }
+    pub fn clear_tx_id(&mut self) {
+        self.tx_id.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_tx_id(&mut self, v: ::std::string::String) {
+        self.tx_id = v;
     }
 
     // Mutable pointer to the field.
     // If field is not initialized, it is initialized with default value first.
-    pub fn mut_value(&mut self) -> &mut ::std::string::String {
-        &mut self.value
+    pub fn mut_tx_id(&mut self) -> &mut ::std::string::String {
+        &mut self.tx_id
     }
 
     // Take field
-    pub fn take_value(&mut self) -> ::std::string::String {
-        ::std::mem::replace(&mut self.value, ::std::string::String::new())
+    pub fn take_tx_id(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.tx_id, ::std::string::String::new())
     }
 
-    // uint64 ts = 3;
+    // .emerald.state.Direction direction = 5;
 
 
-    pub fn get_ts(&self) -> u64 {
-        self.ts
+    pub fn get_direction(&self) -> Direction {
+        self.direction
     }
-    pub fn clear_ts(&mut self) {
-        self.ts = 0;
+    pub fn clear_direction(&mut self) {
+        self.direction = Direction::RECEIVE;
     }
 
     // Param is passed by value, moved
-    pub fn set_ts(&mut self, v: u64) {
-        self.ts = v;
+    pub fn set_direction(&mut self, v: Direction) {
+        self.direction = v;
     }
 }
 
-impl ::protobuf::Message for Cursor {
+impl ::protobuf::Message for FirstSeen {
     fn is_initialized(&self) -> bool {
         true
     }
@@ -1674,14 +4013,20 @@ impl ::protobuf::Message for Cursor {
                     ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.address)?;
                 },
                 2 => {
-                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.value)?;
+                    ::protobuf::rt::read_proto3_enum_with_unknown_fields_into(wire_type, is, &mut self.blockchain, 2, &mut self.unknown_fields)?
                 },
                 3 => {
                     if wire_type != ::protobuf::wire_format::WireTypeVarint {
                         return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
                     }
                     let tmp = is.read_uint64()?;
-                    self.ts = tmp;
+                    self.timestamp = tmp;
+                },
+                4 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.tx_id)?;
+                },
+                5 => {
+                    ::protobuf::rt::read_proto3_enum_with_unknown_fields_into(wire_type, is, &mut self.direction, 5, &mut self.unknown_fields)?
                 },
                 _ => {
                     ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
@@ -1698,11 +4043,17 @@ impl ::protobuf::Message for Cursor {
         if !self.address.is_empty() {
             my_size += ::protobuf::rt::string_size(1, &self.address);
         }
-        if !self.value.is_empty() {
-            my_size += ::protobuf::rt::string_size(2, &self.value);
+        if self.blockchain != BlockchainId::CHAIN_UNSPECIFIED {
+            my_size += ::protobuf::rt::enum_size(2, self.blockchain);
         }
-        if self.ts != 0 {
-            my_size += ::protobuf::rt::value_size(3, self.ts, ::protobuf::wire_format::WireTypeVarint);
+        if self.timestamp != 0 {
+            my_size += ::protobuf::rt::value_size(3, self.timestamp, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if !self.tx_id.is_empty() {
+            my_size += ::protobuf::rt::string_size(4, &self.tx_id);
+        }
+        if self.direction != Direction::RECEIVE {
+            my_size += ::protobuf::rt::enum_size(5, self.direction);
         }
         my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
         self.cached_size.set(my_size);
@@ -1713,11 +4064,17 @@ impl ::protobuf::Message for Cursor {
         if !self.address.is_empty() {
             os.write_string(1, &self.address)?;
         }
-        if !self.value.is_empty() {
-            os.write_string(2, &self.value)?;
+        if self.blockchain != BlockchainId::CHAIN_UNSPECIFIED {
+            os.write_enum(2, ::protobuf::ProtobufEnum::value(&self.blockchain))?;
         }
-        if self.ts != 0 {
-            os.write_uint64(3, self.ts)?;
+        if self.timestamp != 0 {
+            os.write_uint64(3, self.timestamp)?;
+        }
+        if !self.tx_id.is_empty() {
+            os.write_string(4, &self.tx_id)?;
+        }
+        if self.direction != Direction::RECEIVE {
+            os.write_enum(5, ::protobuf::ProtobufEnum::value(&self.direction))?;
         }
         os.write_unknown_fields(self.get_unknown_fields())?;
         ::std::result::Result::Ok(())
@@ -1749,8 +4106,8 @@ impl ::protobuf::Message for Cursor {
         Self::descriptor_static()
     }
 
-    fn new() -> Cursor {
-        Cursor::new()
+    fn new() -> FirstSeen {
+        FirstSeen::new()
     }
 
     fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
@@ -1759,54 +4116,184 @@ impl ::protobuf::Message for Cursor {
             let mut fields = ::std::vec::Vec::new();
             fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
                 "address",
-                |m: &Cursor| { &m.address },
-                |m: &mut Cursor| { &mut m.address },
+                |m: &FirstSeen| { &m.address },
+                |m: &mut FirstSeen| { &mut m.address },
             ));
-            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
-                "value",
-                |m: &Cursor| { &m.value },
-                |m: &mut Cursor| { &mut m.value },
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeEnum<BlockchainId>>(
+                "blockchain",
+                |m: &FirstSeen| { &m.blockchain },
+                |m: &mut FirstSeen| { &mut m.blockchain },
             ));
             fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
-                "ts",
-                |m: &Cursor| { &m.ts },
-                |m: &mut Cursor| { &mut m.ts },
+                "timestamp",
+                |m: &FirstSeen| { &m.timestamp },
+                |m: &mut FirstSeen| { &mut m.timestamp },
             ));
-            ::protobuf::reflect::MessageDescriptor::new_pb_name::<Cursor>(
-                "Cursor",
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "tx_id",
+                |m: &FirstSeen| { &m.tx_id },
+                |m: &mut FirstSeen| { &mut m.tx_id },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeEnum<Direction>>(
+                "direction",
+                |m: &FirstSeen| { &m.direction },
+                |m: &mut FirstSeen| { &mut m.direction },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<FirstSeen>(
+                "FirstSeen",
                 fields,
                 file_descriptor_proto()
             )
         })
     }
 
-    fn default_instance() -> &'static Cursor {
-        static instance: ::protobuf::rt::LazyV2<Cursor> = ::protobuf::rt::LazyV2::INIT;
-        instance.get(Cursor::new)
+    fn default_instance() -> &'static FirstSeen {
+        static instance: ::protobuf::rt::LazyV2<FirstSeen> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(FirstSeen::new)
     }
 }
 
-impl ::protobuf::Clear for Cursor {
+impl ::protobuf::Clear for FirstSeen {
     fn clear(&mut self) {
         self.address.clear();
-        self.value.clear();
-        self.ts = 0;
+        self.blockchain = BlockchainId::CHAIN_UNSPECIFIED;
+        self.timestamp = 0;
+        self.tx_id.clear();
+        self.direction = Direction::RECEIVE;
         self.unknown_fields.clear();
     }
 }
 
-impl ::std::fmt::Debug for Cursor {
+impl ::std::fmt::Debug for FirstSeen {
     fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
         ::protobuf::text_format::fmt(self, f)
     }
 }
 
-impl ::protobuf::reflect::ProtobufValue for Cursor {
+impl ::protobuf::reflect::ProtobufValue for FirstSeen {
     fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
         ::protobuf::reflect::ReflectValueRef::Message(self)
     }
 }
 
+#[derive(Clone,PartialEq,Eq,Debug,Hash)]
+pub enum TokenStandard {
+    TOKEN_UNKNOWN = 0,
+    TOKEN_ERC20 = 1,
+    TOKEN_ERC721 = 2,
+}
+
+impl ::protobuf::ProtobufEnum for TokenStandard {
+    fn value(&self) -> i32 {
+        *self as i32
+    }
+
+    fn from_i32(value: i32) -> ::std::option::Option<TokenStandard> {
+        match value {
+            0 => ::std::option::Option::Some(TokenStandard::TOKEN_UNKNOWN),
+            1 => ::std::option::Option::Some(TokenStandard::TOKEN_ERC20),
+            2 => ::std::option::Option::Some(TokenStandard::TOKEN_ERC721),
+            _ => ::std::option::Option::None
+        }
+    }
+
+    fn values() -> &'static [Self] {
+        static values: &'static [TokenStandard] = &[
+            TokenStandard::TOKEN_UNKNOWN,
+            TokenStandard::TOKEN_ERC20,
+            TokenStandard::TOKEN_ERC721,
+        ];
+        values
+    }
+
+    fn enum_descriptor_static() -> &'static ::protobuf::reflect::EnumDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::EnumDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            ::protobuf::reflect::EnumDescriptor::new_pb_name::<TokenStandard>("TokenStandard", file_descriptor_proto())
+        })
+    }
+}
+
+impl ::std::marker::Copy for TokenStandard {
+}
+
+impl ::std::default::Default for TokenStandard {
+    fn default() -> Self {
+        TokenStandard::TOKEN_UNKNOWN
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for TokenStandard {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Enum(::protobuf::ProtobufEnum::descriptor(self))
+    }
+}
+
+#[derive(Clone,PartialEq,Eq,Debug,Hash)]
+pub enum ScriptType {
+    SCRIPT_UNKNOWN = 0,
+    SCRIPT_P2PKH = 1,
+    SCRIPT_P2SH = 2,
+    SCRIPT_P2WPKH = 3,
+    SCRIPT_P2WSH = 4,
+    SCRIPT_P2TR = 5,
+    SCRIPT_OP_RETURN = 6,
+}
+
+impl ::protobuf::ProtobufEnum for ScriptType {
+    fn value(&self) -> i32 {
+        *self as i32
+    }
+
+    fn from_i32(value: i32) -> ::std::option::Option<ScriptType> {
+        match value {
+            0 => ::std::option::Option::Some(ScriptType::SCRIPT_UNKNOWN),
+            1 => ::std::option::Option::Some(ScriptType::SCRIPT_P2PKH),
+            2 => ::std::option::Option::Some(ScriptType::SCRIPT_P2SH),
+            3 => ::std::option::Option::Some(ScriptType::SCRIPT_P2WPKH),
+            4 => ::std::option::Option::Some(ScriptType::SCRIPT_P2WSH),
+            5 => ::std::option::Option::Some(ScriptType::SCRIPT_P2TR),
+            6 => ::std::option::Option::Some(ScriptType::SCRIPT_OP_RETURN),
+            _ => ::std::option::Option::None
+        }
+    }
+
+    fn values() -> &'static [Self] {
+        static values: &'static [ScriptType] = &[
+            ScriptType::SCRIPT_UNKNOWN,
+            ScriptType::SCRIPT_P2PKH,
+            ScriptType::SCRIPT_P2SH,
+            ScriptType::SCRIPT_P2WPKH,
+            ScriptType::SCRIPT_P2WSH,
+            ScriptType::SCRIPT_P2TR,
+            ScriptType::SCRIPT_OP_RETURN,
+        ];
+        values
+    }
+
+    fn enum_descriptor_static() -> &'static ::protobuf::reflect::EnumDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::EnumDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            ::protobuf::reflect::EnumDescriptor::new_pb_name::<ScriptType>("ScriptType", file_descriptor_proto())
+        })
+    }
+}
+
+impl ::std::marker::Copy for ScriptType {
+}
+
+impl ::std::default::Default for ScriptType {
+    fn default() -> Self {
+        ScriptType::SCRIPT_UNKNOWN
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for ScriptType {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Enum(::protobuf::ProtobufEnum::descriptor(self))
+    }
+}
+
 #[derive(Clone,PartialEq,Eq,Debug,Hash)]
 pub enum BlockchainId {
     CHAIN_UNSPECIFIED = 0,
@@ -2050,193 +4537,93 @@ impl ::protobuf::reflect::ProtobufValue for Direction {
 }
 
 static file_descriptor_proto_data: &'static [u8] = b"\
-    \n\x12transactions.proto\x12\remerald.state\"\xce\x03\n\x0bTransaction\
-    \x12;\n\nblockchain\x18\x01\x20\x01(\x0e2\x1b.emerald.state.BlockchainId\
-    R\nblockchain\x12\x13\n\x05tx_id\x18\x02\x20\x01(\tR\x04txId\x12'\n\x0fs\
-    ince_timestamp\x18\x03\x20\x01(\x04R\x0esinceTimestamp\x12%\n\x0esync_ti\
-    mestamp\x18\x04\x20\x01(\x04R\rsyncTimestamp\x12+\n\x11confirm_timestamp\
-    \x18\x05\x20\x01(\x04R\x10confirmTimestamp\x12*\n\x05state\x18\x06\x20\
-    \x01(\x0e2\x14.emerald.state.StateR\x05state\x12-\n\x05block\x18\x07\x20\
-    \x01(\x0b2\x17.emerald.state.BlockRefR\x05block\x12\x1b\n\tblock_pos\x18\
-    \x0b\x20\x01(\rR\x08blockPos\x12-\n\x06status\x18\x08\x20\x01(\x0e2\x15.\
-    emerald.state.StatusR\x06status\x12/\n\x07changes\x18\t\x20\x03(\x0b2\
-    \x15.emerald.state.ChangeR\x07changes\x12\x18\n\x07version\x18\n\x20\x01\
-    (\x04R\x07version\"\xa9\x01\n\x0fTransactionMeta\x12\x1c\n\ttimestamp\
-    \x18\x01\x20\x01(\x04R\ttimestamp\x12;\n\nblockchain\x18\x02\x20\x01(\
-    \x0e2\x1b.emerald.state.BlockchainIdR\nblockchain\x12\x13\n\x05tx_id\x18\
-    \x03\x20\x01(\tR\x04txId\x12\x14\n\x05label\x18\x04\x20\x01(\tR\x05label\
-    \x12\x10\n\x03raw\x18\x05\x20\x01(\x0cR\x03raw\"[\n\x08BlockRef\x12\x16\
-    \n\x06height\x18\x01\x20\x01(\x04R\x06height\x12\x19\n\x08block_id\x18\
-    \x02\x20\x01(\tR\x07blockId\x12\x1c\n\ttimestamp\x18\x03\x20\x01(\x04R\t\
-    timestamp\"\xd2\x02\n\x06Change\x12\x1b\n\twallet_id\x18\x01\x20\x01(\tR\
-    \x08walletId\x12\x19\n\x08entry_id\x18\x02\x20\x01(\rR\x07entryId\x12\
-    \x18\n\x07address\x18\x03\x20\x01(\tR\x07address\x12\x17\n\x07hd_path\
-    \x18\x04\x20\x01(\tR\x06hdPath\x12\x14\n\x05asset\x18\x05\x20\x01(\tR\
-    \x05asset\x12\x16\n\x06amount\x18\x06\x20\x01(\tR\x06amount\x12A\n\x0bch\
-    ange_type\x18\x07\x20\x01(\x0e2\x20.emerald.state.Change.ChangeTypeR\nch\
-    angeType\x126\n\tdirection\x18\x08\x20\x01(\x0e2\x18.emerald.state.Direc\
-    tionR\tdirection\"4\n\nChangeType\x12\x0f\n\x0bUNSPECIFIED\x10\0\x12\x0c\
-    \n\x08TRANSFER\x10\x01\x12\x07\n\x03FEE\x10\x02\"H\n\x06Cursor\x12\x18\n\
-    \x07address\x18\x01\x20\x01(\tR\x07address\x12\x14\n\x05value\x18\x02\
-    \x20\x01(\tR\x05value\x12\x0e\n\x02ts\x18\x03\x20\x01(\x04R\x02ts*\x8c\
-    \x02\n\x0cBlockchainId\x12\x15\n\x11CHAIN_UNSPECIFIED\x10\0\x12\x11\n\rC\
-    HAIN_BITCOIN\x10\x01\x12\x12\n\x0eCHAIN_ETHEREUM\x10d\x12\x1a\n\x16CHAIN\
-    _ETHEREUM_CLASSIC\x10e\x12\x11\n\x0cCHAIN_MORDEN\x10\x91N\x12\x10\n\x0bC\
-    HAIN_KOVAN\x10\x92N\x12\x1a\n\x15CHAIN_TESTNET_BITCOIN\x10\x93N\x12\x11\
-    \n\x0cCHAIN_GOERLI\x10\x95N\x12\x12\n\rCHAIN_ROPSTEN\x10\x96N\x12\x12\n\
-    \rCHAIN_RINKEBY\x10\x97N\x12\x12\n\rCHAIN_HOLESKY\x10\x98N\x12\x12\n\rCH\
-    AIN_SEPOLIA\x10\x99N*N\n\x05State\x12\x0c\n\x08PREPARED\x10\0\x12\r\n\tS\
-    UBMITTED\x10\n\x12\x0c\n\x08REPLACED\x10\x0b\x12\r\n\tCONFIRMED\x10\x0c\
-    \x12\x0b\n\x07DROPPED\x10\x14*)\n\x06Status\x12\x0b\n\x07UNKNOWN\x10\0\
-    \x12\x06\n\x02OK\x10\x01\x12\n\n\x06FAILED\x10\x02*\"\n\tDirection\x12\
-    \x0b\n\x07RECEIVE\x10\0\x12\x08\n\x04SEND\x10\x01J\xcf\x18\n\x06\x12\x04\
-    \0\0Z\x01\n\x08\n\x01\x0c\x12\x03\0\0\x12\n\x08\n\x01\x02\x12\x03\x01\0\
-    \x16\n\n\n\x02\x04\0\x12\x04\x03\0\x0f\x01\n\n\n\x03\x04\0\x01\x12\x03\
-    \x03\x08\x13\n\x0b\n\x04\x04\0\x02\0\x12\x03\x04\x02\x1e\n\x0c\n\x05\x04\
-    \0\x02\0\x06\x12\x03\x04\x02\x0e\n\x0c\n\x05\x04\0\x02\0\x01\x12\x03\x04\
-    \x0f\x19\n\x0c\n\x05\x04\0\x02\0\x03\x12\x03\x04\x1c\x1d\n\x0b\n\x04\x04\
-    \0\x02\x01\x12\x03\x05\x02\x13\n\x0c\n\x05\x04\0\x02\x01\x05\x12\x03\x05\
-    \x02\x08\n\x0c\n\x05\x04\0\x02\x01\x01\x12\x03\x05\t\x0e\n\x0c\n\x05\x04\
-    \0\x02\x01\x03\x12\x03\x05\x11\x12\n\x0b\n\x04\x04\0\x02\x02\x12\x03\x06\
-    \x02\x1d\n\x0c\n\x05\x04\0\x02\x02\x05\x12\x03\x06\x02\x08\n\x0c\n\x05\
-    \x04\0\x02\x02\x01\x12\x03\x06\t\x18\n\x0c\n\x05\x04\0\x02\x02\x03\x12\
-    \x03\x06\x1b\x1c\n\x0b\n\x04\x04\0\x02\x03\x12\x03\x07\x02\x1c\n\x0c\n\
-    \x05\x04\0\x02\x03\x05\x12\x03\x07\x02\x08\n\x0c\n\x05\x04\0\x02\x03\x01\
-    \x12\x03\x07\t\x17\n\x0c\n\x05\x04\0\x02\x03\x03\x12\x03\x07\x1a\x1b\n\
-    \x0b\n\x04\x04\0\x02\x04\x12\x03\x08\x02\x1f\n\x0c\n\x05\x04\0\x02\x04\
-    \x05\x12\x03\x08\x02\x08\n\x0c\n\x05\x04\0\x02\x04\x01\x12\x03\x08\t\x1a\
-    \n\x0c\n\x05\x04\0\x02\x04\x03\x12\x03\x08\x1d\x1e\n\x0b\n\x04\x04\0\x02\
-    \x05\x12\x03\t\x02\x12\n\x0c\n\x05\x04\0\x02\x05\x06\x12\x03\t\x02\x07\n\
-    \x0c\n\x05\x04\0\x02\x05\x01\x12\x03\t\x08\r\n\x0c\n\x05\x04\0\x02\x05\
-    \x03\x12\x03\t\x10\x11\n\x0b\n\x04\x04\0\x02\x06\x12\x03\n\x02\x15\n\x0c\
-    \n\x05\x04\0\x02\x06\x06\x12\x03\n\x02\n\n\x0c\n\x05\x04\0\x02\x06\x01\
-    \x12\x03\n\x0b\x10\n\x0c\n\x05\x04\0\x02\x06\x03\x12\x03\n\x13\x14\n\x0b\
-    \n\x04\x04\0\x02\x07\x12\x03\x0b\x02\x18\n\x0c\n\x05\x04\0\x02\x07\x05\
-    \x12\x03\x0b\x02\x08\n\x0c\n\x05\x04\0\x02\x07\x01\x12\x03\x0b\t\x12\n\
-    \x0c\n\x05\x04\0\x02\x07\x03\x12\x03\x0b\x15\x17\n\x0b\n\x04\x04\0\x02\
-    \x08\x12\x03\x0c\x02\x14\n\x0c\n\x05\x04\0\x02\x08\x06\x12\x03\x0c\x02\
-    \x08\n\x0c\n\x05\x04\0\x02\x08\x01\x12\x03\x0c\t\x0f\n\x0c\n\x05\x04\0\
-    \x02\x08\x03\x12\x03\x0c\x12\x13\n\x0b\n\x04\x04\0\x02\t\x12\x03\r\x02\
-    \x1e\n\x0c\n\x05\x04\0\x02\t\x04\x12\x03\r\x02\n\n\x0c\n\x05\x04\0\x02\t\
-    \x06\x12\x03\r\x0b\x11\n\x0c\n\x05\x04\0\x02\t\x01\x12\x03\r\x12\x19\n\
-    \x0c\n\x05\x04\0\x02\t\x03\x12\x03\r\x1c\x1d\n\x0b\n\x04\x04\0\x02\n\x12\
-    \x03\x0e\x02\x16\n\x0c\n\x05\x04\0\x02\n\x05\x12\x03\x0e\x02\x08\n\x0c\n\
-    \x05\x04\0\x02\n\x01\x12\x03\x0e\t\x10\n\x0c\n\x05\x04\0\x02\n\x03\x12\
-    \x03\x0e\x13\x15\n\n\n\x02\x04\x01\x12\x04\x11\0\x17\x01\n\n\n\x03\x04\
-    \x01\x01\x12\x03\x11\x08\x17\n\x0b\n\x04\x04\x01\x02\0\x12\x03\x12\x02\
-    \x17\n\x0c\n\x05\x04\x01\x02\0\x05\x12\x03\x12\x02\x08\n\x0c\n\x05\x04\
-    \x01\x02\0\x01\x12\x03\x12\t\x12\n\x0c\n\x05\x04\x01\x02\0\x03\x12\x03\
-    \x12\x15\x16\n\x0b\n\x04\x04\x01\x02\x01\x12\x03\x13\x02\x1e\n\x0c\n\x05\
-    \x04\x01\x02\x01\x06\x12\x03\x13\x02\x0e\n\x0c\n\x05\x04\x01\x02\x01\x01\
-    \x12\x03\x13\x0f\x19\n\x0c\n\x05\x04\x01\x02\x01\x03\x12\x03\x13\x1c\x1d\
-    \n\x0b\n\x04\x04\x01\x02\x02\x12\x03\x14\x02\x13\n\x0c\n\x05\x04\x01\x02\
-    \x02\x05\x12\x03\x14\x02\x08\n\x0c\n\x05\x04\x01\x02\x02\x01\x12\x03\x14\
-    \t\x0e\n\x0c\n\x05\x04\x01\x02\x02\x03\x12\x03\x14\x11\x12\n\x0b\n\x04\
-    \x04\x01\x02\x03\x12\x03\x15\x02\x13\n\x0c\n\x05\x04\x01\x02\x03\x05\x12\
-    \x03\x15\x02\x08\n\x0c\n\x05\x04\x01\x02\x03\x01\x12\x03\x15\t\x0e\n\x0c\
-    \n\x05\x04\x01\x02\x03\x03\x12\x03\x15\x11\x12\n\x0b\n\x04\x04\x01\x02\
-    \x04\x12\x03\x16\x02\x10\n\x0c\n\x05\x04\x01\x02\x04\x05\x12\x03\x16\x02\
-    \x07\n\x0c\n\x05\x04\x01\x02\x04\x01\x12\x03\x16\x08\x0b\n\x0c\n\x05\x04\
-    \x01\x02\x04\x03\x12\x03\x16\x0e\x0f\n\n\n\x02\x04\x02\x12\x04\x19\0\x1d\
-    \x01\n\n\n\x03\x04\x02\x01\x12\x03\x19\x08\x10\n\x0b\n\x04\x04\x02\x02\0\
-    \x12\x03\x1a\x02\x14\n\x0c\n\x05\x04\x02\x02\0\x05\x12\x03\x1a\x02\x08\n\
-    \x0c\n\x05\x04\x02\x02\0\x01\x12\x03\x1a\t\x0f\n\x0c\n\x05\x04\x02\x02\0\
-    \x03\x12\x03\x1a\x12\x13\n\x0b\n\x04\x04\x02\x02\x01\x12\x03\x1b\x02\x16\
-    \n\x0c\n\x05\x04\x02\x02\x01\x05\x12\x03\x1b\x02\x08\n\x0c\n\x05\x04\x02\
-    \x02\x01\x01\x12\x03\x1b\t\x11\n\x0c\n\x05\x04\x02\x02\x01\x03\x12\x03\
-    \x1b\x14\x15\n\x0b\n\x04\x04\x02\x02\x02\x12\x03\x1c\x02\x17\n\x0c\n\x05\
-    \x04\x02\x02\x02\x05\x12\x03\x1c\x02\x08\n\x0c\n\x05\x04\x02\x02\x02\x01\
-    \x12\x03\x1c\t\x12\n\x0c\n\x05\x04\x02\x02\x02\x03\x12\x03\x1c\x15\x16\n\
-    \n\n\x02\x05\0\x12\x04\x1f\0,\x01\n\n\n\x03\x05\0\x01\x12\x03\x1f\x05\
-    \x11\n\x0b\n\x04\x05\0\x02\0\x12\x03\x20\x02\x18\n\x0c\n\x05\x05\0\x02\0\
-    \x01\x12\x03\x20\x02\x13\n\x0c\n\x05\x05\0\x02\0\x02\x12\x03\x20\x16\x17\
-    \n\x0b\n\x04\x05\0\x02\x01\x12\x03!\x02\x14\n\x0c\n\x05\x05\0\x02\x01\
-    \x01\x12\x03!\x02\x0f\n\x0c\n\x05\x05\0\x02\x01\x02\x12\x03!\x12\x13\n\
-    \x0b\n\x04\x05\0\x02\x02\x12\x03\"\x02\x17\n\x0c\n\x05\x05\0\x02\x02\x01\
-    \x12\x03\"\x02\x10\n\x0c\n\x05\x05\0\x02\x02\x02\x12\x03\"\x13\x16\n\x0b\
-    \n\x04\x05\0\x02\x03\x12\x03#\x02\x1f\n\x0c\n\x05\x05\0\x02\x03\x01\x12\
-    \x03#\x02\x18\n\x0c\n\x05\x05\0\x02\x03\x02\x12\x03#\x1b\x1e\n\x0b\n\x04\
-    \x05\0\x02\x04\x12\x03$\x02\x17\n\x0c\n\x05\x05\0\x02\x04\x01\x12\x03$\
-    \x02\x0e\n\x0c\n\x05\x05\0\x02\x04\x02\x12\x03$\x11\x16\n\x0b\n\x04\x05\
-    \0\x02\x05\x12\x03%\x02\x16\n\x0c\n\x05\x05\0\x02\x05\x01\x12\x03%\x02\r\
-    \n\x0c\n\x05\x05\0\x02\x05\x02\x12\x03%\x10\x15\n\x0b\n\x04\x05\0\x02\
-    \x06\x12\x03&\x02\x20\n\x0c\n\x05\x05\0\x02\x06\x01\x12\x03&\x02\x17\n\
-    \x0c\n\x05\x05\0\x02\x06\x02\x12\x03&\x1a\x1f\n\x0b\n\x04\x05\0\x02\x07\
-    \x12\x03'\x02\x17\n\x0c\n\x05\x05\0\x02\x07\x01\x12\x03'\x02\x0e\n\x0c\n\
-    \x05\x05\0\x02\x07\x02\x12\x03'\x11\x16\n\x0b\n\x04\x05\0\x02\x08\x12\
-    \x03(\x02\x18\n\x0c\n\x05\x05\0\x02\x08\x01\x12\x03(\x02\x0f\n\x0c\n\x05\
-    \x05\0\x02\x08\x02\x12\x03(\x12\x17\n\x0b\n\x04\x05\0\x02\t\x12\x03)\x02\
-    \x18\n\x0c\n\x05\x05\0\x02\t\x01\x12\x03)\x02\x0f\n\x0c\n\x05\x05\0\x02\
-    \t\x02\x12\x03)\x12\x17\n\x0b\n\x04\x05\0\x02\n\x12\x03*\x02\x18\n\x0c\n\
-    \x05\x05\0\x02\n\x01\x12\x03*\x02\x0f\n\x0c\n\x05\x05\0\x02\n\x02\x12\
-    \x03*\x12\x17\n\x0b\n\x04\x05\0\x02\x0b\x12\x03+\x02\x18\n\x0c\n\x05\x05\
-    \0\x02\x0b\x01\x12\x03+\x02\x0f\n\x0c\n\x05\x05\0\x02\x0b\x02\x12\x03+\
-    \x12\x17\n\n\n\x02\x05\x01\x12\x04.\04\x01\n\n\n\x03\x05\x01\x01\x12\x03\
-    .\x05\n\n\x0b\n\x04\x05\x01\x02\0\x12\x03/\x02\x0f\n\x0c\n\x05\x05\x01\
-    \x02\0\x01\x12\x03/\x02\n\n\x0c\n\x05\x05\x01\x02\0\x02\x12\x03/\r\x0e\n\
-    \x0b\n\x04\x05\x01\x02\x01\x12\x030\x02\x11\n\x0c\n\x05\x05\x01\x02\x01\
-    \x01\x12\x030\x02\x0b\n\x0c\n\x05\x05\x01\x02\x01\x02\x12\x030\x0e\x10\n\
-    \x0b\n\x04\x05\x01\x02\x02\x12\x031\x02\x10\n\x0c\n\x05\x05\x01\x02\x02\
-    \x01\x12\x031\x02\n\n\x0c\n\x05\x05\x01\x02\x02\x02\x12\x031\r\x0f\n\x0b\
-    \n\x04\x05\x01\x02\x03\x12\x032\x02\x11\n\x0c\n\x05\x05\x01\x02\x03\x01\
-    \x12\x032\x02\x0b\n\x0c\n\x05\x05\x01\x02\x03\x02\x12\x032\x0e\x10\n\x0b\
-    \n\x04\x05\x01\x02\x04\x12\x033\x02\x0f\n\x0c\n\x05\x05\x01\x02\x04\x01\
-    \x12\x033\x02\t\n\x0c\n\x05\x05\x01\x02\x04\x02\x12\x033\x0c\x0e\n\n\n\
-    \x02\x05\x02\x12\x046\0:\x01\n\n\n\x03\x05\x02\x01\x12\x036\x05\x0b\n\
-    \x0b\n\x04\x05\x02\x02\0\x12\x037\x02\x0e\n\x0c\n\x05\x05\x02\x02\0\x01\
-    \x12\x037\x02\t\n\x0c\n\x05\x05\x02\x02\0\x02\x12\x037\x0c\r\n\x0b\n\x04\
-    \x05\x02\x02\x01\x12\x038\x02\t\n\x0c\n\x05\x05\x02\x02\x01\x01\x12\x038\
-    \x02\x04\n\x0c\n\x05\x05\x02\x02\x01\x02\x12\x038\x07\x08\n\x0b\n\x04\
-    \x05\x02\x02\x02\x12\x039\x02\r\n\x0c\n\x05\x05\x02\x02\x02\x01\x12\x039\
-    \x02\x08\n\x0c\n\x05\x05\x02\x02\x02\x02\x12\x039\x0b\x0c\n\n\n\x02\x05\
-    \x03\x12\x04<\0?\x01\n\n\n\x03\x05\x03\x01\x12\x03<\x05\x0e\n\x0b\n\x04\
-    \x05\x03\x02\0\x12\x03=\x02\x0e\n\x0c\n\x05\x05\x03\x02\0\x01\x12\x03=\
-    \x02\t\n\x0c\n\x05\x05\x03\x02\0\x02\x12\x03=\x0c\r\n\x0b\n\x04\x05\x03\
-    \x02\x01\x12\x03>\x02\x0b\n\x0c\n\x05\x05\x03\x02\x01\x01\x12\x03>\x02\
-    \x06\n\x0c\n\x05\x05\x03\x02\x01\x02\x12\x03>\t\n\n\n\n\x02\x04\x03\x12\
-    \x04A\0R\x01\n\n\n\x03\x04\x03\x01\x12\x03A\x08\x0e\n\x0b\n\x04\x04\x03\
-    \x02\0\x12\x03B\x02\x17\n\x0c\n\x05\x04\x03\x02\0\x05\x12\x03B\x02\x08\n\
-    \x0c\n\x05\x04\x03\x02\0\x01\x12\x03B\t\x12\n\x0c\n\x05\x04\x03\x02\0\
-    \x03\x12\x03B\x15\x16\n\x0b\n\x04\x04\x03\x02\x01\x12\x03C\x02\x16\n\x0c\
-    \n\x05\x04\x03\x02\x01\x05\x12\x03C\x02\x08\n\x0c\n\x05\x04\x03\x02\x01\
-    \x01\x12\x03C\t\x11\n\x0c\n\x05\x04\x03\x02\x01\x03\x12\x03C\x14\x15\n\
-    \x0b\n\x04\x04\x03\x02\x02\x12\x03D\x02\x15\n\x0c\n\x05\x04\x03\x02\x02\
-    \x05\x12\x03D\x02\x08\n\x0c\n\x05\x04\x03\x02\x02\x01\x12\x03D\t\x10\n\
-    \x0c\n\x05\x04\x03\x02\x02\x03\x12\x03D\x13\x14\n\x0b\n\x04\x04\x03\x02\
-    \x03\x12\x03E\x02\x15\n\x0c\n\x05\x04\x03\x02\x03\x05\x12\x03E\x02\x08\n\
-    \x0c\n\x05\x04\x03\x02\x03\x01\x12\x03E\t\x10\n\x0c\n\x05\x04\x03\x02\
-    \x03\x03\x12\x03E\x13\x14\n\x0b\n\x04\x04\x03\x02\x04\x12\x03F\x02\x13\n\
-    \x0c\n\x05\x04\x03\x02\x04\x05\x12\x03F\x02\x08\n\x0c\n\x05\x04\x03\x02\
-    \x04\x01\x12\x03F\t\x0e\n\x0c\n\x05\x04\x03\x02\x04\x03\x12\x03F\x11\x12\
-    \nX\n\x04\x04\x03\x02\x05\x12\x03H\x02\x14\x1aK\x20Positive\x20number\
-    \x20encoded\x20as\x20string.\x20For\x20negative\x20transfers\x20use\x20`\
-    direction`\n\n\x0c\n\x05\x04\x03\x02\x05\x05\x12\x03H\x02\x08\n\x0c\n\
-    \x05\x04\x03\x02\x05\x01\x12\x03H\t\x0f\n\x0c\n\x05\x04\x03\x02\x05\x03\
-    \x12\x03H\x12\x13\n\x0b\n\x04\x04\x03\x02\x06\x12\x03I\x02\x1d\n\x0c\n\
-    \x05\x04\x03\x02\x06\x06\x12\x03I\x02\x0c\n\x0c\n\x05\x04\x03\x02\x06\
-    \x01\x12\x03I\r\x18\n\x0c\n\x05\x04\x03\x02\x06\x03\x12\x03I\x1b\x1c\n$\
-    \n\x04\x04\x03\x02\x07\x12\x03K\x02\x1a\x1a\x17\x20Direction\x20of\x20tr\
-    ansfer\n\n\x0c\n\x05\x04\x03\x02\x07\x06\x12\x03K\x02\x0b\n\x0c\n\x05\
-    \x04\x03\x02\x07\x01\x12\x03K\x0c\x15\n\x0c\n\x05\x04\x03\x02\x07\x03\
-    \x12\x03K\x18\x19\n\x0c\n\x04\x04\x03\x04\0\x12\x04M\x02Q\x03\n\x0c\n\
-    \x05\x04\x03\x04\0\x01\x12\x03M\x07\x11\n\r\n\x06\x04\x03\x04\0\x02\0\
-    \x12\x03N\x04\x14\n\x0e\n\x07\x04\x03\x04\0\x02\0\x01\x12\x03N\x04\x0f\n\
-    \x0e\n\x07\x04\x03\x04\0\x02\0\x02\x12\x03N\x12\x13\n\r\n\x06\x04\x03\
-    \x04\0\x02\x01\x12\x03O\x04\x11\n\x0e\n\x07\x04\x03\x04\0\x02\x01\x01\
-    \x12\x03O\x04\x0c\n\x0e\n\x07\x04\x03\x04\0\x02\x01\x02\x12\x03O\x0f\x10\
-    \n\r\n\x06\x04\x03\x04\0\x02\x02\x12\x03P\x04\x0c\n\x0e\n\x07\x04\x03\
-    \x04\0\x02\x02\x01\x12\x03P\x04\x07\n\x0e\n\x07\x04\x03\x04\0\x02\x02\
-    \x02\x12\x03P\n\x0b\nP\n\x02\x04\x04\x12\x04V\0Z\x01\x1aD\n\x20Cursor\
-    \x20used\x20for\x20querying\x20the\x20transaction\x20history\x20from\x20\
-    Emerald\x20API\n\n\n\n\x03\x04\x04\x01\x12\x03V\x08\x0e\n\x0b\n\x04\x04\
-    \x04\x02\0\x12\x03W\x02\x15\n\x0c\n\x05\x04\x04\x02\0\x05\x12\x03W\x02\
-    \x08\n\x0c\n\x05\x04\x04\x02\0\x01\x12\x03W\t\x10\n\x0c\n\x05\x04\x04\
-    \x02\0\x03\x12\x03W\x13\x14\n\x0b\n\x04\x04\x04\x02\x01\x12\x03X\x02\x13\
-    \n\x0c\n\x05\x04\x04\x02\x01\x05\x12\x03X\x02\x08\n\x0c\n\x05\x04\x04\
-    \x02\x01\x01\x12\x03X\t\x0e\n\x0c\n\x05\x04\x04\x02\x01\x03\x12\x03X\x11\
-    \x12\n\x0b\n\x04\x04\x04\x02\x02\x12\x03Y\x02\x10\n\x0c\n\x05\x04\x04\
-    \x02\x02\x05\x12\x03Y\x02\x08\n\x0c\n\x05\x04\x04\x02\x02\x01\x12\x03Y\t\
-    \x0b\n\x0c\n\x05\x04\x04\x02\x02\x03\x12\x03Y\x0e\x0fb\x06proto3\
+    \n\x12transactions.proto\x12\remerald.state\"\xc2\x06\n\x0bTransaction\
+    \x12=\n\nblockchain\x18\x01\x20\x01(\x0e2\x1b.emerald.state.BlockchainId\
+    R\nblockchainB\0\x12\x15\n\x05tx_id\x18\x02\x20\x01(\tR\x04txIdB\0\x12)\
+    \n\x0fsince_timestamp\x18\x03\x20\x01(\x04R\x0esinceTimestampB\0\x12'\n\
+    \x0esync_timestamp\x18\x04\x20\x01(\x04R\rsyncTimestampB\0\x12-\n\x11con\
+    firm_timestamp\x18\x05\x20\x01(\x04R\x10confirmTimestampB\0\x12,\n\x05st\
+    ate\x18\x06\x20\x01(\x0e2\x14.emerald.state.StateR\x05stateB\0\x12/\n\
+    \x05block\x18\x07\x20\x01(\x0b2\x17.emerald.state.BlockRefR\x05blockB\0\
+    \x12\x1d\n\tblock_pos\x18\x0b\x20\x01(\rR\x08blockPosB\0\x12/\n\x06statu\
+    s\x18\x08\x20\x01(\x0e2\x15.emerald.state.StatusR\x06statusB\0\x121\n\
+    \x07changes\x18\t\x20\x03(\x0b2\x15.emerald.state.ChangeR\x07changesB\0\
+    \x12\x1a\n\x07version\x18\n\x20\x01(\x04R\x07versionB\0\x126\n\tnonce_re\
+    f\x18\x0c\x20\x01(\x0b2\x17.emerald.state.NonceRefR\x08nonceRefB\0\x12!\
+    \n\x0breplaced_by\x18\r\x20\x01(\tR\nreplacedByB\0\x12-\n\x11internal_tr\
+    ansfer\x18\x0e\x20\x01(\x08R\x10internalTransferB\0\x125\n\x06inputs\x18\
+    \x0f\x20\x03(\x0b2\x1b.emerald.state.BitcoinInputR\x06inputsB\0\x128\n\
+    \x07outputs\x18\x10\x20\x03(\x0b2\x1c.emerald.state.BitcoinOutputR\x07ou\
+    tputsB\0\x12\x16\n\x05vsize\x18\x11\x20\x01(\rR\x05vsizeB\0\x12G\n\x0fto\
+    ken_transfers\x18\x12\x20\x03(\x0b2\x1c.emerald.state.TokenTransferR\x0e\
+    tokenTransfersB\0:\0\"\xe9\x01\n\rTokenTransfer\x12\x1d\n\tlog_index\x18\
+    \x01\x20\x01(\rR\x08logIndexB\0\x12\x1c\n\x08contract\x18\x02\x20\x01(\t\
+    R\x08contractB\0\x12:\n\x08standard\x18\x03\x20\x01(\x0e2\x1c.emerald.st\
+    ate.TokenStandardR\x08standardB\0\x12\x14\n\x04from\x18\x04\x20\x01(\tR\
+    \x04fromB\0\x12\x10\n\x02to\x18\x05\x20\x01(\tR\x02toB\0\x12\x18\n\x06am\
+    ount\x18\x06\x20\x01(\tR\x06amountB\0\x12\x1b\n\x08token_id\x18\x07\x20\
+    \x01(\tR\x07tokenIdB\0:\0\"\xc4\x01\n\x0cBitcoinInput\x12\x14\n\x04txid\
+    \x18\x01\x20\x01(\tR\x04txidB\0\x12\x14\n\x04vout\x18\x02\x20\x01(\rR\
+    \x04voutB\0\x12\x18\n\x06amount\x18\x03\x20\x01(\x04R\x06amountB\0\x12<\
+    \n\x0bscript_type\x18\x04\x20\x01(\x0e2\x19.emerald.state.ScriptTypeR\ns\
+    criptTypeB\0\x12\x1a\n\x07address\x18\x05\x20\x01(\tR\x07addressB\0\x12\
+    \x12\n\x03own\x18\x06\x20\x01(\x08R\x03ownB\0:\0\"\xaf\x01\n\rBitcoinOut\
+    put\x12\x14\n\x04vout\x18\x01\x20\x01(\rR\x04voutB\0\x12\x18\n\x06amount\
+    \x18\x02\x20\x01(\x04R\x06amountB\0\x12<\n\x0bscript_type\x18\x03\x20\
+    \x01(\x0e2\x19.emerald.state.ScriptTypeR\nscriptTypeB\0\x12\x1a\n\x07add\
+    ress\x18\x04\x20\x01(\tR\x07addressB\0\x12\x12\n\x03own\x18\x05\x20\x01(\
+    \x08R\x03ownB\0:\0\":\n\x08NonceRef\x12\x14\n\x04from\x18\x01\x20\x01(\t\
+    R\x04fromB\0\x12\x16\n\x05nonce\x18\x02\x20\x01(\x04R\x05nonceB\0:\0\"\
+    \xc3\x02\n\x0fTransactionMeta\x12\x1e\n\ttimestamp\x18\x01\x20\x01(\x04R\
+    \ttimestampB\0\x12=\n\nblockchain\x18\x02\x20\x01(\x0e2\x1b.emerald.stat\
+    e.BlockchainIdR\nblockchainB\0\x12\x15\n\x05tx_id\x18\x03\x20\x01(\tR\
+    \x04txIdB\0\x12\x16\n\x05label\x18\x04\x20\x01(\tR\x05labelB\0\x12\x12\n\
+    \x03raw\x18\x05\x20\x01(\x0cR\x03rawB\0\x12\x14\n\x04tags\x18\x06\x20\
+    \x03(\tR\x04tagsB\0\x12\x1b\n\x08raw_hash\x18\x07\x20\x01(\x0cR\x07rawHa\
+    shB\0\x12\x1a\n\x07starred\x18\x08\x20\x01(\x08R\x07starredB\0\x12\x18\n\
+    \x06hidden\x18\t\x20\x01(\x08R\x06hiddenB\0\x12#\n\x0cneeds_review\x18\n\
+    \x20\x01(\x08R\x0bneedsReviewB\0:\0\"\xb1\x01\n\x0cMetaRevision\x12\x12\
+    \n\x03seq\x18\x01\x20\x01(\x04R\x03seqB\0\x12!\n\x0brecorded_at\x18\x02\
+    \x20\x01(\x04R\nrecordedAtB\0\x12\x14\n\x04meta\x18\x03\x20\x01(\x0cR\
+    \x04metaB\0\x12\x1d\n\tprev_hash\x18\x04\x20\x01(\x0cR\x08prevHashB\0\
+    \x12\x14\n\x04hash\x18\x05\x20\x01(\x0cR\x04hashB\0\x12\x1d\n\twrite_seq\
+    \x18\x06\x20\x01(\x04R\x08writeSeqB\0:\0\"c\n\x08BlockRef\x12\x18\n\x06h\
+    eight\x18\x01\x20\x01(\x04R\x06heightB\0\x12\x1b\n\x08block_id\x18\x02\
+    \x20\x01(\tR\x07blockIdB\0\x12\x1e\n\ttimestamp\x18\x03\x20\x01(\x04R\tt\
+    imestampB\0:\0\"\xe6\x02\n\x06Change\x12\x1d\n\twallet_id\x18\x01\x20\
+    \x01(\tR\x08walletIdB\0\x12\x1b\n\x08entry_id\x18\x02\x20\x01(\rR\x07ent\
+    ryIdB\0\x12\x1a\n\x07address\x18\x03\x20\x01(\tR\x07addressB\0\x12\x19\n\
+    \x07hd_path\x18\x04\x20\x01(\tR\x06hdPathB\0\x12\x16\n\x05asset\x18\x05\
+    \x20\x01(\tR\x05assetB\0\x12\x18\n\x06amount\x18\x06\x20\x01(\tR\x06amou\
+    ntB\0\x12C\n\x0bchange_type\x18\x07\x20\x01(\x0e2\x20.emerald.state.Chan\
+    ge.ChangeTypeR\nchangeTypeB\0\x128\n\tdirection\x18\x08\x20\x01(\x0e2\
+    \x18.emerald.state.DirectionR\tdirectionB\0\"6\n\nChangeType\x12\x0f\n\
+    \x0bUNSPECIFIED\x10\0\x12\x0c\n\x08TRANSFER\x10\x01\x12\x07\n\x03FEE\x10\
+    \x02\x1a\0:\0\"P\n\x06Cursor\x12\x1a\n\x07address\x18\x01\x20\x01(\tR\
+    \x07addressB\0\x12\x16\n\x05value\x18\x02\x20\x01(\tR\x05valueB\0\x12\
+    \x10\n\x02ts\x18\x03\x20\x01(\x04R\x02tsB\0:\0\"\xd9\x01\n\tFirstSeen\
+    \x12\x1a\n\x07address\x18\x01\x20\x01(\tR\x07addressB\0\x12=\n\nblockcha\
+    in\x18\x02\x20\x01(\x0e2\x1b.emerald.state.BlockchainIdR\nblockchainB\0\
+    \x12\x1e\n\ttimestamp\x18\x03\x20\x01(\x04R\ttimestampB\0\x12\x15\n\x05t\
+    x_id\x18\x04\x20\x01(\tR\x04txIdB\0\x128\n\tdirection\x18\x05\x20\x01(\
+    \x0e2\x18.emerald.state.DirectionR\tdirectionB\0:\0*G\n\rTokenStandard\
+    \x12\x11\n\rTOKEN_UNKNOWN\x10\0\x12\x0f\n\x0bTOKEN_ERC20\x10\x01\x12\x10\
+    \n\x0cTOKEN_ERC721\x10\x02\x1a\0*\x91\x01\n\nScriptType\x12\x12\n\x0eSCR\
+    IPT_UNKNOWN\x10\0\x12\x10\n\x0cSCRIPT_P2PKH\x10\x01\x12\x0f\n\x0bSCRIPT_\
+    P2SH\x10\x02\x12\x11\n\rSCRIPT_P2WPKH\x10\x03\x12\x10\n\x0cSCRIPT_P2WSH\
+    \x10\x04\x12\x0f\n\x0bSCRIPT_P2TR\x10\x05\x12\x14\n\x10SCRIPT_OP_RETURN\
+    \x10\x06\x1a\0*\x8e\x02\n\x0cBlockchainId\x12\x15\n\x11CHAIN_UNSPECIFIED\
+    \x10\0\x12\x11\n\rCHAIN_BITCOIN\x10\x01\x12\x12\n\x0eCHAIN_ETHEREUM\x10d\
+    \x12\x1a\n\x16CHAIN_ETHEREUM_CLASSIC\x10e\x12\x11\n\x0cCHAIN_MORDEN\x10\
+    \x91N\x12\x10\n\x0bCHAIN_KOVAN\x10\x92N\x12\x1a\n\x15CHAIN_TESTNET_BITCO\
+    IN\x10\x93N\x12\x11\n\x0cCHAIN_GOERLI\x10\x95N\x12\x12\n\rCHAIN_ROPSTEN\
+    \x10\x96N\x12\x12\n\rCHAIN_RINKEBY\x10\x97N\x12\x12\n\rCHAIN_HOLESKY\x10\
+    \x98N\x12\x12\n\rCHAIN_SEPOLIA\x10\x99N\x1a\0*P\n\x05State\x12\x0c\n\x08\
+    PREPARED\x10\0\x12\r\n\tSUBMITTED\x10\n\x12\x0c\n\x08REPLACED\x10\x0b\
+    \x12\r\n\tCONFIRMED\x10\x0c\x12\x0b\n\x07DROPPED\x10\x14\x1a\0*+\n\x06St\
+    atus\x12\x0b\n\x07UNKNOWN\x10\0\x12\x06\n\x02OK\x10\x01\x12\n\n\x06FAILE\
+    D\x10\x02\x1a\0*$\n\tDirection\x12\x0b\n\x07RECEIVE\x10\0\x12\x08\n\x04S\
+    END\x10\x01\x1a\0B\0b\x06proto3\
 ";
 
 static file_descriptor_proto_lazy: ::protobuf::rt::LazyV2<::protobuf::descriptor::FileDescriptorProto> = ::protobuf::rt::LazyV2::INIT;
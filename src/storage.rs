@@ -10,7 +10,27 @@ pub(crate) mod trigrams;
 pub mod balance_store;
 pub mod cache_store;
 pub mod allowance_store;
+pub mod sync_state_store;
+pub mod quarantine_store;
+pub mod address_registry_store;
+pub mod nonce_store;
+pub mod task_store;
+pub mod flag_store;
+pub mod token_store;
+pub mod ttl_policy;
+pub mod health;
+pub mod tuning;
 mod version;
+mod projection;
+mod backup;
+pub mod mirror;
+pub mod wipe;
+pub mod diff;
+mod transaction_json;
+pub mod meta_proof;
+mod sequence;
+pub mod rate_limit;
+mod notification;
 
 /// Default path (*nix)
 #[cfg(all(
@@ -0,0 +1,230 @@
+use std::str::FromStr;
+use std::sync::Arc;
+use sled::Db;
+use uuid::Uuid;
+use crate::access::address_registry::{AddressRegistry, EntryRef};
+use crate::access::permissions::{Permissions, Store};
+use crate::errors::{InvalidValueError, StateError};
+use crate::storage::rate_limit::RateLimiter;
+use crate::storage::health::Health;
+use crate::validate;
+use crate::storage::sequence::Batch;
+
+///
+/// # Storage:
+///
+/// - `entry_addr:<BLOCKCHAIN>/<ADDRESS>` with `<WALLET_ID>/<ENTRY_ID>` of the entry the address belongs to
+/// - `entry_addr_idx:<WALLET_ID>/<ENTRY_ID>/<BLOCKCHAIN>/<ADDRESS>` to list the addresses of an entry, with an empty value
+///
+const PREFIX_KEY: &str = "entry_addr:";
+const PREFIX_IDX: &str = "entry_addr_idx:";
+
+pub struct AddressRegistryAccess {
+    pub(crate) db: Arc<Db>,
+    pub(crate) permissions: Permissions,
+    pub(crate) limiter: Arc<RateLimiter>,
+    pub(crate) health: Arc<Health>,
+}
+
+///
+/// Address in the form it's stored. Ethereum addresses are case-insensitive so they are kept in lowercase, others as is.
+pub(crate) fn normalize_address(address: &str) -> Result<String, StateError> {
+    let address = address.trim();
+    validate::check_address(address)?;
+    if validate::check_ethereum_address(address).is_ok() {
+        Ok(address.to_lowercase())
+    } else {
+        Ok(address.to_string())
+    }
+}
+
+fn get_key(blockchain: u32, address: &str) -> String {
+    format!("{}{}/{}", PREFIX_KEY, blockchain, address)
+}
+
+fn get_idx_key(entry: &EntryRef, blockchain: u32, address: &str) -> String {
+    format!("{}{}/{}/{}/{}", PREFIX_IDX, entry.wallet_id, entry.entry_id, blockchain, address)
+}
+
+///
+/// Blockchain and address from the index key, which ends with `<BLOCKCHAIN>/<ADDRESS>`
+fn decode_idx_key(key: &[u8]) -> Result<(u32, String), StateError> {
+    let key = std::str::from_utf8(key).map_err(|_| StateError::CorruptedValue)?;
+    let mut parts = key.rsplitn(3, '/');
+    let address = parts.next().ok_or(StateError::CorruptedValue)?;
+    let blockchain = parts.next().and_then(|b| u32::from_str(b).ok()).ok_or(StateError::CorruptedValue)?;
+    Ok((blockchain, address.to_string()))
+}
+
+fn encode_entry(entry: &EntryRef) -> String {
+    format!("{}/{}", entry.wallet_id, entry.entry_id)
+}
+
+fn decode_entry(value: &[u8]) -> Result<EntryRef, StateError> {
+    let value = std::str::from_utf8(value).map_err(|_| StateError::CorruptedValue)?;
+    let (wallet_id, entry_id) = value.split_once('/').ok_or(StateError::CorruptedValue)?;
+    Ok(EntryRef {
+        wallet_id: Uuid::from_str(wallet_id).map_err(|_| StateError::CorruptedValue)?,
+        entry_id: u32::from_str(entry_id).map_err(|_| StateError::CorruptedValue)?,
+    })
+}
+
+///
+/// Find the entry of the address on the blockchain for the transaction attribution. Gives `None` if the address is not
+/// known, or it's not permitted to read the registry, or the address is not valid.
+pub(crate) fn find(db: &Db, permissions: &Permissions, blockchain: u32, address: &str) -> Option<EntryRef> {
+    if !permissions.can_read(Store::AddressRegistry) {
+        return None
+    }
+    let address = normalize_address(address).ok()?;
+    db.get(get_key(blockchain, address.as_str())).ok()
+        .flatten()
+        .and_then(|value| decode_entry(value.as_ref()).ok())
+}
+
+impl AddressRegistryAccess {
+
+    ///
+    /// Remove the addresses with the index keys starting with the `prefix`. Returns the removed addresses.
+    fn remove_indexed(&self, prefix: String, batch: &mut Batch) -> Result<Vec<String>, StateError> {
+        let mut removed = Vec::new();
+        for key in self.db.scan_prefix(prefix.as_bytes()).keys() {
+            let key = key?;
+            let (blockchain, address) = decode_idx_key(key.as_ref())?;
+            batch.remove(get_key(blockchain, address.as_str()).as_bytes());
+            batch.remove(key);
+            removed.push(address);
+        }
+        Ok(removed)
+    }
+
+    ///
+    /// Put the removal of all addresses of the wallet into the batch. Returns the removed addresses.
+    pub(crate) fn wipe_wallet(&self, wallet_id: Uuid, batch: &mut Batch) -> Result<Vec<String>, StateError> {
+        self.remove_indexed(format!("{}{}/", PREFIX_IDX, wallet_id), batch)
+    }
+}
+
+impl AddressRegistry for AddressRegistryAccess {
+
+    fn set_addresses(&self, entry: EntryRef, blockchain: u32, addresses: Vec<String>) -> Result<(), StateError> {
+        self.permissions.check_write(Store::AddressRegistry)?;
+        self.limiter.check(Store::AddressRegistry)?;
+        let addresses = addresses.iter()
+            .map(|a| normalize_address(a.as_str()))
+            .collect::<Result<Vec<String>, StateError>>()
+            .map_err(|_| StateError::InvalidValue(InvalidValueError::Name("addresses".to_string())))?;
+        let mut batch = Batch::default();
+        self.remove_indexed(format!("{}{}/{}/", PREFIX_IDX, entry.wallet_id, entry.entry_id), &mut batch)?;
+        for address in addresses {
+            let key = get_key(blockchain, address.as_str());
+            if let Some(current) = self.db.get(key.as_bytes())? {
+                if let Ok(current) = decode_entry(current.as_ref()) {
+                    if current != entry {
+                        batch.remove(get_idx_key(&current, blockchain, address.as_str()).as_bytes());
+                    }
+                }
+            }
+            batch.insert(key.as_bytes(), encode_entry(&entry).as_bytes());
+            batch.insert(get_idx_key(&entry, blockchain, address.as_str()).as_bytes(), vec![]);
+        }
+        self.health.apply(&self.db, batch)?;
+        Ok(())
+    }
+
+    fn get_addresses(&self, entry: EntryRef) -> Result<Vec<(u32, String)>, StateError> {
+        self.permissions.check_read(Store::AddressRegistry)?;
+        let prefix = format!("{}{}/{}/", PREFIX_IDX, entry.wallet_id, entry.entry_id);
+        let mut result = Vec::new();
+        for key in self.db.scan_prefix(prefix.as_bytes()).keys() {
+            result.push(decode_idx_key(key?.as_ref())?);
+        }
+        Ok(result)
+    }
+
+    fn find_entry(&self, blockchain: u32, address: String) -> Result<Option<EntryRef>, StateError> {
+        self.permissions.check_read(Store::AddressRegistry)?;
+        let address = normalize_address(address.as_str())?;
+        match self.db.get(get_key(blockchain, address.as_str()))? {
+            Some(value) => Ok(Some(decode_entry(value.as_ref())?)),
+            None => Ok(None)
+        }
+    }
+
+    fn remove_wallet(&self, wallet_id: Uuid) -> Result<usize, StateError> {
+        self.permissions.check_write(Store::AddressRegistry)?;
+        self.limiter.check(Store::AddressRegistry)?;
+        let mut batch = Batch::default();
+        let count = self.wipe_wallet(wallet_id, &mut batch)?.len();
+        if count > 0 {
+            self.health.apply(&self.db, batch)?;
+        }
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use tempdir::TempDir;
+    use uuid::Uuid;
+    use crate::access::address_registry::{AddressRegistry, EntryRef};
+    use crate::access::transactions::Transactions;
+    use crate::proto::transactions::{BlockchainId, Change as proto_Change, Transaction as proto_Transaction};
+    use crate::storage::sled_access::SledStorage;
+
+    #[test]
+    fn set_and_find_addresses() {
+        let tmp_dir = TempDir::new("registry").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let registry = access.get_address_registry();
+        let entry = EntryRef { wallet_id: Uuid::from_str("72279ede-44c4-4951-925b-f51a7b9e929a").unwrap(), entry_id: 1 };
+        let other = EntryRef { entry_id: 2, ..entry };
+
+        registry.set_addresses(entry, 1, vec![
+            "0xA0b86991c6218b36c1d19d4a2e9eb0ce3606eB48".to_string(),
+            "bc1qxy2kgdygjrsqtzq2n0yrf2493p83kkfjhx0wlh".to_string(),
+        ]).unwrap();
+        registry.set_addresses(other, 1, vec!["bc1qxy2kgdygjrsqtzq2n0yrf2493p83kkfjhx0wlh".to_string()]).unwrap();
+
+        assert_eq!(registry.find_entry(1, "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string()).unwrap(), Some(entry));
+        // moved to another entry
+        assert_eq!(registry.find_entry(1, "bc1qxy2kgdygjrsqtzq2n0yrf2493p83kkfjhx0wlh".to_string()).unwrap(), Some(other));
+        assert_eq!(registry.get_addresses(entry).unwrap(), vec![(1, "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string())]);
+        // known only on the specified blockchain
+        assert!(registry.find_entry(100, "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string()).unwrap().is_none());
+
+        assert_eq!(registry.remove_wallet(entry.wallet_id).unwrap(), 2);
+        assert!(registry.find_entry(1, "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string()).unwrap().is_none());
+        assert!(registry.get_addresses(other).unwrap().is_empty());
+    }
+
+    #[test]
+    fn attribute_submitted_tx() {
+        let tmp_dir = TempDir::new("registry").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let registry = access.get_address_registry();
+        let transactions = access.get_transactions();
+        let entry = EntryRef { wallet_id: Uuid::from_str("72279ede-44c4-4951-925b-f51a7b9e929a").unwrap(), entry_id: 1 };
+        registry.set_addresses(entry, 100, vec!["0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string()]).unwrap();
+        // same address on another blockchain
+        registry.set_addresses(EntryRef { entry_id: 2, ..entry }, 101, vec!["0x6218b36c1d19d4a2e9eb0ce3606eb48a0b86991c".to_string()]).unwrap();
+
+        let mut tx = proto_Transaction::new();
+        tx.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        tx.since_timestamp = 1_647_313_850_992;
+        let mut change = proto_Change::new();
+        change.address = "0xA0B86991C6218B36C1D19D4A2E9EB0CE3606EB48".to_string();
+        tx.changes.push(change);
+        let mut change = proto_Change::new();
+        change.address = "0x6218b36c1d19d4a2e9eb0ce3606eb48a0b86991c".to_string();
+        tx.changes.push(change);
+        transactions.submit(vec![tx.clone()]).unwrap();
+
+        let act = transactions.get_tx(100, tx.tx_id.as_str()).unwrap();
+        assert_eq!(act.changes[0].wallet_id, "72279ede-44c4-4951-925b-f51a7b9e929a");
+        assert_eq!(act.changes[0].entry_id, 1);
+        assert!(act.changes[1].wallet_id.is_empty());
+    }
+}
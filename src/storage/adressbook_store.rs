@@ -1,23 +1,40 @@
-use std::collections::HashSet;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::convert::TryFrom;
 use std::ops::{Bound, Deref};
 use std::str::FromStr;
 use std::sync::Arc;
 use bitcoin::Address;
-use protobuf::Message;
-use sled::{Batch, Db};
+use protobuf::{Message, ProtobufEnum};
+use num_bigint::BigUint;
+use num_traits::Zero;
+use sled::Db;
 use uuid::Uuid;
-use chrono::{Utc};
+use chrono::{DateTime, Utc};
+use log::warn;
 use emerald_vault::blockchain::bitcoin::XPub;
-use crate::access::addressbook::{AddressBook, BookItemEnriched, Filter};
+use crate::access::addressbook::{AddressBook, BookItemEnriched, CorruptedBookItem, BookItemField, BookItemSort, ContactUsage, DuplicatePolicy, Filter, FUZZY_MIN_SCORE, ScoredBookItem, XPUB_GAP_LIMIT};
+use crate::access::asset::AssetId;
 use crate::access::pagination::{Cursor, PageQuery, PageResult};
+use crate::access::validation::{CheckedWrite, ValidationMode, ValidationWarning, Validators};
 use crate::access::xpubpos::XPubPosition;
-use crate::errors::StateError;
-use crate::proto::addressbook::{Address_AddressType, BookItem as proto_BookItem};
+use crate::access::permissions::{Permissions, Store};
+use crate::errors::{InvalidValueError, StateError};
+use crate::proto::addressbook::{Address_AddressType, Avatar, BookItem as proto_BookItem, BookItemBundle as proto_BookItemBundle, BookItemUsage as proto_BookItemUsage, DomainResolution, NetworkKind};
+use crate::proto::quarantine::StoreType;
+use crate::storage::tuning::Profiler;
 use crate::storage::indexing::{IndexConvert, IndexedValue, IndexEncoding, Indexing, QueryRanges};
+use crate::storage::projection::decode_projected;
+use crate::storage::quarantine_store;
 use crate::storage::trigrams::Trigram;
+use crate::storage::rate_limit::RateLimiter;
+use crate::storage::health::Health;
+use crate::storage::version::Migration;
+use crate::storage::sequence::Batch;
 
 const PREFIX_KEY: &'static str = "addrbook";
 const PREFIX_IDX: &'static str = "idx:addrbook";
+const PREFIX_USAGE: &str = "book_usage:";
 
 enum IndexType {
     // `<ADDR>/<TIMESTAMP>`
@@ -25,7 +42,17 @@ enum IndexType {
     // `/<TIMESTAMP>`
     Everything(u64),
     // `/<TRIGRAM>/<TIMESTAMP>` timestamp is mostly used for uniquiness, but also gives a useful order
-    ByTrigram(String, u64)
+    ByTrigram(String, u64),
+    // `<NETWORK_KIND>/<TIMESTAMP>`
+    ByNetwork(NetworkKind, u64),
+    // `<GROUP>/<TIMESTAMP>`, with the group in lowercase
+    ByGroup(String, u64),
+    // `L<LABEL>/<ID>`, with the label in lowercase, or `U/<ID>` for an item without a label, so they are after the others
+    ByLabel(String, String),
+    // `<UPDATE_TIMESTAMP>/<ID>`
+    ByUpdated(u64, String),
+    // `<BLOCKCHAIN>/<TIMESTAMP>`
+    ByBlockchain(u32, u64),
 }
 
 impl IndexType {
@@ -34,6 +61,11 @@ impl IndexType {
             IndexType::Everything(_) => 1,
             IndexType::ByAddress(_, _) => 2,
             IndexType::ByTrigram(_, _) => 3,
+            IndexType::ByNetwork(_, _) => 4,
+            IndexType::ByGroup(_, _) => 5,
+            IndexType::ByLabel(_, _) => 6,
+            IndexType::ByUpdated(_, _) => 7,
+            IndexType::ByBlockchain(_, _) => 8,
         }
     }
 }
@@ -44,25 +76,76 @@ impl IndexEncoding for IndexType {
             IndexType::ByAddress(addr, ts) => format!("{}:{:}/{:}/{:}", PREFIX_IDX, self.get_prefix(), addr, IndexConvert::get_desc_timestamp(*ts)),
             IndexType::Everything(ts) => format!("{}:{:}/{:}", PREFIX_IDX, self.get_prefix(), IndexConvert::get_desc_timestamp(*ts)),
             IndexType::ByTrigram(s, ts) => format!("{}:{:}/{:}/{:}", PREFIX_IDX, self.get_prefix(), s, IndexConvert::get_desc_timestamp(*ts)),
+            IndexType::ByNetwork(network, ts) => format!("{}:{:}/{:}/{:}", PREFIX_IDX, self.get_prefix(), network.value(), IndexConvert::get_desc_timestamp(*ts)),
+            IndexType::ByGroup(group, ts) => format!("{}:{:}/{:}/{:}", PREFIX_IDX, self.get_prefix(), group, IndexConvert::get_desc_timestamp(*ts)),
+            IndexType::ByLabel(label, id) => if label.is_empty() {
+                format!("{}:{:}/U/{:}", PREFIX_IDX, self.get_prefix(), id)
+            } else {
+                format!("{}:{:}/L{:}/{:}", PREFIX_IDX, self.get_prefix(), label, id)
+            },
+            IndexType::ByUpdated(ts, id) => format!("{}:{:}/{:}/{:}", PREFIX_IDX, self.get_prefix(), IndexConvert::get_desc_timestamp(*ts), id),
+            IndexType::ByBlockchain(blockchain, ts) => format!("{}:{:}/{:}/{:}", PREFIX_IDX, self.get_prefix(), blockchain, IndexConvert::get_desc_timestamp(*ts)),
         }
     }
 }
 
 impl QueryRanges for Filter {
     fn get_index_bounds(&self) -> (Bound<String>, Bound<String>) {
+        // a sorted query goes through all items in the order of the sort, and the other criteria are only checked
+        let sort_prefix = match self.sort {
+            BookItemSort::Created => None,
+            BookItemSort::Label => Some(IndexType::ByLabel(String::new(), String::new()).get_prefix()),
+            BookItemSort::Updated => Some(IndexType::ByUpdated(0, String::new()).get_prefix()),
+        };
+        if let Some(prefix) = sort_prefix {
+            if let (BookItemSort::Updated, Some(updated_after)) = (self.sort, self.updated_after) {
+                // the index is in the descending order, so it ends right after the last key with that timestamp
+                let end = format!("{}:{}/{}0", PREFIX_IDX, prefix, IndexConvert::get_desc_timestamp(updated_after.timestamp_millis() as u64));
+                return (Bound::Included(format!("{}:{}/", PREFIX_IDX, prefix)), Bound::Excluded(end))
+            }
+            // `0` is the next char after the `/` separator
+            return (Bound::Included(format!("{}:{}/", PREFIX_IDX, prefix)), Bound::Excluded(format!("{}:{}0", PREFIX_IDX, prefix)))
+        }
+
+        // all other indexes are ordered by the creation time
+        let now = self.created_before
+            .map(|ts| ts.timestamp_millis() as u64)
+            .unwrap_or_else(|| Utc::now().timestamp_millis() as u64);
+        // never an inverted range, for an empty one the filter rejects the items anyway
+        let first = self.created_after.map(|ts| ts.timestamp_millis() as u64).unwrap_or(0).min(now);
+
         // use the index build over the text
         if let Some(text) = &self.text {
             if let Some(b) = Trigram::search_bound(&text) {
-                let start = IndexType::ByTrigram(b.clone(), 0).get_index_key();
-                let now = IndexType::ByTrigram(b, Utc::now().naive_utc().timestamp_millis() as u64).get_index_key();
+                let start = IndexType::ByTrigram(b.clone(), first).get_index_key();
+                let now = IndexType::ByTrigram(b, now).get_index_key();
                 // timestamp index is built on descending order
                 return (Bound::Included(now), Bound::Included(start))
             }
         }
 
+        if let Some(group) = &self.group {
+            let group = proto_BookItem::normalize_group(group);
+            let start = IndexType::ByGroup(group.clone(), first).get_index_key();
+            let now = IndexType::ByGroup(group, now).get_index_key();
+            return (Bound::Included(now), Bound::Included(start))
+        }
+
+        if let Some(blockchain) = self.blockchain {
+            let start = IndexType::ByBlockchain(blockchain, first).get_index_key();
+            let now = IndexType::ByBlockchain(blockchain, now).get_index_key();
+            return (Bound::Included(now), Bound::Included(start))
+        }
+
+        if let Some(network) = self.network {
+            let start = IndexType::ByNetwork(network, first).get_index_key();
+            let now = IndexType::ByNetwork(network, now).get_index_key();
+            return (Bound::Included(now), Bound::Included(start))
+        }
+
         // just scan everythign for other queries
-        let now = IndexType::Everything(Utc::now().naive_utc().timestamp_millis() as u64).get_index_key();
-        let start = IndexType::Everything(0).get_index_key();
+        let now = IndexType::Everything(now).get_index_key();
+        let start = IndexType::Everything(first).get_index_key();
         // timestamp index is built on descending order
         (Bound::Included(now), Bound::Included(start))
     }
@@ -77,11 +160,40 @@ impl IndexedValue<IndexType> for proto_BookItem {
         let ts = self.create_timestamp;
 
         keys.push(IndexType::Everything(ts));
+        keys.push(IndexType::ByBlockchain(self.blockchain, ts));
+        if self.network != NetworkKind::NETWORK_UNSPECIFIED {
+            keys.push(IndexType::ByNetwork(self.network, ts));
+        }
+        let group = proto_BookItem::normalize_group(&self.group);
+        if !group.is_empty() {
+            keys.push(IndexType::ByGroup(group, ts));
+        }
 
         let label = self.get_label().trim();
         if !label.is_empty() {
             text.push_str(label);
         }
+        for variant in self.get_localized_labels() {
+            let label = variant.get_text().trim();
+            if !label.is_empty() {
+                text.push(' ');
+                text.push_str(label);
+            }
+        }
+
+        keys.push(IndexType::ByLabel(label.to_lowercase(), self.id.clone()));
+        keys.push(IndexType::ByUpdated(self.update_timestamp, self.id.clone()));
+
+        if !self.domain_name.is_empty() {
+            text.push(' ');
+            text.push_str(self.domain_name.as_str());
+        }
+
+        let description = self.description.trim();
+        if !description.is_empty() {
+            text.push(' ');
+            text.push_str(description);
+        }
 
         let address = &self.get_address().address.trim();
         if !address.is_empty() {
@@ -101,6 +213,11 @@ impl IndexedValue<IndexType> for proto_BookItem {
 pub struct AddressBookAccess {
     pub(crate) db: Arc<Db>,
     pub(crate) xpub: Arc<dyn XPubPosition>,
+    pub(crate) validators: Arc<Validators>,
+    pub(crate) permissions: Permissions,
+    pub(crate) limiter: Arc<RateLimiter>,
+    pub(crate) health: Arc<Health>,
+    pub(crate) profiler: Arc<Profiler>,
 }
 
 impl AddressBookAccess {
@@ -108,6 +225,78 @@ impl AddressBookAccess {
         format!("{}{}", PREFIX_KEY, id.to_string())
     }
 
+    fn get_usage_key(id: Uuid) -> String {
+        format!("{}{}", PREFIX_USAGE, id)
+    }
+
+    ///
+    /// Items with the label, compared case-insensitively
+    fn find_by_label(&self, label: &str) -> Vec<proto_BookItem> {
+        let label = label.trim().to_lowercase();
+        // same as the `ByLabel` index key, but without the id part
+        let prefix = format!("{}:6/L{}/", PREFIX_IDX, label);
+        self.db.scan_prefix(prefix.as_bytes())
+            .values()
+            .filter_map(|v| v.ok())
+            .filter_map(|v| String::from_utf8(v.to_vec()).ok())
+            .filter_map(|key| AddressBookAccess::extract_id(key).ok())
+            .filter_map(|id| self.get_item(id))
+            // a label with a slash may have the same prefix
+            .filter(|item| item.label.trim().to_lowercase() == label)
+            .collect()
+    }
+
+    ///
+    /// Check that no other item has the same label, if the labels must be unique. The `pending` are the items written
+    /// in the same batch, as their labels (in lowercase) with the ids, which replace the stored items with those ids.
+    /// If the item is accepted it's added to the `pending`.
+    fn check_unique_label(&self, item: &proto_BookItem, pending: &mut HashMap<String, String>) -> Result<(), StateError> {
+        if !self.validators.is_unique_book_labels() {
+            return Ok(())
+        }
+        let label = item.label.trim().to_lowercase();
+        if label.is_empty() {
+            return Ok(())
+        }
+        let other = match pending.get(&label) {
+            Some(id) if *id != item.id => Some(id.clone()),
+            _ => self.find_by_label(&label).into_iter()
+                .find(|existing| existing.id != item.id && !pending.values().any(|id| *id == existing.id))
+                .map(|existing| existing.id),
+        };
+        if let Some(other) = other {
+            return Err(StateError::InvalidValue(InvalidValueError::NameMessage(
+                "label".to_string(),
+                format!("Label \"{}\" is already used by {}", item.label.trim(), other),
+            )))
+        }
+        pending.insert(label, item.id.clone());
+        Ok(())
+    }
+
+    ///
+    /// Recorded sends to the item, if any
+    fn get_usage(&self, id: Uuid) -> Result<Option<ContactUsage>, StateError> {
+        match self.db.get(AddressBookAccess::get_usage_key(id))? {
+            Some(b) => {
+                let proto = proto_BookItemUsage::parse_from_bytes(b.as_ref())?;
+                Ok(Some(ContactUsage::try_from(&proto)?))
+            }
+            None => Ok(None)
+        }
+    }
+
+    ///
+    /// Read the first items of the book so they get into the cache. Returns the number of read items.
+    pub(crate) fn warm(&self, limit: usize) -> Result<usize, StateError> {
+        let mut count = 0;
+        for row in self.db.scan_prefix(PREFIX_KEY).take(limit) {
+            let _ = row?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
     fn extract_id(key: String) -> Result<Uuid, StateError> {
         if !key.starts_with(PREFIX_KEY) {
             return Err(StateError::InvalidId)
@@ -119,11 +308,46 @@ impl AddressBookAccess {
         Uuid::parse_str(id.unwrap()).map_err(|_| StateError::InvalidId)
     }
 
+    fn list_items(&self) -> Vec<proto_BookItem> {
+        self.db.scan_prefix(PREFIX_KEY.as_bytes())
+            .values()
+            .filter_map(|v| v.ok())
+            .filter_map(|v| proto_BookItem::parse_from_bytes(v.as_ref()).ok())
+            .collect()
+    }
+
+    ///
+    /// Find an existing item for the same address on the same blockchain
+    fn find_by_address(&self, blockchain: u32, address: &str) -> Option<proto_BookItem> {
+        self.find_all_by_address(blockchain, address).next()
+    }
+
+    ///
+    /// All items for the address on the blockchain, most recent first
+    fn find_all_by_address<'a>(&'a self, blockchain: u32, address: &str) -> impl Iterator<Item = proto_BookItem> + 'a {
+        // same as the `ByAddress` index key, but without the timestamp part
+        let prefix = format!("{}:2/{:}/", PREFIX_IDX, address.trim().to_lowercase());
+        self.db.scan_prefix(prefix.as_bytes())
+            .values()
+            .filter_map(|v| v.ok())
+            .filter_map(|v| String::from_utf8(v.to_vec()).ok())
+            .filter_map(|key| AddressBookAccess::extract_id(key).ok())
+            .filter_map(|id| self.get_item(id))
+            .filter(move |item| item.blockchain == blockchain)
+    }
+
     fn get_item(&self, id: Uuid) -> Option<proto_BookItem> {
-        match self.db.get(AddressBookAccess::get_key(id)) {
+        let key = AddressBookAccess::get_key(id);
+        match self.db.get(key.as_str()) {
             Ok(data) => {
                 match data {
-                    Some(b) => proto_BookItem::parse_from_bytes(b.deref()).ok(),
+                    Some(b) => match proto_BookItem::parse_from_bytes(b.deref()) {
+                        Ok(item) => Some(item),
+                        Err(e) => {
+                            quarantine_store::put(&self.db, &self.health, &self.permissions, StoreType::STORE_ADDRESS_BOOK, key.as_str(), e);
+                            None
+                        }
+                    },
                     None => None
                 }
             }
@@ -133,33 +357,50 @@ impl AddressBookAccess {
 
     ///
     /// Enrich the stored data with addition values.
-    /// It expect that the original data is fully valid and has all required fields, otherwise may panic
-    fn enrich(&self, data: proto_BookItem) -> BookItemEnriched {
-        let address = data.address.clone().unwrap();
+    /// Returns `CorruptedValue` if the stored data misses the address or has an invalid xpub, ex. a legacy or a hand-edited record
+    fn enrich(&self, data: proto_BookItem, locale: Option<&str>, lookahead: usize) -> Result<BookItemEnriched, StateError> {
+        let usage = Uuid::parse_str(data.get_id()).ok()
+            .and_then(|id| self.get_usage(id).unwrap_or_else(|e| {
+                warn!("Skip unreadable usage of address book item {}: {:?}", id, e);
+                None
+            }));
+        let mut enriched = self.enrich_address(data, locale, lookahead)?;
+        enriched.usage = usage;
+        Ok(enriched)
+    }
+
+    fn enrich_address(&self, data: proto_BookItem, locale: Option<&str>, lookahead: usize) -> Result<BookItemEnriched, StateError> {
+        let address = data.address.clone().into_option().ok_or(StateError::CorruptedValue)?;
+        let label = match locale {
+            Some(locale) => data.get_label_for(locale).to_string(),
+            None => data.label.clone(),
+        };
         match address.get_field_type() {
             Address_AddressType::PLAIN => {
-                BookItemEnriched {
-                    data,
-                    current_address: address.address.clone(),
-                }
+                Ok(BookItemEnriched::new(data, address.address.clone(), label, vec![]))
             }
             Address_AddressType::XPUB => {
                 let index = self.xpub.get_next(address.address.clone()).unwrap_or(0);
-                let xpub = XPub::from_str(address.address.as_str()).expect("not an xpub");
+                let xpub = XPub::from_str(address.address.as_str()).map_err(|_| StateError::CorruptedValue)?;
                 let current_address = xpub.get_address::<Address>(index)
                     .map(|a| a.to_string())
                     .unwrap_or("".to_string());
-                BookItemEnriched {
-                    data,
-                    current_address,
-                }
+                // never beyond the gap limit, otherwise a wallet scanning the xpub may not find the payment
+                let count = lookahead.clamp(1, XPUB_GAP_LIMIT as usize) as u32;
+                let next_addresses = (index..index.saturating_add(count))
+                    .filter_map(|i| xpub.get_address::<Address>(i).ok())
+                    .map(|a| a.to_string())
+                    .collect();
+                Ok(BookItemEnriched::new(data, current_address, label, next_addresses))
             }
         }
     }
 }
 
 impl AddressBookAccess {
-    fn add_item(&self, item: proto_BookItem, batch: &mut Batch) -> Result<(), StateError> {
+    pub(crate) fn add_item(&self, mut item: proto_BookItem, batch: &mut Batch) -> Result<(), StateError> {
+        // it's always derived from the blockchain, so it cannot get out of sync with it
+        item.network = NetworkKind::of_blockchain(item.blockchain);
         let id = Uuid::parse_str(item.get_id()).unwrap();
         if let Ok(item_bytes) = item.write_to_bytes() {
             let item_key = AddressBookAccess::get_key(id);
@@ -174,70 +415,160 @@ impl AddressBookAccess {
             Err(StateError::CorruptedValue)
         }
     }
-}
 
-impl AddressBook for AddressBookAccess {
-
-    fn add(&self, items_original: Vec<proto_BookItem>) -> Result<Vec<Uuid>, StateError> {
-        // first fix or fill missing parts, if any
-        let mut items = Vec::new();
-        for x in items_original {
-            items.push(x.preprocess()?)
+    ///
+    /// Read an item for a query. If `fields` are specified it decodes only those fields.
+    /// Returns `Ok(None)` if there is no such item, and `Err` if it exists but cannot be read
+    fn read_item_projected(&self, id: Uuid, fields: &Option<HashSet<u32>>) -> Result<Option<proto_BookItem>, StateError> {
+        match self.db.get(AddressBookAccess::get_key(id))? {
+            Some(b) => match fields {
+                None => Ok(Some(proto_BookItem::parse_from_bytes(b.deref())?)),
+                Some(fields) => decode_projected(b.deref(), fields).map(Some),
+            },
+            None => Ok(None)
         }
+    }
 
-        // validate the data we got before storing it, return error if any item is invalid
-        for item in &items {
-            item.validate()?;
+    ///
+    /// Set the archived flag of the item, if it exists. Returns `false` if there is no such item.
+    fn set_archived(&self, id: Uuid, archived: bool) -> Result<bool, StateError> {
+        self.permissions.check_write(Store::AddressBook)?;
+        self.limiter.check(Store::AddressBook)?;
+        let mut item = match self.get_item(id) {
+            Some(item) => item,
+            None => return Ok(false)
+        };
+        if item.archived == archived {
+            return Ok(true)
         }
-
-        // all data is good, store it
+        item.archived = archived;
+        item.set_update_timestamp(Utc::now().timestamp_millis() as u64);
         let mut batch = Batch::default();
-        let mut ids = Vec::new();
-        for item in items {
-            let id = Uuid::parse_str(item.get_id()).unwrap();
-            let _ = self.add_item(item, &mut batch)?;
-            ids.push(id);
+        Indexing::remove_backref(AddressBookAccess::get_key(id), self.db.clone(), &mut batch)?;
+        self.add_item(item, &mut batch)?;
+        self.health.apply(&self.db, batch)?;
+        Ok(true)
+    }
+
+    ///
+    /// Score all items accepted by the filter, except the items without a score, and return the best `limit` of them
+    fn find_scored<F: Fn(&proto_BookItem) -> Option<f32>>(&self, filter: &Filter, limit: usize, score: F) -> Result<Vec<ScoredBookItem>, StateError> {
+        let mut processed = HashSet::new();
+        let mut scored = Vec::new();
+        self.profiler.read(|| -> Result<(), StateError> {
+            for row in Indexing::scan_page(&self.db, PREFIX_IDX, filter.get_index_bounds(), &PageQuery::default())? {
+                let (_, item_key) = row?;
+                let id = AddressBookAccess::extract_id(String::from_utf8(item_key.to_vec()).map_err(|_| StateError::CorruptedValue)?)?;
+                if !processed.insert(id) {
+                    continue
+                }
+                if let Some(item) = self.get_item(id) {
+                    if filter.check_filter(&item) {
+                        if let Some(value) = score(&item) {
+                            scored.push((item, value));
+                        }
+                    }
+                }
+            }
+            Ok(())
+        })?;
+        // it's a stable sort, so the same score keeps the order of the index, i.e. the most recent first
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        let results = scored.into_iter()
+            .filter_map(|(item, score)| match self.enrich(item, filter.locale.as_deref(), filter.xpub_lookahead) {
+                Ok(item) => Some(ScoredBookItem { item, score }),
+                Err(e) => {
+                    warn!("Skip unusable address book item: {:?}", e);
+                    None
+                }
+            })
+            .take(limit)
+            .collect();
+        Ok(results)
+    }
+
+    ///
+    /// How close the item is to the fuzzy search `query`
+    fn fuzzy_score(item: &proto_BookItem, query: &str) -> f32 {
+        if item.has_address() && item.get_address().address.to_lowercase().contains(&query.trim().to_lowercase()) {
+            return 1.0
+        }
+        let mut score = Trigram::similarity(query, &item.label);
+        for variant in item.localized_labels.iter() {
+            score = score.max(Trigram::similarity(query, &variant.text));
+        }
+        if !item.domain_name.is_empty() {
+            score = score.max(Trigram::similarity(query, &item.domain_name));
         }
-        self.db.apply_batch(batch)
-            .map_err(|e| StateError::from(e))
-            .map(|_| ids)
+        score
     }
 
-    fn get(&self, id: Uuid) -> Result<Option<BookItemEnriched>, StateError> {
-        let item_key = AddressBookAccess::get_key(id);
-        let result = self.db.get(item_key)?
-            .map(|b| proto_BookItem::parse_from_bytes(b.as_ref()));
-        match result {
-            Some(parsed) => if let Ok(msg) = parsed {
-                Ok(Some(self.enrich(msg)))
+    ///
+    /// Relevance of the item found by the text `query`, where `(2 + coverage) / 3` is for a label starting with it,
+    /// `(1 + coverage) / 3` for a label or a domain name containing it, and `coverage / 3` for an address containing it.
+    /// The `coverage` is the share of the matched label (or address) covered by the query.
+    fn rank_score(item: &proto_BookItem, query: &str) -> f32 {
+        let query = query.trim().to_lowercase();
+        let mut names: Vec<&str> = vec![item.label.as_str(), item.domain_name.as_str()];
+        names.extend(item.localized_labels.iter().map(|v| v.text.as_str()));
+        let mut best: Option<(u8, f32)> = None;
+        for name in names {
+            let lower = name.trim().to_lowercase();
+            let kind = if lower.starts_with(&query) {
+                2
+            } else if lower.contains(&query) {
+                1
             } else {
-                Err(StateError::CorruptedValue)
-            },
-            None => Ok(None)
+                continue
+            };
+            let current = (kind, Trigram::similarity(&lower, &query));
+            if best.is_none_or(|b| current > b) {
+                best = Some(current);
+            }
+        }
+        if best.is_none() && item.has_address() {
+            let address = item.get_address().address.to_lowercase();
+            if address.contains(&query) {
+                best = Some((0, Trigram::similarity(&address, &query)));
+            }
         }
+        best.map_or(0.0, |(kind, coverage)| (kind as f32 + coverage) / 3.0)
     }
 
-    fn remove(&self, id: Uuid) -> Result<(), StateError> {
+    ///
+    /// Set or remove (with `None`) the avatar of the item, if it exists. Returns `false` if there is no such item.
+    fn update_avatar(&self, id: Uuid, avatar: Option<Avatar>) -> Result<bool, StateError> {
+        self.permissions.check_write(Store::AddressBook)?;
+        self.limiter.check(Store::AddressBook)?;
+        if let Some(avatar) = &avatar {
+            avatar.validate()?;
+        }
+        let mut item = match self.get_item(id) {
+            Some(item) => item,
+            None => return Ok(false)
+        };
+        match avatar {
+            Some(avatar) => item.set_avatar(avatar),
+            None if item.has_avatar() => item.clear_avatar(),
+            None => return Ok(true)
+        }
+        item.set_update_timestamp(Utc::now().timestamp_millis() as u64);
         let mut batch = Batch::default();
-        let item_key = AddressBookAccess::get_key(id);
-        batch.remove(item_key.as_bytes());
-        Indexing::remove_backref(item_key, self.db.clone(), &mut batch)?;
-        self.db.apply_batch(batch)
-            .map_err(|e| StateError::from(e))
+        Indexing::remove_backref(AddressBookAccess::get_key(id), self.db.clone(), &mut batch)?;
+        self.add_item(item, &mut batch)?;
+        self.health.apply(&self.db, batch)?;
+        Ok(true)
     }
 
-    fn query(&self, filter: Filter, page: PageQuery) -> Result<PageResult<BookItemEnriched>, StateError> {
-        let mut bounds = filter.get_index_bounds();
-        if let Some(cursor) = page.cursor {
-            bounds.0 = Bound::Excluded(cursor.offset)
-        };
+    fn query_with_fields(&self, filter: Filter, page: PageQuery, fields: Option<HashSet<u32>>) -> Result<PageResult<BookItemEnriched>, StateError> {
         let mut processed = HashSet::new();
-        let mut iter = self.db.range(bounds);
+        let mut iter = Indexing::scan_page(&self.db, PREFIX_IDX, filter.get_index_bounds(), &page)?;
         let mut done = false;
 
         let mut results = Vec::new();
         let mut cursor_key: Option<String> = None;
         let mut read_count = 0;
+        let mut skipped = 0;
 
         while !done {
             let next = iter.next();
@@ -253,17 +584,35 @@ impl AddressBook for AddressBookAccess {
                         let item_key = AddressBookAccess::extract_id(String::from_utf8(item_key).unwrap())?;
                         let unprocessed = processed.insert(item_key.clone());
                         if unprocessed {
-                            if let Some(item) = self.get_item(item_key) {
-                                if filter.check_filter(&item) {
-                                    results.push(self.enrich(item));
+                            let read = self.read_item_projected(item_key, &fields)
+                                .and_then(|item| match item {
+                                    Some(item) if filter.check_filter(&item) =>
+                                        self.enrich(item, filter.locale.as_deref(), filter.xpub_lookahead).map(Some),
+                                    _ => Ok(None)
+                                });
+                            match read {
+                                Ok(Some(item)) => {
+                                    results.push(item);
                                     if results.len() >= page.limit {
                                         done = true
                                     }
                                 }
+                                Ok(None) => {}
+                                Err(e) => {
+                                    warn!("Skip unreadable address book item {}: {:?}", item_key, e);
+                                    if e == StateError::CorruptedValue {
+                                        let key = AddressBookAccess::get_key(item_key);
+                                        quarantine_store::put(&self.db, &self.health, &self.permissions, StoreType::STORE_ADDRESS_BOOK, key.as_str(), &e);
+                                    }
+                                    skipped += 1;
+                                }
                             }
                         }
                     }
-                    Err(_) => {}
+                    Err(e) => {
+                        warn!("Skip unreadable address book index entry: {:?}", e);
+                        skipped += 1;
+                    }
                 },
                 None => done = true
             }
@@ -273,13 +622,316 @@ impl AddressBook for AddressBookAccess {
 
         let result = PageResult {
             values: results,
-            cursor: if reached_end { None } else { cursor_key.map(|offset| Cursor {offset}) },
+            cursor: if reached_end { None } else { cursor_key.map(|key| Cursor::encode(PREFIX_IDX, page.order, key)) },
+            skipped,
         };
 
         Ok(result)
     }
+}
+
+impl Migration for AddressBookAccess {
+    fn migrate(&self, version: usize) -> Result<(), StateError> {
+        // version 4 introduced the network kind, so existing items must be tagged and indexed with it.
+        // version 5 introduced the indexes to sort by the label and the update time.
+        // version 6 introduced the index by the blockchain.
+        if (4..=6).contains(&version) {
+            let mut batch = Batch::default();
+            for item in self.list_items() {
+                let item_key = AddressBookAccess::get_key(Uuid::parse_str(item.get_id())?);
+                Indexing::remove_backref(item_key, self.db.clone(), &mut batch)?;
+                self.add_item(item, &mut batch)?;
+            }
+            self.health.apply_batch(&self.db, batch)?;
+        }
+        Ok(())
+    }
+}
+
+impl AddressBook for AddressBookAccess {
+
+    fn add(&self, items: Vec<proto_BookItem>) -> Result<Vec<Uuid>, StateError> {
+        self.add_with_policy(items, DuplicatePolicy::Allow)
+    }
+
+    fn add_with_policy(&self, items_original: Vec<proto_BookItem>, policy: DuplicatePolicy) -> Result<Vec<Uuid>, StateError> {
+        self.permissions.check_write(Store::AddressBook)?;
+        self.limiter.check(Store::AddressBook)?;
+        // first fix or fill missing parts, if any
+        let mut items = Vec::new();
+        for x in items_original {
+            items.push(x.preprocess()?)
+        }
+
+        // validate the data we got before storing it, return error if any item is invalid
+        for item in &items {
+            item.validate()?;
+            self.validators.check_book_item(item)?;
+        }
+
+        // all data is good, find the duplicates and store the rest
+        let mut batch = Batch::default();
+        let mut ids = Vec::new();
+        let mut pending: Vec<proto_BookItem> = Vec::new();
+        // position in `pending` by the blockchain and address, to find a duplicate which isn't stored yet
+        let mut added: HashMap<(u32, String), usize> = HashMap::new();
+        for item in items {
+            let address = (item.blockchain, item.get_address().get_address().trim().to_lowercase());
+            let existing = match policy {
+                DuplicatePolicy::Allow => None,
+                _ => match added.get(&address) {
+                    Some(pos) => Some(pending[*pos].clone()),
+                    None => self.find_by_address(item.blockchain, address.1.as_str()),
+                }.filter(|existing| existing.id != item.id),
+            };
+            let existing = match existing {
+                Some(existing) => existing,
+                None => {
+                    ids.push(Uuid::parse_str(item.get_id()).unwrap());
+                    added.insert(address, pending.len());
+                    pending.push(item);
+                    continue
+                }
+            };
+            let existing_id = Uuid::parse_str(existing.get_id())?;
+            match policy {
+                DuplicatePolicy::Allow | DuplicatePolicy::ReturnExisting => {}
+                DuplicatePolicy::Reject => {
+                    return Err(StateError::InvalidValue(InvalidValueError::NameMessage(
+                        "address".to_string(),
+                        format!("{} is already in the Address Book as {}", address.1, existing_id),
+                    )))
+                }
+                DuplicatePolicy::Merge => {
+                    let merged = existing.merge(&item);
+                    match added.get(&address) {
+                        Some(pos) => pending[*pos] = merged,
+                        None => {
+                            let existing_key = AddressBookAccess::get_key(existing_id);
+                            batch.remove(existing_key.as_bytes());
+                            Indexing::remove_backref(existing_key, self.db.clone(), &mut batch)?;
+                            added.insert(address, pending.len());
+                            pending.push(merged);
+                        }
+                    }
+                }
+            }
+            ids.push(existing_id);
+        }
+        let mut labels = HashMap::new();
+        for item in pending.iter() {
+            self.check_unique_label(item, &mut labels)?;
+        }
+        for item in pending {
+            self.add_item(item, &mut batch)?;
+        }
+        self.health.apply(&self.db, batch)?;
+        Ok(ids)
+    }
+
+    fn add_checked(&self, items: Vec<proto_BookItem>, mode: ValidationMode) -> Result<CheckedWrite<Uuid>, StateError> {
+        self.permissions.check_write(Store::AddressBook)?;
+        self.limiter.check(Store::AddressBook)?;
+        let mut batch = Batch::default();
+        let mut results = Vec::with_capacity(items.len());
+        let mut warnings = Vec::new();
+        let mut labels = HashMap::new();
+        for (index, item) in items.into_iter().enumerate() {
+            let checked = item.preprocess()
+                .map_err(StateError::from)
+                .and_then(|item| self.validators.check_book_item_with(&item, mode).map(|messages| (item, messages)))
+                .and_then(|(item, messages)| self.check_unique_label(&item, &mut labels).map(|_| (item, messages)));
+            match checked {
+                Ok((item, messages)) => {
+                    let id = Uuid::parse_str(item.get_id()).unwrap();
+                    self.add_item(item, &mut batch)?;
+                    warnings.extend(messages.into_iter().map(|message| ValidationWarning { index, skipped: false, message }));
+                    results.push(Some(id));
+                }
+                Err(e) if mode == ValidationMode::Strict => return Err(e),
+                Err(e) => {
+                    warnings.push(ValidationWarning { index, skipped: true, message: format!("{:?}", e) });
+                    results.push(None);
+                }
+            }
+        }
+        self.health.apply(&self.db, batch)?;
+        Ok(CheckedWrite { results, warnings })
+    }
+
+    fn search_fuzzy(&self, mut filter: Filter, limit: usize) -> Result<Vec<ScoredBookItem>, StateError> {
+        self.permissions.check_read(Store::AddressBook)?;
+        let query = match filter.text.take() {
+            Some(query) if !query.trim().is_empty() => query,
+            _ => return Err(StateError::InvalidValue(InvalidValueError::NameMessage("text".to_string(), "Text is required for a fuzzy search".to_string())))
+        };
+        // the trigram index has only exact parts of the text, so it goes through all items allowed by the other
+        // criteria and scores each of them
+        self.find_scored(&filter, limit, |item| {
+            let score = AddressBookAccess::fuzzy_score(item, query.as_str());
+            if score >= FUZZY_MIN_SCORE { Some(score) } else { None }
+        })
+    }
+
+    fn search_ranked(&self, filter: Filter, limit: usize) -> Result<Vec<ScoredBookItem>, StateError> {
+        self.permissions.check_read(Store::AddressBook)?;
+        let query = match &filter.text {
+            Some(query) if !query.trim().is_empty() => query.clone(),
+            _ => return Err(StateError::InvalidValue(InvalidValueError::NameMessage("text".to_string(), "Text is required for a ranked search".to_string())))
+        };
+        // the best match may be anywhere in the time order, so it reads all matching items before ranking them
+        self.find_scored(&filter, limit, |item| Some(AddressBookAccess::rank_score(item, query.as_str())))
+    }
+
+    fn get_by_address(&self, blockchain: u32, address: String) -> Result<Vec<BookItemEnriched>, StateError> {
+        self.permissions.check_read(Store::AddressBook)?;
+        let items: Vec<proto_BookItem> = self.profiler.read(|| self.find_all_by_address(blockchain, address.as_str()).collect());
+        let results = items.into_iter()
+            .filter_map(|item| match self.enrich(item, None, 1) {
+                Ok(item) => Some(item),
+                Err(e) => {
+                    warn!("Skip unusable address book item: {:?}", e);
+                    None
+                }
+            })
+            .collect();
+        Ok(results)
+    }
+
+    fn get(&self, id: Uuid) -> Result<Option<BookItemEnriched>, StateError> {
+        self.permissions.check_read(Store::AddressBook)?;
+        let item_key = AddressBookAccess::get_key(id);
+        let result = self.db.get(item_key)?
+            .map(|b| proto_BookItem::parse_from_bytes(b.as_ref()));
+        match result {
+            Some(parsed) => if let Ok(msg) = parsed {
+                self.enrich(msg, None, 1).map(Some)
+            } else {
+                Err(StateError::CorruptedValue)
+            },
+            None => Ok(None)
+        }
+    }
+
+    fn remove(&self, id: Uuid) -> Result<(), StateError> {
+        self.permissions.check_write(Store::AddressBook)?;
+        self.limiter.check(Store::AddressBook)?;
+        let item_key = AddressBookAccess::get_key(id);
+        let usage_key = AddressBookAccess::get_usage_key(id);
+        if !self.db.contains_key(&item_key)? && !self.db.contains_key(&usage_key)? {
+            return Ok(())
+        }
+        let mut batch = Batch::default();
+        batch.remove(item_key.as_bytes());
+        batch.remove(usage_key.as_bytes());
+        Indexing::remove_backref(item_key, self.db.clone(), &mut batch)?;
+        self.health.apply(&self.db, batch)?;
+        Ok(())
+    }
+
+    fn archive(&self, id: Uuid) -> Result<bool, StateError> {
+        self.set_archived(id, true)
+    }
+
+    fn unarchive(&self, id: Uuid) -> Result<bool, StateError> {
+        self.set_archived(id, false)
+    }
+
+    fn set_domain_resolution(&self, id: Uuid, address: String, timestamp: DateTime<Utc>) -> Result<bool, StateError> {
+        self.permissions.check_write(Store::AddressBook)?;
+        self.limiter.check(Store::AddressBook)?;
+        let mut item = match self.get_item(id) {
+            Some(item) => item,
+            None => return Ok(false)
+        };
+        let mut resolution = DomainResolution::new();
+        resolution.address = address;
+        resolution.timestamp = timestamp.timestamp_millis() as u64;
+        item.set_domain_resolution(resolution);
+        // it's not indexed, so the indexes stay the same
+        let mut batch = Batch::default();
+        batch.insert(AddressBookAccess::get_key(id).as_bytes(), item.write_to_bytes()?);
+        self.health.apply(&self.db, batch)?;
+        Ok(true)
+    }
+
+    fn set_avatar(&self, id: Uuid, image: Vec<u8>, content_type: String) -> Result<bool, StateError> {
+        let mut avatar = Avatar::new();
+        avatar.image = image;
+        avatar.content_type = content_type.trim().to_lowercase();
+        self.update_avatar(id, Some(avatar))
+    }
+
+    fn clear_avatar(&self, id: Uuid) -> Result<bool, StateError> {
+        self.update_avatar(id, None)
+    }
+
+    fn record_send(&self, id: Uuid, tx_id: String, asset: AssetId, amount: BigUint) -> Result<bool, StateError> {
+        self.permissions.check_write(Store::AddressBook)?;
+        self.limiter.check(Store::AddressBook)?;
+        if self.get_item(id).is_none() {
+            return Ok(false)
+        }
+        let usage = match self.get_usage(id)? {
+            Some(usage) if usage.last_tx_id == tx_id => return Ok(true),
+            Some(mut usage) => {
+                usage.sends += 1;
+                *usage.volume.entry(asset).or_insert_with(BigUint::zero) += amount;
+                usage.last_tx_id = tx_id;
+                usage.last_sent = Utc::now();
+                usage
+            }
+            None => ContactUsage {
+                sends: 1,
+                volume: BTreeMap::from([(asset, amount)]),
+                last_tx_id: tx_id,
+                last_sent: Utc::now(),
+            }
+        };
+        let proto: proto_BookItemUsage = (&usage).into();
+        let mut batch = Batch::default();
+        batch.insert(AddressBookAccess::get_usage_key(id).as_bytes(), proto.write_to_bytes()?);
+        self.health.apply(&self.db, batch)?;
+        Ok(true)
+    }
+
+    fn get_groups(&self) -> Result<Vec<(String, usize)>, StateError> {
+        self.permissions.check_read(Store::AddressBook)?;
+        // the group is displayed as it was spelled in the oldest item
+        let mut groups: BTreeMap<String, (u64, String, usize)> = BTreeMap::new();
+        for item in self.list_items() {
+            let group = proto_BookItem::normalize_group(&item.group);
+            if group.is_empty() || item.archived {
+                continue
+            }
+            let current = groups.entry(group).or_insert_with(|| (item.create_timestamp, item.group.trim().to_string(), 0));
+            if item.create_timestamp < current.0 {
+                current.0 = item.create_timestamp;
+                current.1 = item.group.trim().to_string();
+            }
+            current.2 += 1;
+        }
+        Ok(groups.into_values().map(|(_, name, count)| (name, count)).collect())
+    }
+
+    fn query(&self, filter: Filter, page: PageQuery) -> Result<PageResult<BookItemEnriched>, StateError> {
+        self.permissions.check_read(Store::AddressBook)?;
+        self.profiler.read(|| self.query_with_fields(filter, page, None))
+    }
+
+    fn query_projected(&self, filter: Filter, page: PageQuery, fields: Vec<BookItemField>) -> Result<PageResult<BookItemEnriched>, StateError> {
+        self.permissions.check_read(Store::AddressBook)?;
+        let fields: HashSet<u32> = fields.iter()
+            .chain(filter.get_required_fields().iter())
+            .map(|f| f.get_number())
+            .collect();
+        self.query_with_fields(filter, page, Some(fields))
+    }
 
     fn update(&self, id: Uuid, update: proto_BookItem) -> Result<(), StateError> {
+        self.permissions.check_write(Store::AddressBook)?;
+        self.limiter.check(Store::AddressBook)?;
+        self.validators.check_book_item(&update)?;
         let mut batch = Batch::default();
         let item_key = AddressBookAccess::get_key(id);
         batch.remove(item_key.as_bytes());
@@ -290,38 +942,265 @@ impl AddressBook for AddressBookAccess {
         let mut item = update.clone();
         item.set_update_timestamp(now);
         item.set_id(id.to_string());
+        self.check_unique_label(&item, &mut HashMap::new())?;
         let _ = self.add_item(item, &mut batch)?;
 
-        self.db.apply_batch(batch)
-            .map_err(|e| StateError::from(e))
+        self.health.apply(&self.db, batch)?;
+        Ok(())
+    }
+
+    fn export_bundle(&self, max_bytes: usize) -> Result<Vec<u8>, StateError> {
+        self.permissions.check_read(Store::AddressBook)?;
+        let mut items = self.list_items();
+        // most recent changes are the most relevant for the other side
+        items.sort_by_key(|item| Reverse(item.update_timestamp));
+
+        let mut bundle = proto_BookItemBundle::new();
+        let mut size = 0usize;
+        for mut item in items {
+            item.clear_avatar();
+            let item_size = item.compute_size();
+            // each item is encoded with a one byte tag and a varint length
+            let encoded_size = 1 + protobuf::rt::compute_raw_varint32_size(item_size) as usize + item_size as usize;
+            if size + encoded_size > max_bytes {
+                break
+            }
+            size += encoded_size;
+            bundle.items.push(item);
+        }
+        Ok(bundle.write_to_bytes()?)
+    }
+
+    fn import_bundle(&self, bundle: &[u8]) -> Result<Vec<Uuid>, StateError> {
+        self.permissions.check_write(Store::AddressBook)?;
+        self.limiter.check(Store::AddressBook)?;
+        let bundle = proto_BookItemBundle::parse_from_bytes(bundle)?;
+
+        let mut items = Vec::new();
+        for item in bundle.items {
+            let item = item.preprocess()?;
+            item.validate()?;
+            self.validators.check_book_item(&item)?;
+            items.push(item);
+        }
+
+        let mut batch = Batch::default();
+        let mut ids = Vec::new();
+        let mut labels = HashMap::new();
+        for mut item in items {
+            let id = Uuid::parse_str(item.get_id()).unwrap();
+            let existing = self.get_item(id)
+                .or_else(|| self.find_by_address(item.blockchain, item.get_address().get_address()));
+            if let Some(existing) = existing {
+                if existing.update_timestamp >= item.update_timestamp {
+                    continue
+                }
+                // the avatars are not exported, so it keeps the local one
+                if !item.has_avatar() && existing.has_avatar() {
+                    item.set_avatar(existing.get_avatar().clone());
+                }
+                // keep the local id, so the existing references to the item are still valid
+                item.set_id(existing.id.clone());
+                let existing_key = AddressBookAccess::get_key(Uuid::parse_str(existing.get_id())?);
+                batch.remove(existing_key.as_bytes());
+                Indexing::remove_backref(existing_key, self.db.clone(), &mut batch)?;
+            }
+            self.check_unique_label(&item, &mut labels)?;
+            let id = Uuid::parse_str(item.get_id()).unwrap();
+            self.add_item(item, &mut batch)?;
+            ids.push(id);
+        }
+        self.health.apply(&self.db, batch)?;
+        Ok(ids)
+    }
+
+    fn verify_all(&self) -> Result<Vec<CorruptedBookItem>, StateError> {
+        self.permissions.check_read(Store::AddressBook)?;
+        let mut corrupted = Vec::new();
+        for row in self.db.scan_prefix(PREFIX_KEY.as_bytes()) {
+            let (key, value) = row?;
+            let id = match String::from_utf8(key.to_vec()).map_err(|_| StateError::InvalidId).and_then(AddressBookAccess::extract_id) {
+                Ok(id) => id,
+                Err(_) => {
+                    warn!("Skip address book record with invalid key: {:?}", key);
+                    continue
+                }
+            };
+            let usable = proto_BookItem::parse_from_bytes(value.as_ref())
+                .map_err(StateError::from)
+                .and_then(|item| self.enrich(item, None, 1));
+            if let Err(error) = usable {
+                corrupted.push(CorruptedBookItem { id, error });
+            }
+        }
+        Ok(corrupted)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashSet;
+    use std::ops::Bound;
+    use num_bigint::BigUint;
     use tempdir::TempDir;
     use uuid::Uuid;
-    use chrono::Utc;
-    use crate::access::addressbook::{AddressBook, Filter};
-    use crate::access::pagination::PageQuery;
+    use chrono::{TimeZone, Utc};
+    use crate::access::addressbook::{AddressBook, BookItemField, BookItemSort, DuplicatePolicy, Filter, FUZZY_MIN_SCORE, MAX_AVATAR_SIZE, XPUB_GAP_LIMIT};
+    use crate::access::asset::AssetId;
+    use crate::access::pagination::{PageOrder, PageQuery};
+    use crate::access::validation::ValidationMode;
     use crate::access::xpubpos::XPubPosition;
+    use crate::errors::{InvalidValueError, StateError};
+    use crate::storage::indexing::{IndexConvert, QueryRanges};
     use crate::storage::sled_access::SledStorage;
-    use crate::proto::addressbook::{BookItem as proto_BookItem, Address as proto_Address, Address_AddressType};
+    use protobuf::Message;
+    use crate::proto::addressbook::{BookItem as proto_BookItem, BookItemBundle as proto_BookItemBundle, Address as proto_Address, Address_AddressType, LocalizedLabel, NetworkKind};
+    use crate::storage::indexing::IndexedValue;
+    use crate::storage::version::Migration;
+    use super::AddressBookAccess;
 
     #[test]
-    fn create_and_find() {
+    fn export_and_import_bundle() {
         let tmp_dir = TempDir::new("test-addressbook").unwrap();
         let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
         let store = access.get_addressbook();
 
-        let mut item = proto_BookItem::new();
-        item.create_timestamp = 1_647_313_850_992;
-        item.blockchain = 101;
-        let mut address = proto_Address::new();
-        address.address = "0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb".to_string();
-        item.set_address(address);
-
+        for i in 0..10 {
+            let mut item = proto_BookItem::new();
+            item.create_timestamp = 1_647_313_850_000 + i;
+            item.update_timestamp = 1_647_313_850_000 + i;
+            item.blockchain = 101;
+            item.label = format!("Hello World! {}", i);
+            let mut address = proto_Address::new();
+            address.address = format!("0xedd91797204d3537fbabde0e0e42aae99975f00{}", i);
+            item.set_address(address);
+            let _ = store.add(vec![item]).expect("not saved");
+        }
+
+        let full = store.export_bundle(10_000).expect("exported");
+        let partial = store.export_bundle(full.len() / 2).expect("exported");
+        assert!(partial.len() <= full.len() / 2);
+
+        let tmp_dir_2 = TempDir::new("test-addressbook").unwrap();
+        let access_2 = SledStorage::open(tmp_dir_2.path().to_path_buf()).unwrap();
+        let store_2 = access_2.get_addressbook();
+
+        let imported = store_2.import_bundle(partial.as_slice()).expect("imported");
+        assert!(!imported.is_empty());
+        assert!(imported.len() < 10);
+
+        let results = store_2.query(Filter::default(), PageQuery::default()).expect("queried");
+        assert_eq!(results.values.len(), imported.len());
+        // most recent go first
+        assert_eq!(results.values[0].data.label, "Hello World! 9");
+
+        // importing again doesn't change anything because nothing is newer
+        let imported_again = store_2.import_bundle(full.as_slice()).expect("imported");
+        assert_eq!(imported_again.len(), 10 - imported.len());
+    }
+
+    #[test]
+    fn import_bundle_merges_by_address() {
+        let tmp_dir = TempDir::new("test-addressbook").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let store = access.get_addressbook();
+
+        let mut item = proto_BookItem::new();
+        item.create_timestamp = 1_647_313_850_000;
+        item.update_timestamp = 1_647_313_850_000;
+        item.blockchain = 101;
+        item.label = "Local".to_string();
+        let mut address = proto_Address::new();
+        address.address = "0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb".to_string();
+        item.set_address(address);
+        let id = store.add(vec![item.clone()]).expect("not saved")[0];
+
+        let tmp_dir_2 = TempDir::new("test-addressbook").unwrap();
+        let access_2 = SledStorage::open(tmp_dir_2.path().to_path_buf()).unwrap();
+        let store_2 = access_2.get_addressbook();
+        let mut remote = item.clone();
+        remote.update_timestamp = 1_647_313_860_000;
+        remote.label = "Remote".to_string();
+        let _ = store_2.add(vec![remote]).expect("not saved");
+
+        let imported = store.import_bundle(store_2.export_bundle(10_000).unwrap().as_slice()).expect("imported");
+        assert_eq!(imported, vec![id]);
+
+        let results = store.query(Filter::default(), PageQuery::default()).expect("queried");
+        assert_eq!(results.values.len(), 1);
+        assert_eq!(results.values[0].data.id, id.to_string());
+        assert_eq!(results.values[0].data.label, "Remote");
+    }
+
+    #[test]
+    fn add_with_duplicate_policy() {
+        let tmp_dir = TempDir::new("test-addressbook").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let store = access.get_addressbook();
+
+        let mut item = proto_BookItem::new();
+        item.create_timestamp = 1_647_313_850_000;
+        item.update_timestamp = 1_647_313_850_000;
+        item.blockchain = 101;
+        item.label = "Alice".to_string();
+        let mut address = proto_Address::new();
+        address.address = "0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb".to_string();
+        item.set_address(address);
+        let id = store.add(vec![item.clone()]).expect("not saved")[0];
+
+        // same address with a different case
+        let mut duplicate = item.clone();
+        duplicate.create_timestamp = 1_647_313_860_000;
+        duplicate.update_timestamp = 1_647_313_860_000;
+        duplicate.label = "".to_string();
+        duplicate.group = "Friends".to_string();
+        duplicate.mut_address().address = "0xedd91797204d3537fbabde0e0e42aae99975f2bb".to_string();
+
+        let result = store.add_with_policy(vec![duplicate.clone()], DuplicatePolicy::Reject);
+        assert!(matches!(result, Err(StateError::InvalidValue(_))));
+        assert_eq!(store.add_with_policy(vec![duplicate.clone()], DuplicatePolicy::ReturnExisting).unwrap(), vec![id]);
+        assert_eq!(store.query(Filter::default(), PageQuery::default()).unwrap().values.len(), 1);
+
+        assert_eq!(store.add_with_policy(vec![duplicate.clone()], DuplicatePolicy::Merge).unwrap(), vec![id]);
+        let results = store.query(Filter::default(), PageQuery::default()).unwrap();
+        assert_eq!(results.values.len(), 1);
+        assert_eq!(results.values[0].data.label, "Alice");
+        assert_eq!(results.values[0].data.group, "Friends");
+        assert_eq!(results.values[0].data.create_timestamp, 1_647_313_850_000);
+        let filter = Filter { group: Some("friends".to_string()), ..Filter::default() };
+        assert_eq!(store.query(filter, PageQuery::default()).unwrap().values.len(), 1);
+
+        // another blockchain is not a duplicate, but it's added twice in the same call
+        let mut other = item.clone();
+        other.blockchain = 100;
+        other.create_timestamp = 1_647_313_865_000;
+        let mut other_2 = other.clone();
+        other_2.create_timestamp = 1_647_313_870_000;
+        let ids = store.add_with_policy(vec![other, other_2.clone()], DuplicatePolicy::ReturnExisting).unwrap();
+        assert_eq!(ids.len(), 2);
+        assert_ne!(ids[0], id);
+        assert_eq!(ids[0], ids[1]);
+        assert!(store.add_with_policy(vec![other_2], DuplicatePolicy::Reject).is_err());
+
+        assert_eq!(store.add_with_policy(vec![duplicate], DuplicatePolicy::Allow).unwrap().len(), 1);
+        assert_eq!(store.query(Filter::default(), PageQuery::default()).unwrap().values.len(), 3);
+    }
+
+    #[test]
+    fn create_and_find() {
+        let tmp_dir = TempDir::new("test-addressbook").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let store = access.get_addressbook();
+
+        let mut item = proto_BookItem::new();
+        item.create_timestamp = 1_647_313_850_992;
+        item.blockchain = 101;
+        let mut address = proto_Address::new();
+        address.address = "0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb".to_string();
+        item.set_address(address);
+
         let mut exp = item.clone();
+        exp.network = NetworkKind::NETWORK_MAINNET;
 
         let results = store.add(vec![item.clone()]).expect("not saved");
         assert_eq!(results.len(), 1);
@@ -336,6 +1215,29 @@ mod tests {
         assert!(results.cursor.is_none());
     }
 
+    #[test]
+    fn query_only_selected_fields() {
+        let tmp_dir = TempDir::new("test-addressbook").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let store = access.get_addressbook();
+
+        let mut item = proto_BookItem::new();
+        item.create_timestamp = 1_647_313_850_992;
+        item.blockchain = 101;
+        item.label = "Hello World!".to_string();
+        let mut address = proto_Address::new();
+        address.address = "0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb".to_string();
+        item.set_address(address);
+        let _ = store.add(vec![item.clone()]).expect("not saved");
+
+        let results = store.query_projected(Filter::default(), PageQuery::default(), vec![BookItemField::Blockchain]).expect("queried");
+        assert_eq!(results.values.len(), 1);
+        assert_eq!(results.values[0].data.blockchain, 101);
+        assert_eq!(results.values[0].data.create_timestamp, 0);
+        assert_eq!(results.values[0].data.label, "");
+        assert_eq!(results.values[0].current_address, "0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb");
+    }
+
     #[test]
     fn create_and_get() {
         let tmp_dir = TempDir::new("test-addressbook").unwrap();
@@ -350,6 +1252,7 @@ mod tests {
         item.set_address(address);
 
         let mut exp = item.clone();
+        exp.network = NetworkKind::NETWORK_MAINNET;
 
         let results = store.add(vec![item.clone()]).expect("not saved");
         assert_eq!(results.len(), 1);
@@ -439,6 +1342,41 @@ mod tests {
         assert_eq!(result.current_address, "bc1q03p495zw08k8dvdl9guy5nw3kw7qmfsx2y7g3f");
     }
 
+    #[test]
+    fn provide_with_next_addrs_on_xpub() {
+        let tmp_dir = TempDir::new("test-addressbook").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+
+        // tent because ski crew unknown labor blouse forest spice night peace fold cup august equal
+        let xpub = "zpub6ttpB5kpi5EbjzUhRC9gqYBJEnDE5TKxN3wsBLh4TM1JJz8ZKcpCjtrmvw8bAQVUkxTcMUBcHK9oGgAAhe97Xpd8HDNzzDx59u13wz32dyS";
+        access.get_xpub_pos().set_at_least(xpub.to_string(), 6).expect("xpub pos is not set");
+
+        let store = access.get_addressbook();
+
+        let mut item = proto_BookItem::new();
+        item.create_timestamp = 1_647_313_850_992;
+        item.blockchain = 1;
+        let mut address = proto_Address::new();
+        address.address = xpub.to_string();
+        address.field_type = Address_AddressType::XPUB;
+        item.set_address(address);
+        store.add(vec![item]).expect("not saved");
+
+        let results = store.query(Filter::default(), PageQuery::default()).unwrap();
+        assert_eq!(results.values[0].next_addresses, vec!["bc1q03p495zw08k8dvdl9guy5nw3kw7qmfsx2y7g3f"]);
+
+        let filter = Filter { xpub_lookahead: 3, ..Filter::default() };
+        let results = store.query(filter, PageQuery::default()).unwrap();
+        let next = &results.values[0].next_addresses;
+        assert_eq!(next.len(), 3);
+        assert_eq!(next[0], "bc1q03p495zw08k8dvdl9guy5nw3kw7qmfsx2y7g3f");
+        assert_eq!(next.iter().collect::<HashSet<_>>().len(), 3);
+
+        let filter = Filter { xpub_lookahead: 100, ..Filter::default() };
+        let results = store.query(filter, PageQuery::default()).unwrap();
+        assert_eq!(results.values[0].next_addresses.len(), XPUB_GAP_LIMIT as usize);
+    }
+
     #[test]
     fn provide_with_current_addr_on_legacy_xpub() {
         let tmp_dir = TempDir::new("test-addressbook").unwrap();
@@ -511,7 +1449,8 @@ mod tests {
         address.address = "0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb".to_string();
         item.set_address(address);
 
-        let exp = item.clone();
+        let mut exp = item.clone();
+        exp.network = NetworkKind::NETWORK_MAINNET;
 
         let results = store.add(vec![item.clone()]).expect("not saved");
         assert_eq!(results.len(), 1);
@@ -557,6 +1496,36 @@ mod tests {
         assert_eq!(result.id, id);
     }
 
+    #[test]
+    fn can_find_by_description() {
+        let tmp_dir = TempDir::new("test-addressbook").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let store = access.get_addressbook();
+
+        let mut item = proto_BookItem::new();
+        item.create_timestamp = 1_647_313_850_992;
+        item.blockchain = 101;
+        item.label = "Alice".to_string();
+        item.description = "Met at conference, pays invoices monthly".to_string();
+        let mut address = proto_Address::new();
+        address.address = "0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb".to_string();
+        item.set_address(address);
+        store.add(vec![item]).unwrap();
+
+        let filter = Filter { text: Some("invoices".to_string()), ..Filter::default() };
+        let results = store.query(filter, PageQuery::default()).unwrap();
+        assert_eq!(results.values.len(), 1);
+        assert_eq!(results.values[0].data.description, "Met at conference, pays invoices monthly");
+
+        let filter = Filter { text: Some("invoices".to_string()), ..Filter::default() };
+        let results = store.query_projected(filter, PageQuery::default(), vec![BookItemField::Label]).unwrap();
+        assert_eq!(results.values.len(), 1);
+        assert_eq!(results.values[0].data.label, "Alice");
+
+        let filter = Filter { text: Some("weekly".to_string()), ..Filter::default() };
+        assert!(store.query(filter, PageQuery::default()).unwrap().values.is_empty());
+    }
+
     #[test]
     fn can_find_by_russian_text() {
         let tmp_dir = TempDir::new("test-addressbook").unwrap();
@@ -587,6 +1556,45 @@ mod tests {
         assert_eq!(result.id, id);
     }
 
+    #[test]
+    fn find_by_localized_label() {
+        let tmp_dir = TempDir::new("test-addressbook").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let store = access.get_addressbook();
+
+        let mut item = proto_BookItem::new();
+        item.create_timestamp = 1_647_313_850_992;
+        item.blockchain = 101;
+        item.label = "Hello World!".to_string();
+        let mut variant = LocalizedLabel::new();
+        variant.locale = "ru".to_string();
+        variant.text = "Привет Мир!".to_string();
+        item.localized_labels.push(variant);
+        let mut address = proto_Address::new();
+        address.address = "0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb".to_string();
+        item.set_address(address);
+
+        store.add(vec![item.clone()]).expect("not saved");
+
+        let filter = Filter {
+            text: Some("мир".to_string()),
+            locale: Some("ru-RU".to_string()),
+            ..Filter::default()
+        };
+        let results = store.query(filter, PageQuery::default()).expect("queried");
+        assert_eq!(results.values.len(), 1);
+        assert_eq!(results.values[0].label, "Привет Мир!");
+
+        let filter = Filter {
+            text: Some("world".to_string()),
+            locale: Some("en".to_string()),
+            ..Filter::default()
+        };
+        let results = store.query_projected(filter, PageQuery::default(), vec![]).expect("queried");
+        assert_eq!(results.values.len(), 1);
+        assert_eq!(results.values[0].label, "Hello World!");
+    }
+
     #[test]
     fn can_find_by_one_char_of_text() {
         let tmp_dir = TempDir::new("test-addressbook").unwrap();
@@ -647,6 +1655,268 @@ mod tests {
         assert_eq!(result.id, id);
     }
 
+    #[test]
+    fn get_by_exact_address() {
+        let tmp_dir = TempDir::new("test-addressbook").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let store = access.get_addressbook();
+
+        let mut item_1 = proto_BookItem::new();
+        item_1.create_timestamp = 1_647_313_850_992;
+        item_1.blockchain = 100;
+        item_1.label = "Alice".to_string();
+        let mut address = proto_Address::new();
+        address.address = "0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb".to_string();
+        item_1.set_address(address);
+
+        let mut item_2 = item_1.clone();
+        item_2.create_timestamp = 1_647_313_860_992;
+        item_2.label = "Alice (old)".to_string();
+        item_2.archived = true;
+
+        let mut item_3 = item_1.clone();
+        item_3.create_timestamp = 1_647_313_870_992;
+        item_3.blockchain = 101;
+        item_3.label = "Alice on ETC".to_string();
+
+        let mut item_4 = item_1.clone();
+        item_4.create_timestamp = 1_647_313_880_992;
+        item_4.label = "Bob".to_string();
+        item_4.mut_address().address = "0xedd91797204d3537fbabde0e0e42aae99975f2b0".to_string();
+
+        store.add(vec![item_1, item_2, item_3, item_4]).unwrap();
+
+        let results = store.get_by_address(100, "0xedd91797204d3537fbabde0e0e42aae99975f2bb".to_string()).unwrap();
+        let labels: Vec<String> = results.iter().map(|v| v.label.clone()).collect();
+        assert_eq!(labels, vec!["Alice (old)", "Alice"]);
+        assert_eq!(results[0].current_address, "0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb");
+        assert_eq!(results[0].normalized_address, "0xedd91797204d3537fbabde0e0e42aae99975f2bb");
+
+        let results = store.get_by_address(100, "0xEdD91797204D3537fBaBDe0E0E42AaE99975f2B0".to_string()).unwrap();
+        assert_eq!(results[0].current_address, "0xedd91797204d3537fbabde0e0e42aae99975f2b0");
+        assert_eq!(results[0].display_address, proto_Address::to_checksum("0xedd91797204d3537fbabde0e0e42aae99975f2b0"));
+        assert_ne!(results[0].display_address, results[0].current_address);
+
+        let results = store.get_by_address(101, "0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb".to_string()).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].label, "Alice on ETC");
+
+        // not a partial match
+        assert!(store.get_by_address(100, "0xEdD91797204D3537fBaBDe0E0E42AaE99975f2".to_string()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn search_with_typos() {
+        let tmp_dir = TempDir::new("test-addressbook").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let store = access.get_addressbook();
+
+        let mut items = Vec::new();
+        for (i, label) in ["John Smith", "Jane Doe", "Binance", "Johnny"].iter().enumerate() {
+            let mut item = proto_BookItem::new();
+            item.create_timestamp = 1_647_313_850_000 + i as u64;
+            item.blockchain = if i == 3 { 101 } else { 100 };
+            item.label = label.to_string();
+            let mut address = proto_Address::new();
+            address.address = "0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb".to_string();
+            item.set_address(address);
+            items.push(item);
+        }
+        store.add(items).unwrap();
+
+        let filter = Filter { text: Some("Jhon".to_string()), ..Filter::default() };
+        let results = store.search_fuzzy(filter, 10).unwrap();
+        let labels: Vec<String> = results.iter().map(|v| v.item.label.clone()).collect();
+        assert_eq!(labels, vec!["Johnny", "John Smith"]);
+        assert!(results.iter().all(|v| v.score >= FUZZY_MIN_SCORE && v.score < 1.0));
+
+        // exact match is first
+        let filter = Filter { text: Some("Jhon Smith".to_string()), ..Filter::default() };
+        let results = store.search_fuzzy(filter, 1).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].item.label, "John Smith");
+
+        let filter = Filter { text: Some("Jhon".to_string()), blockchain: Some(100), ..Filter::default() };
+        let results = store.search_fuzzy(filter, 10).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].item.label, "John Smith");
+
+        let filter = Filter { text: Some("smith".to_string()), ..Filter::default() };
+        let results = store.search_fuzzy(filter, 10).unwrap();
+        assert_eq!(results[0].score, 1.0);
+
+        assert!(store.search_fuzzy(Filter::default(), 10).is_err());
+    }
+
+    #[test]
+    fn search_ranked_by_relevance() {
+        let tmp_dir = TempDir::new("test-addressbook").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let store = access.get_addressbook();
+
+        let mut items = Vec::new();
+        // the most recent is the least relevant
+        for (i, (label, address)) in [
+            ("Alice", "0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb"),
+            ("Bob Alice", "0x60bcd26c20586076eea2e7206e22bf5256e76a20"),
+            ("Alice Cooper", "0x000000000D71b31F9C460f26C45589EC91551969"),
+            ("Bob", "0x0000000000000000000000000000000000a11ce0"),
+        ].iter().enumerate() {
+            let mut item = proto_BookItem::new();
+            item.create_timestamp = 1_647_313_850_000 - i as u64;
+            item.blockchain = 100;
+            item.label = label.to_string();
+            let mut proto_address = proto_Address::new();
+            proto_address.address = address.to_string();
+            item.set_address(proto_address);
+            items.push(item);
+        }
+        store.add(items).unwrap();
+
+        let filter = Filter { text: Some("alice".to_string()), ..Filter::default() };
+        let results = store.search_ranked(filter, 5).unwrap();
+        let labels: Vec<String> = results.iter().map(|v| v.item.label.clone()).collect();
+        assert_eq!(labels, vec!["Alice", "Alice Cooper", "Bob Alice"]);
+        assert_eq!(results[0].score, 1.0);
+
+        let filter = Filter { text: Some("a11ce".to_string()), ..Filter::default() };
+        let results = store.search_ranked(filter, 5).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].item.label, "Bob");
+        assert!(results[0].score < 1.0 / 3.0);
+
+        let filter = Filter { text: Some("alice".to_string()), ..Filter::default() };
+        assert_eq!(store.search_ranked(filter, 2).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn set_and_clear_avatar() {
+        let tmp_dir = TempDir::new("test-addressbook").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let store = access.get_addressbook();
+
+        let mut item = proto_BookItem::new();
+        item.create_timestamp = 1_647_313_850_992;
+        item.update_timestamp = 1_647_313_850_992;
+        item.blockchain = 100;
+        item.label = "Alice".to_string();
+        let mut address = proto_Address::new();
+        address.address = "0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb".to_string();
+        item.set_address(address);
+        let id = store.add(vec![item]).unwrap()[0];
+
+        let image = vec![0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a];
+        assert!(store.set_avatar(id, image.clone(), "image/png".to_string()).unwrap());
+        assert!(!store.set_avatar(Uuid::new_v4(), image.clone(), "image/png".to_string()).unwrap());
+        let stored = store.get(id).unwrap().unwrap().data;
+        assert_eq!(stored.get_avatar().image, image);
+        assert_eq!(stored.get_avatar().content_type, "image/png");
+        assert!(stored.update_timestamp > 1_647_313_850_992);
+
+        // it's still found by the indexes
+        let filter = Filter { text: Some("alice".to_string()), ..Filter::default() };
+        assert_eq!(store.query(filter, PageQuery::default()).unwrap().values.len(), 1);
+
+        // not exported
+        let bundle = store.export_bundle(1024).unwrap();
+        let parsed = proto_BookItemBundle::parse_from_bytes(&bundle).unwrap();
+        assert!(!parsed.items[0].has_avatar());
+
+        let too_large = vec![0u8; MAX_AVATAR_SIZE + 1];
+        assert!(store.set_avatar(id, too_large, "image/png".to_string()).is_err());
+        assert!(store.set_avatar(id, image.clone(), "text/plain".to_string()).is_err());
+        assert!(store.set_avatar(id, vec![], "image/png".to_string()).is_err());
+        assert_eq!(store.get(id).unwrap().unwrap().data.get_avatar().image, image);
+
+        assert!(store.clear_avatar(id).unwrap());
+        assert!(!store.get(id).unwrap().unwrap().data.has_avatar());
+    }
+
+    #[test]
+    fn record_sends_to_contact() {
+        let tmp_dir = TempDir::new("test-addressbook").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let store = access.get_addressbook();
+
+        let mut item = proto_BookItem::new();
+        item.create_timestamp = 1_647_313_850_992;
+        item.blockchain = 100;
+        item.label = "Alice".to_string();
+        let mut address = proto_Address::new();
+        address.address = "0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb".to_string();
+        item.set_address(address);
+        let id = store.add(vec![item]).unwrap()[0];
+        assert!(store.get(id).unwrap().unwrap().usage.is_none());
+
+        let usdt = AssetId::erc20(100, "0xdAC17F958D2ee523a2206206994597C13D831ec7").unwrap();
+        assert!(store.record_send(id, "0x01".to_string(), AssetId::Native(100), BigUint::from(100u32)).unwrap());
+        assert!(store.record_send(id, "0x02".to_string(), AssetId::Native(100), BigUint::from(50u32)).unwrap());
+        assert!(store.record_send(id, "0x03".to_string(), usdt.clone(), BigUint::from(10u32)).unwrap());
+        // a retry of the same tx
+        assert!(store.record_send(id, "0x03".to_string(), usdt.clone(), BigUint::from(10u32)).unwrap());
+        assert!(!store.record_send(Uuid::new_v4(), "0x04".to_string(), usdt.clone(), BigUint::from(10u32)).unwrap());
+
+        let filter = Filter { text: Some("alice".to_string()), ..Filter::default() };
+        let results = store.query(filter, PageQuery::default()).unwrap();
+        let usage = results.values[0].usage.clone().expect("no usage");
+        assert_eq!(usage.sends, 3);
+        assert_eq!(usage.volume.get(&AssetId::Native(100)), Some(&BigUint::from(150u32)));
+        assert_eq!(usage.volume.get(&usdt), Some(&BigUint::from(10u32)));
+        assert_eq!(usage.last_tx_id, "0x03");
+
+        store.remove(id).unwrap();
+        assert!(access.db.get(format!("book_usage:{}", id)).unwrap().is_none());
+    }
+
+    #[test]
+    fn require_unique_labels() {
+        let tmp_dir = TempDir::new("test-addressbook").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let store = access.get_addressbook();
+
+        let create = |label: &str, ts: u64| {
+            let mut item = proto_BookItem::new();
+            item.create_timestamp = ts;
+            item.blockchain = 100;
+            item.label = label.to_string();
+            let mut address = proto_Address::new();
+            address.address = "0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb".to_string();
+            item.set_address(address);
+            item
+        };
+
+        // allowed by default
+        store.add(vec![create("Kraken", 1_647_313_850_000), create("kraken", 1_647_313_850_001)]).unwrap();
+
+        access.validators().unwrap().set_unique_book_labels(true);
+        let id = store.add(vec![create("Main exchange", 1_647_313_850_002)]).unwrap()[0];
+        let err = store.add(vec![create(" MAIN Exchange", 1_647_313_850_003)]).unwrap_err();
+        assert!(matches!(err, StateError::InvalidValue(InvalidValueError::NameMessage(field, _)) if field == "label"));
+        // same in a single call
+        assert!(store.add(vec![create("Binance", 1_647_313_850_004), create("binance", 1_647_313_850_005)]).is_err());
+        // the item itself isn't a duplicate
+        let mut item = store.get(id).unwrap().unwrap().data;
+        item.description = "Most of the funds".to_string();
+        store.update(id, item.clone()).unwrap();
+
+        let checked = store.add_checked(vec![create("main exchange", 1_647_313_850_006), create("Bitstamp", 1_647_313_850_007)], ValidationMode::Lenient).unwrap();
+        assert_eq!(checked.results[0], None);
+        assert!(checked.results[1].is_some());
+        assert!(checked.warnings[0].skipped);
+
+        let mut other = store.get(checked.results[1].unwrap()).unwrap().unwrap().data;
+        other.label = "Main Exchange".to_string();
+        assert!(store.update(Uuid::parse_str(&other.id).unwrap(), other).is_err());
+
+        // the label is free after a rename
+        item.label = "Old exchange".to_string();
+        store.update(id, item).unwrap();
+        store.add(vec![create("Main exchange", 1_647_313_850_008)]).unwrap();
+
+        let filter = Filter { include_archived: true, ..Filter::default() };
+        assert_eq!(store.query(filter, PageQuery::default()).unwrap().values.len(), 5);
+    }
+
     #[test]
     fn updates_existing_entry() {
         let tmp_dir = TempDir::new("test-addressbook").unwrap();
@@ -670,7 +1940,8 @@ mod tests {
 
         let ts_end = Utc::now().naive_utc().timestamp_millis() as u64;
 
-        let exp = updated.clone();
+        let mut exp = updated.clone();
+        exp.network = NetworkKind::NETWORK_MAINNET;
 
         let results = store.query(Filter::default(), PageQuery::default()).expect("queried");
         assert_eq!(results.values.len(), 1);
@@ -724,7 +1995,7 @@ mod tests {
             item.blockchain = 101;
             item.label = format!("Hello World! {}", i);
             let mut address = proto_Address::new();
-            address.address = format!("0xEdD91797204D3537fBaBDe0E0E42AaE99975f00{}", i);
+            address.address = format!("0xedd91797204d3537fbabde0e0e42aae99975f00{}", i);
             item.set_address(address);
 
             let _ = store.add(vec![item.clone()]).expect("not saved");
@@ -771,6 +2042,498 @@ mod tests {
     }
 
 
+    #[test]
+    fn validates_with_plugin() {
+        let tmp_dir = TempDir::new("test-addressbook").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        access.validators().unwrap().add_addressbook_validator(Box::new(|item| {
+            if item.label.is_empty() {
+                Err(InvalidValueError::NameMessage("label".to_string(), "required".to_string()))
+            } else {
+                Ok(())
+            }
+        }));
+        let store = access.get_addressbook();
+
+        let mut item = proto_BookItem::new();
+        item.create_timestamp = 1_647_313_850_992;
+        item.blockchain = 101;
+        let mut address = proto_Address::new();
+        address.address = "0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb".to_string();
+        item.set_address(address);
+
+        let results = store.add(vec![item.clone()]);
+        assert!(results.is_err());
+
+        item.label = "Hello World!".to_string();
+        let results = store.add(vec![item.clone()]);
+        assert!(results.is_ok());
+
+        let results = store.query(Filter::default(), PageQuery::default()).expect("queried");
+        assert_eq!(results.values.len(), 1);
+    }
+
+    #[test]
+    fn query_by_network() {
+        let tmp_dir = TempDir::new("test-addressbook").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let store = access.get_addressbook();
+
+        let mut item_etc = proto_BookItem::new();
+        item_etc.create_timestamp = 1_647_313_850_992;
+        item_etc.blockchain = 101;
+        let mut address = proto_Address::new();
+        address.address = "0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb".to_string();
+        item_etc.set_address(address);
+
+        let mut item_sepolia = item_etc.clone();
+        item_sepolia.create_timestamp = 1_647_313_860_992;
+        item_sepolia.blockchain = 10009;
+
+        store.add(vec![item_etc, item_sepolia]).unwrap();
+
+        let filter = Filter {
+            network: Some(NetworkKind::NETWORK_MAINNET),
+            ..Filter::default()
+        };
+        let results = store.query(filter, PageQuery::default()).unwrap();
+        assert_eq!(results.values.len(), 1);
+        assert_eq!(results.values[0].data.blockchain, 101);
+        assert_eq!(results.values[0].data.network, NetworkKind::NETWORK_MAINNET);
+
+        let filter = Filter {
+            network: Some(NetworkKind::NETWORK_TESTNET),
+            ..Filter::default()
+        };
+        let results = store.query(filter, PageQuery::default()).unwrap();
+        assert_eq!(results.values.len(), 1);
+        assert_eq!(results.values[0].data.blockchain, 10009);
+    }
+
+    #[test]
+    fn archive_and_unarchive() {
+        let tmp_dir = TempDir::new("test-addressbook").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let store = access.get_addressbook();
+
+        let mut item_1 = proto_BookItem::new();
+        item_1.create_timestamp = 1_647_313_850_992;
+        item_1.blockchain = 100;
+        item_1.label = "Alice".to_string();
+        let mut address = proto_Address::new();
+        address.address = "0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb".to_string();
+        item_1.set_address(address);
+
+        let mut item_2 = item_1.clone();
+        item_2.create_timestamp = 1_647_313_860_992;
+        item_2.label = "Bob".to_string();
+
+        let ids = store.add(vec![item_1, item_2]).unwrap();
+        assert!(store.archive(ids[0]).unwrap());
+
+        let results = store.query(Filter::default(), PageQuery::default()).unwrap();
+        assert_eq!(results.values.len(), 1);
+        assert_eq!(results.values[0].data.label, "Bob");
+
+        let filter = Filter { include_archived: true, ..Filter::default() };
+        let results = store.query(filter, PageQuery::default()).unwrap();
+        assert_eq!(results.values.len(), 2);
+        // it's still searchable when requested
+        let filter = Filter { include_archived: true, text: Some("alice".to_string()), ..Filter::default() };
+        let results = store.query(filter, PageQuery::default()).unwrap();
+        assert_eq!(results.values.len(), 1);
+        assert!(results.values[0].data.archived);
+        assert!(results.values[0].data.update_timestamp > 0);
+
+        // the archived flag is always available, even for a partial read
+        let results = store.query_projected(Filter::default(), PageQuery::default(), vec![BookItemField::Label]).unwrap();
+        assert_eq!(results.values.len(), 1);
+
+        assert!(store.unarchive(ids[0]).unwrap());
+        let results = store.query(Filter::default(), PageQuery::default()).unwrap();
+        assert_eq!(results.values.len(), 2);
+        assert!(!store.get(ids[0]).unwrap().unwrap().data.archived);
+
+        assert!(!store.archive(Uuid::new_v4()).unwrap());
+    }
+
+    #[test]
+    fn query_by_group() {
+        let tmp_dir = TempDir::new("test-addressbook").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let store = access.get_addressbook();
+
+        let mut item_1 = proto_BookItem::new();
+        item_1.create_timestamp = 1_647_313_850_992;
+        item_1.blockchain = 100;
+        item_1.label = "Kraken".to_string();
+        item_1.group = "Exchanges".to_string();
+        let mut address = proto_Address::new();
+        address.address = "0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb".to_string();
+        item_1.set_address(address);
+
+        let mut item_2 = item_1.clone();
+        item_2.create_timestamp = 1_647_313_860_992;
+        item_2.label = "Binance".to_string();
+        item_2.group = "exchanges ".to_string();
+
+        let mut item_3 = item_1.clone();
+        item_3.create_timestamp = 1_647_313_870_992;
+        item_3.label = "Alice".to_string();
+        item_3.group = "Friends".to_string();
+
+        let mut item_4 = item_1.clone();
+        item_4.create_timestamp = 1_647_313_880_992;
+        item_4.label = "Bob".to_string();
+        item_4.clear_group();
+
+        store.add(vec![item_1, item_2, item_3, item_4]).unwrap();
+
+        let filter = Filter { group: Some("EXCHANGES".to_string()), ..Filter::default() };
+        let results = store.query(filter, PageQuery::default()).unwrap();
+        let labels: Vec<String> = results.values.iter().map(|v| v.data.label.clone()).collect();
+        assert_eq!(labels, vec!["Binance", "Kraken"]);
+
+        let filter = Filter { group: Some("friends".to_string()), text: Some("ali".to_string()), ..Filter::default() };
+        let results = store.query(filter, PageQuery::default()).unwrap();
+        assert_eq!(results.values.len(), 1);
+        assert_eq!(results.values[0].data.label, "Alice");
+
+        let groups = store.get_groups().unwrap();
+        assert_eq!(groups, vec![("Exchanges".to_string(), 2), ("Friends".to_string(), 1)]);
+    }
+
+    #[test]
+    fn find_by_domain_name() {
+        let tmp_dir = TempDir::new("test-addressbook").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let store = access.get_addressbook();
+
+        let mut item = proto_BookItem::new();
+        item.create_timestamp = 1_647_313_850_992;
+        item.blockchain = 100;
+        item.label = "Alice".to_string();
+        item.domain_name = "Wonderland.ETH".to_string();
+        let mut address = proto_Address::new();
+        address.address = "0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb".to_string();
+        item.set_address(address);
+        let id = store.add(vec![item]).unwrap()[0];
+
+        let filter = Filter { text: Some("wonderland".to_string()), ..Filter::default() };
+        let results = store.query(filter, PageQuery::default()).unwrap();
+        assert_eq!(results.values.len(), 1);
+        assert_eq!(results.values[0].data.domain_name, "wonderland.eth");
+        let before = results.values[0].data.update_timestamp;
+
+        let ts = Utc::now();
+        assert!(store.set_domain_resolution(id, "0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb".to_string(), ts).unwrap());
+        assert!(!store.set_domain_resolution(Uuid::new_v4(), "0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb".to_string(), ts).unwrap());
+
+        let filter = Filter { text: Some("land.eth".to_string()), ..Filter::default() };
+        let results = store.query(filter, PageQuery::default()).unwrap();
+        assert_eq!(results.values.len(), 1);
+        let item = &results.values[0].data;
+        assert_eq!(item.update_timestamp, before);
+        assert_eq!(item.get_domain_resolution().timestamp, ts.timestamp_millis() as u64);
+        assert_eq!(item.get_resolved_address(chrono::Duration::hours(1)), Some("0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb"));
+    }
+
+    #[test]
+    fn query_sorted() {
+        let tmp_dir = TempDir::new("test-addressbook").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let store = access.get_addressbook();
+
+        let mut items = Vec::new();
+        for (i, label) in ["kraken", "Binance", "", "alice", "Bob"].iter().enumerate() {
+            let mut item = proto_BookItem::new();
+            item.create_timestamp = 1_647_313_850_000 + i as u64;
+            item.update_timestamp = 1_647_313_860_000 - i as u64;
+            item.blockchain = 100;
+            item.label = label.to_string();
+            let mut address = proto_Address::new();
+            address.address = "0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb".to_string();
+            item.set_address(address);
+            items.push(item);
+        }
+        store.add(items).unwrap();
+
+        let labels = |sort: BookItemSort, order: PageOrder| {
+            let mut labels = Vec::new();
+            let mut cursor = None;
+            loop {
+                let filter = Filter { sort, ..Filter::default() };
+                let results = store.query(filter, PageQuery { limit: 2, cursor, order }).unwrap();
+                labels.extend(results.values.iter().map(|v| v.data.label.clone()));
+                cursor = results.cursor;
+                if cursor.is_none() {
+                    break
+                }
+            }
+            labels
+        };
+
+        assert_eq!(labels(BookItemSort::Label, PageOrder::NewestFirst), vec!["alice", "Binance", "Bob", "kraken", ""]);
+        assert_eq!(labels(BookItemSort::Label, PageOrder::OldestFirst), vec!["", "kraken", "Bob", "Binance", "alice"]);
+        assert_eq!(labels(BookItemSort::Created, PageOrder::NewestFirst), vec!["Bob", "alice", "", "Binance", "kraken"]);
+        assert_eq!(labels(BookItemSort::Updated, PageOrder::NewestFirst), vec!["kraken", "Binance", "", "alice", "Bob"]);
+
+        // other criteria are still applied
+        let filter = Filter { sort: BookItemSort::Label, text: Some("bob".to_string()), ..Filter::default() };
+        let results = store.query(filter, PageQuery::default()).unwrap();
+        assert_eq!(results.values.len(), 1);
+        assert_eq!(results.values[0].data.label, "Bob");
+
+        // an update moves it to the top
+        let mut item = store.query(Filter::default(), PageQuery::default()).unwrap().values.into_iter()
+            .find(|v| v.data.label == "Bob").unwrap().data;
+        item.label = "Aaron".to_string();
+        store.update(Uuid::parse_str(&item.id).unwrap(), item).unwrap();
+        assert_eq!(labels(BookItemSort::Label, PageOrder::NewestFirst), vec!["Aaron", "alice", "Binance", "kraken", ""]);
+        assert_eq!(labels(BookItemSort::Updated, PageOrder::NewestFirst)[0], "Aaron");
+    }
+
+    #[test]
+    fn query_by_date_range() {
+        let tmp_dir = TempDir::new("test-addressbook").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let store = access.get_addressbook();
+
+        let mut items = Vec::new();
+        for (i, label) in ["first", "second", "third", "fourth"].iter().enumerate() {
+            let mut item = proto_BookItem::new();
+            item.create_timestamp = 1_647_313_850_000 + i as u64 * 1000;
+            // the second one is updated last
+            item.update_timestamp = if i == 1 { 1_647_313_900_000 } else { item.create_timestamp };
+            item.blockchain = 100;
+            item.label = label.to_string();
+            let mut address = proto_Address::new();
+            address.address = "0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb".to_string();
+            item.set_address(address);
+            items.push(item);
+        }
+        store.add(items).unwrap();
+
+        let labels = |filter: Filter| -> Vec<String> {
+            store.query(filter, PageQuery::default()).unwrap().values.iter().map(|v| v.data.label.clone()).collect()
+        };
+
+        let filter = Filter {
+            created_after: Some(Utc.timestamp_millis_opt(1_647_313_851_000).unwrap()),
+            ..Filter::default()
+        };
+        assert_eq!(labels(filter), vec!["fourth", "third", "second"]);
+
+        let filter = Filter {
+            created_after: Some(Utc.timestamp_millis_opt(1_647_313_851_000).unwrap()),
+            created_before: Some(Utc.timestamp_millis_opt(1_647_313_852_000).unwrap()),
+            ..Filter::default()
+        };
+        assert_eq!(labels(filter), vec!["third", "second"]);
+
+        let filter = Filter {
+            created_before: Some(Utc.timestamp_millis_opt(1_647_313_851_500).unwrap()),
+            blockchain: Some(100),
+            ..Filter::default()
+        };
+        assert_eq!(labels(filter), vec!["second", "first"]);
+
+        // an inverted range is just empty
+        let filter = Filter {
+            created_after: Some(Utc.timestamp_millis_opt(1_647_313_852_000).unwrap()),
+            created_before: Some(Utc.timestamp_millis_opt(1_647_313_851_000).unwrap()),
+            ..Filter::default()
+        };
+        assert!(labels(filter).is_empty());
+
+        let filter = Filter {
+            updated_after: Some(Utc.timestamp_millis_opt(1_647_313_853_000).unwrap()),
+            sort: BookItemSort::Updated,
+            ..Filter::default()
+        };
+        assert_eq!(labels(filter), vec!["second", "fourth"]);
+
+        let filter = Filter {
+            updated_after: Some(Utc.timestamp_millis_opt(1_647_313_853_000).unwrap()),
+            ..Filter::default()
+        };
+        assert_eq!(labels(filter), vec!["fourth", "second"]);
+    }
+
+    #[test]
+    fn query_by_blockchain() {
+        let tmp_dir = TempDir::new("test-addressbook").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let store = access.get_addressbook();
+
+        let mut items = Vec::new();
+        for (i, blockchain) in [100, 101, 10001, 100, 10002].iter().enumerate() {
+            let mut item = proto_BookItem::new();
+            item.create_timestamp = 1_647_313_850_000 + i as u64;
+            item.blockchain = *blockchain;
+            item.label = format!("Item {}", i);
+            let mut address = proto_Address::new();
+            address.address = "0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb".to_string();
+            item.set_address(address);
+            items.push(item);
+        }
+        store.add(items).unwrap();
+
+        let filter = Filter { blockchain: Some(100), ..Filter::default() };
+        match filter.get_index_bounds() {
+            (Bound::Included(start), _) => assert!(start.starts_with("idx:addrbook:8/100/")),
+            other => panic!("unexpected bounds: {:?}", other),
+        }
+        let results = store.query(filter, PageQuery::default()).unwrap();
+        let labels: Vec<String> = results.values.iter().map(|v| v.data.label.clone()).collect();
+        assert_eq!(labels, vec!["Item 3", "Item 0"]);
+
+        // doesn't mix up 100 and 10001
+        let filter = Filter { blockchain: Some(10001), ..Filter::default() };
+        let results = store.query(filter, PageQuery::default()).unwrap();
+        let labels: Vec<String> = results.values.iter().map(|v| v.data.label.clone()).collect();
+        assert_eq!(labels, vec!["Item 2"]);
+    }
+
+    #[test]
+    fn migrate_blockchain_index() {
+        let tmp_dir = TempDir::new("test-addressbook").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let store = access.get_addressbook();
+
+        // as it was stored before the blockchain index
+        let mut item = proto_BookItem::new();
+        item.id = "989d7648-13e3-4cb9-acfb-85464f063b34".to_string();
+        item.create_timestamp = 1_647_313_850_992;
+        item.blockchain = 100;
+        let mut address = proto_Address::new();
+        address.address = "0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb".to_string();
+        item.set_address(address);
+        let key = format!("addrbook{}", item.id);
+        let mut batch = sled::Batch::default();
+        batch.insert(format!("idx:addrbook:1/{}", IndexConvert::get_desc_timestamp(item.create_timestamp)).as_bytes(), key.as_bytes());
+        batch.insert(key.as_bytes(), item.write_to_bytes().unwrap());
+        access.db.apply_batch(batch).unwrap();
+
+        let filter = || Filter { blockchain: Some(100), ..Filter::default() };
+        assert!(store.query(filter(), PageQuery::default()).unwrap().values.is_empty());
+
+        store.migrate(6).unwrap();
+
+        let results = store.query(filter(), PageQuery::default()).unwrap();
+        assert_eq!(results.values.len(), 1);
+    }
+
+    #[test]
+    fn migrate_sort_indexes() {
+        let tmp_dir = TempDir::new("test-addressbook").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let store = access.get_addressbook();
+
+        // as it was stored before the sort indexes
+        let mut item = proto_BookItem::new();
+        item.id = "989d7648-13e3-4cb9-acfb-85464f063b34".to_string();
+        item.create_timestamp = 1_647_313_850_992;
+        item.blockchain = 100;
+        item.label = "Alice".to_string();
+        let mut address = proto_Address::new();
+        address.address = "0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb".to_string();
+        item.set_address(address);
+        let key = format!("addrbook{}", item.id);
+        let mut batch = sled::Batch::default();
+        batch.insert(format!("idx:addrbook:1/{}", IndexConvert::get_desc_timestamp(item.create_timestamp)).as_bytes(), key.as_bytes());
+        batch.insert(key.as_bytes(), item.write_to_bytes().unwrap());
+        access.db.apply_batch(batch).unwrap();
+
+        let filter = || Filter { sort: BookItemSort::Label, ..Filter::default() };
+        assert!(store.query(filter(), PageQuery::default()).unwrap().values.is_empty());
+
+        store.migrate(5).unwrap();
+
+        let results = store.query(filter(), PageQuery::default()).unwrap();
+        assert_eq!(results.values.len(), 1);
+        assert_eq!(results.values[0].data.label, "Alice");
+    }
+
+    #[test]
+    fn migrate_network_kind() {
+        let tmp_dir = TempDir::new("test-addressbook").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let store = access.get_addressbook();
+
+        // as it was stored before the network kind
+        let mut item = proto_BookItem::new();
+        item.id = "989d7648-13e3-4cb9-acfb-85464f063b34".to_string();
+        item.create_timestamp = 1_647_313_850_992;
+        item.blockchain = 10009;
+        let mut address = proto_Address::new();
+        address.address = "0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb".to_string();
+        item.set_address(address);
+        let key = format!("addrbook{}", item.id);
+        let mut batch = sled::Batch::default();
+        for idx in item.get_index_keys() {
+            batch.insert(idx.as_bytes(), key.as_bytes());
+        }
+        batch.insert(key.as_bytes(), item.write_to_bytes().unwrap());
+        access.db.apply_batch(batch).unwrap();
+
+        let filter = || Filter {
+            network: Some(NetworkKind::NETWORK_TESTNET),
+            ..Filter::default()
+        };
+        assert!(store.query(filter(), PageQuery::default()).unwrap().values.is_empty());
+
+        store.migrate(4).unwrap();
+
+        let results = store.query(filter(), PageQuery::default()).unwrap();
+        assert_eq!(results.values.len(), 1);
+        assert_eq!(results.values[0].data.network, NetworkKind::NETWORK_TESTNET);
+    }
+
+    #[test]
+    fn add_with_validation_mode() {
+        let tmp_dir = TempDir::new("test-addressbook").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let store = access.get_addressbook();
+
+        let mut item_good = proto_BookItem::new();
+        item_good.create_timestamp = 1_647_313_850_992;
+        item_good.blockchain = 101;
+        let mut address = proto_Address::new();
+        address.address = "0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb".to_string();
+        item_good.set_address(address);
+
+        let mut item_unknown = item_good.clone();
+        item_unknown.blockchain = 777;
+        item_unknown.create_timestamp = 1_647_313_860_992;
+
+        let mut item_invalid = item_good.clone();
+        item_invalid.mut_address().address = "INVALID!!!".to_string();
+        item_invalid.create_timestamp = 1_647_313_870_992;
+
+        let all = vec![item_good.clone(), item_unknown.clone(), item_invalid.clone()];
+
+        let results = store.add_checked(all.clone(), ValidationMode::Strict);
+        assert!(results.is_err());
+        assert!(store.query(Filter::default(), PageQuery::default()).unwrap().values.is_empty());
+
+        let results = store.add_checked(all.clone(), ValidationMode::Lenient).unwrap();
+        assert!(results.results[0].is_some());
+        assert!(results.results[1].is_some());
+        assert!(results.results[2].is_none());
+        assert_eq!(results.warnings.len(), 2);
+        assert_eq!(results.warnings[0].index, 1);
+        assert!(!results.warnings[0].skipped);
+        assert_eq!(results.warnings[1].index, 2);
+        assert!(results.warnings[1].skipped);
+        assert_eq!(store.query(Filter::default(), PageQuery::default()).unwrap().values.len(), 2);
+
+        let results = store.add_checked(vec![item_invalid], ValidationMode::Off).unwrap();
+        assert!(results.results[0].is_some());
+        assert!(results.warnings.is_empty());
+        assert_eq!(store.query(Filter::default(), PageQuery::default()).unwrap().values.len(), 3);
+    }
+
     #[test]
     fn validates_address() {
         let tmp_dir = TempDir::new("test-addressbook").unwrap();
@@ -790,4 +2553,46 @@ mod tests {
         let results = store.query(Filter::default(), PageQuery::default()).expect("queried");
         assert!(results.values.is_empty());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn verify_corrupted_items() {
+        let tmp_dir = TempDir::new("test-addressbook").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let store = access.get_addressbook();
+
+        let mut items = Vec::new();
+        for i in 0..3 {
+            let mut item = proto_BookItem::new();
+            item.create_timestamp = 1_647_313_850_992 + i;
+            item.blockchain = 101;
+            let mut address = proto_Address::new();
+            address.address = format!("0xedd91797204d3537fbabde0e0e42aae99975f2b{}", i);
+            item.set_address(address);
+            items.push(item);
+        }
+        let ids = store.add(items).expect("not saved");
+        assert!(store.verify_all().unwrap().is_empty());
+
+        // a legacy record without an address
+        let mut no_address = store.get(ids[0]).unwrap().unwrap().data;
+        no_address.clear_address();
+        store.db.insert(AddressBookAccess::get_key(ids[0]), no_address.write_to_bytes().unwrap()).unwrap();
+        // a hand-edited record with an invalid xpub
+        let mut bad_xpub = store.get(ids[1]).unwrap().unwrap().data;
+        bad_xpub.mut_address().set_field_type(Address_AddressType::XPUB);
+        store.db.insert(AddressBookAccess::get_key(ids[1]), bad_xpub.write_to_bytes().unwrap()).unwrap();
+
+        let corrupted = store.verify_all().unwrap();
+        assert_eq!(corrupted.len(), 2);
+        let corrupted_ids: HashSet<Uuid> = corrupted.iter().map(|c| c.id).collect();
+        assert_eq!(corrupted_ids, HashSet::from([ids[0], ids[1]]));
+        assert!(corrupted.iter().all(|c| c.error == StateError::CorruptedValue));
+
+        assert_eq!(store.get(ids[0]).err(), Some(StateError::CorruptedValue));
+        assert_eq!(store.get(ids[1]).err(), Some(StateError::CorruptedValue));
+
+        let results = store.query(Filter::default(), PageQuery::default()).expect("queried");
+        assert_eq!(results.values.len(), 1);
+        assert_eq!(results.values[0].data.get_id(), ids[2].to_string());
+    }
+}
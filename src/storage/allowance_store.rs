@@ -1,25 +1,36 @@
-use std::str::FromStr;
 use std::sync::Arc;
 use chrono::Utc;
+use log::warn;
 use protobuf::Message;
-use sled::{Batch, Db};
+use sled::Db;
 use uuid::Uuid;
-use crate::access::allowance::Allowances;
+use crate::access::allowance::{AllowanceKey, Allowances};
 use crate::access::pagination::PageResult;
+use crate::access::permissions::{Permissions, Store};
 use crate::errors::{InvalidValueError, StateError};
 use crate::proto::balance::{Allowance};
 use crate::{validate};
+use crate::storage::ttl_policy::TtlPolicy;
+use crate::storage::rate_limit::RateLimiter;
+use crate::storage::health::Health;
+use crate::storage::sequence::{self, Batch};
 
 const PREFIX_KEY: &'static str = "allowance:";
-const DEFAULT_TTL: u64 = 24 * 60 * 60 * 1000;
-const MAX_TTL: u64 = 30 * DEFAULT_TTL;
 
 pub struct AllowanceAccess {
     pub(crate) db: Arc<Db>,
+    pub(crate) ttl: TtlPolicy,
+    pub(crate) permissions: Permissions,
+    pub(crate) limiter: Arc<RateLimiter>,
+    pub(crate) health: Arc<Health>,
 }
 
 impl AllowanceAccess {
 
+    fn get_key(key: &AllowanceKey) -> String {
+        format!("{}_{}_{}_{}_{}_{}", PREFIX_KEY, key.wallet_id, key.blockchain, key.token, key.owner, key.spender)
+    }
+
     fn purge(&self) -> Result<usize, StateError> {
         let mut count = 0;
         let mut iter = self.db.scan_prefix(PREFIX_KEY);
@@ -39,38 +50,70 @@ impl AllowanceAccess {
             }
         }
         if count > 0 {
-            let _ = self.db.apply_batch(batch);
+            let _ = self.health.apply_batch(&self.db, batch);
+        }
+        Ok(count)
+    }
+
+    ///
+    /// Put the removal of all allowances of the wallet into the batch. Returns the number of removed allowances.
+    pub(crate) fn wipe_wallet(&self, wallet_id: Uuid, batch: &mut Batch) -> Result<usize, StateError> {
+        let mut count = 0;
+        for key in self.db.scan_prefix(format!("{}_{}_", PREFIX_KEY, wallet_id)).keys() {
+            batch.remove(key?);
+            count += 1;
         }
         Ok(count)
     }
 
 }
 
+///
+/// Allowances revoked by a transaction, as pairs of the storage key and the allowance. Used to keep the references to
+/// the transactions consistent.
+pub(crate) fn find_revoked(db: &Db) -> Result<Vec<(String, Allowance)>, StateError> {
+    let mut result = Vec::new();
+    for row in db.scan_prefix(PREFIX_KEY) {
+        let (key, value) = row?;
+        if let Ok(allowance) = Allowance::parse_from_bytes(value.as_ref()) {
+            if !allowance.revoked_by_tx.is_empty() {
+                result.push((String::from_utf8(key.to_vec()).map_err(|_| StateError::CorruptedValue)?, allowance));
+            }
+        }
+    }
+    Ok(result)
+}
+
 impl Allowances for AllowanceAccess {
     fn add(&self, allowance: Allowance, ttl: Option<u64>) -> Result<(), StateError> {
+        self.permissions.check_write(Store::Allowances)?;
+        self.limiter.check(Store::Allowances)?;
         validate::check_ethereum_address(&allowance.token)
             .map_err(|_| InvalidValueError::Name("token".to_string()))?;
         validate::check_ethereum_address(&allowance.owner)
             .map_err(|_| InvalidValueError::Name("owner".to_string()))?;
         validate::check_ethereum_address(&allowance.spender)
             .map_err(|_| InvalidValueError::Name("spender".to_string()))?;
-        let _ = Uuid::from_str(&allowance.wallet_id)
-            .map_err(|_| InvalidValueError::Name("wallet_id".to_string()))?;
+        let key = AllowanceKey::try_from(&allowance)?;
 
         let mut allowance = allowance.clone();
         allowance.ts = Utc::now().naive_utc().timestamp_millis() as u64;
-        allowance.ttl = allowance.ts + ttl.or(Some(DEFAULT_TTL))
-            .map(|v| if v > MAX_TTL { MAX_TTL } else { v })
+        let max_ttl = self.ttl.allowance_max.as_millis() as u64;
+        allowance.ttl = allowance.ts + ttl.or(Some(self.ttl.allowance_default.as_millis() as u64))
+            .map(|v| if v > max_ttl { max_ttl } else { v })
             .unwrap();
 
-        let key = format!("{}_{}_{}_{}_{}_{}", PREFIX_KEY, allowance.wallet_id, allowance.blockchain, allowance.token, allowance.owner, allowance.spender);
+        let key = AllowanceAccess::get_key(&key);
 
-        self.db.insert(key.as_bytes(), allowance.write_to_bytes()?.as_slice())?;
+        let mut batch = Batch::default();
+        batch.insert(key.as_bytes(), allowance.write_to_bytes()?.as_slice());
+        self.health.apply(&self.db, batch)?;
 
         Ok(())
     }
 
     fn list(&self, wallet_id: Option<Uuid>) -> Result<PageResult<Allowance>, StateError> {
+        self.permissions.check_read(Store::Allowances)?;
         let prefix = match wallet_id {
             None => PREFIX_KEY.to_string(),
             Some(wallet) => format!("{}_{}_", PREFIX_KEY, wallet.to_string())
@@ -78,14 +121,25 @@ impl Allowances for AllowanceAccess {
         let mut iter = self.db.scan_prefix(prefix);
         let mut result = vec![];
         let mut outdated = 0;
+        let mut skipped = 0;
         while let Some(entry) = iter.next() {
-            if let Ok(next) = entry {
-                if let Ok(allowance) = Allowance::parse_from_bytes(next.1.as_ref()) {
-                    if allowance.ttl < Utc::now().naive_utc().timestamp_millis() as u64 {
-                        outdated += 1;
-                        continue;
+            match entry {
+                Ok(next) => match Allowance::parse_from_bytes(next.1.as_ref()) {
+                    Ok(allowance) => {
+                        if allowance.ttl < Utc::now().timestamp_millis() as u64 {
+                            outdated += 1;
+                            continue;
+                        }
+                        result.push(allowance);
                     }
-                    result.push(allowance);
+                    Err(e) => {
+                        warn!("Skip unreadable allowance {}: {:?}", String::from_utf8_lossy(next.0.as_ref()), e);
+                        skipped += 1;
+                    }
+                },
+                Err(e) => {
+                    warn!("Skip unreadable allowance entry: {:?}", e);
+                    skipped += 1;
                 }
             }
         }
@@ -96,11 +150,14 @@ impl Allowances for AllowanceAccess {
 
         Ok(PageResult {
             values: result,
-            cursor: None
+            cursor: None,
+            skipped,
         })
     }
 
     fn remove(&self, wallet_id: Uuid, blockchain: Option<u32>, min_ts: Option<u64>) -> Result<usize, StateError> {
+        self.permissions.check_write(Store::Allowances)?;
+        self.limiter.check(Store::Allowances)?;
         let prefix = format!("{}_{}_", PREFIX_KEY, wallet_id.to_string());
 
         let mut iter = self.db.scan_prefix(prefix);
@@ -126,11 +183,33 @@ impl Allowances for AllowanceAccess {
         }
 
         if count > 0 {
-            let _ = self.db.apply_batch(batch);
+            let _ = self.health.apply(&self.db, batch);
         }
         Ok(count)
     }
 
+    fn mark_revoked(&self, key: AllowanceKey, tx_id: String) -> Result<Option<Allowance>, StateError> {
+        self.permissions.check_write(Store::Allowances)?;
+        self.limiter.check(Store::Allowances)?;
+        if tx_id.is_empty() {
+            return Err(StateError::InvalidValue(InvalidValueError::Name("tx_id".to_string())))
+        }
+        let key = AllowanceAccess::get_key(&key);
+        let updated = self.health.transaction(&self.db, |tx| {
+            let current = match tx.get(key.as_bytes())? {
+                Some(current) => current,
+                None => return Ok(None)
+            };
+            // an unreadable value is kept as is
+            let mut allowance = Allowance::parse_from_bytes(current.as_ref())
+                .map_err(|_| sequence::abort(StateError::CorruptedValue))?;
+            allowance.revoked_by_tx = tx_id.clone();
+            tx.insert(key.as_bytes(), allowance.write_to_bytes().map_err(sequence::abort)?)?;
+            Ok(Some(allowance))
+        })?;
+        Ok(updated.map(|(allowance, _)| allowance))
+    }
+
 }
 
 #[cfg(test)]
@@ -141,8 +220,10 @@ mod tests {
     use chrono::Utc;
     use tempdir::TempDir;
     use uuid::Uuid;
-    use crate::access::allowance::Allowances;
+    use crate::access::allowance::{AllowanceKey, Allowances};
+    use crate::errors::StateError;
     use crate::proto::balance::Allowance;
+    use crate::storage::allowance_store::AllowanceAccess;
     use crate::storage::sled_access::SledStorage;
 
     #[test]
@@ -177,6 +258,63 @@ mod tests {
         assert_eq!(all_by_wallet.unwrap().values.len(), 1);
     }
 
+    #[test]
+    fn mark_revoked() {
+        let tmp_dir = TempDir::new("test-allowance").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let store = access.get_allowance();
+
+        let mut item = Allowance::new();
+        item.wallet_id = "5e0e8fb5-9ffb-4b18-b79a-b732d19576f3".to_string();
+        item.blockchain = 100;
+        item.token = "0xdAC17F958D2ee523a2206206994597C13D831ec7".to_string();
+        item.owner = "0x9696f59E4d72E237BE84fFD425DCaD154Bf96976".to_string();
+        item.spender = "0x65A0947BA5175359Bb457D3b34491eDf4cBF7997".to_string();
+        item.amount = "10000000".to_string();
+        item.last_checked_block = 14_380_000;
+        item.token_symbol = "USDT".to_string();
+        item.token_decimals = 6;
+        store.add(item.clone(), None).unwrap();
+
+        let key = AllowanceKey::try_from(&item).unwrap();
+        let act = store.mark_revoked(key.clone(), "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string()).unwrap();
+        assert!(act.is_some());
+
+        let all = store.list(None).unwrap();
+        assert_eq!(all.values.len(), 1);
+        assert_eq!(all.values[0].revoked_by_tx, "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b");
+        assert_eq!(all.values[0].amount, "10000000");
+        assert_eq!(all.values[0].last_checked_block, 14_380_000);
+        assert_eq!(all.values[0].token_symbol, "USDT");
+        assert_eq!(all.values[0].token_decimals, 6);
+
+        let other = AllowanceKey { blockchain: 101, ..key };
+        let act = store.mark_revoked(other, "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string()).unwrap();
+        assert!(act.is_none());
+        assert_eq!(store.list(None).unwrap().values.len(), 1);
+    }
+
+    #[test]
+    fn keep_corrupted_when_mark_revoked() {
+        let tmp_dir = TempDir::new("test-allowance").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let store = access.get_allowance();
+
+        let key = AllowanceKey {
+            wallet_id: Uuid::from_str("5e0e8fb5-9ffb-4b18-b79a-b732d19576f3").unwrap(),
+            blockchain: 100,
+            token: "0xdac17f958d2ee523a2206206994597c13d831ec7".to_string(),
+            owner: "0x9696f59e4d72e237be84ffd425dcad154bf96976".to_string(),
+            spender: "0x65a0947ba5175359bb457d3b34491edf4cbf7997".to_string(),
+        };
+        let db_key = AllowanceAccess::get_key(&key);
+        access.db.insert(db_key.as_bytes(), vec![0xff, 0xff, 0xff]).unwrap();
+
+        let act = store.mark_revoked(key, "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string());
+        assert_eq!(act, Err(StateError::CorruptedValue));
+        assert_eq!(access.db.get(db_key.as_bytes()).unwrap().unwrap().to_vec(), vec![0xff, 0xff, 0xff]);
+    }
+
     #[test]
     fn add_and_list_by_wallet() {
         let tmp_dir = TempDir::new("test-allowance").unwrap();
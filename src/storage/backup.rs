@@ -0,0 +1,227 @@
+//!
+//! Backup of the whole storage into a file, either full or incremental.
+//!
+//! A backup file is a `BackupHeader` followed by a list of `BackupEntry`, each of them length-delimited. Together with
+//! the file the backup produces a `BackupManifest` with the sequence number of the last write included in it, which
+//! must be kept by the caller to make an incremental backup later. An incremental backup contains only the keys changed
+//! or removed since the base backup, as found in the log of changes, and can be restored only on top of it.
+//!
+//! The writes to the storage are paused while a backup is made, so it's a consistent state of the storage at the
+//! moment of the sequence number in the manifest.
+//!
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use chrono::Utc;
+use protobuf::{CodedInputStream, Message};
+use sled::{Batch, Db, IVec};
+use uuid::Uuid;
+use crate::errors::{InvalidValueError, StateError};
+use crate::proto::backup::{BackupEntry, BackupHeader, BackupManifest};
+use crate::storage::health::Health;
+use crate::storage::sequence;
+
+/// Number of entries written to the DB at once on restore
+const RESTORE_BATCH: usize = 10_000;
+
+fn write_entry<W: Write>(out: &mut W, key: &[u8], value: Option<IVec>) -> Result<(), StateError> {
+    let mut entry = BackupEntry::new();
+    entry.key = key.to_vec();
+    match value {
+        Some(value) => entry.value = value.to_vec(),
+        None => entry.removed = true,
+    }
+    entry.write_length_delimited_to_writer(out)?;
+    Ok(())
+}
+
+///
+/// Write a backup of the DB to the file at `path`. If the `base` manifest is provided it's an incremental backup
+/// with only the changes since that base, otherwise it's a full backup and the log of changes before it is removed, so
+/// the next increments can be based only on it or the increments after it.
+/// Returns the manifest of the DB state at the moment of the backup.
+pub(crate) fn write_backup(db: &Db, health: &Health, base: Option<&BackupManifest>, path: &Path) -> Result<BackupManifest, StateError> {
+    health.exclusive(|seq| {
+        let changes = match base {
+            Some(base) => match sequence::get_changes(db, base.seq)? {
+                Some(changes) => Some(changes),
+                None => return Err(StateError::InvalidValue(InvalidValueError::NameMessage(
+                    "base_id".to_string(),
+                    format!("Changes since backup {} are not known, it's older than the last full backup", base.id)
+                ))),
+            },
+            None => None,
+        };
+
+        let mut header = BackupHeader::new();
+        header.id = Uuid::new_v4().to_string();
+        header.base_id = base.map(|b| b.id.clone()).unwrap_or_default();
+        header.timestamp = Utc::now().timestamp_millis() as u64;
+
+        let mut out = BufWriter::new(File::create(path)?);
+        header.write_length_delimited_to_writer(&mut out)?;
+        match changes {
+            Some(keys) => {
+                for key in keys {
+                    write_entry(&mut out, key.as_ref(), db.get(&key)?)?;
+                }
+            }
+            None => {
+                for row in db.iter() {
+                    let (key, value) = row?;
+                    if !sequence::is_changes_log(key.as_ref()) {
+                        write_entry(&mut out, key.as_ref(), Some(value))?;
+                    }
+                }
+            }
+        }
+        out.flush()?;
+        if base.is_none() {
+            sequence::truncate_changes(db, seq)?;
+        }
+
+        let mut manifest = BackupManifest::new();
+        manifest.id = header.id;
+        manifest.base_id = header.base_id;
+        manifest.timestamp = header.timestamp;
+        manifest.seq = seq;
+        Ok(manifest)
+    })
+}
+
+///
+/// Restore the backups into the DB. The first one must be a full backup, and each next must be an increment over
+/// the previous one.
+pub(crate) fn restore(db: &Db, backups: &[PathBuf]) -> Result<(), StateError> {
+    let mut previous: Option<String> = None;
+    for path in backups {
+        let mut reader = BufReader::new(File::open(path)?);
+        let mut input = CodedInputStream::from_buffered_reader(&mut reader);
+        let header: BackupHeader = input.read_message()?;
+        let expected_base = previous.clone().unwrap_or_default();
+        if header.base_id != expected_base {
+            return Err(StateError::InvalidValue(InvalidValueError::NameMessage(
+                "base_id".to_string(),
+                format!("Backup {} is based on {}, but {} is expected", header.id, header.base_id, expected_base)
+            )))
+        }
+
+        let mut batch = Batch::default();
+        let mut size = 0;
+        while !input.eof()? {
+            let entry: BackupEntry = input.read_message()?;
+            if entry.removed {
+                batch.remove(entry.key);
+            } else {
+                batch.insert(entry.key, entry.value);
+            }
+            size += 1;
+            if size >= RESTORE_BATCH {
+                db.apply_batch(std::mem::take(&mut batch))?;
+                size = 0;
+            }
+        }
+        db.apply_batch(batch)?;
+        previous = Some(header.id);
+    }
+    db.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use tempdir::TempDir;
+    use crate::access::cache::Cache;
+    use crate::errors::StateError;
+    use crate::storage::sled_access::SledStorage;
+
+    #[test]
+    fn restore_full_and_incremental() {
+        let tmp_dir = TempDir::new("backup").unwrap();
+        let access = SledStorage::open(tmp_dir.path().join("db")).unwrap();
+        let mut cache = access.get_cache();
+        cache.put("test-1".to_string(), "Test 1".to_string(), None).unwrap();
+        cache.put("test-2".to_string(), "Test 2".to_string(), None).unwrap();
+        for i in 0..100 {
+            cache.put(format!("other-{}", i), "Other".to_string(), None).unwrap();
+        }
+
+        let full_path = tmp_dir.path().join("full.bak");
+        let full = access.backup(&full_path).unwrap();
+        assert!(full.base_id.is_empty());
+        assert_eq!(full.seq, access.get_sequence().unwrap());
+
+        cache.put("test-2".to_string(), "Test 2 updated".to_string(), None).unwrap();
+        cache.put("test-3".to_string(), "Test 3".to_string(), None).unwrap();
+        cache.evict("test-1".to_string()).unwrap();
+
+        let incremental_path = tmp_dir.path().join("incremental-1.bak");
+        let incremental = access.backup_incremental(&full, &incremental_path).unwrap();
+        assert_eq!(incremental.base_id, full.id);
+        // only the changes are written
+        assert!(incremental_path.metadata().unwrap().len() < full_path.metadata().unwrap().len() / 4);
+
+        let restored = SledStorage::restore(tmp_dir.path().join("restored"), vec![full_path.clone(), incremental_path.clone()]).unwrap();
+        let restored_cache = restored.get_cache();
+        assert_eq!(restored_cache.get("test-1".to_string()).unwrap(), None);
+        assert_eq!(restored_cache.get("test-2".to_string()).unwrap(), Some("Test 2 updated".to_string()));
+        assert_eq!(restored_cache.get("test-3".to_string()).unwrap(), Some("Test 3".to_string()));
+        assert_eq!(restored_cache.get("other-10".to_string()).unwrap(), Some("Other".to_string()));
+
+        let restored = SledStorage::restore(tmp_dir.path().join("restored-full"), vec![full_path.clone()]).unwrap();
+        assert_eq!(restored.get_cache().get("test-1".to_string()).unwrap(), Some("Test 1".to_string()));
+    }
+
+    #[test]
+    fn reject_increment_without_base() {
+        let tmp_dir = TempDir::new("backup").unwrap();
+        let access = SledStorage::open(tmp_dir.path().join("db")).unwrap();
+        let mut cache = access.get_cache();
+        cache.put("test-1".to_string(), "Test 1".to_string(), None).unwrap();
+
+        let full_path = tmp_dir.path().join("full.bak");
+        let full = access.backup(&full_path).unwrap();
+        let incremental_path = tmp_dir.path().join("incremental-1.bak");
+        access.backup_incremental(&full, &incremental_path).unwrap();
+
+        let act = SledStorage::restore(tmp_dir.path().join("restored"), vec![incremental_path]);
+        assert!(matches!(act, Err(StateError::InvalidValue(_))));
+    }
+
+    #[test]
+    fn reject_increment_before_last_full() {
+        let tmp_dir = TempDir::new("backup").unwrap();
+        let access = SledStorage::open(tmp_dir.path().join("db")).unwrap();
+        let mut cache = access.get_cache();
+        cache.put("test-1".to_string(), "Test 1".to_string(), None).unwrap();
+        let first = access.backup(&tmp_dir.path().join("full-1.bak")).unwrap();
+        cache.put("test-2".to_string(), "Test 2".to_string(), None).unwrap();
+        let second = access.backup(&tmp_dir.path().join("full-2.bak")).unwrap();
+        cache.put("test-3".to_string(), "Test 3".to_string(), None).unwrap();
+
+        let act = access.backup_incremental(&first, &tmp_dir.path().join("incremental-1.bak"));
+        assert!(matches!(act, Err(StateError::InvalidValue(_))));
+        let act = access.backup_incremental(&second, &tmp_dir.path().join("incremental-2.bak"));
+        assert!(act.is_ok());
+    }
+
+    #[test]
+    fn increment_after_restore() {
+        let tmp_dir = TempDir::new("backup").unwrap();
+        let access = SledStorage::open(tmp_dir.path().join("db")).unwrap();
+        access.get_cache().put("test-1".to_string(), "Test 1".to_string(), None).unwrap();
+        let full_path = tmp_dir.path().join("full.bak");
+        let full = access.backup(&full_path).unwrap();
+
+        let restored = SledStorage::restore(tmp_dir.path().join("restored"), vec![full_path.clone()]).unwrap();
+        assert_eq!(restored.get_sequence().unwrap(), full.seq);
+        let mut cache = restored.get_cache();
+        cache.put("test-2".to_string(), "Test 2".to_string(), None).unwrap();
+        let incremental_path = tmp_dir.path().join("incremental.bak");
+        restored.backup_incremental(&full, &incremental_path).unwrap();
+
+        let restored_again = SledStorage::restore(tmp_dir.path().join("restored-again"), vec![full_path, incremental_path]).unwrap();
+        assert_eq!(restored_again.get_cache().get("test-2".to_string()).unwrap(), Some("Test 2".to_string()));
+        assert_eq!(restored_again.get_sequence().unwrap(), restored.get_sequence().unwrap());
+    }
+}
@@ -1,16 +1,48 @@
+use std::collections::{BTreeMap, HashMap};
+use std::convert::TryFrom;
+use std::ops::Bound;
+use std::str::FromStr;
 use std::sync::Arc;
+use std::sync::mpsc::Receiver;
+use bitcoin::Address;
+use chrono::{Duration, TimeZone, Utc};
+use emerald_vault::blockchain::bitcoin::XPub;
+use log::warn;
 use protobuf::Message;
-use sled::{Db, IVec};
-use crate::access::balance::{Balance, Balances, concat};
-use crate::errors::{StateError};
-use crate::proto::balance::{BalanceBundle as proto_BalanceBundle};
+use sled::Db;
+use crate::access::asset::AssetId;
+use crate::access::balance::{Balance, BalanceChange, Balances, BalancesChange, UtxoReport, XPubBalance, concat};
+use crate::access::pagination::{Cursor, PageQuery, PageResult};
+use crate::access::transactions::Staleness;
+use crate::access::permissions::{Permissions, Store};
+use crate::access::xpubpos::XPubPosition;
+use crate::errors::{InvalidValueError, StateError};
+use crate::proto::balance::{Balance as proto_Balance, BalanceBundle as proto_BalanceBundle};
+use crate::proto::transactions::{State, Transaction as proto_Transaction};
 use crate::{validate};
+use crate::storage::health::Health;
+use crate::storage::indexing::Indexing;
+use crate::storage::notification::Notifier;
 use crate::storage::version::Migration;
+use crate::storage::rate_limit::RateLimiter;
+use crate::storage::sequence::{self, Batch};
+use crate::storage::transaction_store;
+use crate::storage::ttl_policy::TtlPolicy;
+use crate::storage::xpubpos_store::XPubPositionAccess;
 
 const PREFIX_KEY: &'static str = "balance:";
+// the next char after the `:` of the prefix, i.e. the end of the balances range
+const END_KEY: &str = "balance;";
+const PREFIX_RESERVED: &str = "utxo_reserved:";
 
 pub struct BalanceAccess {
     pub(crate) db: Arc<Db>,
+    pub(crate) permissions: Permissions,
+    pub(crate) limiter: Arc<RateLimiter>,
+    pub(crate) health: Arc<Health>,
+    pub(crate) xpub: Arc<XPubPositionAccess>,
+    pub(crate) ttl: TtlPolicy,
+    pub(crate) notifier: Arc<Notifier<BalancesChange>>,
 }
 
 impl BalanceAccess {
@@ -18,12 +50,84 @@ impl BalanceAccess {
         format!("{}{}", PREFIX_KEY, addr.to_string())
     }
 
-    fn convert_stored(base: IVec) -> Vec<Balance> {
-        match proto_BalanceBundle::parse_from_bytes(base.as_ref()) {
+    ///
+    /// Read the stored balances so they get into the cache. Returns the number of read addresses.
+    pub(crate) fn warm(&self, limit: usize) -> Result<usize, StateError> {
+        let mut count = 0;
+        for row in self.db.scan_prefix(PREFIX_KEY).take(limit) {
+            let _ = row?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    ///
+    /// Put the removal of the balances of the addresses into the batch. Returns the number of addresses with a balance.
+    pub(crate) fn wipe_addresses(&self, addresses: &[String], batch: &mut Batch) -> Result<usize, StateError> {
+        let mut count = 0;
+        for address in addresses {
+            let key = BalanceAccess::get_key(address);
+            if self.health.read(&self.db, key.as_str())?.is_some() {
+                batch.remove(key.as_bytes());
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    fn get_reserved_key(txid: &str, vout: u32) -> String {
+        format!("{}{}:{}", PREFIX_RESERVED, txid.to_lowercase(), vout)
+    }
+
+    ///
+    /// Mark the outputs reserved for a pending transaction
+    fn with_reservations(&self, mut balances: Vec<Balance>) -> Result<Vec<Balance>, StateError> {
+        for balance in balances.iter_mut() {
+            for utxo in balance.utxo.iter_mut() {
+                utxo.reserved_by = self.db.get(BalanceAccess::get_reserved_key(utxo.txid.as_str(), utxo.vout))?
+                    .map(|tx_id| String::from_utf8_lossy(tx_id.as_ref()).to_string());
+            }
+        }
+        Ok(balances)
+    }
+
+    fn convert_stored(base: Vec<u8>) -> Vec<Balance> {
+        match proto_BalanceBundle::parse_from_bytes(base.as_slice()) {
             Ok(parsed) => parsed.into(),
             Err(_) => vec![]
         }
     }
+
+    ///
+    /// Same as `convert_stored`, but also returns the stored balances of an asset unknown to `AssetId` (ex. a legacy
+    /// code of a token), which cannot be converted but must be kept when the bundle is written back
+    fn convert_for_update(base: Vec<u8>) -> (Vec<Balance>, Vec<proto_Balance>) {
+        let mut converted = Vec::new();
+        let mut unknown = Vec::new();
+        if let Ok(parsed) = proto_BalanceBundle::parse_from_bytes(base.as_slice()) {
+            for b in parsed.balances.into_iter() {
+                if AssetId::from_legacy(b.blockchain, b.asset.as_str()).is_err() {
+                    unknown.push(b);
+                } else if let Ok(balance) = Balance::try_from(&b) {
+                    converted.push(balance);
+                }
+            }
+        }
+        (converted, unknown)
+    }
+
+    ///
+    /// Same as `convert_stored`, but it converts only the balances on the `blockchain` and of the `asset`, if specified
+    fn convert_filtered(base: Vec<u8>, blockchain: Option<u32>, asset: Option<&AssetId>) -> Vec<Balance> {
+        match proto_BalanceBundle::parse_from_bytes(base.as_slice()) {
+            Ok(parsed) => parsed.balances.iter()
+                .filter(|b| blockchain.is_none_or(|blockchain| b.blockchain == blockchain))
+                .filter_map(|b| Balance::try_from(b).ok())
+                .filter(|b| asset.is_none_or(|asset| &b.asset == asset))
+                .collect(),
+            Err(_) => vec![]
+        }
+    }
 }
 
 impl Migration for BalanceAccess {
@@ -42,52 +146,276 @@ impl Migration for BalanceAccess {
     }
 }
 
+///
+/// Put the release of the outputs reserved for the inputs of the transaction into the batch, once it's not pending
+/// anymore. A confirmed transaction has spent them, so any reservation is released, and a dropped or replaced one
+/// releases only those reserved for itself. Called by the transactions store, where the permissions are checked by
+/// the caller. Returns the number of released outputs.
+pub(crate) fn release_spent(db: &Db, tx: &proto_Transaction, batch: &mut Batch) -> Result<usize, StateError> {
+    let any = match tx.state {
+        State::CONFIRMED => true,
+        State::DROPPED | State::REPLACED => false,
+        _ => return Ok(0)
+    };
+    let mut count = 0;
+    for (txid, vout) in tx.get_spent_outpoints() {
+        let key = BalanceAccess::get_reserved_key(txid.as_str(), vout);
+        if let Some(current) = db.get(key.as_str())? {
+            if any || String::from_utf8_lossy(current.as_ref()).eq_ignore_ascii_case(tx.tx_id.as_str()) {
+                batch.remove(key.as_bytes());
+                count += 1;
+            }
+        }
+    }
+    Ok(count)
+}
+
 impl Balances for BalanceAccess {
 
     fn set(&self, value: Balance) -> Result<(), StateError> {
+        self.permissions.check_write(Store::Balances)?;
+        self.limiter.check(Store::Balances)?;
         validate::check_address(&value.address)?;
 
         let key = BalanceAccess::get_key(&value.address);
-        let value = if let Some(base) = self.db.get(&key)? {
-            let base: Vec<Balance> = BalanceAccess::convert_stored(base);
-            concat(base, value)
-        } else {
-            vec![value]
-        };
-        let value: proto_BalanceBundle = value.into();
+        let (base, unknown) = self.health.read(&self.db, key.as_str())?
+            .map(BalanceAccess::convert_for_update)
+            .unwrap_or_default();
+        let change = BalanceChange::of(&base, &value);
+        let mut value: proto_BalanceBundle = concat(base, value).into();
+        value.balances.extend(unknown);
         let bytes = value.write_to_bytes()?;
-        self.db.insert(key.as_bytes(), bytes)?;
+        let seq = self.health.write(&self.db, key.as_str(), Some(bytes))?;
+        self.notifier.notify(BalancesChange { changes: change.into_iter().collect(), seq });
+        Ok(())
+    }
+
+    fn set_many(&self, values: Vec<Balance>) -> Result<(), StateError> {
+        self.permissions.check_write(Store::Balances)?;
+        self.limiter.check(Store::Balances)?;
+        for value in values.iter() {
+            validate::check_address(&value.address)?;
+        }
 
+        // merge all values of the same address in memory, so each address is read and written only once
+        let mut merged: HashMap<String, (Vec<Balance>, Vec<proto_Balance>)> = HashMap::new();
+        let mut changes = Vec::new();
+        for value in values {
+            let key = BalanceAccess::get_key(&value.address);
+            let (base, unknown) = match merged.remove(&key) {
+                Some(base) => base,
+                None => self.health.read(&self.db, key.as_str())?
+                    .map(BalanceAccess::convert_for_update)
+                    .unwrap_or_default(),
+            };
+            changes.extend(BalanceChange::of(&base, &value));
+            merged.insert(key, (concat(base, value), unknown));
+        }
+        let mut writes = BTreeMap::new();
+        for (key, (value, unknown)) in merged {
+            let mut value: proto_BalanceBundle = value.into();
+            value.balances.extend(unknown);
+            writes.insert(key, Some(value.write_to_bytes()?));
+        }
+        let seq = self.health.write_all(&self.db, writes)?;
+        self.notifier.notify(BalancesChange { changes, seq });
         Ok(())
     }
 
     fn list(&self, address: String) -> Result<Vec<Balance>, StateError> {
+        self.list_filtered(address, None, None)
+    }
+
+    fn list_filtered(&self, address: String, blockchain: Option<u32>, asset: Option<AssetId>) -> Result<Vec<Balance>, StateError> {
+        self.permissions.check_read(Store::Balances)?;
         validate::check_address(&address)?;
 
         let key = BalanceAccess::get_key(&address);
-        let value = if let Some(base) = self.db.get(&key)? {
-            BalanceAccess::convert_stored(base)
+        let value = if let Some(base) = self.health.read(&self.db, key.as_str())? {
+            BalanceAccess::convert_filtered(base, blockchain, asset.as_ref())
         } else {
             vec![]
         };
-        Ok(value)
+        self.with_reservations(value)
+    }
+
+    fn get(&self, address: String, blockchain: u32, asset: AssetId) -> Result<Option<Balance>, StateError> {
+        let value = self.list_filtered(address, Some(blockchain), Some(asset))?;
+        Ok(value.into_iter().next())
+    }
+
+    fn list_with_age(&self, address: String) -> Result<Vec<(Balance, Duration, Staleness)>, StateError> {
+        let now = Utc::now();
+        let stale_after = Duration::from_std(self.ttl.balance_stale_after).unwrap_or(Duration::MAX);
+        let result = self.list(address)?.into_iter()
+            .map(|b| {
+                let age = b.age(now);
+                let staleness = if age > stale_after { Staleness::Stale } else { Staleness::Fresh };
+                (b, age, staleness)
+            })
+            .collect();
+        Ok(result)
+    }
+
+    fn list_stale(&self, max_age: Duration) -> Result<Vec<String>, StateError> {
+        self.permissions.check_read(Store::Balances)?;
+        let now = Utc::now();
+        let mut stale = Vec::new();
+        for (key, value) in self.health.scan_prefix(&self.db, PREFIX_KEY)? {
+            let address = key[PREFIX_KEY.len()..].to_string();
+            let oldest = BalanceAccess::convert_stored(value).into_iter()
+                .map(|b| b.age(now))
+                .max();
+            if let Some(age) = oldest.filter(|age| *age > max_age) {
+                stale.push((address, age));
+            }
+        }
+        stale.sort_by_key(|(_, age)| std::cmp::Reverse(*age));
+        Ok(stale.into_iter().map(|(address, _)| address).collect())
+    }
+
+    fn list_all(&self, page: PageQuery) -> Result<PageResult<Balance>, StateError> {
+        self.permissions.check_read(Store::Balances)?;
+        let bounds = (Bound::Included(PREFIX_KEY.to_string()), Bound::Excluded(END_KEY.to_string()));
+        let mut values = Vec::new();
+        let mut cursor_key = None;
+        let mut skipped = 0;
+        let mut reached_end = true;
+        for row in Indexing::scan_page(&self.db, PREFIX_KEY, bounds, &page)? {
+            if values.len() >= page.limit {
+                reached_end = false;
+                break
+            }
+            let key = match row.map_err(StateError::from).and_then(|(key, _)| String::from_utf8(key.to_vec()).map_err(|_| StateError::CorruptedValue)) {
+                Ok(key) => key,
+                Err(e) => {
+                    warn!("Skip unreadable balance: {:?}", e);
+                    skipped += 1;
+                    continue
+                }
+            };
+            // prefer the value queued in the degraded mode
+            if let Some(value) = self.health.read(&self.db, key.as_str())? {
+                values.extend(self.with_reservations(BalanceAccess::convert_stored(value))?);
+            }
+            cursor_key = Some(key);
+        }
+        Ok(PageResult {
+            values,
+            cursor: if reached_end { None } else { cursor_key.map(|key| Cursor::encode(PREFIX_KEY, page.order, key)) },
+            skipped,
+        })
+    }
+
+    fn reserve_utxo(&self, tx_id: String, outputs: Vec<(String, u32)>) -> Result<(), StateError> {
+        self.permissions.check_write(Store::Balances)?;
+        self.limiter.check(Store::Balances)?;
+        if tx_id.is_empty() {
+            return Err(StateError::InvalidValue(InvalidValueError::Name("tx_id".to_string())))
+        }
+        // all outputs are taken in one transaction, so two concurrent calls never get the same output, and it's either
+        // all or nothing
+        self.health.transaction(&self.db, |tx| {
+            let mut changed = false;
+            for (txid, vout) in outputs.iter() {
+                let key = BalanceAccess::get_reserved_key(txid.as_str(), *vout);
+                match tx.get(key.as_bytes())? {
+                    Some(current) if current.as_ref() == tx_id.as_bytes() => {},
+                    Some(current) => {
+                        let current = String::from_utf8_lossy(current.as_ref()).to_string();
+                        return Err(sequence::abort(InvalidValueError::NameMessage("utxo".to_string(), format!("{}:{} is reserved for {}", txid, vout, current))))
+                    },
+                    None => {
+                        tx.insert(key.as_bytes(), tx_id.as_bytes())?;
+                        changed = true;
+                    }
+                }
+            }
+            Ok(if changed { Some(()) } else { None })
+        })?;
+        Ok(())
+    }
+
+    fn release_utxo(&self, tx_id: String) -> Result<usize, StateError> {
+        self.permissions.check_write(Store::Balances)?;
+        self.limiter.check(Store::Balances)?;
+        let mut batch = Batch::default();
+        let mut count = 0;
+        for row in self.db.scan_prefix(PREFIX_RESERVED) {
+            let (key, value) = row?;
+            if value.as_ref() == tx_id.as_bytes() {
+                batch.remove(key);
+                count += 1;
+            }
+        }
+        if count > 0 {
+            self.health.apply(&self.db, batch)?;
+        }
+        Ok(count)
     }
 
     fn clear(&self, address: String) -> Result<(), StateError> {
+        self.permissions.check_write(Store::Balances)?;
+        self.limiter.check(Store::Balances)?;
         validate::check_address(&address)?;
 
         let key = BalanceAccess::get_key(&address);
-        self.db.remove(key.as_bytes())?;
+        self.health.write(&self.db, key.as_str(), None)?;
         Ok(())
     }
+
+    fn utxo_report(&self, address: String, dust_threshold: u64) -> Result<UtxoReport, StateError> {
+        let mut utxo = Vec::new();
+        for balance in self.list(address)? {
+            for u in balance.utxo {
+                let ts = transaction_store::find_tx(&self.db, &self.permissions, balance.blockchain, u.txid.as_str())
+                    .map(|tx| if tx.confirm_timestamp > 0 { tx.confirm_timestamp } else { tx.since_timestamp })
+                    .map(|ts| Utc.timestamp_millis_opt(ts as i64).unwrap());
+                utxo.push((u, ts));
+            }
+        }
+        Ok(UtxoReport::build(utxo, dust_threshold, Utc::now()))
+    }
+
+    fn subscribe(&self) -> Result<Receiver<BalancesChange>, StateError> {
+        self.permissions.check_read(Store::Balances)?;
+        Ok(self.notifier.subscribe())
+    }
+
+    fn list_xpub(&self, xpub: String, gap: u32) -> Result<XPubBalance, StateError> {
+        self.permissions.check_read(Store::Balances)?;
+        let parsed = XPub::from_str(xpub.as_str())
+            .map_err(|_| InvalidValueError::Name("xpub".to_string()))?;
+        let scanned = self.xpub.get_next(xpub.clone())?.saturating_add(gap);
+        let mut addresses = Vec::new();
+        for i in 0..scanned {
+            let address = parsed.get_address::<Address>(i)
+                .map_err(|_| InvalidValueError::Name("xpub".to_string()))?;
+            addresses.extend(self.list(address.to_string())?);
+        }
+        Ok(XPubBalance::new(xpub, addresses, scanned))
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use chrono::{TimeZone, Utc};
+    use std::str::FromStr;
+    use std::thread;
+    use bitcoin::Address;
+    use chrono::{Duration, TimeZone, Utc};
+    use emerald_vault::blockchain::bitcoin::XPub;
     use num_bigint::BigUint;
     use tempdir::TempDir;
-    use crate::access::balance::{Balance, Balances, Utxo};
+    use protobuf::Message;
+    use crate::access::asset::AssetId;
+    use crate::proto::balance::{Balance as proto_Balance, BalanceBundle as proto_BalanceBundle};
+    use crate::access::balance::{Balance, Balances, Utxo, UtxoAge, UtxoAgeBucket};
+    use crate::access::pagination::{PageOrder, PageQuery};
+    use crate::errors::StateError;
+    use crate::storage::balance_store::BalanceAccess;
+    use crate::access::transactions::{Staleness, Transactions};
+    use crate::access::xpubpos::XPubPosition;
+    use crate::proto::transactions::{BitcoinInput, BlockchainId, State, Transaction as proto_Transaction};
     use crate::storage::sled_access::SledStorage;
 
     #[test]
@@ -112,7 +440,7 @@ mod tests {
         let balance0 = Balance {
             address: "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826".to_string(),
             blockchain: 100,
-            asset: "ETHER".to_string(),
+            asset: AssetId::Native(100),
             amount: BigUint::from(100u32),
             ts: Utc.timestamp_millis(1675123456789),
             ..Balance::default()
@@ -129,6 +457,83 @@ mod tests {
         assert_eq!(act[0], balance0);
     }
 
+    #[test]
+    fn replace_legacy_asset() {
+        let tmp_dir = TempDir::new("balance").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let balances = access.get_balance();
+
+        // as it was stored before the typed asset ids
+        let mut legacy = proto_Balance::new();
+        legacy.address = "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826".to_string();
+        legacy.blockchain = 100;
+        legacy.asset = "ETHER".to_string();
+        legacy.amount = "100".to_string();
+        legacy.ts = 1675123456789;
+        let mut bundle = proto_BalanceBundle::new();
+        bundle.balances.push(legacy);
+        access.db.insert("balance:0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826", bundle.write_to_bytes().unwrap()).unwrap();
+
+        let act = balances.list("0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826".to_string()).unwrap();
+        assert_eq!(act.len(), 1);
+        assert_eq!(act[0].asset, AssetId::Native(100));
+
+        let balance1 = Balance {
+            address: "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826".to_string(),
+            blockchain: 100,
+            asset: AssetId::Native(100),
+            amount: BigUint::from(200u32),
+            ts: Utc.timestamp_millis_opt(1675123456799).unwrap(),
+            ..Balance::default()
+        };
+        balances.set(balance1.clone()).unwrap();
+
+        let act = balances.list("0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826".to_string()).unwrap();
+        assert_eq!(act, vec![balance1]);
+    }
+
+    #[test]
+    fn keep_unknown_legacy_asset() {
+        let tmp_dir = TempDir::new("balance").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let balances = access.get_balance();
+
+        // a legacy code which cannot be converted to an AssetId
+        let mut legacy = proto_Balance::new();
+        legacy.address = "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826".to_string();
+        legacy.blockchain = 100;
+        legacy.asset = "DAI".to_string();
+        legacy.amount = "100".to_string();
+        legacy.ts = 1675123456789;
+        let mut bundle = proto_BalanceBundle::new();
+        bundle.balances.push(legacy.clone());
+        access.db.insert("balance:0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826", bundle.write_to_bytes().unwrap()).unwrap();
+
+        let balance1 = Balance {
+            address: "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826".to_string(),
+            blockchain: 100,
+            asset: AssetId::Native(100),
+            amount: BigUint::from(200u32),
+            ts: Utc.timestamp_millis_opt(1675123456799).unwrap(),
+            ..Balance::default()
+        };
+        balances.set(balance1.clone()).unwrap();
+        let balance2 = Balance {
+            amount: BigUint::from(300u32),
+            ts: Utc.timestamp_millis_opt(1675123456809).unwrap(),
+            ..balance1.clone()
+        };
+        balances.set_many(vec![balance2.clone()]).unwrap();
+
+        let act = balances.list("0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826".to_string()).unwrap();
+        assert_eq!(act, vec![balance2]);
+
+        let stored = access.db.get("balance:0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826").unwrap().unwrap();
+        let stored = proto_BalanceBundle::parse_from_bytes(stored.as_ref()).unwrap();
+        assert_eq!(stored.balances.len(), 2);
+        assert!(stored.balances.contains(&legacy));
+    }
+
     #[test]
     fn keeps_multiple_assets() {
         let tmp_dir = TempDir::new("balance").unwrap();
@@ -138,7 +543,7 @@ mod tests {
         let balance0 = Balance {
             address: "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826".to_string(),
             blockchain: 100,
-            asset: "ETHER".to_string(),
+            asset: AssetId::Native(100),
             amount: BigUint::from(100u32),
             ts: Utc.timestamp_millis(1675123456789),
             ..Balance::default()
@@ -147,7 +552,7 @@ mod tests {
         let balance1 = Balance {
             address: "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826".to_string(),
             blockchain: 100,
-            asset: "ERC20:0xdAC17F958D2ee523a2206206994597C13D831ec7".to_string(),
+            asset: AssetId::erc20(100, "0xdAC17F958D2ee523a2206206994597C13D831ec7").unwrap(),
             amount: BigUint::from(200u32),
             ts: Utc.timestamp_millis(1675123456789),
             ..Balance::default()
@@ -176,7 +581,7 @@ mod tests {
         let balance0 = Balance {
             address: "12cbQLTFMXRnSzktFkuoG3eHoMeFtpTu3S".to_string(),
             blockchain: 1,
-            asset: "BTC".to_string(),
+            asset: AssetId::Native(1),
             amount: BigUint::from(1000u32),
             ts: Utc.timestamp_millis(1675123456789),
             ..Balance::default()
@@ -185,7 +590,7 @@ mod tests {
         let balance1 = Balance {
             address: "12cbQLTFMXRnSzktFkuoG3eHoMeFtpTu3S".to_string(),
             blockchain: 1,
-            asset: "BTC".to_string(),
+            asset: AssetId::Native(1),
             amount: BigUint::from(2000u32),
             ts: Utc.timestamp_millis(1675123456789),
             ..Balance::default()
@@ -213,7 +618,7 @@ mod tests {
         let balance0 = Balance {
             address: "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826".to_string(),
             blockchain: 100,
-            asset: "ETHER".to_string(),
+            asset: AssetId::Native(100),
             amount: BigUint::from(100u32),
             ts: Utc.timestamp_millis(1675123456789),
             ..Balance::default()
@@ -240,14 +645,15 @@ mod tests {
         let balance0 = Balance {
             address: "bc1qywz558j2ja7fwmg32jupn02qvla5zm3dvggpqv".to_string(),
             blockchain: 1,
-            asset: "BTC".to_string(),
+            asset: AssetId::Native(1),
             amount: BigUint::from(23045u64),
             ts: Utc.timestamp_millis(1675123456789),
             utxo: vec![
                 Utxo {
                     txid: "01ff3e2b6d2f1e52aa548e79b8f43d0091e9541bc4f70cda4e6549aaf836268b".to_string(),
                     vout: 1,
-                    amount: 23045
+                    amount: 23045,
+                    reserved_by: None,
                 }
             ],
             ..Balance::default()
@@ -265,7 +671,8 @@ mod tests {
         assert_eq!(act[0].utxo[0], Utxo {
             txid: "01ff3e2b6d2f1e52aa548e79b8f43d0091e9541bc4f70cda4e6549aaf836268b".to_string(),
             vout: 1,
-            amount: 23045
+            amount: 23045,
+            reserved_by: None,
         });
     }
 
@@ -278,14 +685,15 @@ mod tests {
         let balance0 = Balance {
             address: "bc1qywz558j2ja7fwmg32jupn02qvla5zm3dvggpqv".to_string(),
             blockchain: 1,
-            asset: "BTC".to_string(),
+            asset: AssetId::Native(1),
             amount: BigUint::from(23045u64),
             ts: Utc.timestamp_millis(1675123456789),
             utxo: vec![
                 Utxo {
                     txid: "01ff3e2b6d2f1e52aa548e79b8f43d0091e9541bc4f70cda4e6549aaf836268b".to_string(),
                     vout: 1,
-                    amount: 12345
+                    amount: 12345,
+                    reserved_by: None,
                 }
             ],
             ..Balance::default()
@@ -301,4 +709,483 @@ mod tests {
         assert_eq!(act.len(), 1);
         assert_eq!(act[0].utxo.len(), 0);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn report_utxo() {
+        let tmp_dir = TempDir::new("balance").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let balances = access.get_balance();
+        let transactions = access.get_transactions();
+
+        let now = Utc::now().timestamp_millis() as u64;
+        let txids: Vec<String> = (0..7).map(|i| format!("{:02x}ff3e2b6d2f1e52aa548e79b8f43d0091e9541bc4f70cda4e6549aaf836268b", i)).collect();
+        let mut tx_recent = proto_Transaction::new();
+        tx_recent.blockchain = BlockchainId::CHAIN_BITCOIN;
+        tx_recent.tx_id = txids[0].clone();
+        tx_recent.since_timestamp = now - 60 * 60 * 1000;
+        let mut tx_old = tx_recent.clone();
+        tx_old.tx_id = txids[1].clone();
+        tx_old.since_timestamp = now - 100 * 24 * 60 * 60 * 1000;
+        tx_old.confirm_timestamp = now - 90 * 24 * 60 * 60 * 1000;
+        tx_old.state = State::CONFIRMED;
+        transactions.submit(vec![tx_recent, tx_old]).unwrap();
+
+        let amounts = [500u64, 20_000, 300, 15_000, 40_000, 70_000, 10_000];
+        let utxo: Vec<Utxo> = txids.iter().zip(amounts.iter())
+            .map(|(txid, amount)| Utxo { txid: txid.clone(), vout: 0, amount: *amount, reserved_by: None })
+            .collect();
+        balances.set(Balance {
+            address: "bc1qywz558j2ja7fwmg32jupn02qvla5zm3dvggpqv".to_string(),
+            blockchain: 1,
+            asset: AssetId::Native(1),
+            amount: BigUint::from(amounts.iter().sum::<u64>()),
+            utxo,
+            ..Balance::default()
+        }).unwrap();
+
+        let act = balances.utxo_report("bc1qywz558j2ja7fwmg32jupn02qvla5zm3dvggpqv".to_string(), 1_000).unwrap();
+        assert_eq!(act.ages, vec![
+            UtxoAgeBucket { age: UtxoAge::Day, count: 1, amount: 500 },
+            UtxoAgeBucket { age: UtxoAge::Year, count: 1, amount: 20_000 },
+            UtxoAgeBucket { age: UtxoAge::Unknown, count: 5, amount: 135_300 },
+        ]);
+        assert_eq!(act.dust.iter().map(|u| u.amount).collect::<Vec<u64>>(), vec![300, 500]);
+        // everything except the dust and the largest one
+        assert_eq!(act.consolidate.iter().map(|u| u.amount).collect::<Vec<u64>>(), vec![10_000, 15_000, 20_000, 40_000]);
+
+        // too few outputs to consolidate with a higher dust threshold
+        let act = balances.utxo_report("bc1qywz558j2ja7fwmg32jupn02qvla5zm3dvggpqv".to_string(), 16_000).unwrap();
+        assert_eq!(act.dust.len(), 4);
+        assert!(act.consolidate.is_empty());
+    }
+
+    #[test]
+    fn list_xpub_balance() {
+        let tmp_dir = TempDir::new("balance").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let balances = access.get_balance();
+
+        let xpub = "zpub6ttpB5kpi5EbjzUhRC9gqYBJEnDE5TKxN3wsBLh4TM1JJz8ZKcpCjtrmvw8bAQVUkxTcMUBcHK9oGgAAhe97Xpd8HDNzzDx59u13wz32dyS";
+        let parsed = XPub::from_str(xpub).unwrap();
+        let address = |i: u32| parsed.get_address::<Address>(i).unwrap().to_string();
+
+        for (i, amount) in [(0u32, 1000u64), (3, 2000), (10, 5000)] {
+            balances.set(Balance {
+                address: address(i),
+                blockchain: 1,
+                asset: AssetId::Native(1),
+                amount: BigUint::from(amount),
+                ts: Utc.timestamp_millis_opt(1675123456789 + i as i64).unwrap(),
+                utxo: vec![
+                    Utxo { txid: format!("{:064x}", i), vout: 0, amount, reserved_by: None }
+                ],
+            }).unwrap();
+        }
+
+        // nothing is known about the position, so it's just the gap
+        let act = balances.list_xpub(xpub.to_string(), 5).unwrap();
+        assert_eq!(act.scanned, 5);
+        assert_eq!(act.addresses.len(), 2);
+        assert_eq!(act.addresses[0].address, address(0));
+        assert_eq!(act.addresses[1].address, address(3));
+        assert_eq!(act.total.len(), 1);
+        assert_eq!(act.total[0].address, xpub);
+        assert_eq!(act.total[0].amount, BigUint::from(3000u64));
+        assert_eq!(act.total[0].ts, Utc.timestamp_millis_opt(1675123456789).unwrap());
+        assert_eq!(act.total[0].utxo.len(), 2);
+
+        access.get_xpub_pos().set_at_least(xpub.to_string(), 6).unwrap();
+        let act = balances.list_xpub(xpub.to_string(), 5).unwrap();
+        assert_eq!(act.scanned, 12);
+        assert_eq!(act.addresses.len(), 3);
+        assert_eq!(act.total[0].amount, BigUint::from(8000u64));
+
+        assert!(balances.list_xpub("xpub-invalid".to_string(), 5).is_err());
+    }
+
+    #[test]
+    fn list_stale_balances() {
+        let tmp_dir = TempDir::new("balance").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let balances = access.get_balance();
+
+        let now = Utc::now();
+        let addresses = [
+            ("0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826", Duration::minutes(20)),
+            ("0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb", Duration::minutes(1)),
+            ("bc1qywz558j2ja7fwmg32jupn02qvla5zm3dvggpqv", Duration::hours(2)),
+        ];
+        for (address, age) in addresses {
+            balances.set(Balance {
+                address: address.to_string(),
+                blockchain: 100,
+                asset: AssetId::Native(100),
+                amount: BigUint::from(100u32),
+                ts: now - age,
+                ..Balance::default()
+            }).unwrap();
+        }
+
+        let act = balances.list_with_age("0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826".to_string()).unwrap();
+        assert_eq!(act.len(), 1);
+        assert!(act[0].1 >= Duration::minutes(20));
+        assert_eq!(act[0].2, Staleness::Stale);
+        let act = balances.list_with_age("0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb".to_string()).unwrap();
+        assert_eq!(act[0].2, Staleness::Fresh);
+
+        let act = balances.list_stale(Duration::minutes(10)).unwrap();
+        assert_eq!(act, vec!["bc1qywz558j2ja7fwmg32jupn02qvla5zm3dvggpqv", "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"]);
+        assert!(balances.list_stale(Duration::days(1)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn list_all_by_pages() {
+        let tmp_dir = TempDir::new("balance").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let balances = access.get_balance();
+
+        let addresses = [
+            "0xedd91797204d3537fbabde0e0e42aae99975f2bb",
+            "0xcd2a3d9f938e13cd947ec05abc7fe734df8dd826",
+            "bc1qywz558j2ja7fwmg32jupn02qvla5zm3dvggpqv",
+        ];
+        for address in addresses {
+            balances.set(Balance {
+                address: address.to_string(),
+                blockchain: 100,
+                asset: AssetId::Native(100),
+                amount: BigUint::from(100u32),
+                ..Balance::default()
+            }).unwrap();
+        }
+        balances.set(Balance {
+            address: addresses[1].to_string(),
+            blockchain: 100,
+            asset: AssetId::Erc20(100, "0x6b175474e89094c44da98b954eedeac495271d0f".to_string()),
+            amount: BigUint::from(500u32),
+            ..Balance::default()
+        }).unwrap();
+
+        let walk = |order: PageOrder| {
+            let mut result = Vec::new();
+            let mut cursor = None;
+            let mut pages = 0;
+            loop {
+                let page = balances.list_all(PageQuery { limit: 1, cursor, order }).unwrap();
+                result.extend(page.values.iter().map(|b| b.address.clone()));
+                pages += 1;
+                cursor = page.cursor;
+                if cursor.is_none() {
+                    break
+                }
+            }
+            (result, pages)
+        };
+
+        let (act, pages) = walk(PageOrder::NewestFirst);
+        assert_eq!(pages, 3);
+        assert_eq!(act, vec![addresses[1], addresses[1], addresses[0], addresses[2]]);
+
+        let (act, _) = walk(PageOrder::OldestFirst);
+        assert_eq!(act, vec![addresses[2], addresses[0], addresses[1], addresses[1]]);
+
+        let act = balances.list_all(PageQuery::default()).unwrap();
+        assert_eq!(act.values.len(), 4);
+        assert!(act.cursor.is_none());
+    }
+
+    #[test]
+    fn set_many_at_once() {
+        let tmp_dir = TempDir::new("balance").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let balances = access.get_balance();
+
+        let dai = AssetId::Erc20(100, "0x6b175474e89094c44da98b954eedeac495271d0f".to_string());
+        balances.set(Balance {
+            address: "0xcd2a3d9f938e13cd947ec05abc7fe734df8dd826".to_string(),
+            blockchain: 100,
+            asset: dai.clone(),
+            amount: BigUint::from(500u32),
+            ..Balance::default()
+        }).unwrap();
+
+        let mut values = Vec::new();
+        for (i, address) in ["0xcd2a3d9f938e13cd947ec05abc7fe734df8dd826", "0xedd91797204d3537fbabde0e0e42aae99975f2bb"].iter().enumerate() {
+            values.push(Balance {
+                address: address.to_string(),
+                blockchain: 100,
+                asset: AssetId::Native(100),
+                amount: BigUint::from(100u32 + i as u32),
+                ..Balance::default()
+            });
+        }
+        // a later value for the same asset replaces the earlier one
+        values.push(Balance {
+            address: "0xedd91797204d3537fbabde0e0e42aae99975f2bb".to_string(),
+            blockchain: 100,
+            asset: AssetId::Native(100),
+            amount: BigUint::from(200u32),
+            ..Balance::default()
+        });
+        balances.set_many(values).unwrap();
+
+        let act = balances.list("0xcd2a3d9f938e13cd947ec05abc7fe734df8dd826".to_string()).unwrap();
+        assert_eq!(act.len(), 2);
+        assert_eq!(act.iter().find(|b| b.asset == dai).unwrap().amount, BigUint::from(500u32));
+        assert_eq!(act.iter().find(|b| b.asset == AssetId::Native(100)).unwrap().amount, BigUint::from(100u32));
+        let act = balances.list("0xedd91797204d3537fbabde0e0e42aae99975f2bb".to_string()).unwrap();
+        assert_eq!(act.len(), 1);
+        assert_eq!(act[0].amount, BigUint::from(200u32));
+
+        // nothing is stored if any of them is invalid
+        let values = vec![
+            Balance {
+                address: "0x3f5ce5fbfe3e9af3971dd833d26ba9b5c936f0be".to_string(),
+                blockchain: 100,
+                asset: AssetId::Native(100),
+                amount: BigUint::from(100u32),
+                ..Balance::default()
+            },
+            Balance {
+                address: "адрес".to_string(),
+                ..Balance::default()
+            },
+        ];
+        assert!(balances.set_many(values).is_err());
+        assert!(balances.list("0x3f5ce5fbfe3e9af3971dd833d26ba9b5c936f0be".to_string()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn reserve_and_release_utxo() {
+        let tmp_dir = TempDir::new("balance").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let balances = access.get_balance();
+
+        let txids: Vec<String> = (1..=3).map(|i| format!("{:064x}", i)).collect();
+        balances.set(Balance {
+            address: "bc1qywz558j2ja7fwmg32jupn02qvla5zm3dvggpqv".to_string(),
+            blockchain: 1,
+            asset: AssetId::Native(1),
+            amount: BigUint::from(3000u64),
+            utxo: txids.iter()
+                .map(|txid| Utxo { txid: txid.clone(), vout: 0, amount: 1000, reserved_by: None })
+                .collect(),
+            ..Balance::default()
+        }).unwrap();
+
+        balances.reserve_utxo("draft-1".to_string(), vec![(txids[0].clone(), 0), (txids[1].clone(), 0)]).unwrap();
+        // the same tx may reserve again
+        balances.reserve_utxo("draft-1".to_string(), vec![(txids[1].clone(), 0)]).unwrap();
+        // but another one cannot take any of them, and then it reserves nothing
+        let act = balances.reserve_utxo("draft-2".to_string(), vec![(txids[2].clone(), 0), (txids[1].clone(), 0)]);
+        assert!(matches!(act, Err(StateError::InvalidValue(_))));
+
+        let reserved = |balances: &BalanceAccess| -> Vec<Option<String>> {
+            balances.list("bc1qywz558j2ja7fwmg32jupn02qvla5zm3dvggpqv".to_string()).unwrap()[0]
+                .utxo.iter().map(|u| u.reserved_by.clone()).collect()
+        };
+        assert_eq!(reserved(&balances), vec![Some("draft-1".to_string()), Some("draft-1".to_string()), None]);
+
+        // a refreshed balance keeps the reservations
+        balances.set(Balance {
+            address: "bc1qywz558j2ja7fwmg32jupn02qvla5zm3dvggpqv".to_string(),
+            blockchain: 1,
+            asset: AssetId::Native(1),
+            amount: BigUint::from(2000u64),
+            utxo: txids[1..].iter()
+                .map(|txid| Utxo { txid: txid.clone(), vout: 0, amount: 1000, reserved_by: None })
+                .collect(),
+            ..Balance::default()
+        }).unwrap();
+        assert_eq!(reserved(&balances), vec![Some("draft-1".to_string()), None]);
+
+        assert_eq!(balances.release_utxo("draft-1".to_string()).unwrap(), 2);
+        assert_eq!(reserved(&balances), vec![None, None]);
+        balances.reserve_utxo("draft-2".to_string(), vec![(txids[1].clone(), 0)]).unwrap();
+        assert_eq!(reserved(&balances), vec![Some("draft-2".to_string()), None]);
+    }
+
+    #[test]
+    fn reserve_utxo_concurrently() {
+        let tmp_dir = TempDir::new("balance").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+
+        for round in 0..20 {
+            let txid = format!("{:064x}", round);
+            let handles: Vec<_> = (0..4).map(|i| {
+                let balances = access.get_balance();
+                let outputs = vec![(format!("{:064x}", 1000 + i), 0), (txid.clone(), 0)];
+                thread::spawn(move || balances.reserve_utxo(format!("draft-{}", i), outputs).is_ok())
+            }).collect();
+            let succeeded = handles.into_iter().map(|h| h.join().unwrap()).filter(|ok| *ok).count();
+            assert_eq!(succeeded, 1);
+            // the failed ones have nothing left reserved
+            let reserved = access.db.scan_prefix("utxo_reserved:").count();
+            assert_eq!(reserved, 2);
+            for i in 0..4 {
+                access.get_balance().release_utxo(format!("draft-{}", i)).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn release_utxo_when_spent() {
+        let tmp_dir = TempDir::new("balance").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let balances = access.get_balance();
+        let transactions = access.get_transactions();
+
+        let txids: Vec<String> = (1..=3).map(|i| format!("{:064x}", i)).collect();
+        let make_tx = |tx_id: &str, input: &str, state: State| {
+            let mut tx = proto_Transaction::new();
+            tx.blockchain = BlockchainId::CHAIN_BITCOIN;
+            tx.tx_id = tx_id.to_string();
+            tx.state = state;
+            let mut spent = BitcoinInput::new();
+            spent.txid = input.to_string();
+            spent.vout = 0;
+            tx.inputs.push(spent);
+            tx
+        };
+        let tx1 = make_tx("aa00000000000000000000000000000000000000000000000000000000000001", txids[0].as_str(), State::SUBMITTED);
+        let tx2 = make_tx("aa00000000000000000000000000000000000000000000000000000000000002", txids[1].as_str(), State::SUBMITTED);
+        balances.reserve_utxo(tx1.tx_id.clone(), vec![(txids[0].clone(), 0)]).unwrap();
+        balances.reserve_utxo(tx2.tx_id.clone(), vec![(txids[1].clone(), 0)]).unwrap();
+        balances.reserve_utxo("draft".to_string(), vec![(txids[2].clone(), 0)]).unwrap();
+        transactions.submit(vec![tx1.clone(), tx2.clone()]).unwrap();
+        assert_eq!(access.db.scan_prefix("utxo_reserved:").count(), 3);
+
+        let mut confirmed = tx1.clone();
+        confirmed.state = State::CONFIRMED;
+        let mut dropped = tx2.clone();
+        dropped.state = State::DROPPED;
+        // a dropped tx which spends an output reserved by someone else doesn't release it
+        let other = make_tx("aa00000000000000000000000000000000000000000000000000000000000003", txids[2].as_str(), State::DROPPED);
+        transactions.submit(vec![confirmed, dropped, other]).unwrap();
+
+        let left: Vec<String> = access.db.scan_prefix("utxo_reserved:").keys()
+            .map(|k| String::from_utf8(k.unwrap().to_vec()).unwrap())
+            .collect();
+        assert_eq!(left, vec![format!("utxo_reserved:{}:0", txids[2])]);
+    }
+
+    #[test]
+    fn release_utxo_when_state_set() {
+        let tmp_dir = TempDir::new("balance").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let balances = access.get_balance();
+        let transactions = access.get_transactions();
+
+        let txid = format!("{:064x}", 1);
+        let mut tx = proto_Transaction::new();
+        tx.blockchain = BlockchainId::CHAIN_BITCOIN;
+        tx.tx_id = "aa00000000000000000000000000000000000000000000000000000000000001".to_string();
+        tx.state = State::SUBMITTED;
+        tx.sync_timestamp = 1_647_313_850_992;
+        let mut spent = BitcoinInput::new();
+        spent.txid = txid.clone();
+        spent.vout = 0;
+        tx.inputs.push(spent);
+
+        balances.reserve_utxo(tx.tx_id.clone(), vec![(txid.clone(), 0)]).unwrap();
+        transactions.submit(vec![tx.clone()]).unwrap();
+        assert!(balances.reserve_utxo("draft".to_string(), vec![(txid.clone(), 0)]).is_err());
+
+        transactions.set_state(1, tx.tx_id.clone(), State::DROPPED, 1_647_313_900_000).unwrap();
+        assert_eq!(access.db.scan_prefix("utxo_reserved:").count(), 0);
+        assert!(balances.reserve_utxo("draft".to_string(), vec![(txid, 0)]).is_ok());
+    }
+
+    #[test]
+    fn get_by_asset() {
+        let tmp_dir = TempDir::new("balance").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let balances = access.get_balance();
+
+        let address = "0xcd2a3d9f938e13cd947ec05abc7fe734df8dd826";
+        let dai = AssetId::Erc20(100, "0x6b175474e89094c44da98b954eedeac495271d0f".to_string());
+        let values = vec![
+            (100, AssetId::Native(100), 100u32),
+            (100, dai.clone(), 200),
+            (101, AssetId::Native(101), 300),
+        ];
+        for (blockchain, asset, amount) in values {
+            balances.set(Balance {
+                address: address.to_string(),
+                blockchain,
+                asset,
+                amount: BigUint::from(amount),
+                ..Balance::default()
+            }).unwrap();
+        }
+
+        assert_eq!(balances.list_filtered(address.to_string(), None, None).unwrap().len(), 3);
+        assert_eq!(balances.list_filtered(address.to_string(), Some(100), None).unwrap().len(), 2);
+        let act = balances.list_filtered(address.to_string(), None, Some(AssetId::Native(101))).unwrap();
+        assert_eq!(act.len(), 1);
+        assert_eq!(act[0].amount, BigUint::from(300u32));
+
+        let act = balances.get(address.to_string(), 100, dai.clone()).unwrap();
+        assert_eq!(act.map(|b| b.amount), Some(BigUint::from(200u32)));
+        assert!(balances.get(address.to_string(), 101, dai).unwrap().is_none());
+        assert!(balances.get("0xedd91797204d3537fbabde0e0e42aae99975f2bb".to_string(), 100, AssetId::Native(100)).unwrap().is_none());
+    }
+
+    #[test]
+    fn notify_about_changes() {
+        let tmp_dir = TempDir::new("balance").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let balances = access.get_balance();
+        let events = balances.subscribe().unwrap();
+
+        let balance = |address: &str, amount: u32| Balance {
+            address: address.to_string(),
+            blockchain: 100,
+            asset: AssetId::Native(100),
+            amount: BigUint::from(amount),
+            ..Balance::default()
+        };
+
+        balances.set(balance("0xcd2a3d9f938e13cd947ec05abc7fe734df8dd826", 100)).unwrap();
+        // the same amount, so nothing is changed
+        balances.set(balance("0xcd2a3d9f938e13cd947ec05abc7fe734df8dd826", 100)).unwrap();
+        access.get_balance().set_many(vec![
+            balance("0xcd2a3d9f938e13cd947ec05abc7fe734df8dd826", 250),
+            // a new zero balance is not a change
+            balance("0xedd91797204d3537fbabde0e0e42aae99975f2bb", 0),
+        ]).unwrap();
+
+        let act: Vec<_> = events.try_iter().collect();
+        assert_eq!(act.len(), 2);
+        assert_eq!(act[0].changes.len(), 1);
+        assert_eq!(act[0].changes[0].old_amount, None);
+        assert_eq!(act[0].changes[0].new_amount, BigUint::from(100u32));
+        assert_eq!(act[1].changes.len(), 1);
+        assert_eq!(act[1].changes[0].address, "0xcd2a3d9f938e13cd947ec05abc7fe734df8dd826");
+        assert_eq!(act[1].changes[0].old_amount, Some(BigUint::from(100u32)));
+        assert_eq!(act[1].changes[0].new_amount, BigUint::from(250u32));
+        assert_eq!(act[1].seq, Some(access.get_sequence().unwrap()));
+    }
+
+    #[test]
+    fn notify_about_queued_changes() {
+        let tmp_dir = TempDir::new("balances").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let balances = access.get_balance();
+        let events = balances.subscribe().unwrap();
+        access.health.degrade();
+
+        balances.set(Balance {
+            address: "0xcd2a3d9f938e13cd947ec05abc7fe734df8dd826".to_string(),
+            blockchain: 100,
+            asset: AssetId::Native(100),
+            amount: BigUint::from(100u32),
+            ..Balance::default()
+        }).unwrap();
+
+        let act: Vec<_> = events.try_iter().collect();
+        assert_eq!(act.len(), 1);
+        assert_eq!(act[0].changes.len(), 1);
+        assert_eq!(act[0].seq, None);
+    }
+}
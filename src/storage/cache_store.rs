@@ -1,24 +1,28 @@
 use std::sync::Arc;
 use chrono::{Duration, TimeZone, Utc};
 use protobuf::Message;
-use sled::{Batch, Db};
+use sled::Db;
 use crate::access::cache::{Cache, CacheEntry};
 use crate::proto::cache::{Cache as proto_Cache};
+use crate::access::permissions::{Permissions, Store};
 use crate::errors::StateError;
+use crate::storage::health::Health;
+use crate::storage::tuning::Profiler;
+use crate::storage::ttl_policy::TtlPolicy;
+use crate::storage::rate_limit::RateLimiter;
+use crate::storage::sequence::Batch;
 
 const PREFIX_KEY: &'static str = "cache:";
 
-// 1 week by default
-const DEFAULT_TTL_SECOND: u64 = 60 * 60 * 24 * 7;
-// 1 month
-const MAX_TTL_SECOND: u64 = 60 * 60 * 24 * 30;
-
 const PURGE_KEY: &str = "_purge";
-// purge cache every 1 hour
-const PURGE_EVERY_SECONDS: i64 = 60 * 60;
 
 pub struct CacheAccess {
     pub(crate) db: Arc<Db>,
+    pub(crate) ttl: TtlPolicy,
+    pub(crate) permissions: Permissions,
+    pub(crate) limiter: Arc<RateLimiter>,
+    pub(crate) profiler: Arc<Profiler>,
+    pub(crate) health: Arc<Health>,
 }
 
 impl CacheAccess {
@@ -38,8 +42,8 @@ impl CacheAccess {
             .unwrap();
 
         Utc.timestamp_millis(last_purge).lt(
-            &Utc::now()
-                .checked_sub_signed(Duration::seconds(PURGE_EVERY_SECONDS))
+            &self.ttl.clock.now()
+                .checked_sub_signed(Duration::seconds(self.ttl.cache_purge_every.as_secs() as i64))
                 .unwrap()
         )
     }
@@ -47,8 +51,8 @@ impl CacheAccess {
     fn mark_purged(&mut self) {
         let _ = self.put(
             PURGE_KEY.to_string(),
-            Utc::now().timestamp_millis().to_string(),
-            Some(MAX_TTL_SECOND)
+            self.ttl.clock.now().timestamp_millis().to_string(),
+            Some(self.ttl.cache_max.as_secs())
         );
     }
 
@@ -57,21 +61,25 @@ impl CacheAccess {
 impl Cache for CacheAccess {
 
     fn put(&mut self, id: String, value: String, ttl_seconds: Option<u64>) -> Result<(), StateError> {
-        let duration = ttl_seconds.or(Some(DEFAULT_TTL_SECOND))
-            .map(|v| if v > MAX_TTL_SECOND { MAX_TTL_SECOND } else {v})
+        self.permissions.check_write(Store::Cache)?;
+        self.limiter.check(Store::Cache)?;
+        let max_ttl = self.ttl.cache_max.as_secs();
+        let duration = ttl_seconds.or(Some(self.ttl.cache_default.as_secs()))
+            .map(|v| if v > max_ttl { max_ttl } else {v})
             .map(|v| Duration::seconds(v as i64))
             .unwrap();
+        let now = self.ttl.clock.now();
         let entry = CacheEntry {
             id: id.clone(),
             value,
-            ts: Utc::now(),
-            ttl: Utc::now()
+            ts: now,
+            ttl: now
                 .checked_add_signed(duration)
                 .unwrap()
         };
         let proto: proto_Cache = entry.into();
         if let Ok(bytes) = proto.write_to_bytes() {
-            self.db.insert(CacheAccess::get_key(&id).as_bytes(), bytes)?;
+            self.health.write(&self.db, CacheAccess::get_key(&id).as_str(), Some(bytes))?;
         }
         if self.should_purge() {
             let _ = self.purge();
@@ -80,8 +88,9 @@ impl Cache for CacheAccess {
     }
 
     fn get(&self, id: String) -> Result<Option<String>, StateError> {
+        self.permissions.check_read(Store::Cache)?;
         let key = CacheAccess::get_key(&id);
-        if let Some(base) = self.db.get(&key)? {
+        if let Some(base) = self.profiler.read(|| self.health.read(&self.db, key.as_str()))? {
             let proto = proto_Cache::parse_from_bytes(base.as_ref())?;
             Ok(Some(proto.value))
         } else {
@@ -90,12 +99,15 @@ impl Cache for CacheAccess {
     }
 
     fn evict(&mut self, id: String) -> Result<(), StateError> {
-        self.db.remove(CacheAccess::get_key(&id).as_bytes())
-            .map(|_| ())
-            .map_err(StateError::from)
+        self.permissions.check_write(Store::Cache)?;
+        self.limiter.check(Store::Cache)?;
+        self.health.write(&self.db, CacheAccess::get_key(&id).as_str(), None)?;
+        Ok(())
     }
 
     fn purge(&mut self) -> Result<usize, StateError> {
+        self.permissions.check_write(Store::Cache)?;
+        self.limiter.check(Store::Cache)?;
         let mut iter = self.db.scan_prefix(PREFIX_KEY);
         let mut done = false;
         let mut count = 0;
@@ -107,7 +119,7 @@ impl Cache for CacheAccess {
                     if let Ok(entry) = entry {
                         let delete = if let Ok(proto) = proto_Cache::parse_from_bytes(entry.1.as_ref()) {
                             Utc.timestamp_millis(proto.get_ttl() as i64)
-                                .lt(&Utc::now())
+                                .lt(&self.ttl.clock.now())
                         } else {
                             // always delete corrupted values
                             true
@@ -122,7 +134,7 @@ impl Cache for CacheAccess {
             }
         }
         if count > 0 {
-            let _ = self.db.apply_batch(batch);
+            let _ = self.health.apply(&self.db, batch);
         }
         self.mark_purged();
         Ok(count)
@@ -132,9 +144,12 @@ impl Cache for CacheAccess {
 
 #[cfg(test)]
 mod tests {
+    use chrono::Utc;
     use tempdir::TempDir;
+    use std::time::Duration;
     use crate::access::cache::Cache;
     use crate::storage::sled_access::SledStorage;
+    use crate::storage::ttl_policy::{Clock, TtlPolicy};
 
     #[test]
     fn get_nothing_exist() {
@@ -222,4 +237,27 @@ mod tests {
         let act = act.unwrap();
         assert!(act.is_none());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn uses_default_ttl_from_policy() {
+        let tmp_dir = TempDir::new("cache").unwrap();
+        let policy = TtlPolicy {
+            cache_default: Duration::from_secs(1),
+            ..TtlPolicy::default()
+        };
+        let access = SledStorage::open_with_policy(tmp_dir.path().to_path_buf(), policy).unwrap();
+        let mut cache = access.get_cache();
+        let now = Utc::now();
+        cache.ttl.clock = Clock::Fixed(now);
+
+        let put = cache.put("test".to_string(), "hello world!".to_string(), None);
+        assert!(put.is_ok());
+        let evict = cache.purge();
+        assert_eq!(0, evict.unwrap());
+
+        cache.ttl.clock = Clock::Fixed(now + chrono::Duration::seconds(2));
+        let evict = cache.purge();
+        assert!(evict.is_ok());
+        assert_eq!(1, evict.unwrap());
+    }
+}
@@ -0,0 +1,151 @@
+//!
+//! Comparison of two storages, ex. to check what is different between a backup and the current state when
+//! investigating a data loss. The keys are grouped by the kind of the records, which is the prefix of the key (ex.
+//! `tx`, `txmeta`, `idx:tx` or `balance`), so it's easy to see which of the stores is affected.
+//!
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use sled::IVec;
+use crate::errors::StateError;
+use crate::storage::sequence;
+use crate::storage::sled_access::SledStorage;
+
+///
+/// Differences in a group of keys
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GroupDiff {
+    /// Keys which are only in the second storage
+    pub added: Vec<String>,
+    /// Keys which are only in the first storage
+    pub removed: Vec<String>,
+    /// Keys which are in both, but with different values
+    pub changed: Vec<String>,
+}
+
+///
+/// Differences between two storages, per group of keys. A group without differences is not included.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DiffReport {
+    pub groups: BTreeMap<String, GroupDiff>,
+}
+
+impl DiffReport {
+
+    ///
+    /// True if the storages have the same data
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    fn get_group(&mut self, key: &str) -> &mut GroupDiff {
+        self.groups.entry(get_group_name(key)).or_default()
+    }
+}
+
+///
+/// Group of the key, which is the prefix before `:`. For the indexes it also includes the indexed store.
+fn get_group_name(key: &str) -> String {
+    let mut parts = key.splitn(3, ':');
+    let first = parts.next().unwrap_or_default();
+    match (first, parts.next(), parts.next()) {
+        ("idx", Some(store), Some(_)) => format!("{}:{}", first, store),
+        _ => first.to_string()
+    }
+}
+
+fn is_data(row: &sled::Result<(IVec, IVec)>) -> bool {
+    !matches!(row, Ok((key, _)) if sequence::is_changes_log(key.as_ref()))
+}
+
+///
+/// Compare the storage `a` with `b`, where `added` are the keys which are in `b` but not in `a`. Both storages must be
+/// permitted to read everything. The log of changes is not compared, because it's not a part of the data. Note that the
+/// storages are not locked while they are compared.
+pub fn compare(a: &SledStorage, b: &SledStorage) -> Result<DiffReport, StateError> {
+    a.check_read_all()?;
+    b.check_read_all()?;
+    let mut report = DiffReport::default();
+    let mut iter_a = a.db.iter().filter(is_data).peekable();
+    let mut iter_b = b.db.iter().filter(is_data).peekable();
+    loop {
+        let order = match (iter_a.peek(), iter_b.peek()) {
+            (None, None) => break,
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (Some(Ok((key_a, _))), Some(Ok((key_b, _)))) => key_a.cmp(key_b),
+            // take the failed one to return its error
+            (Some(Err(_)), _) => Ordering::Less,
+            (_, Some(Err(_))) => Ordering::Greater,
+        };
+        match order {
+            Ordering::Less => {
+                let (key, _) = iter_a.next().unwrap()?;
+                let key = String::from_utf8_lossy(key.as_ref()).to_string();
+                report.get_group(key.as_str()).removed.push(key);
+            }
+            Ordering::Greater => {
+                let (key, _) = iter_b.next().unwrap()?;
+                let key = String::from_utf8_lossy(key.as_ref()).to_string();
+                report.get_group(key.as_str()).added.push(key);
+            }
+            Ordering::Equal => {
+                let (key, value_a) = iter_a.next().unwrap()?;
+                let (_, value_b) = iter_b.next().unwrap()?;
+                if value_a != value_b {
+                    let key = String::from_utf8_lossy(key.as_ref()).to_string();
+                    report.get_group(key.as_str()).changed.push(key);
+                }
+            }
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use tempdir::TempDir;
+    use chrono::{TimeZone, Utc};
+    use crate::access::permissions::{Permissions, Store};
+    use crate::access::sync_state::SyncStates;
+    use crate::access::transactions::Transactions;
+    use crate::errors::StateError;
+    use crate::proto::transactions::{BlockchainId, Transaction as proto_Transaction};
+    use crate::storage::diff::{compare, get_group_name};
+    use crate::storage::sled_access::SledStorage;
+
+    #[test]
+    fn group_by_prefix() {
+        assert_eq!(get_group_name("tx:100/0x2f761cbf"), "tx");
+        assert_eq!(get_group_name("idx:tx:1/D8352686149007"), "idx:tx");
+        assert_eq!(get_group_name("sequence"), "sequence");
+    }
+
+    #[test]
+    fn compare_storages() {
+        let tmp_dir_a = TempDir::new("diff").unwrap();
+        let tmp_dir_b = TempDir::new("diff").unwrap();
+        let a = SledStorage::open(tmp_dir_a.path().to_path_buf()).unwrap();
+        let b = SledStorage::open(tmp_dir_b.path().to_path_buf()).unwrap();
+
+        let mut tx = proto_Transaction::new();
+        tx.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        tx.since_timestamp = 1_647_313_850_992;
+        a.get_transactions().submit(vec![tx.clone()]).unwrap();
+        a.get_sync_state().on_success("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string(), Utc.timestamp_millis_opt(1_647_313_850_992).unwrap()).unwrap();
+        b.get_sync_state().on_success("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string(), Utc.timestamp_millis_opt(1_647_313_860_992).unwrap()).unwrap();
+        b.get_sync_state().on_success("0x6218b36c1d19d4a2e9eb0ce3606eb48a0b86991c".to_string(), Utc.timestamp_millis_opt(1_647_313_860_992).unwrap()).unwrap();
+
+        let report = compare(&a, &b).unwrap();
+        let txes = report.groups.get("tx").unwrap();
+        assert_eq!(txes.removed, vec![format!("tx:100/{}", tx.tx_id)]);
+        assert!(report.groups.contains_key("idx:tx"));
+        let sync = report.groups.get("sync").unwrap();
+        assert_eq!(sync.added, vec!["sync:0x6218b36c1d19d4a2e9eb0ce3606eb48a0b86991c".to_string()]);
+        assert_eq!(sync.changed, vec!["sync:0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string()]);
+
+        assert!(compare(&a, &a).unwrap().is_empty());
+        let restricted = a.restrict(Permissions::read_only().without(Store::Cache));
+        assert_eq!(compare(&restricted, &b), Err(StateError::NotPermitted));
+    }
+}
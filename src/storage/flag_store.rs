@@ -0,0 +1,250 @@
+use std::collections::HashMap;
+use std::ops::Deref;
+use std::sync::Arc;
+use std::sync::mpsc::Receiver;
+use chrono::Utc;
+use protobuf::Message;
+use sled::Db;
+use crate::access::flags::{FlagValue, Flags, FlagsChange};
+use crate::access::permissions::{Permissions, Store};
+use crate::errors::{InvalidValueError, StateError};
+use crate::proto::flags::FeatureFlag;
+use crate::storage::notification::Notifier;
+use crate::storage::rate_limit::RateLimiter;
+use crate::storage::health::Health;
+use crate::storage::sequence::Batch;
+
+///
+/// # Storage:
+///
+/// - `flag:<KEY>` to store a `FeatureFlag`
+///
+const PREFIX_KEY: &str = "flag:";
+
+pub struct FlagAccess {
+    pub(crate) db: Arc<Db>,
+    pub(crate) permissions: Permissions,
+    pub(crate) limiter: Arc<RateLimiter>,
+    pub(crate) health: Arc<Health>,
+    pub(crate) notifier: Arc<Notifier<FlagsChange>>,
+}
+
+impl FlagAccess {
+
+    /// Checks if the `key` can be used as a part of the storage key
+    fn is_valid_key(key: &str) -> bool {
+        !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.')
+    }
+
+    fn key(key: &str) -> Result<String, StateError> {
+        if !FlagAccess::is_valid_key(key) {
+            return Err(StateError::InvalidValue(InvalidValueError::Name("key".to_string())))
+        }
+        Ok(format!("{}{}", PREFIX_KEY, key))
+    }
+
+    fn read(&self, key: &str) -> Result<Option<FeatureFlag>, StateError> {
+        match self.db.get(FlagAccess::key(key)?)? {
+            Some(b) => Ok(Some(FeatureFlag::parse_from_bytes(b.deref())?)),
+            None => Ok(None)
+        }
+    }
+
+    fn read_all(&self) -> Result<Vec<FeatureFlag>, StateError> {
+        let mut result = Vec::new();
+        for entry in self.db.scan_prefix(PREFIX_KEY) {
+            let (_, value) = entry?;
+            result.push(FeatureFlag::parse_from_bytes(value.as_ref())?);
+        }
+        Ok(result)
+    }
+
+    fn create(key: String, value: FlagValue, set_by: &str, now: u64) -> FeatureFlag {
+        let mut flag = FeatureFlag::new();
+        flag.set_key(key);
+        flag.set_flag_value(value);
+        flag.set_set_by(set_by.to_string());
+        flag.set_updated_at(now);
+        flag
+    }
+}
+
+impl Flags for FlagAccess {
+
+    fn set(&self, key: String, value: FlagValue, set_by: String) -> Result<FeatureFlag, StateError> {
+        self.permissions.check_write(Store::Flags)?;
+        self.limiter.check(Store::Flags)?;
+        let storage_key = FlagAccess::key(&key)?;
+        let is_changed = self.read(&key)?
+            .is_none_or(|current| current.get_flag_value() != Some(value.clone()));
+        let flag = FlagAccess::create(key, value, &set_by, Utc::now().timestamp_millis() as u64);
+        let mut batch = Batch::default();
+        batch.insert(storage_key.as_bytes(), flag.write_to_bytes()?);
+        let seq = self.health.apply(&self.db, batch)?;
+        if is_changed {
+            let mut change = FlagsChange { seq, ..FlagsChange::default() };
+            change.keys.insert(flag.key.clone());
+            self.notifier.notify(change);
+        }
+        Ok(flag)
+    }
+
+    fn set_snapshot(&self, values: Vec<(String, FlagValue)>, set_by: String) -> Result<usize, StateError> {
+        self.permissions.check_write(Store::Flags)?;
+        self.limiter.check(Store::Flags)?;
+        let now = Utc::now().timestamp_millis() as u64;
+        let mut current: HashMap<String, FeatureFlag> = self.read_all()?.into_iter()
+            .map(|flag| (flag.key.clone(), flag))
+            .collect();
+        let mut batch = Batch::default();
+        let mut change = FlagsChange::default();
+        for (key, value) in values {
+            let storage_key = FlagAccess::key(&key)?;
+            let is_changed = current.remove(&key)
+                .is_none_or(|flag| flag.get_flag_value() != Some(value.clone()));
+            if is_changed {
+                change.keys.insert(key.clone());
+            }
+            let flag = FlagAccess::create(key, value, &set_by, now);
+            batch.insert(storage_key.as_bytes(), flag.write_to_bytes()?);
+        }
+        // what's left is not in the snapshot, and only those from the same source are replaced by it
+        for (key, flag) in current {
+            if flag.set_by == set_by {
+                batch.remove(FlagAccess::key(&key)?.as_bytes());
+                change.keys.insert(key);
+            }
+        }
+        change.seq = self.health.apply(&self.db, batch)?;
+        let count = change.keys.len();
+        self.notifier.notify(change);
+        Ok(count)
+    }
+
+    fn get(&self, key: String) -> Result<Option<FeatureFlag>, StateError> {
+        self.permissions.check_read(Store::Flags)?;
+        self.read(&key)
+    }
+
+    fn list(&self) -> Result<Vec<FeatureFlag>, StateError> {
+        self.permissions.check_read(Store::Flags)?;
+        self.read_all()
+    }
+
+    fn remove(&self, key: String) -> Result<bool, StateError> {
+        self.permissions.check_write(Store::Flags)?;
+        self.limiter.check(Store::Flags)?;
+        let storage_key = FlagAccess::key(&key)?;
+        let removed = self.health.transaction(&self.db, |tx| {
+            Ok(tx.remove(storage_key.as_bytes())?.map(|_| ()))
+        })?;
+        match removed {
+            Some((_, seq)) => {
+                let mut change = FlagsChange { seq, ..FlagsChange::default() };
+                change.keys.insert(key);
+                self.notifier.notify(change);
+                Ok(true)
+            },
+            None => Ok(false)
+        }
+    }
+
+    fn subscribe(&self) -> Result<Receiver<FlagsChange>, StateError> {
+        self.permissions.check_read(Store::Flags)?;
+        Ok(self.notifier.subscribe())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempdir::TempDir;
+    use crate::access::flags::{FlagValue, Flags};
+    use crate::access::permissions::{Permissions, Store};
+    use crate::errors::StateError;
+    use crate::storage::sled_access::SledStorage;
+
+    #[test]
+    fn set_and_get() {
+        let tmp_dir = TempDir::new("flags").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let store = access.get_flags();
+
+        assert!(store.get("history.new-layout".to_string()).unwrap().is_none());
+
+        store.set("history.new-layout".to_string(), FlagValue::Bool(true), "user".to_string()).unwrap();
+        store.set("sync.batch_size".to_string(), FlagValue::Int(50), "remote-config".to_string()).unwrap();
+
+        let act = store.get("history.new-layout".to_string()).unwrap().unwrap();
+        assert_eq!(act.get_flag_value(), Some(FlagValue::Bool(true)));
+        assert_eq!(act.set_by, "user");
+        assert!(act.updated_at > 0);
+
+        let all = store.list().unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].key, "history.new-layout");
+        assert_eq!(all[1].get_flag_value(), Some(FlagValue::Int(50)));
+
+        assert!(store.remove("history.new-layout".to_string()).unwrap());
+        assert!(!store.remove("history.new-layout".to_string()).unwrap());
+        assert!(store.get("history.new-layout".to_string()).unwrap().is_none());
+
+        let act = store.set("invalid key!".to_string(), FlagValue::Bool(true), "user".to_string());
+        assert!(act.is_err());
+    }
+
+    #[test]
+    fn replace_snapshot() {
+        let tmp_dir = TempDir::new("flags").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let store = access.get_flags();
+
+        store.set("history.new-layout".to_string(), FlagValue::Bool(true), "user".to_string()).unwrap();
+        let count = store.set_snapshot(vec![
+            ("sync.batch_size".to_string(), FlagValue::Int(50)),
+            ("ui.theme".to_string(), FlagValue::Text("dark".to_string())),
+        ], "remote-config".to_string()).unwrap();
+        assert_eq!(count, 2);
+
+        let count = store.set_snapshot(vec![
+            ("sync.batch_size".to_string(), FlagValue::Int(50)),
+            ("sync.ratio".to_string(), FlagValue::Float(0.5)),
+        ], "remote-config".to_string()).unwrap();
+        // one added and one removed
+        assert_eq!(count, 2);
+
+        let keys: Vec<String> = store.list().unwrap().into_iter().map(|f| f.key).collect();
+        assert_eq!(keys, vec!["history.new-layout", "sync.batch_size", "sync.ratio"]);
+    }
+
+    #[test]
+    fn notify_about_changes() {
+        let tmp_dir = TempDir::new("flags").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let store = access.get_flags();
+        let events = store.subscribe().unwrap();
+
+        store.set("history.new-layout".to_string(), FlagValue::Bool(true), "user".to_string()).unwrap();
+        // the same value, so nothing is changed
+        store.set("history.new-layout".to_string(), FlagValue::Bool(true), "user".to_string()).unwrap();
+        access.get_flags().remove("history.new-layout".to_string()).unwrap();
+
+        let act: Vec<_> = events.try_iter().collect();
+        assert_eq!(act.len(), 2);
+        assert!(act[0].keys.contains("history.new-layout"));
+        assert!(act[1].keys.contains("history.new-layout"));
+        assert!(act[0].seq < act[1].seq);
+        assert_eq!(act[1].seq, access.get_sequence().unwrap());
+    }
+
+    #[test]
+    fn deny_without_permission() {
+        let tmp_dir = TempDir::new("flags").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let restricted = access.restrict(Permissions::full().without(Store::Flags));
+
+        let act = restricted.get_flags().set("history.new-layout".to_string(), FlagValue::Bool(true), "user".to_string());
+        assert_eq!(act, Err(StateError::NotPermitted));
+        let act = restricted.get_flags().list();
+        assert_eq!(act, Err(StateError::NotPermitted));
+    }
+}
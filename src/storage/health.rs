@@ -0,0 +1,304 @@
+//!
+//! Degraded mode of the storage when the disk is full.
+//!
+//! Sled cannot write anything after it failed to write to a full disk, but it still can read the data. So when a write
+//! fails with `StateError::DiskFull` the storage switches to the degraded mode, where the wallet is still usable for
+//! viewing. The important writes, like transactions or the address book, keep failing with the error without trying to
+//! write, so the app can tell the user. The non-critical writes, i.e. the cache and the balances, are kept in memory
+//! instead, and the reads of those stores see them as if they were stored. They are lost if the app is closed before
+//! they are written with `SledStorage::retry_queued`. All writes of the stores go through `Health`, so any of them
+//! can switch it to the degraded mode, and it also assigns them the numbers of the `Sequence`.
+//!
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use chrono::{DateTime, Utc};
+use sled::Db;
+use sled::transaction::ConflictableTransactionResult;
+use crate::errors::StateError;
+use crate::storage::sequence::{Batch, Sequence, TransactionalTree};
+
+///
+/// Current health of the storage, see `SledStorage::health`
+#[derive(Debug, Clone, PartialEq)]
+pub struct HealthStatus {
+    /// The disk is full and the storage is in the degraded mode
+    pub degraded: bool,
+    /// When the storage switched to the degraded mode
+    pub since: Option<DateTime<Utc>>,
+    /// Number of the writes kept in memory until the storage can write again
+    pub queued: usize,
+}
+
+#[derive(Default)]
+struct State {
+    since: Option<DateTime<Utc>>,
+    /// Queued writes by the key, where `None` is a removal
+    queue: BTreeMap<String, Option<Vec<u8>>>,
+}
+
+///
+/// Degraded mode state shared by all handles of the same storage
+pub(crate) struct Health {
+    state: Mutex<State>,
+    sequence: Sequence,
+}
+
+impl Health {
+
+    ///
+    /// Health of the storage opened with the DB, starting with the sequence stored in it
+    pub(crate) fn open(db: &Db) -> Result<Health, StateError> {
+        Ok(Health {
+            state: Mutex::new(State::default()),
+            sequence: Sequence::load(db)?,
+        })
+    }
+
+    ///
+    /// Switch to the degraded mode, if it's not yet
+    pub(crate) fn degrade(&self) {
+        let mut state = self.state.lock().unwrap();
+        if state.since.is_none() {
+            log::warn!("Disk is full, storage is switched to read-only mode");
+            state.since = Some(Utc::now());
+        }
+    }
+
+    pub(crate) fn is_degraded(&self) -> bool {
+        self.state.lock().unwrap().since.is_some()
+    }
+
+    pub(crate) fn status(&self) -> HealthStatus {
+        let state = self.state.lock().unwrap();
+        HealthStatus {
+            degraded: state.since.is_some(),
+            since: state.since,
+            queued: state.queue.len(),
+        }
+    }
+
+    ///
+    /// Switch to the degraded mode if the result is a full disk error, and return the result as is
+    pub(crate) fn observe<T>(&self, result: Result<T, StateError>) -> Result<T, StateError> {
+        if let Err(StateError::DiskFull) = result {
+            self.degrade();
+        }
+        result
+    }
+
+    ///
+    /// Fail with `StateError::DiskFull` without trying to write, if it's in the degraded mode
+    fn check(&self) -> Result<(), StateError> {
+        if self.is_degraded() {
+            return Err(StateError::DiskFull)
+        }
+        Ok(())
+    }
+
+    ///
+    /// Write of a critical value, see `Sequence::apply`. Fails with `StateError::DiskFull` in the degraded mode.
+    pub(crate) fn apply(&self, db: &Db, batch: Batch) -> Result<u64, StateError> {
+        self.check()?;
+        self.observe(self.sequence.apply(db, batch))
+    }
+
+    ///
+    /// Same as `apply`, see `Sequence::apply_with`
+    pub(crate) fn apply_with<F>(&self, db: &Db, batch: Batch, f: F) -> Result<u64, StateError>
+        where F: FnOnce(u64, &mut Batch) -> Result<(), StateError> {
+        self.check()?;
+        self.observe(self.sequence.apply_with(db, batch, f))
+    }
+
+    ///
+    /// Same as `apply`, see `Sequence::transaction`
+    pub(crate) fn transaction<R, F>(&self, db: &Db, f: F) -> Result<Option<(R, u64)>, StateError>
+        where F: Fn(&TransactionalTree) -> ConflictableTransactionResult<Option<R>, StateError> {
+        self.check()?;
+        self.observe(self.sequence.transaction(db, f))
+    }
+
+    ///
+    /// Write of a maintenance batch, which doesn't change the data and so is not counted by the sequence, ex. a purge
+    /// of the expired values or a migration. Fails with `StateError::DiskFull` in the degraded mode.
+    pub(crate) fn apply_batch(&self, db: &Db, batch: Batch) -> Result<(), StateError> {
+        self.check()?;
+        self.observe(self.sequence.apply_unsequenced(db, batch))
+    }
+
+    ///
+    /// See `Sequence::exclusive`
+    pub(crate) fn exclusive<R, F>(&self, f: F) -> R where F: FnOnce(u64) -> R {
+        self.sequence.exclusive(f)
+    }
+
+    ///
+    /// Write or remove (with `None`) a non-critical value. In the degraded mode, or if the disk is full, it's queued
+    /// in memory instead. Returns the sequence number of the write, or `None` if it's queued.
+    pub(crate) fn write(&self, db: &Db, key: &str, value: Option<Vec<u8>>) -> Result<Option<u64>, StateError> {
+        let mut values = BTreeMap::new();
+        values.insert(key.to_string(), value);
+        self.write_all(db, values)
+    }
+
+    ///
+    /// Write or remove (with `None`) multiple non-critical values in one batch. Same as `write`, in the degraded mode,
+    /// or if the disk is full, all of them are queued in memory instead.
+    pub(crate) fn write_all(&self, db: &Db, values: BTreeMap<String, Option<Vec<u8>>>) -> Result<Option<u64>, StateError> {
+        if !self.is_degraded() {
+            let mut batch = Batch::default();
+            for (key, value) in values.iter() {
+                match value {
+                    Some(value) => batch.insert(key.as_bytes(), value.as_slice()),
+                    None => batch.remove(key.as_bytes()),
+                }
+            }
+            match self.observe(self.sequence.apply(db, batch)) {
+                Err(StateError::DiskFull) => {}
+                other => return other.map(Some),
+            }
+        }
+        self.state.lock().unwrap().queue.extend(values);
+        Ok(None)
+    }
+
+    ///
+    /// Read a non-critical value, preferring the queued one
+    pub(crate) fn read(&self, db: &Db, key: &str) -> Result<Option<Vec<u8>>, StateError> {
+        if let Some(value) = self.state.lock().unwrap().queue.get(key) {
+            return Ok(value.clone())
+        }
+        Ok(db.get(key)?.map(|v| v.to_vec()))
+    }
+
+    ///
+    /// Read all non-critical values with the key prefix, with the queued ones applied over the stored
+    pub(crate) fn scan_prefix(&self, db: &Db, prefix: &str) -> Result<BTreeMap<String, Vec<u8>>, StateError> {
+        let mut result = BTreeMap::new();
+        for row in db.scan_prefix(prefix) {
+            let (key, value) = row?;
+            if let Ok(key) = String::from_utf8(key.to_vec()) {
+                result.insert(key, value.to_vec());
+            }
+        }
+        let state = self.state.lock().unwrap();
+        for (key, value) in state.queue.range(prefix.to_string()..).take_while(|(k, _)| k.starts_with(prefix)) {
+            match value {
+                Some(value) => result.insert(key.clone(), value.clone()),
+                None => result.remove(key),
+            };
+        }
+        Ok(result)
+    }
+
+    ///
+    /// Try to write the queued values, and leave the degraded mode if it succeeds. Returns the number of written values.
+    pub(crate) fn retry(&self, db: &Db) -> Result<usize, StateError> {
+        let mut state = self.state.lock().unwrap();
+        let mut batch = Batch::default();
+        for (key, value) in state.queue.iter() {
+            match value {
+                Some(value) => batch.insert(key.as_bytes(), value.as_slice()),
+                None => batch.remove(key.as_bytes()),
+            }
+        }
+        let count = state.queue.len();
+        let result = if count > 0 {
+            self.sequence.apply(db, batch).map(|_| ())
+        } else {
+            Ok(())
+        }.and_then(|_| db.flush().map(|_| ()).map_err(StateError::from));
+        if let Err(e) = result {
+            if e == StateError::DiskFull && state.since.is_none() {
+                state.since = Some(Utc::now());
+            }
+            return Err(e)
+        }
+        state.queue.clear();
+        state.since = None;
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+    use chrono::{TimeZone, Utc};
+    use num_bigint::BigUint;
+    use tempdir::TempDir;
+    use crate::access::asset::AssetId;
+    use crate::access::balance::{Balance, Balances};
+    use crate::access::cache::Cache;
+    use crate::access::flags::{FlagValue, Flags};
+    use crate::access::transactions::Transactions;
+    use crate::errors::StateError;
+    use crate::storage::sled_access::SledStorage;
+
+    #[test]
+    fn detect_disk_full() {
+        let err = sled::Error::Io(io::Error::from(io::ErrorKind::StorageFull));
+        assert_eq!(StateError::from(err), StateError::DiskFull);
+        let err = sled::Error::Io(io::Error::from(io::ErrorKind::PermissionDenied));
+        assert_eq!(StateError::from(err), StateError::IOError);
+    }
+
+    #[test]
+    fn queue_when_degraded() {
+        let tmp_dir = TempDir::new("health").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let mut cache = access.get_cache();
+        cache.put("before".to_string(), "1".to_string(), None).unwrap();
+        assert!(!access.health().unwrap().degraded);
+
+        access.health.degrade();
+        cache.put("after".to_string(), "2".to_string(), None).unwrap();
+        cache.evict("before".to_string()).unwrap();
+        let balances = access.get_balance();
+        let balance = Balance {
+            address: "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826".to_string(),
+            blockchain: 100,
+            asset: AssetId::Native(100),
+            amount: BigUint::from(100u32),
+            ts: Utc.timestamp_millis_opt(1675123456789).unwrap(),
+            ..Balance::default()
+        };
+        balances.set(balance.clone()).unwrap();
+
+        let status = access.health().unwrap();
+        assert!(status.degraded);
+        assert!(status.since.is_some());
+        assert_eq!(status.queued, 3);
+        // visible as stored
+        assert_eq!(cache.get("after".to_string()).unwrap(), Some("2".to_string()));
+        assert_eq!(cache.get("before".to_string()).unwrap(), None);
+        assert_eq!(balances.list(balance.address.clone()).unwrap(), vec![balance.clone()]);
+        // but not written
+        assert!(access.db.get("cache:before").unwrap().is_some());
+        assert!(access.db.get("cache:after").unwrap().is_none());
+
+        assert_eq!(access.retry_queued().unwrap(), 3);
+        let status = access.health().unwrap();
+        assert!(!status.degraded);
+        assert_eq!(status.queued, 0);
+        assert!(access.db.get("cache:before").unwrap().is_none());
+        assert_eq!(cache.get("after".to_string()).unwrap(), Some("2".to_string()));
+        assert_eq!(balances.list(balance.address.clone()).unwrap(), vec![balance]);
+    }
+
+    #[test]
+    fn reject_critical_when_degraded() {
+        let tmp_dir = TempDir::new("health").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        access.health.degrade();
+        let seq = access.get_sequence().unwrap();
+
+        let act = access.get_flags().set("history.new-layout".to_string(), FlagValue::Bool(true), "user".to_string());
+        assert_eq!(act, Err(StateError::DiskFull));
+        let act = access.get_transactions().add_tag(100, "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string(), "rent".to_string());
+        assert_eq!(act, Err(StateError::DiskFull));
+
+        assert_eq!(access.get_sequence().unwrap(), seq);
+        assert!(access.get_flags().list().unwrap().is_empty());
+        assert_eq!(access.health().unwrap().queued, 0);
+    }
+}
@@ -1,14 +1,19 @@
 use std::collections::HashSet;
-use std::ops::Bound;
+use std::ops::{Bound, RangeBounds};
 use std::sync::Arc;
 use chrono::Utc;
 use protobuf::{Message, RepeatedField};
-use sled::{Batch, Db};
+use sled::{Db, IVec};
+use crate::access::pagination::{PageOrder, PageQuery};
 use crate::errors::StateError;
 use crate::proto::internal::{Indexes as proto_Indexes};
+use crate::storage::sequence::Batch;
 
 const IDX_BACKREF: &'static str = "idx_back:";
 
+/// Iterator over index entries, as pairs of the index key and the target key
+pub(crate) type IndexIter = Box<dyn Iterator<Item = sled::Result<(IVec, IVec)>>>;
+
 pub(crate) struct Indexing {}
 
 impl Indexing {
@@ -27,6 +32,51 @@ impl Indexing {
         Ok(())
     }
 
+    ///
+    /// Iterate over the index entries within the `bounds`, in the order requested by the page and starting after its
+    /// cursor. The indexes are expected to have the most recent entries first, i.e. a descending timestamp.
+    /// The cursor must be produced by the same `store` for a query with the same bounds, otherwise it's an error.
+    pub fn scan_page(db: &Db, store: &str, bounds: (Bound<String>, Bound<String>), page: &PageQuery) -> Result<IndexIter, StateError> {
+        Indexing::scan_ranges(db, store, vec![bounds], page)
+    }
+
+    ///
+    /// Same as `scan_page`, but over multiple ranges of the index, which must be in the order of the keys and not
+    /// overlap. The ranges are scanned one after another, as if it's a single range.
+    pub fn scan_ranges(db: &Db, store: &str, mut ranges: Vec<(Bound<String>, Bound<String>)>, page: &PageQuery) -> Result<IndexIter, StateError> {
+        if let Some(cursor) = &page.cursor {
+            let cursor = cursor.decode(store, page.order, &ranges)?;
+            let pos = ranges.iter().position(|r| r.contains(&cursor)).unwrap_or_default();
+            match page.order {
+                PageOrder::NewestFirst => {
+                    ranges.drain(0..pos);
+                    ranges[0].0 = Bound::Excluded(cursor);
+                }
+                PageOrder::OldestFirst => {
+                    ranges.truncate(pos + 1);
+                    ranges[pos].1 = Bound::Excluded(cursor);
+                }
+            }
+        }
+        let db = db.clone();
+        match page.order {
+            PageOrder::NewestFirst => Ok(Box::new(ranges.into_iter().flat_map(move |r| db.range(r)))),
+            PageOrder::OldestFirst => Ok(Box::new(ranges.into_iter().rev().flat_map(move |r| db.range(r).rev()))),
+        }
+    }
+
+    ///
+    /// Remove the backrefs of all target keys starting with `target_prefix`, without touching the indexes they point to.
+    /// Returns the number of removed backrefs.
+    pub fn remove_backrefs_by_prefix(target_prefix: &str, db: &Db, batch: &mut Batch) -> Result<usize, StateError> {
+        let mut count = 0;
+        for key in db.scan_prefix(format!("{}{}", IDX_BACKREF, target_prefix)).keys() {
+            batch.remove(key?);
+            count += 1;
+        }
+        Ok(count)
+    }
+
     ///
     /// Remove all indexes for the specified `target_key`
     pub fn remove_backref(target_key: String, db: Arc<Db>, batch: &mut Batch) -> Result<(), StateError> {
@@ -81,6 +131,13 @@ pub trait QueryRanges {
     ///
     /// Bounds of the indexes. Note that it query for _indexes_, not actual entries
     fn get_index_bounds(&self) -> (Bound<String>, Bound<String>);
+
+    ///
+    /// Ranges of the indexes within the bounds which may have the entries, in the order of the keys. By default it's
+    /// the whole bounds, but a query may skip some parts of it, ex. when the indexes have multiple sections.
+    fn get_index_ranges(&self) -> Vec<(Bound<String>, Bound<String>)> {
+        vec![self.get_index_bounds()]
+    }
 }
 
 pub struct  IndexConvert {
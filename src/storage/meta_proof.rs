@@ -0,0 +1,210 @@
+//!
+//! Proof of the history of a transaction meta, so users who co-manage a wallet can show when a label or a flag was set.
+//!
+//! Each change of the meta is stored as a `MetaRevision` linked to the previous one by a hash chain, where the hash of
+//! a revision is `sha256(prev_hash ++ recorded_at ++ meta)`, with `recorded_at` as a big-endian `u64` and `meta` as
+//! the encoded Protobuf message exactly as it's stored in the revision. The exported JSON lists the revisions from the
+//! first one, ex.:
+//!
+//! ```json
+//! {"blockchain": 100, "txId": "0x2f76...", "head": "9a1c...", "revisions": [
+//!   {"seq": 0, "recordedAt": 1647313850992, "timestamp": 1647313850992, "label": "Rent", "tags": [], "starred": false,
+//!    "hidden": false, "needsReview": false, "meta": "0880...", "prevHash": "", "hash": "9a1c..."}
+//! ]}
+//! ```
+//!
+//! The readable fields are there for convenience, while the proof is made by the encoded `meta`, so they must match it,
+//! as well as the `blockchain` and the `txId` of the document.
+//!
+use bitcoin::hashes::{sha256, Hash};
+use protobuf::{Message, ProtobufEnum};
+use serde_json::{json, Value};
+use crate::errors::{InvalidValueError, StateError};
+use crate::proto::transactions::{MetaRevision, TransactionMeta};
+
+///
+/// Hash of a revision, where `meta` is the encoded meta as it's stored in the revision
+pub(crate) fn get_hash(prev_hash: &[u8], recorded_at: u64, meta: &[u8]) -> Vec<u8> {
+    let mut data = prev_hash.to_vec();
+    data.extend_from_slice(&recorded_at.to_be_bytes());
+    data.extend_from_slice(meta);
+    sha256::Hash::hash(&data).to_vec()
+}
+
+///
+/// Readable fields of the revision, which are copied from the meta
+fn get_readable(meta: &TransactionMeta) -> Vec<(&'static str, Value)> {
+    vec![
+        ("timestamp", json!(meta.timestamp)),
+        ("label", json!(meta.label)),
+        ("tags", json!(meta.tags.to_vec())),
+        ("starred", json!(meta.starred)),
+        ("hidden", json!(meta.hidden)),
+        ("needsReview", json!(meta.needs_review)),
+    ]
+}
+
+fn invalid(name: &str, message: String) -> StateError {
+    StateError::InvalidValue(InvalidValueError::NameMessage(name.to_string(), message))
+}
+
+///
+/// Convert the history of the meta of a single transaction to a JSON document
+pub(crate) fn to_json(blockchain: u32, tx_id: &str, revisions: &[MetaRevision]) -> Result<String, StateError> {
+    let mut items = Vec::with_capacity(revisions.len());
+    for revision in revisions {
+        let meta = TransactionMeta::parse_from_bytes(&revision.meta)?;
+        let mut item = json!({
+            "seq": revision.seq,
+            "recordedAt": revision.recorded_at,
+            "meta": hex::encode(&revision.meta),
+            "prevHash": hex::encode(&revision.prev_hash),
+            "hash": hex::encode(&revision.hash),
+        });
+        for (name, value) in get_readable(&meta) {
+            item[name] = value;
+        }
+        items.push(item);
+    }
+    let head = revisions.last().map(|r| hex::encode(&r.hash)).unwrap_or_default();
+    let proof = json!({
+        "blockchain": blockchain,
+        "txId": tx_id,
+        "head": head,
+        "revisions": items,
+    });
+    Ok(proof.to_string())
+}
+
+///
+/// Check that the exported history is consistent, i.e. each revision is linked to the previous one, its hash matches
+/// the data, and the readable fields match the encoded meta. Returns the number of revisions, or
+/// `StateError::InvalidValue` with the field of the first inconsistency.
+pub fn verify(json: &str) -> Result<usize, StateError> {
+    let proof: Value = serde_json::from_str(json)
+        .map_err(|e| StateError::InvalidValue(InvalidValueError::Other(e.to_string())))?;
+    let blockchain = proof.get("blockchain").and_then(|v| v.as_u64())
+        .ok_or_else(|| invalid("blockchain", "Invalid blockchain".to_string()))?;
+    let tx_id = proof.get("txId").and_then(|v| v.as_str())
+        .ok_or_else(|| invalid("txId", "Invalid txId".to_string()))?;
+    let revisions = proof.get("revisions")
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| invalid("revisions", "No revisions".to_string()))?;
+    let mut prev_hash = String::new();
+    for (i, revision) in revisions.iter().enumerate() {
+        let field = |name: &str| revision.get(name).ok_or_else(|| invalid(name, format!("No {} in revision {}", name, i)));
+        let stated_prev = field("prevHash")?.as_str().unwrap_or_default();
+        if stated_prev != prev_hash {
+            return Err(invalid("prevHash", format!("Revision {} is not linked to the previous one", i)))
+        }
+        let recorded_at = field("recordedAt")?.as_u64()
+            .ok_or_else(|| invalid("recordedAt", format!("Invalid recordedAt in revision {}", i)))?;
+        let encoded = field("meta")?.as_str()
+            .and_then(|v| hex::decode(v).ok())
+            .ok_or_else(|| invalid("meta", format!("Invalid meta in revision {}", i)))?;
+        let meta = TransactionMeta::parse_from_bytes(&encoded)
+            .map_err(|_| invalid("meta", format!("Invalid meta in revision {}", i)))?;
+        let prev = hex::decode(&prev_hash)
+            .map_err(|_| invalid("prevHash", format!("Invalid prevHash in revision {}", i)))?;
+        let hash = hex::encode(get_hash(&prev, recorded_at, &encoded));
+        if field("hash")?.as_str() != Some(hash.as_str()) {
+            return Err(invalid("hash", format!("Invalid hash of revision {}", i)))
+        }
+        if meta.blockchain.value() as u64 != blockchain || meta.tx_id != tx_id {
+            return Err(invalid("txId", format!("Transaction doesn't match the meta in revision {}", i)))
+        }
+        for (name, value) in get_readable(&meta) {
+            if *field(name)? != value {
+                return Err(invalid(name, format!("Field {} doesn't match the meta in revision {}", name, i)))
+            }
+        }
+        prev_hash = hash;
+    }
+    if proof.get("head").and_then(|v| v.as_str()) != Some(prev_hash.as_str()) {
+        return Err(invalid("head", "Head is not the last revision".to_string()))
+    }
+    Ok(revisions.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use protobuf::Message;
+    use crate::proto::transactions::{BlockchainId, MetaRevision, TransactionMeta};
+    use crate::storage::meta_proof::{get_hash, invalid, to_json, verify};
+
+    fn revision(seq: u64, label: &str, prev_hash: Vec<u8>) -> MetaRevision {
+        let mut meta = TransactionMeta::new();
+        meta.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        meta.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        meta.timestamp = 1_647_313_850_992 + seq;
+        meta.label = label.to_string();
+        let mut revision = MetaRevision::new();
+        revision.seq = seq;
+        revision.recorded_at = 1_647_313_850_992 + seq;
+        revision.meta = meta.write_to_bytes().unwrap();
+        revision.hash = get_hash(&prev_hash, revision.recorded_at, &revision.meta);
+        revision.prev_hash = prev_hash;
+        revision
+    }
+
+    #[test]
+    fn verify_exported_chain() {
+        let first = revision(0, "Rent", vec![]);
+        let second = revision(1, "Rent for March", first.hash.clone());
+        let json = to_json(100, "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b", &[first, second]).unwrap();
+        assert_eq!(verify(json.as_str()), Ok(2));
+    }
+
+    #[test]
+    fn verify_stored_encoding() {
+        let mut first = revision(0, "Rent", vec![]);
+        // same meta encoded with the label first, which is different from how it's encoded again after parsing
+        let mut label = TransactionMeta::new();
+        label.label = "Rent".to_string();
+        let mut meta = TransactionMeta::parse_from_bytes(&first.meta).unwrap();
+        meta.clear_label();
+        let mut encoded = label.write_to_bytes().unwrap();
+        encoded.extend(meta.write_to_bytes().unwrap());
+        assert_ne!(TransactionMeta::parse_from_bytes(&encoded).unwrap().write_to_bytes().unwrap(), encoded);
+        first.hash = get_hash(&[], first.recorded_at, &encoded);
+        first.meta = encoded;
+
+        let json = to_json(100, "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b", &[first]).unwrap();
+        assert_eq!(verify(json.as_str()), Ok(1));
+    }
+
+    #[test]
+    fn detect_changed_label() {
+        let first = revision(0, "Rent", vec![]);
+        let second = revision(1, "Rent for March", first.hash.clone());
+        let json = to_json(100, "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b", &[first, second]).unwrap();
+        let changed = json.replace("\"label\":\"Rent\"", "\"label\":\"Salary\"");
+        assert!(verify(changed.as_str()).is_err());
+    }
+
+    #[test]
+    fn detect_changed_readable_fields() {
+        let first = revision(0, "Rent", vec![]);
+        let json = to_json(100, "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b", &[first]).unwrap();
+        assert_eq!(verify(json.as_str()), Ok(1));
+
+        let changed = json.replace("\"starred\":false", "\"starred\":true");
+        assert_eq!(verify(changed.as_str()), Err(invalid("starred", "Field starred doesn't match the meta in revision 0".to_string())));
+        let changed = json.replace("\"tags\":[]", "\"tags\":[\"salary\"]");
+        assert_eq!(verify(changed.as_str()), Err(invalid("tags", "Field tags doesn't match the meta in revision 0".to_string())));
+        let changed = json.replace("\"timestamp\":1647313850992", "\"timestamp\":1647313850993");
+        assert_eq!(verify(changed.as_str()), Err(invalid("timestamp", "Field timestamp doesn't match the meta in revision 0".to_string())));
+        let changed = json.replace("\"blockchain\":100", "\"blockchain\":101");
+        assert_eq!(verify(changed.as_str()), Err(invalid("txId", "Transaction doesn't match the meta in revision 0".to_string())));
+        let changed = json.replace("0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b", "0x5f88e4e1ab5d1a3ccb1e0ddfb6f9b5cc0ad43eb7ae1ef1b38ba6b6fcd4a6fb4c");
+        assert_eq!(verify(changed.as_str()), Err(invalid("txId", "Transaction doesn't match the meta in revision 0".to_string())));
+    }
+
+    #[test]
+    fn detect_removed_revision() {
+        let first = revision(0, "Rent", vec![]);
+        let second = revision(1, "Rent for March", first.hash.clone());
+        let json = to_json(100, "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b", &[second]).unwrap();
+        assert!(verify(json.as_str()).is_err());
+    }
+}
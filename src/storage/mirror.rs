@@ -0,0 +1,330 @@
+//!
+//! Write-behind mirror of the storage to a secondary location, ex. an external drive or a network share, as a
+//! continuous on-site backup.
+//!
+//! The mirror runs in a background thread and replays each write to the primary DB on a secondary sled DB. The writes
+//! are not delayed by the mirror, because the changes are moved to an unbounded queue in memory as soon as they are
+//! made, so the secondary may be behind the primary, which is reported as the lag in the `MirrorStatus`. The changes
+//! made close together are replayed as a single batch, so a write of multiple values (ex. a transaction with its
+//! indexes) gets to the secondary at once. If the secondary location is not available, or a write to it fails, the
+//! mirror retries to open it later and catches up by copying the whole difference from the primary.
+//!
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use sled::{Batch, Db, Event, Subscriber};
+use crate::errors::StateError;
+use crate::storage::sequence;
+
+/// Maximum time to wait for a write to the primary, so the mirror can check if it should stop
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// Maximum pause between the changes replayed as a single batch. The changes of one write come one by one, but
+/// without a pause between them.
+const SETTLE_INTERVAL: Duration = Duration::from_millis(10);
+/// Maximum number of changes replayed as a single batch
+const REPLAY_BATCH: usize = 10_000;
+
+///
+/// Current state of the mirror
+#[derive(Debug, Clone, PartialEq)]
+pub struct MirrorStatus {
+    /// The secondary location is open and receives the writes
+    pub connected: bool,
+    /// Sequence of the last write to the primary, see `SledStorage::get_sequence`
+    pub primary_seq: u64,
+    /// Sequence of the last write replayed on the secondary
+    pub mirrored_seq: u64,
+    /// Number of writes to the primary not yet replayed on the secondary
+    pub lag: u64,
+    /// Moment of the last full copy to the secondary, made when it's opened
+    pub last_sync: Option<DateTime<Utc>>,
+    /// Last error of the secondary location, if it's not connected because of it
+    pub last_error: Option<String>,
+}
+
+///
+/// A running mirror, which is stopped when it's dropped
+pub struct Mirror {
+    primary: Arc<Db>,
+    status: Arc<Mutex<MirrorStatus>>,
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Mirror {
+
+    ///
+    /// Start mirroring the `primary` to the DB at `path`. If it cannot be opened it's retried each `retry` interval.
+    pub(crate) fn start(primary: Arc<Db>, path: PathBuf, retry: Duration) -> Mirror {
+        let status = Arc::new(Mutex::new(MirrorStatus {
+            connected: false,
+            primary_seq: 0,
+            mirrored_seq: 0,
+            lag: 0,
+            last_sync: None,
+            last_error: None,
+        }));
+        let stop = Arc::new(AtomicBool::new(false));
+        let worker = Worker {
+            primary: primary.clone(),
+            path,
+            retry,
+            status: status.clone(),
+            stop: stop.clone(),
+        };
+        let handle = thread::spawn(move || worker.run());
+        Mirror {
+            primary,
+            status,
+            stop,
+            handle: Some(handle),
+        }
+    }
+
+    ///
+    /// Current state of the mirror, with the lag calculated at the moment of the call
+    pub fn status(&self) -> MirrorStatus {
+        let mut status = self.status.lock().unwrap().clone();
+        status.primary_seq = sequence::get(&self.primary).unwrap_or(status.primary_seq);
+        status.lag = status.primary_seq.saturating_sub(status.mirrored_seq);
+        status
+    }
+
+    ///
+    /// Stop the mirror and wait until the secondary DB is closed. The writes not replayed yet are left behind, and
+    /// are copied when the mirror is started again.
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            if handle.join().is_err() {
+                log::warn!("Mirror thread failed");
+            }
+        }
+    }
+}
+
+impl Drop for Mirror {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+struct Worker {
+    primary: Arc<Db>,
+    path: PathBuf,
+    retry: Duration,
+    status: Arc<Mutex<MirrorStatus>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl Worker {
+
+    fn run(&self) {
+        while !self.stop.load(Ordering::Relaxed) {
+            if let Err(e) = self.mirror() {
+                log::warn!("Mirror is disconnected: {:?}", e);
+                let mut status = self.status.lock().unwrap();
+                status.connected = false;
+                status.last_error = Some(format!("{:?}", e));
+            }
+            self.sleep(self.retry);
+        }
+    }
+
+    fn sleep(&self, duration: Duration) {
+        let mut left = duration;
+        while !left.is_zero() && !self.stop.load(Ordering::Relaxed) {
+            let step = left.min(POLL_INTERVAL);
+            thread::sleep(step);
+            left -= step;
+        }
+    }
+
+    ///
+    /// Open the secondary, catch up with the primary, and then replay the writes until it's stopped or fails
+    fn mirror(&self) -> Result<(), StateError> {
+        let secondary = sled::open(&self.path)?;
+        // subscribe before the copy, so a write made during it isn't lost. Replaying it again is harmless.
+        let (sender, events) = mpsc::channel();
+        let done = Arc::new(AtomicBool::new(false));
+        let drain = Worker::drain(self.primary.watch_prefix(vec![]), sender, done.clone());
+        let result = self.replay(&secondary, &events);
+        done.store(true, Ordering::Relaxed);
+        drop(events);
+        if drain.join().is_err() {
+            log::warn!("Mirror queue thread failed");
+        }
+        result
+    }
+
+    ///
+    /// Move the events of the primary to the unbounded queue until it's `done`. Sled has a limited buffer for each
+    /// subscriber and blocks the writes when it's full, so the events must be taken as soon as they are made, even
+    /// if the secondary is slow or is still catching up.
+    fn drain(mut subscriber: Subscriber, sender: Sender<Event>, done: Arc<AtomicBool>) -> JoinHandle<()> {
+        thread::spawn(move || {
+            while !done.load(Ordering::Relaxed) {
+                if let Ok(event) = subscriber.next_timeout(POLL_INTERVAL) {
+                    if sender.send(event).is_err() {
+                        break
+                    }
+                }
+            }
+        })
+    }
+
+    fn replay(&self, secondary: &Db, events: &Receiver<Event>) -> Result<(), StateError> {
+        self.catch_up(secondary)?;
+        {
+            let mut status = self.status.lock().unwrap();
+            status.connected = true;
+            status.last_sync = Some(Utc::now());
+            status.last_error = None;
+            status.mirrored_seq = sequence::get(secondary)?;
+        }
+        while !self.stop.load(Ordering::Relaxed) {
+            let mut next = match events.recv_timeout(POLL_INTERVAL) {
+                Ok(event) => Some(event),
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(RecvTimeoutError::Disconnected) => break,
+            };
+            let mut batch = Batch::default();
+            let mut size = 0;
+            while let Some(event) = next {
+                match event {
+                    Event::Insert { key, value } => batch.insert(key, value),
+                    Event::Remove { key } => batch.remove(key),
+                }
+                size += 1;
+                next = if size < REPLAY_BATCH {
+                    events.recv_timeout(SETTLE_INTERVAL).ok()
+                } else {
+                    None
+                };
+            }
+            secondary.apply_batch(batch)?;
+            self.status.lock().unwrap().mirrored_seq = sequence::get(secondary)?;
+        }
+        secondary.flush()?;
+        Ok(())
+    }
+
+    ///
+    /// Copy the values changed since the last run, and remove the values which don't exist in the primary anymore
+    fn catch_up(&self, secondary: &Db) -> Result<(), StateError> {
+        let mut batch = Batch::default();
+        for row in self.primary.iter() {
+            let (key, value) = row?;
+            if secondary.get(&key)?.as_ref() != Some(&value) {
+                batch.insert(key, value);
+            }
+        }
+        for key in secondary.iter().keys() {
+            let key = key?;
+            if !self.primary.contains_key(&key)? {
+                batch.remove(key);
+            }
+        }
+        secondary.apply_batch(batch)?;
+        secondary.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::thread;
+    use std::time::{Duration, Instant};
+    use tempdir::TempDir;
+    use crate::access::cache::Cache;
+    use crate::storage::mirror::Mirror;
+    use crate::storage::sled_access::SledStorage;
+
+    fn wait_for<F: Fn() -> bool>(f: F) {
+        let start = Instant::now();
+        while !f() {
+            assert!(start.elapsed() < Duration::from_secs(10), "timeout");
+            thread::sleep(Duration::from_millis(20));
+        }
+    }
+
+    #[test]
+    fn replay_writes() {
+        let tmp_dir = TempDir::new("mirror").unwrap();
+        let access = SledStorage::open(tmp_dir.path().join("primary")).unwrap();
+        let mut cache = access.get_cache();
+        cache.put("before".to_string(), "1".to_string(), None).unwrap();
+
+        let mirror = access.start_mirror(tmp_dir.path().join("secondary"), Duration::from_millis(50)).unwrap();
+        wait_for(|| mirror.status().connected);
+
+        cache.put("after".to_string(), "2".to_string(), None).unwrap();
+        cache.put("before".to_string(), "3".to_string(), None).unwrap();
+        wait_for(|| mirror.status().lag == 0);
+        let status = mirror.status();
+        assert!(status.mirrored_seq > 0);
+        assert!(status.last_sync.is_some());
+        mirror.stop();
+
+        let db = sled::open(tmp_dir.path().join("secondary")).unwrap();
+        for key in ["before", "after"] {
+            let key = format!("cache:{}", key);
+            assert_eq!(db.get(&key).unwrap(), access.db.get(&key).unwrap());
+        }
+        assert_eq!(db.len(), access.db.len());
+    }
+
+    #[test]
+    fn replay_large_write() {
+        let tmp_dir = TempDir::new("mirror").unwrap();
+        let access = SledStorage::open(tmp_dir.path().join("primary")).unwrap();
+        let mirror = access.start_mirror(tmp_dir.path().join("secondary"), Duration::from_millis(50)).unwrap();
+        wait_for(|| mirror.status().connected);
+
+        // much more than the buffer of a sled subscriber, which would block the write if not taken in time
+        let mut batch = sled::Batch::default();
+        for i in 0..5_000 {
+            batch.insert(format!("test:{:05}", i).as_bytes(), "value".as_bytes());
+        }
+        access.db.apply_batch(batch).unwrap();
+        let mut cache = access.get_cache();
+        cache.put("after".to_string(), "1".to_string(), None).unwrap();
+        wait_for(|| mirror.status().lag == 0);
+        mirror.stop();
+
+        let db = sled::open(tmp_dir.path().join("secondary")).unwrap();
+        assert_eq!(db.scan_prefix("test:").count(), 5_000);
+        assert_eq!(db.len(), access.db.len());
+    }
+
+    #[test]
+    fn catch_up_on_reconnect() {
+        let tmp_dir = TempDir::new("mirror").unwrap();
+        let access = SledStorage::open(tmp_dir.path().join("primary")).unwrap();
+        let secondary = tmp_dir.path().join("secondary");
+        // a file in place of the DB, as if the drive is not available
+        fs::write(&secondary, "").unwrap();
+
+        let mirror: Mirror = access.start_mirror(secondary.clone(), Duration::from_millis(50)).unwrap();
+        let mut cache = access.get_cache();
+        cache.put("test".to_string(), "1".to_string(), None).unwrap();
+        wait_for(|| mirror.status().last_error.is_some());
+        let status = mirror.status();
+        assert!(!status.connected);
+        assert!(status.lag > 0);
+
+        fs::remove_file(&secondary).unwrap();
+        wait_for(|| mirror.status().connected && mirror.status().lag == 0);
+        assert!(mirror.status().last_error.is_none());
+    }
+}
@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use log::warn;
+use sled::Db;
+use crate::access::nonces::Nonces;
+use crate::access::permissions::{Permissions, Store};
+use crate::errors::{InvalidValueError, StateError};
+use crate::storage::rate_limit::RateLimiter;
+use crate::storage::health::Health;
+use crate::validate;
+use crate::storage::sequence::Batch;
+
+///
+/// # Storage:
+///
+/// - `nonce:<BLOCKCHAIN>/<ADDRESS>` with the highest used nonce as a big-endian `u64`, and the address in lowercase
+///
+const PREFIX_KEY: &str = "nonce:";
+
+pub struct NonceAccess {
+    pub(crate) db: Arc<Db>,
+    pub(crate) permissions: Permissions,
+    pub(crate) limiter: Arc<RateLimiter>,
+    pub(crate) health: Arc<Health>,
+}
+
+fn key(blockchain: u32, address: &str) -> Result<String, StateError> {
+    let address = address.trim();
+    validate::check_ethereum_address(address)
+        .map_err(|_| InvalidValueError::Name("address".to_string()))?;
+    Ok(format!("{}{}/{}", PREFIX_KEY, blockchain, address.to_lowercase()))
+}
+
+fn decode(value: &[u8]) -> u64 {
+    <[u8; 8]>::try_from(value)
+        .map(u64::from_be_bytes)
+        .unwrap_or(0)
+}
+
+///
+/// Put the update of the stored nonces into the batch, for each of `(blockchain, address, nonce)` larger than the current
+/// one. It's called by the transactions store with the nonces of the submitted transactions, so they are written together,
+/// where the permissions are checked by the caller. An invalid address is skipped, as it doesn't make the transaction
+/// invalid. Returns the number of nonces to update.
+pub(crate) fn put_at_least(db: &Db, nonces: Vec<(u32, String, u64)>, batch: &mut Batch) -> Result<usize, StateError> {
+    let mut highest: HashMap<String, u64> = HashMap::new();
+    for (blockchain, address, nonce) in nonces {
+        let key = match key(blockchain, address.as_str()) {
+            Ok(key) => key,
+            Err(e) => {
+                warn!("Skip nonce of {}: {:?}", address, e);
+                continue
+            }
+        };
+        let current = match highest.get(&key) {
+            Some(current) => Some(*current),
+            None => db.get(&key)?.map(|b| decode(b.as_ref()))
+        };
+        if current.is_none_or(|current| current < nonce) {
+            highest.insert(key, nonce);
+        }
+    }
+    let count = highest.len();
+    for (key, nonce) in highest {
+        batch.insert(key.as_bytes(), nonce.to_be_bytes().to_vec());
+    }
+    Ok(count)
+}
+
+///
+/// Update the stored nonce to `nonce` if it's larger than the current
+fn set_at_least(db: &Db, health: &Health, blockchain: u32, address: &str, nonce: u64) -> Result<(), StateError> {
+    let key = key(blockchain, address)?;
+    health.transaction(db, |tx| {
+        if tx.get(&key)?.is_some_and(|b| decode(b.as_ref()) >= nonce) {
+            return Ok(None)
+        }
+        tx.insert(key.as_bytes(), &nonce.to_be_bytes())?;
+        Ok(Some(()))
+    })?;
+    Ok(())
+}
+
+impl Nonces for NonceAccess {
+
+    fn set_at_least(&self, blockchain: u32, address: String, nonce: u64) -> Result<(), StateError> {
+        self.permissions.check_write(Store::Nonces)?;
+        self.limiter.check(Store::Nonces)?;
+        set_at_least(&self.db, &self.health, blockchain, address.as_str(), nonce)
+    }
+
+    fn get(&self, blockchain: u32, address: String) -> Result<Option<u64>, StateError> {
+        self.permissions.check_read(Store::Nonces)?;
+        let key = key(blockchain, address.as_str())?;
+        Ok(self.db.get(key)?.map(|b| decode(b.as_ref())))
+    }
+
+    fn get_next_nonce(&self, blockchain: u32, address: String) -> Result<u64, StateError> {
+        match self.get(blockchain, address)? {
+            Some(nonce) => Ok(nonce + 1),
+            None => Ok(0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempdir::TempDir;
+    use crate::access::nonces::Nonces;
+    use crate::access::transactions::Transactions;
+    use crate::errors::{InvalidValueError, StateError};
+    use crate::proto::transactions::{BlockchainId, NonceRef, State, Transaction as proto_Transaction};
+    use crate::storage::sled_access::SledStorage;
+
+    #[test]
+    fn keep_highest_nonce() {
+        let tmp_dir = TempDir::new("nonce").unwrap();
+        let store = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let nonces = store.get_nonces();
+        let address = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
+
+        assert_eq!(nonces.get(100, address.clone()).unwrap(), None);
+        assert_eq!(nonces.get_next_nonce(100, address.clone()).unwrap(), 0);
+
+        nonces.set_at_least(100, address.clone(), 5).unwrap();
+        nonces.set_at_least(100, "0xA0B86991C6218B36C1D19D4A2E9EB0CE3606EB48".to_string(), 3).unwrap();
+        assert_eq!(nonces.get(100, address.clone()).unwrap(), Some(5));
+        assert_eq!(nonces.get_next_nonce(100, address.clone()).unwrap(), 6);
+        // another blockchain has its own nonces
+        assert_eq!(nonces.get_next_nonce(101, address.clone()).unwrap(), 0);
+
+        assert_eq!(
+            nonces.set_at_least(100, "0xa0b86991".to_string(), 1),
+            Err(StateError::InvalidValue(InvalidValueError::Name("address".to_string())))
+        );
+    }
+
+    #[test]
+    fn update_from_submitted_tx() {
+        let tmp_dir = TempDir::new("nonce").unwrap();
+        let store = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let nonces = store.get_nonces();
+        let transactions = store.get_transactions();
+
+        let mut tx1 = proto_Transaction::new();
+        tx1.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx1.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        tx1.since_timestamp = 1_647_313_850_992;
+        let mut nonce_ref = NonceRef::new();
+        nonce_ref.from = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
+        nonce_ref.nonce = 7;
+        tx1.set_nonce_ref(nonce_ref);
+        let mut tx2 = tx1.clone();
+        tx2.tx_id = "0x5f88e4e1ab5d1a3ccb1e0ddfb6f9b5cc0ad43eb7ae1ef1b38ba6b6fcd4a6fb4c".to_string();
+        tx2.mut_nonce_ref().nonce = 8;
+        // a dropped one doesn't use its nonce, otherwise the next tx would wait for a gap that is never filled
+        let mut tx3 = tx1.clone();
+        tx3.tx_id = "0x9b11c453e5d0caf4fb6d192624360def7bd1e81b2f761cbf069962cf3a82ab0d".to_string();
+        tx3.mut_nonce_ref().nonce = 9;
+        tx3.state = State::DROPPED;
+        transactions.submit(vec![tx2, tx1, tx3]).unwrap();
+
+        assert_eq!(nonces.get_next_nonce(100, "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string()).unwrap(), 9);
+    }
+}
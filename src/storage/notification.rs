@@ -0,0 +1,64 @@
+//!
+//! Notifications about modified data, so the app can update what it shows instead of polling the store.
+//!
+use std::sync::Mutex;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use crate::access::balance::BalancesChange;
+use crate::access::flags::FlagsChange;
+use crate::access::transactions::TransactionsChange;
+
+///
+/// A change sent to the subscribers
+pub(crate) trait Notification: Clone {
+    fn is_empty(&self) -> bool;
+}
+
+impl Notification for TransactionsChange {
+    fn is_empty(&self) -> bool {
+        TransactionsChange::is_empty(self)
+    }
+}
+
+impl Notification for FlagsChange {
+    fn is_empty(&self) -> bool {
+        FlagsChange::is_empty(self)
+    }
+}
+
+impl Notification for BalancesChange {
+    fn is_empty(&self) -> bool {
+        BalancesChange::is_empty(self)
+    }
+}
+
+///
+/// Subscribers to the changes of a store, shared by all handles of the same storage
+pub(crate) struct Notifier<T: Notification = TransactionsChange> {
+    subscribers: Mutex<Vec<Sender<T>>>,
+}
+
+impl<T: Notification> Default for Notifier<T> {
+    fn default() -> Self {
+        Notifier { subscribers: Mutex::new(Vec::new()) }
+    }
+}
+
+impl<T: Notification> Notifier<T> {
+
+    pub(crate) fn subscribe(&self) -> Receiver<T> {
+        let (sender, receiver) = channel();
+        self.subscribers.lock().unwrap().push(sender);
+        receiver
+    }
+
+    ///
+    /// Send the change to all current subscribers, forgetting those who dropped their receiver. Does nothing if the
+    /// change is empty.
+    pub(crate) fn notify(&self, change: T) {
+        if change.is_empty() {
+            return
+        }
+        self.subscribers.lock().unwrap()
+            .retain(|s| s.send(change.clone()).is_ok());
+    }
+}
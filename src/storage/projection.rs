@@ -0,0 +1,79 @@
+use std::collections::{BTreeMap, HashSet};
+use protobuf::{CodedInputStream, Message};
+use crate::errors::StateError;
+
+///
+/// Decode only the specified `fields` (by their protobuf field number) of the message. All other fields are skipped
+/// in the stream without being decoded, which is much cheaper for large nested fields.
+pub(crate) fn decode_projected<M: Message>(bytes: &[u8], fields: &HashSet<u32>) -> Result<M, StateError> {
+    let mut filtered = Vec::with_capacity(bytes.len());
+    let mut input = CodedInputStream::from_bytes(bytes);
+    while !input.eof()? {
+        let start = input.pos() as usize;
+        let (field, wire_type) = input.read_tag_unpack()?;
+        input.skip_field(wire_type)?;
+        let end = input.pos() as usize;
+        if fields.contains(&field) {
+            filtered.extend_from_slice(&bytes[start..end]);
+        }
+    }
+    Ok(M::parse_from_bytes(filtered.as_slice())?)
+}
+
+///
+/// Find the fields (by their protobuf field number) which are encoded differently in two messages of the same type.
+/// A repeated field is compared as a whole, including the order of its elements.
+pub(crate) fn get_changed_fields(a: &[u8], b: &[u8]) -> Result<Vec<u32>, StateError> {
+    let a = split_fields(a)?;
+    let b = split_fields(b)?;
+    let mut changed: Vec<u32> = a.keys().chain(b.keys())
+        .filter(|field| a.get(field) != b.get(field))
+        .cloned()
+        .collect();
+    changed.sort();
+    changed.dedup();
+    Ok(changed)
+}
+
+fn split_fields(bytes: &[u8]) -> Result<BTreeMap<u32, Vec<u8>>, StateError> {
+    let mut result: BTreeMap<u32, Vec<u8>> = BTreeMap::new();
+    let mut input = CodedInputStream::from_bytes(bytes);
+    while !input.eof()? {
+        let start = input.pos() as usize;
+        let (field, wire_type) = input.read_tag_unpack()?;
+        input.skip_field(wire_type)?;
+        let end = input.pos() as usize;
+        result.entry(field).or_default().extend_from_slice(&bytes[start..end]);
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use protobuf::Message;
+    use crate::proto::transactions::{BlockchainId, Change, State, Transaction};
+    use super::decode_projected;
+
+    #[test]
+    fn decode_only_selected_fields() {
+        let mut tx = Transaction::new();
+        tx.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        tx.since_timestamp = 1_647_313_850_992;
+        tx.state = State::CONFIRMED;
+        let mut change = Change::new();
+        change.address = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
+        tx.changes.push(change);
+        let bytes = tx.write_to_bytes().unwrap();
+
+        let fields: HashSet<u32> = vec![2, 6].into_iter().collect();
+        let act: Transaction = decode_projected(bytes.as_slice(), &fields).unwrap();
+
+        assert_eq!(act.tx_id, tx.tx_id);
+        assert_eq!(act.state, State::CONFIRMED);
+        assert_eq!(act.blockchain, BlockchainId::CHAIN_UNSPECIFIED);
+        assert_eq!(act.since_timestamp, 0);
+        assert!(act.changes.is_empty());
+    }
+}
@@ -0,0 +1,292 @@
+use std::str::FromStr;
+use std::sync::Arc;
+use chrono::Utc;
+use log::warn;
+use protobuf::{Message, RepeatedField};
+use sled::Db;
+use uuid::Uuid;
+use crate::access::permissions::{Permissions, Store};
+use crate::access::quarantine::Quarantine;
+use crate::access::validation::Validators;
+use crate::access::integrity::References;
+use crate::errors::StateError;
+use crate::proto::addressbook::BookItem as proto_BookItem;
+use crate::proto::quarantine::{QuarantineBundle, QuarantinedRecord, StoreType};
+use crate::proto::transactions::Transaction as proto_Transaction;
+use crate::storage::tuning::Profiler;
+use crate::storage::indexing::Indexing;
+use crate::storage::transaction_store::TransactionsAccess;
+use crate::storage::adressbook_store::AddressBookAccess;
+use crate::storage::xpubpos_store::XPubPositionAccess;
+use crate::storage::rate_limit::RateLimiter;
+use crate::storage::health::Health;
+use crate::storage::notification::Notifier;
+use crate::storage::ttl_policy::TtlPolicy;
+use crate::storage::sequence::Batch;
+
+///
+/// # Storage:
+///
+/// - `quarantine:<ORIGINAL_KEY>` to store a `QuarantinedRecord` with the original key and value
+///
+const PREFIX_KEY: &str = "quarantine:";
+
+pub struct QuarantineAccess {
+    pub(crate) db: Arc<Db>,
+    pub(crate) permissions: Permissions,
+    pub(crate) limiter: Arc<RateLimiter>,
+    pub(crate) health: Arc<Health>,
+}
+
+fn get_permission_store(store: StoreType) -> Option<Store> {
+    match store {
+        StoreType::STORE_UNSPECIFIED => None,
+        StoreType::STORE_TRANSACTIONS => Some(Store::Transactions),
+        StoreType::STORE_ADDRESS_BOOK => Some(Store::AddressBook),
+    }
+}
+
+fn get_key(original: &[u8]) -> Vec<u8> {
+    let mut key = PREFIX_KEY.as_bytes().to_vec();
+    key.extend_from_slice(original);
+    key
+}
+
+///
+/// Move the record at `key` from its store into the quarantine, removing also its indexes. It's called by the stores
+/// when they fail to decode a record, and it's a best effort action, so a failure is only logged, as well as when the
+/// storage is not permitted to change the store.
+pub(crate) fn put<E: std::fmt::Debug>(db: &Arc<Db>, health: &Health, permissions: &Permissions, store: StoreType, key: &str, error: E) {
+    let is_permitted = get_permission_store(store).is_some_and(|s| permissions.can_write(s));
+    if !is_permitted {
+        warn!("Not permitted to quarantine {}", key);
+        return
+    }
+    let value = match db.get(key) {
+        Ok(Some(value)) => value,
+        // already removed or moved by another thread
+        Ok(None) => return,
+        Err(e) => {
+            warn!("Failed to quarantine {}: {:?}", key, e);
+            return
+        }
+    };
+    let mut record = QuarantinedRecord::new();
+    record.store = store;
+    record.key = key.as_bytes().to_vec();
+    record.value = value.to_vec();
+    record.error = format!("{:?}", error);
+    record.timestamp = Utc::now().timestamp_millis() as u64;
+
+    let result = record.write_to_bytes()
+        .map_err(StateError::from)
+        .and_then(|record_bytes| {
+            let mut batch = Batch::default();
+            Indexing::remove_backref(key.to_string(), db.clone(), &mut batch)?;
+            batch.remove(key.as_bytes());
+            batch.insert(get_key(key.as_bytes()), record_bytes);
+            health.apply_batch(db, batch)
+        });
+    match result {
+        Ok(_) => warn!("Quarantined unreadable record {}: {:?}", key, error),
+        Err(e) => warn!("Failed to quarantine {}: {:?}", key, e),
+    }
+}
+
+impl QuarantineAccess {
+
+    ///
+    /// Put the record back into its store, if it can be decoded now. Returns `true` if the record was processed and
+    /// can be removed from the quarantine.
+    fn restore(&self, record: &QuarantinedRecord, batch: &mut Batch) -> Result<bool, StateError> {
+        let key = String::from_utf8(record.key.clone())
+            .map_err(|_| StateError::CorruptedValue)?;
+        match record.store {
+            StoreType::STORE_UNSPECIFIED => Ok(false),
+            StoreType::STORE_TRANSACTIONS => {
+                let tx = match proto_Transaction::parse_from_bytes(record.value.as_slice()) {
+                    Ok(tx) => tx,
+                    Err(_) => return Ok(false),
+                };
+                if self.db.contains_key(key.as_bytes())? {
+                    return Ok(true)
+                }
+                let access = TransactionsAccess {
+                    db: self.db.clone(),
+                    validators: Arc::new(Validators::default()),
+                    references: Arc::new(References::default()),
+                    permissions: self.permissions,
+                    ttl: TtlPolicy::default(),
+                    limiter: self.limiter.clone(),
+                    health: self.health.clone(),
+                    notifier: Arc::new(Notifier::default()),
+                    profiler: Arc::new(Profiler::default()),
+                };
+                access.write_reindexed(key, &tx, batch)?;
+                Ok(true)
+            }
+            StoreType::STORE_ADDRESS_BOOK => {
+                let item = match proto_BookItem::parse_from_bytes(record.value.as_slice()) {
+                    Ok(item) => item,
+                    Err(_) => return Ok(false),
+                };
+                if Uuid::from_str(item.get_id()).is_err() {
+                    return Ok(false)
+                }
+                if self.db.contains_key(key.as_bytes())? {
+                    return Ok(true)
+                }
+                let access = AddressBookAccess {
+                    db: self.db.clone(),
+                    xpub: Arc::new(XPubPositionAccess { db: self.db.clone(), permissions: self.permissions, limiter: self.limiter.clone(), health: self.health.clone() }),
+                    validators: Arc::new(Validators::default()),
+                    permissions: self.permissions,
+                    limiter: self.limiter.clone(),
+                    health: self.health.clone(),
+                    profiler: Arc::new(Profiler::default()),
+                };
+                access.add_item(item, batch)?;
+                Ok(true)
+            }
+        }
+    }
+}
+
+impl Quarantine for QuarantineAccess {
+
+    fn list(&self) -> Result<Vec<QuarantinedRecord>, StateError> {
+        self.permissions.check_read(Store::Quarantine)?;
+        let mut result = Vec::new();
+        for row in self.db.scan_prefix(PREFIX_KEY) {
+            let (_, value) = row?;
+            result.push(QuarantinedRecord::parse_from_bytes(value.as_ref())?);
+        }
+        Ok(result)
+    }
+
+    fn retry(&self) -> Result<usize, StateError> {
+        self.permissions.check_write(Store::Quarantine)?;
+        self.limiter.check(Store::Quarantine)?;
+        let mut restored = 0;
+        for record in self.list()? {
+            let is_permitted = get_permission_store(record.store).is_some_and(|s| self.permissions.can_write(s));
+            if !is_permitted {
+                continue
+            }
+            let mut batch = Batch::default();
+            let is_existing = self.db.contains_key(record.key.as_slice())?;
+            if self.restore(&record, &mut batch)? {
+                batch.remove(get_key(record.key.as_slice()));
+                self.health.apply(&self.db, batch)?;
+                if !is_existing {
+                    restored += 1;
+                }
+            }
+        }
+        Ok(restored)
+    }
+
+    fn export(&self) -> Result<Vec<u8>, StateError> {
+        let mut bundle = QuarantineBundle::new();
+        bundle.set_records(RepeatedField::from_vec(self.list()?));
+        Ok(bundle.write_to_bytes()?)
+    }
+
+    fn purge(&self) -> Result<usize, StateError> {
+        self.permissions.check_write(Store::Quarantine)?;
+        self.limiter.check(Store::Quarantine)?;
+        let mut batch = Batch::default();
+        let mut count = 0;
+        for key in self.db.scan_prefix(PREFIX_KEY).keys() {
+            batch.remove(key?);
+            count += 1;
+        }
+        if count > 0 {
+            self.health.apply(&self.db, batch)?;
+        }
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use protobuf::Message;
+    use tempdir::TempDir;
+    use crate::access::pagination::PageQuery;
+    use crate::access::quarantine::Quarantine;
+    use crate::access::transactions::{Filter, Transactions};
+    use crate::proto::quarantine::{QuarantineBundle, StoreType};
+    use crate::proto::transactions::{BlockchainId, Transaction as proto_Transaction};
+    use crate::storage::sled_access::SledStorage;
+
+    #[test]
+    fn quarantine_unreadable_tx() {
+        let tmp_dir = TempDir::new("quarantine").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
+        let quarantine = access.get_quarantine();
+
+        let mut tx1 = proto_Transaction::new();
+        tx1.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx1.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        tx1.since_timestamp = 1_647_313_850_992;
+        let mut tx2 = tx1.clone();
+        tx2.tx_id = "0x5f88e4e1ab5d1a3ccb1e0ddfb6f9b5cc0ad43eb7ae1ef1b38ba6b6fcd4a6fb4c".to_string();
+        tx2.since_timestamp = 1_647_313_860_992;
+        transactions.submit(vec![tx1.clone(), tx2.clone()]).unwrap();
+
+        let key = format!("tx:100/{}", tx2.tx_id);
+        access.db.insert(&key, vec![0xff, 0xff, 0xff]).unwrap();
+
+        let results = transactions.query(Filter::default(), PageQuery::default()).unwrap();
+        assert_eq!(results.values.len(), 1);
+        assert_eq!(results.skipped, 1);
+
+        // it's not in the store anymore, so the next query doesn't skip it
+        let results = transactions.query(Filter::default(), PageQuery::default()).unwrap();
+        assert_eq!(results.values.len(), 1);
+        assert_eq!(results.skipped, 0);
+
+        let records = quarantine.list().unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].store, StoreType::STORE_TRANSACTIONS);
+        assert_eq!(records[0].key, key.as_bytes().to_vec());
+        assert!(!records[0].error.is_empty());
+
+        let bundle = QuarantineBundle::parse_from_bytes(quarantine.export().unwrap().as_slice()).unwrap();
+        assert_eq!(bundle.records.len(), 1);
+
+        // still cannot be decoded
+        assert_eq!(quarantine.retry().unwrap(), 0);
+        assert_eq!(quarantine.list().unwrap().len(), 1);
+
+        assert_eq!(quarantine.purge().unwrap(), 1);
+        assert!(quarantine.list().unwrap().is_empty());
+    }
+
+    #[test]
+    fn restore_decodable_tx() {
+        let tmp_dir = TempDir::new("quarantine").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
+        let quarantine = access.get_quarantine();
+
+        let mut tx = proto_Transaction::new();
+        tx.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        tx.since_timestamp = 1_647_313_850_992;
+        transactions.submit(vec![tx.clone()]).unwrap();
+
+        // as if it was quarantined by an older version, which couldn't read it
+        let key = format!("tx:100/{}", tx.tx_id);
+        super::put(&access.db, &access.health, &access.permissions, StoreType::STORE_TRANSACTIONS, key.as_str(), "unknown field");
+        assert!(transactions.get_tx(100, tx.tx_id.as_str()).is_none());
+        assert_eq!(transactions.query(Filter::default(), PageQuery::default()).unwrap().values.len(), 0);
+
+        assert_eq!(quarantine.retry().unwrap(), 1);
+        assert!(quarantine.list().unwrap().is_empty());
+        assert_eq!(transactions.get_tx(100, tx.tx_id.as_str()), Some(tx.clone()));
+        let results = transactions.query(Filter::default(), PageQuery::default()).unwrap();
+        assert_eq!(results.values, vec![tx]);
+    }
+}
@@ -0,0 +1,136 @@
+//!
+//! Limits of the write rate per store, to protect the shared DB from a misbehaving caller, ex. a runaway sync loop
+//! which would otherwise starve the reads made by the UI. A write over the limit fails with `StateError::Throttled`.
+//!
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Instant;
+use crate::access::permissions::Store;
+use crate::errors::StateError;
+
+///
+/// Allowed rate of writes to a store. The writes may come in bursts up to `burst` at once, and then the allowance is
+/// restored with `per_second` writes each second.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    pub per_second: u32,
+    pub burst: u32,
+}
+
+struct Bucket {
+    limit: RateLimit,
+    tokens: f64,
+    updated: Instant,
+}
+
+#[derive(Default)]
+struct State {
+    buckets: HashMap<Store, Bucket>,
+    throttled: HashMap<Store, u64>,
+}
+
+///
+/// Token buckets of the stores with a limit, shared by all handles of the same storage. A store without a limit is
+/// never throttled.
+#[derive(Default)]
+pub(crate) struct RateLimiter {
+    state: Mutex<State>,
+}
+
+impl RateLimiter {
+
+    ///
+    /// Set or remove (with `None`) the limit of the store. A new limit starts with the full burst.
+    pub(crate) fn set_limit(&self, store: Store, limit: Option<RateLimit>) {
+        let mut state = self.state.lock().unwrap();
+        match limit {
+            Some(limit) => {
+                state.buckets.insert(store, Bucket { limit, tokens: limit.burst as f64, updated: Instant::now() });
+            }
+            None => {
+                state.buckets.remove(&store);
+            }
+        }
+    }
+
+    ///
+    /// Take a write allowance for the store, or fail with `StateError::Throttled` if it's over the limit
+    pub(crate) fn check(&self, store: Store) -> Result<(), StateError> {
+        let mut state = self.state.lock().unwrap();
+        let allowed = match state.buckets.get_mut(&store) {
+            Some(bucket) => {
+                let now = Instant::now();
+                let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+                bucket.tokens = (bucket.tokens + elapsed * bucket.limit.per_second as f64).min(bucket.limit.burst as f64);
+                bucket.updated = now;
+                if bucket.tokens >= 1.0 {
+                    bucket.tokens -= 1.0;
+                    true
+                } else {
+                    false
+                }
+            }
+            None => true
+        };
+        if !allowed {
+            *state.throttled.entry(store).or_insert(0) += 1;
+            return Err(StateError::Throttled)
+        }
+        Ok(())
+    }
+
+    ///
+    /// Number of throttled writes per store, since the storage was opened
+    pub(crate) fn get_throttled(&self) -> HashMap<Store, u64> {
+        self.state.lock().unwrap().throttled.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempdir::TempDir;
+    use chrono::Utc;
+    use crate::access::permissions::{Permissions, Store};
+    use crate::access::sync_state::SyncStates;
+    use crate::access::transactions::Transactions;
+    use crate::errors::StateError;
+    use crate::storage::rate_limit::{RateLimit, RateLimiter};
+    use crate::storage::sled_access::SledStorage;
+
+    #[test]
+    fn throttle_after_burst() {
+        let limiter = RateLimiter::default();
+        limiter.set_limit(Store::Cache, Some(RateLimit { per_second: 1, burst: 2 }));
+        assert!(limiter.check(Store::Cache).is_ok());
+        assert!(limiter.check(Store::Cache).is_ok());
+        assert_eq!(limiter.check(Store::Cache), Err(StateError::Throttled));
+        // other stores are not limited
+        assert!(limiter.check(Store::Transactions).is_ok());
+        assert_eq!(limiter.get_throttled().get(&Store::Cache), Some(&1));
+
+        limiter.set_limit(Store::Cache, None);
+        assert!(limiter.check(Store::Cache).is_ok());
+    }
+
+    #[test]
+    fn throttle_storage_writes() {
+        let tmp_dir = TempDir::new("rate_limit").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        access.set_rate_limit(Store::SyncState, Some(RateLimit { per_second: 1, burst: 1 })).unwrap();
+
+        // shared with the restricted handles
+        let restricted = access.restrict(Permissions::full().without(Store::Balances));
+        let sync = restricted.get_sync_state();
+        let target = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
+        sync.on_success(target.clone(), Utc::now()).unwrap();
+        assert_eq!(sync.on_success(target.clone(), Utc::now()), Err(StateError::Throttled));
+        // reads are not limited
+        assert!(sync.get(target.clone()).unwrap().is_some());
+        assert!(sync.get(target.clone()).unwrap().is_some());
+        access.get_transactions().forget(100, "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string()).unwrap();
+
+        assert_eq!(access.get_throttled().get(&Store::SyncState), Some(&1));
+        // only the full storage can change the limits
+        assert_eq!(restricted.set_rate_limit(Store::SyncState, None), Err(StateError::NotPermitted));
+    }
+}
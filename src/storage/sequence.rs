@@ -0,0 +1,414 @@
+//!
+//! Global sequence of the writes to the storage. Each write call to any of the stores increments it, so a consumer can
+//! remember the last seen value and later find out if anything was changed since then, regardless of which store it
+//! was.
+//!
+//! The current value is kept in memory, loaded when the storage is opened, and the new value is stored in the same batch
+//! as the data, so it's changed only by a write that was actually stored, and the number assigned to the write is
+//! provided with the events about it. Sled applies each batch under its global write lock anyway, so assigning the
+//! numbers in the order the batches are applied doesn't make the writes wait any longer. Only a read-modify-write goes
+//! through a transaction.
+//!
+//! Together with the number, each write stores the keys it changes in the log of changes, so the keys changed since a
+//! known number can be found without comparing the whole storage, ex. for an incremental backup. A maintenance write,
+//! which is not counted by the sequence, is logged under the number of the next write. For that all writes go through
+//! the `Batch` and `TransactionalTree` defined here, which are the same as in sled but remember the changed keys.
+//!
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use protobuf::Message;
+use sled::{Db, IVec};
+use sled::transaction::{ConflictableTransactionError, ConflictableTransactionResult, TransactionError, UnabortableTransactionError};
+use crate::errors::StateError;
+use crate::proto::internal::ChangedKeys;
+
+const KEY: &str = "sequence";
+/// Prefix of the log of changes, followed by the sequence number
+const CHANGES_PREFIX: &str = "changes:";
+/// Number since which the log of changes is complete, i.e. it has all writes after it
+const CHANGES_SINCE_KEY: &str = "changes_since";
+
+fn decode(value: Option<&[u8]>) -> u64 {
+    value
+        .and_then(|b| <[u8; 8]>::try_from(b).ok())
+        .map(u64::from_be_bytes)
+        .unwrap_or(0)
+}
+
+fn get_changes_key(seq: u64) -> String {
+    format!("{}{:020}", CHANGES_PREFIX, seq)
+}
+
+///
+/// Current value of the sequence, i.e. the number assigned to the last write. Zero if nothing was written yet.
+pub(crate) fn get(db: &Db) -> Result<u64, StateError> {
+    Ok(decode(db.get(KEY)?.as_deref()))
+}
+
+///
+/// Check if the key is a part of the log of changes rather than of the data
+pub(crate) fn is_changes_log(key: &[u8]) -> bool {
+    key.starts_with(CHANGES_PREFIX.as_bytes()) || key == CHANGES_SINCE_KEY.as_bytes()
+}
+
+///
+/// Keys changed by the writes after the `since` number, or `None` if the log doesn't have all of them because it was
+/// truncated after it. Must be called with no concurrent writes, see `Sequence::exclusive`.
+pub(crate) fn get_changes(db: &Db, since: u64) -> Result<Option<BTreeSet<IVec>>, StateError> {
+    if since < decode(db.get(CHANGES_SINCE_KEY)?.as_deref()) {
+        return Ok(None)
+    }
+    let mut keys = BTreeSet::new();
+    let start = get_changes_key(since + 1);
+    for row in db.range(start.as_bytes()..) {
+        let (key, value) = row?;
+        if !key.starts_with(CHANGES_PREFIX.as_bytes()) {
+            break
+        }
+        let changes = ChangedKeys::parse_from_bytes(value.as_ref())?;
+        keys.extend(changes.keys.into_iter().map(IVec::from));
+    }
+    Ok(Some(keys))
+}
+
+///
+/// Remove the log of the writes up to the `until` number, inclusive, after which the changes can be found only since
+/// that number. Must be called with no concurrent writes, see `Sequence::exclusive`.
+pub(crate) fn truncate_changes(db: &Db, until: u64) -> Result<(), StateError> {
+    let mut batch = sled::Batch::default();
+    let end = get_changes_key(until + 1);
+    for row in db.range(CHANGES_PREFIX.as_bytes()..end.as_bytes()) {
+        let (key, _) = row?;
+        batch.remove(key);
+    }
+    batch.insert(CHANGES_SINCE_KEY, &until.to_be_bytes());
+    db.apply_batch(batch)?;
+    Ok(())
+}
+
+///
+/// Value of the log of changes with the keys added to the ones already logged under the same number
+fn append_changes(current: Option<IVec>, keys: &BTreeSet<IVec>) -> Result<Vec<u8>, StateError> {
+    let mut changes = match current {
+        Some(value) => ChangedKeys::parse_from_bytes(value.as_ref())?,
+        None => ChangedKeys::new(),
+    };
+    changes.keys.extend(keys.iter().map(|k| k.to_vec()));
+    Ok(changes.write_to_bytes()?)
+}
+
+///
+/// A batch of writes, same as `sled::Batch`, which also remembers the changed keys
+#[derive(Default)]
+pub(crate) struct Batch {
+    batch: sled::Batch,
+    keys: BTreeSet<IVec>,
+}
+
+impl Batch {
+
+    pub(crate) fn insert<K, V>(&mut self, key: K, value: V) where K: Into<IVec>, V: Into<IVec> {
+        let key = key.into();
+        self.keys.insert(key.clone());
+        self.batch.insert(key, value);
+    }
+
+    pub(crate) fn remove<K>(&mut self, key: K) where K: Into<IVec> {
+        let key = key.into();
+        self.keys.insert(key.clone());
+        self.batch.remove(key);
+    }
+
+    ///
+    /// Add the keys to the log of changes under the number, and apply the batch
+    fn apply_logged(mut self, db: &Db, seq: u64) -> Result<(), StateError> {
+        if !self.keys.is_empty() {
+            let key = get_changes_key(seq);
+            let value = append_changes(db.get(&key)?, &self.keys)?;
+            self.batch.insert(key.as_bytes(), value);
+        }
+        db.apply_batch(self.batch)?;
+        Ok(())
+    }
+}
+
+///
+/// A transaction, same as `sled::transaction::TransactionalTree`, which also remembers the changed keys
+pub(crate) struct TransactionalTree<'a> {
+    tx: &'a sled::transaction::TransactionalTree,
+    keys: RefCell<BTreeSet<IVec>>,
+}
+
+impl TransactionalTree<'_> {
+
+    pub(crate) fn get<K>(&self, key: K) -> Result<Option<IVec>, UnabortableTransactionError> where K: AsRef<[u8]> {
+        self.tx.get(key)
+    }
+
+    pub(crate) fn insert<K, V>(&self, key: K, value: V) -> Result<Option<IVec>, UnabortableTransactionError>
+        where K: Into<IVec>, V: Into<IVec> {
+        let key = key.into();
+        self.keys.borrow_mut().insert(key.clone());
+        self.tx.insert(key, value)
+    }
+
+    pub(crate) fn remove<K>(&self, key: K) -> Result<Option<IVec>, UnabortableTransactionError> where K: Into<IVec> {
+        let key = key.into();
+        self.keys.borrow_mut().insert(key.clone());
+        self.tx.remove(key)
+    }
+
+    ///
+    /// Add the keys to the log of changes under the number, in the same transaction
+    fn log(&self, seq: u64) -> ConflictableTransactionResult<(), StateError> {
+        let keys = self.keys.borrow();
+        if !keys.is_empty() {
+            let key = get_changes_key(seq);
+            let value = append_changes(self.tx.get(&key)?, &keys).map_err(abort)?;
+            self.tx.insert(key.as_bytes(), value)?;
+        }
+        Ok(())
+    }
+}
+
+///
+/// Sequence of a storage, shared by all its handles
+pub(crate) struct Sequence {
+    /// Number assigned to the last stored write
+    current: AtomicU64,
+    /// Held while a write is applied, so the numbers are stored in the same order as they are assigned
+    lock: Mutex<()>,
+}
+
+impl Sequence {
+
+    ///
+    /// Load the current value stored in the DB. If the DB doesn't have the log of changes yet it's started from the
+    /// current value.
+    pub(crate) fn load(db: &Db) -> Result<Sequence, StateError> {
+        let current = get(db)?;
+        if !db.contains_key(CHANGES_SINCE_KEY)? {
+            db.insert(CHANGES_SINCE_KEY, &current.to_be_bytes())?;
+        }
+        Ok(Sequence {
+            current: AtomicU64::new(current),
+            lock: Mutex::new(()),
+        })
+    }
+
+    ///
+    /// Current value of the sequence, same as `get` but without reading the DB
+    pub(crate) fn current(&self) -> u64 {
+        self.current.load(Ordering::SeqCst)
+    }
+
+    ///
+    /// Apply the batch of a write together with the increment of the sequence. Returns the number assigned to the write.
+    pub(crate) fn apply(&self, db: &Db, batch: Batch) -> Result<u64, StateError> {
+        self.apply_with(db, batch, |_, _| Ok(()))
+    }
+
+    ///
+    /// Same as `apply`, but `f` can add to the batch the values which depend on the number assigned to the write, ex.
+    /// to store it in a log of changes.
+    pub(crate) fn apply_with<F>(&self, db: &Db, mut batch: Batch, f: F) -> Result<u64, StateError>
+        where F: FnOnce(u64, &mut Batch) -> Result<(), StateError> {
+        let _lock = self.lock.lock().unwrap();
+        let seq = self.current() + 1;
+        f(seq, &mut batch)?;
+        batch.insert(KEY, &seq.to_be_bytes());
+        batch.apply_logged(db, seq)?;
+        self.current.store(seq, Ordering::SeqCst);
+        Ok(seq)
+    }
+
+    ///
+    /// Apply a maintenance batch, which is not counted by the sequence but is still logged as a change
+    pub(crate) fn apply_unsequenced(&self, db: &Db, batch: Batch) -> Result<(), StateError> {
+        let _lock = self.lock.lock().unwrap();
+        batch.apply_logged(db, self.current() + 1)
+    }
+
+    ///
+    /// Make a read-modify-write in a transaction, ex. when the value must not be changed by another write in between.
+    /// The sequence is incremented together with it, unless `f` returns `None` because there is nothing to change. `f`
+    /// may be called again on a conflict with another write. Returns the result of `f` and the number assigned to
+    /// the write.
+    pub(crate) fn transaction<R, F>(&self, db: &Db, f: F) -> Result<Option<(R, u64)>, StateError>
+        where F: Fn(&TransactionalTree) -> ConflictableTransactionResult<Option<R>, StateError> {
+        let _lock = self.lock.lock().unwrap();
+        let seq = self.current() + 1;
+        let result = db.transaction(|tx| {
+            let tx = TransactionalTree { tx, keys: RefCell::default() };
+            let result = f(&tx)?;
+            if result.is_some() {
+                tx.insert(KEY, &seq.to_be_bytes())?;
+            }
+            tx.log(seq)?;
+            Ok(result)
+        }).map_err(|e| match e {
+            TransactionError::Abort(e) => e,
+            TransactionError::Storage(e) => StateError::from(e),
+        })?;
+        if result.is_some() {
+            self.current.store(seq, Ordering::SeqCst);
+        }
+        Ok(result.map(|result| (result, seq)))
+    }
+
+    ///
+    /// Call `f` with the current value while no write can be applied, ex. to read a consistent view of the storage.
+    pub(crate) fn exclusive<R, F>(&self, f: F) -> R where F: FnOnce(u64) -> R {
+        let _lock = self.lock.lock().unwrap();
+        f(self.current())
+    }
+}
+
+///
+/// Abort the transaction with the error
+pub(crate) fn abort<E: Into<StateError>>(e: E) -> ConflictableTransactionError<StateError> {
+    ConflictableTransactionError::Abort(e.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+    use tempdir::TempDir;
+    use crate::access::cache::Cache;
+    use crate::access::flags::{FlagValue, Flags};
+    use crate::access::permissions::Permissions;
+    use crate::access::nonces::Nonces;
+    use crate::access::tokens::Tokens;
+    use crate::access::transactions::Transactions;
+    use crate::proto::tokens::TokenMetadata;
+    use crate::storage::sequence::{get, get_changes, truncate_changes, Batch, Sequence};
+    use crate::storage::sled_access::SledStorage;
+
+    #[test]
+    fn increment_on_writes() {
+        let tmp_dir = TempDir::new("sequence").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        assert_eq!(access.get_sequence().unwrap(), 0);
+
+        let mut cache = access.get_cache();
+        cache.put("test".to_string(), "Test".to_string(), None).unwrap();
+        let first = access.get_sequence().unwrap();
+        assert!(first > 0);
+
+        // reads don't change it
+        cache.get("test".to_string()).unwrap();
+        assert_eq!(access.get_sequence().unwrap(), first);
+
+        access.get_transactions().forget(100, "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string()).unwrap();
+        assert!(access.get_sequence().unwrap() > first);
+    }
+
+    #[test]
+    fn load_stored() {
+        let tmp_dir = TempDir::new("sequence").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let flags = access.get_flags();
+        flags.set("test.first".to_string(), FlagValue::Bool(true), "test".to_string()).unwrap();
+        flags.set("test.second".to_string(), FlagValue::Bool(true), "test".to_string()).unwrap();
+
+        let sequence = Sequence::load(&access.db).unwrap();
+        assert_eq!(sequence.current(), access.get_sequence().unwrap());
+        let seq = sequence.apply(&access.db, Batch::default()).unwrap();
+        assert_eq!(seq, sequence.current());
+        assert_eq!(get(&access.db).unwrap(), seq);
+    }
+
+    #[test]
+    fn log_changed_keys() {
+        let tmp_dir = TempDir::new("sequence").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let mut cache = access.get_cache();
+        cache.put("test-1".to_string(), "Test 1".to_string(), None).unwrap();
+        let since = access.get_sequence().unwrap();
+
+        cache.put("test-2".to_string(), "Test 2".to_string(), None).unwrap();
+        access.get_nonces().set_at_least(100, "0xcd2a3d9f938e13cd947ec05abc7fe734df8dd826".to_string(), 10).unwrap();
+        let mut batch = Batch::default();
+        batch.remove("test-3");
+        access.health.apply_batch(&access.db, batch).unwrap();
+
+        let changes = get_changes(&access.db, since).unwrap().unwrap();
+        let keys: Vec<String> = changes.iter().map(|k| String::from_utf8(k.to_vec()).unwrap()).collect();
+        assert_eq!(keys.len(), 4);
+        assert!(keys.contains(&"cache:test-2".to_string()));
+        assert!(keys.contains(&"test-3".to_string()));
+        assert!(keys.contains(&"sequence".to_string()));
+
+        truncate_changes(&access.db, access.get_sequence().unwrap()).unwrap();
+        assert!(get_changes(&access.db, since).unwrap().is_none());
+        // the maintenance write is logged for the next write, which is not yet made
+        let changes = get_changes(&access.db, access.get_sequence().unwrap()).unwrap().unwrap();
+        assert_eq!(changes.len(), 1);
+    }
+
+    #[test]
+    fn unique_for_concurrent_writes() {
+        let tmp_dir = TempDir::new("sequence").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        // both the batches and the transactions
+        let handles: Vec<_> = (0..4).map(|i| {
+            let access = access.restrict(Permissions::full());
+            thread::spawn(move || {
+                for j in 0..25 {
+                    if i % 2 == 0 {
+                        access.get_flags().set(format!("test.flag-{}-{}", i, j), FlagValue::Bool(true), "test".to_string()).unwrap();
+                    } else {
+                        access.get_nonces().set_at_least(100, format!("0xcd2a3d9f938e13cd947ec05abc7fe734df8dd82{}", i), j + 1).unwrap();
+                    }
+                }
+            })
+        }).collect();
+        for h in handles {
+            h.join().unwrap();
+        }
+        assert_eq!(access.get_sequence().unwrap(), 100);
+    }
+
+    #[test]
+    fn not_incremented_without_write() {
+        let tmp_dir = TempDir::new("sequence").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+
+        let nonces = access.get_nonces();
+        nonces.set_at_least(100, "0xcd2a3d9f938e13cd947ec05abc7fe734df8dd826".to_string(), 10).unwrap();
+        let current = access.get_sequence().unwrap();
+        assert_eq!(current, 1);
+
+        // a lower nonce is ignored
+        nonces.set_at_least(100, "0xcd2a3d9f938e13cd947ec05abc7fe734df8dd826".to_string(), 5).unwrap();
+        assert_eq!(access.get_sequence().unwrap(), current);
+
+        // nothing to remove
+        let removed = access.get_flags().remove("unknown".to_string()).unwrap();
+        assert!(!removed);
+        assert_eq!(access.get_sequence().unwrap(), current);
+
+        // rejected by the validation
+        let result = access.get_tokens().set(TokenMetadata::new(), None);
+        assert!(result.is_err());
+        assert_eq!(access.get_sequence().unwrap(), current);
+    }
+
+    #[test]
+    fn stored_with_meta_revision() {
+        let tmp_dir = TempDir::new("sequence").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
+        let tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b";
+
+        transactions.add_tag(100, tx_id.to_string(), "first".to_string()).unwrap();
+        transactions.add_tag(100, tx_id.to_string(), "second".to_string()).unwrap();
+
+        let history = transactions.get_meta_history(100, tx_id).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[1].write_seq, access.get_sequence().unwrap());
+        assert_eq!(history[0].write_seq, history[1].write_seq - 1);
+    }
+}
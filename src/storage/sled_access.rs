@@ -1,18 +1,72 @@
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::Duration;
 use sled::{Db};
-use crate::errors::StateError;
+use uuid::Uuid;
+use crate::access::balance::BalancesChange;
+use crate::access::flags::FlagsChange;
+use crate::access::integrity::References;
+use crate::access::permissions::{Permissions, Store};
+use crate::access::validation::Validators;
+use crate::errors::{InvalidValueError, StateError};
+use crate::proto::backup::BackupManifest;
+use crate::storage::address_registry_store::AddressRegistryAccess;
 use crate::storage::adressbook_store::AddressBookAccess;
+use crate::storage::backup;
 use crate::storage::allowance_store::AllowanceAccess;
 use crate::storage::balance_store::BalanceAccess;
 use crate::storage::cache_store::CacheAccess;
 use crate::storage::default_path;
+use crate::storage::flag_store::FlagAccess;
+use crate::storage::mirror::Mirror;
+use crate::storage::wipe;
+use crate::storage::wipe::WipeReport;
+use crate::storage::nonce_store::NonceAccess;
+use crate::storage::notification::Notifier;
+use crate::storage::quarantine_store::QuarantineAccess;
+use crate::storage::rate_limit::{RateLimit, RateLimiter};
+use crate::storage::sequence;
+use crate::storage::sync_state_store::SyncStateAccess;
+use crate::storage::task_store::TaskAccess;
+use crate::storage::token_store::TokenAccess;
 use crate::storage::transaction_store::{TransactionsAccess};
+use crate::storage::ttl_policy::TtlPolicy;
+use crate::storage::tuning::{Profiler, StorageConfig, StorageStats};
+use crate::storage::health::{Health, HealthStatus};
 use crate::storage::version::Version;
 use crate::storage::xpubpos_store::XPubPositionAccess;
 
+/// Number of records read per store by `SledStorage::warm`, which is about the first page shown by the UI
+const WARM_LIMIT: usize = 100;
+
+///
+/// Data to read into the cache by `SledStorage::warm`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WarmTarget {
+    /// Most recent transactions and their index
+    Transactions,
+    /// Address book items
+    AddressBook,
+    /// Cached balances
+    Balances,
+}
+
 pub struct SledStorage {
     pub(crate) db: Arc<Db>,
+    pub(crate) ttl: TtlPolicy,
+    pub(crate) config: StorageConfig,
+    pub(crate) validators: Arc<Validators>,
+    pub(crate) references: Arc<References>,
+    pub(crate) permissions: Permissions,
+    pub(crate) limiter: Arc<RateLimiter>,
+    pub(crate) notifier: Arc<Notifier>,
+    pub(crate) flags_notifier: Arc<Notifier<FlagsChange>>,
+    pub(crate) balances_notifier: Arc<Notifier<BalancesChange>>,
+    pub(crate) profiler: Arc<Profiler>,
+    pub(crate) health: Arc<Health>,
 }
 
 /// Sled backed storage
@@ -25,50 +79,381 @@ impl SledStorage {
 
     /// Open DB at the specified path
     pub fn open(path: PathBuf) -> Result<SledStorage, StateError> {
-        let db = Arc::new(sled::open(path)?);
-        let version = Version::new(db.clone());
+        SledStorage::open_with_policy(path, TtlPolicy::default())
+    }
+
+    /// Open DB at the specified path, using the provided retention settings for caches
+    pub fn open_with_policy(path: PathBuf, ttl: TtlPolicy) -> Result<SledStorage, StateError> {
+        SledStorage::open_with_config(path, ttl, StorageConfig::default())
+    }
+
+    ///
+    /// Open DB at the specified path, with the DB configured for the expected workload, ex. to use less memory with
+    /// `StorageConfig::for_workload(Workload::LowMemory)`
+    pub fn open_with_config(path: PathBuf, ttl: TtlPolicy, config: StorageConfig) -> Result<SledStorage, StateError> {
+        let db = Arc::new(config.open(&path)?);
+        SledStorage::from_db(db, ttl, config)
+    }
+
+    fn from_db(db: Arc<Db>, ttl: TtlPolicy, config: StorageConfig) -> Result<SledStorage, StateError> {
+        let health = Arc::new(Health::open(&db)?);
+        let version = Version::new(db.clone(), health.clone(), ttl);
         if let Err(e) = version.migrate() {
             println!("Failed to migrate DB: {:?}", e);
         }
         Ok(SledStorage {
             db,
+            ttl,
+            config,
+            validators: Arc::new(Validators::default()),
+            references: Arc::new(References::default()),
+            permissions: Permissions::full(),
+            limiter: Arc::new(RateLimiter::default()),
+            notifier: Arc::new(Notifier::default()),
+            flags_notifier: Arc::new(Notifier::default()),
+            balances_notifier: Arc::new(Notifier::default()),
+            profiler: Arc::new(Profiler::default()),
+            health,
         })
     }
 
     ///
-    /// Open API to access DB version
+    /// Make a full backup of the storage into the file at `path`. The writes wait until it's finished.
+    /// Returns the manifest of the backup, which should be kept to make incremental backups on top of it. After a full
+    /// backup the increments can't be based on the backups made before it anymore.
+    pub fn backup(&self, path: &Path) -> Result<BackupManifest, StateError> {
+        self.check_read_all()?;
+        backup::write_backup(&self.db, &self.health, None, path)
+    }
+
+    ///
+    /// Make an incremental backup into the file at `path`, with only the data changed since the backup described by
+    /// `base_manifest`, which may be a full or another incremental backup. The writes wait until it's finished.
+    /// Returns the manifest of the new backup, to be used as a base for the next one.
+    pub fn backup_incremental(&self, base_manifest: &BackupManifest, path: &Path) -> Result<BackupManifest, StateError> {
+        self.check_read_all()?;
+        backup::write_backup(&self.db, &self.health, Some(base_manifest), path)
+    }
+
+    ///
+    /// Restore a storage at `path` from the backup files, where the first one is a full backup followed by the
+    /// increments in the order they were made. The target DB must be empty.
+    pub fn restore(path: PathBuf, backups: Vec<PathBuf>) -> Result<SledStorage, StateError> {
+        let db = Arc::new(sled::open(path)?);
+        if !db.is_empty() {
+            return Err(StateError::InvalidValue(InvalidValueError::NameMessage("path".to_string(), "Target DB is not empty".to_string())))
+        }
+        backup::restore(&db, &backups)?;
+        // the backup may be made by a previous version
+        SledStorage::from_db(db, TtlPolicy::default(), StorageConfig::default())
+    }
+
+    ///
+    /// Start mirroring all writes to a DB at `path` in background, ex. on an external drive. If the location is not
+    /// available the mirror tries to open it again each `retry` interval, and then catches up with the current state.
+    /// The mirror works until the returned handle is stopped or dropped.
+    pub fn start_mirror(&self, path: PathBuf, retry: Duration) -> Result<Mirror, StateError> {
+        self.check_read_all()?;
+        Ok(Mirror::start(self.db.clone(), path, retry))
+    }
+
+    ///
+    /// Remove all data of the wallet from all stores, ex. when the user removes the wallet from the app, and check that
+    /// nothing referencing it is left. Requires the full access to the stores of the wallet data.
+    pub fn wipe_wallet(&self, wallet_id: Uuid) -> Result<WipeReport, StateError> {
+        wipe::wipe_wallet(self, wallet_id)
+    }
+
+    ///
+    /// Read the first records of the specified stores in background, so they are already in the sled cache when the UI
+    /// makes its first queries after a cold start. It's a best effort optimization, so errors are only logged.
+    /// Returns a handle to the background thread, which results in the total number of read records.
+    pub fn warm(&self, targets: Vec<WarmTarget>) -> JoinHandle<usize> {
+        let storage = self.restrict(Permissions::full());
+        thread::spawn(move || {
+            let mut total = 0;
+            for target in targets {
+                let store = match target {
+                    WarmTarget::Transactions => Store::Transactions,
+                    WarmTarget::AddressBook => Store::AddressBook,
+                    WarmTarget::Balances => Store::Balances,
+                };
+                if !storage.permissions.can_read(store) {
+                    continue
+                }
+                let result = match target {
+                    WarmTarget::Transactions => storage.get_transactions().warm(WARM_LIMIT),
+                    WarmTarget::AddressBook => storage.get_addressbook().warm(WARM_LIMIT),
+                    WarmTarget::Balances => storage.get_balance().warm(WARM_LIMIT),
+                };
+                match result {
+                    Ok(count) => total += count,
+                    Err(e) => log::warn!("Failed to warm {:?}: {:?}", target, e),
+                }
+            }
+            total
+        })
+    }
+
+    ///
+    /// Another handle to the same storage, limited to the specified permissions. The permissions can only be reduced,
+    /// so a restricted storage can be restricted further, but it never gets more than it already has.
+    /// It's intended to hand the storage to a less trusted part of the app, ex. to a renderer process.
+    pub fn restrict(&self, permissions: Permissions) -> SledStorage {
+        SledStorage {
+            db: self.db.clone(),
+            ttl: self.ttl,
+            config: self.config,
+            validators: self.validators.clone(),
+            references: self.references.clone(),
+            permissions: self.permissions.intersect(&permissions),
+            limiter: self.limiter.clone(),
+            notifier: self.notifier.clone(),
+            flags_notifier: self.flags_notifier.clone(),
+            balances_notifier: self.balances_notifier.clone(),
+            profiler: self.profiler.clone(),
+            health: self.health.clone(),
+        }
+    }
+
+    ///
+    /// Size of the DB and the read counters since it's opened, to check if the configuration fits the workload
+    pub fn stats(&self) -> Result<StorageStats, StateError> {
+        Ok(StorageStats::new(self.config, self.db.size_on_disk()?, &self.profiler))
+    }
+
+    ///
+    /// Check if the storage can write, by flushing the pending writes to the disk. If the disk is full the storage
+    /// switches to the degraded mode, where it's still readable, but only the cache and the balances are updated, and
+    /// only in memory.
+    pub fn health(&self) -> Result<HealthStatus, StateError> {
+        if !self.health.is_degraded() {
+            match self.health.observe(self.db.flush().map_err(StateError::from)) {
+                Ok(_) | Err(StateError::DiskFull) => {}
+                Err(e) => return Err(e)
+            }
+        }
+        Ok(self.health.status())
+    }
+
+    ///
+    /// Write the values queued in the degraded mode, ex. after the user freed some space on the disk. If it succeeds the
+    /// storage leaves the degraded mode. Returns the number of written values.
+    pub fn retry_queued(&self) -> Result<usize, StateError> {
+        self.permissions.check_write(Store::Cache)?;
+        self.permissions.check_write(Store::Balances)?;
+        self.health.retry(&self.db)
+    }
+
+    ///
+    /// Number of the last write to any of the stores. It only grows, so if it's the same as before then nothing
+    /// was changed since then.
+    pub fn get_sequence(&self) -> Result<u64, StateError> {
+        sequence::get(&self.db)
+    }
+
+    ///
+    /// Set or remove (with `None`) the limit of the write rate to the store. The limit applies to all handles of the
+    /// storage, including the restricted ones, so only a handle with full permissions can change it.
+    pub fn set_rate_limit(&self, store: Store, limit: Option<RateLimit>) -> Result<(), StateError> {
+        self.check_full()?;
+        self.limiter.set_limit(store, limit);
+        Ok(())
+    }
+
+    ///
+    /// Number of writes per store rejected by the rate limit since the storage was opened
+    pub fn get_throttled(&self) -> HashMap<Store, u64> {
+        self.limiter.get_throttled()
+    }
+
+    ///
+    /// Permissions of the current storage handle
+    pub fn permissions(&self) -> Permissions {
+        self.permissions
+    }
+
+    pub(crate) fn check_read_all(&self) -> Result<(), StateError> {
+        if !self.permissions.can_read_all() {
+            return Err(StateError::NotPermitted)
+        }
+        Ok(())
+    }
+
+    ///
+    /// Check that it's the handle with full permissions, which is required to change the settings shared by all
+    /// handles of the storage
+    pub(crate) fn check_full(&self) -> Result<(), StateError> {
+        if self.permissions != Permissions::full() {
+            return Err(StateError::NotPermitted)
+        }
+        Ok(())
+    }
+
+    ///
+    /// Open API to access DB version. It's read-only, because the DB is migrated to the current version when it's opened.
     pub fn version(&self) -> Version {
-        Version::new(self.db.clone())
+        Version::new(self.db.clone(), self.health.clone(), self.ttl)
+    }
+
+    ///
+    /// Additional validations applied to the data before it's stored, shared by all stores opened from this storage,
+    /// including the restricted ones, so only a handle with full permissions can change them.
+    pub fn validators(&self) -> Result<Arc<Validators>, StateError> {
+        self.check_full()?;
+        Ok(self.validators.clone())
+    }
+
+    ///
+    /// Policies applied to the records referencing a transaction when it's forgotten, shared by all stores opened from
+    /// this storage, including the restricted ones, so only a handle with full permissions can change them.
+    pub fn references(&self) -> Result<Arc<References>, StateError> {
+        self.check_full()?;
+        Ok(self.references.clone())
     }
 
     /// Open API to access transactions store
     pub fn get_transactions(&self) -> TransactionsAccess {
-        return TransactionsAccess { db: self.db.clone() };
+        TransactionsAccess { db: self.db.clone(), validators: self.validators.clone(), references: self.references.clone(), permissions: self.permissions, ttl: self.ttl, limiter: self.limiter.clone(), health: self.health.clone(), notifier: self.notifier.clone(), profiler: self.profiler.clone() }
     }
 
     pub fn get_addressbook(&self) -> AddressBookAccess {
-        return AddressBookAccess { db: self.db.clone(), xpub: Arc::new(self.get_xpub_pos()) }
+        AddressBookAccess { db: self.db.clone(), xpub: Arc::new(self.get_xpub_pos()), validators: self.validators.clone(), permissions: self.permissions, limiter: self.limiter.clone(), health: self.health.clone(), profiler: self.profiler.clone() }
     }
 
     pub fn get_xpub_pos(&self) -> XPubPositionAccess {
-        return XPubPositionAccess { db: self.db.clone() }
+        XPubPositionAccess { db: self.db.clone(), permissions: self.permissions, limiter: self.limiter.clone(), health: self.health.clone() }
     }
 
     ///
     /// Cache for address balances
     pub fn get_balance(&self) -> BalanceAccess {
-        return BalanceAccess { db: self.db.clone() }
+        BalanceAccess { db: self.db.clone(), permissions: self.permissions, limiter: self.limiter.clone(), health: self.health.clone(), xpub: Arc::new(self.get_xpub_pos()), ttl: self.ttl, notifier: self.balances_notifier.clone() }
     }
 
     ///
     /// Generic persistent cache
     pub fn get_cache(&self) -> CacheAccess {
-        return CacheAccess { db: self.db.clone() }
+        CacheAccess { db: self.db.clone(), ttl: self.ttl, permissions: self.permissions, limiter: self.limiter.clone(), profiler: self.profiler.clone(), health: self.health.clone() }
     }
 
     ///
     /// ERC20 Allowance Cache
     pub fn get_allowance(&self) -> AllowanceAccess {
-        return AllowanceAccess { db: self.db.clone() }
+        AllowanceAccess { db: self.db.clone(), ttl: self.ttl, permissions: self.permissions, limiter: self.limiter.clone(), health: self.health.clone() }
+    }
+
+    ///
+    /// Bookkeeping of the remote sync per address or xpub
+    pub fn get_sync_state(&self) -> SyncStateAccess {
+        SyncStateAccess { db: self.db.clone(), permissions: self.permissions, limiter: self.limiter.clone(), health: self.health.clone() }
+    }
+
+    ///
+    /// Records moved out of the stores because they cannot be decoded
+    pub fn get_quarantine(&self) -> QuarantineAccess {
+        QuarantineAccess { db: self.db.clone(), permissions: self.permissions, limiter: self.limiter.clone(), health: self.health.clone() }
+    }
+
+    ///
+    /// Known addresses of the wallet entries, used to attribute the submitted transactions
+    pub fn get_address_registry(&self) -> AddressRegistryAccess {
+        AddressRegistryAccess { db: self.db.clone(), permissions: self.permissions, limiter: self.limiter.clone(), health: self.health.clone() }
     }
-}
\ No newline at end of file
+
+    ///
+    /// Highest used nonce per address
+    pub fn get_nonces(&self) -> NonceAccess {
+        NonceAccess { db: self.db.clone(), permissions: self.permissions, limiter: self.limiter.clone(), health: self.health.clone() }
+    }
+
+    ///
+    /// Queue of the background tasks
+    pub fn get_tasks(&self) -> TaskAccess {
+        TaskAccess { db: self.db.clone(), permissions: self.permissions, limiter: self.limiter.clone(), health: self.health.clone() }
+    }
+
+    ///
+    /// Persistent feature flags
+    pub fn get_flags(&self) -> FlagAccess {
+        FlagAccess { db: self.db.clone(), permissions: self.permissions, limiter: self.limiter.clone(), health: self.health.clone(), notifier: self.flags_notifier.clone() }
+    }
+
+    pub fn get_tokens(&self) -> TokenAccess {
+        TokenAccess { db: self.db.clone(), ttl: self.ttl, permissions: self.permissions, limiter: self.limiter.clone(), health: self.health.clone() }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use tempdir::TempDir;
+    use crate::access::transactions::Transactions;
+    use crate::proto::transactions::{BlockchainId, Transaction as proto_Transaction};
+    use crate::access::balance::Balances;
+    use crate::access::cache::Cache;
+    use crate::access::permissions::{Permissions, Store};
+    use crate::errors::StateError;
+    use crate::storage::sled_access::{SledStorage, WarmTarget};
+
+    #[test]
+    fn warm_recent_transactions() {
+        let tmp_dir = TempDir::new("warm").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+
+        let mut tx1 = proto_Transaction::new();
+        tx1.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx1.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        tx1.since_timestamp = 1_647_313_850_992;
+        let mut tx2 = tx1.clone();
+        tx2.tx_id = "0x5f88e4e1ab5d1a3ccb1e0ddfb6f9b5cc0ad43eb7ae1ef1b38ba6b6fcd4a6fb4c".to_string();
+        tx2.since_timestamp = 1_647_313_860_992;
+        access.get_transactions().submit(vec![tx1, tx2]).unwrap();
+
+        let act = access.warm(vec![WarmTarget::Transactions, WarmTarget::AddressBook, WarmTarget::Balances])
+            .join().unwrap();
+        assert_eq!(act, 2);
+    }
+
+    #[test]
+    fn enforce_restricted_permissions() {
+        let tmp_dir = TempDir::new("permissions").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+
+        let mut tx = proto_Transaction::new();
+        tx.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        tx.since_timestamp = 1_647_313_850_992;
+        access.get_transactions().submit(vec![tx.clone()]).unwrap();
+
+        let restricted = access.restrict(
+            Permissions::none().with_read(Store::Transactions).with_write(Store::Cache)
+        );
+        let transactions = restricted.get_transactions();
+        assert!(transactions.get_tx(100, tx.tx_id.as_str()).is_some());
+        assert_eq!(transactions.submit(vec![tx.clone()]), Err(StateError::NotPermitted));
+        assert_eq!(transactions.forget(100, tx.tx_id.clone()), Err(StateError::NotPermitted));
+
+        let mut cache = restricted.get_cache();
+        cache.put("test".to_string(), "Test".to_string(), None).unwrap();
+        assert_eq!(cache.get("test".to_string()).unwrap(), Some("Test".to_string()));
+        assert!(matches!(restricted.get_balance().list("bc1qywz558j2ja7fwmg32jupn02qvla5zm3dvggpqv".to_string()), Err(StateError::NotPermitted)));
+        assert!(matches!(restricted.backup(&tmp_dir.path().join("full.bak")), Err(StateError::NotPermitted)));
+
+        // cannot get back the permissions it doesn't have
+        let extended = restricted.restrict(Permissions::full());
+        assert_eq!(extended.permissions(), restricted.permissions());
+        assert!(extended.get_transactions().get_tx(100, tx.tx_id.as_str()).is_some());
+        assert_eq!(extended.get_transactions().submit(vec![tx]), Err(StateError::NotPermitted));
+    }
+
+    #[test]
+    fn shared_settings_only_with_full_permissions() {
+        let tmp_dir = TempDir::new("permissions").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        assert!(access.validators().is_ok());
+        assert!(access.references().is_ok());
+
+        let restricted = access.restrict(Permissions::read_only());
+        assert!(matches!(restricted.validators(), Err(StateError::NotPermitted)));
+        assert!(matches!(restricted.references(), Err(StateError::NotPermitted)));
+    }
+}
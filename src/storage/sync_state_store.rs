@@ -0,0 +1,239 @@
+use std::ops::Deref;
+use std::sync::Arc;
+use chrono::{DateTime, Utc};
+use protobuf::Message;
+use sled::Db;
+use crate::access::sync_state::SyncStates;
+use crate::access::permissions::{Permissions, Store};
+use crate::errors::{InvalidValueError, StateError};
+use crate::proto::sync::SyncState;
+use crate::storage::rate_limit::RateLimiter;
+use crate::storage::health::Health;
+use crate::storage::sequence::{self, Batch};
+
+const PREFIX_KEY: &str = "sync:";
+
+/// Delay after the first failure, in milliseconds
+const BACKOFF_INITIAL: u64 = 30 * 1000;
+/// Longest delay between attempts, in milliseconds
+const BACKOFF_MAX: u64 = 60 * 60 * 1000;
+
+pub struct SyncStateAccess {
+    pub(crate) db: Arc<Db>,
+    pub(crate) permissions: Permissions,
+    pub(crate) limiter: Arc<RateLimiter>,
+    pub(crate) health: Arc<Health>,
+}
+
+impl SyncStateAccess {
+
+    fn key(target: &str) -> Result<String, StateError> {
+        if target.is_empty() {
+            return Err(StateError::InvalidValue(InvalidValueError::Name("target".to_string())))
+        }
+        Ok(format!("{}{}", PREFIX_KEY, target))
+    }
+
+    ///
+    /// Put the removal of the sync state of the targets into the batch. Returns the number of the known targets.
+    pub(crate) fn wipe_targets(&self, targets: &[String], batch: &mut Batch) -> Result<usize, StateError> {
+        let mut count = 0;
+        for target in targets {
+            let key = SyncStateAccess::key(target)?;
+            if self.db.contains_key(&key)? {
+                batch.remove(key.as_bytes());
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    ///
+    /// Delay before the next attempt after the specified number of consecutive failures
+    pub(crate) fn get_backoff(failures: u32) -> u64 {
+        if failures == 0 {
+            return 0
+        }
+        // 2^20 is way beyond the max delay, so no need to go any further and risk an overflow
+        let multiplier = 1u64 << (failures - 1).min(20);
+        (BACKOFF_INITIAL * multiplier).min(BACKOFF_MAX)
+    }
+
+    fn update<F>(&self, target: String, f: F) -> Result<SyncState, StateError>
+        where F: Fn(&mut SyncState) {
+        let key = SyncStateAccess::key(&target)?;
+        let (state, _) = self.health.transaction(&self.db, |tx| {
+            let mut state = match tx.get(&key)? {
+                Some(b) => SyncState::parse_from_bytes(b.deref()).map_err(sequence::abort)?,
+                None => {
+                    let mut state = SyncState::new();
+                    state.set_target(target.clone());
+                    state
+                }
+            };
+            f(&mut state);
+            tx.insert(key.as_bytes(), state.write_to_bytes().map_err(sequence::abort)?)?;
+            Ok(Some(state))
+        })?.expect("always written");
+        Ok(state)
+    }
+}
+
+impl SyncStates for SyncStateAccess {
+    fn get(&self, target: String) -> Result<Option<SyncState>, StateError> {
+        self.permissions.check_read(Store::SyncState)?;
+        let key = SyncStateAccess::key(&target)?;
+        match self.db.get(key)? {
+            Some(b) => Ok(Some(SyncState::parse_from_bytes(b.deref())?)),
+            None => Ok(None)
+        }
+    }
+
+    fn on_success(&self, target: String, ts: DateTime<Utc>) -> Result<SyncState, StateError> {
+        self.permissions.check_write(Store::SyncState)?;
+        self.limiter.check(Store::SyncState)?;
+        let ts = ts.timestamp_millis() as u64;
+        self.update(target, |state| {
+            state.last_attempt = ts;
+            state.last_success = ts;
+            state.failures = 0;
+            state.next_attempt = ts;
+            state.last_error = "".to_string();
+        })
+    }
+
+    fn on_failure(&self, target: String, error: String, ts: DateTime<Utc>) -> Result<SyncState, StateError> {
+        self.permissions.check_write(Store::SyncState)?;
+        self.limiter.check(Store::SyncState)?;
+        let ts = ts.timestamp_millis() as u64;
+        self.update(target, |state| {
+            state.last_attempt = ts;
+            state.failures = state.failures.saturating_add(1);
+            state.next_attempt = ts + SyncStateAccess::get_backoff(state.failures);
+            state.last_error = error.clone();
+        })
+    }
+
+    fn is_allowed(&self, target: String, now: DateTime<Utc>) -> Result<bool, StateError> {
+        self.permissions.check_read(Store::SyncState)?;
+        let now = now.timestamp_millis() as u64;
+        match self.get(target)? {
+            Some(state) => Ok(state.next_attempt <= now),
+            None => Ok(true)
+        }
+    }
+
+    fn list_allowed(&self, now: DateTime<Utc>, limit: usize) -> Result<Vec<SyncState>, StateError> {
+        self.permissions.check_read(Store::SyncState)?;
+        let now = now.timestamp_millis() as u64;
+        let mut result = Vec::new();
+        for entry in self.db.scan_prefix(PREFIX_KEY) {
+            let (_, value) = entry?;
+            if let Ok(state) = SyncState::parse_from_bytes(value.as_ref()) {
+                if state.next_attempt <= now {
+                    result.push(state);
+                }
+            }
+        }
+        result.sort_by_key(|state| state.last_success);
+        result.truncate(limit);
+        Ok(result)
+    }
+
+    fn remove(&self, target: String) -> Result<(), StateError> {
+        self.permissions.check_write(Store::SyncState)?;
+        self.limiter.check(Store::SyncState)?;
+        let key = SyncStateAccess::key(&target)?;
+        self.health.transaction(&self.db, |tx| {
+            Ok(tx.remove(key.as_bytes())?.map(|_| ()))
+        })?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone, Utc};
+    use tempdir::TempDir;
+    use crate::access::sync_state::SyncStates;
+    use crate::storage::sled_access::SledStorage;
+
+    #[test]
+    fn none_by_default() {
+        let tmp_dir = TempDir::new("sync-state").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let store = access.get_sync_state();
+
+        let act = store.get("0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb".to_string()).unwrap();
+        assert!(act.is_none());
+        let allowed = store.is_allowed("0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb".to_string(), Utc::now()).unwrap();
+        assert!(allowed);
+    }
+
+    #[test]
+    fn backoff_after_failures() {
+        let tmp_dir = TempDir::new("sync-state").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let store = access.get_sync_state();
+
+        let target = "0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb".to_string();
+        let ts = Utc.timestamp_millis_opt(1_647_313_850_992).unwrap();
+
+        let act = store.on_failure(target.clone(), "Timeout".to_string(), ts).unwrap();
+        assert_eq!(act.failures, 1);
+        assert_eq!(act.last_error, "Timeout");
+        assert!(!store.is_allowed(target.clone(), ts + Duration::seconds(10)).unwrap());
+        assert!(store.is_allowed(target.clone(), ts + Duration::seconds(30)).unwrap());
+
+        let act = store.on_failure(target.clone(), "Timeout".to_string(), ts + Duration::seconds(30)).unwrap();
+        assert_eq!(act.failures, 2);
+        assert!(!store.is_allowed(target.clone(), ts + Duration::seconds(80)).unwrap());
+        assert!(store.is_allowed(target.clone(), ts + Duration::seconds(90)).unwrap());
+
+        let act = store.on_success(target.clone(), ts + Duration::seconds(90)).unwrap();
+        assert_eq!(act.failures, 0);
+        assert_eq!(act.last_error, "");
+        assert_eq!(act.last_success, 1_647_313_940_992);
+        assert!(store.is_allowed(target.clone(), ts + Duration::seconds(90)).unwrap());
+    }
+
+    #[test]
+    fn limit_backoff() {
+        let tmp_dir = TempDir::new("sync-state").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let store = access.get_sync_state();
+
+        let target = "0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb".to_string();
+        let ts = Utc.timestamp_millis_opt(1_647_313_850_992).unwrap();
+        for _ in 0..100 {
+            let _ = store.on_failure(target.clone(), "Timeout".to_string(), ts).unwrap();
+        }
+        assert!(store.is_allowed(target.clone(), ts + Duration::hours(1)).unwrap());
+    }
+
+    #[test]
+    fn list_least_recent_first() {
+        let tmp_dir = TempDir::new("sync-state").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let store = access.get_sync_state();
+
+        let ts = Utc.timestamp_millis_opt(1_647_313_850_992).unwrap();
+        store.on_success("0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb".to_string(), ts + Duration::seconds(10)).unwrap();
+        store.on_success("0x60bcd26c20586076eea2e7206e22bf5256e76a20".to_string(), ts).unwrap();
+        store.on_failure("0x000000000D71b31F9C460f26C45589EC91551969".to_string(), "Timeout".to_string(), ts + Duration::seconds(20)).unwrap();
+
+        let act = store.list_allowed(ts + Duration::seconds(30), 10).unwrap();
+        assert_eq!(act.len(), 2);
+        assert_eq!(act[0].target, "0x60bcd26c20586076eea2e7206e22bf5256e76a20");
+        assert_eq!(act[1].target, "0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb");
+
+        let act = store.list_allowed(ts + Duration::seconds(60), 10).unwrap();
+        assert_eq!(act.len(), 3);
+        assert_eq!(act[0].target, "0x000000000D71b31F9C460f26C45589EC91551969");
+
+        store.remove("0x000000000D71b31F9C460f26C45589EC91551969".to_string()).unwrap();
+        let act = store.list_allowed(ts + Duration::seconds(60), 1).unwrap();
+        assert_eq!(act.len(), 1);
+        assert_eq!(act[0].target, "0x60bcd26c20586076eea2e7206e22bf5256e76a20");
+    }
+}
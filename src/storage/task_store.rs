@@ -0,0 +1,309 @@
+use std::ops::Deref;
+use std::sync::Arc;
+use chrono::{DateTime, Duration, Utc};
+use protobuf::Message;
+use sled::Db;
+use uuid::Uuid;
+use crate::access::permissions::{Permissions, Store};
+use crate::access::tasks::Tasks;
+use crate::errors::{InvalidValueError, StateError};
+use crate::proto::tasks::{Task, TaskStatus};
+use crate::storage::rate_limit::RateLimiter;
+use crate::storage::health::Health;
+use crate::storage::sequence::{self, Batch};
+use crate::storage::sync_state_store::SyncStateAccess;
+
+///
+/// # Storage:
+///
+/// - `task:<KIND>/<ID>` to store a `Task`
+///
+const PREFIX_KEY: &str = "task:";
+
+pub struct TaskAccess {
+    pub(crate) db: Arc<Db>,
+    pub(crate) permissions: Permissions,
+    pub(crate) limiter: Arc<RateLimiter>,
+    pub(crate) health: Arc<Health>,
+}
+
+impl TaskAccess {
+
+    /// Checks if the `kind` can be used as a part of the key
+    fn is_valid_kind(kind: &str) -> bool {
+        !kind.is_empty() && kind.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.')
+    }
+
+    fn key(kind: &str, id: &str) -> Result<String, StateError> {
+        if !TaskAccess::is_valid_kind(kind) {
+            return Err(StateError::InvalidValue(InvalidValueError::Name("kind".to_string())))
+        }
+        Ok(format!("{}{}/{}", PREFIX_KEY, kind, id))
+    }
+
+    fn key_prefix(kind: &str) -> Result<String, StateError> {
+        TaskAccess::key(kind, "")
+    }
+
+    ///
+    /// Apply `f` to the stored copy of the `task`, if it's still under the same lease (i.e., has the same number of
+    /// attempts). The function returns the new value, or `None` to remove the task.
+    fn update_leased<F>(&self, task: &Task, f: F) -> Result<bool, StateError>
+        where F: Fn(Task) -> Option<Task> {
+        let key = TaskAccess::key(task.get_kind(), task.get_id())?;
+        let updated = self.health.transaction(&self.db, |tx| {
+            let current = match tx.get(&key)? {
+                Some(b) => Task::parse_from_bytes(b.deref()).map_err(sequence::abort)?,
+                None => return Ok(None)
+            };
+            if current.status != TaskStatus::TASK_PENDING || current.attempts != task.attempts {
+                return Ok(None)
+            }
+            match f(current) {
+                Some(next) => tx.insert(key.as_bytes(), next.write_to_bytes().map_err(sequence::abort)?)?,
+                None => tx.remove(key.as_bytes())?
+            };
+            Ok(Some(()))
+        })?;
+        Ok(updated.is_some())
+    }
+}
+
+impl Tasks for TaskAccess {
+
+    fn enqueue(&self, kind: String, payload: Vec<u8>, max_attempts: u32) -> Result<Task, StateError> {
+        self.permissions.check_write(Store::Tasks)?;
+        self.limiter.check(Store::Tasks)?;
+        let now = Utc::now().timestamp_millis() as u64;
+        let mut task = Task::new();
+        task.set_id(Uuid::new_v4().to_string());
+        task.set_kind(kind);
+        task.set_payload(payload);
+        task.set_created_at(now);
+        task.set_max_attempts(max_attempts.max(1));
+        task.set_visible_at(now);
+        let key = TaskAccess::key(task.get_kind(), task.get_id())?;
+        // the tasks are ordered by the write that added them
+        self.health.apply_with(&self.db, Batch::default(), |seq, batch| {
+            task.set_position(seq);
+            batch.insert(key.as_bytes(), task.write_to_bytes()?);
+            Ok(())
+        })?;
+        Ok(task)
+    }
+
+    fn lease(&self, kind: String, now: DateTime<Utc>, timeout: Duration, limit: usize) -> Result<Vec<Task>, StateError> {
+        self.permissions.check_write(Store::Tasks)?;
+        self.limiter.check(Store::Tasks)?;
+        let prefix = TaskAccess::key_prefix(&kind)?;
+        let now = now.timestamp_millis() as u64;
+        let visible_at = now.saturating_add(timeout.num_milliseconds().max(0) as u64);
+
+        let mut available = Vec::new();
+        for entry in self.db.scan_prefix(&prefix) {
+            let (key, value) = entry?;
+            if let Ok(task) = Task::parse_from_bytes(value.as_ref()) {
+                if task.status == TaskStatus::TASK_PENDING && task.visible_at <= now {
+                    available.push((key, value, task));
+                }
+            }
+        }
+        available.sort_by_key(|(_, _, task)| task.position);
+
+        let leased = self.health.transaction(&self.db, |tx| {
+            let mut result = Vec::new();
+            let mut changed = false;
+            for (key, value, task) in available.iter() {
+                if result.len() >= limit {
+                    break
+                }
+                // skip it if another worker has leased it in the meantime
+                if tx.get(key)?.as_ref() != Some(value) {
+                    continue
+                }
+                let mut task = task.clone();
+                if task.attempts >= task.max_attempts {
+                    // the last lease has expired without the task being completed or failed by the worker
+                    task.status = TaskStatus::TASK_FAILED;
+                    task.last_error = "Lease expired".to_string();
+                    tx.insert(key, task.write_to_bytes().map_err(sequence::abort)?)?;
+                    changed = true;
+                    continue
+                }
+                task.attempts = task.attempts.saturating_add(1);
+                task.visible_at = visible_at;
+                tx.insert(key, task.write_to_bytes().map_err(sequence::abort)?)?;
+                changed = true;
+                result.push(task);
+            }
+            Ok(if changed { Some(result) } else { None })
+        })?;
+        Ok(leased.map(|(result, _)| result).unwrap_or_default())
+    }
+
+    fn complete(&self, task: &Task) -> Result<bool, StateError> {
+        self.permissions.check_write(Store::Tasks)?;
+        self.limiter.check(Store::Tasks)?;
+        self.update_leased(task, |_| None)
+    }
+
+    fn fail(&self, task: &Task, error: String, now: DateTime<Utc>) -> Result<bool, StateError> {
+        self.permissions.check_write(Store::Tasks)?;
+        self.limiter.check(Store::Tasks)?;
+        let now = now.timestamp_millis() as u64;
+        self.update_leased(task, |mut current| {
+            current.last_error = error.clone();
+            if current.attempts >= current.max_attempts {
+                current.status = TaskStatus::TASK_FAILED;
+            } else {
+                current.visible_at = now + SyncStateAccess::get_backoff(current.attempts);
+            }
+            Some(current)
+        })
+    }
+
+    fn get(&self, kind: String, id: Uuid) -> Result<Option<Task>, StateError> {
+        self.permissions.check_read(Store::Tasks)?;
+        let key = TaskAccess::key(&kind, &id.to_string())?;
+        match self.db.get(key)? {
+            Some(b) => Ok(Some(Task::parse_from_bytes(b.deref())?)),
+            None => Ok(None)
+        }
+    }
+
+    fn list_failed(&self, kind: String) -> Result<Vec<Task>, StateError> {
+        self.permissions.check_read(Store::Tasks)?;
+        let prefix = TaskAccess::key_prefix(&kind)?;
+        let mut result = Vec::new();
+        for entry in self.db.scan_prefix(prefix) {
+            let (_, value) = entry?;
+            if let Ok(task) = Task::parse_from_bytes(value.as_ref()) {
+                if task.status == TaskStatus::TASK_FAILED {
+                    result.push(task);
+                }
+            }
+        }
+        result.sort_by_key(|task| task.position);
+        Ok(result)
+    }
+
+    fn remove(&self, kind: String, id: Uuid) -> Result<(), StateError> {
+        self.permissions.check_write(Store::Tasks)?;
+        self.limiter.check(Store::Tasks)?;
+        let key = TaskAccess::key(&kind, &id.to_string())?;
+        self.health.transaction(&self.db, |tx| {
+            Ok(tx.remove(key.as_bytes())?.map(|_| ()))
+        })?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use chrono::{Duration, Utc};
+    use tempdir::TempDir;
+    use uuid::Uuid;
+    use crate::access::tasks::Tasks;
+    use crate::proto::tasks::TaskStatus;
+    use crate::storage::sled_access::SledStorage;
+
+    #[test]
+    fn lease_and_complete() {
+        let tmp_dir = TempDir::new("tasks").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let store = access.get_tasks();
+
+        let task1 = store.enqueue("broadcast".to_string(), vec![1], 3).unwrap();
+        let task2 = store.enqueue("broadcast".to_string(), vec![2], 3).unwrap();
+        store.enqueue("sync".to_string(), vec![3], 3).unwrap();
+
+        let now = Utc::now();
+        let leased = store.lease("broadcast".to_string(), now, Duration::minutes(1), 10).unwrap();
+        assert_eq!(leased.len(), 2);
+        assert_eq!(leased[0].payload, vec![1]);
+        assert_eq!(leased[0].attempts, 1);
+
+        // hidden while leased
+        let act = store.lease("broadcast".to_string(), now, Duration::minutes(1), 10).unwrap();
+        assert!(act.is_empty());
+
+        assert!(store.complete(&leased[0]).unwrap());
+        assert!(store.get("broadcast".to_string(), Uuid::from_str(task1.get_id()).unwrap()).unwrap().is_none());
+
+        // the lease of the second one has expired, so it's available again
+        let act = store.lease("broadcast".to_string(), now + Duration::minutes(2), Duration::minutes(1), 10).unwrap();
+        assert_eq!(act.len(), 1);
+        assert_eq!(act[0].id, task2.id);
+        assert_eq!(act[0].attempts, 2);
+        // and cannot be completed by the first worker anymore
+        assert!(!store.complete(&leased[1]).unwrap());
+        assert!(store.complete(&act[0]).unwrap());
+    }
+
+    #[test]
+    fn fail_after_last_lease_expired() {
+        let tmp_dir = TempDir::new("tasks").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let store = access.get_tasks();
+
+        let task = store.enqueue("broadcast".to_string(), vec![1], 1).unwrap();
+
+        let now = Utc::now();
+        let leased = store.lease("broadcast".to_string(), now, Duration::minutes(1), 10).unwrap();
+        assert_eq!(leased.len(), 1);
+
+        // the worker didn't complete it, and there are no attempts left
+        let act = store.lease("broadcast".to_string(), now + Duration::minutes(2), Duration::minutes(1), 10).unwrap();
+        assert!(act.is_empty());
+
+        let failed = store.list_failed("broadcast".to_string()).unwrap();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].id, task.id);
+        assert_eq!(failed[0].attempts, 1);
+        assert!(!store.complete(&leased[0]).unwrap());
+    }
+
+    #[test]
+    fn retry_failed() {
+        let tmp_dir = TempDir::new("tasks").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let store = access.get_tasks();
+
+        store.enqueue("maintenance".to_string(), vec![], 2).unwrap();
+
+        let now = Utc::now();
+        let leased = store.lease("maintenance".to_string(), now, Duration::minutes(1), 10).unwrap();
+        assert!(store.fail(&leased[0], "Timeout".to_string(), now).unwrap());
+
+        // backed off after a failure
+        let act = store.lease("maintenance".to_string(), now + Duration::seconds(10), Duration::minutes(1), 10).unwrap();
+        assert!(act.is_empty());
+        let leased = store.lease("maintenance".to_string(), now + Duration::seconds(30), Duration::minutes(1), 10).unwrap();
+        assert_eq!(leased.len(), 1);
+        assert!(store.list_failed("maintenance".to_string()).unwrap().is_empty());
+
+        assert!(store.fail(&leased[0], "Timeout again".to_string(), now + Duration::seconds(30)).unwrap());
+        let act = store.lease("maintenance".to_string(), now + Duration::hours(2), Duration::minutes(1), 10).unwrap();
+        assert!(act.is_empty());
+
+        let failed = store.list_failed("maintenance".to_string()).unwrap();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].status, TaskStatus::TASK_FAILED);
+        assert_eq!(failed[0].attempts, 2);
+        assert_eq!(failed[0].last_error, "Timeout again");
+
+        store.remove("maintenance".to_string(), Uuid::from_str(failed[0].get_id()).unwrap()).unwrap();
+        assert!(store.list_failed("maintenance".to_string()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn reject_invalid_kind() {
+        let tmp_dir = TempDir::new("tasks").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let store = access.get_tasks();
+
+        assert!(store.enqueue("".to_string(), vec![], 1).is_err());
+        assert!(store.enqueue("sync/other".to_string(), vec![], 1).is_err());
+    }
+}
@@ -0,0 +1,292 @@
+use std::ops::Deref;
+use std::sync::Arc;
+use chrono::Utc;
+use log::warn;
+use protobuf::Message;
+use sled::Db;
+use crate::access::permissions::{Permissions, Store};
+use crate::access::tokens::Tokens;
+use crate::errors::{InvalidValueError, StateError};
+use crate::proto::tokens::TokenMetadata;
+use crate::storage::rate_limit::RateLimiter;
+use crate::storage::health::Health;
+use crate::storage::ttl_policy::TtlPolicy;
+use crate::validate;
+use crate::storage::sequence::Batch;
+
+///
+/// # Storage:
+///
+/// - `token:<BLOCKCHAIN>/<CONTRACT>` to store a `TokenMetadata`, where the contract address is in lowercase
+///
+const PREFIX_KEY: &str = "token:";
+
+///
+/// Max decimals that make sense for an ERC20 token, as the max uint256 value has 78 digits
+const MAX_DECIMALS: u32 = 77;
+
+pub struct TokenAccess {
+    pub(crate) db: Arc<Db>,
+    pub(crate) ttl: TtlPolicy,
+    pub(crate) permissions: Permissions,
+    pub(crate) limiter: Arc<RateLimiter>,
+    pub(crate) health: Arc<Health>,
+}
+
+impl TokenAccess {
+
+    fn get_key(blockchain: u32, contract: &str) -> Result<String, StateError> {
+        validate::check_ethereum_address(contract)
+            .map_err(|_| InvalidValueError::Name("contract".to_string()))?;
+        Ok(format!("{}{}/{}", PREFIX_KEY, blockchain, contract.to_lowercase()))
+    }
+
+    fn validate(meta: &TokenMetadata) -> Result<(), InvalidValueError> {
+        if meta.symbol.trim().is_empty() {
+            return Err(InvalidValueError::Name("symbol".to_string()))
+        }
+        if meta.decimals > MAX_DECIMALS {
+            return Err(InvalidValueError::NameMessage("decimals".to_string(), format!("more than {}", MAX_DECIMALS)))
+        }
+        if !meta.icon_url.is_empty() && !meta.icon_url.starts_with("https://") && !meta.icon_url.starts_with("http://") {
+            return Err(InvalidValueError::Name("icon_url".to_string()))
+        }
+        Ok(())
+    }
+
+    fn is_expired(meta: &TokenMetadata, now: u64) -> bool {
+        meta.ttl < now
+    }
+
+    fn purge(&self) -> Result<usize, StateError> {
+        let now = Utc::now().timestamp_millis() as u64;
+        let mut count = 0;
+        let mut batch = Batch::default();
+        for entry in self.db.scan_prefix(PREFIX_KEY) {
+            let (key, value) = entry?;
+            let delete = match TokenMetadata::parse_from_bytes(value.as_ref()) {
+                Ok(meta) => TokenAccess::is_expired(&meta, now),
+                // always delete invalid entries
+                Err(_) => true
+            };
+            if delete {
+                count += 1;
+                batch.remove(key);
+            }
+        }
+        if count > 0 {
+            self.health.apply_batch(&self.db, batch)?;
+        }
+        Ok(count)
+    }
+}
+
+impl Tokens for TokenAccess {
+
+    fn set(&self, meta: TokenMetadata, ttl: Option<u64>) -> Result<TokenMetadata, StateError> {
+        self.permissions.check_write(Store::Tokens)?;
+        self.limiter.check(Store::Tokens)?;
+        let key = TokenAccess::get_key(meta.blockchain, &meta.contract)?;
+        TokenAccess::validate(&meta)?;
+
+        let mut meta = meta;
+        meta.contract = meta.contract.to_lowercase();
+        meta.ts = Utc::now().timestamp_millis() as u64;
+        let max_ttl = self.ttl.token_max.as_millis() as u64;
+        meta.ttl = meta.ts + ttl.unwrap_or(self.ttl.token_default.as_millis() as u64).min(max_ttl);
+
+        let mut batch = Batch::default();
+        batch.insert(key.as_bytes(), meta.write_to_bytes()?);
+        self.health.apply(&self.db, batch)?;
+        Ok(meta)
+    }
+
+    fn get(&self, blockchain: u32, contract: String) -> Result<Option<TokenMetadata>, StateError> {
+        self.permissions.check_read(Store::Tokens)?;
+        let key = TokenAccess::get_key(blockchain, &contract)?;
+        match self.db.get(key)? {
+            Some(b) => {
+                let meta = TokenMetadata::parse_from_bytes(b.deref())?;
+                if TokenAccess::is_expired(&meta, Utc::now().timestamp_millis() as u64) {
+                    Ok(None)
+                } else {
+                    Ok(Some(meta))
+                }
+            },
+            None => Ok(None)
+        }
+    }
+
+    fn list(&self, blockchain: Option<u32>) -> Result<Vec<TokenMetadata>, StateError> {
+        self.permissions.check_read(Store::Tokens)?;
+        let prefix = match blockchain {
+            None => PREFIX_KEY.to_string(),
+            Some(blockchain) => format!("{}{}/", PREFIX_KEY, blockchain)
+        };
+        let now = Utc::now().timestamp_millis() as u64;
+        let mut result = Vec::new();
+        let mut outdated = 0;
+        for entry in self.db.scan_prefix(prefix) {
+            let (key, value) = entry?;
+            match TokenMetadata::parse_from_bytes(value.as_ref()) {
+                Ok(meta) => {
+                    if TokenAccess::is_expired(&meta, now) {
+                        outdated += 1;
+                        continue;
+                    }
+                    result.push(meta);
+                }
+                Err(e) => {
+                    warn!("Skip unreadable token {}: {:?}", String::from_utf8_lossy(key.as_ref()), e);
+                }
+            }
+        }
+
+        if outdated > result.len() && self.permissions.can_write(Store::Tokens) {
+            let _ = self.purge();
+        }
+
+        // the keys are ordered as strings, which is not the numeric order of the blockchains
+        result.sort_by(|a, b| a.blockchain.cmp(&b.blockchain).then_with(|| a.contract.cmp(&b.contract)));
+        Ok(result)
+    }
+
+    fn remove(&self, blockchain: u32, contract: String) -> Result<bool, StateError> {
+        self.permissions.check_write(Store::Tokens)?;
+        self.limiter.check(Store::Tokens)?;
+        let key = TokenAccess::get_key(blockchain, &contract)?;
+        let removed = self.health.transaction(&self.db, |tx| {
+            Ok(tx.remove(key.as_bytes())?.map(|_| ()))
+        })?;
+        Ok(removed.is_some())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+    use std::time::Duration;
+    use tempdir::TempDir;
+    use crate::access::permissions::{Permissions, Store};
+    use crate::access::tokens::Tokens;
+    use crate::errors::StateError;
+    use crate::proto::tokens::TokenMetadata;
+    use crate::storage::sled_access::SledStorage;
+
+    fn create_token(blockchain: u32, contract: &str, symbol: &str, decimals: u32) -> TokenMetadata {
+        let mut meta = TokenMetadata::new();
+        meta.blockchain = blockchain;
+        meta.contract = contract.to_string();
+        meta.symbol = symbol.to_string();
+        meta.decimals = decimals;
+        meta
+    }
+
+    #[test]
+    fn set_and_get() {
+        let tmp_dir = TempDir::new("tokens").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let store = access.get_tokens();
+
+        let mut meta = create_token(100, "0xdAC17F958D2ee523a2206206994597C13D831ec7", "USDT", 6);
+        meta.name = "Tether USD".to_string();
+        meta.icon_url = "https://example.com/usdt.png".to_string();
+        meta.verified = true;
+        let stored = store.set(meta, None).unwrap();
+        assert_eq!(stored.contract, "0xdac17f958d2ee523a2206206994597c13d831ec7");
+        assert!(stored.ts > 0);
+        assert!(stored.ttl > stored.ts);
+
+        let act = store.get(100, "0xDAC17F958D2EE523A2206206994597C13D831EC7".to_string()).unwrap().unwrap();
+        assert_eq!(act.symbol, "USDT");
+        assert_eq!(act.decimals, 6);
+        assert_eq!(act.name, "Tether USD");
+        assert!(act.verified);
+
+        assert!(store.get(101, "0xdac17f958d2ee523a2206206994597c13d831ec7".to_string()).unwrap().is_none());
+
+        assert!(store.remove(100, "0xdac17f958d2ee523a2206206994597c13d831ec7".to_string()).unwrap());
+        assert!(!store.remove(100, "0xdac17f958d2ee523a2206206994597c13d831ec7".to_string()).unwrap());
+        assert!(store.get(100, "0xdac17f958d2ee523a2206206994597c13d831ec7".to_string()).unwrap().is_none());
+    }
+
+    #[test]
+    fn reject_invalid() {
+        let tmp_dir = TempDir::new("tokens").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let store = access.get_tokens();
+
+        assert!(store.set(create_token(100, "0xdac17f", "USDT", 6), None).is_err());
+        assert!(store.set(create_token(100, "0xdac17f958d2ee523a2206206994597c13d831ec7", "", 6), None).is_err());
+        assert!(store.set(create_token(100, "0xdac17f958d2ee523a2206206994597c13d831ec7", "USDT", 78), None).is_err());
+
+        let mut meta = create_token(100, "0xdac17f958d2ee523a2206206994597c13d831ec7", "USDT", 6);
+        meta.icon_url = "javascript:alert(1)".to_string();
+        assert!(store.set(meta, None).is_err());
+
+        assert!(store.list(None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn list_by_blockchain() {
+        let tmp_dir = TempDir::new("tokens").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let store = access.get_tokens();
+
+        store.set(create_token(100, "0xdac17f958d2ee523a2206206994597c13d831ec7", "USDT", 6), None).unwrap();
+        store.set(create_token(100, "0x6b175474e89094c44da98b954eedeac495271d0f", "DAI", 18), None).unwrap();
+        store.set(create_token(10005, "0x3f7dd0b106b10d06bb4813715b4e24d9e626dd64", "WEENUS", 18), None).unwrap();
+        store.set(create_token(2, "0xdac17f958d2ee523a2206206994597c13d831ec7", "USDT", 6), None).unwrap();
+
+        let all = store.list(None).unwrap();
+        let keys: Vec<(u32, String)> = all.into_iter().map(|t| (t.blockchain, t.symbol)).collect();
+        assert_eq!(keys, vec![
+            (2, "USDT".to_string()),
+            (100, "DAI".to_string()),
+            (100, "USDT".to_string()),
+            (10005, "WEENUS".to_string()),
+        ]);
+
+        let eth = store.list(Some(100)).unwrap();
+        assert_eq!(eth.len(), 2);
+        assert!(store.list(Some(101)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn expire_by_ttl() {
+        let tmp_dir = TempDir::new("tokens").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let store = access.get_tokens();
+
+        store.set(create_token(100, "0xdac17f958d2ee523a2206206994597c13d831ec7", "USDT", 6), Some(10)).unwrap();
+        store.set(create_token(100, "0x6b175474e89094c44da98b954eedeac495271d0f", "DAI", 18), None).unwrap();
+        thread::sleep(Duration::from_millis(20));
+
+        assert!(store.get(100, "0xdac17f958d2ee523a2206206994597c13d831ec7".to_string()).unwrap().is_none());
+        let all = store.list(None).unwrap();
+        assert_eq!(all.len(), 1);
+        assert_eq!(all[0].symbol, "DAI");
+    }
+
+    #[test]
+    fn limit_ttl() {
+        let tmp_dir = TempDir::new("tokens").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let store = access.get_tokens();
+
+        let stored = store.set(create_token(100, "0xdac17f958d2ee523a2206206994597c13d831ec7", "USDT", 6), Some(u64::MAX / 2)).unwrap();
+        assert_eq!(stored.ttl - stored.ts, 30 * 24 * 60 * 60 * 1000);
+    }
+
+    #[test]
+    fn check_permissions() {
+        let tmp_dir = TempDir::new("tokens").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let restricted = access.restrict(Permissions::full().without(Store::Tokens));
+
+        let act = restricted.get_tokens().set(create_token(100, "0xdac17f958d2ee523a2206206994597c13d831ec7", "USDT", 6), None);
+        assert_eq!(act, Err(StateError::NotPermitted));
+        let act = restricted.get_tokens().list(None);
+        assert_eq!(act, Err(StateError::NotPermitted));
+    }
+}
@@ -0,0 +1,426 @@
+//!
+//! Transactions in JSON, as they are imported from JSON Lines.
+//!
+//! The format follows the canonical Protobuf JSON mapping of the `Transaction` message, i.e. the fields are in
+//! lowerCamelCase (the original snake_case names are accepted too), enums are referenced by name or by number, and
+//! 64-bit numbers may be encoded as strings. An unknown field is an error, so a misspelled one is not silently dropped.
+//! Ex.:
+//!
+//! ```json
+//! {"blockchain": "CHAIN_ETHEREUM", "txId": "0x2f76...", "sinceTimestamp": "1647313850992", "state": "CONFIRMED", "changes": [...]}
+//! ```
+//!
+use protobuf::ProtobufEnum;
+use serde_json::{Map, Value};
+use crate::proto::transactions::{BitcoinInput, BitcoinOutput, BlockRef, Change, Change_ChangeType, NonceRef, TokenTransfer, Transaction};
+
+type Object = Map<String, Value>;
+
+///
+/// Known fields of each message, as pairs of the JSON name and the original proto name
+const TX_FIELDS: &[(&str, &str)] = &[
+    ("blockchain", "blockchain"), ("txId", "tx_id"), ("sinceTimestamp", "since_timestamp"),
+    ("syncTimestamp", "sync_timestamp"), ("confirmTimestamp", "confirm_timestamp"), ("state", "state"),
+    ("block", "block"), ("blockPos", "block_pos"), ("status", "status"), ("changes", "changes"),
+    ("version", "version"), ("nonceRef", "nonce_ref"), ("replacedBy", "replaced_by"),
+    ("internalTransfer", "internal_transfer"), ("inputs", "inputs"), ("outputs", "outputs"), ("vsize", "vsize"),
+    ("tokenTransfers", "token_transfers"),
+];
+const BLOCK_FIELDS: &[(&str, &str)] = &[("height", "height"), ("blockId", "block_id"), ("timestamp", "timestamp")];
+const NONCE_REF_FIELDS: &[(&str, &str)] = &[("from", "from"), ("nonce", "nonce")];
+const CHANGE_FIELDS: &[(&str, &str)] = &[
+    ("walletId", "wallet_id"), ("entryId", "entry_id"), ("address", "address"), ("hdPath", "hd_path"),
+    ("asset", "asset"), ("amount", "amount"), ("changeType", "change_type"), ("direction", "direction"),
+];
+const INPUT_FIELDS: &[(&str, &str)] = &[
+    ("txid", "txid"), ("vout", "vout"), ("amount", "amount"), ("scriptType", "script_type"), ("address", "address"),
+    ("own", "own"),
+];
+const OUTPUT_FIELDS: &[(&str, &str)] = &[
+    ("vout", "vout"), ("amount", "amount"), ("scriptType", "script_type"), ("address", "address"), ("own", "own"),
+];
+const TOKEN_TRANSFER_FIELDS: &[(&str, &str)] = &[
+    ("logIndex", "log_index"), ("contract", "contract"), ("standard", "standard"), ("from", "from"), ("to", "to"),
+    ("amount", "amount"), ("tokenId", "token_id"),
+];
+
+///
+/// Parse a single transaction. The error describes the first invalid field.
+pub(crate) fn parse_transaction(json: &str) -> Result<Transaction, String> {
+    let value: Value = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    let obj = as_object(&value, "transaction")?;
+    check_known(obj, "", TX_FIELDS)?;
+
+    let mut tx = Transaction::new();
+    if let Some(v) = get_field(obj, "blockchain", "blockchain") {
+        tx.blockchain = as_enum(v, "blockchain")?;
+    }
+    if let Some(v) = get_field(obj, "txId", "tx_id") {
+        tx.tx_id = as_string(v, "txId")?;
+    }
+    if let Some(v) = get_field(obj, "sinceTimestamp", "since_timestamp") {
+        tx.since_timestamp = as_u64(v, "sinceTimestamp")?;
+    }
+    if let Some(v) = get_field(obj, "syncTimestamp", "sync_timestamp") {
+        tx.sync_timestamp = as_u64(v, "syncTimestamp")?;
+    }
+    if let Some(v) = get_field(obj, "confirmTimestamp", "confirm_timestamp") {
+        tx.confirm_timestamp = as_u64(v, "confirmTimestamp")?;
+    }
+    if let Some(v) = get_field(obj, "state", "state") {
+        tx.state = as_enum(v, "state")?;
+    }
+    if let Some(v) = get_field(obj, "block", "block") {
+        tx.set_block(parse_block(v)?);
+    }
+    if let Some(v) = get_field(obj, "blockPos", "block_pos") {
+        tx.block_pos = as_u32(v, "blockPos")?;
+    }
+    if let Some(v) = get_field(obj, "status", "status") {
+        tx.status = as_enum(v, "status")?;
+    }
+    if let Some(v) = get_field(obj, "changes", "changes") {
+        let items = v.as_array().ok_or_else(|| invalid("changes"))?;
+        for item in items {
+            tx.changes.push(parse_change(item)?);
+        }
+    }
+    if let Some(v) = get_field(obj, "version", "version") {
+        tx.version = as_u64(v, "version")?;
+    }
+    if let Some(v) = get_field(obj, "nonceRef", "nonce_ref") {
+        tx.set_nonce_ref(parse_nonce_ref(v)?);
+    }
+    if let Some(v) = get_field(obj, "replacedBy", "replaced_by") {
+        tx.replaced_by = as_string(v, "replacedBy")?;
+    }
+    if let Some(v) = get_field(obj, "internalTransfer", "internal_transfer") {
+        tx.internal_transfer = as_bool(v, "internalTransfer")?;
+    }
+    if let Some(v) = get_field(obj, "inputs", "inputs") {
+        let items = v.as_array().ok_or_else(|| invalid("inputs"))?;
+        for item in items {
+            tx.inputs.push(parse_input(item)?);
+        }
+    }
+    if let Some(v) = get_field(obj, "outputs", "outputs") {
+        let items = v.as_array().ok_or_else(|| invalid("outputs"))?;
+        for item in items {
+            tx.outputs.push(parse_output(item)?);
+        }
+    }
+    if let Some(v) = get_field(obj, "vsize", "vsize") {
+        tx.vsize = as_u32(v, "vsize")?;
+    }
+    if let Some(v) = get_field(obj, "tokenTransfers", "token_transfers") {
+        let items = v.as_array().ok_or_else(|| invalid("tokenTransfers"))?;
+        for item in items {
+            tx.token_transfers.push(parse_token_transfer(item)?);
+        }
+    }
+    if tx.tx_id.is_empty() {
+        return Err(invalid("txId"))
+    }
+    Ok(tx)
+}
+
+fn parse_block(value: &Value) -> Result<BlockRef, String> {
+    let obj = as_object(value, "block")?;
+    check_known(obj, "block.", BLOCK_FIELDS)?;
+    let mut block = BlockRef::new();
+    if let Some(v) = get_field(obj, "height", "height") {
+        block.height = as_u64(v, "block.height")?;
+    }
+    if let Some(v) = get_field(obj, "blockId", "block_id") {
+        block.block_id = as_string(v, "block.blockId")?;
+    }
+    if let Some(v) = get_field(obj, "timestamp", "timestamp") {
+        block.timestamp = as_u64(v, "block.timestamp")?;
+    }
+    Ok(block)
+}
+
+fn parse_nonce_ref(value: &Value) -> Result<NonceRef, String> {
+    let obj = as_object(value, "nonceRef")?;
+    check_known(obj, "nonceRef.", NONCE_REF_FIELDS)?;
+    let mut nonce_ref = NonceRef::new();
+    if let Some(v) = get_field(obj, "from", "from") {
+        nonce_ref.from = as_string(v, "nonceRef.from")?;
+    }
+    if let Some(v) = get_field(obj, "nonce", "nonce") {
+        nonce_ref.nonce = as_u64(v, "nonceRef.nonce")?;
+    }
+    Ok(nonce_ref)
+}
+
+fn parse_change(value: &Value) -> Result<Change, String> {
+    let obj = as_object(value, "changes")?;
+    check_known(obj, "changes.", CHANGE_FIELDS)?;
+    let mut change = Change::new();
+    if let Some(v) = get_field(obj, "walletId", "wallet_id") {
+        change.wallet_id = as_string(v, "changes.walletId")?;
+    }
+    if let Some(v) = get_field(obj, "entryId", "entry_id") {
+        change.entry_id = as_u32(v, "changes.entryId")?;
+    }
+    if let Some(v) = get_field(obj, "address", "address") {
+        change.address = as_string(v, "changes.address")?;
+    }
+    if let Some(v) = get_field(obj, "hdPath", "hd_path") {
+        change.hd_path = as_string(v, "changes.hdPath")?;
+    }
+    if let Some(v) = get_field(obj, "asset", "asset") {
+        change.asset = as_string(v, "changes.asset")?;
+    }
+    if let Some(v) = get_field(obj, "amount", "amount") {
+        change.amount = as_string(v, "changes.amount")?;
+    }
+    if let Some(v) = get_field(obj, "changeType", "change_type") {
+        change.change_type = as_enum::<Change_ChangeType>(v, "changes.changeType")?;
+    }
+    if let Some(v) = get_field(obj, "direction", "direction") {
+        change.direction = as_enum(v, "changes.direction")?;
+    }
+    Ok(change)
+}
+
+fn parse_input(value: &Value) -> Result<BitcoinInput, String> {
+    let obj = as_object(value, "inputs")?;
+    check_known(obj, "inputs.", INPUT_FIELDS)?;
+    let mut input = BitcoinInput::new();
+    if let Some(v) = get_field(obj, "txid", "txid") {
+        input.txid = as_string(v, "inputs.txid")?;
+    }
+    if let Some(v) = get_field(obj, "vout", "vout") {
+        input.vout = as_u32(v, "inputs.vout")?;
+    }
+    if let Some(v) = get_field(obj, "amount", "amount") {
+        input.amount = as_u64(v, "inputs.amount")?;
+    }
+    if let Some(v) = get_field(obj, "scriptType", "script_type") {
+        input.script_type = as_enum(v, "inputs.scriptType")?;
+    }
+    if let Some(v) = get_field(obj, "address", "address") {
+        input.address = as_string(v, "inputs.address")?;
+    }
+    if let Some(v) = get_field(obj, "own", "own") {
+        input.own = as_bool(v, "inputs.own")?;
+    }
+    Ok(input)
+}
+
+fn parse_output(value: &Value) -> Result<BitcoinOutput, String> {
+    let obj = as_object(value, "outputs")?;
+    check_known(obj, "outputs.", OUTPUT_FIELDS)?;
+    let mut output = BitcoinOutput::new();
+    if let Some(v) = get_field(obj, "vout", "vout") {
+        output.vout = as_u32(v, "outputs.vout")?;
+    }
+    if let Some(v) = get_field(obj, "amount", "amount") {
+        output.amount = as_u64(v, "outputs.amount")?;
+    }
+    if let Some(v) = get_field(obj, "scriptType", "script_type") {
+        output.script_type = as_enum(v, "outputs.scriptType")?;
+    }
+    if let Some(v) = get_field(obj, "address", "address") {
+        output.address = as_string(v, "outputs.address")?;
+    }
+    if let Some(v) = get_field(obj, "own", "own") {
+        output.own = as_bool(v, "outputs.own")?;
+    }
+    Ok(output)
+}
+
+fn parse_token_transfer(value: &Value) -> Result<TokenTransfer, String> {
+    let obj = as_object(value, "tokenTransfers")?;
+    check_known(obj, "tokenTransfers.", TOKEN_TRANSFER_FIELDS)?;
+    let mut transfer = TokenTransfer::new();
+    if let Some(v) = get_field(obj, "logIndex", "log_index") {
+        transfer.log_index = as_u32(v, "tokenTransfers.logIndex")?;
+    }
+    if let Some(v) = get_field(obj, "contract", "contract") {
+        transfer.contract = as_string(v, "tokenTransfers.contract")?;
+    }
+    if let Some(v) = get_field(obj, "standard", "standard") {
+        transfer.standard = as_enum(v, "tokenTransfers.standard")?;
+    }
+    if let Some(v) = get_field(obj, "from", "from") {
+        transfer.from = as_string(v, "tokenTransfers.from")?;
+    }
+    if let Some(v) = get_field(obj, "to", "to") {
+        transfer.to = as_string(v, "tokenTransfers.to")?;
+    }
+    if let Some(v) = get_field(obj, "amount", "amount") {
+        transfer.amount = as_string(v, "tokenTransfers.amount")?;
+    }
+    if let Some(v) = get_field(obj, "tokenId", "token_id") {
+        transfer.token_id = as_string(v, "tokenTransfers.tokenId")?;
+    }
+    Ok(transfer)
+}
+
+fn invalid(field: &str) -> String {
+    format!("Invalid {}", field)
+}
+
+///
+/// Fail on the first field that is not in the list of `known` fields, by either name
+fn check_known(obj: &Object, path: &str, known: &[(&str, &str)]) -> Result<(), String> {
+    match obj.keys().find(|key| !known.iter().any(|(json_name, proto_name)| key == json_name || key == proto_name)) {
+        Some(key) => Err(format!("Unknown {}{}", path, key)),
+        None => Ok(())
+    }
+}
+
+///
+/// Value of the field by its JSON name or the original proto name. A `null` value is the same as a missing field.
+fn get_field<'a>(obj: &'a Object, json_name: &str, proto_name: &str) -> Option<&'a Value> {
+    obj.get(json_name)
+        .or_else(|| obj.get(proto_name))
+        .filter(|v| !v.is_null())
+}
+
+fn as_object<'a>(value: &'a Value, field: &str) -> Result<&'a Object, String> {
+    value.as_object().ok_or_else(|| invalid(field))
+}
+
+fn as_string(value: &Value, field: &str) -> Result<String, String> {
+    value.as_str().map(|s| s.to_string()).ok_or_else(|| invalid(field))
+}
+
+fn as_bool(value: &Value, field: &str) -> Result<bool, String> {
+    value.as_bool().ok_or_else(|| invalid(field))
+}
+
+fn as_u64(value: &Value, field: &str) -> Result<u64, String> {
+    match value {
+        Value::Number(n) => n.as_u64(),
+        Value::String(s) => s.parse::<u64>().ok(),
+        _ => None
+    }.ok_or_else(|| invalid(field))
+}
+
+fn as_u32(value: &Value, field: &str) -> Result<u32, String> {
+    as_u64(value, field)
+        .and_then(|n| u32::try_from(n).map_err(|_| invalid(field)))
+}
+
+fn as_enum<E: ProtobufEnum>(value: &Value, field: &str) -> Result<E, String> {
+    match value {
+        Value::String(name) => E::values().iter()
+            .find(|v| v.descriptor().name() == name.as_str())
+            .copied(),
+        Value::Number(n) => n.as_i64()
+            .and_then(|n| i32::try_from(n).ok())
+            .and_then(E::from_i32),
+        _ => None
+    }.ok_or_else(|| invalid(field))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::proto::transactions::{BlockchainId, Change_ChangeType, Direction, ScriptType, State, TokenStandard};
+    use crate::storage::transaction_json::parse_transaction;
+
+    #[test]
+    fn parse_full_tx() {
+        let json = r#"{"blockchain": "CHAIN_ETHEREUM", "txId": "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b",
+            "sinceTimestamp": "1647313850992", "confirmTimestamp": 1647313865992, "state": "CONFIRMED",
+            "block": {"height": "14389253", "blockId": "0xb7e7b6b2bba0e5fa4e67a8fef1ca8a0e1b2a2e0b1d4e1d4e0a6f8b1c2d3e4f50"},
+            "nonceRef": {"from": "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48", "nonce": 5},
+            "changes": [{"wallet_id": "72279ede-44c4-4951-925b-f51a7b9e929a", "address": "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48",
+                "asset": "ETH", "amount": "1000000000000000000", "changeType": "TRANSFER", "direction": 1}]}"#;
+        let act = parse_transaction(json).unwrap();
+        assert_eq!(act.blockchain, BlockchainId::CHAIN_ETHEREUM);
+        assert_eq!(act.tx_id, "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b");
+        assert_eq!(act.since_timestamp, 1_647_313_850_992);
+        assert_eq!(act.confirm_timestamp, 1_647_313_865_992);
+        assert_eq!(act.state, State::CONFIRMED);
+        assert_eq!(act.get_block().height, 14389253);
+        assert_eq!(act.get_nonce_ref().nonce, 5);
+        assert_eq!(act.changes.len(), 1);
+        assert_eq!(act.changes[0].wallet_id, "72279ede-44c4-4951-925b-f51a7b9e929a");
+        assert_eq!(act.changes[0].change_type, Change_ChangeType::TRANSFER);
+        assert_eq!(act.changes[0].direction, Direction::SEND);
+    }
+
+    #[test]
+    fn parse_bitcoin_tx() {
+        let json = r#"{"blockchain": "CHAIN_BITCOIN", "txId": "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b",
+            "sinceTimestamp": "1647313850992", "state": "SUBMITTED", "vsize": 141, "internalTransfer": true,
+            "inputs": [{"txid": "0e3e2357e806b6cdb1f70b54c3a3a17b6714ee1f0e68bebb44a74b1efd512098", "vout": 1,
+                "amount": "150000", "scriptType": "SCRIPT_P2WPKH", "address": "bc1qxy2kgdygjrsqtzq2n0yrf2493p83kkfjhx0wlh", "own": true}],
+            "outputs": [{"vout": 0, "amount": 100000, "script_type": "SCRIPT_P2WPKH", "address": "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq"},
+                {"vout": 1, "amount": 49000, "scriptType": "SCRIPT_P2WPKH", "address": "bc1qxy2kgdygjrsqtzq2n0yrf2493p83kkfjhx0wlh", "own": true}]}"#;
+        let act = parse_transaction(json).unwrap();
+        assert_eq!(act.blockchain, BlockchainId::CHAIN_BITCOIN);
+        assert_eq!(act.vsize, 141);
+        assert!(act.internal_transfer);
+        assert_eq!(act.inputs.len(), 1);
+        assert_eq!(act.inputs[0].txid, "0e3e2357e806b6cdb1f70b54c3a3a17b6714ee1f0e68bebb44a74b1efd512098");
+        assert_eq!(act.inputs[0].vout, 1);
+        assert_eq!(act.inputs[0].amount, 150000);
+        assert_eq!(act.inputs[0].script_type, ScriptType::SCRIPT_P2WPKH);
+        assert!(act.inputs[0].own);
+        assert_eq!(act.outputs.len(), 2);
+        assert_eq!(act.outputs[0].amount, 100000);
+        assert_eq!(act.outputs[0].script_type, ScriptType::SCRIPT_P2WPKH);
+        assert!(!act.outputs[0].own);
+        assert_eq!(act.outputs[1].vout, 1);
+        assert!(act.outputs[1].own);
+    }
+
+    #[test]
+    fn parse_token_transfers() {
+        let json = r#"{"blockchain": "CHAIN_ETHEREUM", "txId": "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b",
+            "tokenTransfers": [{"logIndex": 12, "contract": "0xdac17f958d2ee523a2206206994597c13d831ec7", "standard": "TOKEN_ERC20",
+                "from": "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48", "to": "0x6b175474e89094c44da98b954eedeac495271d0f", "amount": "1000000"},
+                {"log_index": 13, "contract": "0x3f7dd0b106b10d06bb4813715b4e24d9e626dd64", "standard": 2,
+                "from": "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48", "to": "0x6b175474e89094c44da98b954eedeac495271d0f", "token_id": "1234"}]}"#;
+        let act = parse_transaction(json).unwrap();
+        assert_eq!(act.token_transfers.len(), 2);
+        assert_eq!(act.token_transfers[0].log_index, 12);
+        assert_eq!(act.token_transfers[0].standard, TokenStandard::TOKEN_ERC20);
+        assert_eq!(act.token_transfers[0].amount, "1000000");
+        assert_eq!(act.token_transfers[0].to, "0x6b175474e89094c44da98b954eedeac495271d0f");
+        assert_eq!(act.token_transfers[1].log_index, 13);
+        assert_eq!(act.token_transfers[1].standard, TokenStandard::TOKEN_ERC721);
+        assert_eq!(act.token_transfers[1].token_id, "1234");
+
+        assert_eq!(
+            parse_transaction(r#"{"txId": "0x2f761cbf", "tokenTransfers": [{"logIndex": 1, "value": "1000000"}]}"#),
+            Err("Unknown tokenTransfers.value".to_string())
+        );
+    }
+
+    #[test]
+    fn reject_unknown_fields() {
+        assert_eq!(
+            parse_transaction(r#"{"txId": "0x2f761cbf", "sinceTimestmp": "1647313850992"}"#),
+            Err("Unknown sinceTimestmp".to_string())
+        );
+        assert_eq!(
+            parse_transaction(r#"{"txId": "0x2f761cbf", "block": {"height": 1, "hash": "0xb7e7b6b2"}}"#),
+            Err("Unknown block.hash".to_string())
+        );
+        assert_eq!(
+            parse_transaction(r#"{"txId": "0x2f761cbf", "outputs": [{"vout": 0, "value": 100000}]}"#),
+            Err("Unknown outputs.value".to_string())
+        );
+    }
+
+    #[test]
+    fn reject_invalid_tx() {
+        assert!(parse_transaction("not a json").is_err());
+        assert!(parse_transaction("[]").is_err());
+        assert_eq!(parse_transaction(r#"{"blockchain": "CHAIN_ETHEREUM"}"#), Err("Invalid txId".to_string()));
+        assert_eq!(
+            parse_transaction(r#"{"blockchain": "CHAIN_UNKNOWN", "txId": "0x2f761cbf"}"#),
+            Err("Invalid blockchain".to_string())
+        );
+        assert_eq!(
+            parse_transaction(r#"{"txId": "0x2f761cbf", "sinceTimestamp": -1}"#),
+            Err("Invalid sinceTimestamp".to_string())
+        );
+    }
+}
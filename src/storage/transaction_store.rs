@@ -1,29 +1,58 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::io::BufRead;
 use std::ops::{Bound, Deref};
 use std::str::FromStr;
 use std::sync::Arc;
+use std::sync::mpsc::Receiver;
+use bitcoin::hashes::{sha256, Hash};
 use chrono::{TimeZone, Utc};
+use log::warn;
+use num_bigint::BigUint;
+use num_traits::Zero;
 use protobuf::{Message, ProtobufEnum};
-use sled::{Batch, Db};
+use sled::Db;
 use uuid::Uuid;
-use crate::access::transactions::{Filter, RemoteCursor, Transactions};
+use crate::access::transactions::{AssetTotal, FacetedResult, Facets, Filter, ImportReport, Granularity, MergePolicy, PeriodStats, RemoteCursor, RetentionPolicy, Staleness, SubmitOutcome, TransactionField, TransactionWithMeta, Transactions, TransactionsChange, TimeMatch, TxFlag, WalletRef};
 use crate::access::pagination::{PageResult, PageQuery, Cursor};
+use crate::access::validation::{CheckedWrite, ValidationMode, ValidationWarning, Validators};
+use crate::access::permissions::{Permissions, Store};
+use crate::access::integrity::{CascadePolicy, DanglingReference, References, TxReference};
 use crate::errors::{StateError,InvalidValueError};
-use crate::proto::transactions::{Transaction as proto_Transaction, Cursor as proto_Cursor, TransactionMeta as proto_TransactionMeta, State};
+use crate::proto::quarantine::StoreType;
+use crate::proto::transactions::{Transaction as proto_Transaction, Cursor as proto_Cursor, TransactionMeta as proto_TransactionMeta, BlockchainId, Change_ChangeType, Direction, FirstSeen, MetaRevision, NonceRef, State};
+use crate::storage::tuning::Profiler;
 use crate::storage::indexing::{IndexedValue, QueryRanges, IndexConvert, IndexEncoding, Indexing};
 use crate::storage::version::Migration;
+use crate::storage::projection::{decode_projected, get_changed_fields};
+use crate::storage::address_registry_store;
+use crate::storage::allowance_store;
+use crate::storage::balance_store;
+use crate::storage::nonce_store;
+use crate::storage::notification::Notifier;
+use crate::storage::meta_proof;
+use crate::storage::quarantine_store;
+use crate::storage::transaction_json;
+use crate::storage::rate_limit::RateLimiter;
+use crate::storage::health::Health;
+use crate::storage::ttl_policy::TtlPolicy;
+use crate::storage::sequence::Batch;
 
 ///
 /// # Storage:
 ///
 /// - `tx:<UUID>` to store transaction data
 /// - `idx:tx:<INDEX>` for indexes, where the value is a UUID to reference the Transactions Data
+/// - `tx_head:<BLOCKCHAIN>` for the current height of the blockchain, as a big-endian `u64`
+/// - `addr_seen:<ADDRESS>` with the `FirstSeen` activity of the address
+/// - `txmeta_log:<BLOCKCHAIN>/<TXID>/<SEQ>` with the `MetaRevision` history of the transaction meta
 ///
 /// # Indexes:
 ///
 /// - `1/<TIMESTAMP>`
 /// - `2/<WALLET_ID>/<TIMESTAMP>`
 /// - `3/<WALLET_ID>/<IS_RECENT>/<TIMESTAMP>/<POS>/<TXHASH>`
+/// - `4/<BLOCKCHAIN>/<TIMESTAMP>`
+/// - `5/<BLOCKCHAIN>/<FROM>/<NONCE>/<TXHASH>`
 ///
 ///
 
@@ -31,6 +60,15 @@ const PREFIX_KEY: &'static str = "tx";
 const PREFIX_KEY_META: &'static str = "txmeta";
 const PREFIX_IDX: &'static str = "idx:tx";
 const PREFIX_CURSOR: &'static str = "addr_cursor";
+const PREFIX_HEAD: &str = "tx_head";
+const PREFIX_SEEN: &str = "addr_seen";
+const PREFIX_META_LOG: &str = "txmeta_log";
+/// Number of transactions submitted at once by `import_jsonl`
+const IMPORT_BATCH: usize = 1_000;
+/// Number of transactions removed at once by `prune`
+const PRUNE_BATCH: usize = 1_000;
+/// Index of the transactions by wallet with the recent ones first, used to find the pending duplicates
+const WALLET_CONFIRM_INDEX: usize = 3;
 
 enum IndexType {
     // `<WALLET_ID>/<IS_RECENT>/<TIMESTAMP>/<POS>/<TXHASH>`
@@ -39,6 +77,14 @@ enum IndexType {
     ByWallet(Uuid, u64),
     // `/<TIMESTAMP>`
     Everything(u64),
+    // `<BLOCKCHAIN>/<TIMESTAMP>`
+    ByBlockchain(u32, u64),
+    // `<BLOCKCHAIN>/<FROM>/<NONCE>/<TXHASH>`
+    ByNonce(u32, String, u64, String),
+    // `<TAG>/<BLOCKCHAIN>/<TXHASH>`, from the transaction meta
+    ByTag(String, u32, String),
+    // `<FLAG>/<BLOCKCHAIN>/<TXHASH>`, from the transaction meta
+    ByFlag(TxFlag, u32, String),
 }
 
 impl IndexType {
@@ -46,7 +92,11 @@ impl IndexType {
         match self {
             IndexType::Everything(_) => 1,
             IndexType::ByWallet(_, _) => 2,
-            IndexType::ByWalletAndConfirm(_, _, _, _, _) => 3,
+            IndexType::ByWalletAndConfirm(_, _, _, _, _) => WALLET_CONFIRM_INDEX,
+            IndexType::ByBlockchain(_, _) => 4,
+            IndexType::ByNonce(_, _, _, _) => 5,
+            IndexType::ByTag(_, _, _) => 6,
+            IndexType::ByFlag(_, _, _) => 7,
         }
     }
 }
@@ -72,120 +122,733 @@ impl IndexEncoding for IndexType {
                 format!("{}:{:}/{:}", PREFIX_IDX, self.get_prefix(),
                         IndexConvert::get_desc_timestamp(*ts))
             }
+            IndexType::ByBlockchain(blockchain, ts) => {
+                format!("{}:{:}/{:}/{:}", PREFIX_IDX, self.get_prefix(),
+                        blockchain,
+                        IndexConvert::get_desc_timestamp(*ts))
+            }
+            IndexType::ByNonce(blockchain, from, nonce, tx_id) => {
+                format!("{}:{:}/{:}/{:}/{:}/{:}", PREFIX_IDX, self.get_prefix(),
+                        blockchain,
+                        from.to_lowercase(),
+                        IndexConvert::get_asc_number(*nonce),
+                        tx_id)
+            }
+            IndexType::ByTag(tag, blockchain, tx_id) => {
+                format!("{}:{:}/{:}/{:}/{:}", PREFIX_IDX, self.get_prefix(),
+                        tag,
+                        blockchain,
+                        tx_id)
+            }
+            IndexType::ByFlag(flag, blockchain, tx_id) => {
+                format!("{}:{:}/{:}/{:}/{:}", PREFIX_IDX, self.get_prefix(),
+                        flag.get_code(),
+                        blockchain,
+                        tx_id)
+            }
         }
     }
 }
 
-impl IndexedValue<IndexType> for proto_Transaction {
+///
+/// Transaction indexed with a window for the _recent_ section of the wallet history. Besides the pending transactions
+/// the section keeps the transactions confirmed at the specified timestamp or later.
+struct RecentWindowed<'a>(&'a proto_Transaction, u64);
 
-    fn get_index(&self) -> Vec<IndexType> {
-        let mut keys: Vec<IndexType> = Vec::new();
+///
+/// True if the transaction belongs to the recent section of the wallet history, i.e. it's pending or it was confirmed
+/// at `recent_since` or later
+fn is_recent(tx: &proto_Transaction, recent_since: u64) -> bool {
+    tx.state == State::SUBMITTED || tx.state == State::PREPARED
+        || (tx.state == State::CONFIRMED && tx.confirm_timestamp > 0 && tx.confirm_timestamp >= recent_since)
+}
 
-        let timestamp = if self.confirm_timestamp > 0 {
-            self.confirm_timestamp
-        } else {
-            self.since_timestamp
-        };
+fn get_tx_index(tx: &proto_Transaction, recent_since: u64) -> Vec<IndexType> {
+    let mut keys: Vec<IndexType> = Vec::new();
 
+    let timestamp = if tx.confirm_timestamp > 0 {
+        tx.confirm_timestamp
+    } else {
+        tx.since_timestamp
+    };
 
-        keys.push(IndexType::Everything(timestamp));
 
-        let recent = self.state == State::SUBMITTED || self.state == State::PREPARED;
+    keys.push(IndexType::Everything(timestamp));
+    keys.push(IndexType::ByBlockchain(tx.blockchain.value() as u32, timestamp));
+
+    if let Some(nonce_ref) = tx.nonce_ref.as_ref() {
+        if !nonce_ref.from.is_empty() {
+            keys.push(IndexType::ByNonce(tx.blockchain.value() as u32, nonce_ref.from.clone(), nonce_ref.nonce, tx.tx_id.clone()));
+        }
+    }
+
+    let pending = tx.state == State::SUBMITTED || tx.state == State::PREPARED;
+    let recent = is_recent(tx, recent_since);
 
-        for change in self.get_changes() {
-            if let Ok(wallet_id) = Uuid::from_str(change.get_wallet_id()) {
-                keys.push(IndexType::ByWallet(wallet_id, timestamp));
-                let pos = if recent {
-                    IndexConvert::txid_as_pos(self.tx_id.clone())
+    for change in tx.get_changes() {
+        if let Ok(wallet_id) = Uuid::from_str(change.get_wallet_id()) {
+            keys.push(IndexType::ByWallet(wallet_id, timestamp));
+            let pos = if pending {
+                IndexConvert::txid_as_pos(tx.tx_id.clone())
+            } else {
+                if tx.block.is_some() {
+                    tx.block_pos.into()
                 } else {
-                    if self.block.is_some() {
-                        self.block_pos.into()
-                    } else {
-                        999999
-                    }
-                };
-                keys.push(IndexType::ByWalletAndConfirm(wallet_id.clone(), recent, timestamp, pos, self.tx_id.clone()));
-            }
+                    999999
+                }
+            };
+            keys.push(IndexType::ByWalletAndConfirm(wallet_id, recent, timestamp, pos, tx.tx_id.clone()));
         }
+    }
+
+    keys
+}
 
+impl IndexedValue<IndexType> for RecentWindowed<'_> {
+
+    fn get_index(&self) -> Vec<IndexType> {
+        get_tx_index(self.0, self.1)
+    }
+}
+
+impl IndexedValue<IndexType> for proto_TransactionMeta {
+
+    fn get_index(&self) -> Vec<IndexType> {
+        let blockchain = self.blockchain.value() as u32;
+        let mut keys: Vec<IndexType> = self.tags.iter()
+            .map(|tag| IndexType::ByTag(tag.clone(), blockchain, self.tx_id.clone()))
+            .collect();
+        for flag in [TxFlag::Starred, TxFlag::Hidden, TxFlag::NeedsReview] {
+            if flag.is_set(self) {
+                keys.push(IndexType::ByFlag(flag, blockchain, self.tx_id.clone()));
+            }
+        }
         keys
     }
 }
 
+///
+/// Time window of the index scan, as the timestamps of the most recent and the earliest entries. The indexes are ordered
+/// by the confirmation time of the confirmed transactions, so only with `TimeMatch::Ordered` it's limited by the window
+/// of the filter.
+fn get_index_window(filter: &Filter) -> (u64, u64) {
+    let now = Utc::now().timestamp_millis() as u64;
+    if filter.time_match != TimeMatch::Ordered {
+        return (now, 0)
+    }
+    let ts_now = filter.before
+        .map(|ts| ts.timestamp_millis().max(0) as u64)
+        .unwrap_or(now);
+    let ts_start = filter.after
+        .map(|ts| ts.timestamp_millis().max(0) as u64)
+        .unwrap_or(0);
+    (ts_now, ts_start)
+}
+
+///
+/// Bounds of the section of the wallet history, i.e. of the recent or of the rest of the transactions, within the window
+fn get_wallet_section(wallet_id: Uuid, recent: bool, (ts_now, ts_start): (u64, u64)) -> (Bound<String>, Bound<String>) {
+    let now = IndexType::ByWalletAndConfirm(wallet_id, recent, ts_now, u64::MAX, "0000000000000000".to_string()).get_index_key();
+    let start = IndexType::ByWalletAndConfirm(wallet_id, recent, ts_start, 0u64, "ffffffffffffffff".to_string()).get_index_key();
+    (Bound::Included(now), Bound::Included(start))
+}
 
 impl QueryRanges for Filter {
+    ///
+    /// Bounds of the index scan, limited to the time window of the filter with `TimeMatch::Ordered`. For a wallet it
+    /// includes both sections of its history, see `get_index_ranges` for the actual ranges to scan.
     fn get_index_bounds(&self) -> (Bound<String>, Bound<String>) {
-        let ts_now = Utc::now().naive_utc().timestamp_millis() as u64;
-        let ts_start = 0u64;
+        let window = get_index_window(self);
+        let (ts_now, ts_start) = window;
 
         if let Some(wallet) = &self.wallet {
-            let now = IndexType::ByWalletAndConfirm(wallet.get_wallet_id(), true, ts_now, u64::MAX, "0000000000000000".to_string()).get_index_key();
-            let start = IndexType::ByWalletAndConfirm(wallet.get_wallet_id(), false, ts_start, 0u64, "ffffffffffffffff".to_string()).get_index_key();
-            return (Bound::Included(now), Bound::Included(start))
+            let (now, _) = get_wallet_section(wallet.get_wallet_id(), true, window);
+            let (_, start) = get_wallet_section(wallet.get_wallet_id(), false, window);
+            return (now, start)
+        }
+
+        // a single blockchain can be scanned using its own index, for multiple we still have to check everything
+        if let Some(blockchains) = &self.blockchains {
+            if blockchains.len() == 1 {
+                let blockchain = blockchains[0];
+                let now = IndexType::ByBlockchain(blockchain, ts_now).get_index_key();
+                let start = IndexType::ByBlockchain(blockchain, ts_start).get_index_key();
+                return (Bound::Included(now), Bound::Included(start))
+            }
         }
 
         let now = IndexType::Everything(ts_now).get_index_key();
         let start = IndexType::Everything(ts_start).get_index_key();
         (Bound::Included(now), Bound::Included(start))
     }
+
+    ///
+    /// The wallet history has the recent section first, and then the rest, each ordered by time. So each of them is
+    /// scanned only within the time window.
+    fn get_index_ranges(&self) -> Vec<(Bound<String>, Bound<String>)> {
+        match &self.wallet {
+            Some(wallet) => {
+                let window = get_index_window(self);
+                vec![
+                    get_wallet_section(wallet.get_wallet_id(), true, window),
+                    get_wallet_section(wallet.get_wallet_id(), false, window),
+                ]
+            },
+            None => vec![self.get_index_bounds()]
+        }
+    }
+}
+
+///
+/// Read a stored transaction for another store. Gives `None` if there is no such transaction, or it cannot be read, or
+/// it's not permitted to read the transactions.
+pub(crate) fn find_tx(db: &Db, permissions: &Permissions, blockchain: u32, tx_id: &str) -> Option<proto_Transaction> {
+    if !permissions.can_read(Store::Transactions) {
+        return None
+    }
+    db.get(TransactionsAccess::get_key(blockchain, tx_id)).ok()
+        .flatten()
+        .and_then(|value| proto_Transaction::parse_from_bytes(value.as_ref()).ok())
+}
+
+///
+/// Transactions selected by the criteria of a filter which are checked against the transaction meta, i.e. the tag and
+/// the user flags
+struct MetaSelection {
+    /// Keys of the transactions which may be accepted, or `None` if the filter has no such criteria
+    included: Option<HashSet<String>>,
+    /// Keys of the transactions which must be skipped
+    excluded: HashSet<String>,
+}
+
+impl MetaSelection {
+    fn accepts(&self, tx_key: &String) -> bool {
+        let included = match &self.included {
+            Some(included) => included.contains(tx_key),
+            None => true
+        };
+        included && !self.excluded.contains(tx_key)
+    }
 }
 
 pub struct TransactionsAccess {
     pub(crate) db: Arc<Db>,
+    pub(crate) validators: Arc<Validators>,
+    pub(crate) references: Arc<References>,
+    pub(crate) permissions: Permissions,
+    pub(crate) ttl: TtlPolicy,
+    pub(crate) limiter: Arc<RateLimiter>,
+    pub(crate) health: Arc<Health>,
+    pub(crate) notifier: Arc<Notifier>,
+    pub(crate) profiler: Arc<Profiler>,
 }
 
 impl TransactionsAccess {
+
+    ///
+    /// Index keys of the transaction, with the recent section defined by the current `tx_recent_window`
+    fn get_tx_index_keys(&self, tx: &proto_Transaction) -> Vec<String> {
+        RecentWindowed(tx, self.get_recent_since()).get_index_keys()
+    }
+
+    ///
+    /// Earliest confirmation timestamp of a transaction that is still recent
+    fn get_recent_since(&self) -> u64 {
+        if self.ttl.tx_recent_window.is_zero() {
+            return u64::MAX
+        }
+        let now = Utc::now().timestamp_millis() as u64;
+        now.saturating_sub(self.ttl.tx_recent_window.as_millis() as u64)
+    }
+
     fn get_key<S: Into<String>>(blockchain: u32, txid: S) -> String {
-        format!("{}:{}/{}", PREFIX_KEY, blockchain, txid.into())
+        format!("{}:{}/{}", PREFIX_KEY, blockchain, TransactionsAccess::normalize_tx_id(blockchain, txid.into().as_str()))
+    }
+
+    ///
+    /// Tx id in the form it's stored, so the same transaction always gets the same key regardless of the source.
+    /// It's lowercase, with `0x` prefix for Ethereum-like blockchains and without it for Bitcoin.
+    fn normalize_tx_id(blockchain: u32, tx_id: &str) -> String {
+        let tx_id = tx_id.trim().to_lowercase();
+        if tx_id.is_empty() {
+            return tx_id
+        }
+        let hex = tx_id.strip_prefix("0x").unwrap_or(tx_id.as_str());
+        match BlockchainId::from_i32(blockchain as i32) {
+            None | Some(BlockchainId::CHAIN_UNSPECIFIED) => tx_id,
+            Some(BlockchainId::CHAIN_BITCOIN) | Some(BlockchainId::CHAIN_TESTNET_BITCOIN) => hex.to_string(),
+            Some(_) => format!("0x{}", hex),
+        }
+    }
+
+    ///
+    /// Read the most recent transactions, as they are shown on the first page of the history, so their index and
+    /// data pages get into the cache. Returns the number of read transactions.
+    pub(crate) fn warm(&self, limit: usize) -> Result<usize, StateError> {
+        let prefix = format!("{}:{:}/", PREFIX_IDX, IndexType::Everything(0).get_prefix());
+        let mut count = 0;
+        for row in self.db.scan_prefix(prefix).take(limit) {
+            let (_, tx_key) = row?;
+            if self.db.get(tx_key)?.is_some() {
+                count += 1;
+            }
+        }
+        Ok(count)
     }
     fn get_key_meta<S: Into<String>>(blockchain: u32, txid: S) -> String {
-        format!("{}:{}/{}", PREFIX_KEY_META, blockchain, txid.into())
+        format!("{}:{}/{}", PREFIX_KEY_META, blockchain, TransactionsAccess::normalize_tx_id(blockchain, txid.into().as_str()))
     }
 
-    fn get_tx_by_key(&self, key: String) -> Option<proto_Transaction> {
-        match self.db.get(key) {
-            Ok(data) => {
-                match data {
-                    Some(b) => proto_Transaction::parse_from_bytes(b.deref()).ok(),
-                    None => None
+    ///
+    /// Tag in the form it's stored and indexed
+    fn normalize_tag(tag: &str) -> Result<String, StateError> {
+        let tag = tag.trim().to_lowercase();
+        if tag.is_empty() || tag.contains('/') {
+            return Err(StateError::InvalidValue(InvalidValueError::Name("tags".to_string())))
+        }
+        Ok(tag)
+    }
+
+    ///
+    /// Validate the meta and bring its tags to the stored form, removing duplicates
+    fn normalize_meta(mut value: proto_TransactionMeta) -> Result<proto_TransactionMeta, StateError> {
+        if value.tx_id.is_empty() {
+            return Err(StateError::InvalidValue(InvalidValueError::Name("tx_id".to_string())))
+        }
+        value.tx_id = TransactionsAccess::normalize_tx_id(value.blockchain.value() as u32, value.tx_id.as_str());
+        let mut tags: Vec<String> = Vec::with_capacity(value.tags.len());
+        for tag in value.tags.iter() {
+            let tag = TransactionsAccess::normalize_tag(tag)?;
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+        value.set_tags(tags.into());
+        Ok(value)
+    }
+
+    ///
+    /// Put the meta into the batch, replacing its previous tag indexes
+    fn write_meta(&self, key: String, value: &proto_TransactionMeta, batch: &mut Batch) -> Result<(), StateError> {
+        let tx_key = TransactionsAccess::get_key(value.blockchain.value() as u32, value.tx_id.clone());
+        Indexing::remove_backref(key.clone(), self.db.clone(), batch)?;
+        let indexes: Vec<String> = value.get_index_keys();
+        Indexing::add_backrefs(&indexes, key.clone(), batch)?;
+        for idx in indexes {
+            batch.insert(idx.as_bytes(), tx_key.as_bytes());
+        }
+        batch.insert(key.as_bytes(), value.write_to_bytes()?);
+        Ok(())
+    }
+
+    fn get_meta_log_prefix(blockchain: u32, tx_id: &str) -> String {
+        format!("{}:{}/{}/", PREFIX_META_LOG, blockchain, TransactionsAccess::normalize_tx_id(blockchain, tx_id))
+    }
+
+    fn read_meta_history(&self, blockchain: u32, tx_id: &str) -> Result<Vec<MetaRevision>, StateError> {
+        let mut result = Vec::new();
+        for row in self.db.scan_prefix(TransactionsAccess::get_meta_log_prefix(blockchain, tx_id)) {
+            let (_, value) = row?;
+            result.push(MetaRevision::parse_from_bytes(value.as_ref())?);
+        }
+        Ok(result)
+    }
+
+    ///
+    /// Remove the meta of the transaction, with its index entries and history
+    fn remove_meta(&self, blockchain: u32, tx_id: &str, batch: &mut Batch) -> Result<(), StateError> {
+        let meta_key = TransactionsAccess::get_key_meta(blockchain, tx_id);
+        Indexing::remove_backref(meta_key.clone(), self.db.clone(), batch)?;
+        batch.remove(meta_key.as_bytes());
+        for key in self.db.scan_prefix(TransactionsAccess::get_meta_log_prefix(blockchain, tx_id)).keys() {
+            batch.remove(key?);
+        }
+        Ok(())
+    }
+
+    ///
+    /// Put the removal of the transaction into the batch, with its indexes and the records referencing it
+    fn remove_tx(&self, blockchain: u32, tx_id: &str, batch: &mut Batch, change: &mut TransactionsChange) -> Result<(), StateError> {
+        let tx_key = TransactionsAccess::get_key(blockchain, tx_id);
+        if let Some(tx) = self.get_tx_by_key(tx_key.clone()) {
+            change.add(&tx);
+            // there is no record of the previous activity, so just forget the address
+            for c in tx.get_changes() {
+                if let Ok(address) = address_registry_store::normalize_address(c.address.as_str()) {
+                    if self.read_first_seen(address.as_str())?.is_some_and(|seen| seen.tx_id == tx.tx_id) {
+                        batch.remove(format!("{}:{}", PREFIX_SEEN, address).as_bytes());
+                    }
                 }
             }
-            Err(_) => None
         }
+        batch.remove(tx_key.as_bytes());
+        Indexing::remove_backref(tx_key, self.db.clone(), batch)?;
+        self.cascade_forget(blockchain, tx_id, batch)
     }
-}
 
-impl Migration for TransactionsAccess {
-    fn migrate(&self, version: usize) -> Result<(), StateError> {
-        if version == 1 {
-            // before version 1 we may have some transactions without full details,
-            // here we drop the cursors to ensure all transactions are reloaded
-            self.db.scan_prefix(PREFIX_CURSOR.as_bytes()).keys().for_each(|k| {
-                if let Ok(key) = k {
-                    let _ = self.db.remove(key);
+    ///
+    /// Put the removal of the wallet transactions into the batch, with their meta, and the remote cursors of the
+    /// wallet `addresses`. A transaction shared with another wallet is kept, but its changes are not attributed to the
+    /// removed wallet anymore. Returns the numbers of the removed and the kept transactions.
+    pub(crate) fn wipe_wallet(&self, wallet_id: Uuid, addresses: &[String], batch: &mut Batch, change: &mut TransactionsChange) -> Result<(usize, usize), StateError> {
+        let wallet = wallet_id.to_string();
+        let prefix = format!("{}:{:}/{}/", PREFIX_IDX, IndexType::ByWalletAndConfirm(Uuid::nil(), true, 0, 0, String::new()).get_prefix(), wallet);
+        let mut tx_keys = BTreeSet::new();
+        for row in self.db.scan_prefix(prefix) {
+            let (_, tx_key) = row?;
+            tx_keys.insert(String::from_utf8(tx_key.to_vec()).map_err(|_| StateError::CorruptedValue)?);
+        }
+        let mut removed = 0;
+        let mut detached = 0;
+        for tx_key in tx_keys {
+            let mut tx = match self.get_tx_by_key(tx_key.clone()) {
+                Some(tx) => tx,
+                None => continue
+            };
+            let blockchain = tx.blockchain.value() as u32;
+            if tx.changes.iter().any(|c| !c.wallet_id.is_empty() && c.wallet_id != wallet) {
+                change.add(&tx);
+                for c in tx.changes.iter_mut().filter(|c| c.wallet_id == wallet) {
+                    c.clear_wallet_id();
+                    c.entry_id = 0;
+                }
+                self.write_reindexed(tx_key, &tx, batch)?;
+                detached += 1;
+            } else {
+                self.remove_tx(blockchain, tx.tx_id.as_str(), batch, change)?;
+                self.remove_meta(blockchain, tx.tx_id.as_str(), batch)?;
+                removed += 1;
+            }
+        }
+        for address in addresses {
+            batch.remove(format!("{}:{}", PREFIX_CURSOR, address).as_bytes());
+        }
+        Ok((removed, detached))
+    }
+
+    ///
+    /// Apply the cascade policies to the records referencing the forgotten transaction. The allowances are changed
+    /// only if it's permitted to write them, otherwise they are left as is and reported by `find_dangling`.
+    fn cascade_forget(&self, blockchain: u32, tx_id: &str, batch: &mut Batch) -> Result<(), StateError> {
+        if self.references.get_policy(TxReference::Meta) == CascadePolicy::Delete {
+            self.remove_meta(blockchain, tx_id, batch)?;
+        }
+        if self.permissions.can_write(Store::Allowances) {
+            let tx_key = TransactionsAccess::get_key(blockchain, tx_id);
+            let policy = self.references.get_policy(TxReference::AllowanceRevoke);
+            for (key, mut allowance) in allowance_store::find_revoked(&self.db)? {
+                if TransactionsAccess::get_key(allowance.blockchain, allowance.revoked_by_tx.as_str()) != tx_key {
+                    continue
+                }
+                match policy {
+                    CascadePolicy::Delete => batch.remove(key.as_bytes()),
+                    CascadePolicy::Detach => {
+                        allowance.clear_revoked_by_tx();
+                        batch.insert(key.as_bytes(), allowance.write_to_bytes()?);
+                    }
                 }
+            }
+        }
+        Ok(())
+    }
+
+    ///
+    /// Record the meta as the next revision of its history, linked to the last stored one
+    fn append_meta_revision(&self, value: &proto_TransactionMeta, revisions: &mut Vec<(String, MetaRevision)>) -> Result<(), StateError> {
+        let prefix = TransactionsAccess::get_meta_log_prefix(value.blockchain.value() as u32, value.tx_id.as_str());
+        let last = match self.db.scan_prefix(prefix.as_bytes()).next_back() {
+            Some(row) => Some(MetaRevision::parse_from_bytes(row?.1.as_ref())?),
+            None => None
+        };
+        let mut meta = value.clone();
+        // the payload is not made by the user, and may be removed later to save space
+        meta.clear_raw();
+        let mut revision = MetaRevision::new();
+        revision.seq = last.as_ref().map_or(0, |r| r.seq + 1);
+        revision.recorded_at = Utc::now().timestamp_millis() as u64;
+        revision.prev_hash = last.map(|r| r.hash).unwrap_or_default();
+        revision.meta = meta.write_to_bytes()?;
+        revision.hash = meta_proof::get_hash(&revision.prev_hash, revision.recorded_at, &revision.meta);
+        let key = format!("{}{}", prefix, IndexConvert::get_asc_number(revision.seq));
+        revisions.push((key, revision));
+        Ok(())
+    }
+
+    ///
+    /// Apply the batch together with the meta revisions, which get the number assigned to the write
+    fn apply_with_revisions(&self, batch: Batch, revisions: Vec<(String, MetaRevision)>) -> Result<u64, StateError> {
+        self.health.apply_with(&self.db, batch, |seq, batch| {
+            for (key, mut revision) in revisions {
+                revision.write_seq = seq;
+                batch.insert(key.as_bytes(), revision.write_to_bytes()?);
+            }
+            Ok(())
+        })
+    }
+
+    ///
+    /// Keys of the transactions referenced by the meta index entries with the `prefix`
+    fn scan_meta_index(&self, prefix: String) -> Result<HashSet<String>, StateError> {
+        let mut result = HashSet::new();
+        for row in self.db.scan_prefix(prefix) {
+            let (_, tx_key) = row?;
+            if let Ok(tx_key) = String::from_utf8(tx_key.to_vec()) {
+                result.insert(tx_key);
+            }
+        }
+        Ok(result)
+    }
+
+    ///
+    /// Keys of the transactions with the `text` in the label or the tags of their meta
+    fn scan_meta_text(&self, text: &str) -> Result<HashSet<String>, StateError> {
+        let text = text.trim().to_lowercase();
+        let mut result = HashSet::new();
+        for row in self.db.scan_prefix(format!("{}:", PREFIX_KEY_META)) {
+            let (_, value) = row?;
+            let meta = match proto_TransactionMeta::parse_from_bytes(value.as_ref()) {
+                Ok(meta) => meta,
+                Err(_) => continue
+            };
+            let found = meta.label.to_lowercase().contains(&text) || meta.tags.iter().any(|tag| tag.contains(&text));
+            if found {
+                result.insert(TransactionsAccess::get_key(meta.blockchain.value() as u32, meta.tx_id.as_str()));
+            }
+        }
+        Ok(result)
+    }
+
+    ///
+    /// Select the transactions by the tag, the text and the flags required by the filter. The hidden transactions are excluded
+    /// unless the filter asks to include them.
+    fn get_meta_selection(&self, filter: &Filter) -> Result<MetaSelection, StateError> {
+        let flag_prefix = |flag: TxFlag| format!("{}:{:}/{:}/", PREFIX_IDX, IndexType::ByFlag(flag, 0, String::new()).get_prefix(), flag.get_code());
+        let mut required = Vec::new();
+        if let Some(tag) = &filter.tag {
+            required.push(format!("{}:{:}/{:}/", PREFIX_IDX, IndexType::ByTag(String::new(), 0, String::new()).get_prefix(), tag.trim().to_lowercase()));
+        }
+        if filter.starred_only {
+            required.push(flag_prefix(TxFlag::Starred));
+        }
+        if filter.needs_review_only {
+            required.push(flag_prefix(TxFlag::NeedsReview));
+        }
+        let mut selected = Vec::new();
+        for prefix in required {
+            selected.push(self.scan_meta_index(prefix)?);
+        }
+        if let Some(text) = &filter.text {
+            selected.push(self.scan_meta_text(text.as_str())?);
+        }
+        let mut included: Option<HashSet<String>> = None;
+        for keys in selected {
+            included = Some(match included {
+                Some(current) => current.intersection(&keys).cloned().collect(),
+                None => keys
             });
         }
+        let excluded = if filter.include_hidden {
+            HashSet::new()
+        } else {
+            self.scan_meta_index(flag_prefix(TxFlag::Hidden))?
+        };
+        Ok(MetaSelection { included, excluded })
+    }
+
+    ///
+    /// Remember the transfers of the transaction in `seen` if they are earlier than the first known activity of their
+    /// addresses, which is taken from `seen` or, if not there yet, from the storage
+    fn collect_first_seen(&self, tx: &proto_Transaction, seen: &mut HashMap<String, FirstSeen>) -> Result<(), StateError> {
+        let timestamp = if tx.since_timestamp > 0 { tx.since_timestamp } else { tx.confirm_timestamp };
+        if timestamp == 0 {
+            return Ok(())
+        }
+        for change in tx.get_changes() {
+            if change.change_type == Change_ChangeType::FEE {
+                continue
+            }
+            let address = match address_registry_store::normalize_address(change.address.as_str()) {
+                Ok(address) => address,
+                Err(_) => continue
+            };
+            if !seen.contains_key(&address) {
+                if let Some(current) = self.read_first_seen(address.as_str())? {
+                    seen.insert(address.clone(), current);
+                }
+            }
+            let is_earlier = seen.get(&address).is_none_or(|current| timestamp < current.timestamp);
+            if is_earlier {
+                let mut value = FirstSeen::new();
+                value.set_address(address.clone());
+                value.set_blockchain(tx.blockchain);
+                value.set_timestamp(timestamp);
+                value.set_tx_id(tx.tx_id.clone());
+                value.set_direction(change.direction);
+                seen.insert(address, value);
+            }
+        }
         Ok(())
     }
-}
 
-impl Transactions for TransactionsAccess {
+    fn read_first_seen(&self, address: &str) -> Result<Option<FirstSeen>, StateError> {
+        let key = format!("{}:{}", PREFIX_SEEN, address);
+        match self.db.get(key)? {
+            Some(value) => Ok(Some(FirstSeen::parse_from_bytes(value.deref())?)),
+            None => Ok(None)
+        }
+    }
 
-    fn query(&self, filter: Filter, page: PageQuery) -> Result<PageResult<proto_Transaction>, StateError> {
-        let mut bounds = filter.get_index_bounds();
-        if let Some(cursor) = page.cursor {
-            bounds.0 = Bound::Excluded(cursor.offset)
+    ///
+    /// Current head heights per blockchain, if the filter requires confirmations. Otherwise it's not needed and empty.
+    fn get_heads(&self, filter: &Filter) -> Result<HashMap<u32, u64>, StateError> {
+        let mut result = HashMap::new();
+        if filter.min_confirmations.is_none() {
+            return Ok(result)
+        }
+        for row in self.db.scan_prefix(format!("{}:", PREFIX_HEAD)) {
+            let (key, value) = row?;
+            let blockchain = String::from_utf8(key.to_vec()).ok()
+                .and_then(|key| key.split(':').nth(1).and_then(|b| u32::from_str(b).ok()));
+            if let (Some(blockchain), Ok(height)) = (blockchain, <[u8; 8]>::try_from(value.as_ref())) {
+                result.insert(blockchain, u64::from_be_bytes(height));
+            }
+        }
+        Ok(result)
+    }
+
+    ///
+    /// Count transactions matching the filter per blockchain and per state, in a single scan
+    fn get_facets(&self, filter: &Filter) -> Result<Facets, StateError> {
+        // each facet ignores its own criterion, so scan with neither of them and check them separately
+        let relaxed = Filter {
+            blockchains: None,
+            state: None,
+            ..filter.clone()
+        };
+        let mut fields: HashSet<u32> = relaxed.get_required_fields().iter().map(|f| f.get_number()).collect();
+        fields.insert(TransactionField::State.get_number());
+        let fields = Some(fields);
+
+        let selection = self.get_meta_selection(filter)?;
+        let heads = self.get_heads(filter)?;
+        let mut facets = Facets::default();
+        let mut processed = HashSet::new();
+        for row in Indexing::scan_ranges(&self.db, PREFIX_IDX, relaxed.get_index_ranges(), &PageQuery::default())? {
+            let (_, tx_key) = match row {
+                Ok(v) => v,
+                Err(e) => {
+                    warn!("Skip unreadable transaction index entry: {:?}", e);
+                    continue
+                }
+            };
+            let tx_key = String::from_utf8(tx_key.to_vec()).unwrap();
+            if !processed.insert(tx_key.clone()) || !selection.accepts(&tx_key) {
+                continue
+            }
+            let tx = match self.read_tx_projected(tx_key.clone(), &fields) {
+                Ok(Some(tx)) => tx,
+                Ok(None) => continue,
+                Err(e) => {
+                    warn!("Skip unreadable transaction {}: {:?}", tx_key, e);
+                    self.quarantine(tx_key.as_str(), &e);
+                    continue
+                }
+            };
+            if !relaxed.check_filter(&tx) || !relaxed.check_confirmations(&tx, &heads) {
+                continue
+            }
+            let blockchain = tx.blockchain.value() as u32;
+            let blockchain_ok = match &filter.blockchains {
+                Some(blockchains) => blockchains.contains(&blockchain),
+                None => true
+            };
+            let state_ok = match &filter.state {
+                Some(state) => tx.state == *state,
+                None => true
+            };
+            if state_ok {
+                *facets.by_blockchain.entry(blockchain).or_insert(0) += 1;
+            }
+            if blockchain_ok {
+                *facets.by_state.entry(tx.state).or_insert(0) += 1;
+            }
+        }
+        Ok(facets)
+    }
+
+    ///
+    /// Update the transaction meta, creating it if needed. The update gets a fresh timestamp, which is always later
+    /// than the current one, so it's never ignored by `set_tx_meta`.
+    fn update_meta<F>(&self, blockchain: u32, tx_id: String, create: bool, f: F) -> Result<Option<proto_TransactionMeta>, StateError>
+        where F: Fn(&mut proto_TransactionMeta) {
+        let mut meta = match self.get_tx_meta(blockchain, tx_id.as_str())? {
+            Some(meta) => meta,
+            None if create => {
+                let mut meta = proto_TransactionMeta::new();
+                meta.blockchain = BlockchainId::from_i32(blockchain as i32)
+                    .ok_or(StateError::InvalidValue(InvalidValueError::Name("blockchain".to_string())))?;
+                meta.tx_id = tx_id;
+                meta
+            },
+            None => return Ok(None)
         };
+        f(&mut meta);
+        let now = Utc::now().timestamp_millis() as u64;
+        meta.timestamp = now.max(meta.timestamp + 1);
+        self.set_tx_meta(meta).map(Some)
+    }
+
+    fn get_tx_by_key(&self, key: String) -> Option<proto_Transaction> {
+        match self.db.get(key.as_str()) {
+            Ok(data) => {
+                match data {
+                    Some(b) => match proto_Transaction::parse_from_bytes(b.deref()) {
+                        Ok(tx) => Some(tx),
+                        Err(e) => {
+                            quarantine_store::put(&self.db, &self.health, &self.permissions, StoreType::STORE_TRANSACTIONS, key.as_str(), e);
+                            None
+                        }
+                    },
+                    None => None
+                }
+            }
+            Err(_) => None
+        }
+    }
 
+    ///
+    /// Move a transaction which cannot be decoded into the quarantine. Other errors, ex. IO, don't mean the
+    /// transaction itself is broken, so it's kept in place.
+    fn quarantine(&self, tx_key: &str, error: &StateError) {
+        if *error == StateError::CorruptedValue {
+            quarantine_store::put(&self.db, &self.health, &self.permissions, StoreType::STORE_TRANSACTIONS, tx_key, error);
+        }
+    }
+
+    ///
+    /// Read a transaction for a query. If `fields` are specified it decodes only those fields.
+    /// Returns `Ok(None)` if there is no such transaction, and `Err` if it exists but cannot be read
+    fn read_tx_projected(&self, key: String, fields: &Option<HashSet<u32>>) -> Result<Option<proto_Transaction>, StateError> {
+        match self.db.get(key)? {
+            Some(b) => match fields {
+                None => Ok(Some(proto_Transaction::parse_from_bytes(b.deref())?)),
+                Some(fields) => decode_projected(b.deref(), fields).map(Some),
+            },
+            None => Ok(None)
+        }
+    }
 
+    fn query_with_fields(&self, filter: Filter, page: PageQuery, fields: Option<HashSet<u32>>) -> Result<PageResult<proto_Transaction>, StateError> {
+        let selection = self.get_meta_selection(&filter)?;
+        let heads = self.get_heads(&filter)?;
         let mut processed = HashSet::new();
-        let mut iter = self.db.range(bounds);
+        let mut iter = Indexing::scan_ranges(&self.db, PREFIX_IDX, filter.get_index_ranges(), &page)?;
         let mut done = false;
 
         let mut txes = Vec::new();
         let mut cursor_key: Option<String> = None;
         let mut read_count = 0;
+        let mut skipped = 0;
 
         while !done {
             let next = iter.next();
@@ -201,18 +864,30 @@ impl Transactions for TransactionsAccess {
                         let tx_key = String::from_utf8(tx_key).unwrap();
 
                         let unprocessed = processed.insert(tx_key.clone());
-                        if unprocessed {
-                            if let Some(tx) = self.get_tx_by_key(tx_key) {
-                                if filter.check_filter(&tx) {
-                                    txes.push(tx);
-                                    if txes.len() >= page.limit {
-                                        done = true
+                        let tag_ok = selection.accepts(&tx_key);
+                        if unprocessed && tag_ok {
+                            match self.read_tx_projected(tx_key.clone(), &fields) {
+                                Ok(Some(tx)) => {
+                                    if filter.check_filter(&tx) && filter.check_confirmations(&tx, &heads) {
+                                        txes.push(tx);
+                                        if txes.len() >= page.limit {
+                                            done = true
+                                        }
                                     }
                                 }
+                                Ok(None) => {}
+                                Err(e) => {
+                                    warn!("Skip unreadable transaction {}: {:?}", tx_key, e);
+                                    self.quarantine(tx_key.as_str(), &e);
+                                    skipped += 1;
+                                }
                             }
                         }
                     }
-                    Err(_) => {}
+                    Err(e) => {
+                        warn!("Skip unreadable transaction index entry: {:?}", e);
+                        skipped += 1;
+                    }
                 },
                 None => done = true
             }
@@ -222,63 +897,202 @@ impl Transactions for TransactionsAccess {
 
         let result = PageResult {
             values: txes,
-            cursor: if reached_end { None } else { cursor_key.map(|offset| Cursor {offset}) },
+            cursor: if reached_end { None } else { cursor_key.map(|key| Cursor::encode(PREFIX_IDX, page.order, key)) },
+            skipped,
         };
 
         Ok(result)
     }
 
-    fn get_tx(&self, blockchain: u32, txid: &str) -> Option<proto_Transaction> {
-        let key = TransactionsAccess::get_key(blockchain, txid);
-        self.get_tx_by_key(key)
+    ///
+    /// Check if the index selected by `get_index_bounds` for the filter covers all of its criteria, i.e. each entry
+    /// within the bounds references a transaction accepted by the filter
+    fn is_index_only(filter: &Filter) -> bool {
+        let no_extra = filter.addresses.is_none()
+            && filter.after.is_none()
+            && filter.before.is_none()
+            && filter.state.is_none()
+            && filter.status.is_none()
+            && !filter.collapse_replaced
+            && !filter.unattributed_only
+            && !filter.starred_only
+            && !filter.needs_review_only
+            && filter.min_confirmations.is_none()
+            && filter.tag.is_none()
+            && filter.asset.is_none()
+            && filter.direction.is_none()
+            && filter.text.is_none();
+        if !no_extra {
+            return false
+        }
+        match (&filter.wallet, &filter.blockchains) {
+            (None, None) => true,
+            (Some(WalletRef::WholeWallet(_)), None) => true,
+            (None, Some(blockchains)) => blockchains.len() == 1,
+            _ => false
+        }
     }
 
-    fn get_tx_meta(&self, blockchain: u32, txid: &str) -> Result<Option<proto_TransactionMeta>, StateError> {
-        let key = TransactionsAccess::get_key_meta(blockchain, txid);
-        match self.db.get(key) {
-            Ok(data) => {
-                match data {
-                    Some(b) => Ok(proto_TransactionMeta::parse_from_bytes(b.deref()).ok()),
-                    None => Ok(None)
-                }
+    ///
+    /// Put an updated version of an existing transaction into the batch, replacing its indexes
+    pub(crate) fn write_reindexed(&self, tx_key: String, tx: &proto_Transaction, batch: &mut Batch) -> Result<(), StateError> {
+        Indexing::remove_backref(tx_key.clone(), self.db.clone(), batch)?;
+        let indexes: Vec<String> = self.get_tx_index_keys(tx);
+        Indexing::add_backrefs(&indexes, tx_key.clone(), batch)?;
+        for idx in indexes {
+            batch.insert(idx.as_bytes(), tx_key.as_bytes());
+        }
+        batch.insert(tx_key.as_bytes(), tx.write_to_bytes()?);
+        Ok(())
+    }
+
+    ///
+    /// Find all transactions with the same sender and nonce. Returns pairs of the storage key and the transaction
+    fn find_by_nonce(&self, blockchain: u32, nonce_ref: &NonceRef) -> Result<Vec<(String, proto_Transaction)>, StateError> {
+        // with an empty tx id it gives a prefix shared by all the transactions with that nonce
+        let prefix = IndexType::ByNonce(blockchain, nonce_ref.from.clone(), nonce_ref.nonce, "".to_string()).get_index_key();
+        let mut result = Vec::new();
+        for row in self.db.scan_prefix(prefix) {
+            let (_, tx_key) = row?;
+            let tx_key = String::from_utf8(tx_key.to_vec())
+                .map_err(|_| StateError::CorruptedValue)?;
+            if let Some(tx) = self.get_tx_by_key(tx_key.clone()) {
+                result.push((tx_key, tx));
             }
-            Err(_) => Err(StateError::IOError)
         }
+        Ok(result)
     }
 
-    fn set_tx_meta(&self, value: proto_TransactionMeta) -> Result<proto_TransactionMeta, StateError> {
-        let blockchain = value.blockchain.value() as u32;
-        let tx_id = value.tx_id.clone();
-        if tx_id.is_empty() {
-            return Err(StateError::InvalidValue(InvalidValueError::Name("tx_id".to_string())))
+    ///
+    /// Find pending transactions conflicting with the specified one, i.e. spending any of the same outputs under a
+    /// different tx id. It happens on Bitcoin when a transaction is malleated, rebroadcasted or replaced by fee, and the
+    /// found transactions can never be confirmed anymore. Without the known inputs there is no way to tell a copy from a
+    /// separate payment of the same amount, so nothing is found. Returns pairs of the storage key and the transaction.
+    fn find_pending_duplicates(&self, tx: &proto_Transaction) -> Result<Vec<(String, proto_Transaction)>, StateError> {
+        if tx.blockchain != BlockchainId::CHAIN_BITCOIN && tx.blockchain != BlockchainId::CHAIN_TESTNET_BITCOIN {
+            return Ok(vec![])
         }
-        let existing = self.get_tx_meta(blockchain, tx_id.as_str())?;
-        if let Some(existing_value) = existing {
-            if existing_value.timestamp >= value.timestamp {
-                return Ok(existing_value)
+        let spent = tx.get_spent_outpoints();
+        if spent.is_empty() {
+            return Ok(vec![])
+        }
+        let wallets: HashSet<Uuid> = tx.changes.iter()
+            .filter_map(|c| Uuid::from_str(c.wallet_id.as_str()).ok())
+            .collect();
+        let mut processed = HashSet::new();
+        let mut result = Vec::new();
+        for wallet_id in wallets {
+            // only recent (i.e., pending or just confirmed) transactions of the wallet
+            let prefix = format!("{}:{:}/{:}/{:}/", PREFIX_IDX, WALLET_CONFIRM_INDEX, wallet_id, IndexConvert::get_bool_tf(&true));
+            for row in self.db.scan_prefix(prefix) {
+                let (_, dup_key) = row?;
+                let dup_key = String::from_utf8(dup_key.to_vec())
+                    .map_err(|_| StateError::CorruptedValue)?;
+                if !processed.insert(dup_key.clone()) {
+                    continue
+                }
+                if let Some(dup) = self.get_tx_by_key(dup_key.clone()) {
+                    // the recent section may also have just confirmed transactions
+                    let is_pending = dup.state == State::SUBMITTED || dup.state == State::PREPARED;
+                    let is_duplicate = is_pending
+                        && dup.tx_id != tx.tx_id
+                        && dup.blockchain == tx.blockchain
+                        && !dup.get_spent_outpoints().is_disjoint(&spent);
+                    if is_duplicate {
+                        result.push((dup_key, dup));
+                    }
+                }
             }
         }
-        let key = TransactionsAccess::get_key_meta(blockchain, tx_id);
-        let b = value.write_to_bytes()?;
-        let mut batch = Batch::default();
-        batch.insert(key.as_bytes(), b);
-        self.db.apply_batch(batch)?;
-        Ok(value)
+        Ok(result)
+    }
+
+    ///
+    /// Transactions affected by the updated meta, with their wallets if the transactions are known
+    fn get_meta_change(&self, values: &[proto_TransactionMeta], seq: u64) -> TransactionsChange {
+        let mut change = TransactionsChange { seq, ..TransactionsChange::default() };
+        for value in values {
+            let blockchain = value.blockchain.value() as u32;
+            match self.get_tx_by_key(TransactionsAccess::get_key(blockchain, value.tx_id.as_str())) {
+                Some(tx) => change.add(&tx),
+                None => {
+                    change.transactions.insert((blockchain, value.tx_id.clone()));
+                }
+            }
+        }
+        change
+    }
+
+    ///
+    /// Normalize the ids of a submitted transaction and attribute its changes to the known wallets
+    fn prepare(&self, mut tx: proto_Transaction) -> proto_Transaction {
+        let blockchain = tx.blockchain.value() as u32;
+        tx.tx_id = TransactionsAccess::normalize_tx_id(blockchain, tx.tx_id.as_str());
+        tx.replaced_by = TransactionsAccess::normalize_tx_id(blockchain, tx.replaced_by.as_str());
+        self.attribute(&mut tx);
+        tx
     }
 
-    fn submit(&self, transactions: Vec<proto_Transaction>) -> Result<(), StateError> {
+    ///
+    /// Store the submitted transactions, merging them with the existing ones. They must be already validated.
+    fn write_submitted(&self, transactions: Vec<proto_Transaction>, policy: MergePolicy) -> Result<Vec<SubmitOutcome>, StateError> {
         let mut batch = Batch::default();
+        let mut nonces = Vec::new();
+        let mut change = TransactionsChange::default();
+        let mut outcomes = Vec::with_capacity(transactions.len());
+        let mut seen = HashMap::new();
+        let mut written = 0;
         for mut tx in transactions {
             let tx_id = tx.tx_id.clone();
             let tx_key = TransactionsAccess::get_key(tx.blockchain.value() as u32, tx_id.clone());
 
-            if let Some(existing_tx) = self.get_tx_by_key(tx_key.clone()) {
+            let existing_tx = self.get_tx_by_key(tx_key.clone());
+            if let Some(existing_tx) = &existing_tx {
+                tx = existing_tx.clone().merge_with(tx, policy);
+            }
+            tx.dedup_changes();
+            tx.internal_transfer = tx.detect_internal_transfer();
+            // a dropped or replaced transaction never uses its nonce
+            if let Some(nonce_ref) = tx.nonce_ref.as_ref() {
+                if !nonce_ref.from.is_empty() && tx.state != State::DROPPED && tx.state != State::REPLACED {
+                    nonces.push((tx.blockchain.value() as u32, nonce_ref.from.clone(), nonce_ref.nonce));
+                }
+            }
+            let outcome = match &existing_tx {
+                Some(existing_tx) => TransactionsAccess::get_outcome(existing_tx, &tx)?,
+                None => SubmitOutcome::Created,
+            };
+            let is_unchanged = outcome == SubmitOutcome::Unchanged;
+            outcomes.push(outcome);
+            if is_unchanged {
+                continue
+            }
+            written += 1;
+            if existing_tx.is_some() {
                 Indexing::remove_backref(tx_key.clone(), self.db.clone(), &mut batch)?;
-                tx = existing_tx.merge(tx);
             }
+            self.collect_first_seen(&tx, &mut seen)?;
+
+            let releases_utxo = self.permissions.can_write(Store::Balances);
+            if tx.state != State::REPLACED {
+                for (dup_key, mut dup) in self.find_pending_duplicates(&tx)? {
+                    dup.state = State::REPLACED;
+                    dup.replaced_by = tx_id.clone();
+                    dup.version += 1;
+                    self.write_reindexed(dup_key, &dup, &mut batch)?;
+                    if releases_utxo {
+                        balance_store::release_spent(&self.db, &dup, &mut batch)?;
+                    }
+                    change.add(&dup);
+                }
+            }
+            if releases_utxo {
+                balance_store::release_spent(&self.db, &tx, &mut batch)?;
+            }
+            change.add(&tx);
 
             if let Ok(tx_bytes) = tx.write_to_bytes() {
-                let indexes: Vec<String> = tx.get_index_keys();
+                let indexes: Vec<String> = self.get_tx_index_keys(&tx);
                 Indexing::add_backrefs(&indexes, tx_key.clone(), &mut batch)?;
                 for idx in indexes {
                     batch.insert(idx.as_bytes(), tx_key.as_bytes());
@@ -286,23 +1100,751 @@ impl Transactions for TransactionsAccess {
                 batch.insert(tx_key.as_bytes(), tx_bytes);
             }
         }
-        self.db.apply_batch(batch)
-            .map_err(|e| StateError::from(e))
+        for (address, value) in seen {
+            let key = format!("{}:{}", PREFIX_SEEN, address);
+            batch.insert(key.as_bytes(), value.write_to_bytes()?);
+        }
+        if self.permissions.can_write(Store::Nonces) {
+            written += nonce_store::put_at_least(&self.db, nonces, &mut batch)?;
+        }
+        if written > 0 {
+            change.seq = self.health.apply(&self.db, batch)?;
+        }
+        self.notifier.notify(change);
+        Ok(outcomes)
     }
 
-    fn forget(&self, blockchain: u32, tx_id: String) -> Result<(), StateError> {
+    ///
+    /// Fill the wallet of the changes that don't have it, but have an address known by the address registry
+    fn attribute(&self, tx: &mut proto_Transaction) {
+        let blockchain = tx.blockchain.value() as u32;
+        for change in tx.changes.iter_mut() {
+            if !change.wallet_id.is_empty() || change.address.is_empty() {
+                continue
+            }
+            if let Some(entry) = address_registry_store::find(&self.db, &self.permissions, blockchain, change.address.as_str()) {
+                change.wallet_id = entry.wallet_id.to_string();
+                change.entry_id = entry.entry_id;
+            }
+        }
+    }
+
+    ///
+    /// Outcome of merging the `existing` transaction into the `updated` one. The version is not compared, because it's
+    /// always increased by the merge.
+    fn get_outcome(existing: &proto_Transaction, updated: &proto_Transaction) -> Result<SubmitOutcome, StateError> {
+        let mut compared = updated.clone();
+        compared.version = existing.version;
+        let changed_fields: Vec<TransactionField> = get_changed_fields(&existing.write_to_bytes()?, &compared.write_to_bytes()?)?
+            .into_iter()
+            .filter_map(TransactionField::from_number)
+            .collect();
+        if changed_fields.is_empty() {
+            Ok(SubmitOutcome::Unchanged)
+        } else {
+            Ok(SubmitOutcome::Merged { changed_fields })
+        }
+    }
+
+    ///
+    /// Rebuild indexes for all stored transactions from scratch, i.e. drop all existing index entries and their
+    /// backrefs, including ones left from a removed transaction, and index each stored transaction and meta again. Used
+    /// when a new index is introduced and existing data must be available through it, or to recover inconsistent indexes.
+    /// Returns the batch to apply and the number of indexed transactions.
+    fn reindex(&self) -> Result<(Batch, usize), StateError> {
+        let mut batch = Batch::default();
+        for key in self.db.scan_prefix(format!("{}:", PREFIX_IDX)).keys() {
+            batch.remove(key?);
+        }
+        Indexing::remove_backrefs_by_prefix(format!("{}:", PREFIX_KEY).as_str(), &self.db, &mut batch)?;
+        Indexing::remove_backrefs_by_prefix(format!("{}:", PREFIX_KEY_META).as_str(), &self.db, &mut batch)?;
+        let mut count = 0;
+        for key in self.db.scan_prefix(format!("{}:", PREFIX_KEY)).keys() {
+            let tx_key = String::from_utf8(key?.to_vec())
+                .map_err(|_| StateError::CorruptedValue)?;
+            // an unreadable transaction is moved to the quarantine, and gets indexed only when it's restored
+            if let Some(tx) = self.get_tx_by_key(tx_key.clone()) {
+                let indexes: Vec<String> = self.get_tx_index_keys(&tx);
+                Indexing::add_backrefs(&indexes, tx_key.clone(), &mut batch)?;
+                for idx in indexes {
+                    batch.insert(idx.as_bytes(), tx_key.as_bytes());
+                }
+                count += 1;
+            }
+        }
+        // the tag and flag indexes are made from the meta, which may exist even for an unknown transaction
+        for row in self.db.scan_prefix(format!("{}:", PREFIX_KEY_META)) {
+            let (key, value) = row?;
+            let meta_key = String::from_utf8(key.to_vec())
+                .map_err(|_| StateError::CorruptedValue)?;
+            match proto_TransactionMeta::parse_from_bytes(value.as_ref()) {
+                Ok(meta) => self.write_meta(meta_key, &meta, &mut batch)?,
+                Err(e) => warn!("Skip unreadable transaction meta {}: {:?}", meta_key, e)
+            }
+        }
+        Ok((batch, count))
+    }
+
+    ///
+    /// Move transactions and their meta stored under a non-normalized tx id to the normalized key. If the same
+    /// transaction is stored under both, they are merged.
+    fn normalize_stored(&self) -> Result<(), StateError> {
+        let mut moved: HashMap<String, proto_Transaction> = HashMap::new();
+        let mut batch = Batch::default();
+        for row in self.db.scan_prefix(format!("{}:", PREFIX_KEY)) {
+            let (key, value) = row?;
+            let tx_key = String::from_utf8(key.to_vec())
+                .map_err(|_| StateError::CorruptedValue)?;
+            if let Ok(mut tx) = proto_Transaction::parse_from_bytes(value.as_ref()) {
+                let target_key = TransactionsAccess::get_key(tx.blockchain.value() as u32, tx.tx_id.clone());
+                if target_key == tx_key {
+                    continue
+                }
+                batch.remove(tx_key.as_bytes());
+                Indexing::remove_backref(tx_key, self.db.clone(), &mut batch)?;
+                tx.tx_id = TransactionsAccess::normalize_tx_id(tx.blockchain.value() as u32, tx.tx_id.as_str());
+                let existing = moved.remove(&target_key)
+                    .or_else(|| self.get_tx_by_key(target_key.clone()));
+                let tx = match existing {
+                    Some(existing) => existing.merge(tx),
+                    None => tx,
+                };
+                moved.insert(target_key, tx);
+            }
+        }
+        for (tx_key, tx) in moved {
+            self.write_reindexed(tx_key, &tx, &mut batch)?;
+        }
+        self.health.apply_batch(&self.db, batch)?;
+
+        let mut moved: HashMap<String, proto_TransactionMeta> = HashMap::new();
+        let mut batch = Batch::default();
+        for row in self.db.scan_prefix(format!("{}:", PREFIX_KEY_META)) {
+            let (key, value) = row?;
+            let meta_key = String::from_utf8(key.to_vec())
+                .map_err(|_| StateError::CorruptedValue)?;
+            if let Ok(mut meta) = proto_TransactionMeta::parse_from_bytes(value.as_ref()) {
+                let target_key = TransactionsAccess::get_key_meta(meta.blockchain.value() as u32, meta.tx_id.clone());
+                if target_key == meta_key {
+                    continue
+                }
+                batch.remove(meta_key.as_bytes());
+                Indexing::remove_backref(meta_key, self.db.clone(), &mut batch)?;
+                meta.tx_id = TransactionsAccess::normalize_tx_id(meta.blockchain.value() as u32, meta.tx_id.as_str());
+                let existing = match moved.remove(&target_key) {
+                    Some(existing) => Some(existing),
+                    None => self.get_tx_meta(meta.blockchain.value() as u32, meta.tx_id.as_str())?,
+                };
+                // keep the most recent version
+                let meta = match existing {
+                    Some(existing) if existing.timestamp >= meta.timestamp => existing,
+                    _ => meta,
+                };
+                moved.insert(target_key, meta);
+            }
+        }
+        for (meta_key, meta) in moved {
+            self.write_meta(meta_key, &meta, &mut batch)?;
+        }
+        self.health.apply_batch(&self.db, batch)
+    }
+}
+
+impl Migration for TransactionsAccess {
+    fn migrate(&self, version: usize) -> Result<(), StateError> {
+        if version == 1 {
+            // before version 1 we may have some transactions without full details,
+            // here we drop the cursors to ensure all transactions are reloaded
+            self.db.scan_prefix(PREFIX_CURSOR.as_bytes()).keys().for_each(|k| {
+                if let Ok(key) = k {
+                    let _ = self.db.remove(key);
+                }
+            });
+        }
+        if version == 2 {
+            // version 2 introduced an index by blockchain, so existing transactions must be indexed with it
+            let (batch, _) = self.reindex()?;
+            self.health.apply_batch(&self.db, batch)?;
+        }
+        if version == 3 {
+            // version 3 normalizes tx ids, so a transaction previously stored with a different case or prefix
+            // must be moved to the new key
+            self.normalize_stored()?;
+        }
+        Ok(())
+    }
+}
+
+impl Transactions for TransactionsAccess {
+
+    fn query(&self, filter: Filter, page: PageQuery) -> Result<PageResult<proto_Transaction>, StateError> {
+        self.permissions.check_read(Store::Transactions)?;
+        self.profiler.read(|| self.query_with_fields(filter, page, None))
+    }
+
+    fn query_projected(&self, filter: Filter, page: PageQuery, fields: Vec<TransactionField>) -> Result<PageResult<proto_Transaction>, StateError> {
+        self.permissions.check_read(Store::Transactions)?;
+        let fields: HashSet<u32> = fields.iter()
+            .chain(filter.get_required_fields().iter())
+            .map(|f| f.get_number())
+            .collect();
+        self.query_with_fields(filter, page, Some(fields))
+    }
+
+    fn query_faceted(&self, filter: Filter, page: PageQuery) -> Result<FacetedResult, StateError> {
+        self.permissions.check_read(Store::Transactions)?;
+        let facets = self.get_facets(&filter)?;
+        let page = self.query(filter, page)?;
+        Ok(FacetedResult { page, facets })
+    }
+
+    fn subscribe(&self) -> Result<Receiver<TransactionsChange>, StateError> {
+        self.permissions.check_read(Store::Transactions)?;
+        Ok(self.notifier.subscribe())
+    }
+
+    fn query_enriched(&self, filter: Filter, page: PageQuery) -> Result<PageResult<TransactionWithMeta>, StateError> {
+        self.permissions.check_read(Store::Transactions)?;
+        let page = self.query(filter, page)?;
+        let mut values = Vec::with_capacity(page.values.len());
+        for tx in page.values {
+            let key = TransactionsAccess::get_key_meta(tx.blockchain.value() as u32, tx.tx_id.as_str());
+            let meta = match self.db.get(key.as_bytes())? {
+                Some(b) => match proto_TransactionMeta::parse_from_bytes(b.deref()) {
+                    Ok(meta) => Some(meta),
+                    Err(e) => {
+                        warn!("Skip unreadable transaction meta {}: {:?}", key, e);
+                        None
+                    }
+                },
+                None => None
+            };
+            values.push(TransactionWithMeta { tx, meta });
+        }
+        Ok(PageResult { values, cursor: page.cursor, skipped: page.skipped })
+    }
+
+    fn get_recent_activity(&self, limit: usize) -> Result<BTreeMap<Uuid, Vec<proto_Transaction>>, StateError> {
+        self.permissions.check_read(Store::Transactions)?;
+        let mut result = BTreeMap::new();
+        if limit == 0 {
+            return Ok(result)
+        }
+        let hidden = self.get_meta_selection(&Filter::default())?.excluded;
+        let prefix = format!("{}:{:}/", PREFIX_IDX, IndexType::ByWalletAndConfirm(Uuid::nil(), true, 0, 0, String::new()).get_prefix());
+        let mut from = prefix.clone();
+        while let Some(row) = self.db.range(from.as_str()..).next() {
+            let (key, _) = row?;
+            if !key.starts_with(prefix.as_bytes()) {
+                break
+            }
+            let wallet = String::from_utf8(key[prefix.len()..].to_vec()).ok()
+                .and_then(|rest| rest.split('/').next().map(|id| id.to_string()))
+                .ok_or(StateError::CorruptedValue)?;
+            let mut txes = Vec::new();
+            for row in self.db.scan_prefix(format!("{}{}/", prefix, wallet)) {
+                if txes.len() >= limit {
+                    break
+                }
+                let (_, tx_key) = row?;
+                let tx_key = String::from_utf8(tx_key.to_vec())
+                    .map_err(|_| StateError::CorruptedValue)?;
+                if hidden.contains(&tx_key) {
+                    continue
+                }
+                if let Some(tx) = self.get_tx_by_key(tx_key) {
+                    txes.push(tx);
+                }
+            }
+            if let Ok(wallet_id) = Uuid::from_str(wallet.as_str()) {
+                if !txes.is_empty() {
+                    result.insert(wallet_id, txes);
+                }
+            }
+            // `0` goes right after `/`, so it skips the rest of the entries of the wallet
+            from = format!("{}{}0", prefix, wallet);
+        }
+        Ok(result)
+    }
+
+    fn get_tx(&self, blockchain: u32, txid: &str) -> Option<proto_Transaction> {
+        if !self.permissions.can_read(Store::Transactions) {
+            return None
+        }
+        let key = TransactionsAccess::get_key(blockchain, txid);
+        self.get_tx_by_key(key)
+    }
+
+    fn get_txs(&self, refs: Vec<(u32, String)>) -> Vec<Option<proto_Transaction>> {
+        let readable = self.permissions.can_read(Store::Transactions);
+        refs.into_iter()
+            .map(|(blockchain, txid)| if readable {
+                self.get_tx_by_key(TransactionsAccess::get_key(blockchain, txid))
+            } else {
+                None
+            })
+            .collect()
+    }
+
+    fn get_tx_meta(&self, blockchain: u32, txid: &str) -> Result<Option<proto_TransactionMeta>, StateError> {
+        self.permissions.check_read(Store::Transactions)?;
+        let key = TransactionsAccess::get_key_meta(blockchain, txid);
+        match self.db.get(key) {
+            Ok(data) => {
+                match data {
+                    Some(b) => Ok(proto_TransactionMeta::parse_from_bytes(b.deref()).ok()),
+                    None => Ok(None)
+                }
+            }
+            Err(_) => Err(StateError::IOError)
+        }
+    }
+
+    fn get_tx_with_meta(&self, blockchain: u32, txid: &str, include_raw: bool) -> Result<Option<TransactionWithMeta>, StateError> {
+        self.permissions.check_read(Store::Transactions)?;
+        let tx = match self.get_tx_by_key(TransactionsAccess::get_key(blockchain, txid)) {
+            Some(tx) => tx,
+            None => return Ok(None)
+        };
+        let mut meta = self.get_tx_meta(blockchain, txid)?;
+        if !include_raw {
+            if let Some(meta) = meta.as_mut() {
+                meta.clear_raw();
+            }
+        }
+        Ok(Some(TransactionWithMeta { tx, meta }))
+    }
+
+    fn get_meta_history(&self, blockchain: u32, txid: &str) -> Result<Vec<MetaRevision>, StateError> {
+        self.permissions.check_read(Store::Transactions)?;
+        self.read_meta_history(blockchain, txid)
+    }
+
+    fn export_meta_proof(&self, blockchain: u32, txid: &str) -> Result<String, StateError> {
+        self.permissions.check_read(Store::Transactions)?;
+        let revisions = self.read_meta_history(blockchain, txid)?;
+        meta_proof::to_json(blockchain, TransactionsAccess::normalize_tx_id(blockchain, txid).as_str(), &revisions)
+    }
+
+    fn get_first_seen(&self, address: String) -> Result<Option<FirstSeen>, StateError> {
+        self.permissions.check_read(Store::Transactions)?;
+        let address = address_registry_store::normalize_address(address.as_str())?;
+        self.read_first_seen(address.as_str())
+    }
+
+    fn set_tx_meta(&self, value: proto_TransactionMeta) -> Result<proto_TransactionMeta, StateError> {
+        self.permissions.check_write(Store::Transactions)?;
+        self.limiter.check(Store::Transactions)?;
+        let value = TransactionsAccess::normalize_meta(value)?;
+        let blockchain = value.blockchain.value() as u32;
+        let tx_id = value.tx_id.clone();
+        self.validators.check_transaction_meta(&value)?;
+        let existing = self.get_tx_meta(blockchain, tx_id.as_str())?;
+        if let Some(existing_value) = existing {
+            if existing_value.timestamp >= value.timestamp {
+                return Ok(existing_value)
+            }
+        }
+        let key = TransactionsAccess::get_key_meta(blockchain, tx_id);
+        let mut batch = Batch::default();
+        let mut revisions = Vec::with_capacity(1);
+        self.write_meta(key, &value, &mut batch)?;
+        self.append_meta_revision(&value, &mut revisions)?;
+        let seq = self.apply_with_revisions(batch, revisions)?;
+        self.notifier.notify(self.get_meta_change(std::slice::from_ref(&value), seq));
+        Ok(value)
+    }
+
+    fn set_tx_meta_bulk(&self, values: Vec<proto_TransactionMeta>) -> Result<Vec<proto_TransactionMeta>, StateError> {
+        self.permissions.check_write(Store::Transactions)?;
+        self.limiter.check(Store::Transactions)?;
+        // check everything before writing, so an invalid value doesn't leave the others partially stored
+        let values = values.into_iter()
+            .map(TransactionsAccess::normalize_meta)
+            .collect::<Result<Vec<_>, _>>()?;
+        for value in &values {
+            self.validators.check_transaction_meta(value)?;
+        }
+        // the same tx may be listed multiple times, so keep the latest version seen so far for each key
+        let mut current: HashMap<String, Option<proto_TransactionMeta>> = HashMap::new();
+        let mut results = Vec::with_capacity(values.len());
+        for value in values {
+            let blockchain = value.blockchain.value() as u32;
+            let key = TransactionsAccess::get_key_meta(blockchain, value.tx_id.clone());
+            if !current.contains_key(&key) {
+                let existing = self.get_tx_meta(blockchain, value.tx_id.as_str())?;
+                current.insert(key.clone(), existing);
+            }
+            let existing = current.get_mut(&key).unwrap();
+            match existing {
+                Some(existing_value) if existing_value.timestamp >= value.timestamp => {
+                    results.push(existing_value.clone());
+                },
+                _ => {
+                    *existing = Some(value.clone());
+                    results.push(value);
+                }
+            }
+        }
+        let mut batch = Batch::default();
+        let mut revisions = Vec::new();
+        let mut written = Vec::new();
+        for (key, value) in current {
+            if let Some(value) = value {
+                self.write_meta(key, &value, &mut batch)?;
+                self.append_meta_revision(&value, &mut revisions)?;
+                written.push(value);
+            }
+        }
+        if !written.is_empty() {
+            let seq = self.apply_with_revisions(batch, revisions)?;
+            self.notifier.notify(self.get_meta_change(&written, seq));
+        }
+        Ok(results)
+    }
+
+    fn suggest_labels(&self, address: String) -> Result<Vec<(String, usize)>, StateError> {
+        self.permissions.check_read(Store::Transactions)?;
+        let is_ethereum = address.starts_with("0x");
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for row in self.db.scan_prefix(format!("{}:", PREFIX_KEY_META)) {
+            let (_, value) = row?;
+            let meta = match proto_TransactionMeta::parse_from_bytes(value.as_ref()) {
+                Ok(meta) => meta,
+                Err(_) => continue
+            };
+            let label = meta.label.trim();
+            if label.is_empty() {
+                continue
+            }
+            let tx = match self.read_tx_projected(
+                TransactionsAccess::get_key(meta.blockchain.value() as u32, meta.tx_id.clone()),
+                &Some(HashSet::from([TransactionField::Changes.get_number()])),
+            ) {
+                Ok(Some(tx)) => tx,
+                _ => continue
+            };
+            let same_counterparty = tx.changes.iter().any(|c| {
+                if is_ethereum { c.address.eq_ignore_ascii_case(&address) } else { c.address == address }
+            });
+            if same_counterparty {
+                *counts.entry(label.to_string()).or_insert(0) += 1;
+            }
+        }
+        let mut result: Vec<(String, usize)> = counts.into_iter().collect();
+        result.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        Ok(result)
+    }
+
+    fn add_tag(&self, blockchain: u32, tx_id: String, tag: String) -> Result<proto_TransactionMeta, StateError> {
+        self.permissions.check_write(Store::Transactions)?;
+        self.limiter.check(Store::Transactions)?;
+        let tag = TransactionsAccess::normalize_tag(tag.as_str())?;
+        let meta = self.update_meta(blockchain, tx_id, true, |meta| {
+            if !meta.tags.contains(&tag) {
+                meta.tags.push(tag.clone());
+            }
+        })?;
+        // it's always created
+        meta.ok_or(StateError::IOError)
+    }
+
+    fn remove_tag(&self, blockchain: u32, tx_id: String, tag: String) -> Result<Option<proto_TransactionMeta>, StateError> {
+        self.permissions.check_write(Store::Transactions)?;
+        self.limiter.check(Store::Transactions)?;
+        let tag = tag.trim().to_lowercase();
+        self.update_meta(blockchain, tx_id, false, |meta| meta.tags.retain(|t| *t != tag))
+    }
+
+    fn set_flag(&self, blockchain: u32, tx_id: String, flag: TxFlag, value: bool) -> Result<proto_TransactionMeta, StateError> {
+        self.permissions.check_write(Store::Transactions)?;
+        self.limiter.check(Store::Transactions)?;
+        let meta = self.update_meta(blockchain, tx_id, true, |meta| flag.set(meta, value))?;
+        // it's always created
+        meta.ok_or(StateError::IOError)
+    }
+
+    fn compact_raw(&self, max_age: std::time::Duration) -> Result<usize, StateError> {
+        self.permissions.check_write(Store::Transactions)?;
+        self.limiter.check(Store::Transactions)?;
+        let cutoff = (Utc::now().timestamp_millis() as u64).saturating_sub(max_age.as_millis() as u64);
+        let mut batch = Batch::default();
+        let mut count = 0;
+        for row in self.db.scan_prefix(format!("{}:", PREFIX_KEY_META)) {
+            let (key, value) = row?;
+            let mut meta = match proto_TransactionMeta::parse_from_bytes(value.as_ref()) {
+                Ok(meta) => meta,
+                Err(_) => continue
+            };
+            if meta.raw.is_empty() {
+                continue
+            }
+            let is_old = self.get_tx(meta.blockchain.value() as u32, meta.tx_id.as_str())
+                .is_some_and(|tx| tx.state == State::CONFIRMED && tx.confirm_timestamp > 0 && tx.confirm_timestamp < cutoff);
+            if !is_old {
+                continue
+            }
+            meta.raw_hash = sha256::Hash::hash(meta.raw.as_slice()).to_vec();
+            meta.raw = vec![];
+            batch.insert(key, meta.write_to_bytes()?);
+            count += 1;
+        }
+        if count > 0 {
+            self.health.apply(&self.db, batch)?;
+        }
+        Ok(count)
+    }
+
+    fn restore_raw(&self, blockchain: u32, tx_id: String, raw: Vec<u8>) -> Result<Option<proto_TransactionMeta>, StateError> {
+        self.permissions.check_write(Store::Transactions)?;
+        self.limiter.check(Store::Transactions)?;
+        let mut meta = match self.get_tx_meta(blockchain, tx_id.as_str())? {
+            Some(meta) => meta,
+            None => return Ok(None)
+        };
+        if meta.raw_hash.is_empty() {
+            return Ok(Some(meta))
+        }
+        if sha256::Hash::hash(raw.as_slice()).to_vec() != meta.raw_hash {
+            return Err(StateError::InvalidValue(InvalidValueError::NameMessage("raw".to_string(), "Hash doesn't match".to_string())))
+        }
+        meta.raw = raw;
+        meta.raw_hash = vec![];
+        let key = TransactionsAccess::get_key_meta(blockchain, tx_id);
         let mut batch = Batch::default();
+        batch.insert(key.as_bytes(), meta.write_to_bytes()?);
+        self.health.apply(&self.db, batch)?;
+        Ok(Some(meta))
+    }
+
+    fn set_state(&self, blockchain: u32, tx_id: String, state: State, timestamp: u64) -> Result<Option<proto_Transaction>, StateError> {
+        self.permissions.check_write(Store::Transactions)?;
+        self.limiter.check(Store::Transactions)?;
         let tx_key = TransactionsAccess::get_key(blockchain, tx_id);
-        batch.remove(tx_key.as_bytes());
-        Indexing::remove_backref(tx_key, self.db.clone(), &mut batch)?;
-        self.db.apply_batch(batch)
-            .map_err(|e| StateError::from(e))
+        let mut tx = match self.get_tx_by_key(tx_key.clone()) {
+            Some(tx) => tx,
+            None => return Ok(None)
+        };
+        if tx.sync_timestamp > timestamp {
+            return Ok(Some(tx))
+        }
+        tx.state = state;
+        tx.sync_timestamp = timestamp;
+        tx.version += 1;
+        self.validators.check_transaction(&tx)?;
+
+        let mut batch = Batch::default();
+        self.write_reindexed(tx_key, &tx, &mut batch)?;
+        if self.permissions.can_write(Store::Balances) {
+            balance_store::release_spent(&self.db, &tx, &mut batch)?;
+        }
+        let seq = self.health.apply(&self.db, batch)?;
+        let mut change = TransactionsChange { seq, ..TransactionsChange::default() };
+        change.add(&tx);
+        self.notifier.notify(change);
+        Ok(Some(tx))
+    }
+
+    fn get_replacements(&self, blockchain: u32, tx_id: String) -> Result<Vec<proto_Transaction>, StateError> {
+        self.permissions.check_read(Store::Transactions)?;
+        let tx = match self.get_tx(blockchain, tx_id.as_str()) {
+            Some(tx) => tx,
+            None => return Ok(vec![])
+        };
+        match tx.nonce_ref.as_ref() {
+            Some(nonce_ref) if !nonce_ref.from.is_empty() => {
+                let found = self.find_by_nonce(blockchain, nonce_ref)?;
+                Ok(found.into_iter().map(|(_, tx)| tx).collect())
+            },
+            _ => Ok(vec![tx])
+        }
+    }
+
+    fn set_replaced(&self, blockchain: u32, winner_tx_id: String) -> Result<Vec<String>, StateError> {
+        self.permissions.check_write(Store::Transactions)?;
+        self.limiter.check(Store::Transactions)?;
+        let winner_tx_id = TransactionsAccess::normalize_tx_id(blockchain, winner_tx_id.as_str());
+        let winner = match self.get_tx(blockchain, winner_tx_id.as_str()) {
+            Some(tx) => tx,
+            None => return Ok(vec![])
+        };
+        let nonce_ref = match winner.nonce_ref.as_ref() {
+            Some(nonce_ref) if !nonce_ref.from.is_empty() => nonce_ref.clone(),
+            _ => return Ok(vec![])
+        };
+        let mut batch = Batch::default();
+        let mut written = 0;
+        let mut replaced = Vec::new();
+        for (tx_key, mut tx) in self.find_by_nonce(blockchain, &nonce_ref)? {
+            if tx.tx_id == winner_tx_id {
+                // it may be previously replaced by another transaction, which is not the case anymore
+                if !tx.replaced_by.is_empty() || tx.state == State::REPLACED {
+                    if tx.state == State::REPLACED {
+                        tx.state = if tx.block.is_some() { State::CONFIRMED } else { State::SUBMITTED };
+                    }
+                    tx.replaced_by = "".to_string();
+                    tx.version += 1;
+                    self.write_reindexed(tx_key, &tx, &mut batch)?;
+                    written += 1;
+                }
+                continue;
+            }
+            if tx.state != State::REPLACED || tx.replaced_by != winner_tx_id {
+                tx.state = State::REPLACED;
+                tx.replaced_by = winner_tx_id.clone();
+                tx.version += 1;
+                self.write_reindexed(tx_key, &tx, &mut batch)?;
+                written += 1;
+            }
+            replaced.push(tx.tx_id);
+        }
+        if written > 0 {
+            self.health.apply(&self.db, batch)?;
+        }
+        Ok(replaced)
+    }
+
+    fn submit(&self, transactions: Vec<proto_Transaction>) -> Result<Vec<SubmitOutcome>, StateError> {
+        self.submit_with(transactions, MergePolicy::Default)
+    }
+
+    fn submit_with(&self, transactions: Vec<proto_Transaction>, policy: MergePolicy) -> Result<Vec<SubmitOutcome>, StateError> {
+        self.permissions.check_write(Store::Transactions)?;
+        self.limiter.check(Store::Transactions)?;
+        let transactions: Vec<proto_Transaction> = transactions.into_iter()
+            .map(|tx| self.prepare(tx))
+            .collect();
+        // check everything before writing, so an invalid transaction doesn't leave the others partially stored
+        for tx in &transactions {
+            self.validators.check_transaction(tx)?;
+        }
+        self.write_submitted(transactions, policy)
+    }
+
+    fn submit_checked(&self, transactions: Vec<proto_Transaction>, mode: ValidationMode) -> Result<CheckedWrite<SubmitOutcome>, StateError> {
+        self.permissions.check_write(Store::Transactions)?;
+        self.limiter.check(Store::Transactions)?;
+        let now = Utc::now().timestamp_millis() as u64;
+        let mut results = vec![None; transactions.len()];
+        let mut accepted = Vec::new();
+        let mut positions = Vec::new();
+        let mut warnings = Vec::new();
+        for (index, tx) in transactions.into_iter().enumerate() {
+            let mut tx = self.prepare(tx);
+            match self.validators.check_transaction_with(&mut tx, mode, now) {
+                Ok(messages) => {
+                    warnings.extend(messages.into_iter().map(|message| ValidationWarning { index, skipped: false, message }));
+                    accepted.push(tx);
+                    positions.push(index);
+                }
+                Err(e) if mode == ValidationMode::Strict => return Err(e),
+                Err(e) => warnings.push(ValidationWarning { index, skipped: true, message: format!("{:?}", e) }),
+            }
+        }
+        for (index, outcome) in positions.into_iter().zip(self.write_submitted(accepted, MergePolicy::Default)?) {
+            results[index] = Some(outcome);
+        }
+        Ok(CheckedWrite { results, warnings })
+    }
+
+    fn forget(&self, blockchain: u32, tx_id: String) -> Result<(), StateError> {
+        self.permissions.check_write(Store::Transactions)?;
+        self.limiter.check(Store::Transactions)?;
+        let mut batch = Batch::default();
+        let mut change = TransactionsChange::default();
+        self.remove_tx(blockchain, tx_id.as_str(), &mut batch, &mut change)?;
+        change.seq = self.health.apply(&self.db, batch)?;
+        self.notifier.notify(change);
+        Ok(())
+    }
+
+    fn find_dangling(&self) -> Result<Vec<DanglingReference>, StateError> {
+        self.permissions.check_read(Store::Transactions)?;
+        let mut result = Vec::new();
+        for row in self.db.scan_prefix(format!("{}:", PREFIX_KEY_META)) {
+            let (key, value) = row?;
+            let meta = match proto_TransactionMeta::parse_from_bytes(value.as_ref()) {
+                Ok(meta) => meta,
+                Err(_) => continue
+            };
+            let blockchain = meta.blockchain.value() as u32;
+            if !self.db.contains_key(TransactionsAccess::get_key(blockchain, meta.tx_id.as_str()))? {
+                result.push(DanglingReference {
+                    kind: TxReference::Meta,
+                    key: String::from_utf8(key.to_vec()).map_err(|_| StateError::CorruptedValue)?,
+                    blockchain,
+                    tx_id: meta.tx_id,
+                });
+            }
+        }
+        if self.permissions.can_read(Store::Allowances) {
+            for (key, allowance) in allowance_store::find_revoked(&self.db)? {
+                if !self.db.contains_key(TransactionsAccess::get_key(allowance.blockchain, allowance.revoked_by_tx.as_str()))? {
+                    result.push(DanglingReference {
+                        kind: TxReference::AllowanceRevoke,
+                        key,
+                        blockchain: allowance.blockchain,
+                        tx_id: allowance.revoked_by_tx,
+                    });
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    fn import_jsonl<R: BufRead>(&self, reader: R) -> Result<ImportReport, StateError> {
+        self.permissions.check_write(Store::Transactions)?;
+        self.limiter.check(Store::Transactions)?;
+        let mut report = ImportReport::default();
+        let mut transactions = Vec::with_capacity(IMPORT_BATCH);
+        for (i, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue
+            }
+            // check each of them here, because a submit rejects the whole batch if any of the transactions is invalid
+            let tx = transaction_json::parse_transaction(line.as_str())
+                .map(|tx| self.prepare(tx))
+                .and_then(|tx| self.validators.check_transaction(&tx).map(|_| tx).map_err(|e| format!("{:?}", e)));
+            match tx {
+                Ok(tx) => transactions.push(tx),
+                Err(e) => report.errors.push((i + 1, e)),
+            }
+            // written directly, as the whole import is a single write for the rate limit
+            if transactions.len() >= IMPORT_BATCH {
+                let count = transactions.len();
+                self.write_submitted(std::mem::take(&mut transactions), MergePolicy::Default)?;
+                report.imported += count;
+            }
+        }
+        if !transactions.is_empty() {
+            let count = transactions.len();
+            self.write_submitted(transactions, MergePolicy::Default)?;
+            report.imported += count;
+        }
+        Ok(report)
     }
 
     fn get_count(&self, filter: Filter) -> Result<usize, StateError> {
-        let bounds = filter.get_index_bounds();
+        self.permissions.check_read(Store::Transactions)?;
+        let ranges = filter.get_index_ranges();
+        let selection = self.get_meta_selection(&filter)?;
+        if TransactionsAccess::is_index_only(&filter) {
+            // the index already has only matching transactions, so no need to load them
+            let mut tx_keys = HashSet::new();
+            for row in Indexing::scan_ranges(&self.db, PREFIX_IDX, ranges, &PageQuery::default())? {
+                let (_, tx_key) = row?;
+                if let Ok(tx_key) = String::from_utf8(tx_key.to_vec()) {
+                    if selection.accepts(&tx_key) {
+                        tx_keys.insert(tx_key);
+                    }
+                }
+            }
+            return Ok(tx_keys.len())
+        }
+        let heads = self.get_heads(&filter)?;
         let mut processed = HashSet::new();
-        let mut iter = self.db.range(bounds);
+        let mut iter = Indexing::scan_ranges(&self.db, PREFIX_IDX, ranges, &PageQuery::default())?;
         let mut count = 0;
         let mut done = false;
         while !done {
@@ -313,9 +1855,10 @@ impl Transactions for TransactionsAccess {
                             let txkey = v.1.to_vec();
                             let txkey = String::from_utf8(txkey).unwrap();
                             let unprocessed = processed.insert(txkey.clone());
-                            if unprocessed {
+                            let tag_ok = selection.accepts(&txkey);
+                            if unprocessed && tag_ok {
                                 if let Some(tx) = self.get_tx_by_key(txkey) {
-                                    if filter.check_filter(&tx) {
+                                    if filter.check_filter(&tx) && filter.check_confirmations(&tx, &heads) {
                                         count += 1;
                                     }
                                 }
@@ -330,7 +1873,80 @@ impl Transactions for TransactionsAccess {
         Ok(count)
     }
 
+    fn stats(&self, filter: Filter, granularity: Granularity) -> Result<Vec<PeriodStats>, StateError> {
+        self.permissions.check_read(Store::Transactions)?;
+        let mut fields: HashSet<u32> = filter.get_required_fields().iter().map(|f| f.get_number()).collect();
+        for f in [TransactionField::SinceTimestamp, TransactionField::ConfirmTimestamp, TransactionField::Changes] {
+            fields.insert(f.get_number());
+        }
+        let fields = Some(fields);
+
+        // asset -> (sent, received)
+        type Totals = BTreeMap<String, (BigUint, BigUint)>;
+        // period start -> (count, totals)
+        let mut periods: BTreeMap<i64, (usize, Totals)> = BTreeMap::new();
+        let selection = self.get_meta_selection(&filter)?;
+        let heads = self.get_heads(&filter)?;
+        let mut processed = HashSet::new();
+        for row in Indexing::scan_ranges(&self.db, PREFIX_IDX, filter.get_index_ranges(), &PageQuery::default())? {
+            let (_, tx_key) = match row {
+                Ok(v) => v,
+                Err(e) => {
+                    warn!("Skip unreadable transaction index entry: {:?}", e);
+                    continue
+                }
+            };
+            let tx_key = String::from_utf8(tx_key.to_vec()).unwrap();
+            if !processed.insert(tx_key.clone()) {
+                continue
+            }
+            if !selection.accepts(&tx_key) {
+                continue
+            }
+            let tx = match self.read_tx_projected(tx_key.clone(), &fields) {
+                Ok(Some(tx)) => tx,
+                Ok(None) => continue,
+                Err(e) => {
+                    warn!("Skip unreadable transaction {}: {:?}", tx_key, e);
+                    self.quarantine(tx_key.as_str(), &e);
+                    continue
+                }
+            };
+            if !filter.check_filter(&tx) || !filter.check_confirmations(&tx, &heads) {
+                continue
+            }
+            let ts = if tx.confirm_timestamp > 0 { tx.confirm_timestamp } else { tx.since_timestamp };
+            let start = granularity.get_period_start(Utc.timestamp_millis_opt(ts as i64).unwrap()).timestamp_millis();
+            let period = periods.entry(start).or_insert_with(|| (0, BTreeMap::new()));
+            period.0 += 1;
+            for change in tx.changes.iter().filter(|c| filter.check_change(c)) {
+                let amount = match BigUint::from_str(change.amount.as_str()) {
+                    Ok(amount) => amount,
+                    Err(_) => continue
+                };
+                let totals = period.1.entry(change.asset.clone())
+                    .or_insert_with(|| (BigUint::zero(), BigUint::zero()));
+                match change.direction {
+                    Direction::SEND => totals.0 += amount,
+                    Direction::RECEIVE => totals.1 += amount,
+                }
+            }
+        }
+
+        let result = periods.into_iter()
+            .map(|(start, (count, assets))| PeriodStats {
+                start: Utc.timestamp_millis_opt(start).unwrap(),
+                count,
+                assets: assets.into_iter()
+                    .map(|(asset, (sent, received))| AssetTotal { asset, sent, received })
+                    .collect(),
+            })
+            .collect();
+        Ok(result)
+    }
+
     fn get_cursor<S: AsRef<str>>(&self, address: S) -> Result<Option<RemoteCursor>, StateError> {
+        self.permissions.check_read(Store::Transactions)?;
         let key = format!("{}:{}", PREFIX_CURSOR, address.as_ref());
         if let Some(value) = self.db.get(key)? {
             let cursor = proto_Cursor::parse_from_bytes(value.deref())?;
@@ -345,68 +1961,1862 @@ impl Transactions for TransactionsAccess {
         } else {
             Ok(None)
         }
-    }
+    }
+
+    fn set_head_height(&self, blockchain: u32, height: u64) -> Result<(), StateError> {
+        self.permissions.check_write(Store::Transactions)?;
+        self.limiter.check(Store::Transactions)?;
+        let key = format!("{}:{}", PREFIX_HEAD, blockchain);
+        let mut batch = Batch::default();
+        batch.insert(key.as_bytes(), &height.to_be_bytes());
+        self.health.apply(&self.db, batch)?;
+        Ok(())
+    }
+
+    fn get_head_height(&self, blockchain: u32) -> Result<Option<u64>, StateError> {
+        self.permissions.check_read(Store::Transactions)?;
+        let key = format!("{}:{}", PREFIX_HEAD, blockchain);
+        match self.db.get(key)? {
+            Some(value) => {
+                let height = <[u8; 8]>::try_from(value.as_ref()).map_err(|_| StateError::CorruptedValue)?;
+                Ok(Some(u64::from_be_bytes(height)))
+            }
+            None => Ok(None)
+        }
+    }
+
+    fn get_cursors<S: AsRef<str>>(&self, addresses: Vec<S>) -> Result<Vec<(String, Option<RemoteCursor>, Staleness)>, StateError> {
+        self.permissions.check_read(Store::Transactions)?;
+        let now = Utc::now();
+        let stale_after = chrono::Duration::from_std(self.ttl.cursor_stale_after).unwrap_or(chrono::Duration::MAX);
+        let mut result = Vec::with_capacity(addresses.len());
+        for address in addresses {
+            let cursor = self.get_cursor(address.as_ref())?;
+            let staleness = Staleness::of(&cursor, now, stale_after);
+            result.push((address.as_ref().to_string(), cursor, staleness));
+        }
+        Ok(result)
+    }
+
+    fn list_cursors(&self) -> Result<Vec<(String, RemoteCursor)>, StateError> {
+        self.permissions.check_read(Store::Transactions)?;
+        let prefix = format!("{}:", PREFIX_CURSOR);
+        let mut result = Vec::new();
+        for row in self.db.scan_prefix(prefix.as_bytes()) {
+            let (key, value) = row?;
+            let cursor = match proto_Cursor::parse_from_bytes(value.deref()) {
+                Ok(cursor) => cursor,
+                Err(e) => {
+                    warn!("Skip unreadable cursor {}: {:?}", String::from_utf8_lossy(key.as_ref()), e);
+                    continue
+                }
+            };
+            if cursor.value.is_empty() {
+                continue
+            }
+            let address = String::from_utf8_lossy(&key[prefix.len()..]).to_string();
+            result.push((address, RemoteCursor {
+                value: cursor.value,
+                since: Utc.timestamp_millis_opt(cursor.ts as i64).unwrap()
+            }));
+        }
+        Ok(result)
+    }
+
+    fn set_cursor<S: AsRef<str> + ToString>(&self, address: S, cursor: S) -> Result<(), StateError> {
+        self.permissions.check_write(Store::Transactions)?;
+        // the rate is limited by `set_cursors`
+        self.set_cursors(vec![(address, cursor)])
+    }
+
+    fn set_cursors<S: AsRef<str> + ToString>(&self, cursors: Vec<(S, S)>) -> Result<(), StateError> {
+        self.permissions.check_write(Store::Transactions)?;
+        self.limiter.check(Store::Transactions)?;
+        // if the same address is specified multiple times only the last value matters
+        let cursors: HashMap<String, String> = cursors.into_iter()
+            .map(|(address, cursor)| (address.to_string(), cursor.to_string()))
+            .collect();
+        let now = Utc::now().timestamp_millis() as u64;
+        let mut batch = Batch::default();
+        let mut changed = false;
+        for (address, cursor) in cursors {
+            let current = self.get_cursor(address.as_str())?;
+            if current.is_some_and(|c| c.value == cursor) {
+                continue
+            }
+            let key = format!("{}:{}", PREFIX_CURSOR, address);
+            let mut proto = proto_Cursor::new();
+            proto.set_address(address);
+            proto.set_ts(now);
+            proto.set_value(cursor);
+            let value = proto.write_to_bytes()?;
+            batch.insert(key.as_bytes(), value.as_slice());
+            changed = true;
+        }
+        if !changed {
+            return Ok(())
+        }
+        self.health.apply(&self.db, batch)?;
+        Ok(())
+    }
+
+    fn rebuild_indexes(&self) -> Result<usize, StateError> {
+        self.permissions.check_write(Store::Transactions)?;
+        self.limiter.check(Store::Transactions)?;
+        let (batch, count) = self.reindex()?;
+        self.health.apply(&self.db, batch)?;
+        Ok(count)
+    }
+
+    fn refresh_recent(&self) -> Result<usize, StateError> {
+        self.permissions.check_write(Store::Transactions)?;
+        self.limiter.check(Store::Transactions)?;
+        let recent_since = self.get_recent_since();
+        let prefix = format!("{}:{:}/", PREFIX_IDX, IndexType::ByWalletAndConfirm(Uuid::nil(), true, 0, 0, String::new()).get_prefix());
+        let recent_marker = format!("/{}/", IndexConvert::get_bool_tf(&true));
+        let mut processed = HashSet::new();
+        let mut batch = Batch::default();
+        let mut count = 0;
+        for row in self.db.scan_prefix(prefix.as_bytes()) {
+            let (idx_key, tx_key) = row?;
+            let is_recent_idx = String::from_utf8_lossy(&idx_key[prefix.len()..]).contains(recent_marker.as_str());
+            if !is_recent_idx {
+                continue
+            }
+            let tx_key = String::from_utf8(tx_key.to_vec())
+                .map_err(|_| StateError::CorruptedValue)?;
+            if !processed.insert(tx_key.clone()) {
+                continue
+            }
+            if let Some(tx) = self.get_tx_by_key(tx_key.clone()) {
+                if !is_recent(&tx, recent_since) {
+                    self.write_reindexed(tx_key, &tx, &mut batch)?;
+                    count += 1;
+                }
+            }
+        }
+        if count > 0 {
+            self.health.apply(&self.db, batch)?;
+        }
+        Ok(count)
+    }
+
+    fn prune(&self, policy: RetentionPolicy) -> Result<usize, StateError> {
+        self.permissions.check_write(Store::Transactions)?;
+        self.limiter.check(Store::Transactions)?;
+        let min_timestamp = policy.max_age
+            .map(|age| (Utc::now().timestamp_millis() - age.num_milliseconds()).max(0) as u64);
+        let fields: HashSet<u32> = [
+            TransactionField::Blockchain, TransactionField::TxId, TransactionField::SinceTimestamp,
+            TransactionField::ConfirmTimestamp, TransactionField::State, TransactionField::Changes,
+        ].iter().map(|f| f.get_number()).collect();
+
+        // wallet -> (timestamp, tx key) of all its transactions, to find which are out of the limit
+        let mut per_wallet: HashMap<String, Vec<(u64, String)>> = HashMap::new();
+        let mut candidates: Vec<(String, proto_Transaction)> = Vec::new();
+        for row in self.db.scan_prefix(format!("{}:", PREFIX_KEY)) {
+            let (key, value) = row?;
+            let tx_key = String::from_utf8(key.to_vec())
+                .map_err(|_| StateError::CorruptedValue)?;
+            let tx: proto_Transaction = match decode_projected(value.as_ref(), &fields) {
+                Ok(tx) => tx,
+                Err(e) => {
+                    warn!("Skip unreadable transaction {}: {:?}", tx_key, e);
+                    continue
+                }
+            };
+            let ts = if tx.confirm_timestamp > 0 { tx.confirm_timestamp } else { tx.since_timestamp };
+            let wallets: HashSet<String> = tx.changes.iter()
+                .filter(|c| !c.wallet_id.is_empty())
+                .map(|c| c.wallet_id.clone())
+                .collect();
+            for wallet in wallets {
+                per_wallet.entry(wallet).or_default().push((ts, tx_key.clone()));
+            }
+            if matches!(tx.state, State::CONFIRMED | State::REPLACED | State::DROPPED) {
+                candidates.push((tx_key, tx));
+            }
+        }
+
+        // how many of the wallets of the transaction keep it
+        let mut kept_by_wallets: HashMap<String, usize> = HashMap::new();
+        if let Some(max) = policy.max_per_wallet {
+            for txes in per_wallet.values_mut() {
+                txes.sort_by(|a, b| b.cmp(a));
+                for (_, tx_key) in txes.iter().take(max) {
+                    *kept_by_wallets.entry(tx_key.clone()).or_default() += 1;
+                }
+            }
+        }
+
+        let mut change = TransactionsChange::default();
+        let mut batch = Batch::default();
+        let mut batch_size = 0;
+        let mut count = 0;
+        for (tx_key, tx) in candidates {
+            let ts = if tx.confirm_timestamp > 0 { tx.confirm_timestamp } else { tx.since_timestamp };
+            let is_old = min_timestamp.is_some_and(|min| ts < min);
+            let has_wallet = tx.changes.iter().any(|c| !c.wallet_id.is_empty());
+            let is_over_limit = policy.max_per_wallet.is_some() && has_wallet && !kept_by_wallets.contains_key(&tx_key);
+            if !is_old && !is_over_limit {
+                continue
+            }
+            let meta_key = TransactionsAccess::get_key_meta(tx.blockchain.value() as u32, tx.tx_id.clone());
+            let meta = self.db.get(meta_key.as_bytes())?
+                .and_then(|b| proto_TransactionMeta::parse_from_bytes(b.deref()).ok());
+            if policy.keep_labeled && meta.as_ref().is_some_and(|m| m.is_curated()) {
+                continue
+            }
+            // same as forgetting it, so the records referencing the transaction follow the cascade policies
+            self.remove_tx(tx.blockchain.value() as u32, tx.tx_id.as_str(), &mut batch, &mut change)?;
+            if meta.is_some() {
+                self.remove_meta(tx.blockchain.value() as u32, tx.tx_id.as_str(), &mut batch)?;
+            }
+            count += 1;
+            batch_size += 1;
+            if batch_size >= PRUNE_BATCH {
+                change.seq = self.health.apply(&self.db, std::mem::take(&mut batch))?;
+                batch_size = 0;
+            }
+        }
+        if batch_size > 0 {
+            change.seq = self.health.apply(&self.db, batch)?;
+        }
+        self.notifier.notify(change);
+        Ok(count)
+    }
+
+    fn reattribute(&self, wallet_id: Uuid, entry_id: u32, blockchain: u32, addresses: Vec<String>) -> Result<usize, StateError> {
+        self.permissions.check_write(Store::Transactions)?;
+        self.limiter.check(Store::Transactions)?;
+        // same as the address registry, so an address is matched regardless of the form it came in
+        let addresses: HashSet<String> = addresses.iter()
+            .map(|a| address_registry_store::normalize_address(a.as_str()))
+            .collect::<Result<HashSet<String>, StateError>>()
+            .map_err(|_| StateError::InvalidValue(InvalidValueError::Name("addresses".to_string())))?;
+        let is_known = |address: &str| {
+            address_registry_store::normalize_address(address).is_ok_and(|a| addresses.contains(&a))
+        };
+        let mut batch = Batch::default();
+        let mut count = 0;
+        let mut notification = TransactionsChange::default();
+        for key in self.db.scan_prefix(format!("{}:", PREFIX_KEY)).keys() {
+            let tx_key = String::from_utf8(key?.to_vec())
+                .map_err(|_| StateError::CorruptedValue)?;
+            let mut tx = match self.get_tx_by_key(tx_key.clone()) {
+                Some(tx) if tx.blockchain.value() as u32 == blockchain => tx,
+                _ => continue
+            };
+            let mut changed = false;
+            for change in tx.changes.iter_mut() {
+                if change.wallet_id.is_empty() && is_known(change.address.as_str()) {
+                    change.wallet_id = wallet_id.to_string();
+                    change.entry_id = entry_id;
+                    changed = true;
+                }
+            }
+            if changed {
+                tx.version += 1;
+                tx.internal_transfer = tx.detect_internal_transfer();
+                self.write_reindexed(tx_key, &tx, &mut batch)?;
+                notification.add(&tx);
+                count += 1;
+            }
+        }
+        if count > 0 {
+            notification.seq = self.health.apply(&self.db, batch)?;
+        }
+        self.notifier.notify(notification);
+        Ok(count)
+    }
+
+    fn clear_cursors<S: AsRef<str>>(&self, addresses: Vec<S>) -> Result<usize, StateError> {
+        self.permissions.check_write(Store::Transactions)?;
+        self.limiter.check(Store::Transactions)?;
+        let mut batch = Batch::default();
+        let mut count = 0;
+        for address in addresses {
+            let key = format!("{}:{}", PREFIX_CURSOR, address.as_ref());
+            if self.db.contains_key(key.as_bytes())? {
+                batch.remove(key.as_bytes());
+                count += 1;
+            }
+        }
+        if count > 0 {
+            self.health.apply(&self.db, batch)?;
+        }
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempdir::TempDir;
+    use std::str::FromStr;
+    use uuid::Uuid;
+    use chrono::{TimeZone, Utc};
+    use num_bigint::BigUint;
+    use num_traits::Zero;
+    use crate::access::validation::ValidationMode;
+    use crate::access::transactions::{AddressRef, AssetTotal, Filter, Granularity, MergePolicy, RetentionPolicy, Staleness, SubmitOutcome, TimeMatch, TransactionField, Transactions, TxFlag, WalletRef};
+    use crate::access::pagination::{Cursor, PageOrder, PageQuery};
+    use crate::storage::transaction_store::{IndexType, IndexedValue, RecentWindowed, TransactionsAccess};
+    use crate::storage::version::Migration;
+    use protobuf::Message;
+    use crate::proto::transactions::{BitcoinInput, BlockRef, BlockchainId, Transaction as proto_Transaction, Change as proto_Change, TransactionMeta as proto_TransactionMeta, Direction, Change_ChangeType, NonceRef, State, TokenStandard, TokenTransfer};
+    use crate::storage::indexing::{IndexEncoding, QueryRanges};
+    use std::ops::Bound;
+    use crate::storage::sled_access::SledStorage;
+    use crate::storage::ttl_policy::TtlPolicy;
+     use crate::storage::meta_proof;
+     use crate::storage::sequence;
+    use crate::access::allowance::{AllowanceKey, Allowances};
+    use crate::access::integrity::{CascadePolicy, TxReference};
+    use crate::proto::balance::Allowance;
+    use crate::errors::{InvalidValueError, StateError};
+    use crate::access::permissions::Store;
+    use crate::storage::rate_limit::RateLimit;
+
+    #[test]
+    fn get_index_at_ts() {
+        let idx = IndexType::Everything(1_647_313_850_992);
+        assert_eq!("idx:tx:1/D8352686149007", idx.get_index_key());
+    }
+
+    #[test]
+    fn get_index_at_wallet() {
+        let idx = IndexType::ByWallet(Uuid::from_str("72279ede-44c4-4951-925b-f51a7b9e929a").unwrap(), 1_647_313_850_992);
+        assert_eq!("idx:tx:2/72279ede-44c4-4951-925b-f51a7b9e929a/D8352686149007", idx.get_index_key());
+    }
+
+    #[test]
+    fn index_bounds_by_time() {
+        let filter = Filter {
+            after: Some(Utc.timestamp_millis_opt(1_647_313_850_000).unwrap()),
+            before: Some(Utc.timestamp_millis_opt(1_647_313_860_000).unwrap()),
+            time_match: TimeMatch::Ordered,
+            ..Filter::default()
+        };
+        assert_eq!(filter.get_index_bounds(), (
+            Bound::Included(IndexType::Everything(1_647_313_860_000).get_index_key()),
+            Bound::Included(IndexType::Everything(1_647_313_850_000).get_index_key()),
+        ));
+
+        // a transaction known in the window may be confirmed at any time
+        let filter = Filter {
+            time_match: TimeMatch::KnownOrConfirmed,
+            ..filter
+        };
+        assert_eq!(filter.get_index_bounds().1, Bound::Included(IndexType::Everything(0).get_index_key()));
+    }
+
+    #[test]
+    fn index_ranges_of_wallet_by_time() {
+        let wallet_id = Uuid::from_str("72279ede-44c4-4951-925b-f51a7b9e929a").unwrap();
+        let filter = Filter {
+            wallet: Some(WalletRef::WholeWallet(wallet_id)),
+            after: Some(Utc.timestamp_millis_opt(1_647_313_850_000).unwrap()),
+            before: Some(Utc.timestamp_millis_opt(1_647_313_860_000).unwrap()),
+            time_match: TimeMatch::Ordered,
+            ..Filter::default()
+        };
+        let key = |recent: bool, ts: u64, pos: u64, tx_id: &str| {
+            Bound::Included(IndexType::ByWalletAndConfirm(wallet_id, recent, ts, pos, tx_id.to_string()).get_index_key())
+        };
+        assert_eq!(filter.get_index_ranges(), vec![
+            (key(true, 1_647_313_860_000, u64::MAX, "0000000000000000"), key(true, 1_647_313_850_000, 0, "ffffffffffffffff")),
+            (key(false, 1_647_313_860_000, u64::MAX, "0000000000000000"), key(false, 1_647_313_850_000, 0, "ffffffffffffffff")),
+        ]);
+        assert_eq!(filter.get_index_bounds(), (
+            key(true, 1_647_313_860_000, u64::MAX, "0000000000000000"),
+            key(false, 1_647_313_850_000, 0, "ffffffffffffffff"),
+        ));
+    }
+
+    #[test]
+    fn query_in_time_window() {
+        let tmp_dir = TempDir::new("tx").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
+
+        let wallet_id = "72279ede-44c4-4951-925b-f51a7b9e929a";
+        let make_tx = |tx_id: &str, ts: u64| {
+            let mut tx = proto_Transaction::new();
+            tx.blockchain = BlockchainId::CHAIN_ETHEREUM;
+            tx.tx_id = tx_id.to_string();
+            tx.since_timestamp = ts;
+            let mut change = proto_Change::new();
+            change.wallet_id = wallet_id.to_string();
+            change.address = "0x2d8b7c3fc5bbd42e5b44b7aa1a3cef2f3f3b4ec6".to_string();
+            tx.changes.push(change);
+            tx
+        };
+        // known in the window, but confirmed after it
+        let mut confirmed = make_tx("0x9b1c4e2a7d3f5b8c0e6a1d4f7b2c5e8a0d3f6b9c1e4a7d0f3b6c9e2a5d8f1b4c", 1_647_313_858_000);
+        confirmed.state = State::CONFIRMED;
+        confirmed.confirm_timestamp = 1_647_313_866_000;
+        transactions.submit(vec![
+            make_tx("0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b", 1_647_313_850_992),
+            make_tx("0x5f88e4e1ab5d1a3ccb1e0ddfb6f9b5cc0ad43eb7ae1ef1b38ba6b6fcd4a6fb4c", 1_647_313_860_992),
+            make_tx("0x7a0a7e5c3f8f1ee4f4d2c1b5d6e4b3a29f8d7c6b5a4938271605f4e3d2c1b0a9", 1_647_313_870_992),
+            confirmed,
+        ]).unwrap();
+
+        let filter = Filter {
+            after: Some(Utc.timestamp_millis_opt(1_647_313_855_000).unwrap()),
+            before: Some(Utc.timestamp_millis_opt(1_647_313_865_000).unwrap()),
+            ..Filter::default()
+        };
+        let results = transactions.query(filter.clone(), PageQuery::default()).unwrap();
+        assert_eq!(results.values.len(), 2);
+
+        let filter = Filter {
+            time_match: TimeMatch::Ordered,
+            ..filter
+        };
+        let results = transactions.query(filter, PageQuery::default()).unwrap();
+        assert_eq!(results.values.len(), 1);
+        assert_eq!(results.values[0].since_timestamp, 1_647_313_860_992);
+
+        // both the pending and the confirmed sections of the wallet history, page by page
+        let filter = Filter {
+            wallet: Some(WalletRef::WholeWallet(Uuid::from_str(wallet_id).unwrap())),
+            after: Some(Utc.timestamp_millis_opt(1_647_313_855_000).unwrap()),
+            time_match: TimeMatch::Ordered,
+            ..Filter::default()
+        };
+        assert_eq!(transactions.get_count(filter.clone()).unwrap(), 3);
+        for order in [PageOrder::NewestFirst, PageOrder::OldestFirst] {
+            let mut page = PageQuery { limit: 1, order, ..PageQuery::default() };
+            let mut found = Vec::new();
+            loop {
+                let results = transactions.query(filter.clone(), page.clone()).unwrap();
+                found.extend(results.values.into_iter().map(|tx| tx.since_timestamp));
+                match results.cursor {
+                    Some(cursor) => page.cursor = Some(cursor),
+                    None => break
+                }
+            }
+            found.sort();
+            assert_eq!(found, vec![1_647_313_858_000, 1_647_313_860_992, 1_647_313_870_992]);
+        }
+    }
+
+    #[test]
+    fn build_indexes_basic() {
+        let mut tx = proto_Transaction::new();
+        tx.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx.since_timestamp = 1_647_313_850_992;
+        tx.state = State::SUBMITTED;
+        let mut change1 = proto_Change::new();
+        change1.wallet_id = "72279ede-44c4-4951-925b-f51a7b9e929a".to_string();
+        change1.entry_id = 0;
+        change1.address = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
+        tx.changes.push(change1);
+
+        let indexes: Vec<String> = RecentWindowed(&tx, u64::MAX).get_index_keys();
+        assert_eq!(indexes.len(), 4);
+        assert_eq!("idx:tx:1/D8352686149007", indexes.get(0).unwrap());
+        assert_eq!("idx:tx:2/72279ede-44c4-4951-925b-f51a7b9e929a/D8352686149007", indexes.get(1).unwrap());
+        assert_eq!("idx:tx:3/72279ede-44c4-4951-925b-f51a7b9e929a/T0/D8352686149007/D18446744073709551615/A00000000000000000000", indexes.get(2).unwrap());
+        assert_eq!("idx:tx:4/100/D8352686149007", indexes.get(3).unwrap());
+    }
+
+    #[test]
+    fn get_index_at_blockchain() {
+        let idx = IndexType::ByBlockchain(100, 1_647_313_850_992);
+        assert_eq!("idx:tx:4/100/D8352686149007", idx.get_index_key());
+    }
+
+    #[test]
+    fn get_index_at_nonce() {
+        let idx = IndexType::ByNonce(100, "0xA0b86991c6218b36c1d19d4a2e9eb0ce3606eB48".to_string(), 5, "0x2f761cbf".to_string());
+        let key = idx.get_index_key();
+        assert!(key.starts_with("idx:tx:5/100/0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48/"));
+        assert!(key.ends_with("/0x2f761cbf"));
+    }
+
+    #[test]
+    fn link_replaced_transactions() {
+        let tmp_dir = TempDir::new("tx").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
+
+        let mut nonce_ref = NonceRef::new();
+        nonce_ref.from = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
+        nonce_ref.nonce = 15;
+
+        let mut tx1 = proto_Transaction::new();
+        tx1.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx1.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        tx1.since_timestamp = 1_647_313_850_992;
+        tx1.state = State::SUBMITTED;
+        tx1.set_nonce_ref(nonce_ref.clone());
+
+        let mut tx2 = tx1.clone();
+        tx2.tx_id = "0x5f88e4e1ab5d1a3ccb1e0ddfb6f9b5cc0ad43eb7ae1ef1b38ba6b6fcd4a6fb4c".to_string();
+        tx2.since_timestamp = 1_647_313_860_992;
+
+        // same sender, but another nonce
+        let mut tx3 = tx1.clone();
+        tx3.tx_id = "0x9b11c453e5d0caf4fb6d192624360def7bd1e81b2f761cbf069962cf3a82ab0d".to_string();
+        tx3.since_timestamp = 1_647_313_870_992;
+        tx3.mut_nonce_ref().nonce = 16;
+
+        transactions.submit(vec![tx1.clone(), tx2.clone(), tx3.clone()]).expect("not saved");
+
+        let replacements = transactions.get_replacements(100, tx1.tx_id.clone()).expect("loaded");
+        assert_eq!(replacements.len(), 2);
+
+        let replaced = transactions.set_replaced(100, tx2.tx_id.clone()).expect("updated");
+        assert_eq!(replaced, vec![tx1.tx_id.clone()]);
+
+        let act = transactions.get_tx(100, tx1.tx_id.as_str()).unwrap();
+        assert_eq!(act.state, State::REPLACED);
+        assert_eq!(act.replaced_by, tx2.tx_id);
+        let act = transactions.get_tx(100, tx2.tx_id.as_str()).unwrap();
+        assert_eq!(act.state, State::SUBMITTED);
+        assert_eq!(act.replaced_by, "");
+
+        let results = transactions.query(
+            Filter { collapse_replaced: true, ..Filter::default() },
+            PageQuery::default(),
+        ).expect("queried");
+        assert_eq!(results.values.len(), 2);
+        assert!(results.values.iter().all(|tx| tx.tx_id != tx1.tx_id));
+
+        // and the other way around
+        let replaced = transactions.set_replaced(100, tx1.tx_id.clone()).expect("updated");
+        assert_eq!(replaced, vec![tx2.tx_id.clone()]);
+        let act = transactions.get_tx(100, tx1.tx_id.as_str()).unwrap();
+        assert_eq!(act.state, State::SUBMITTED);
+        assert_eq!(act.replaced_by, "");
+        let act = transactions.get_tx(100, tx2.tx_id.as_str()).unwrap();
+        assert_eq!(act.state, State::REPLACED);
+        assert_eq!(act.replaced_by, tx1.tx_id);
+    }
+
+    #[test]
+    fn replace_duplicate_bitcoin_tx() {
+        let tmp_dir = TempDir::new("tx").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
+
+        let mut tx1 = proto_Transaction::new();
+        tx1.blockchain = BlockchainId::CHAIN_BITCOIN;
+        tx1.tx_id = "2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        tx1.since_timestamp = 1_647_313_850_992;
+        tx1.state = State::SUBMITTED;
+        let mut change = proto_Change::new();
+        change.wallet_id = "72279ede-44c4-4951-925b-f51a7b9e929a".to_string();
+        change.entry_id = 1;
+        change.address = "bc1qywz558j2ja7fwmg32jupn02qvla5zm3dvggpqv".to_string();
+        change.amount = "100000".to_string();
+        change.direction = Direction::SEND;
+        tx1.changes.push(change);
+        let mut input = BitcoinInput::new();
+        input.txid = "ad43eb7ae1ef1b38ba6b6fcd4a6fb4c5f88e4e1ab5d1a3ccb1e0ddfb6f9b5cc0".to_string();
+        input.vout = 1;
+        tx1.inputs.push(input);
+
+        // same transaction under another id, ex. a malleated one
+        let mut tx2 = tx1.clone();
+        tx2.tx_id = "5f88e4e1ab5d1a3ccb1e0ddfb6f9b5cc0ad43eb7ae1ef1b38ba6b6fcd4a6fb4c".to_string();
+        tx2.since_timestamp = 1_647_313_860_992;
+
+        // another payment of the same amount, which spends a different output
+        let mut tx3 = tx1.clone();
+        tx3.tx_id = "9b11c453e5d0caf4fb6d192624360def7bd1e81b2f761cbf069962cf3a82ab0d".to_string();
+        tx3.since_timestamp = 1_647_313_870_992;
+        tx3.inputs[0].vout = 2;
+
+        // and one more, with unknown inputs
+        let mut tx4 = tx1.clone();
+        tx4.tx_id = "e1ab5d1a3ccb1e0ddfb6f9b5cc0ad43eb7ae1ef1b38ba6b6fcd4a6fb4c5f88e4".to_string();
+        tx4.since_timestamp = 1_647_313_880_992;
+        tx4.inputs.clear();
+
+        transactions.submit(vec![tx1.clone()]).expect("not saved");
+        transactions.submit(vec![tx3.clone()]).expect("not saved");
+        transactions.submit(vec![tx4.clone()]).expect("not saved");
+        transactions.submit(vec![tx2.clone()]).expect("not saved");
+
+        let act = transactions.get_tx(1, tx1.tx_id.as_str()).unwrap();
+        assert_eq!(act.state, State::REPLACED);
+        assert_eq!(act.replaced_by, tx2.tx_id);
+        let act = transactions.get_tx(1, tx2.tx_id.as_str()).unwrap();
+        assert_eq!(act.state, State::SUBMITTED);
+        assert_eq!(act.replaced_by, "");
+        let act = transactions.get_tx(1, tx3.tx_id.as_str()).unwrap();
+        assert_eq!(act.state, State::SUBMITTED);
+        let act = transactions.get_tx(1, tx4.tx_id.as_str()).unwrap();
+        assert_eq!(act.state, State::SUBMITTED);
+
+        let results = transactions.query(
+            Filter { collapse_replaced: true, ..Filter::default() },
+            PageQuery::default(),
+        ).expect("queried");
+        assert_eq!(results.values.len(), 3);
+        assert!(results.values.iter().all(|tx| tx.tx_id != tx1.tx_id));
+    }
+
+    #[test]
+    fn keep_identical_ethereum_tx() {
+        let tmp_dir = TempDir::new("tx").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
+
+        let mut tx1 = proto_Transaction::new();
+        tx1.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx1.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        tx1.since_timestamp = 1_647_313_850_992;
+        tx1.state = State::SUBMITTED;
+        let mut change = proto_Change::new();
+        change.wallet_id = "72279ede-44c4-4951-925b-f51a7b9e929a".to_string();
+        change.address = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
+        change.amount = "100000".to_string();
+        tx1.changes.push(change);
+
+        // on ethereum it's a legit separate transfer of the same amount
+        let mut tx2 = tx1.clone();
+        tx2.tx_id = "0x5f88e4e1ab5d1a3ccb1e0ddfb6f9b5cc0ad43eb7ae1ef1b38ba6b6fcd4a6fb4c".to_string();
+        tx2.since_timestamp = 1_647_313_860_992;
+
+        transactions.submit(vec![tx1.clone()]).expect("not saved");
+        transactions.submit(vec![tx2.clone()]).expect("not saved");
+
+        let act = transactions.get_tx(100, tx1.tx_id.as_str()).unwrap();
+        assert_eq!(act.state, State::SUBMITTED);
+        assert_eq!(act.replaced_by, "");
+    }
+
+    #[test]
+    fn create_and_find_tx() {
+        let tmp_dir = TempDir::new("create_and_find_tx").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
+
+        let mut tx = proto_Transaction::new();
+        tx.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        tx.since_timestamp = 1_647_313_850_992;
+        let mut change1 = proto_Change::new();
+        change1.wallet_id = "72279ede-44c4-4951-925b-f51a7b9e929a".to_string();
+        change1.entry_id = 0;
+        change1.address = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
+        tx.changes.push(change1);
+
+        transactions.submit(vec![tx.clone()]).expect("not saved");
+
+        let results = transactions.query(Filter::default(), PageQuery::default()).expect("queried");
+        assert_eq!(results.values.len(), 1);
+        assert_eq!(results.values.get(0).unwrap().clone(), tx);
+        assert!(results.cursor.is_none());
+    }
+
+    #[test]
+    fn create_and_find_tx_without_associated_wallet() {
+        let tmp_dir = TempDir::new("create_and_find_tx").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
+
+        let mut tx = proto_Transaction::new();
+        tx.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        tx.since_timestamp = 1_647_313_850_992;
+        let mut change1 = proto_Change::new();
+        change1.address = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
+        tx.changes.push(change1);
+
+        transactions.submit(vec![tx.clone()]).expect("not saved");
+
+        let results = transactions.query(Filter::default(), PageQuery::default()).expect("queried");
+        assert_eq!(results.values.len(), 1);
+        assert_eq!(results.values.get(0).unwrap().clone(), tx);
+        assert!(results.cursor.is_none());
+    }
+
+    #[test]
+    fn create_and_delete_tx() {
+        let tmp_dir = TempDir::new("create_and_find_tx").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
+
+        let mut tx = proto_Transaction::new();
+        tx.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        tx.since_timestamp = 1_647_313_850_992;
+        let mut change1 = proto_Change::new();
+        change1.wallet_id = "72279ede-44c4-4951-925b-f51a7b9e929a".to_string();
+        change1.entry_id = 0;
+        change1.address = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
+        tx.changes.push(change1);
+
+        transactions.submit(vec![tx.clone()]).expect("not saved");
+
+        let results = transactions.query(Filter::default(), PageQuery::default()).expect("queried");
+        assert_eq!(results.values.len(), 1);
+
+        transactions.forget(100, "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string()).expect("not removed");
+        let results = transactions.query(Filter::default(), PageQuery::default()).expect("queried");
+        assert_eq!(results.values.len(), 0);
+
+        let db_size = access.db.iter().keys()
+            .filter(|key| !sequence::is_changes_log(key.as_ref().unwrap()))
+            .count();
+        assert_eq!(db_size, 2); // only version and sequence fields
+    }
+
+    #[test]
+    fn create_and_update_tx_using_merge() {
+        // doesn't do full merge test, only checks that it applied
+
+        let tmp_dir = TempDir::new("create_and_find_tx").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
+
+        let mut tx = proto_Transaction::new();
+        tx.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        tx.since_timestamp = 1_647_313_850_992;
+        let mut change1 = proto_Change::new();
+        change1.wallet_id = "72279ede-44c4-4951-925b-f51a7b9e929a".to_string();
+        change1.entry_id = 0;
+        change1.address = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
+        change1.amount = "100".to_string();
+        change1.direction = Direction::SEND;
+        change1.change_type = Change_ChangeType::TRANSFER;
+        tx.changes.push(change1.clone());
+
+        transactions.submit(vec![tx.clone()]).expect("not saved");
+
+        let mut tx_update = tx.clone();
+        let mut change1_update = change1.clone();
+        change1_update.clear_wallet_id();
+        tx_update.clear_changes();
+        tx_update.changes.push(change1_update);
+
+        transactions.submit(vec![tx_update.clone()]).expect("not saved");
+
+        let tx_read = transactions.get_tx(100, "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b");
+
+        assert!(tx_read.is_some());
+        let tx_read = tx_read.unwrap();
+
+        assert_eq!(tx_read.changes.len(), 1);
+        let change_read = tx_read.changes.get(0).unwrap();
+
+        assert_eq!(change_read.wallet_id, "72279ede-44c4-4951-925b-f51a7b9e929a".to_string());
+    }
+
+    #[test]
+    fn loads_using_desc_order() {
+        let tmp_dir = TempDir::new("create_and_find_tx").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
+
+        let mut tx1 = proto_Transaction::new();
+        tx1.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx1.tx_id = "0xd9b11cef7bd1e81b453e5d0caf4fb6d1922f761cbf069962cf3a82ab0624360d".to_string();
+        tx1.since_timestamp = 1_647_313_000_000;
+        let mut change1 = proto_Change::new();
+        change1.wallet_id = "72279ede-44c4-4951-925b-f51a7b9e929a".to_string();
+        change1.entry_id = 0;
+        change1.address = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
+        tx1.changes.push(change1);
+
+        let mut tx2 = proto_Transaction::new();
+        tx2.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx2.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        tx2.since_timestamp = 1_647_315_000_000;
+        let mut change1 = proto_Change::new();
+        change1.wallet_id = "72279ede-44c4-4951-925b-f51a7b9e929a".to_string();
+        change1.entry_id = 0;
+        change1.address = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
+        tx2.changes.push(change1);
+
+        transactions.submit(vec![tx1.clone(), tx2.clone()]).expect("not saved");
+
+        let results = transactions.query(Filter::default(), PageQuery::default()).expect("query data");
+        assert_eq!(results.values.len(), 2);
+        assert_eq!(results.values.get(0).unwrap().clone(), tx2);
+        assert_eq!(results.values.get(1).unwrap().clone(), tx1);
+        assert!(results.cursor.is_none());
+    }
+
+    #[test]
+    fn set_state_and_reindex() {
+        let tmp_dir = TempDir::new("tx").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
+
+        let mut tx = proto_Transaction::new();
+        tx.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        tx.since_timestamp = 1_647_313_850_992;
+        tx.sync_timestamp = 1_647_313_850_992;
+        tx.state = State::SUBMITTED;
+        let mut change1 = proto_Change::new();
+        change1.wallet_id = "72279ede-44c4-4951-925b-f51a7b9e929a".to_string();
+        change1.entry_id = 0;
+        change1.address = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
+        tx.changes.push(change1);
+
+        transactions.submit(vec![tx.clone()]).expect("not saved");
+
+        let updated = transactions.set_state(100, tx.tx_id.clone(), State::DROPPED, 1_647_313_900_000).expect("updated");
+        assert!(updated.is_some());
+        assert_eq!(updated.unwrap().state, State::DROPPED);
+
+        let act = transactions.get_tx(100, tx.tx_id.as_str()).unwrap();
+        assert_eq!(act.state, State::DROPPED);
+        assert_eq!(act.sync_timestamp, 1_647_313_900_000);
+        assert_eq!(act.changes.len(), 1);
+
+        // the old `recent` index is replaced
+        assert_eq!(transactions.db.scan_prefix("idx:tx:3/").count(), 1);
+        let results = transactions.query(
+            Filter {
+                wallet: Some(WalletRef::WholeWallet(Uuid::from_str("72279ede-44c4-4951-925b-f51a7b9e929a").unwrap())),
+                ..Filter::default()
+            },
+            PageQuery::default(),
+        ).expect("queried");
+        assert_eq!(results.values.len(), 1);
+        assert_eq!(results.values[0].state, State::DROPPED);
+
+        // an older update is ignored
+        let updated = transactions.set_state(100, tx.tx_id.clone(), State::CONFIRMED, 1_647_313_800_000).expect("updated");
+        assert_eq!(updated.unwrap().state, State::DROPPED);
+        let act = transactions.get_tx(100, tx.tx_id.as_str()).unwrap();
+        assert_eq!(act.state, State::DROPPED);
+
+        let unknown = transactions.set_state(100, "0x5f88e4e1ab5d1a3ccb1e0ddfb6f9b5cc0ad43eb7ae1ef1b38ba6b6fcd4a6fb4c".to_string(), State::DROPPED, 1_647_313_900_000);
+        assert_eq!(unknown, Ok(None));
+    }
+
+    #[test]
+    fn query_with_pagination() {
+        let tmp_dir = TempDir::new("create_and_find_tx").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
+
+        let mut insert = Vec::new();
+        for i in 0..10 {
+            let mut tx = proto_Transaction::new();
+            tx.blockchain = BlockchainId::CHAIN_ETHEREUM;
+            tx.tx_id = format!("0xd9b11cef7bd1e81b453e5d0caf4fb6d1922f761cbf069962cf3a82ab062400{}", i);
+            tx.since_timestamp = 1_647_313_000_100 - i; // decrease tx because it returned in desc order
+            let mut change1 = proto_Change::new();
+            change1.wallet_id = "72279ede-44c4-4951-925b-f51a7b9e929a".to_string();
+            change1.entry_id = 0;
+            change1.address = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
+            tx.changes.push(change1);
+            insert.push(tx);
+        }
+
+        transactions.submit(insert).expect("not saved");
+
+        let results_1 = transactions.query(
+            Filter::default(),
+            PageQuery { limit: 5, ..PageQuery::default() }
+        ).expect("query data");
+
+        assert_eq!(results_1.values.len(), 5);
+        assert_eq!(results_1.values.get(0).unwrap().tx_id, "0xd9b11cef7bd1e81b453e5d0caf4fb6d1922f761cbf069962cf3a82ab0624000");
+        assert_eq!(results_1.values.get(4).unwrap().tx_id, "0xd9b11cef7bd1e81b453e5d0caf4fb6d1922f761cbf069962cf3a82ab0624004");
+        assert!(results_1.cursor.is_some());
+
+        let results_2 = transactions.query(
+            Filter::default(),
+            PageQuery { limit: 5, cursor: results_1.cursor, ..PageQuery::default() }
+        ).expect("query data");
+
+        assert_eq!(results_2.values.len(), 5);
+        assert_eq!(results_2.values.get(0).unwrap().tx_id, "0xd9b11cef7bd1e81b453e5d0caf4fb6d1922f761cbf069962cf3a82ab0624005");
+        assert_eq!(results_2.values.get(4).unwrap().tx_id, "0xd9b11cef7bd1e81b453e5d0caf4fb6d1922f761cbf069962cf3a82ab0624009");
+        // a cursor may still presents since the storage doesnt know is there are any other items
+
+        let results_3 = transactions.query(
+            Filter::default(),
+            PageQuery { limit: 5, cursor: results_2.cursor, ..PageQuery::default() }
+        ).expect("query data");
+        assert_eq!(results_3.values.len(), 0);
+        assert!(results_3.cursor.is_none());
+    }
+
+    #[test]
+    fn reject_foreign_cursor() {
+        let tmp_dir = TempDir::new("create_and_find_tx").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
+
+        let mut insert = Vec::new();
+        for i in 0..10 {
+            let mut tx = proto_Transaction::new();
+            tx.blockchain = BlockchainId::CHAIN_ETHEREUM;
+            tx.tx_id = format!("0xd9b11cef7bd1e81b453e5d0caf4fb6d1922f761cbf069962cf3a82ab062400{}", i);
+            tx.since_timestamp = 1_647_313_000_000 + i;
+            let mut change1 = proto_Change::new();
+            change1.wallet_id = "72279ede-44c4-4951-925b-f51a7b9e929a".to_string();
+            tx.changes.push(change1);
+            insert.push(tx);
+        }
+        transactions.submit(insert).expect("not saved");
+
+        let wallet_filter = |id: &str| Filter { wallet: Some(WalletRef::WholeWallet(Uuid::from_str(id).unwrap())), ..Filter::default() };
+        let results = transactions.query(
+            wallet_filter("72279ede-44c4-4951-925b-f51a7b9e929a"),
+            PageQuery { limit: 5, ..PageQuery::default() }
+        ).expect("query data");
+        let cursor = results.cursor.expect("has cursor");
+        let invalid = Err(StateError::InvalidValue(InvalidValueError::Name("cursor".to_string())));
+
+        // a different wallet
+        let act = transactions.query(
+            wallet_filter("f0ad8a03-9d57-4d9c-8c29-8b5a5ea4a4a5"),
+            PageQuery { limit: 5, cursor: Some(cursor.clone()), ..PageQuery::default() }
+        ).map(|r| r.values.len());
+        assert_eq!(act, invalid);
+
+        // a different order
+        let act = transactions.query(
+            wallet_filter("72279ede-44c4-4951-925b-f51a7b9e929a"),
+            PageQuery { limit: 5, cursor: Some(cursor.clone()), order: PageOrder::OldestFirst }
+        ).map(|r| r.values.len());
+        assert_eq!(act, invalid);
+
+        // a raw index key
+        let act = transactions.query(
+            Filter::default(),
+            PageQuery { limit: 5, cursor: Some(Cursor { offset: "idx:tx:1/D0000000000000".to_string() }), ..PageQuery::default() }
+        ).map(|r| r.values.len());
+        assert_eq!(act, invalid);
+
+        let act = transactions.query(
+            wallet_filter("72279ede-44c4-4951-925b-f51a7b9e929a"),
+            PageQuery { limit: 5, cursor: Some(cursor), ..PageQuery::default() }
+        ).map(|r| r.values.len());
+        assert_eq!(act, Ok(5));
+    }
+
+    #[test]
+    fn query_oldest_first() {
+        let tmp_dir = TempDir::new("create_and_find_tx").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
+
+        let mut insert = Vec::new();
+        for i in 0..10 {
+            let mut tx = proto_Transaction::new();
+            tx.blockchain = BlockchainId::CHAIN_ETHEREUM;
+            tx.tx_id = format!("0xd9b11cef7bd1e81b453e5d0caf4fb6d1922f761cbf069962cf3a82ab062400{}", i);
+            tx.since_timestamp = 1_647_313_000_000 + i;
+            let mut change1 = proto_Change::new();
+            change1.wallet_id = "72279ede-44c4-4951-925b-f51a7b9e929a".to_string();
+            change1.address = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
+            tx.changes.push(change1);
+            insert.push(tx);
+        }
+        transactions.submit(insert).expect("not saved");
+
+        for filter in [Filter::default(), Filter { wallet: Some(WalletRef::WholeWallet(Uuid::from_str("72279ede-44c4-4951-925b-f51a7b9e929a").unwrap())), ..Filter::default() }] {
+            let results_1 = transactions.query(
+                filter.clone(),
+                PageQuery { limit: 5, order: PageOrder::OldestFirst, ..PageQuery::default() }
+            ).expect("query data");
+            assert_eq!(results_1.values.len(), 5);
+            assert_eq!(results_1.values.first().unwrap().tx_id, "0xd9b11cef7bd1e81b453e5d0caf4fb6d1922f761cbf069962cf3a82ab0624000");
+            assert_eq!(results_1.values.get(4).unwrap().tx_id, "0xd9b11cef7bd1e81b453e5d0caf4fb6d1922f761cbf069962cf3a82ab0624004");
+            assert!(results_1.cursor.is_some());
+
+            let results_2 = transactions.query(
+                filter.clone(),
+                PageQuery { limit: 5, cursor: results_1.cursor, order: PageOrder::OldestFirst }
+            ).expect("query data");
+            assert_eq!(results_2.values.len(), 5);
+            assert_eq!(results_2.values.first().unwrap().tx_id, "0xd9b11cef7bd1e81b453e5d0caf4fb6d1922f761cbf069962cf3a82ab0624005");
+            assert_eq!(results_2.values.get(4).unwrap().tx_id, "0xd9b11cef7bd1e81b453e5d0caf4fb6d1922f761cbf069962cf3a82ab0624009");
+        }
+    }
+
+    #[test]
+    fn query_by_blockchain() {
+        let tmp_dir = TempDir::new("create_and_find_tx").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
+
+        let mut tx1 = proto_Transaction::new();
+        tx1.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx1.tx_id = "0xd9b11cef7bd1e81b453e5d0caf4fb6d1922f761cbf069962cf3a82ab0624360d".to_string();
+        tx1.since_timestamp = 1_647_313_000_000;
+        let mut change1 = proto_Change::new();
+        change1.wallet_id = "72279ede-44c4-4951-925b-f51a7b9e929a".to_string();
+        change1.entry_id = 0;
+        change1.address = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
+        tx1.changes.push(change1);
+
+        let mut tx2 = proto_Transaction::new();
+        tx2.blockchain = BlockchainId::CHAIN_BITCOIN;
+        tx2.tx_id = "2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        tx2.since_timestamp = 1_647_315_000_000;
+        let mut change1 = proto_Change::new();
+        change1.wallet_id = "72279ede-44c4-4951-925b-f51a7b9e929a".to_string();
+        change1.entry_id = 1;
+        change1.address = "bc1qywz558j2ja7fwmg32jupn02qvla5zm3dvggpqv".to_string();
+        tx2.changes.push(change1);
+
+        transactions.submit(vec![tx1.clone(), tx2.clone()]).expect("not saved");
+
+        let results = transactions.query(Filter {
+            blockchains: Some(vec![1]),
+            ..Filter::default()
+        }, PageQuery::default()).expect("query data");
+        assert_eq!(results.values.len(), 1);
+        assert_eq!(results.values[0], tx2);
+
+        let results = transactions.query(Filter {
+            blockchains: Some(vec![100]),
+            ..Filter::default()
+        }, PageQuery::default()).expect("query data");
+        assert_eq!(results.values.len(), 1);
+        assert_eq!(results.values[0], tx1);
+
+        let count = transactions.get_count(Filter {
+            blockchains: Some(vec![1, 100]),
+            ..Filter::default()
+        }).expect("query count");
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn deny_tx_rejected_by_validator() {
+        let tmp_dir = TempDir::new("create_and_find_tx").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        access.validators().unwrap().add_transaction_validator(Box::new(|tx| {
+            if tx.changes.iter().any(|c| c.address == "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48") {
+                Err(InvalidValueError::Name("address".to_string()))
+            } else {
+                Ok(())
+            }
+        }));
+        let transactions = access.get_transactions();
+
+        let mut tx = proto_Transaction::new();
+        tx.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        tx.since_timestamp = 1_647_313_850_992;
+        let mut change1 = proto_Change::new();
+        change1.wallet_id = "72279ede-44c4-4951-925b-f51a7b9e929a".to_string();
+        change1.entry_id = 0;
+        change1.address = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
+        tx.changes.push(change1);
+
+        let result = transactions.submit(vec![tx.clone()]);
+        assert_eq!(result, Err(StateError::InvalidValue(InvalidValueError::Name("address".to_string()))));
+
+        let results = transactions.query(Filter::default(), PageQuery::default()).expect("queried");
+        assert!(results.values.is_empty());
+    }
+
+    #[test]
+    fn submit_with_validation_mode() {
+        let tmp_dir = TempDir::new("tx").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        access.validators().unwrap().add_transaction_validator(Box::new(|tx| {
+            if tx.changes.iter().any(|c| c.address == "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48") {
+                Err(InvalidValueError::Name("address".to_string()))
+            } else {
+                Ok(())
+            }
+        }));
+        let transactions = access.get_transactions();
+
+        let mut tx_good = proto_Transaction::new();
+        tx_good.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx_good.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        tx_good.since_timestamp = 1_647_313_850_992;
+
+        let mut tx_no_time = tx_good.clone();
+        tx_no_time.tx_id = "0x5f88e4e1ab5d1a3ccb1e0ddfb6f9b5cc0ad43eb7ae1ef1b38ba6b6fcd4a6fb4c".to_string();
+        tx_no_time.since_timestamp = 0;
+        tx_no_time.confirm_timestamp = 1_647_313_860_992;
+
+        let mut tx_denied = tx_good.clone();
+        tx_denied.tx_id = "0x7a0a7e5c3f8f1ee4f4d2c1b5d6e4b3a29f8d7c6b5a4938271605f4e3d2c1b0a9".to_string();
+        let mut change = proto_Change::new();
+        change.address = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
+        tx_denied.changes.push(change);
+
+        let mut tx_no_id = tx_good.clone();
+        tx_no_id.tx_id = "".to_string();
+
+        let all = vec![tx_good.clone(), tx_no_time.clone(), tx_denied.clone(), tx_no_id.clone()];
+
+        let result = transactions.submit_checked(all.clone(), ValidationMode::Strict);
+        assert_eq!(result, Err(StateError::InvalidValue(InvalidValueError::Name("address".to_string()))));
+        assert!(transactions.query(Filter::default(), PageQuery::default()).unwrap().values.is_empty());
+
+        let result = transactions.submit_checked(all.clone(), ValidationMode::Lenient).unwrap();
+        assert_eq!(result.results, vec![Some(SubmitOutcome::Created), Some(SubmitOutcome::Created), None, None]);
+        assert_eq!(result.warnings.len(), 3);
+        assert_eq!(result.warnings[0].index, 1);
+        assert!(!result.warnings[0].skipped);
+        assert_eq!(result.warnings[0].message, "Missing timestamp");
+        assert_eq!(result.warnings[1].index, 2);
+        assert!(result.warnings[1].skipped);
+        assert_eq!(result.warnings[2].index, 3);
+        assert!(result.warnings[2].skipped);
+
+        let stored = transactions.get_tx(100, tx_no_time.tx_id.as_str()).unwrap();
+        assert_eq!(stored.since_timestamp, 1_647_313_860_992);
+        assert!(transactions.get_tx(100, tx_denied.tx_id.as_str()).is_none());
+
+        let result = transactions.submit_checked(all, ValidationMode::Off).unwrap();
+        assert_eq!(result.results[2], Some(SubmitOutcome::Created));
+        assert_eq!(result.results[3], None);
+        assert_eq!(result.warnings.len(), 1);
+        assert!(transactions.get_tx(100, tx_denied.tx_id.as_str()).is_some());
+    }
+
+    #[test]
+    fn stats_per_day() {
+        let tmp_dir = TempDir::new("tx").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
+
+        let wallet_1 = "72279ede-44c4-4951-925b-f51a7b9e929a";
+        let wallet_2 = "f0ad8a03-9d57-4d9c-8c29-8b5a5ea4a4a5";
+        let make_tx = |tx_id: &str, ts: u64, changes: Vec<(&str, &str, &str, Direction)>| {
+            let mut tx = proto_Transaction::new();
+            tx.blockchain = BlockchainId::CHAIN_ETHEREUM;
+            tx.tx_id = tx_id.to_string();
+            tx.since_timestamp = ts;
+            for (wallet_id, asset, amount, direction) in changes {
+                let mut change = proto_Change::new();
+                change.wallet_id = wallet_id.to_string();
+                change.asset = asset.to_string();
+                change.amount = amount.to_string();
+                change.direction = direction;
+                tx.changes.push(change);
+            }
+            tx
+        };
+
+        transactions.submit(vec![
+            // 2022-03-15
+            make_tx("0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b", 1_647_313_850_992, vec![
+                (wallet_1, "ETH", "1000", Direction::SEND),
+                (wallet_1, "ETH", "21", Direction::SEND),
+                (wallet_2, "ETH", "1000", Direction::RECEIVE),
+            ]),
+            make_tx("0x5f88e4e1ab5d1a3ccb1e0ddfb6f9b5cc0ad43eb7ae1ef1b38ba6b6fcd4a6fb4c", 1_647_320_000_000, vec![
+                (wallet_1, "USDT", "500", Direction::RECEIVE),
+            ]),
+            // 2022-03-16
+            make_tx("0x9b11c453e5d0caf4fb6d192624360def7bd1e81b2f761cbf069962cf3a82ab0d", 1_647_400_000_000, vec![
+                (wallet_1, "ETH", "300", Direction::RECEIVE),
+            ]),
+        ]).expect("not saved");
+
+        let stats = transactions.stats(
+            Filter {
+                wallet: Some(WalletRef::WholeWallet(Uuid::from_str(wallet_1).unwrap())),
+                ..Filter::default()
+            },
+            Granularity::Day,
+        ).expect("calculated");
+
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].start, Utc.with_ymd_and_hms(2022, 3, 15, 0, 0, 0).unwrap());
+        assert_eq!(stats[0].count, 2);
+        assert_eq!(stats[0].assets, vec![
+            AssetTotal { asset: "ETH".to_string(), sent: BigUint::from(1021u32), received: BigUint::zero() },
+            AssetTotal { asset: "USDT".to_string(), sent: BigUint::zero(), received: BigUint::from(500u32) },
+        ]);
+        assert_eq!(stats[1].start, Utc.with_ymd_and_hms(2022, 3, 16, 0, 0, 0).unwrap());
+        assert_eq!(stats[1].count, 1);
+        assert_eq!(stats[1].assets, vec![
+            AssetTotal { asset: "ETH".to_string(), sent: BigUint::zero(), received: BigUint::from(300u32) },
+        ]);
+
+        let stats = transactions.stats(Filter::default(), Granularity::Month).expect("calculated");
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].start, Utc.with_ymd_and_hms(2022, 3, 1, 0, 0, 0).unwrap());
+        assert_eq!(stats[0].count, 3);
+        assert_eq!(stats[0].assets[0], AssetTotal { asset: "ETH".to_string(), sent: BigUint::from(1021u32), received: BigUint::from(1300u32) });
+    }
+
+    #[test]
+    fn count_items() {
+        let tmp_dir = TempDir::new("create_and_find_tx").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
+
+        let mut tx1 = proto_Transaction::new();
+        tx1.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx1.tx_id = "0xd9b11cef7bd1e81b453e5d0caf4fb6d1922f761cbf069962cf3a82ab0624360d".to_string();
+        tx1.since_timestamp = 1_647_313_000_000;
+        let mut change1 = proto_Change::new();
+        change1.wallet_id = "72279ede-44c4-4951-925b-f51a7b9e929a".to_string();
+        change1.entry_id = 0;
+        change1.address = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
+        tx1.changes.push(change1);
+
+        let mut tx2 = proto_Transaction::new();
+        tx2.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx2.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        tx2.since_timestamp = 1_647_315_000_000;
+        let mut change1 = proto_Change::new();
+        change1.wallet_id = "72279ede-44c4-4951-925b-f51a7b9e929a".to_string();
+        change1.entry_id = 0;
+        change1.address = "0x6218b36c1d19d4a2e9eb0ce3606eb48a0b86991c".to_string();
+        tx2.changes.push(change1);
+
+        transactions.submit(vec![tx1.clone(), tx2.clone()]).expect("not saved");
+
+        let count = transactions.get_count(Filter::default()).expect("query count");
+        assert_eq!(count, 2);
+
+        let count = transactions.get_count(Filter {
+            addresses: Some(vec![AddressRef::SingleAddress("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string())]),
+            ..Filter::default()
+        }).expect("query count");
+        assert_eq!(count, 1);
+
+        let count = transactions.get_count(Filter {
+            addresses: Some(vec![AddressRef::SingleAddress("0x6218b36c1d19d4a2e9eb0ce3606eb48a0b86991c".to_string())]),
+            ..Filter::default()
+        }).expect("query count");
+        assert_eq!(count, 1);
+
+        let count = transactions.get_count(Filter {
+            wallet: Some(WalletRef::WholeWallet(Uuid::from_str("72279ede-44c4-4951-925b-f51a7b9e929a").unwrap())),
+            ..Filter::default()
+        }).expect("query count");
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn count_using_index_only() {
+        let tmp_dir = TempDir::new("tx").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
+
+        let mut tx1 = proto_Transaction::new();
+        tx1.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx1.tx_id = "0xd9b11cef7bd1e81b453e5d0caf4fb6d1922f761cbf069962cf3a82ab0624360d".to_string();
+        tx1.since_timestamp = 1_647_313_000_000;
+        // two changes of the same wallet
+        for address in ["0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48", "0x6218b36c1d19d4a2e9eb0ce3606eb48a0b86991c"] {
+            let mut change = proto_Change::new();
+            change.wallet_id = "72279ede-44c4-4951-925b-f51a7b9e929a".to_string();
+            change.address = address.to_string();
+            tx1.changes.push(change);
+        }
+
+        let mut tx2 = proto_Transaction::new();
+        tx2.blockchain = BlockchainId::CHAIN_ETHEREUM_CLASSIC;
+        tx2.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        tx2.since_timestamp = 1_647_315_000_000;
+
+        transactions.submit(vec![tx1.clone(), tx2.clone()]).expect("not saved");
+
+        let filter = Filter {
+            wallet: Some(WalletRef::WholeWallet(Uuid::from_str("72279ede-44c4-4951-925b-f51a7b9e929a").unwrap())),
+            ..Filter::default()
+        };
+        assert!(super::TransactionsAccess::is_index_only(&filter));
+        assert_eq!(transactions.get_count(filter).unwrap(), 1);
+
+        let filter = Filter {
+            blockchains: Some(vec![101]),
+            ..Filter::default()
+        };
+        assert!(super::TransactionsAccess::is_index_only(&filter));
+        assert_eq!(transactions.get_count(filter).unwrap(), 1);
+
+        assert!(super::TransactionsAccess::is_index_only(&Filter::default()));
+        assert_eq!(transactions.get_count(Filter::default()).unwrap(), 2);
+
+        let filter = Filter {
+            blockchains: Some(vec![100, 101]),
+            ..Filter::default()
+        };
+        assert!(!super::TransactionsAccess::is_index_only(&filter));
+        assert_eq!(transactions.get_count(filter).unwrap(), 2);
+
+        let filter = Filter {
+            wallet: Some(WalletRef::SelectedEntry(Uuid::from_str("72279ede-44c4-4951-925b-f51a7b9e929a").unwrap(), 1)),
+            ..Filter::default()
+        };
+        assert!(!super::TransactionsAccess::is_index_only(&filter));
+        assert_eq!(transactions.get_count(filter).unwrap(), 0);
+    }
+
+    #[test]
+    fn query_counts_corrupted_records() {
+        let tmp_dir = TempDir::new("tx").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
+
+        let mut tx1 = proto_Transaction::new();
+        tx1.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx1.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        tx1.since_timestamp = 1_647_313_850_992;
+        let mut tx2 = proto_Transaction::new();
+        tx2.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx2.tx_id = "0x5f88e4e1ab5d1a3ccb1e0ddfb6f9b5cc0ad43eb7ae1ef1b38ba6b6fcd4a6fb4c".to_string();
+        tx2.since_timestamp = 1_647_313_860_992;
+
+        transactions.submit(vec![tx1.clone(), tx2.clone()]).expect("not saved");
+
+        // break the stored data for the second tx
+        transactions.db.insert(
+            super::TransactionsAccess::get_key(100, tx2.tx_id.clone()).as_bytes(),
+            vec![0xff, 0xff, 0xff, 0xff],
+        ).unwrap();
+
+        let results = transactions.query(Filter::default(), PageQuery::default()).expect("queried");
+        assert_eq!(results.values.len(), 1);
+        assert_eq!(results.values[0].tx_id, tx1.tx_id);
+        assert_eq!(results.skipped, 1);
+    }
+
+    #[test]
+    fn query_only_selected_fields() {
+        let tmp_dir = TempDir::new("create_and_find_tx").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
+
+        let mut tx = proto_Transaction::new();
+        tx.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        tx.since_timestamp = 1_647_313_850_992;
+        tx.state = State::CONFIRMED;
+        let mut change1 = proto_Change::new();
+        change1.wallet_id = "72279ede-44c4-4951-925b-f51a7b9e929a".to_string();
+        change1.entry_id = 0;
+        change1.address = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
+        tx.changes.push(change1);
+
+        transactions.submit(vec![tx.clone()]).expect("not saved");
+
+        let results = transactions.query_projected(
+            Filter::default(),
+            PageQuery::default(),
+            vec![TransactionField::TxId, TransactionField::State],
+        ).expect("queried");
+        assert_eq!(results.values.len(), 1);
+        assert_eq!(results.values[0].tx_id, tx.tx_id);
+        assert_eq!(results.values[0].state, State::CONFIRMED);
+        assert_eq!(results.values[0].since_timestamp, 0);
+        assert!(results.values[0].changes.is_empty());
+
+        // changes are loaded when the filter needs them
+        let results = transactions.query_projected(
+            Filter {
+                addresses: Some(vec![AddressRef::SingleAddress("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string())]),
+                ..Filter::default()
+            },
+            PageQuery::default(),
+            vec![TransactionField::TxId],
+        ).expect("queried");
+        assert_eq!(results.values.len(), 1);
+        assert_eq!(results.values[0].changes.len(), 1);
+    }
+
+    #[test]
+    fn get_multiple_txes() {
+        let tmp_dir = TempDir::new("create_and_find_tx").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
+
+        let mut tx1 = proto_Transaction::new();
+        tx1.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx1.tx_id = "0xd9b11cef7bd1e81b453e5d0caf4fb6d1922f761cbf069962cf3a82ab0624360d".to_string();
+        tx1.since_timestamp = 1_647_313_000_000;
+
+        let mut tx2 = proto_Transaction::new();
+        tx2.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx2.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        tx2.since_timestamp = 1_647_315_000_000;
+
+        transactions.submit(vec![tx1.clone(), tx2.clone()]).expect("not saved");
+
+        let act = transactions.get_txs(vec![
+            (100, tx2.tx_id.clone()),
+            (1, tx2.tx_id.clone()),
+            (100, tx1.tx_id.clone()),
+        ]);
+        assert_eq!(act, vec![Some(tx2), None, Some(tx1)]);
+    }
+
+    #[test]
+    fn no_cursor_by_default() {
+        let tmp_dir = TempDir::new("create_and_find_tx").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
+
+        let act = transactions.get_cursor("0x6218b36c1d19d4a2e9eb0ce3606eb48a0b86991c");
+        assert!(act.is_ok());
+        assert!(act.unwrap().is_none());
+    }
+
+    #[test]
+    fn save_and_provide_cursor() {
+        let tmp_dir = TempDir::new("create_and_find_tx").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
+
+        let saved = transactions.set_cursor("0x6218b36c1d19d4a2e9eb0ce3606eb48a0b86991c", "MTA5MjQ5MS81ODE=");
+        assert!(saved.is_ok());
+
+        let act = transactions.get_cursor("0x6218b36c1d19d4a2e9eb0ce3606eb48a0b86991c");
+        assert!(act.is_ok());
+        let act = act.unwrap();
+        assert!(act.is_some());
+        assert_eq!(act.unwrap().value, "MTA5MjQ5MS81ODE=".to_string());
+    }
+
+    #[test]
+    fn keep_unchanged_cursor() {
+        let tmp_dir = TempDir::new("create_and_find_tx").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
+
+        transactions.set_cursor("0x6218b36c1d19d4a2e9eb0ce3606eb48a0b86991c", "MTA5MjQ5MS81ODE=").unwrap();
+        let first = transactions.get_cursor("0x6218b36c1d19d4a2e9eb0ce3606eb48a0b86991c").unwrap().unwrap();
+
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        transactions.set_cursor("0x6218b36c1d19d4a2e9eb0ce3606eb48a0b86991c", "MTA5MjQ5MS81ODE=").unwrap();
+        let act = transactions.get_cursor("0x6218b36c1d19d4a2e9eb0ce3606eb48a0b86991c").unwrap().unwrap();
+        assert_eq!(act.since, first.since);
+
+        transactions.set_cursor("0x6218b36c1d19d4a2e9eb0ce3606eb48a0b86991c", "MTA5MjQ5MS81OTA=").unwrap();
+        let act = transactions.get_cursor("0x6218b36c1d19d4a2e9eb0ce3606eb48a0b86991c").unwrap().unwrap();
+        assert_eq!(act.value, "MTA5MjQ5MS81OTA=".to_string());
+        assert!(act.since > first.since);
+    }
+
+    #[test]
+    fn list_all_cursors() {
+        let tmp_dir = TempDir::new("create_and_find_tx").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
+
+        assert!(transactions.list_cursors().unwrap().is_empty());
+
+        transactions.set_cursors(vec![
+            ("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48", "MTA5MjQ5MS81OTA="),
+            ("0x6218b36c1d19d4a2e9eb0ce3606eb48a0b86991c", "MTA5MjQ5MS81ODE="),
+        ]).unwrap();
+
+        let act = transactions.list_cursors().unwrap();
+        assert_eq!(act.len(), 2);
+        assert_eq!(act[0].0, "0x6218b36c1d19d4a2e9eb0ce3606eb48a0b86991c");
+        assert_eq!(act[0].1.value, "MTA5MjQ5MS81ODE=");
+        assert_eq!(act[1].0, "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48");
+        assert_eq!(act[1].1.value, "MTA5MjQ5MS81OTA=");
+        assert_eq!(act[1].1.since, transactions.get_cursor("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").unwrap().unwrap().since);
+    }
+
+    #[test]
+    fn save_multiple_cursors() {
+        let tmp_dir = TempDir::new("create_and_find_tx").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
+
+        transactions.set_cursors(vec![
+            ("0x6218b36c1d19d4a2e9eb0ce3606eb48a0b86991c", "MTA5MjQ5MS81ODE="),
+            ("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48", "MTA5MjQ5MS81OTA="),
+        ]).unwrap();
+
+        let act = transactions.get_cursor("0x6218b36c1d19d4a2e9eb0ce3606eb48a0b86991c").unwrap().unwrap();
+        assert_eq!(act.value, "MTA5MjQ5MS81ODE=".to_string());
+        let act = transactions.get_cursor("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").unwrap().unwrap();
+        assert_eq!(act.value, "MTA5MjQ5MS81OTA=".to_string());
+    }
+
+    #[test]
+    fn get_multiple_cursors() {
+        let tmp_dir = TempDir::new("tx").unwrap();
+        let policy = TtlPolicy {
+            cursor_stale_after: std::time::Duration::ZERO,
+            ..TtlPolicy::default()
+        };
+        let access = SledStorage::open_with_policy(tmp_dir.path().to_path_buf(), policy).unwrap();
+        let transactions = access.get_transactions();
+        transactions.set_cursor("0x6218b36c1d19d4a2e9eb0ce3606eb48a0b86991c", "MTA5MjQ5MS81ODE=").unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(5));
+
+        let act = transactions.get_cursors(vec![
+            "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48",
+            "0x6218b36c1d19d4a2e9eb0ce3606eb48a0b86991c",
+        ]).unwrap();
+        assert_eq!(act.len(), 2);
+        assert_eq!(act[0].0, "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48");
+        assert!(act[0].1.is_none());
+        assert_eq!(act[0].2, Staleness::Missing);
+        assert_eq!(act[1].1.as_ref().unwrap().value, "MTA5MjQ5MS81ODE=");
+        assert_eq!(act[1].2, Staleness::Stale);
+
+        // with the default policy it's fresh
+        let tmp_dir = TempDir::new("tx").unwrap();
+        let fresh = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap().get_transactions();
+        fresh.set_cursor("0x6218b36c1d19d4a2e9eb0ce3606eb48a0b86991c", "MTA5MjQ5MS81ODE=").unwrap();
+        let act = fresh.get_cursors(vec!["0x6218b36c1d19d4a2e9eb0ce3606eb48a0b86991c"]).unwrap();
+        assert_eq!(act[0].2, Staleness::Fresh);
+    }
+
+    #[test]
+    fn submit_with_remote_policy() {
+        let tmp_dir = TempDir::new("tx").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
+
+        let mut tx = proto_Transaction::new();
+        tx.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        tx.since_timestamp = 1_647_313_850_992;
+        let mut change = proto_Change::new();
+        change.wallet_id = "72279ede-44c4-4951-925b-f51a7b9e929a".to_string();
+        change.address = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
+        change.amount = "100".to_string();
+        change.change_type = Change_ChangeType::TRANSFER;
+        tx.changes.push(change);
+        transactions.submit(vec![tx.clone()]).unwrap();
+
+        // a stale local attribution, which the indexer doesn't confirm
+        let mut update = tx.clone();
+        update.changes[0].wallet_id = "".to_string();
+        transactions.submit(vec![update.clone()]).unwrap();
+        let act = transactions.get_tx(100, tx.tx_id.as_str()).unwrap();
+        assert_eq!(act.changes[0].wallet_id, "72279ede-44c4-4951-925b-f51a7b9e929a");
+
+        let outcomes = transactions.submit_with(vec![update], MergePolicy::PreferRemote).unwrap();
+        assert_eq!(outcomes, vec![SubmitOutcome::Merged { changed_fields: vec![TransactionField::Changes] }]);
+        let act = transactions.get_tx(100, tx.tx_id.as_str()).unwrap();
+        assert!(act.changes[0].wallet_id.is_empty());
+    }
+
+    #[test]
+    fn submit_outcome() {
+        let tmp_dir = TempDir::new("tx").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
+
+        let mut tx = proto_Transaction::new();
+        tx.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        tx.since_timestamp = 1_647_313_850_992;
+        tx.state = State::SUBMITTED;
+
+        assert_eq!(transactions.submit(vec![tx.clone()]).unwrap(), vec![SubmitOutcome::Created]);
+        let stored = transactions.get_tx(100, tx.tx_id.as_str()).unwrap();
+
+        assert_eq!(transactions.submit(vec![tx.clone()]).unwrap(), vec![SubmitOutcome::Unchanged]);
+        // not touched at all
+        assert_eq!(transactions.get_tx(100, tx.tx_id.as_str()).unwrap(), stored);
+
+        let mut confirmed = tx.clone();
+        confirmed.state = State::CONFIRMED;
+        confirmed.confirm_timestamp = 1_647_313_900_992;
+        let mut tx2 = tx.clone();
+        tx2.tx_id = "0x5f88e4e1ab5d1a3ccb1e0ddfb6f9b5cc0ad43eb7ae1ef1b38ba6b6fcd4a6fb4c".to_string();
+        assert_eq!(
+            transactions.submit(vec![confirmed, tx2]).unwrap(),
+            vec![
+                SubmitOutcome::Merged { changed_fields: vec![TransactionField::ConfirmTimestamp, TransactionField::State] },
+                SubmitOutcome::Created,
+            ]
+        );
+        assert!(transactions.get_tx(100, tx.tx_id.as_str()).unwrap().version > stored.version);
+    }
+
+    #[test]
+    fn rebuild_broken_indexes() {
+        let tmp_dir = TempDir::new("tx").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
+
+        let mut tx1 = proto_Transaction::new();
+        tx1.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx1.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        tx1.since_timestamp = 1_647_313_850_992;
+        let mut change = proto_Change::new();
+        change.wallet_id = "72279ede-44c4-4951-925b-f51a7b9e929a".to_string();
+        change.address = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
+        tx1.changes.push(change);
+        let mut tx2 = tx1.clone();
+        tx2.tx_id = "0x5f88e4e1ab5d1a3ccb1e0ddfb6f9b5cc0ad43eb7ae1ef1b38ba6b6fcd4a6fb4c".to_string();
+        tx2.since_timestamp = 1_647_313_860_992;
+        transactions.submit(vec![tx1.clone(), tx2.clone()]).unwrap();
+        transactions.add_tag(100, tx1.tx_id.clone(), "rent".to_string()).unwrap();
+        transactions.set_flag(100, tx1.tx_id.clone(), TxFlag::Hidden, true).unwrap();
+
+        let index_keys = |db: &sled::Db| -> Vec<String> {
+            db.scan_prefix("idx:tx:").keys()
+                .map(|k| String::from_utf8(k.unwrap().to_vec()).unwrap())
+                .collect()
+        };
+        let original = index_keys(&access.db);
+
+        // as if a write was interrupted in the middle: indexes of one tx are lost, and another one left a stale entry
+        for key in original.iter().filter(|k| k.contains(tx1.tx_id.as_str()) || k.starts_with("idx:tx:3/")) {
+            access.db.remove(key).unwrap();
+        }
+        access.db.insert("idx:tx:0/0000000000000/tx:100/0x00", "tx:100/0x00").unwrap();
 
-    fn set_cursor<S: AsRef<str> + ToString>(&self, address: S, cursor: S) -> Result<(), StateError> {
-        let key = format!("{}:{}", PREFIX_CURSOR, address.as_ref());
-        let mut proto = proto_Cursor::new();
-        proto.set_address(address.to_string());
-        proto.set_ts(Utc::now().naive_utc().timestamp_millis() as u64);
-        proto.set_value(cursor.to_string());
-        let value = proto.write_to_bytes()?;
-        let mut batch = Batch::default();
-        batch.insert(key.as_bytes(), value.as_slice());
-        self.db.apply_batch(batch)
-            .map_err(|e| StateError::from(e))
+        let wallet_filter = || Filter {
+            wallet: Some(WalletRef::WholeWallet(Uuid::from_str("72279ede-44c4-4951-925b-f51a7b9e929a").unwrap())),
+            ..Filter::default()
+        };
+        assert!(transactions.query(wallet_filter(), PageQuery::default()).unwrap().values.is_empty());
+
+        assert_eq!(transactions.rebuild_indexes().unwrap(), 2);
+
+        assert_eq!(index_keys(&access.db), original);
+        // the hidden one is still hidden
+        let results = transactions.query(wallet_filter(), PageQuery::default()).unwrap();
+        assert_eq!(results.values, vec![tx2.clone()]);
+        let results = transactions.query(Filter { include_hidden: true, ..wallet_filter() }, PageQuery::default()).unwrap();
+        assert_eq!(results.values, vec![tx2, tx1.clone()]);
+        let results = transactions.query(Filter { tag: Some("rent".to_string()), include_hidden: true, ..Filter::default() }, PageQuery::default()).unwrap();
+        assert_eq!(results.values, vec![tx1]);
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use tempdir::TempDir;
-    use std::str::FromStr;
-    use uuid::Uuid;
-    use crate::access::transactions::{AddressRef, Filter, Transactions, WalletRef};
-    use crate::access::pagination::PageQuery;
-    use crate::storage::transaction_store::{IndexType, IndexedValue};
-    use crate::proto::transactions::{BlockchainId, Transaction as proto_Transaction, Change as proto_Change, TransactionMeta as proto_TransactionMeta, Direction, Change_ChangeType, State};
-    use crate::storage::indexing::IndexEncoding;
-    use crate::storage::sled_access::SledStorage;
 
     #[test]
-    fn get_index_at_ts() {
-        let idx = IndexType::Everything(1_647_313_850_992);
-        assert_eq!("idx:tx:1/D8352686149007", idx.get_index_key());
+    fn prune_by_retention_policy() {
+        let tmp_dir = TempDir::new("tx").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
+        let now = Utc::now().timestamp_millis() as u64;
+        let day = 24 * 60 * 60 * 1000;
+
+        let make_tx = |i: u64, ts: u64, state: State| {
+            let mut tx = proto_Transaction::new();
+            tx.blockchain = BlockchainId::CHAIN_ETHEREUM;
+            tx.tx_id = format!("0x{:064x}", i);
+            tx.since_timestamp = ts;
+            tx.state = state;
+            let mut change = proto_Change::new();
+            change.wallet_id = "72279ede-44c4-4951-925b-f51a7b9e929a".to_string();
+            change.address = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
+            change.amount = i.to_string();
+            change.change_type = Change_ChangeType::TRANSFER;
+            tx.changes.push(change);
+            tx
+        };
+        let tx_old = make_tx(1, now - 100 * day, State::CONFIRMED);
+        let tx_old_labeled = make_tx(2, now - 90 * day, State::CONFIRMED);
+        let tx_old_pending = make_tx(3, now - 80 * day, State::SUBMITTED);
+        let tx_recent_1 = make_tx(4, now - 2 * day, State::CONFIRMED);
+        let tx_recent_2 = make_tx(5, now - day, State::CONFIRMED);
+        transactions.submit(vec![
+            tx_old.clone(), tx_old_labeled.clone(), tx_old_pending.clone(), tx_recent_1.clone(), tx_recent_2.clone()
+        ]).unwrap();
+        // hiding doesn't make it worth keeping
+        transactions.set_flag(100, tx_old.tx_id.clone(), TxFlag::Hidden, true).unwrap();
+        transactions.add_tag(100, tx_old_labeled.tx_id.clone(), "salary".to_string()).unwrap();
+        let allowances = access.get_allowance();
+        let mut allowance = Allowance::new();
+        allowance.wallet_id = "72279ede-44c4-4951-925b-f51a7b9e929a".to_string();
+        allowance.blockchain = 100;
+        allowance.token = "0xdAC17F958D2ee523a2206206994597C13D831ec7".to_string();
+        allowance.owner = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
+        allowance.spender = "0x65A0947BA5175359Bb457D3b34491eDf4cBF7997".to_string();
+        allowance.amount = "10000000".to_string();
+        allowances.add(allowance.clone(), None).unwrap();
+        allowances.mark_revoked(AllowanceKey::try_from(&allowance).unwrap(), tx_old.tx_id.clone()).unwrap();
+        let address = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
+        assert_eq!(transactions.get_first_seen(address.clone()).unwrap().unwrap().tx_id, tx_old.tx_id);
+
+        // nothing to remove without limits
+        assert_eq!(transactions.prune(RetentionPolicy::default()).unwrap(), 0);
+
+        let policy = RetentionPolicy {
+            max_age: Some(chrono::Duration::days(30)),
+            ..RetentionPolicy::default()
+        };
+        assert_eq!(transactions.prune(policy).unwrap(), 1);
+        assert!(transactions.get_tx(100, tx_old.tx_id.as_str()).is_none());
+        assert!(transactions.get_tx_meta(100, tx_old.tx_id.as_str()).unwrap().is_none());
+        assert!(transactions.get_tx(100, tx_old_labeled.tx_id.as_str()).is_some());
+        assert!(transactions.get_tx(100, tx_old_pending.tx_id.as_str()).is_some());
+        let filter = Filter { include_hidden: true, ..Filter::default() };
+        assert_eq!(transactions.get_count(filter.clone()).unwrap(), 4);
+        // nothing references the pruned one
+        assert!(transactions.get_first_seen(address).unwrap().is_none());
+        assert_eq!(allowances.list(None).unwrap().values[0].revoked_by_tx, "");
+        assert!(transactions.find_dangling().unwrap().is_empty());
+
+        // keeps only the latest, besides the labeled and pending
+        let policy = RetentionPolicy {
+            max_per_wallet: Some(1),
+            ..RetentionPolicy::default()
+        };
+        assert_eq!(transactions.prune(policy).unwrap(), 1);
+        assert!(transactions.get_tx(100, tx_recent_1.tx_id.as_str()).is_none());
+        assert!(transactions.get_tx(100, tx_recent_2.tx_id.as_str()).is_some());
+
+        let policy = RetentionPolicy {
+            max_per_wallet: Some(1),
+            keep_labeled: false,
+            ..RetentionPolicy::default()
+        };
+        assert_eq!(transactions.prune(policy).unwrap(), 1);
+        assert!(transactions.get_tx(100, tx_old_labeled.tx_id.as_str()).is_none());
+        // no index references the removed
+        assert_eq!(transactions.get_count(filter.clone()).unwrap(), 2);
+        let results = transactions.query(Filter { tag: Some("salary".to_string()), ..filter }, PageQuery::default()).unwrap();
+        assert!(results.values.is_empty());
     }
 
     #[test]
-    fn get_index_at_wallet() {
-        let idx = IndexType::ByWallet(Uuid::from_str("72279ede-44c4-4951-925b-f51a7b9e929a").unwrap(), 1_647_313_850_992);
-        assert_eq!("idx:tx:2/72279ede-44c4-4951-925b-f51a7b9e929a/D8352686149007", idx.get_index_key());
+    fn keep_just_confirmed_as_recent() {
+        let tmp_dir = TempDir::new("tx").unwrap();
+        let policy = TtlPolicy {
+            tx_recent_window: std::time::Duration::from_secs(60 * 60),
+            ..TtlPolicy::default()
+        };
+        let access = SledStorage::open_with_policy(tmp_dir.path().to_path_buf(), policy).unwrap();
+        let transactions = access.get_transactions();
+        let now = Utc::now().timestamp_millis() as u64;
+
+        let mut tx_recent = proto_Transaction::new();
+        tx_recent.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx_recent.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        tx_recent.since_timestamp = now - 60_000;
+        tx_recent.confirm_timestamp = now - 10_000;
+        tx_recent.state = State::CONFIRMED;
+        let mut change = proto_Change::new();
+        change.wallet_id = "72279ede-44c4-4951-925b-f51a7b9e929a".to_string();
+        change.address = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
+        tx_recent.changes.push(change);
+        let mut tx_old = tx_recent.clone();
+        tx_old.tx_id = "0x5f88e4e1ab5d1a3ccb1e0ddfb6f9b5cc0ad43eb7ae1ef1b38ba6b6fcd4a6fb4c".to_string();
+        tx_old.since_timestamp = now - 3 * 60 * 60 * 1000;
+        tx_old.confirm_timestamp = now - 2 * 60 * 60 * 1000;
+        transactions.submit(vec![tx_recent.clone(), tx_old.clone()]).unwrap();
+
+        let recent_keys = |db: &sled::Db| -> Vec<String> {
+            db.scan_prefix("idx:tx:3/72279ede-44c4-4951-925b-f51a7b9e929a/T0/").values()
+                .map(|v| String::from_utf8(v.unwrap().to_vec()).unwrap())
+                .collect()
+        };
+        assert_eq!(recent_keys(&access.db), vec![format!("tx:100/{}", tx_recent.tx_id)]);
+
+        // a migration reindexes with the same window
+        access.db.insert("version", "1").unwrap();
+        access.version().migrate().unwrap();
+        assert_eq!(recent_keys(&access.db), vec![format!("tx:100/{}", tx_recent.tx_id)]);
+
+        // nothing is outside of the window yet
+        assert_eq!(transactions.refresh_recent().unwrap(), 0);
+
+        // same as after the window passed
+        let later = TransactionsAccess {
+            db: access.db.clone(),
+            validators: access.validators.clone(),
+            references: access.references.clone(),
+            permissions: access.permissions(),
+            limiter: access.limiter.clone(),
+            health: access.health.clone(),
+            notifier: access.notifier.clone(),
+            profiler: access.profiler.clone(),
+            ttl: TtlPolicy {
+                tx_recent_window: std::time::Duration::from_secs(1),
+                ..TtlPolicy::default()
+            },
+        };
+        assert_eq!(later.refresh_recent().unwrap(), 1);
+        assert!(recent_keys(&access.db).is_empty());
+
+        let results = transactions.query(
+            Filter { wallet: Some(WalletRef::WholeWallet(Uuid::from_str("72279ede-44c4-4951-925b-f51a7b9e929a").unwrap())), ..Filter::default() },
+            PageQuery::default()
+        ).unwrap();
+        assert_eq!(results.values.len(), 2);
+        assert_eq!(results.values[0].tx_id, tx_recent.tx_id);
     }
 
     #[test]
-    fn build_indexes_basic() {
+    fn notify_subscribers() {
+        let tmp_dir = TempDir::new("tx").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
+        let receiver = transactions.subscribe().unwrap();
+        let dropped = transactions.subscribe().unwrap();
+        drop(dropped);
+
         let mut tx = proto_Transaction::new();
         tx.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
         tx.since_timestamp = 1_647_313_850_992;
-        tx.state = State::SUBMITTED;
-        let mut change1 = proto_Change::new();
-        change1.wallet_id = "72279ede-44c4-4951-925b-f51a7b9e929a".to_string();
-        change1.entry_id = 0;
-        change1.address = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
-        tx.changes.push(change1);
+        let mut change = proto_Change::new();
+        change.wallet_id = "72279ede-44c4-4951-925b-f51a7b9e929a".to_string();
+        change.address = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
+        tx.changes.push(change);
+        // made through another handle of the same storage
+        access.get_transactions().submit(vec![tx.clone()]).unwrap();
+
+        let act = receiver.try_recv().unwrap();
+        assert_eq!(act.seq, access.get_sequence().unwrap());
+        assert_eq!(act.wallets.into_iter().collect::<Vec<Uuid>>(), vec![Uuid::from_str("72279ede-44c4-4951-925b-f51a7b9e929a").unwrap()]);
+        assert_eq!(act.transactions.into_iter().collect::<Vec<(u32, String)>>(), vec![(100, tx.tx_id.clone())]);
 
-        let indexes: Vec<String> = tx.get_index_keys();
-        assert_eq!(indexes.len(), 3);
-        assert_eq!("idx:tx:1/D8352686149007", indexes.get(0).unwrap());
-        assert_eq!("idx:tx:2/72279ede-44c4-4951-925b-f51a7b9e929a/D8352686149007", indexes.get(1).unwrap());
-        assert_eq!("idx:tx:3/72279ede-44c4-4951-925b-f51a7b9e929a/T0/D8352686149007/D18446744073709551615/A00000000000000000000", indexes.get(2).unwrap());
+        let mut meta = proto_TransactionMeta::new();
+        meta.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        meta.tx_id = tx.tx_id.clone();
+        meta.timestamp = 1_647_313_870_992;
+        meta.label = "Rent".to_string();
+        transactions.set_tx_meta(meta).unwrap();
+        let act = receiver.try_recv().unwrap();
+        assert_eq!(act.wallets.len(), 1);
+        assert_eq!(act.seq, access.get_sequence().unwrap());
+
+        transactions.forget(100, tx.tx_id.clone()).unwrap();
+        let act = receiver.try_recv().unwrap();
+        assert_eq!(act.wallets.len(), 1);
+        assert_eq!(act.seq, access.get_sequence().unwrap());
+        assert!(receiver.try_recv().is_err());
     }
 
     #[test]
-    fn create_and_find_tx() {
-        let tmp_dir = TempDir::new("create_and_find_tx").unwrap();
+    fn query_with_meta() {
+        let tmp_dir = TempDir::new("tx").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
+
+        let mut tx1 = proto_Transaction::new();
+        tx1.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx1.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        tx1.since_timestamp = 1_647_313_850_992;
+        let mut tx2 = tx1.clone();
+        tx2.tx_id = "0x5f88e4e1ab5d1a3ccb1e0ddfb6f9b5cc0ad43eb7ae1ef1b38ba6b6fcd4a6fb4c".to_string();
+        tx2.since_timestamp = 1_647_313_860_992;
+        transactions.submit(vec![tx1.clone(), tx2.clone()]).unwrap();
+
+        let mut meta = proto_TransactionMeta::new();
+        meta.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        meta.tx_id = tx1.tx_id.clone();
+        meta.timestamp = 1_647_313_870_992;
+        meta.label = "Rent".to_string();
+        transactions.set_tx_meta(meta).unwrap();
+
+        let results = transactions.query_enriched(Filter::default(), PageQuery::default()).unwrap();
+        assert_eq!(results.values.len(), 2);
+        // the most recent first
+        assert_eq!(results.values[0].tx.tx_id, tx2.tx_id);
+        assert!(results.values[0].meta.is_none());
+        assert_eq!(results.values[1].tx.tx_id, tx1.tx_id);
+        assert_eq!(results.values[1].meta.as_ref().unwrap().label, "Rent");
+    }
+
+    #[test]
+    fn mark_internal_transfer_on_submit() {
+        let tmp_dir = TempDir::new("tx").unwrap();
         let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
         let transactions = access.get_transactions();
 
@@ -416,326 +3826,714 @@ mod tests {
         tx.since_timestamp = 1_647_313_850_992;
         let mut change1 = proto_Change::new();
         change1.wallet_id = "72279ede-44c4-4951-925b-f51a7b9e929a".to_string();
-        change1.entry_id = 0;
         change1.address = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
+        change1.amount = "-1000".to_string();
+        change1.direction = Direction::SEND;
+        change1.change_type = Change_ChangeType::TRANSFER;
         tx.changes.push(change1);
+        let mut change2 = proto_Change::new();
+        change2.address = "0x6218b36c1d19d4a2e9eb0ce3606eb48a0b86991c".to_string();
+        change2.amount = "1000".to_string();
+        change2.direction = Direction::RECEIVE;
+        change2.change_type = Change_ChangeType::TRANSFER;
+        tx.changes.push(change2);
+        transactions.submit(vec![tx.clone()]).unwrap();
+        assert!(!transactions.get_tx(100, tx.tx_id.as_str()).unwrap().internal_transfer);
+
+        // the receiving address is found to be in another wallet
+        transactions.reattribute(
+            Uuid::from_str("11c7ec4d-2b5f-4f8a-9e6a-0d3a8b1c3e6f").unwrap(), 0, 100,
+            vec!["0x6218b36c1d19d4a2e9eb0ce3606eb48a0b86991c".to_string()]
+        ).unwrap();
+        assert!(transactions.get_tx(100, tx.tx_id.as_str()).unwrap().internal_transfer);
+    }
 
-        transactions.submit(vec![tx.clone()]).expect("not saved");
+    #[test]
+    fn reattribute_unattributed() {
+        let tmp_dir = TempDir::new("tx").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
+        let wallet_id = Uuid::from_str("72279ede-44c4-4951-925b-f51a7b9e929a").unwrap();
 
-        let results = transactions.query(Filter::default(), PageQuery::default()).expect("queried");
+        let mut tx1 = proto_Transaction::new();
+        tx1.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx1.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        tx1.since_timestamp = 1_647_313_850_992;
+        let mut change = proto_Change::new();
+        change.address = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
+        tx1.changes.push(change);
+        let mut tx2 = tx1.clone();
+        tx2.tx_id = "0x5f88e4e1ab5d1a3ccb1e0ddfb6f9b5cc0ad43eb7ae1ef1b38ba6b6fcd4a6fb4c".to_string();
+        tx2.since_timestamp = 1_647_313_860_992;
+        tx2.changes[0].wallet_id = "11c7ec4d-2b5f-4f8a-9e6a-0d3a8b1c3e6f".to_string();
+        transactions.submit(vec![tx1.clone(), tx2.clone()]).unwrap();
+
+        let unattributed = Filter { unattributed_only: true, ..Filter::default() };
+        let results = transactions.query(unattributed.clone(), PageQuery::default()).unwrap();
         assert_eq!(results.values.len(), 1);
-        assert_eq!(results.values.get(0).unwrap().clone(), tx);
-        assert!(results.cursor.is_none());
+        assert_eq!(results.values[0].tx_id, tx1.tx_id);
+
+        // same address on another blockchain
+        let count = transactions.reattribute(wallet_id, 2, 101, vec!["0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string()]).unwrap();
+        assert_eq!(count, 0);
+
+        let count = transactions.reattribute(wallet_id, 2, 100, vec![" 0xA0B86991C6218B36C1D19D4A2E9EB0CE3606EB48".to_string()]).unwrap();
+        assert_eq!(count, 1);
+
+        assert!(transactions.query(unattributed, PageQuery::default()).unwrap().values.is_empty());
+        let results = transactions.query(
+            Filter { wallet: Some(WalletRef::SelectedEntry(wallet_id, 2)), ..Filter::default() },
+            PageQuery::default()
+        ).unwrap();
+        assert_eq!(results.values.len(), 1);
+        assert_eq!(results.values[0].tx_id, tx1.tx_id);
+        // the one attributed to another wallet is kept as is
+        let act = transactions.get_tx(100, tx2.tx_id.as_str()).unwrap();
+        assert_eq!(act.changes[0].wallet_id, "11c7ec4d-2b5f-4f8a-9e6a-0d3a8b1c3e6f");
     }
 
     #[test]
-    fn create_and_find_tx_without_associated_wallet() {
+    fn clear_selected_cursors() {
         let tmp_dir = TempDir::new("create_and_find_tx").unwrap();
         let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
         let transactions = access.get_transactions();
 
-        let mut tx = proto_Transaction::new();
-        tx.blockchain = BlockchainId::CHAIN_ETHEREUM;
-        tx.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
-        tx.since_timestamp = 1_647_313_850_992;
-        let mut change1 = proto_Change::new();
-        change1.address = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
-        tx.changes.push(change1);
+        transactions.set_cursors(vec![
+            ("0x6218b36c1d19d4a2e9eb0ce3606eb48a0b86991c", "MTA5MjQ5MS81ODE="),
+            ("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48", "MTA5MjQ5MS81OTA="),
+        ]).unwrap();
 
-        transactions.submit(vec![tx.clone()]).expect("not saved");
+        let count = transactions.clear_cursors(vec![
+            "0x6218b36c1d19d4a2e9eb0ce3606eb48a0b86991c",
+            "0x3606eb48a0b86991c6218b36c1d19d4a2e9eb0ce",
+        ]).unwrap();
+        assert_eq!(count, 1);
 
-        let results = transactions.query(Filter::default(), PageQuery::default()).expect("queried");
-        assert_eq!(results.values.len(), 1);
-        assert_eq!(results.values.get(0).unwrap().clone(), tx);
-        assert!(results.cursor.is_none());
+        assert!(transactions.get_cursor("0x6218b36c1d19d4a2e9eb0ce3606eb48a0b86991c").unwrap().is_none());
+        assert!(transactions.get_cursor("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").unwrap().is_some());
     }
 
     #[test]
-    fn create_and_delete_tx() {
-        let tmp_dir = TempDir::new("create_and_find_tx").unwrap();
+    fn no_tx_meta_by_default() {
+        let tmp_dir = TempDir::new("tx").unwrap();
         let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
         let transactions = access.get_transactions();
 
-        let mut tx = proto_Transaction::new();
-        tx.blockchain = BlockchainId::CHAIN_ETHEREUM;
-        tx.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
-        tx.since_timestamp = 1_647_313_850_992;
-        let mut change1 = proto_Change::new();
-        change1.wallet_id = "72279ede-44c4-4951-925b-f51a7b9e929a".to_string();
-        change1.entry_id = 0;
-        change1.address = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
-        tx.changes.push(change1);
+        let act = transactions.get_tx_meta(100, "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b");
 
-        transactions.submit(vec![tx.clone()]).expect("not saved");
+        assert!(act.is_ok());
+        let act = act.unwrap();
+        assert!(act.is_none());
+    }
 
-        let results = transactions.query(Filter::default(), PageQuery::default()).expect("queried");
-        assert_eq!(results.values.len(), 1);
+    #[test]
+    fn set_and_get_tx_meta() {
+        let tmp_dir = TempDir::new("tx").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
 
-        transactions.forget(100, "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string()).expect("not removed");
-        let results = transactions.query(Filter::default(), PageQuery::default()).expect("queried");
-        assert_eq!(results.values.len(), 0);
+        let mut meta = proto_TransactionMeta::new();
+        meta.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        meta.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        meta.timestamp = 1_647_313_850_992;
+        meta.label = "test".to_string();
+        transactions.set_tx_meta(meta.clone()).unwrap();
+
+        let act = transactions.get_tx_meta(100, "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b");
 
-        let db_size = access.db.scan_prefix("").count();
-        assert_eq!(db_size, 1); // only version field
+        assert!(act.is_ok());
+        let act = act.unwrap();
+        assert!(act.is_some());
+        let act = act.unwrap();
+        assert_eq!(act, meta);
     }
 
     #[test]
-    fn create_and_update_tx_using_merge() {
-        // doesn't do full merge test, only checks that it applied
+    fn set_and_get_tx_meta_with_raw() {
+        let tmp_dir = TempDir::new("tx").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
 
-        let tmp_dir = TempDir::new("create_and_find_tx").unwrap();
+        let mut meta = proto_TransactionMeta::new();
+        meta.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        meta.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        meta.timestamp = 1_647_313_850_992;
+        meta.raw = hex::decode("af4fb6d192624360def7b0d72b1014cb9799de95781ce61b9b11c453e5d0c7c1eec752021ebcb344da0a88cdf49e97854d4fa861cbf069962cf3a82abd1e82f7").unwrap();
+        transactions.set_tx_meta(meta.clone()).unwrap();
+
+        let act = transactions.get_tx_meta(100, "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b");
+
+        assert!(act.is_ok());
+        let act = act.unwrap().unwrap();
+        assert_eq!(act.raw, hex::decode("af4fb6d192624360def7b0d72b1014cb9799de95781ce61b9b11c453e5d0c7c1eec752021ebcb344da0a88cdf49e97854d4fa861cbf069962cf3a82abd1e82f7").unwrap());
+    }
+
+    #[test]
+    fn compact_raw_of_old_confirmed() {
+        let tmp_dir = TempDir::new("tx").unwrap();
         let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
         let transactions = access.get_transactions();
+        let raw = hex::decode("af4fb6d192624360def7b0d72b1014cb9799de95781ce61b9b11c453e5d0c7c1eec752021ebcb344da0a88cdf49e97854d4fa861cbf069962cf3a82abd1e82f7").unwrap();
+
+        let mut tx_old = proto_Transaction::new();
+        tx_old.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx_old.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        tx_old.since_timestamp = 1_647_313_850_992;
+        tx_old.confirm_timestamp = 1_647_313_865_992;
+        tx_old.state = State::CONFIRMED;
+        let mut tx_pending = tx_old.clone();
+        tx_pending.tx_id = "0x5f88e4e1ab5d1a3ccb1e0ddfb6f9b5cc0ad43eb7ae1ef1b38ba6b6fcd4a6fb4c".to_string();
+        tx_pending.confirm_timestamp = 0;
+        tx_pending.state = State::SUBMITTED;
+        transactions.submit(vec![tx_old.clone(), tx_pending.clone()]).unwrap();
+
+        for tx in [&tx_old, &tx_pending] {
+            let mut meta = proto_TransactionMeta::new();
+            meta.blockchain = BlockchainId::CHAIN_ETHEREUM;
+            meta.tx_id = tx.tx_id.clone();
+            meta.timestamp = 1_647_313_850_992;
+            meta.raw = raw.clone();
+            transactions.set_tx_meta(meta).unwrap();
+        }
 
-        let mut tx = proto_Transaction::new();
-        tx.blockchain = BlockchainId::CHAIN_ETHEREUM;
-        tx.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
-        tx.since_timestamp = 1_647_313_850_992;
-        let mut change1 = proto_Change::new();
-        change1.wallet_id = "72279ede-44c4-4951-925b-f51a7b9e929a".to_string();
-        change1.entry_id = 0;
-        change1.address = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
-        change1.amount = "100".to_string();
-        change1.direction = Direction::SEND;
-        change1.change_type = Change_ChangeType::TRANSFER;
-        tx.changes.push(change1.clone());
+        let count = transactions.compact_raw(std::time::Duration::from_secs(86_400)).unwrap();
+        assert_eq!(count, 1);
 
-        transactions.submit(vec![tx.clone()]).expect("not saved");
+        let act = transactions.get_tx_meta(100, tx_old.tx_id.as_str()).unwrap().unwrap();
+        assert!(act.raw.is_empty());
+        assert_eq!(act.raw_hash.len(), 32);
+        let act = transactions.get_tx_meta(100, tx_pending.tx_id.as_str()).unwrap().unwrap();
+        assert_eq!(act.raw, raw);
+
+        // nothing else to compact
+        assert_eq!(transactions.compact_raw(std::time::Duration::from_secs(86_400)).unwrap(), 0);
+
+        let restored = transactions.restore_raw(100, tx_old.tx_id.clone(), vec![0x01, 0x02]);
+        assert!(restored.is_err());
+        let restored = transactions.restore_raw(100, tx_old.tx_id.clone(), raw.clone()).unwrap().unwrap();
+        assert_eq!(restored.raw, raw);
+        assert!(restored.raw_hash.is_empty());
+        let act = transactions.get_tx_meta(100, tx_old.tx_id.as_str()).unwrap().unwrap();
+        assert_eq!(act.raw, raw);
+    }
 
-        let mut tx_update = tx.clone();
-        let mut change1_update = change1.clone();
-        change1_update.clear_wallet_id();
-        tx_update.clear_changes();
-        tx_update.changes.push(change1_update);
+    #[test]
+    fn set_tx_meta_bulk_with_timestamp_checks() {
+        let tmp_dir = TempDir::new("tx").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
 
-        transactions.submit(vec![tx_update.clone()]).expect("not saved");
+        let mut meta1 = proto_TransactionMeta::new();
+        meta1.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        meta1.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        meta1.timestamp = 1_647_313_100_000;
+        meta1.label = "existing".to_string();
+        transactions.set_tx_meta(meta1.clone()).unwrap();
+
+        let mut meta1_old = meta1.clone();
+        meta1_old.timestamp = 1_647_313_000_000;
+        meta1_old.label = "old".to_string();
+
+        let mut meta2 = proto_TransactionMeta::new();
+        meta2.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        meta2.tx_id = "0x5f88e4e1ab5d1a3ccb1e0ddfb6f9b5cc0ad43eb7ae1ef1b38ba6b6fcd4a6fb4c".to_string();
+        meta2.timestamp = 1_647_313_000_000;
+        meta2.label = "first".to_string();
+
+        let mut meta2_new = meta2.clone();
+        meta2_new.timestamp = 1_647_313_200_000;
+        meta2_new.label = "second".to_string();
+
+        let results = transactions.set_tx_meta_bulk(vec![meta1_old, meta2, meta2_new.clone()]).unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].label, "existing");
+        assert_eq!(results[1].label, "first");
+        assert_eq!(results[2].label, "second");
+
+        let act1 = transactions
+            .get_tx_meta(100, "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b")
+            .unwrap().unwrap();
+        assert_eq!(act1, meta1);
+        let act2 = transactions
+            .get_tx_meta(100, "0x5f88e4e1ab5d1a3ccb1e0ddfb6f9b5cc0ad43eb7ae1ef1b38ba6b6fcd4a6fb4c")
+            .unwrap().unwrap();
+        assert_eq!(act2, meta2_new);
+    }
 
-        let tx_read = transactions.get_tx(100, "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b");
+    #[test]
+    fn query_by_tag() {
+        let tmp_dir = TempDir::new("tx").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
 
-        assert!(tx_read.is_some());
-        let tx_read = tx_read.unwrap();
+        let mut tx1 = proto_Transaction::new();
+        tx1.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx1.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        tx1.since_timestamp = 1_647_313_850_992;
+        let mut tx2 = tx1.clone();
+        tx2.tx_id = "0x5f88e4e1ab5d1a3ccb1e0ddfb6f9b5cc0ad43eb7ae1ef1b38ba6b6fcd4a6fb4c".to_string();
+        tx2.since_timestamp = 1_647_313_860_992;
+        let mut tx3 = tx1.clone();
+        tx3.tx_id = "0x9b11c453e5d0caf4fb6d192624360def7bd1e81b2f761cbf069962cf3a82ab0d".to_string();
+        tx3.since_timestamp = 1_647_313_870_992;
+        transactions.submit(vec![tx1.clone(), tx2.clone(), tx3.clone()]).expect("not saved");
 
-        assert_eq!(tx_read.changes.len(), 1);
-        let change_read = tx_read.changes.get(0).unwrap();
+        let meta = transactions.add_tag(100, tx1.tx_id.clone(), "Exchange".to_string()).unwrap();
+        assert_eq!(meta.tags.to_vec(), vec!["exchange".to_string()]);
+        transactions.add_tag(100, tx1.tx_id.clone(), "defi".to_string()).unwrap();
+        transactions.add_tag(100, tx3.tx_id.clone(), "exchange".to_string()).unwrap();
 
-        assert_eq!(change_read.wallet_id, "72279ede-44c4-4951-925b-f51a7b9e929a".to_string());
+        let filter = Filter { tag: Some("exchange".to_string()), ..Filter::default() };
+        let results = transactions.query(filter.clone(), PageQuery::default()).unwrap();
+        assert_eq!(results.values.len(), 2);
+        // keeps the usual order, most recent first
+        assert_eq!(results.values[0].tx_id, tx3.tx_id);
+        assert_eq!(results.values[1].tx_id, tx1.tx_id);
+        assert_eq!(transactions.get_count(filter.clone()).unwrap(), 2);
+
+        let meta = transactions.remove_tag(100, tx1.tx_id.clone(), "exchange".to_string()).unwrap().unwrap();
+        assert_eq!(meta.tags.to_vec(), vec!["defi".to_string()]);
+        let results = transactions.query(filter.clone(), PageQuery::default()).unwrap();
+        assert_eq!(results.values.len(), 1);
+        assert_eq!(results.values[0].tx_id, tx3.tx_id);
+
+        let results = transactions.query(Filter { tag: Some("DeFi".to_string()), ..Filter::default() }, PageQuery::default()).unwrap();
+        assert_eq!(results.values.len(), 1);
+        assert_eq!(results.values[0].tx_id, tx1.tx_id);
+
+        let results = transactions.query(Filter { tag: Some("salary".to_string()), ..Filter::default() }, PageQuery::default()).unwrap();
+        assert!(results.values.is_empty());
+
+        // the label is kept when tags are changed
+        let mut meta = transactions.get_tx_meta(100, tx2.tx_id.as_str()).unwrap().unwrap_or_default();
+        meta.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        meta.tx_id = tx2.tx_id.clone();
+        meta.timestamp = 1_647_313_850_992;
+        meta.label = "test".to_string();
+        transactions.set_tx_meta(meta).unwrap();
+        let meta = transactions.add_tag(100, tx2.tx_id.clone(), "salary".to_string()).unwrap();
+        assert_eq!(meta.label, "test");
     }
 
     #[test]
-    fn loads_using_desc_order() {
-        let tmp_dir = TempDir::new("create_and_find_tx").unwrap();
+    fn query_by_flags() {
+        let tmp_dir = TempDir::new("tx").unwrap();
         let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
         let transactions = access.get_transactions();
 
         let mut tx1 = proto_Transaction::new();
         tx1.blockchain = BlockchainId::CHAIN_ETHEREUM;
-        tx1.tx_id = "0xd9b11cef7bd1e81b453e5d0caf4fb6d1922f761cbf069962cf3a82ab0624360d".to_string();
-        tx1.since_timestamp = 1_647_313_000_000;
-        let mut change1 = proto_Change::new();
-        change1.wallet_id = "72279ede-44c4-4951-925b-f51a7b9e929a".to_string();
-        change1.entry_id = 0;
-        change1.address = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
-        tx1.changes.push(change1);
+        tx1.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        tx1.since_timestamp = 1_647_313_850_992;
+        let mut tx2 = tx1.clone();
+        tx2.tx_id = "0x5f88e4e1ab5d1a3ccb1e0ddfb6f9b5cc0ad43eb7ae1ef1b38ba6b6fcd4a6fb4c".to_string();
+        tx2.since_timestamp = 1_647_313_860_992;
+        let mut tx3 = tx1.clone();
+        tx3.tx_id = "0x9b11c453e5d0caf4fb6d192624360def7bd1e81b2f761cbf069962cf3a82ab0d".to_string();
+        tx3.since_timestamp = 1_647_313_870_992;
+        transactions.submit(vec![tx1.clone(), tx2.clone(), tx3.clone()]).expect("not saved");
 
-        let mut tx2 = proto_Transaction::new();
-        tx2.blockchain = BlockchainId::CHAIN_ETHEREUM;
-        tx2.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
-        tx2.since_timestamp = 1_647_315_000_000;
-        let mut change1 = proto_Change::new();
-        change1.wallet_id = "72279ede-44c4-4951-925b-f51a7b9e929a".to_string();
-        change1.entry_id = 0;
-        change1.address = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
-        tx2.changes.push(change1);
+        let meta = transactions.set_flag(100, tx1.tx_id.clone(), TxFlag::Starred, true).unwrap();
+        assert!(meta.starred);
+        transactions.add_tag(100, tx1.tx_id.clone(), "exchange".to_string()).unwrap();
+        transactions.set_flag(100, tx2.tx_id.clone(), TxFlag::Hidden, true).unwrap();
+        transactions.set_flag(100, tx3.tx_id.clone(), TxFlag::NeedsReview, true).unwrap();
 
-        transactions.submit(vec![tx1.clone(), tx2.clone()]).expect("not saved");
+        let filter = Filter { starred_only: true, ..Filter::default() };
+        let results = transactions.query(filter.clone(), PageQuery::default()).unwrap();
+        assert_eq!(results.values.len(), 1);
+        assert_eq!(results.values[0].tx_id, tx1.tx_id);
+        // the flags don't change the tags
+        let meta = transactions.get_tx_meta(100, tx1.tx_id.as_str()).unwrap().unwrap();
+        assert_eq!(meta.tags.to_vec(), vec!["exchange".to_string()]);
+
+        let results = transactions.query(Filter { needs_review_only: true, ..Filter::default() }, PageQuery::default()).unwrap();
+        assert_eq!(results.values.len(), 1);
+        assert_eq!(results.values[0].tx_id, tx3.tx_id);
 
-        let results = transactions.query(Filter::default(), PageQuery::default()).expect("query data");
+        // hidden is excluded by default
+        let results = transactions.query(Filter::default(), PageQuery::default()).unwrap();
         assert_eq!(results.values.len(), 2);
-        assert_eq!(results.values.get(0).unwrap().clone(), tx2);
-        assert_eq!(results.values.get(1).unwrap().clone(), tx1);
-        assert!(results.cursor.is_none());
+        assert!(results.values.iter().all(|tx| tx.tx_id != tx2.tx_id));
+        assert_eq!(transactions.get_count(Filter::default()).unwrap(), 2);
+
+        let filter = Filter { include_hidden: true, ..Filter::default() };
+        assert_eq!(transactions.query(filter.clone(), PageQuery::default()).unwrap().values.len(), 3);
+        assert_eq!(transactions.get_count(filter).unwrap(), 3);
+
+        // unset the flag brings it back
+        let meta = transactions.set_flag(100, tx2.tx_id.clone(), TxFlag::Hidden, false).unwrap();
+        assert!(!meta.hidden);
+        assert_eq!(transactions.get_count(Filter::default()).unwrap(), 3);
     }
 
     #[test]
-    fn query_with_pagination() {
-        let tmp_dir = TempDir::new("create_and_find_tx").unwrap();
+    fn remember_first_seen() {
+        let tmp_dir = TempDir::new("tx").unwrap();
         let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
         let transactions = access.get_transactions();
 
-        let mut insert = Vec::new();
-        for i in 0..10 {
-            let mut tx = proto_Transaction::new();
-            tx.blockchain = BlockchainId::CHAIN_ETHEREUM;
-            tx.tx_id = format!("0xd9b11cef7bd1e81b453e5d0caf4fb6d1922f761cbf069962cf3a82ab062400{}", i);
-            tx.since_timestamp = 1_647_313_000_100 - i; // decrease tx because it returned in desc order
-            let mut change1 = proto_Change::new();
-            change1.wallet_id = "72279ede-44c4-4951-925b-f51a7b9e929a".to_string();
-            change1.entry_id = 0;
-            change1.address = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
-            tx.changes.push(change1);
-            insert.push(tx);
-        }
+        let mut tx1 = proto_Transaction::new();
+        tx1.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx1.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        tx1.since_timestamp = 1_647_313_850_992;
+        let mut change = proto_Change::new();
+        change.address = "0xA0B86991C6218B36C1D19D4A2E9EB0CE3606EB48".to_string();
+        change.direction = Direction::SEND;
+        tx1.changes.push(change);
+        let mut change = proto_Change::new();
+        change.address = "0x6218b36c1d19d4a2e9eb0ce3606eb48a0b86991c".to_string();
+        change.direction = Direction::RECEIVE;
+        tx1.changes.push(change);
+        transactions.submit(vec![tx1.clone()]).unwrap();
+
+        assert!(transactions.get_first_seen("0xdac17f958d2ee523a2206206994597c13d831ec7".to_string()).unwrap().is_none());
+        let seen = transactions.get_first_seen("0x6218B36C1D19D4A2E9EB0CE3606EB48A0B86991C".to_string()).unwrap().unwrap();
+        assert_eq!(seen.tx_id, tx1.tx_id);
+        assert_eq!(seen.timestamp, 1_647_313_850_992);
+        assert_eq!(seen.direction, Direction::RECEIVE);
+
+        // a later one doesn't change it
+        let mut tx2 = tx1.clone();
+        tx2.tx_id = "0x5f88e4e1ab5d1a3ccb1e0ddfb6f9b5cc0ad43eb7ae1ef1b38ba6b6fcd4a6fb4c".to_string();
+        tx2.since_timestamp = 1_647_313_860_992;
+        // but an earlier, ex. when the history is loaded backwards, replaces it
+        let mut tx3 = tx1.clone();
+        tx3.tx_id = "0x9b11c453e5d0caf4fb6d192624360def7bd1e81b2f761cbf069962cf3a82ab0d".to_string();
+        tx3.since_timestamp = 1_647_313_000_000;
+        tx3.changes.remove(1);
+        transactions.submit(vec![tx2, tx3.clone()]).unwrap();
+
+        let seen = transactions.get_first_seen("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string()).unwrap().unwrap();
+        assert_eq!(seen.tx_id, tx3.tx_id);
+        assert_eq!(seen.direction, Direction::SEND);
+        let seen = transactions.get_first_seen("0x6218b36c1d19d4a2e9eb0ce3606eb48a0b86991c".to_string()).unwrap().unwrap();
+        assert_eq!(seen.tx_id, tx1.tx_id);
+
+        // the record of the removed tx is removed too
+        transactions.forget(100, tx3.tx_id.clone()).unwrap();
+        assert!(transactions.get_first_seen("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string()).unwrap().is_none());
+        assert!(transactions.get_first_seen("0x6218b36c1d19d4a2e9eb0ce3606eb48a0b86991c".to_string()).unwrap().is_some());
+    }
 
-        transactions.submit(insert).expect("not saved");
+    #[test]
+    fn query_by_confirmations() {
+        let tmp_dir = TempDir::new("tx").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
 
-        let results_1 = transactions.query(
-            Filter::default(),
-            PageQuery { limit: 5, ..PageQuery::default() }
-        ).expect("query data");
+        let mut tx1 = proto_Transaction::new();
+        tx1.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx1.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        tx1.since_timestamp = 1_647_313_850_992;
+        tx1.state = State::CONFIRMED;
+        let mut block = BlockRef::new();
+        block.height = 15_000_000;
+        tx1.set_block(block.clone());
+        let mut tx2 = tx1.clone();
+        tx2.tx_id = "0x5f88e4e1ab5d1a3ccb1e0ddfb6f9b5cc0ad43eb7ae1ef1b38ba6b6fcd4a6fb4c".to_string();
+        tx2.since_timestamp = 1_647_313_860_992;
+        block.height = 15_000_010;
+        tx2.set_block(block);
+        let mut tx3 = tx1.clone();
+        tx3.tx_id = "0x9b11c453e5d0caf4fb6d192624360def7bd1e81b2f761cbf069962cf3a82ab0d".to_string();
+        tx3.since_timestamp = 1_647_313_870_992;
+        tx3.state = State::SUBMITTED;
+        tx3.clear_block();
+        transactions.submit(vec![tx1.clone(), tx2.clone(), tx3.clone()]).expect("not saved");
 
-        assert_eq!(results_1.values.len(), 5);
-        assert_eq!(results_1.values.get(0).unwrap().tx_id, "0xd9b11cef7bd1e81b453e5d0caf4fb6d1922f761cbf069962cf3a82ab0624000");
-        assert_eq!(results_1.values.get(4).unwrap().tx_id, "0xd9b11cef7bd1e81b453e5d0caf4fb6d1922f761cbf069962cf3a82ab0624004");
-        assert!(results_1.cursor.is_some());
+        let filter = Filter { min_confirmations: Some(12), ..Filter::default() };
+        // the head is unknown yet
+        assert_eq!(transactions.get_head_height(100).unwrap(), None);
+        assert!(transactions.query(filter.clone(), PageQuery::default()).unwrap().values.is_empty());
 
-        let results_2 = transactions.query(
-            Filter::default(),
-            PageQuery { limit: 5, cursor: results_1.cursor, ..PageQuery::default() }
-        ).expect("query data");
+        transactions.set_head_height(100, 15_000_011).unwrap();
+        assert_eq!(transactions.get_head_height(100).unwrap(), Some(15_000_011));
+        let results = transactions.query(filter.clone(), PageQuery::default()).unwrap();
+        assert_eq!(results.values.len(), 1);
+        assert_eq!(results.values[0].tx_id, tx1.tx_id);
+        assert_eq!(transactions.get_count(filter.clone()).unwrap(), 1);
 
-        assert_eq!(results_2.values.len(), 5);
-        assert_eq!(results_2.values.get(0).unwrap().tx_id, "0xd9b11cef7bd1e81b453e5d0caf4fb6d1922f761cbf069962cf3a82ab0624005");
-        assert_eq!(results_2.values.get(4).unwrap().tx_id, "0xd9b11cef7bd1e81b453e5d0caf4fb6d1922f761cbf069962cf3a82ab0624009");
-        // a cursor may still presents since the storage doesnt know is there are any other items
+        let filter_one = Filter { min_confirmations: Some(1), ..Filter::default() };
+        assert_eq!(transactions.get_count(filter_one).unwrap(), 2);
 
-        let results_3 = transactions.query(
-            Filter::default(),
-            PageQuery { limit: 5, cursor: results_2.cursor, ..PageQuery::default() }
-        ).expect("query data");
-        assert_eq!(results_3.values.len(), 0);
-        assert!(results_3.cursor.is_none());
+        transactions.set_head_height(100, 15_000_021).unwrap();
+        assert_eq!(transactions.get_count(filter).unwrap(), 2);
     }
 
     #[test]
-    fn count_items() {
-        let tmp_dir = TempDir::new("create_and_find_tx").unwrap();
+    fn query_with_facets() {
+        let tmp_dir = TempDir::new("tx").unwrap();
         let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
         let transactions = access.get_transactions();
 
-        let mut tx1 = proto_Transaction::new();
-        tx1.blockchain = BlockchainId::CHAIN_ETHEREUM;
-        tx1.tx_id = "0xd9b11cef7bd1e81b453e5d0caf4fb6d1922f761cbf069962cf3a82ab0624360d".to_string();
-        tx1.since_timestamp = 1_647_313_000_000;
-        let mut change1 = proto_Change::new();
-        change1.wallet_id = "72279ede-44c4-4951-925b-f51a7b9e929a".to_string();
-        change1.entry_id = 0;
-        change1.address = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
-        tx1.changes.push(change1);
+        let make_tx = |blockchain: BlockchainId, tx_id: &str, ts: u64, state: State| {
+            let mut tx = proto_Transaction::new();
+            tx.blockchain = blockchain;
+            tx.tx_id = tx_id.to_string();
+            tx.since_timestamp = ts;
+            tx.state = state;
+            tx
+        };
+        transactions.submit(vec![
+            make_tx(BlockchainId::CHAIN_ETHEREUM, "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b", 1_647_313_850_992, State::CONFIRMED),
+            make_tx(BlockchainId::CHAIN_ETHEREUM, "0x5f88e4e1ab5d1a3ccb1e0ddfb6f9b5cc0ad43eb7ae1ef1b38ba6b6fcd4a6fb4c", 1_647_313_860_992, State::SUBMITTED),
+            make_tx(BlockchainId::CHAIN_ETHEREUM_CLASSIC, "0x9b11c453e5d0caf4fb6d192624360def7bd1e81b2f761cbf069962cf3a82ab0d", 1_647_313_870_992, State::CONFIRMED),
+            make_tx(BlockchainId::CHAIN_BITCOIN, "2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b", 1_647_313_880_992, State::CONFIRMED),
+        ]).expect("not saved");
+
+        let results = transactions.query_faceted(
+            Filter { blockchains: Some(vec![100]), ..Filter::default() },
+            PageQuery::default(),
+        ).expect("queried");
+        assert_eq!(results.page.values.len(), 2);
+        // counts all blockchains, as the blockchain criterion is ignored for its own facet
+        assert_eq!(results.facets.by_blockchain.len(), 3);
+        assert_eq!(results.facets.by_blockchain.get(&100), Some(&2));
+        assert_eq!(results.facets.by_blockchain.get(&101), Some(&1));
+        assert_eq!(results.facets.by_blockchain.get(&1), Some(&1));
+        // but the states are only for the selected blockchain
+        assert_eq!(results.facets.by_state.get(&State::CONFIRMED), Some(&1));
+        assert_eq!(results.facets.by_state.get(&State::SUBMITTED), Some(&1));
+
+        let results = transactions.query_faceted(
+            Filter { state: Some(State::CONFIRMED), ..Filter::default() },
+            PageQuery::default(),
+        ).expect("queried");
+        assert_eq!(results.page.values.len(), 3);
+        assert_eq!(results.facets.by_blockchain.get(&100), Some(&1));
+        assert_eq!(results.facets.by_state.get(&State::CONFIRMED), Some(&3));
+        assert_eq!(results.facets.by_state.get(&State::SUBMITTED), Some(&1));
+    }
 
-        let mut tx2 = proto_Transaction::new();
-        tx2.blockchain = BlockchainId::CHAIN_ETHEREUM;
-        tx2.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
-        tx2.since_timestamp = 1_647_315_000_000;
-        let mut change1 = proto_Change::new();
-        change1.wallet_id = "72279ede-44c4-4951-925b-f51a7b9e929a".to_string();
-        change1.entry_id = 0;
-        change1.address = "0x6218b36c1d19d4a2e9eb0ce3606eb48a0b86991c".to_string();
-        tx2.changes.push(change1);
+    #[test]
+    fn normalize_tx_id() {
+        assert_eq!(
+            TransactionsAccess::normalize_tx_id(100, "0x2F761CBF069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b"),
+            "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b"
+        );
+        assert_eq!(
+            TransactionsAccess::normalize_tx_id(100, "2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b"),
+            "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b"
+        );
+        assert_eq!(
+            TransactionsAccess::normalize_tx_id(1, "0x2F761CBF069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b"),
+            "2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b"
+        );
+        assert_eq!(TransactionsAccess::normalize_tx_id(100, ""), "");
+    }
 
-        transactions.submit(vec![tx1.clone(), tx2.clone()]).expect("not saved");
+    #[test]
+    fn find_tx_with_any_case() {
+        let tmp_dir = TempDir::new("tx").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
 
-        let count = transactions.get_count(Filter::default()).expect("query count");
-        assert_eq!(count, 2);
+        let mut tx = proto_Transaction::new();
+        tx.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx.tx_id = "0x2F761CBF069962CF3A82AB0D9B11C453E5D0CAF4FB6D192624360DEF7BD1E81B".to_string();
+        tx.since_timestamp = 1_647_313_850_992;
+        transactions.submit(vec![tx]).expect("not saved");
 
-        let count = transactions.get_count(Filter {
-            addresses: Some(vec![AddressRef::SingleAddress("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string())]),
-            ..Filter::default()
-        }).expect("query count");
-        assert_eq!(count, 1);
+        let act = transactions.get_tx(100, "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b").unwrap();
+        assert_eq!(act.tx_id, "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b");
+        assert!(transactions.get_tx(100, "2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b").is_some());
 
-        let count = transactions.get_count(Filter {
-            addresses: Some(vec![AddressRef::SingleAddress("0x6218b36c1d19d4a2e9eb0ce3606eb48a0b86991c".to_string())]),
-            ..Filter::default()
-        }).expect("query count");
-        assert_eq!(count, 1);
+        let mut tx = proto_Transaction::new();
+        tx.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx.tx_id = "2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        tx.since_timestamp = 1_647_313_850_992;
+        tx.state = State::CONFIRMED;
+        transactions.submit(vec![tx]).expect("not saved");
 
-        let count = transactions.get_count(Filter {
-            wallet: Some(WalletRef::WholeWallet(Uuid::from_str("72279ede-44c4-4951-925b-f51a7b9e929a").unwrap())),
-            ..Filter::default()
-        }).expect("query count");
-        assert_eq!(count, 2);
+        assert_eq!(transactions.get_count(Filter::default()).unwrap(), 1);
+        let act = transactions.get_tx(100, "0x2F761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b").unwrap();
+        assert_eq!(act.state, State::CONFIRMED);
+
+        transactions.forget(100, "0x2F761CBF069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string()).unwrap();
+        assert!(transactions.get_tx(100, "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b").is_none());
+        assert_eq!(transactions.get_count(Filter::default()).unwrap(), 0);
     }
 
     #[test]
-    fn no_cursor_by_default() {
-        let tmp_dir = TempDir::new("create_and_find_tx").unwrap();
+    fn migrate_non_normalized_tx_id() {
+        let tmp_dir = TempDir::new("tx").unwrap();
         let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
         let transactions = access.get_transactions();
 
-        let act = transactions.get_cursor("0x6218b36c1d19d4a2e9eb0ce3606eb48a0b86991c");
-        assert!(act.is_ok());
-        assert!(act.unwrap().is_none());
+        // as it was stored before the normalization
+        let mut tx = proto_Transaction::new();
+        tx.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx.tx_id = "0x2F761CBF069962CF3A82AB0D9B11C453E5D0CAF4FB6D192624360DEF7BD1E81B".to_string();
+        tx.since_timestamp = 1_647_313_850_992;
+        let key = format!("tx:100/{}", tx.tx_id);
+        let mut batch = sled::Batch::default();
+        for idx in transactions.get_tx_index_keys(&tx) {
+            batch.insert(idx.as_bytes(), key.as_bytes());
+        }
+        batch.insert(key.as_bytes(), tx.write_to_bytes().unwrap());
+        access.db.apply_batch(batch).unwrap();
+
+        transactions.migrate(3).unwrap();
+
+        assert!(access.db.get(key).unwrap().is_none());
+        let act = transactions.get_tx(100, "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b").unwrap();
+        assert_eq!(act.tx_id, "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b");
+        let results = transactions.query(Filter::default(), PageQuery::default()).unwrap();
+        assert_eq!(results.values.len(), 1);
+        assert_eq!(results.values[0].tx_id, act.tx_id);
     }
 
     #[test]
-    fn save_and_provide_cursor() {
-        let tmp_dir = TempDir::new("create_and_find_tx").unwrap();
+    fn import_from_jsonl() {
+        let tmp_dir = TempDir::new("tx").unwrap();
         let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
         let transactions = access.get_transactions();
 
-        let saved = transactions.set_cursor("0x6218b36c1d19d4a2e9eb0ce3606eb48a0b86991c", "MTA5MjQ5MS81ODE=");
-        assert!(saved.is_ok());
+        let jsonl = r#"{"blockchain": "CHAIN_ETHEREUM", "txId": "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b", "sinceTimestamp": "1647313850992", "state": "CONFIRMED"}
+{"blockchain": "CHAIN_ETHEREUM", "sinceTimestamp": "1647313860992"}
 
-        let act = transactions.get_cursor("0x6218b36c1d19d4a2e9eb0ce3606eb48a0b86991c");
-        assert!(act.is_ok());
-        let act = act.unwrap();
-        assert!(act.is_some());
-        assert_eq!(act.unwrap().value, "MTA5MjQ5MS81ODE=".to_string());
+{"blockchain": 1, "tx_id": "5f88e4e1ab5d1a3ccb1e0ddfb6f9b5cc0ad43eb7ae1ef1b38ba6b6fcd4a6fb4c", "since_timestamp": 1647313870992}
+"#;
+        let report = transactions.import_jsonl(jsonl.as_bytes()).unwrap();
+        assert_eq!(report.imported, 2);
+        assert_eq!(report.errors, vec![(2, "Invalid txId".to_string())]);
+
+        let act = transactions.get_tx(100, "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b").unwrap();
+        assert_eq!(act.state, State::CONFIRMED);
+        assert!(transactions.get_tx(1, "5f88e4e1ab5d1a3ccb1e0ddfb6f9b5cc0ad43eb7ae1ef1b38ba6b6fcd4a6fb4c").is_some());
     }
 
     #[test]
-    fn no_tx_meta_by_default() {
+    fn import_from_jsonl_as_single_write() {
         let tmp_dir = TempDir::new("tx").unwrap();
         let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        access.set_rate_limit(Store::Transactions, Some(RateLimit { per_second: 1, burst: 1 })).unwrap();
         let transactions = access.get_transactions();
 
-        let act = transactions.get_tx_meta(100, "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b");
+        // more than a single batch
+        let jsonl: Vec<String> = (0..1_500).map(|i| {
+            format!(r#"{{"blockchain": "CHAIN_ETHEREUM", "txId": "0x{:064x}", "sinceTimestamp": "1647313850992"}}"#, i)
+        }).collect();
+        let report = transactions.import_jsonl(jsonl.join("\n").as_bytes()).unwrap();
+        assert_eq!(report.imported, 1_500);
+        assert!(report.errors.is_empty());
 
-        assert!(act.is_ok());
-        let act = act.unwrap();
-        assert!(act.is_none());
+        let act = transactions.import_jsonl(jsonl.join("\n").as_bytes());
+        assert_eq!(act, Err(StateError::Throttled));
     }
 
     #[test]
-    fn set_and_get_tx_meta() {
+    fn import_token_transfers_from_jsonl() {
         let tmp_dir = TempDir::new("tx").unwrap();
         let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
         let transactions = access.get_transactions();
 
-        let mut meta = proto_TransactionMeta::new();
-        meta.blockchain = BlockchainId::CHAIN_ETHEREUM;
-        meta.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
-        meta.timestamp = 1_647_313_850_992;
-        meta.label = "test".to_string();
-        transactions.set_tx_meta(meta.clone()).unwrap();
+        let mut transfer_erc20 = TokenTransfer::new();
+        transfer_erc20.log_index = 12;
+        transfer_erc20.contract = "0xdac17f958d2ee523a2206206994597c13d831ec7".to_string();
+        transfer_erc20.standard = TokenStandard::TOKEN_ERC20;
+        transfer_erc20.from = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
+        transfer_erc20.to = "0x6b175474e89094c44da98b954eedeac495271d0f".to_string();
+        transfer_erc20.amount = "1000000".to_string();
+        let mut transfer_erc721 = TokenTransfer::new();
+        transfer_erc721.log_index = 13;
+        transfer_erc721.contract = "0x3f7dd0b106b10d06bb4813715b4e24d9e626dd64".to_string();
+        transfer_erc721.standard = TokenStandard::TOKEN_ERC721;
+        transfer_erc721.from = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
+        transfer_erc721.to = "0x6b175474e89094c44da98b954eedeac495271d0f".to_string();
+        transfer_erc721.token_id = "1234".to_string();
+        let expected = vec![transfer_erc20, transfer_erc721];
+
+        let json = serde_json::json!({
+            "blockchain": "CHAIN_ETHEREUM",
+            "txId": "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b",
+            "sinceTimestamp": "1647313850992",
+            "tokenTransfers": expected.iter().map(|t| serde_json::json!({
+                "logIndex": t.log_index,
+                "contract": t.contract,
+                "standard": t.standard as i32,
+                "from": t.from,
+                "to": t.to,
+                "amount": t.amount,
+                "tokenId": t.token_id,
+            })).collect::<Vec<_>>(),
+        });
+        let report = transactions.import_jsonl(json.to_string().as_bytes()).unwrap();
+        assert_eq!(report.imported, 1);
+        assert!(report.errors.is_empty());
+
+        let act = transactions.get_tx(100, "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b").unwrap();
+        assert_eq!(act.token_transfers.to_vec(), expected);
+    }
 
-        let act = transactions.get_tx_meta(100, "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b");
+    #[test]
+    fn suggest_labels_by_counterparty() {
+        let tmp_dir = TempDir::new("tx").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
 
-        assert!(act.is_ok());
-        let act = act.unwrap();
-        assert!(act.is_some());
-        let act = act.unwrap();
-        assert_eq!(act, meta);
+        let make_tx = |tx_id: &str, ts: u64, counterparty: &str| {
+            let mut tx = proto_Transaction::new();
+            tx.blockchain = BlockchainId::CHAIN_ETHEREUM;
+            tx.tx_id = tx_id.to_string();
+            tx.since_timestamp = ts;
+            let mut change = proto_Change::new();
+            change.wallet_id = "72279ede-44c4-4951-925b-f51a7b9e929a".to_string();
+            change.address = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
+            change.direction = Direction::SEND;
+            tx.changes.push(change);
+            let mut change = proto_Change::new();
+            change.address = counterparty.to_string();
+            change.direction = Direction::RECEIVE;
+            tx.changes.push(change);
+            tx
+        };
+        let make_meta = |tx_id: &str, label: &str| {
+            let mut meta = proto_TransactionMeta::new();
+            meta.blockchain = BlockchainId::CHAIN_ETHEREUM;
+            meta.tx_id = tx_id.to_string();
+            meta.timestamp = 1_647_313_900_000;
+            meta.label = label.to_string();
+            meta
+        };
+
+        let exchange = "0x28c6c06298d514db089934071355e5743bf21d60";
+        let other = "0xedd91797204d3537fbabde0e0e42aae99975f2bb";
+        transactions.submit(vec![
+            make_tx("0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b", 1_647_313_850_992, exchange),
+            make_tx("0x5f88e4e1ab5d1a3ccb1e0ddfb6f9b5cc0ad43eb7ae1ef1b38ba6b6fcd4a6fb4c", 1_647_313_860_992, exchange),
+            make_tx("0x9b11c453e5d0caf4fb6d192624360def7bd1e81b2f761cbf069962cf3a82ab0d", 1_647_313_870_992, exchange),
+            make_tx("0xd9b11cef7bd1e81b453e5d0caf4fb6d1922f761cbf069962cf3a82ab0624360d", 1_647_313_880_992, other),
+        ]).expect("not saved");
+        transactions.set_tx_meta_bulk(vec![
+            make_meta("0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b", "Deposit to Binance"),
+            make_meta("0x5f88e4e1ab5d1a3ccb1e0ddfb6f9b5cc0ad43eb7ae1ef1b38ba6b6fcd4a6fb4c", "Binance"),
+            make_meta("0x9b11c453e5d0caf4fb6d192624360def7bd1e81b2f761cbf069962cf3a82ab0d", "Binance "),
+            make_meta("0xd9b11cef7bd1e81b453e5d0caf4fb6d1922f761cbf069962cf3a82ab0624360d", "Rent"),
+        ]).expect("not saved");
+
+        let act = transactions.suggest_labels("0x28C6c06298d514Db089934071355E5743bf21d60".to_string()).unwrap();
+        assert_eq!(act, vec![("Binance".to_string(), 2), ("Deposit to Binance".to_string(), 1)]);
+
+        let act = transactions.suggest_labels("0x60bcd26c20586076eea2e7206e22bf5256e76a20".to_string()).unwrap();
+        assert!(act.is_empty());
     }
 
     #[test]
-    fn set_and_get_tx_meta_with_raw() {
+    fn reject_invalid_tag() {
         let tmp_dir = TempDir::new("tx").unwrap();
         let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
         let transactions = access.get_transactions();
 
-        let mut meta = proto_TransactionMeta::new();
-        meta.blockchain = BlockchainId::CHAIN_ETHEREUM;
-        meta.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
-        meta.timestamp = 1_647_313_850_992;
-        meta.raw = hex::decode("af4fb6d192624360def7b0d72b1014cb9799de95781ce61b9b11c453e5d0c7c1eec752021ebcb344da0a88cdf49e97854d4fa861cbf069962cf3a82abd1e82f7").unwrap();
-        transactions.set_tx_meta(meta.clone()).unwrap();
-
-        let act = transactions.get_tx_meta(100, "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b");
+        let tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        let act = transactions.add_tag(100, tx_id.clone(), " ".to_string());
+        assert_eq!(act, Err(StateError::InvalidValue(InvalidValueError::Name("tags".to_string()))));
+        let act = transactions.add_tag(100, tx_id.clone(), "a/b".to_string());
+        assert_eq!(act, Err(StateError::InvalidValue(InvalidValueError::Name("tags".to_string()))));
+        assert!(transactions.get_tx_meta(100, tx_id.as_str()).unwrap().is_none());
 
-        assert!(act.is_ok());
-        let act = act.unwrap().unwrap();
-        assert_eq!(act.raw, hex::decode("af4fb6d192624360def7b0d72b1014cb9799de95781ce61b9b11c453e5d0c7c1eec752021ebcb344da0a88cdf49e97854d4fa861cbf069962cf3a82abd1e82f7").unwrap());
+        assert_eq!(transactions.remove_tag(100, tx_id, "defi".to_string()), Ok(None));
     }
 
     #[test]
@@ -762,6 +4560,220 @@ mod tests {
         assert_eq!(act.label, "test 2");
     }
 
+    #[test]
+    fn cascade_on_forget() {
+        let tmp_dir = TempDir::new("test-storage").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
+        let allowances = access.get_allowance();
+
+        let mut tx1 = proto_Transaction::new();
+        tx1.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx1.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        tx1.since_timestamp = 1_647_313_850_992;
+        let mut tx2 = tx1.clone();
+        tx2.tx_id = "0x5f88e4e1ab5d1a3ccb1e0ddfb6f9b5cc0ad43eb7ae1ef1b38ba6b6fcd4a6fb4c".to_string();
+        tx2.since_timestamp = 1_647_313_860_992;
+        transactions.submit(vec![tx1.clone(), tx2.clone()]).expect("not saved");
+        transactions.add_tag(100, tx1.tx_id.clone(), "rent".to_string()).unwrap();
+        transactions.add_tag(100, tx2.tx_id.clone(), "rent".to_string()).unwrap();
+
+        let mut allowance = Allowance::new();
+        allowance.wallet_id = "5e0e8fb5-9ffb-4b18-b79a-b732d19576f3".to_string();
+        allowance.blockchain = 100;
+        allowance.token = "0xdAC17F958D2ee523a2206206994597C13D831ec7".to_string();
+        allowance.owner = "0x9696f59E4d72E237BE84fFD425DCaD154Bf96976".to_string();
+        allowance.spender = "0x65A0947BA5175359Bb457D3b34491eDf4cBF7997".to_string();
+        allowance.amount = "10000000".to_string();
+        allowances.add(allowance.clone(), None).unwrap();
+        let mut allowance_2 = allowance.clone();
+        allowance_2.spender = "0xEdD91797204D3537fBaBDe0E0E42AaE99975f2Bb".to_string();
+        allowances.add(allowance_2.clone(), None).unwrap();
+        allowances.mark_revoked(AllowanceKey::try_from(&allowance).unwrap(), tx1.tx_id.clone()).unwrap();
+        allowances.mark_revoked(AllowanceKey::try_from(&allowance_2).unwrap(), tx2.tx_id.clone()).unwrap();
+        assert!(transactions.find_dangling().unwrap().is_empty());
+
+        // by default the meta is kept and the allowance is detached
+        transactions.forget(100, tx1.tx_id.clone()).unwrap();
+        assert!(transactions.get_tx_meta(100, tx1.tx_id.as_str()).unwrap().is_some());
+        let revoked: Vec<String> = allowances.list(None).unwrap().values.into_iter().map(|a| a.revoked_by_tx).collect();
+        assert!(revoked.contains(&String::new()));
+        assert!(revoked.contains(&tx2.tx_id));
+        let dangling = transactions.find_dangling().unwrap();
+        assert_eq!(dangling.len(), 1);
+        assert_eq!(dangling[0].kind, TxReference::Meta);
+        assert_eq!(dangling[0].tx_id, tx1.tx_id);
+
+        access.references().unwrap().set_policy(TxReference::Meta, CascadePolicy::Delete);
+        access.references().unwrap().set_policy(TxReference::AllowanceRevoke, CascadePolicy::Delete);
+        transactions.forget(100, tx2.tx_id.clone()).unwrap();
+        assert!(transactions.get_tx_meta(100, tx2.tx_id.as_str()).unwrap().is_none());
+        assert_eq!(allowances.list(None).unwrap().values.len(), 1);
+        // the index of the meta is removed too
+        let filter = Filter { tag: Some("rent".to_string()), ..Filter::default() };
+        assert!(transactions.query(filter, PageQuery::default()).unwrap().values.is_empty());
+        assert_eq!(transactions.find_dangling().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn query_by_parsed_text() {
+        let tmp_dir = TempDir::new("test-storage").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
+
+        let tx = |i: u64, asset: &str, direction: Direction| {
+            let mut tx = proto_Transaction::new();
+            tx.blockchain = BlockchainId::CHAIN_ETHEREUM;
+            tx.tx_id = format!("0x{:064x}", i);
+            tx.since_timestamp = 1_647_313_850_000 + i * 1000;
+            let mut change = proto_Change::new();
+            change.wallet_id = "72279ede-44c4-4951-925b-f51a7b9e929a".to_string();
+            change.address = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
+            change.asset = asset.to_string();
+            change.direction = direction;
+            tx.changes.push(change);
+            tx
+        };
+        let tx1 = tx(1, "USDC", Direction::SEND);
+        let tx2 = tx(2, "USDC", Direction::RECEIVE);
+        let tx3 = tx(3, "ETHER", Direction::SEND);
+        transactions.submit(vec![tx1.clone(), tx2.clone(), tx3.clone()]).expect("not saved");
+
+        let mut meta = proto_TransactionMeta::new();
+        meta.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        meta.tx_id = tx1.tx_id.clone();
+        meta.timestamp = 1_647_313_850_992;
+        meta.label = "Rent for March".to_string();
+        transactions.set_tx_meta(meta).unwrap();
+        transactions.add_tag(100, tx3.tx_id.clone(), "rent".to_string()).unwrap();
+
+        let filter: Filter = "asset:usdc direction:send".parse().unwrap();
+        let results = transactions.query(filter, PageQuery::default()).unwrap();
+        assert_eq!(results.values.len(), 1);
+        assert_eq!(results.values[0].tx_id, tx1.tx_id);
+
+        let filter: Filter = r#"text:"RENT""#.parse().unwrap();
+        let results = transactions.query(filter, PageQuery::default()).unwrap();
+        let ids: Vec<String> = results.values.iter().map(|tx| tx.tx_id.clone()).collect();
+        assert_eq!(ids, vec![tx3.tx_id.clone(), tx1.tx_id.clone()]);
+
+        let filter: Filter = "rent direction:receive".parse().unwrap();
+        assert!(transactions.query(filter, PageQuery::default()).unwrap().values.is_empty());
+    }
+
+    #[test]
+    fn get_recent_activity() {
+        let tmp_dir = TempDir::new("test-storage").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
+        let wallet_1 = Uuid::from_str("72279ede-44c4-4951-925b-f51a7b9e929a").unwrap();
+        let wallet_2 = Uuid::from_str("b76ebc4f-0da4-4fe2-8ed9-d8b0ec2c5da3").unwrap();
+
+        let tx = |i: u64, wallets: Vec<Uuid>| {
+            let mut tx = proto_Transaction::new();
+            tx.blockchain = BlockchainId::CHAIN_ETHEREUM;
+            tx.tx_id = format!("0x{:064x}", i);
+            tx.since_timestamp = 1_647_313_850_000 + i * 1000;
+            tx.confirm_timestamp = tx.since_timestamp;
+            tx.state = State::CONFIRMED;
+            for wallet_id in wallets {
+                let mut change = proto_Change::new();
+                change.wallet_id = wallet_id.to_string();
+                change.address = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string();
+                tx.changes.push(change);
+            }
+            tx
+        };
+        let mut txes = Vec::new();
+        for i in 1..=5 {
+            txes.push(tx(i, vec![wallet_1]));
+        }
+        txes.push(tx(6, vec![wallet_1, wallet_2]));
+        txes.push(tx(7, vec![wallet_2]));
+        transactions.submit(txes).expect("not saved");
+        transactions.set_flag(100, format!("0x{:064x}", 5), TxFlag::Hidden, true).unwrap();
+
+        let act = transactions.get_recent_activity(3).unwrap();
+        assert_eq!(act.len(), 2);
+        let ids = |txes: &Vec<proto_Transaction>| txes.iter().map(|tx| tx.tx_id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(act.get(&wallet_1).unwrap()), vec![format!("0x{:064x}", 6), format!("0x{:064x}", 4), format!("0x{:064x}", 3)]);
+        assert_eq!(ids(act.get(&wallet_2).unwrap()), vec![format!("0x{:064x}", 7), format!("0x{:064x}", 6)]);
+
+        assert!(transactions.get_recent_activity(0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn get_tx_with_meta() {
+        let tmp_dir = TempDir::new("test-storage").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
+
+        let mut tx = proto_Transaction::new();
+        tx.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx.tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+        tx.since_timestamp = 1_647_313_850_992;
+        transactions.submit(vec![tx.clone()]).expect("not saved");
+
+        let act = transactions.get_tx_with_meta(100, tx.tx_id.as_str(), false).unwrap().unwrap();
+        assert_eq!(act.tx.tx_id, tx.tx_id);
+        assert!(act.meta.is_none());
+
+        let mut meta = proto_TransactionMeta::new();
+        meta.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        meta.tx_id = tx.tx_id.clone();
+        meta.timestamp = 1_647_313_850_992;
+        meta.label = "Rent".to_string();
+        meta.raw = vec![0xf8, 0x6b, 0x80];
+        transactions.set_tx_meta(meta).unwrap();
+
+        // the tx id is case-insensitive
+        let act = transactions.get_tx_with_meta(100, tx.tx_id.to_uppercase().replace("0X", "0x").as_str(), false).unwrap().unwrap();
+        assert_eq!(act.tx.tx_id, tx.tx_id);
+        let meta = act.meta.unwrap();
+        assert_eq!(meta.label, "Rent");
+        assert!(meta.raw.is_empty());
+
+        let act = transactions.get_tx_with_meta(100, tx.tx_id.as_str(), true).unwrap().unwrap();
+        assert_eq!(act.meta.unwrap().raw, vec![0xf8, 0x6b, 0x80]);
+
+        let act = transactions.get_tx_with_meta(100, "0x5f88e4e1ab5d1a3ccb1e0ddfb6f9b5cc0ad43eb7ae1ef1b38ba6b6fcd4a6fb4c", true).unwrap();
+        assert!(act.is_none());
+    }
+
+    #[test]
+    fn export_meta_history() {
+        let tmp_dir = TempDir::new("test-storage").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let transactions = access.get_transactions();
+        let tx_id = "0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b".to_string();
+
+        let mut meta = proto_TransactionMeta::new();
+        meta.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        meta.tx_id = tx_id.clone();
+        meta.timestamp = 1_647_313_850_992;
+        meta.label = "Rent".to_string();
+        transactions.set_tx_meta(meta).unwrap();
+        transactions.add_tag(100, tx_id.clone(), "home".to_string()).unwrap();
+        transactions.set_flag(100, tx_id.clone(), TxFlag::Starred, true).unwrap();
+
+        let history = transactions.get_meta_history(100, tx_id.as_str()).unwrap();
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[0].seq, 0);
+        assert!(history[0].prev_hash.is_empty());
+        assert_eq!(history[1].prev_hash, history[0].hash);
+        assert_eq!(history[2].prev_hash, history[1].hash);
+        let meta: Vec<proto_TransactionMeta> = history.iter().map(|r| proto_TransactionMeta::parse_from_bytes(&r.meta).unwrap()).collect();
+        assert_eq!(meta[0].label, "Rent");
+        assert!(!meta[1].starred);
+        assert!(meta[2].starred);
+
+        let json = transactions.export_meta_proof(100, tx_id.as_str()).unwrap();
+        assert_eq!(meta_proof::verify(json.as_str()), Ok(3));
+
+        let history = transactions.get_meta_history(100, "0x5f88e4e1ab5d1a3ccb1e0ddfb6f9b5cc0ad43eb7ae1ef1b38ba6b6fcd4a6fb4c").unwrap();
+        assert!(history.is_empty());
+    }
+
     #[test]
     fn no_update_tx_meta_to_old() {
         let tmp_dir = TempDir::new("tx").unwrap();
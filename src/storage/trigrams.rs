@@ -60,6 +60,19 @@ impl Trigram {
 
         results.iter().cloned().collect()
     }
+
+    ///
+    /// Share of the n-grams of the query found in the text, from 0.0 (nothing in common) to 1.0 (all found, ex. when the
+    /// query is a part of the text). Unlike a substring search it tolerates small typos in the query.
+    pub(crate) fn similarity<Q: ToString, T: ToString>(query: Q, text: T) -> f32 {
+        let query = Trigram::extract(query);
+        if query.is_empty() {
+            return 0.0
+        }
+        let text: HashSet<String> = Trigram::extract(text).into_iter().collect();
+        let found = query.iter().filter(|gram| text.contains(*gram)).count();
+        found as f32 / query.len() as f32
+    }
 }
 
 #[cfg(test)]
@@ -107,6 +120,16 @@ mod tests {
         assert_eq!(vec!["hi".to_string()], act);
     }
 
+    #[test]
+    fn similarity_with_typos() {
+        assert_eq!(Trigram::similarity("john", "John Smith"), 1.0);
+        assert!(Trigram::similarity("jhon", "John Smith") > 0.4);
+        assert!(Trigram::similarity("smiht", "John Smith") > 0.4);
+        assert!(Trigram::similarity("jhon", "Jane") < 0.4);
+        assert_eq!(Trigram::similarity("jhon", ""), 0.0);
+        assert_eq!(Trigram::similarity(" ", "John"), 0.0);
+    }
+
     #[test]
     fn extract_trigrams() {
         let mut act = Trigram::extract("test test test");
@@ -0,0 +1,77 @@
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+
+///
+/// Source of the current time for the expiration, which can be fixed to a specific moment, ex. in tests
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Clock {
+    #[default]
+    System,
+    Fixed(DateTime<Utc>),
+}
+
+impl Clock {
+    pub fn now(&self) -> DateTime<Utc> {
+        match self {
+            Clock::System => Utc::now(),
+            Clock::Fixed(value) => *value,
+        }
+    }
+}
+
+///
+/// Retention settings for the cache-like stores. All values have defaults, so the host app may override only
+/// what it needs for the specific deployment.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TtlPolicy {
+    /// TTL for a generic cache value when it's not specified with `put`
+    pub cache_default: Duration,
+    /// Maximum TTL for a generic cache value, larger values are reduced to it
+    pub cache_max: Duration,
+    /// How often the expired cache values are purged
+    pub cache_purge_every: Duration,
+    /// TTL for an ERC20 allowance when it's not specified with `add`
+    pub allowance_default: Duration,
+    /// Maximum TTL for an ERC20 allowance, larger values are reduced to it
+    pub allowance_max: Duration,
+    /// How long a confirmed transaction stays in the recent section of the wallet history, along with the pending
+    /// ones. With zero only the pending transactions are there.
+    pub tx_recent_window: Duration,
+    /// Age after which a remote cursor is considered stale, i.e. the address should be fetched again
+    pub cursor_stale_after: Duration,
+    /// Age after which a cached balance is considered stale, i.e. it should be fetched again
+    pub balance_stale_after: Duration,
+    /// TTL for the token metadata when it's not specified with `set`
+    pub token_default: Duration,
+    /// Maximum TTL for the token metadata, larger values are reduced to it
+    pub token_max: Duration,
+    /// Current time for the expiration of the cache values
+    pub clock: Clock,
+}
+
+impl Default for TtlPolicy {
+    fn default() -> Self {
+        TtlPolicy {
+            // 1 week
+            cache_default: Duration::from_secs(60 * 60 * 24 * 7),
+            // 1 month
+            cache_max: Duration::from_secs(60 * 60 * 24 * 30),
+            // 1 hour
+            cache_purge_every: Duration::from_secs(60 * 60),
+            // 1 day
+            allowance_default: Duration::from_secs(60 * 60 * 24),
+            // 1 month
+            allowance_max: Duration::from_secs(60 * 60 * 24 * 30),
+            tx_recent_window: Duration::ZERO,
+            // 1 hour
+            cursor_stale_after: Duration::from_secs(60 * 60),
+            // 15 minutes
+            balance_stale_after: Duration::from_secs(60 * 15),
+            // 1 week
+            token_default: Duration::from_secs(60 * 60 * 24 * 7),
+            // 1 month
+            token_max: Duration::from_secs(60 * 60 * 24 * 30),
+            clock: Clock::System,
+        }
+    }
+}
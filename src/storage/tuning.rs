@@ -0,0 +1,250 @@
+//!
+//! Configuration of the underlying sled DB for the expected workload, and the counters to check how it performs.
+//!
+//! Sled doesn't map the files into the memory, instead it keeps the pages in its own cache, so the reads are fast only
+//! as long as the data fits the cache. The default sled configuration takes up to 1Gb for it, which is too much for a
+//! machine with a few Gb of RAM, where the OS starts to swap and the reads become even slower than from the disk.
+//!
+//! Sled doesn't expose its internal cache metrics, so the storage counts the reads by itself and uses the time of a
+//! read as a proxy: a read served from the cache takes microseconds, and a read slower than `COLD_READ` most likely
+//! had to load a page from the disk.
+//!
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use sled::{Db, Mode};
+use crate::errors::StateError;
+
+/// A read slower than this is considered as served from the disk, i.e. a cache miss
+const COLD_READ: Duration = Duration::from_millis(2);
+
+/// File where sled keeps the parameters of an existing DB
+const SLED_CONF: &str = "conf";
+
+///
+/// Expected usage of the storage, to choose the DB configuration for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Workload {
+    /// Default sled configuration
+    Balanced,
+    /// Mostly reads from the UI, with rare writes of the synced data
+    ReadHeavy,
+    /// Frequent writes, ex. during the initial sync of a large wallet
+    WriteHeavy,
+    /// Minimal memory usage, for machines with a small RAM. The reads are slower.
+    LowMemory,
+}
+
+///
+/// Configuration of the sled DB. Use `StorageConfig::for_workload` to get the recommended values, and adjust them if
+/// needed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StorageConfig {
+    pub workload: Workload,
+    /// Maximum memory (in bytes) used to cache the pages of the DB
+    pub cache_capacity: u64,
+    /// Size (in bytes) of the IO buffers and the segments of the DB file, must be a power of 2. Larger segments make
+    /// the writes faster, smaller make them use less memory. Note that it's applied only when a new DB is created,
+    /// an existing DB keeps the segment size it was created with.
+    pub segment_size: usize,
+    /// How often the writes are flushed to the disk, or never in background if `None`
+    pub flush_every: Option<Duration>,
+    /// Prefer the write throughput over the disk space
+    pub high_throughput: bool,
+}
+
+impl StorageConfig {
+
+    ///
+    /// Recommended configuration for the workload
+    pub fn for_workload(workload: Workload) -> StorageConfig {
+        match workload {
+            Workload::Balanced => StorageConfig {
+                workload,
+                // 1Gb
+                cache_capacity: 1024 * 1024 * 1024,
+                segment_size: 512 * 1024,
+                flush_every: Some(Duration::from_millis(500)),
+                high_throughput: false,
+            },
+            Workload::ReadHeavy => StorageConfig {
+                workload,
+                // 512Mb
+                cache_capacity: 512 * 1024 * 1024,
+                segment_size: 512 * 1024,
+                flush_every: Some(Duration::from_millis(1000)),
+                // keeps the data less fragmented, so it's read from fewer segments
+                high_throughput: false,
+            },
+            Workload::WriteHeavy => StorageConfig {
+                workload,
+                // 256Mb
+                cache_capacity: 256 * 1024 * 1024,
+                segment_size: 1024 * 1024,
+                flush_every: Some(Duration::from_millis(500)),
+                high_throughput: true,
+            },
+            Workload::LowMemory => StorageConfig {
+                workload,
+                // 32Mb
+                cache_capacity: 32 * 1024 * 1024,
+                segment_size: 128 * 1024,
+                flush_every: Some(Duration::from_millis(1000)),
+                high_throughput: false,
+            },
+        }
+    }
+
+    ///
+    /// Open the DB at the path with this configuration
+    pub(crate) fn open(&self, path: &Path) -> Result<Db, StateError> {
+        let segment_size = StorageConfig::read_segment_size(path).unwrap_or(self.segment_size);
+        let db = sled::Config::new()
+            .path(path)
+            .cache_capacity(self.cache_capacity)
+            .segment_size(segment_size)
+            .flush_every_ms(self.flush_every.map(|d| d.as_millis() as u64))
+            .mode(if self.high_throughput { Mode::HighThroughput } else { Mode::LowSpace })
+            .open()?;
+        Ok(db)
+    }
+
+    ///
+    /// Segment size of an existing DB, because sled refuses to open it with a different one
+    fn read_segment_size(path: &Path) -> Option<usize> {
+        // the file ends with a binary checksum, so it's not a valid UTF-8
+        let conf = fs::read(path.join(SLED_CONF)).ok()?;
+        String::from_utf8_lossy(&conf).lines()
+            .filter_map(|line| line.strip_prefix("segment_size:"))
+            .find_map(|value| value.trim().parse().ok())
+    }
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        StorageConfig::for_workload(Workload::Balanced)
+    }
+}
+
+///
+/// Read counters shared by all handles of the same storage
+#[derive(Default)]
+pub(crate) struct Profiler {
+    reads: AtomicU64,
+    cold_reads: AtomicU64,
+    read_micros: AtomicU64,
+}
+
+impl Profiler {
+
+    ///
+    /// Run the read operation and count it
+    pub(crate) fn read<T, F: FnOnce() -> T>(&self, f: F) -> T {
+        let start = Instant::now();
+        let result = f();
+        let elapsed = start.elapsed();
+        self.reads.fetch_add(1, Ordering::Relaxed);
+        self.read_micros.fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+        if elapsed >= COLD_READ {
+            self.cold_reads.fetch_add(1, Ordering::Relaxed);
+        }
+        result
+    }
+}
+
+///
+/// Current state of the storage, see `SledStorage::stats`
+#[derive(Debug, Clone, PartialEq)]
+pub struct StorageStats {
+    pub config: StorageConfig,
+    /// Size of the DB files
+    pub size_on_disk: u64,
+    /// Number of the read operations (queries and cache lookups) since the storage is opened
+    pub reads: u64,
+    /// Number of the reads served from the memory, a proxy for the cache hits
+    pub warm_reads: u64,
+    /// Number of the reads which had to load data from the disk, a proxy for the cache misses and page faults
+    pub cold_reads: u64,
+    /// Average time of a read
+    pub avg_read: Duration,
+}
+
+impl StorageStats {
+
+    pub(crate) fn new(config: StorageConfig, size_on_disk: u64, profiler: &Profiler) -> StorageStats {
+        let reads = profiler.reads.load(Ordering::Relaxed);
+        let cold_reads = profiler.cold_reads.load(Ordering::Relaxed).min(reads);
+        let read_micros = profiler.read_micros.load(Ordering::Relaxed);
+        StorageStats {
+            config,
+            size_on_disk,
+            reads,
+            warm_reads: reads - cold_reads,
+            cold_reads,
+            avg_read: Duration::from_micros(read_micros.checked_div(reads).unwrap_or(0)),
+        }
+    }
+
+    ///
+    /// Share of the reads served from the memory, or `None` if nothing is read yet. If it's low the `cache_capacity`
+    /// is too small for the data, or the OS swaps the cache out.
+    pub fn hit_ratio(&self) -> Option<f64> {
+        if self.reads == 0 {
+            None
+        } else {
+            Some(self.warm_reads as f64 / self.reads as f64)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+    use std::time::Duration;
+    use tempdir::TempDir;
+    use crate::access::cache::Cache;
+    use crate::access::permissions::Permissions;
+    use crate::storage::sled_access::SledStorage;
+    use crate::storage::tuning::{Profiler, StorageConfig, StorageStats, Workload};
+    use crate::storage::ttl_policy::TtlPolicy;
+
+    #[test]
+    fn count_cold_reads() {
+        let profiler = Profiler::default();
+        assert_eq!(profiler.read(|| 1), 1);
+        profiler.read(|| thread::sleep(Duration::from_millis(5)));
+        let stats = StorageStats::new(StorageConfig::default(), 0, &profiler);
+        assert_eq!(stats.reads, 2);
+        assert_eq!(stats.warm_reads, 1);
+        assert_eq!(stats.cold_reads, 1);
+        assert_eq!(stats.hit_ratio(), Some(0.5));
+        assert!(stats.avg_read >= Duration::from_micros(2500));
+    }
+
+    #[test]
+    fn open_for_workload() {
+        let tmp_dir = TempDir::new("tuning").unwrap();
+        let config = StorageConfig::for_workload(Workload::LowMemory);
+        let access = SledStorage::open_with_config(tmp_dir.path().to_path_buf(), TtlPolicy::default(), config).unwrap();
+        // it's used when the DB is opened next time
+        assert_eq!(StorageConfig::read_segment_size(tmp_dir.path()), Some(128 * 1024));
+
+        let mut cache = access.get_cache();
+        cache.put("test".to_string(), "1".to_string(), None).unwrap();
+        let before = access.stats().unwrap().reads;
+        assert_eq!(cache.get("test".to_string()).unwrap(), Some("1".to_string()));
+        assert_eq!(cache.get("other".to_string()).unwrap(), None);
+
+        let stats = access.restrict(Permissions::read_only()).stats().unwrap();
+        assert_eq!(stats.config.workload, Workload::LowMemory);
+        assert_eq!(stats.reads, before + 2);
+        assert!(stats.size_on_disk > 0);
+    }
+
+    #[test]
+    fn no_segment_size_for_new_db() {
+        let tmp_dir = TempDir::new("tuning").unwrap();
+        assert_eq!(StorageConfig::read_segment_size(tmp_dir.path()), None);
+    }
+}
@@ -1,14 +1,26 @@
 use std::sync::Arc;
 use sled::Db;
+use crate::access::permissions::Permissions;
+use crate::access::validation::Validators;
+use crate::access::integrity::References;
 use crate::errors::StateError;
+use crate::storage::tuning::Profiler;
+use crate::storage::health::Health;
+use crate::storage::adressbook_store::AddressBookAccess;
 use crate::storage::balance_store::BalanceAccess;
 use crate::storage::transaction_store::TransactionsAccess;
+use crate::storage::notification::Notifier;
+use crate::storage::rate_limit::RateLimiter;
+use crate::storage::ttl_policy::TtlPolicy;
+use crate::storage::xpubpos_store::XPubPositionAccess;
 
-const KEY: &'static str = "version";
-const CURRENT_VERSION: usize = 1usize;
+const KEY: &str = "version";
+const CURRENT_VERSION: usize = 6usize;
 
 pub struct Version {
     db: Arc<Db>,
+    health: Arc<Health>,
+    ttl: TtlPolicy,
 }
 
 pub(crate) trait Migration {
@@ -19,8 +31,8 @@ pub(crate) trait Migration {
 /// Manage DB version
 ///
 impl Version {
-    pub(crate) fn new(db: Arc<Db>) -> Self {
-        Version { db }
+    pub(crate) fn new(db: Arc<Db>, health: Arc<Health>, ttl: TtlPolicy) -> Self {
+        Version { db, health, ttl }
     }
 
     ///
@@ -49,15 +61,24 @@ impl Version {
 
     ///
     /// Migrate DB to the latest version. May include a deletion of some data.
+    /// It's made when the storage is opened, and writes to all stores regardless of the permissions of a handle, so it's
+    /// not available outside of the crate.
     ///
-    pub fn migrate(&self) -> Result<(), StateError> {
-        let act = self.get_version()?;
-        if act.is_none() || act.unwrap() < CURRENT_VERSION {
-            let balances = BalanceAccess { db: self.db.clone() };
-            balances.migrate(CURRENT_VERSION)?;
+    pub(crate) fn migrate(&self) -> Result<(), StateError> {
+        let act = self.get_version()?.unwrap_or(0);
+        if act < CURRENT_VERSION {
+            let health = self.health.clone();
+            let xpub = Arc::new(XPubPositionAccess { db: self.db.clone(), permissions: Permissions::full(), limiter: Arc::new(RateLimiter::default()), health: health.clone() });
+            let balances = BalanceAccess { db: self.db.clone(), permissions: Permissions::full(), limiter: Arc::new(RateLimiter::default()), health: health.clone(), xpub: xpub.clone(), ttl: self.ttl, notifier: Arc::new(Notifier::default()) };
+            let transactions = TransactionsAccess { db: self.db.clone(), validators: Arc::new(Validators::default()), references: Arc::new(References::default()), permissions: Permissions::full(), ttl: self.ttl, limiter: Arc::new(RateLimiter::default()), health: health.clone(), notifier: Arc::new(Notifier::default()), profiler: Arc::new(Profiler::default()) };
+            let addressbook = AddressBookAccess { db: self.db.clone(), xpub, validators: Arc::new(Validators::default()), permissions: Permissions::full(), limiter: Arc::new(RateLimiter::default()), health, profiler: Arc::new(Profiler::default()) };
 
-            let transactions = TransactionsAccess { db: self.db.clone() };
-            transactions.migrate(CURRENT_VERSION)?;
+            // apply each step in order, so a DB from any previous version gets all the changes
+            for version in (act + 1)..=CURRENT_VERSION {
+                balances.migrate(version)?;
+                transactions.migrate(version)?;
+                addressbook.migrate(version)?;
+            }
 
             self.set_version(CURRENT_VERSION)?;
         }
@@ -0,0 +1,180 @@
+//!
+//! Removal of all data of a wallet, when the user removes the wallet from the app.
+//!
+//! The data of a wallet is spread over multiple stores, and some of it is keyed by the addresses of the wallet rather
+//! than by its id. The addresses are taken from the Address Registry, so it must be filled for the wallet to remove the
+//! balances and the sync state too. Everything is removed in a single batch, so it's either fully removed or not
+//! changed at all.
+//!
+use uuid::Uuid;
+use crate::access::permissions::Store;
+use crate::access::transactions::TransactionsChange;
+use crate::errors::StateError;
+use crate::storage::sled_access::SledStorage;
+use crate::storage::sequence::{self, Batch};
+
+/// Stores changed by a wipe
+const WIPED_STORES: [Store; 5] = [Store::Transactions, Store::Allowances, Store::AddressRegistry, Store::SyncState, Store::Balances];
+
+///
+/// What was removed by `SledStorage::wipe_wallet`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WipeReport {
+    /// Transactions of the wallet, with their labels, tags and other meta
+    pub transactions: usize,
+    /// Transactions shared with another wallet, which are kept but not attributed to the removed wallet
+    pub detached_transactions: usize,
+    pub allowances: usize,
+    /// Addresses of the wallet in the Address Registry
+    pub addresses: usize,
+    /// Balances of the wallet addresses
+    pub balances: usize,
+    /// Sync states of the wallet addresses
+    pub sync_states: usize,
+    /// Keys still referencing the wallet after the wipe, i.e. having its id in the key or in the value. Expected to be
+    /// empty, otherwise it's some data the wipe doesn't know about, ex. a cache value set by the app.
+    pub remaining: Vec<String>,
+}
+
+impl WipeReport {
+
+    ///
+    /// Check if nothing referencing the wallet is left in the storage
+    pub fn is_clean(&self) -> bool {
+        self.remaining.is_empty()
+    }
+}
+
+pub(crate) fn wipe_wallet(storage: &SledStorage, wallet_id: Uuid) -> Result<WipeReport, StateError> {
+    storage.check_read_all()?;
+    for store in WIPED_STORES {
+        storage.permissions.check_write(store)?;
+        storage.limiter.check(store)?;
+    }
+
+    let mut report = WipeReport::default();
+    let mut batch = Batch::default();
+    let mut change = TransactionsChange::default();
+    let addresses = storage.get_address_registry().wipe_wallet(wallet_id, &mut batch)?;
+    report.addresses = addresses.len();
+    let transactions = storage.get_transactions();
+    (report.transactions, report.detached_transactions) = transactions.wipe_wallet(wallet_id, &addresses, &mut batch, &mut change)?;
+    report.allowances = storage.get_allowance().wipe_wallet(wallet_id, &mut batch)?;
+    report.balances = storage.get_balance().wipe_addresses(&addresses, &mut batch)?;
+    report.sync_states = storage.get_sync_state().wipe_targets(&addresses, &mut batch)?;
+    change.seq = storage.health.apply(&storage.db, batch)?;
+    transactions.notifier.notify(change);
+
+    report.remaining = find_references(storage, wallet_id)?;
+    Ok(report)
+}
+
+///
+/// Keys which have the wallet id in the key itself or in the value. The log of changes isn't checked, because it has
+/// only the keys changed by the recent writes, including the wipe itself, and is cleared by the next full backup.
+fn find_references(storage: &SledStorage, wallet_id: Uuid) -> Result<Vec<String>, StateError> {
+    let id = wallet_id.to_string();
+    let mut result = Vec::new();
+    for row in storage.db.iter() {
+        let (key, value) = row?;
+        if sequence::is_changes_log(key.as_ref()) {
+            continue
+        }
+        let found = key.windows(id.len()).any(|w| w == id.as_bytes())
+            || value.windows(id.len()).any(|w| w == id.as_bytes());
+        if found {
+            result.push(String::from_utf8_lossy(key.as_ref()).to_string());
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use chrono::Utc;
+    use tempdir::TempDir;
+    use uuid::Uuid;
+    use crate::access::address_registry::{AddressRegistry, EntryRef};
+    use crate::access::allowance::Allowances;
+    use crate::access::cache::Cache;
+    use crate::access::sync_state::SyncStates;
+    use crate::access::transactions::{Filter, Transactions, WalletRef};
+    use crate::access::pagination::PageQuery;
+    use crate::proto::balance::Allowance;
+    use crate::proto::transactions::{BlockchainId, Change as proto_Change, Change_ChangeType, Transaction as proto_Transaction};
+    use crate::storage::sled_access::SledStorage;
+
+    const WALLET: &str = "72279ede-44c4-4951-925b-f51a7b9e929a";
+    const OTHER_WALLET: &str = "5e0e8fb5-9ffb-4b18-b79a-b732d19576f3";
+    const ADDRESS: &str = "0xedd91797204d3537fbabde0e0e42aae99975f2bb";
+
+    fn create_tx(tx_id: &str, ts: u64, wallets: Vec<&str>) -> proto_Transaction {
+        let mut tx = proto_Transaction::new();
+        tx.blockchain = BlockchainId::CHAIN_ETHEREUM;
+        tx.tx_id = tx_id.to_string();
+        tx.since_timestamp = ts;
+        for wallet in wallets {
+            let mut change = proto_Change::new();
+            change.wallet_id = wallet.to_string();
+            change.address = ADDRESS.to_string();
+            change.amount = "100".to_string();
+            change.change_type = Change_ChangeType::TRANSFER;
+            tx.changes.push(change);
+        }
+        tx
+    }
+
+    #[test]
+    fn wipe_all_wallet_data() {
+        let tmp_dir = TempDir::new("wipe").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        let wallet_id = Uuid::from_str(WALLET).unwrap();
+
+        access.get_address_registry().set_addresses(EntryRef { wallet_id, entry_id: 0 }, 100, vec![ADDRESS.to_string()]).unwrap();
+        let transactions = access.get_transactions();
+        let own = create_tx("0x2f761cbf069962cf3a82ab0d9b11c453e5d0caf4fb6d192624360def7bd1e81b", 1_647_313_850_992, vec![WALLET]);
+        let shared = create_tx("0x5f88e4e1ab5d1a3ccb1e0ddfb6f9b5cc0ad43eb7ae1ef1b38ba6b6fcd4a6fb4c", 1_647_313_860_992, vec![WALLET, OTHER_WALLET]);
+        transactions.submit(vec![own.clone(), shared.clone()]).unwrap();
+        transactions.add_tag(100, own.tx_id.clone(), "rent".to_string()).unwrap();
+
+        let mut allowance = Allowance::new();
+        allowance.wallet_id = WALLET.to_string();
+        allowance.blockchain = 100;
+        allowance.token = "0xdAC17F958D2ee523a2206206994597C13D831ec7".to_string();
+        allowance.owner = ADDRESS.to_string();
+        allowance.spender = "0x65A0947BA5175359Bb457D3b34491eDf4cBF7997".to_string();
+        allowance.amount = "10000000".to_string();
+        access.get_allowance().add(allowance, None).unwrap();
+        access.get_sync_state().on_success(ADDRESS.to_string(), Utc::now()).unwrap();
+
+        let report = access.wipe_wallet(wallet_id).unwrap();
+        assert_eq!(report.transactions, 1);
+        assert_eq!(report.detached_transactions, 1);
+        assert_eq!(report.allowances, 1);
+        assert_eq!(report.addresses, 1);
+        assert_eq!(report.sync_states, 1);
+        assert!(report.is_clean(), "remaining: {:?}", report.remaining);
+
+        assert!(transactions.get_tx_meta(100, own.tx_id.as_str()).unwrap().is_none());
+        let filter = Filter { wallet: Some(WalletRef::WholeWallet(wallet_id)), ..Filter::default() };
+        assert!(transactions.query(filter, PageQuery::default()).unwrap().values.is_empty());
+        let filter = Filter { wallet: Some(WalletRef::WholeWallet(Uuid::from_str(OTHER_WALLET).unwrap())), ..Filter::default() };
+        let kept = transactions.query(filter, PageQuery::default()).unwrap().values;
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].tx_id, shared.tx_id);
+        assert!(access.get_sync_state().get(ADDRESS.to_string()).unwrap().is_none());
+    }
+
+    #[test]
+    fn report_unknown_references() {
+        let tmp_dir = TempDir::new("wipe").unwrap();
+        let access = SledStorage::open(tmp_dir.path().to_path_buf()).unwrap();
+        access.get_cache().put(format!("wallet/{}", WALLET), "test".to_string(), None).unwrap();
+
+        let report = access.wipe_wallet(Uuid::from_str(WALLET).unwrap()).unwrap();
+        assert_eq!(report.transactions, 0);
+        assert!(!report.is_clean());
+        assert_eq!(report.remaining, vec![format!("cache:wallet/{}", WALLET)]);
+    }
+}
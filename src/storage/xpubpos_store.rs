@@ -1,12 +1,18 @@
 use std::sync::Arc;
 use sled::{Db, IVec};
 use crate::access::xpubpos::XPubPosition;
+use crate::access::permissions::{Permissions, Store};
 use crate::errors::{InvalidValueError, StateError};
+use crate::storage::rate_limit::RateLimiter;
+use crate::storage::health::Health;
 
 const PREFIX_KEY: &'static str = "xpubpos:";
 
 pub struct XPubPositionAccess {
     pub(crate) db: Arc<Db>,
+    pub(crate) permissions: Permissions,
+    pub(crate) limiter: Arc<RateLimiter>,
+    pub(crate) health: Arc<Health>,
 }
 
 impl XPubPositionAccess {
@@ -50,27 +56,27 @@ impl XPubPositionAccess {
 
 impl XPubPosition for XPubPositionAccess {
     fn set_at_least(&self, xpub: String, pos: u32) -> Result<(), StateError> {
+        self.permissions.check_write(Store::XPubPosition)?;
+        self.limiter.check(Store::XPubPosition)?;
         let key = XPubPositionAccess::key(xpub)?;
-        let mut updated = false;
-        while !updated {
-            let prev = self.db.get(&key)?;
-            let next = match prev.as_ref().map(|b| XPubPositionAccess::deserialize(b) ) {
+        self.health.transaction(&self.db, |tx| {
+            let next = match tx.get(&key)?.as_ref().map(XPubPositionAccess::deserialize) {
                 None => pos,
-                Some(existing) => if existing == pos {
-                    // already contains the same value, doesn't need to be updated
-                    return Ok(())
+                Some(existing) => if existing >= pos {
+                    // already contains the same or a larger value, doesn't need to be updated
+                    return Ok(None)
                 } else {
-                    // otherwise try to find the largest of current and proposed
-                    if existing < pos { pos } else { existing }
+                    pos
                 }
             };
-            let result = self.db.compare_and_swap(&key, prev, Some(XPubPositionAccess::serialize(next)))?;
-            updated = result.is_ok();
-        }
+            tx.insert(key.as_bytes(), XPubPositionAccess::serialize(next))?;
+            Ok(Some(()))
+        })?;
         Ok(())
     }
 
     fn get(&self, xpub: String) -> Result<Option<u32>, StateError> {
+        self.permissions.check_read(Store::XPubPosition)?;
         let key = XPubPositionAccess::key(xpub)?;
         let current = self.db.get(&key)?
             .map(|b| XPubPositionAccess::deserialize(&b) );
@@ -78,6 +84,7 @@ impl XPubPosition for XPubPositionAccess {
     }
 
     fn get_next(&self, xpub: String) -> Result<u32, StateError> {
+        self.permissions.check_read(Store::XPubPosition)?;
         let current = self.get(xpub)?;
         match current {
             Some(v) => Ok(v + 1),
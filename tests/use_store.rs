@@ -1,6 +1,7 @@
 use chrono::{Utc, TimeZone};
 use num_bigint::BigUint;
 use tempdir::TempDir;
+use emerald_wallet_state::access::asset::AssetId;
 use emerald_wallet_state::access::balance::{Balance, Balances, Utxo};
 use emerald_wallet_state::access::cache::Cache;
 use emerald_wallet_state::access::transactions::Transactions;
@@ -59,14 +60,15 @@ fn write_multiple() {
     let balance = Balance {
         address: "bc1qywz558j2ja7fwmg32jupn02qvla5zm3dvggpqv".to_string(),
         blockchain: 1,
-        asset: "BTC".to_string(),
+        asset: AssetId::Native(1),
         amount: BigUint::from(23045u64),
         ts: Utc.timestamp_millis(1675123456789),
         utxo: vec![
             Utxo {
                 txid: "01ff3e2b6d2f1e52aa548e79b8f43d0091e9541bc4f70cda4e6549aaf836268b".to_string(),
                 vout: 1,
-                amount: 23045
+                amount: 23045,
+                reserved_by: None,
             }
         ],
         ..Balance::default()